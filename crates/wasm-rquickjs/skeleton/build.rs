@@ -0,0 +1,221 @@
+fn main() {
+    #[cfg(feature = "intl-full")]
+    intl_data::generate();
+}
+
+/// Bakes the ICU4X locale data used by the `intl-full` feature into a postcard blob.
+///
+/// The data is taken from the `icu_*_data` crates and filtered down to the languages listed in
+/// `intl-locales.txt` (one BCP-47 tag per line, written by the generator). English is always
+/// included as it backs the default `en-US` locale. When the file is missing or empty, every
+/// locale shipped with ICU4X is baked in.
+#[cfg(feature = "intl-full")]
+mod intl_data {
+    extern crate alloc;
+
+    use icu_provider::datagen::{DataExporter, ExportMarker, IterableDynamicDataProvider};
+    use icu_provider::prelude::*;
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+
+    // The data crate macros refer to the components through the `icu` meta crate paths.
+    mod icu {
+        pub use icu_collator as collator;
+        pub use icu_collections as collections;
+        pub use icu_datetime as datetime;
+        pub use icu_decimal as decimal;
+        pub use icu_experimental as experimental;
+        pub use icu_list as list;
+        pub use icu_locid_transform as locid_transform;
+        pub use icu_normalizer as normalizer;
+        pub use icu_plurals as plurals;
+        pub use icu_segmenter as segmenter;
+    }
+
+    struct SourceProvider;
+
+    macro_rules! source_provider {
+        ($($data:ident: $impl_key:ident, $iter_key:ident => $marker:path),+ $(,)?) => {
+            const _: () = {
+                icu_decimal_data::make_provider!(SourceProvider);
+                $(
+                    $data::$impl_key!(SourceProvider);
+                    $data::$iter_key!(SourceProvider);
+                )+
+            };
+
+            icu_provider::make_exportable_provider!(SourceProvider, [$($marker),+,]);
+
+            const KEYS: &[DataKey] = &[$(<$marker as KeyedDataMarker>::KEY),+];
+        };
+    }
+
+    source_provider!(
+        icu_locid_transform_data: impl_fallback_likelysubtags_v1, impliterable_fallback_likelysubtags_v1 => icu_locid_transform::provider::LocaleFallbackLikelySubtagsV1Marker,
+        icu_locid_transform_data: impl_fallback_parents_v1, impliterable_fallback_parents_v1 => icu_locid_transform::provider::LocaleFallbackParentsV1Marker,
+        icu_locid_transform_data: impl_fallback_supplement_co_v1, impliterable_fallback_supplement_co_v1 => icu_locid_transform::provider::CollationFallbackSupplementV1Marker,
+        icu_decimal_data: impl_decimal_symbols_v1, impliterable_decimal_symbols_v1 => icu_decimal::provider::DecimalSymbolsV1Marker,
+        icu_plurals_data: impl_plurals_cardinal_v1, impliterable_plurals_cardinal_v1 => icu_plurals::provider::CardinalV1Marker,
+        icu_plurals_data: impl_plurals_ordinal_v1, impliterable_plurals_ordinal_v1 => icu_plurals::provider::OrdinalV1Marker,
+        icu_plurals_data: impl_plurals_ranges_v1, impliterable_plurals_ranges_v1 => icu_plurals::provider::PluralRangesV1Marker,
+        icu_list_data: impl_list_and_v1, impliterable_list_and_v1 => icu_list::provider::AndListV1Marker,
+        icu_list_data: impl_list_or_v1, impliterable_list_or_v1 => icu_list::provider::OrListV1Marker,
+        icu_list_data: impl_list_unit_v1, impliterable_list_unit_v1 => icu_list::provider::UnitListV1Marker,
+        icu_collator_data: impl_collator_data_v1, impliterable_collator_data_v1 => icu_collator::provider::CollationDataV1Marker,
+        icu_collator_data: impl_collator_dia_v1, impliterable_collator_dia_v1 => icu_collator::provider::CollationDiacriticsV1Marker,
+        icu_collator_data: impl_collator_jamo_v1, impliterable_collator_jamo_v1 => icu_collator::provider::CollationJamoV1Marker,
+        icu_collator_data: impl_collator_meta_v1, impliterable_collator_meta_v1 => icu_collator::provider::CollationMetadataV1Marker,
+        icu_collator_data: impl_collator_prim_v1, impliterable_collator_prim_v1 => icu_collator::provider::CollationSpecialPrimariesV1Marker,
+        icu_collator_data: impl_collator_reord_v1, impliterable_collator_reord_v1 => icu_collator::provider::CollationReorderingV1Marker,
+        icu_normalizer_data: impl_normalizer_nfd_v1, impliterable_normalizer_nfd_v1 => icu_normalizer::provider::CanonicalDecompositionDataV1Marker,
+        icu_normalizer_data: impl_normalizer_nfdex_v1, impliterable_normalizer_nfdex_v1 => icu_normalizer::provider::CanonicalDecompositionTablesV1Marker,
+        icu_datetime_data: impl_datetime_gregory_datelengths_v1, impliterable_datetime_gregory_datelengths_v1 => icu_datetime::provider::calendar::GregorianDateLengthsV1Marker,
+        icu_datetime_data: impl_datetime_gregory_datesymbols_v1, impliterable_datetime_gregory_datesymbols_v1 => icu_datetime::provider::calendar::GregorianDateSymbolsV1Marker,
+        icu_datetime_data: impl_datetime_timelengths_v1, impliterable_datetime_timelengths_v1 => icu_datetime::provider::calendar::TimeLengthsV1Marker,
+        icu_datetime_data: impl_datetime_timesymbols_v1, impliterable_datetime_timesymbols_v1 => icu_datetime::provider::calendar::TimeSymbolsV1Marker,
+        icu_datetime_data: impl_datetime_skeletons_v1, impliterable_datetime_skeletons_v1 => icu_datetime::provider::calendar::DateSkeletonPatternsV1Marker,
+        icu_segmenter_data: impl_segmenter_grapheme_v1, impliterable_segmenter_grapheme_v1 => icu_segmenter::provider::GraphemeClusterBreakDataV1Marker,
+        icu_segmenter_data: impl_segmenter_word_v1, impliterable_segmenter_word_v1 => icu_segmenter::provider::WordBreakDataV1Marker,
+        icu_segmenter_data: impl_segmenter_sentence_v1, impliterable_segmenter_sentence_v1 => icu_segmenter::provider::SentenceBreakDataV1Marker,
+        icu_segmenter_data: impl_segmenter_dictionary_w_auto_v1, impliterable_segmenter_dictionary_w_auto_v1 => icu_segmenter::provider::DictionaryForWordOnlyAutoV1Marker,
+        icu_segmenter_data: impl_segmenter_lstm_wl_auto_v1, impliterable_segmenter_lstm_wl_auto_v1 => icu_segmenter::provider::LstmForWordLineAutoV1Marker,
+        icu_experimental_data: impl_currency_essentials_v1, impliterable_currency_essentials_v1 => icu_experimental::dimension::provider::currency::CurrencyEssentialsV1Marker,
+        icu_experimental_data: impl_percent_essentials_v1, impliterable_percent_essentials_v1 => icu_experimental::dimension::provider::percent::PercentEssentialsV1Marker,
+        icu_experimental_data: impl_relativetime_long_second_v1, impliterable_relativetime_long_second_v1 => icu_experimental::relativetime::provider::LongSecondRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_minute_v1, impliterable_relativetime_long_minute_v1 => icu_experimental::relativetime::provider::LongMinuteRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_hour_v1, impliterable_relativetime_long_hour_v1 => icu_experimental::relativetime::provider::LongHourRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_day_v1, impliterable_relativetime_long_day_v1 => icu_experimental::relativetime::provider::LongDayRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_week_v1, impliterable_relativetime_long_week_v1 => icu_experimental::relativetime::provider::LongWeekRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_month_v1, impliterable_relativetime_long_month_v1 => icu_experimental::relativetime::provider::LongMonthRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_quarter_v1, impliterable_relativetime_long_quarter_v1 => icu_experimental::relativetime::provider::LongQuarterRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_long_year_v1, impliterable_relativetime_long_year_v1 => icu_experimental::relativetime::provider::LongYearRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_second_v1, impliterable_relativetime_short_second_v1 => icu_experimental::relativetime::provider::ShortSecondRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_minute_v1, impliterable_relativetime_short_minute_v1 => icu_experimental::relativetime::provider::ShortMinuteRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_hour_v1, impliterable_relativetime_short_hour_v1 => icu_experimental::relativetime::provider::ShortHourRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_day_v1, impliterable_relativetime_short_day_v1 => icu_experimental::relativetime::provider::ShortDayRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_week_v1, impliterable_relativetime_short_week_v1 => icu_experimental::relativetime::provider::ShortWeekRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_month_v1, impliterable_relativetime_short_month_v1 => icu_experimental::relativetime::provider::ShortMonthRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_quarter_v1, impliterable_relativetime_short_quarter_v1 => icu_experimental::relativetime::provider::ShortQuarterRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_short_year_v1, impliterable_relativetime_short_year_v1 => icu_experimental::relativetime::provider::ShortYearRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_second_v1, impliterable_relativetime_narrow_second_v1 => icu_experimental::relativetime::provider::NarrowSecondRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_minute_v1, impliterable_relativetime_narrow_minute_v1 => icu_experimental::relativetime::provider::NarrowMinuteRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_hour_v1, impliterable_relativetime_narrow_hour_v1 => icu_experimental::relativetime::provider::NarrowHourRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_day_v1, impliterable_relativetime_narrow_day_v1 => icu_experimental::relativetime::provider::NarrowDayRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_week_v1, impliterable_relativetime_narrow_week_v1 => icu_experimental::relativetime::provider::NarrowWeekRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_month_v1, impliterable_relativetime_narrow_month_v1 => icu_experimental::relativetime::provider::NarrowMonthRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_quarter_v1, impliterable_relativetime_narrow_quarter_v1 => icu_experimental::relativetime::provider::NarrowQuarterRelativeTimeFormatDataV1Marker,
+        icu_experimental_data: impl_relativetime_narrow_year_v1, impliterable_relativetime_narrow_year_v1 => icu_experimental::relativetime::provider::NarrowYearRelativeTimeFormatDataV1Marker,
+    );
+
+    /// Language of the default `en-US` locale, baked in regardless of the selection.
+    const DEFAULT_LANGUAGE: &str = "en";
+
+    /// The word segmenter's CJK dictionary is stored under `ja` but also serves Chinese text.
+    const CJK_DICTIONARY_LANGUAGES: &[&str] = &["ja", "zh"];
+
+    pub fn generate() {
+        let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+        let locales_file = manifest_dir.join("intl-locales.txt");
+        println!("cargo:rerun-if-changed={}", locales_file.display());
+
+        let selection = read_selection(&locales_file);
+
+        let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+        let blob = std::fs::File::create(out_dir.join("intl_data.postcard"))
+            .expect("failed to create the Intl data blob");
+        let mut exporter =
+            icu_provider_blob::export::BlobExporter::new_v2_with_sink(Box::new(blob));
+
+        let mut languages = BTreeSet::new();
+        for key in KEYS {
+            let locales = SourceProvider
+                .supported_locales_for_key(*key)
+                .expect("failed to list ICU4X locales");
+            for locale in locales {
+                if !is_selected(&selection, *key, &locale) {
+                    continue;
+                }
+                if !locale.language().is_empty() {
+                    languages.insert(locale.language().to_string());
+                }
+                let payload = DynamicDataProvider::<ExportMarker>::load_data(
+                    &SourceProvider,
+                    *key,
+                    DataRequest {
+                        locale: &locale,
+                        metadata: Default::default(),
+                    },
+                )
+                .and_then(DataResponse::take_payload)
+                .expect("failed to load ICU4X data");
+                exporter
+                    .put_payload(*key, &locale, &payload)
+                    .expect("failed to export ICU4X data");
+            }
+            exporter.flush(*key).expect("failed to export ICU4X data");
+        }
+        exporter.close().expect("failed to export ICU4X data");
+
+        if let Some(selection) = &selection {
+            for language in selection.difference(&languages) {
+                println!("cargo:warning=ICU4X has no locale data for '{language}'");
+            }
+        }
+
+        let languages = languages
+            .iter()
+            .map(|language| format!("{language:?}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        std::fs::write(
+            out_dir.join("intl_languages.rs"),
+            format!("pub const BAKED_LANGUAGES: &[&str] = &[{languages}];\n"),
+        )
+        .expect("failed to write the baked language list");
+    }
+
+    /// Reads the selected languages; `None` means every locale is baked in.
+    ///
+    /// English is added to any non-empty selection since it backs the default locale.
+    fn read_selection(path: &std::path::Path) -> Option<BTreeSet<String>> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let languages = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|tag| {
+                let locale: icu_locid::Locale = tag.parse().unwrap_or_else(|err| {
+                    panic!("invalid locale '{tag}' in intl-locales.txt: {err}")
+                });
+                locale.id.language.to_string()
+            })
+            .collect::<BTreeSet<_>>();
+        (!languages.is_empty()).then(|| {
+            let mut languages = languages;
+            languages.insert(DEFAULT_LANGUAGE.to_string());
+            languages
+        })
+    }
+
+    fn is_selected(
+        selection: &Option<BTreeSet<String>>,
+        key: DataKey,
+        locale: &DataLocale,
+    ) -> bool {
+        let Some(selection) = selection else {
+            return true;
+        };
+        let language = locale.language();
+        if language.is_empty() {
+            return true;
+        }
+        if key
+            == <icu_segmenter::provider::DictionaryForWordOnlyAutoV1Marker as KeyedDataMarker>::KEY
+        {
+            return CJK_DICTIONARY_LANGUAGES
+                .iter()
+                .any(|language| selection.contains(*language));
+        }
+        selection.contains(language.as_str())
+    }
+}
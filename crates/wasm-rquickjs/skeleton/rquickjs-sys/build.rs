@@ -0,0 +1,514 @@
+#![allow(clippy::uninlined_format_args)]
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self},
+};
+
+/// The rquickjs-sys release this crate patches. Its sources are taken from the cargo download cache,
+/// or downloaded from crates.io, and checked against the registry checksum.
+const UPSTREAM_VERSION: &str = "0.10.0";
+const UPSTREAM_CHECKSUM: &str = "57b1b6528590d4d65dc86b5159eae2d0219709546644c66408b2441696d1d725";
+
+/// Returns the directory of the unpacked and patched upstream sources
+fn patched_sources(out_dir: &Path) -> PathBuf {
+    let sources = out_dir.join(format!("rquickjs-sys-{UPSTREAM_VERSION}"));
+    let marker = sources.join(".patched");
+    if marker.try_exists().unwrap() {
+        return sources;
+    }
+    if sources.try_exists().unwrap() {
+        fs::remove_dir_all(&sources).unwrap();
+    }
+
+    let archive = upstream_archive(out_dir);
+    let output = process::Command::new("tar")
+        .args(["-zxf", archive.to_string_lossy().as_ref()])
+        .current_dir(out_dir)
+        .output()
+        .unwrap();
+    if !output.status.success() {
+        panic!(
+            "Unpacking rquickjs-sys failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let patch = fs::read_to_string("quickjs.patch").expect("Unable to read quickjs.patch");
+    apply_patch(&sources, &patch);
+    fs::write(marker, "").unwrap();
+    sources
+}
+
+fn upstream_archive(out_dir: &Path) -> PathBuf {
+    let file_name = format!("rquickjs-sys-{UPSTREAM_VERSION}.crate");
+    let cargo_home = env::var_os("CARGO_HOME").map(PathBuf::from).or_else(|| {
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home| PathBuf::from(home).join(".cargo"))
+    });
+    let cached = cargo_home
+        .and_then(|home| fs::read_dir(home.join("registry").join("cache")).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(&file_name))
+        .find(|path| path.is_file());
+
+    let archive = match cached {
+        Some(archive) => archive,
+        None => {
+            let archive = out_dir.join(&file_name);
+            let uri = format!(
+                "https://static.crates.io/crates/rquickjs-sys/rquickjs-sys-{UPSTREAM_VERSION}.crate"
+            );
+            println!("Downloading rquickjs-sys from {uri} to {archive:?}");
+            let output = process::Command::new("curl")
+                .args([
+                    "--location",
+                    "--fail",
+                    "-o",
+                    archive.to_string_lossy().as_ref(),
+                    uri.as_ref(),
+                ])
+                .output()
+                .expect("failed to download rquickjs-sys with curl");
+            if !output.status.success() {
+                panic!(
+                    "curl rquickjs-sys failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+            }
+            archive
+        }
+    };
+
+    let checksum = Sha256::digest(fs::read(&archive).unwrap())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    if checksum != UPSTREAM_CHECKSUM {
+        panic!("Checksum mismatch for {archive:?}: {checksum}");
+    }
+    archive
+}
+
+/// Applies a unified diff to the files below `root`. Each hunk must apply exactly, at its line
+/// number or anywhere after the previous hunk.
+fn apply_patch(root: &Path, patch: &str) {
+    let mut lines = patch.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(path) = line.strip_prefix("+++ b/") else {
+            continue;
+        };
+        let path = root.join(path);
+        let source = fs::read_to_string(&path).unwrap();
+        let mut file: Vec<&str> = source.lines().collect();
+        let mut search_from = 0;
+        while let Some(header) = lines.next_if(|line| line.starts_with("@@")) {
+            let (old_len, new_len) = hunk_lengths(header);
+            let mut old = Vec::new();
+            let mut new = Vec::new();
+            while old.len() < old_len || new.len() < new_len {
+                let line = lines.next().expect("truncated hunk in quickjs.patch");
+                let (kind, text) = line.split_at(line.len().min(1));
+                if kind != "+" {
+                    old.push(text);
+                }
+                if kind != "-" {
+                    new.push(text);
+                }
+            }
+            let position = (search_from..=file.len().saturating_sub(old.len()))
+                .find(|&start| file[start..start + old.len()] == old[..])
+                .unwrap_or_else(|| panic!("quickjs.patch does not apply to {path:?}"));
+            file.splice(position..position + old.len(), new.iter().copied());
+            search_from = position + new.len();
+        }
+        let mut patched = file.join("\n");
+        patched.push('\n');
+        fs::write(&path, patched).unwrap();
+    }
+}
+
+/// Parses the line counts of a hunk header like `@@ -10,7 +10,8 @@`
+fn hunk_lengths(header: &str) -> (usize, usize) {
+    let mut ranges = header.split(' ').skip(1).take(2).map(|range| {
+        range[1..]
+            .split_once(',')
+            .map_or(Ok(1), |(_, len)| len.parse())
+            .expect("invalid hunk header in quickjs.patch")
+    });
+    (ranges.next().unwrap(), ranges.next().unwrap())
+}
+
+// WASI logic lifted from https://github.com/bytecodealliance/javy/blob/61616e1507d2bf896f46dc8d72687273438b58b2/crates/quickjs-wasm-sys/build.rs#L18
+
+const WASI_SDK_VERSION_MAJOR: usize = 24;
+const WASI_SDK_VERSION_MINOR: usize = 0;
+
+fn download_wasi_sdk() -> PathBuf {
+    let mut wasi_sdk_dir: PathBuf = env::var("OUT_DIR").unwrap().into();
+    wasi_sdk_dir.push("wasi-sdk");
+
+    fs::create_dir_all(&wasi_sdk_dir).unwrap();
+
+    let major_version = WASI_SDK_VERSION_MAJOR;
+    let minor_version = WASI_SDK_VERSION_MINOR;
+
+    let mut archive_path = wasi_sdk_dir.clone();
+    archive_path.push(format!("wasi-sdk-{major_version}-{minor_version}.tar.gz"));
+
+    println!("SDK tar: {archive_path:?}");
+
+    // Download archive if necessary
+    if !archive_path.try_exists().unwrap() {
+        let file_suffix = match (env::consts::OS, env::consts::ARCH) {
+            ("linux", "x86") | ("linux", "x86_64") => "x86_64-linux",
+            ("linux", "aarch64") => "arm64-linux",
+            ("macos", "x86") | ("macos", "x86_64") => "x86_64-macos",
+            ("macos", "aarch64") => "arm64-macos",
+            ("windows", "x86") | ("windows", "x86_64") => "x86_64-windows",
+            ("windows", "aarch64") => "arm64-windows",
+            other => panic!("Unsupported platform tuple {:?}", other),
+        };
+
+        let uri = format!("https://github.com/WebAssembly/wasi-sdk/releases/download/wasi-sdk-{major_version}/wasi-sdk-{major_version}.{minor_version}-{file_suffix}.tar.gz");
+
+        println!("Downloading WASI SDK archive from {uri} to {archive_path:?}");
+
+        let output = process::Command::new("curl")
+            .args([
+                "--location",
+                "-o",
+                archive_path.to_string_lossy().as_ref(),
+                uri.as_ref(),
+            ])
+            .output()
+            .expect("failed to download the WASI SDK with curl");
+        println!("curl output: {}", String::from_utf8_lossy(&output.stdout));
+        println!("curl err: {}", String::from_utf8_lossy(&output.stderr));
+        if !output.status.success() {
+            panic!(
+                "curl WASI SDK failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+
+    let mut test_binary = wasi_sdk_dir.clone();
+    test_binary.extend(["bin", "wasm-ld"]);
+    // Extract archive if necessary
+    if !test_binary.try_exists().unwrap() {
+        println!("Extracting WASI SDK archive {archive_path:?}");
+        let output = process::Command::new("tar")
+            .args([
+                "-zxf",
+                archive_path.to_string_lossy().as_ref(),
+                "--strip-components",
+                "1",
+            ])
+            .current_dir(&wasi_sdk_dir)
+            .output()
+            .unwrap();
+        if !output.status.success() {
+            panic!(
+                "Unpacking WASI SDK failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+
+    wasi_sdk_dir
+}
+
+fn get_wasi_sdk_path() -> PathBuf {
+    std::env::var_os("WASI_SDK")
+        .map(PathBuf::from)
+        .unwrap_or_else(download_wasi_sdk)
+}
+
+fn main() {
+    #[cfg(feature = "logging")]
+    pretty_env_logger::init();
+
+    let features = [
+        "bindgen",
+        "update-bindings",
+        "dump-bytecode",
+        "dump-gc",
+        "dump-gc-free",
+        "dump-free",
+        "dump-leaks",
+        "dump-mem",
+        "dump-objects",
+        "dump-atoms",
+        "dump-shapes",
+        "dump-module-resolve",
+        "dump-promise",
+        "dump-read-object",
+        "disable-assertions",
+    ];
+
+    for feature in &features {
+        println!("cargo:rerun-if-env-changed={}", feature_to_cargo(feature));
+    }
+    println!("cargo:rerun-if-env-changed=CARGO_CFG_SANITIZE");
+    println!("cargo:rerun-if-changed=quickjs.patch");
+
+    let out_dir = env::var("OUT_DIR").expect("No OUT_DIR env var is set by cargo");
+    let out_dir = Path::new(&out_dir);
+
+    let sources = patched_sources(out_dir);
+    let src_dir = sources.join("quickjs");
+
+    let header_files = [
+        "builtin-array-fromasync.h",
+        "dtoa.h",
+        "libregexp-opcode.h",
+        "libregexp.h",
+        "libunicode-table.h",
+        "libunicode.h",
+        "list.h",
+        "quickjs-atom.h",
+        "quickjs-opcode.h",
+        "quickjs-c-atomics.h",
+        "quickjs.h",
+        "cutils.h",
+    ];
+
+    let source_files = [
+        "libregexp.c",
+        "libunicode.c",
+        "cutils.c",
+        "quickjs.c",
+        "dtoa.c",
+    ];
+
+    let mut defines: Vec<(String, Option<&str>)> = vec![("_GNU_SOURCE".into(), None)];
+
+    #[cfg(feature = "disable-assertions")]
+    defines.push(("NDEBUG".into(), None));
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
+    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap();
+
+    let mut builder = cc::Build::new();
+    builder
+        .extra_warnings(false)
+        .flag_if_supported("-Wno-implicit-const-int-float-conversion")
+        //.flag("-Wno-array-bounds")
+        //.flag("-Wno-format-truncation")
+        ;
+
+    match env::var("CARGO_CFG_SANITIZE").as_deref() {
+        Ok("address") => {
+            builder
+                .flag("-fsanitize=address")
+                .flag("-fno-sanitize-recover=all")
+                .flag("-fno-omit-frame-pointer");
+        }
+        Ok("memory") => {
+            builder
+                .flag("-fsanitize=memory")
+                .flag("-fno-sanitize-recover=all")
+                .flag("-fno-omit-frame-pointer");
+        }
+        Ok("thread") => {
+            builder
+                .flag("-fsanitize=thread")
+                .flag("-fno-sanitize-recover=all")
+                .flag("-fno-omit-frame-pointer");
+        }
+        Ok(x) => println!("cargo:warning=Unsupported sanitize_option: '{x}'"),
+        _ => {}
+    }
+
+    let mut bindgen_cflags = vec![];
+
+    if target_os == "windows" {
+        if target_env == "msvc" {
+            env::set_var(
+                "CFLAGS",
+                "/DWIN32_LEAN_AND_MEAN /std:c11 /experimental:c11atomics",
+            );
+        } else {
+            env::set_var("CFLAGS", "-DWIN32_LEAN_AND_MEAN -std=c11");
+        }
+    }
+
+    if target_os == "wasi" {
+        // pretend we're emscripten - there are already ifdefs that match
+        // also, wasi doesn't ahve FE_DOWNWARD or FE_UPWARD
+        defines.push(("EMSCRIPTEN".into(), Some("1")));
+        defines.push(("FE_DOWNWARD".into(), Some("0")));
+        defines.push(("FE_UPWARD".into(), Some("0")));
+    }
+
+    for file in source_files.iter().chain(header_files.iter()) {
+        fs::copy(src_dir.join(file), out_dir.join(file))
+            .expect("Unable to copy source; try 'git submodule update --init'");
+    }
+    fs::copy(
+        sources.join("quickjs.bind.h"),
+        out_dir.join("quickjs.bind.h"),
+    )
+    .expect("Unable to copy source");
+
+    if target_os == "wasi" {
+        let wasi_sdk_path = get_wasi_sdk_path();
+        if !wasi_sdk_path.try_exists().unwrap() {
+            panic!(
+                "wasi-sdk not installed in specified path of {}",
+                wasi_sdk_path.display()
+            );
+        }
+        env::set_var("CC", wasi_sdk_path.join("bin/clang").to_str().unwrap());
+        env::set_var("AR", wasi_sdk_path.join("bin/ar").to_str().unwrap());
+        let sysroot = format!(
+            "--sysroot={}",
+            wasi_sdk_path.join("share/wasi-sysroot").display()
+        );
+        env::set_var("CFLAGS", &sysroot);
+        bindgen_cflags.push(sysroot);
+    }
+
+    // generating bindings
+    bindgen(
+        out_dir,
+        out_dir.join("quickjs.bind.h"),
+        &defines,
+        bindgen_cflags,
+    );
+
+    for (name, value) in &defines {
+        builder.define(name, *value);
+    }
+
+    for src in &source_files {
+        builder.file(out_dir.join(src));
+    }
+
+    builder.compile("libquickjs.a");
+}
+
+fn feature_to_cargo(name: impl AsRef<str>) -> String {
+    format!("CARGO_FEATURE_{}", feature_to_define(name))
+}
+
+fn feature_to_define(name: impl AsRef<str>) -> String {
+    name.as_ref().to_uppercase().replace('-', "_")
+}
+
+#[cfg(not(feature = "bindgen"))]
+fn bindgen<'a, D, H, X, K, V>(out_dir: D, _header_file: H, _defines: X, _add_cflags: Vec<String>)
+where
+    D: AsRef<Path>,
+    H: AsRef<Path>,
+    X: IntoIterator<Item = &'a (K, Option<V>)>,
+    K: AsRef<str> + 'a,
+    V: AsRef<str> + 'a,
+{
+    let target = env::var("TARGET").unwrap();
+
+    if !out_dir
+        .as_ref()
+        .join(format!("rquickjs-sys-{UPSTREAM_VERSION}"))
+        .join("src")
+        .join("bindings")
+        .join(format!("{}.rs", target))
+        .canonicalize()
+        .map(|x| x.exists())
+        .unwrap_or(false)
+    {
+        println!(
+            "cargo:warning=rquickjs probably doesn't ship bindings for platform `{}({})`. try the `bindgen` feature instead.",
+            target,
+            env::var("BUILD_TARGET").unwrap_or("n/a".into())
+        );
+    }
+
+    let bindings_file = out_dir.as_ref().join("bindings.rs");
+
+    fs::write(
+        bindings_file,
+        format!(
+            r#"macro_rules! bindings_env {{
+                ("TARGET") => {{ "{target}" }};
+            }}"#
+        ),
+    )
+    .unwrap();
+}
+
+#[cfg(feature = "bindgen")]
+fn bindgen<'a, D, H, X, K, V>(out_dir: D, header_file: H, defines: X, mut add_cflags: Vec<String>)
+where
+    D: AsRef<Path>,
+    H: AsRef<Path>,
+    X: IntoIterator<Item = &'a (K, Option<V>)>,
+    K: AsRef<str> + 'a,
+    V: AsRef<str> + 'a,
+{
+    let mut target = env::var("TARGET").unwrap();
+    let out_dir = out_dir.as_ref();
+    let header_file = header_file.as_ref();
+
+    // *-pc-windows-gnullvm is special for Rust, Clang accepts only
+    // *-pc-windows-gnu
+    if target.ends_with("windows-gnullvm") {
+        target = target.replace("llvm", "");
+    }
+
+    let mut cflags = vec![format!("--target={}", target)];
+    cflags.append(&mut add_cflags);
+
+    //format!("-I{}", out_dir.parent().display()),
+
+    for (name, value) in defines {
+        cflags.push(if let Some(value) = value {
+            format!("-D{}={}", name.as_ref(), value.as_ref())
+        } else {
+            format!("-D{}", name.as_ref())
+        });
+    }
+
+    let mut builder = bindgen_rs::Builder::default()
+        .use_core()
+        .detect_include_paths(true)
+        .clang_arg("-xc")
+        .clang_arg("-v")
+        .clang_args(cflags)
+        .size_t_is_usize(false)
+        .header(header_file.display().to_string())
+        .allowlist_type("JS.*")
+        .allowlist_function("js.*")
+        .allowlist_function("JS.*")
+        .allowlist_function("__JS.*")
+        .allowlist_var("JS.*")
+        .opaque_type("FILE")
+        .blocklist_type("FILE")
+        .blocklist_function("JS_DumpMemoryUsage");
+
+    if env::var("CARGO_CFG_TARGET_OS").unwrap() == "wasi" {
+        builder = builder.clang_arg("-fvisibility=default");
+    }
+
+    let bindings = builder.generate().expect("Unable to generate bindings");
+
+    let bindings_file = out_dir.join("bindings.rs");
+
+    bindings
+        .write_to_file(&bindings_file)
+        .expect("Couldn't write bindings");
+
+    // Special case to support bundled bindings
+    if env::var("CARGO_FEATURE_UPDATE_BINDINGS").is_ok() {
+        let dest_dir = Path::new("src").join("bindings");
+        fs::create_dir_all(&dest_dir).unwrap();
+
+        let dest_file = format!("{}.rs", target);
+        fs::copy(&bindings_file, dest_dir.join(dest_file)).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+// Async context tracking behind AsyncLocalStorage, following the AsyncContextFrame model of
+// Node.js: the current frame maps each storage to its store, and is replaced instead of mutated,
+// so holding on to a frame is a snapshot of every store.
+//
+// The current context is kept by the engine, which captures it for each job when it is queued
+// (and for promise reactions when they are registered by `then` or `await`) and runs the job in
+// it, so it follows promises, microtasks and the continuations of async functions. It is either
+// `undefined` or a `{ frame, values }` snapshot, with `values` holding the context slots.
+// Callbacks scheduled through timers and process.nextTick capture the context and run in it.
+
+import {
+    get_async_context as getAsyncContext,
+    set_async_context as setAsyncContext,
+    enable_context_hook as enableContextHook,
+} from '__wasm_rquickjs_builtin/async_context_native';
+
+// Context that is not kept in the frame but follows it, like the active domain. Each slot has
+// `enter(value)` and `exit(value)` functions called around running in a context in which the slot
+// is set, and its owner calls `setContextSlot` whenever its current value changes.
+const contextSlots = [];
+
+export function registerContextSlot(slot) {
+    if (contextSlots.length === 0) {
+        enableContextHook();
+    }
+    contextSlots.push(slot);
+    return contextSlots.length - 1;
+}
+
+export function setContextSlot(index, value) {
+    const current = getAsyncContext();
+    const values = current === undefined ? undefined : current.values;
+    if ((values === undefined ? null : values[index] ?? null) === (value ?? null)) {
+        return;
+    }
+    const newValues = values === undefined ? new Array(contextSlots.length) : values.slice();
+    newValues[index] = value;
+    setAsyncContext({ frame: current === undefined ? null : current.frame, values: newValues });
+}
+
+export function getStore(key) {
+    const current = getAsyncContext();
+    return current === undefined || current.frame === null ? undefined : current.frame.get(key);
+}
+
+function withStore(context, key, value) {
+    return {
+        frame: new Map(context === undefined ? null : context.frame).set(key, value),
+        values: context === undefined ? undefined : context.values,
+    };
+}
+
+// Replaces the store of `key` in the current frame until the current context is left
+export function setStore(key, value) {
+    setAsyncContext(withStore(getAsyncContext(), key, value));
+}
+
+export function runWithStore(key, value, fn, thisArg, args) {
+    const previous = getAsyncContext();
+    setAsyncContext(withStore(previous, key, value));
+    try {
+        return fn.apply(thisArg, args);
+    } finally {
+        setAsyncContext(previous);
+    }
+}
+
+export function captureContext() {
+    return getAsyncContext();
+}
+
+function enterSlots(context) {
+    if (context !== undefined && context.values !== undefined) {
+        for (let i = 0; i < contextSlots.length; i++) {
+            if (context.values[i] != null) {
+                contextSlots[i].enter(context.values[i]);
+            }
+        }
+    }
+}
+
+function exitSlots(context) {
+    if (context !== undefined && context.values !== undefined) {
+        for (let i = contextSlots.length - 1; i >= 0; i--) {
+            if (context.values[i] != null) {
+                contextSlots[i].exit(context.values[i]);
+            }
+        }
+    }
+}
+
+export function runInContext(context, fn, thisArg, args) {
+    const previous = getAsyncContext();
+    setAsyncContext(context);
+    enterSlots(context);
+    try {
+        return fn.apply(thisArg, args);
+    } finally {
+        exitSlots(context);
+        setAsyncContext(previous);
+    }
+}
+
+// Called by the engine around running a job in a context, once a context slot is registered
+globalThis.__wasm_rquickjs_async_context_hook = function(isEnter, context) {
+    if (isEnter) {
+        enterSlots(context);
+    } else {
+        exitSlots(context);
+    }
+};
@@ -0,0 +1,96 @@
+use rquickjs::{CatchResultExt, Ctx, Function, Value, qjs};
+use std::ptr::NonNull;
+
+/// The async context API that `rquickjs-sys/quickjs.patch` adds to QuickJS, which the bundled
+/// bindings of rquickjs-sys do not declare
+mod ffi {
+    use rquickjs::qjs::{JSContext, JSRuntime, JSValue};
+    use std::ffi::c_void;
+
+    pub type JSAsyncContextHook = unsafe extern "C" fn(
+        ctx: *mut JSContext,
+        is_enter: bool,
+        async_context: JSValue,
+        opaque: *mut c_void,
+    );
+
+    unsafe extern "C" {
+        pub fn JS_GetAsyncContext(ctx: *mut JSContext) -> JSValue;
+        pub fn JS_SetAsyncContext(ctx: *mut JSContext, value: JSValue);
+        pub fn JS_SetAsyncContextHook(
+            rt: *mut JSRuntime,
+            hook: Option<JSAsyncContextHook>,
+            opaque: *mut c_void,
+        );
+    }
+}
+
+// Native functions for the async context tracking
+#[rquickjs::module]
+pub mod native_module {
+    use super::ffi;
+    use rquickjs::{Ctx, Value, qjs};
+
+    /// Returns the async context of the runtime, which the engine captures for each promise
+    /// reaction and job
+    #[rquickjs::function]
+    pub fn get_async_context<'js>(ctx: Ctx<'js>) -> Value<'js> {
+        unsafe {
+            let raw_ctx = ctx.as_raw().as_ptr();
+            Value::from_raw(ctx, ffi::JS_GetAsyncContext(raw_ctx))
+        }
+    }
+
+    #[rquickjs::function]
+    pub fn set_async_context<'js>(ctx: Ctx<'js>, value: Value<'js>) {
+        unsafe {
+            let raw_ctx = ctx.as_raw().as_ptr();
+            ffi::JS_SetAsyncContext(raw_ctx, qjs::JS_DupValue(raw_ctx, value.as_raw()));
+        }
+    }
+
+    /// Makes the engine report entering and leaving the async context of each job to the
+    /// `__wasm_rquickjs_async_context_hook` global of the job's realm
+    #[rquickjs::function]
+    pub fn enable_context_hook(ctx: Ctx<'_>) {
+        unsafe {
+            ffi::JS_SetAsyncContextHook(
+                qjs::JS_GetRuntime(ctx.as_raw().as_ptr()),
+                Some(super::async_context_hook),
+                std::ptr::null_mut(),
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn async_context_hook(
+    ctx: *mut qjs::JSContext,
+    is_enter: bool,
+    async_context: qjs::JSValue,
+    _opaque: *mut std::ffi::c_void,
+) {
+    let Some(raw_ctx) = NonNull::new(ctx) else {
+        return;
+    };
+    let ctx = unsafe { Ctx::from_raw(raw_ctx) };
+    let async_context = unsafe {
+        Value::from_raw(
+            ctx.clone(),
+            qjs::JS_DupValue(ctx.as_raw().as_ptr(), async_context),
+        )
+    };
+    // The async context stays `undefined` until a store or a context slot gets set
+    if !async_context.is_object() {
+        return;
+    }
+    let Ok(hook) = ctx
+        .globals()
+        .get::<_, Function>("__wasm_rquickjs_async_context_hook")
+    else {
+        return;
+    };
+    let _ = hook.call::<_, ()>((is_enter, async_context)).catch(&ctx);
+}
+
+// Async context frames shared by AsyncLocalStorage, the timers and the domains
+pub const ASYNC_CONTEXT_JS: &str = include_str!("async_context.js");
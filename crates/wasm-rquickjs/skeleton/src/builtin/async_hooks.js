@@ -1,81 +1,126 @@
-// node:async_hooks - partial implementation with AsyncLocalStorage
-// Context propagation through Promise.prototype.then/catch/finally and setTimeout/setInterval.
+// node:async_hooks - AsyncLocalStorage, AsyncResource and the async hooks lifecycle events
+// The underscore-prefixed helpers are only exported for the other builtin modules, node:async_hooks
+// itself re-exports the public API of this module.
+// The async context is kept by the engine across promises, microtasks and `await`, and captured
+// by setTimeout/setInterval and process.nextTick (see __wasm_rquickjs_builtin/async_context).
+//
+// Async ids are assigned to promises, timers, process.nextTick and queueMicrotask callbacks,
+// fetch requests, the requests of the fs callback APIs and TCP sockets and servers, which run
+// their callbacks through _runInAsyncScope.
 //
 // Intentional deviations from upstream Node.js:
-// - triggerAsyncId/executionAsyncResource are stubs (no native async_wrap)
-// - AsyncResource tracks a lightweight execution async id for bind/runInAsyncScope,
-//   but does not emit async_hooks lifecycle events because createHook is a no-op.
-// - AsyncLocalStorage.bind is essentially identity (no context frame capture)
-// - QuickJS `await` uses internal C-level perform_promise_then and bypasses JS-visible
-//   Promise.prototype.then, so await propagation is NOT possible — this is an accepted limitation.
+// - Promises only get async ids while a hook is enabled.
+// - QuickJS resumes `await` internally, so there are no before/after events around the
+//   continuations of async functions, which run in the top-level execution scope.
+// - `destroy` of promises and AsyncResources without `requireManualDestroy` is only emitted for
+//   objects created while a hook with a `destroy` callback is enabled.
+
+import {
+    captureContext,
+    getStore,
+    runInContext,
+    runWithStore,
+    setStore,
+} from '__wasm_rquickjs_builtin/async_context';
+import * as asyncHooksNative from '__wasm_rquickjs_builtin/async_hooks_native';
 
 let _nextAsyncId = 2;
-let _executionAsyncId = 1;
 
-const _alsRegistry = new Set();
-const _enabledHooks = new Set();
+// Async resources whose callbacks are running, innermost last, on top of the top-level execution
+const _topLevelResource = {};
+const _executionStack = [{ asyncId: 1, triggerAsyncId: 0, resource: _topLevelResource }];
+
+const _enabledHooks = [];
+let _hookDepth = 0;
+let _destroyHookCount = 0;
+
+// Emits `destroy` for promises and AsyncResources without `requireManualDestroy` once they are
+// garbage collected
+const _destroyRegistry = new FinalizationRegistry((asyncId) => _emitDestroy(asyncId));
 
 class AsyncLocalStorage {
     constructor() {
-        this._stack = [];
-        _alsRegistry.add(this);
+        this._enabled = false;
     }
 
     getStore() {
-        if (this._stack.length === 0) return undefined;
-        return this._stack[this._stack.length - 1];
+        if (!this._enabled) return undefined;
+        return getStore(this);
     }
 
     run(store, callback, ...args) {
-        _alsRegistry.add(this);
-        this._stack.push(store);
-        try {
-            return callback(...args);
-        } finally {
-            this._stack.pop();
-        }
+        this._enabled = true;
+        return runWithStore(this, store, callback, undefined, args);
     }
 
     exit(callback, ...args) {
-        this._stack.push(undefined);
-        try {
-            return callback(...args);
-        } finally {
-            this._stack.pop();
-        }
+        return runWithStore(this, undefined, callback, undefined, args);
     }
 
     enterWith(store) {
-        _alsRegistry.add(this);
-        if (this._stack.length === 0) {
-            this._stack.push(store);
-        } else {
-            this._stack[this._stack.length - 1] = store;
-        }
+        this._enabled = true;
+        setStore(this, store);
     }
 
     disable() {
-        this._stack.length = 0;
-        _alsRegistry.delete(this);
+        this._enabled = false;
+        if (getStore(this) !== undefined) {
+            setStore(this, undefined);
+        }
     }
 
     snapshot() {
-        const captured = _captureContext();
+        return AsyncLocalStorage.snapshot();
+    }
+
+    static snapshot() {
+        const captured = captureContext();
         return function(fn, ...args) {
-            return _restoreContext(captured, fn, undefined, args);
+            return runInContext(captured, fn, this, args);
         };
     }
 
     static bind(fn) {
-        return fn;
+        if (typeof fn !== 'function') {
+            const err = new TypeError('The "fn" argument must be of type function. Received ' + typeof fn);
+            err.code = 'ERR_INVALID_ARG_TYPE';
+            throw err;
+        }
+        const captured = captureContext();
+        return function(...args) {
+            return runInContext(captured, fn, this, args);
+        };
     }
 }
 
 class AsyncResource {
     constructor(type, options) {
+        if (typeof type !== 'string') {
+            const err = new TypeError('The "type" argument must be of type string. Received ' + typeof type);
+            err.code = 'ERR_INVALID_ARG_TYPE';
+            throw err;
+        }
+        let triggerAsyncId = executionAsyncId();
+        let requireManualDestroy = false;
+        if (typeof options === 'number') {
+            triggerAsyncId = options;
+        } else if (options != null) {
+            if (options.triggerAsyncId !== undefined) {
+                triggerAsyncId = options.triggerAsyncId;
+            }
+            requireManualDestroy = !!options.requireManualDestroy;
+        }
+        if (!Number.isSafeInteger(triggerAsyncId) || triggerAsyncId < -1) {
+            const err = new RangeError('Invalid triggerAsyncId value: ' + triggerAsyncId);
+            err.code = 'ERR_INVALID_ASYNC_ID';
+            throw err;
+        }
         this._type = type;
-        this._asyncId = _nextAsyncId++;
-        this._triggerAsyncId = (options && options.triggerAsyncId) || 0;
+        this._triggerAsyncId = triggerAsyncId;
+        this._asyncId = _emitInit(type, this, triggerAsyncId);
+        if (!requireManualDestroy && _destroyHookCount > 0) {
+            _destroyRegistry.register(this, this._asyncId, this);
+        }
     }
 
     get type() {
@@ -91,6 +136,8 @@ class AsyncResource {
     }
 
     emitDestroy() {
+        _destroyRegistry.unregister(this);
+        _emitDestroy(this._asyncId);
         return this;
     }
 
@@ -100,13 +147,7 @@ class AsyncResource {
             err.code = 'ERR_INVALID_ARG_TYPE';
             throw err;
         }
-        const previous = _executionAsyncId;
-        _executionAsyncId = this._asyncId;
-        try {
-            return fn.apply(thisArg, args);
-        } finally {
-            _executionAsyncId = previous;
-        }
+        return _runInAsyncScope(this._asyncId, this._triggerAsyncId, this, fn, thisArg, args);
     }
 
     bind(fn, thisArg) {
@@ -143,89 +184,208 @@ class AsyncResource {
     }
 }
 
+const _hookNames = ['init', 'before', 'after', 'destroy', 'promiseResolve'];
+
+class AsyncHook {
+    constructor(callbacks = {}) {
+        for (const name of _hookNames) {
+            const callback = callbacks[name];
+            if (callback !== undefined && typeof callback !== 'function') {
+                const err = new TypeError(`hook.${name} must be a function`);
+                err.code = 'ERR_ASYNC_CALLBACK';
+                throw err;
+            }
+            this['_' + name] = callback;
+        }
+    }
+
+    enable() {
+        if (!_enabledHooks.includes(this)) {
+            _enabledHooks.push(this);
+            if (this._destroy !== undefined) {
+                _destroyHookCount++;
+            }
+            if (_enabledHooks.length === 1) {
+                asyncHooksNative.enable_promise_hook();
+            }
+        }
+        return this;
+    }
+
+    disable() {
+        const index = _enabledHooks.indexOf(this);
+        if (index !== -1) {
+            _enabledHooks.splice(index, 1);
+            if (this._destroy !== undefined) {
+                _destroyHookCount--;
+            }
+            if (_enabledHooks.length === 0) {
+                asyncHooksNative.disable_promise_hook();
+            }
+        }
+        return this;
+    }
+}
+
 function createHook(callbacks) {
-    const hook = {
-        enable() {
-            _enabledHooks.add(callbacks || {});
-            return this;
-        },
-        disable() {
-            _enabledHooks.delete(callbacks || {});
-            return this;
-        },
-    };
-    return hook;
+    return new AsyncHook(callbacks);
 }
 
-function _emitInit(type, resource, triggerAsyncId = _executionAsyncId) {
-    const asyncId = _nextAsyncId++;
-    for (const callbacks of [..._enabledHooks]) {
-        if (typeof callbacks.init === 'function') {
-            callbacks.init(asyncId, type, triggerAsyncId, resource);
+// Calls the `name` callback of the enabled hooks. Like in Node.js, errors thrown by the callbacks
+// are uncaught exceptions.
+function _emitHook(name, asyncId, type, triggerAsyncId, resource) {
+    const hooks = _enabledHooks.slice();
+    _hookDepth++;
+    try {
+        for (const hook of hooks) {
+            const callback = hook['_' + name];
+            if (callback === undefined) {
+                continue;
+            }
+            try {
+                if (name === 'init') {
+                    callback.call(hook, asyncId, type, triggerAsyncId, resource);
+                } else {
+                    callback.call(hook, asyncId);
+                }
+            } catch (e) {
+                if (globalThis.__wasm_rquickjs_handleUncaughtError) {
+                    globalThis.__wasm_rquickjs_handleUncaughtError(e);
+                } else if (typeof console !== 'undefined') {
+                    console.error(e);
+                }
+            }
         }
+    } finally {
+        _hookDepth--;
+    }
+}
+
+// Assigns an async id to a new async resource and emits its `init` event
+function _emitInit(type, resource, triggerAsyncId = executionAsyncId()) {
+    const asyncId = _nextAsyncId++;
+    if (_enabledHooks.length > 0) {
+        _emitHook('init', asyncId, type, triggerAsyncId, resource);
     }
     return asyncId;
 }
 
+function _emitDestroy(asyncId) {
+    if (_enabledHooks.length > 0) {
+        _emitHook('destroy', asyncId);
+    }
+}
+
+// Runs `fn` as a callback of the async resource `asyncId`, between its `before` and `after` events
+function _runInAsyncScope(asyncId, triggerAsyncId, resource, fn, thisArg, args) {
+    _executionStack.push({ asyncId, triggerAsyncId, resource });
+    if (_enabledHooks.length > 0) {
+        _emitHook('before', asyncId);
+    }
+    try {
+        return fn.apply(thisArg, args);
+    } finally {
+        if (_enabledHooks.length > 0) {
+            _emitHook('after', asyncId);
+        }
+        _executionStack.pop();
+    }
+}
+
+// Queues `fn` as the only callback of the async `resource`, which is destroyed once it ran. Used
+// for queueMicrotask and the completion callbacks of the fs callback APIs.
+function _queueAsyncCallback(resource, fn) {
+    _originalQueueMicrotask(() => {
+        try {
+            resource.runInAsyncScope(fn);
+        } finally {
+            resource.emitDestroy();
+        }
+    });
+}
+
 function executionAsyncId() {
-    return _executionAsyncId;
+    return _executionStack[_executionStack.length - 1].asyncId;
 }
 
 function triggerAsyncId() {
-    return 0;
+    return _executionStack[_executionStack.length - 1].triggerAsyncId;
 }
 
 function executionAsyncResource() {
-    return {};
+    return _executionStack[_executionStack.length - 1].resource;
 }
 
-function _captureContext() {
-    const snapshot = new Map();
-    for (const als of _alsRegistry) {
-        snapshot.set(als, als.getStore());
+// --- promises ---
+//
+// The runtime reports the creation and fulfillment of promises while hooks are enabled. QuickJS
+// runs the reactions of promises internally, so the callbacks passed to `then` (which `catch` and
+// `finally` also go through) are wrapped below to run as callbacks of the promise `then` returned.
+
+const _promiseAsyncIds = new WeakMap();
+
+globalThis.__wasm_rquickjs_promise_hook = function(type, promise, parent) {
+    // Promises created by the hook callbacks themselves would emit events endlessly
+    if (_hookDepth > 0) {
+        return;
     }
-    return snapshot;
-}
+    if (type === 'init') {
+        const parentIds = parent === undefined ? undefined : _promiseAsyncIds.get(parent);
+        const triggerAsyncId = parentIds === undefined ? executionAsyncId() : parentIds.asyncId;
+        const asyncId = _emitInit('PROMISE', promise, triggerAsyncId);
+        _promiseAsyncIds.set(promise, { asyncId, triggerAsyncId });
+        if (_destroyHookCount > 0) {
+            _destroyRegistry.register(promise, asyncId);
+        }
+    } else if (type === 'resolve') {
+        const ids = _promiseAsyncIds.get(promise);
+        if (ids !== undefined && _enabledHooks.length > 0) {
+            _emitHook('promiseResolve', ids.asyncId);
+        }
+    }
+};
 
-const FunctionPrototypeApply = Function.prototype.apply;
+function _captureContext() {
+    return captureContext();
+}
 
 function _restoreContext(snapshot, fn, thisArg, args) {
-    let wrapped = () => FunctionPrototypeApply.call(fn, thisArg, args);
-    for (const [als, value] of snapshot) {
-        const inner = wrapped;
-        wrapped = () => als.run(value, inner);
-    }
-    return wrapped();
+    return runInContext(snapshot, fn, thisArg, args);
 }
 
-// Wrap a callback to restore the captured async context when invoked.
-// Returns non-function values unchanged (e.g. undefined/null handlers in .then).
-function _wrapCallback(snapshot, cb) {
+// Wraps a callback to run as a callback of the promise returned by `then`, once `reaction.promise`
+// is set to it. Returns non-function values unchanged (e.g. undefined/null handlers in .then).
+function _wrapReaction(reaction, cb) {
     if (typeof cb !== 'function') return cb;
-    return function(...a) { return _restoreContext(snapshot, cb, this, a); };
+    return function(...a) {
+        const ids = _promiseAsyncIds.get(reaction.promise);
+        if (ids === undefined) {
+            return cb.apply(this, a);
+        }
+        return _runInAsyncScope(ids.asyncId, ids.triggerAsyncId, reaction.promise, cb, this, a);
+    };
 }
 
 const _originalThen = Promise.prototype.then;
-const _originalCatch = Promise.prototype.catch;
-const _originalFinally = Promise.prototype.finally;
 
+// `catch` and `finally` call `then`, so they do not need to be patched
 Promise.prototype.then = function(onFulfilled, onRejected) {
-    const snapshot = _captureContext();
-    return _originalThen.call(this, _wrapCallback(snapshot, onFulfilled), _wrapCallback(snapshot, onRejected));
+    if (_enabledHooks.length === 0) {
+        return _originalThen.call(this, onFulfilled, onRejected);
+    }
+    const reaction = { promise: undefined };
+    const promise = _originalThen.call(this, _wrapReaction(reaction, onFulfilled), _wrapReaction(reaction, onRejected));
+    reaction.promise = promise;
+    return promise;
 };
 
-Promise.prototype.catch = function(onRejected) {
-    const snapshot = _captureContext();
-    return _originalCatch.call(this, _wrapCallback(snapshot, onRejected));
-};
+const _originalQueueMicrotask = globalThis.queueMicrotask;
 
-// finally handler receives no arguments, so use a specialized wrapper
-Promise.prototype.finally = function(onFinally) {
-    const snapshot = _captureContext();
-    const wrapped = typeof onFinally === 'function'
-        ? function() { return _restoreContext(snapshot, onFinally, this, []); }
-        : onFinally;
-    return _originalFinally.call(this, wrapped);
+globalThis.queueMicrotask = function queueMicrotask(callback) {
+    if (typeof callback !== 'function') {
+        return _originalQueueMicrotask(callback);
+    }
+    _queueAsyncCallback(new AsyncResource('Microtask', { requireManualDestroy: true }), callback);
 };
 
 export {
@@ -236,6 +396,9 @@ export {
     triggerAsyncId,
     executionAsyncResource,
     _emitInit,
+    _emitDestroy,
+    _runInAsyncScope,
+    _queueAsyncCallback,
     _captureContext,
     _restoreContext,
 };
@@ -247,7 +410,4 @@ export default {
     executionAsyncId,
     triggerAsyncId,
     executionAsyncResource,
-    _emitInit,
-    _captureContext,
-    _restoreContext,
 };
@@ -1,6 +1,71 @@
-// async_hooks module - JavaScript-only implementation (no native functions needed)
+use rquickjs::promise::PromiseHookType;
+use rquickjs::{CatchResultExt, Ctx, Function, Value};
+use std::cell::Cell;
+
+thread_local! {
+    // Number of realms with enabled async hooks, which need the promise lifecycle events
+    static PROMISE_HOOK_CONSUMERS: Cell<usize> = const { Cell::new(0) };
+}
+
+// Native functions for the async_hooks implementation
+#[rquickjs::module]
+pub mod native_module {
+    use super::PROMISE_HOOK_CONSUMERS;
+
+    #[rquickjs::function]
+    pub fn enable_promise_hook() {
+        PROMISE_HOOK_CONSUMERS.set(PROMISE_HOOK_CONSUMERS.get() + 1);
+    }
+
+    #[rquickjs::function]
+    pub fn disable_promise_hook() {
+        PROMISE_HOOK_CONSUMERS.set(PROMISE_HOOK_CONSUMERS.get().saturating_sub(1));
+    }
+}
+
+/// Promise hook of the runtime, forwarding the creation and fulfillment of promises to the
+/// `async_hooks` module of the promise's realm while any realm has enabled async hooks.
+pub fn promise_hook<'js>(
+    ctx: Ctx<'js>,
+    type_: PromiseHookType,
+    promise: Value<'js>,
+    parent: Value<'js>,
+) {
+    // QuickJS only reports before and after around the jobs resolving promises with thenables,
+    // the `then` callbacks are wrapped in JS instead
+    let type_ = match type_ {
+        PromiseHookType::Init => "init",
+        PromiseHookType::Resolve => "resolve",
+        PromiseHookType::Before | PromiseHookType::After => return,
+    };
+    if PROMISE_HOOK_CONSUMERS.get() == 0 {
+        return;
+    }
+    let Ok(handler) = ctx
+        .globals()
+        .get::<_, Function>("__wasm_rquickjs_promise_hook")
+    else {
+        return;
+    };
+    // The handler reports the errors of the hook callbacks itself. Anything else must not stay
+    // pending while QuickJS is in the middle of a promise operation.
+    let _ = handler.call::<_, ()>((type_, promise, parent)).catch(&ctx);
+}
+
+// JS functions for the async_hooks implementation
 pub const ASYNC_HOOKS_JS: &str = include_str!("async_hooks.js");
 
+// The public node:async_hooks API, without the helpers used by the other builtin modules
+pub const NODE_ASYNC_HOOKS_JS: &str = r#"export {
+    AsyncLocalStorage,
+    AsyncResource,
+    createHook,
+    executionAsyncId,
+    triggerAsyncId,
+    executionAsyncResource,
+    default,
+} from '__wasm_rquickjs_builtin/async_hooks';"#;
+
 // Re-export for aliases
 pub const REEXPORT_JS: &str =
     r#"export * from 'node:async_hooks'; export { default } from 'node:async_hooks';"#;
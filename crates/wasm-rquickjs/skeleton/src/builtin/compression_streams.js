@@ -0,0 +1,160 @@
+// CompressionStream and DecompressionStream (https://compression.spec.whatwg.org/)
+import * as streams from '__wasm_rquickjs_builtin/streams';
+import {
+    zlib_stream_new,
+    zlib_stream_push,
+    zlib_stream_close,
+    zlib_stream_ended,
+    brotli_stream_new,
+    brotli_stream_push,
+    brotli_stream_pull,
+    brotli_stream_close,
+} from '__wasm_rquickjs_builtin/zlib_native';
+
+const Z_NO_FLUSH = 0;
+const Z_FINISH = 4;
+const BROTLI_OPERATION_PROCESS = 0;
+const BROTLI_OPERATION_FINISH = 2;
+const CHUNK_SIZE = 16 * 1024;
+
+// zlib_stream_new modes
+const ZLIB_MODES = {
+    'deflate': {compress: 0, decompress: 1},
+    'gzip': {compress: 2, decompress: 3},
+    'deflate-raw': {compress: 4, decompress: 5},
+};
+
+function toUint8Array(chunk) {
+    if (chunk instanceof ArrayBuffer) {
+        return new Uint8Array(chunk);
+    }
+    if (ArrayBuffer.isView(chunk) && !(chunk.buffer instanceof SharedArrayBuffer)) {
+        return new Uint8Array(chunk.buffer, chunk.byteOffset, chunk.byteLength);
+    }
+    throw new TypeError('The chunk must be an ArrayBuffer or an ArrayBufferView');
+}
+
+// Opens a native zlib or brotli stream, returning undefined for unsupported formats
+// (including `br` when the brotli feature is disabled)
+function openHandle(format, decompress) {
+    if (format === 'br') {
+        const handle = brotli_stream_new(decompress ? 1 : 0, '{}');
+        return handle == null ? undefined : {brotli: true, id: handle};
+    }
+    const modes = ZLIB_MODES[format];
+    if (modes === undefined) {
+        return undefined;
+    }
+    const handle = zlib_stream_new(decompress ? modes.decompress : modes.compress, -1, 15, 8, 0);
+    return handle == null ? undefined : {brotli: false, id: handle};
+}
+
+function closeHandle(handle) {
+    if (handle.id !== null) {
+        if (handle.brotli) {
+            brotli_stream_close(handle.id);
+        } else {
+            zlib_stream_close(handle.id);
+        }
+        handle.id = null;
+    }
+}
+
+function push(handle, data, finish) {
+    if (handle.brotli) {
+        return brotli_stream_push(handle.id, data, finish ? BROTLI_OPERATION_FINISH : BROTLI_OPERATION_PROCESS);
+    }
+    return zlib_stream_push(handle.id, data, finish ? Z_FINISH : Z_NO_FLUSH);
+}
+
+function enqueue(controller, bytes) {
+    if (bytes.length > 0) {
+        controller.enqueue(new Uint8Array(bytes));
+    }
+}
+
+function createTransformer(format, decompress, kind) {
+    const handle = openHandle(format, decompress);
+    if (handle === undefined) {
+        const error = new TypeError(`Unsupported ${kind} format: '${format}'`);
+        error.code = 'ERR_INVALID_ARG_VALUE';
+        throw error;
+    }
+
+    const fail = (message) => {
+        closeHandle(handle);
+        return new TypeError(message);
+    };
+
+    return {
+        transform(chunk, controller) {
+            const data = toUint8Array(chunk);
+            if (handle.id === null) {
+                throw new TypeError(`The ${kind} stream has been closed`);
+            }
+            if (decompress && !handle.brotli && data.length > 0 && zlib_stream_ended(handle.id)) {
+                throw fail('Junk found after end of compressed data');
+            }
+            const result = push(handle, data, false);
+            if (result == null) {
+                throw fail(decompress ? 'The compressed data is invalid' : 'Failed to compress data');
+            }
+            enqueue(controller, result);
+        },
+        flush(controller) {
+            if (handle.id === null) {
+                return;
+            }
+            const result = push(handle, new Uint8Array(0), true);
+            if (result == null) {
+                throw fail(decompress ? 'The compressed data is invalid' : 'Failed to compress data');
+            }
+            enqueue(controller, result);
+            if (decompress && handle.brotli) {
+                // The brotli decompressor produces its output on finish, to be pulled in chunks
+                while (true) {
+                    const next = brotli_stream_pull(handle.id, CHUNK_SIZE);
+                    if (next == null) {
+                        throw fail('The compressed data is invalid');
+                    }
+                    if (next.length === 0) {
+                        break;
+                    }
+                    enqueue(controller, next);
+                }
+            } else if (decompress && !zlib_stream_ended(handle.id)) {
+                throw fail('Unexpected end of compressed data');
+            }
+            closeHandle(handle);
+        },
+        cancel() {
+            closeHandle(handle);
+        },
+    };
+}
+
+export class CompressionStream extends streams.TransformStream {
+    constructor(format) {
+        if (arguments.length === 0) {
+            throw new TypeError("Failed to construct 'CompressionStream': 1 argument required, but only 0 present.");
+        }
+        super(createTransformer(String(format), false, 'compression'));
+    }
+
+    get [Symbol.toStringTag]() {
+        return 'CompressionStream';
+    }
+}
+
+export class DecompressionStream extends streams.TransformStream {
+    constructor(format) {
+        if (arguments.length === 0) {
+            throw new TypeError("Failed to construct 'DecompressionStream': 1 argument required, but only 0 present.");
+        }
+        super(createTransformer(String(format), true, 'decompression'));
+    }
+
+    get [Symbol.toStringTag]() {
+        return 'DecompressionStream';
+    }
+}
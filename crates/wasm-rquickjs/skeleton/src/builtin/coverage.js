@@ -0,0 +1,125 @@
+// Code coverage collected by the JS modules instrumented at generation time
+//
+// Every instrumented module registers its statement, function and branch maps on its first
+// executed counter, and increments the returned counters. The reports hold the totals since the
+// component started, so each write replaces the previous one.
+
+import { mkdirSync, writeFileSync } from 'node:fs';
+
+const DEFAULT_DIRECTORY = '/coverage';
+
+const modules = [];
+let stopped = false;
+let reportedFailure = false;
+
+/**
+ * Registers the maps of an instrumented module, returning its zeroed counters.
+ */
+export function registerCoverage(maps) {
+    const counters = {
+        s: new Array(maps.statementMap.length).fill(0),
+        f: new Array(maps.fnMap.length).fill(0),
+        b: maps.branchMap.map(branch => new Array(branch.locations.length).fill(0)),
+    };
+    if (modules.length === 0) {
+        globalThis.process?.on('exit', writeCoverage);
+    }
+    modules.push({ maps, counters });
+    return counters;
+}
+
+function indexed(array) {
+    const result = {};
+    array.forEach((value, index) => {
+        result[index] = value;
+    });
+    return result;
+}
+
+/**
+ * The collected coverage in the Istanbul `coverage-final.json` format
+ */
+export function istanbulCoverage() {
+    const result = {};
+    for (const { maps, counters } of modules) {
+        result[maps.path] = {
+            path: maps.path,
+            statementMap: indexed(maps.statementMap),
+            fnMap: indexed(maps.fnMap),
+            branchMap: indexed(maps.branchMap),
+            s: indexed(counters.s),
+            f: indexed(counters.f),
+            b: indexed(counters.b),
+        };
+    }
+    return result;
+}
+
+/**
+ * The collected coverage as an LCOV tracefile
+ */
+export function lcovCoverage() {
+    const lines = [];
+    for (const { maps, counters } of modules) {
+        lines.push('TN:', `SF:${maps.path}`);
+
+        maps.fnMap.forEach(fn => lines.push(`FN:${fn.line},${fn.name}`));
+        maps.fnMap.forEach((fn, i) => lines.push(`FNDA:${counters.f[i]},${fn.name}`));
+        lines.push(`FNF:${maps.fnMap.length}`, `FNH:${counters.f.filter(count => count > 0).length}`);
+
+        // A line is as covered as the most executed statement starting on it
+        const lineCounts = new Map();
+        maps.statementMap.forEach((loc, i) => {
+            const line = loc.start.line;
+            lineCounts.set(line, Math.max(lineCounts.get(line) ?? 0, counters.s[i]));
+        });
+        const sortedLines = [...lineCounts].sort((a, b) => a[0] - b[0]);
+        sortedLines.forEach(([line, count]) => lines.push(`DA:${line},${count}`));
+        lines.push(`LF:${sortedLines.length}`, `LH:${sortedLines.filter(([, count]) => count > 0).length}`);
+
+        let branchCount = 0;
+        let branchesHit = 0;
+        maps.branchMap.forEach((branch, i) => {
+            counters.b[i].forEach((count, j) => {
+                lines.push(`BRDA:${branch.line},${i},${j},${count}`);
+                branchCount++;
+                if (count > 0) {
+                    branchesHit++;
+                }
+            });
+        });
+        lines.push(`BRF:${branchCount}`, `BRH:${branchesHit}`, 'end_of_record');
+    }
+    return lines.join('\n') + '\n';
+}
+
+/**
+ * Writes `coverage-final.json` and `lcov.info` to the directory named by `NODE_V8_COVERAGE`, or
+ * to `/coverage`. Failures are reported once on stderr, as the directory may not be preopened.
+ */
+export function writeCoverage() {
+    if (stopped || modules.length === 0) {
+        return;
+    }
+    const directory = globalThis.process?.env?.NODE_V8_COVERAGE || DEFAULT_DIRECTORY;
+    try {
+        mkdirSync(directory, { recursive: true });
+        writeFileSync(`${directory}/coverage-final.json`, JSON.stringify(istanbulCoverage()));
+        writeFileSync(`${directory}/lcov.info`, lcovCoverage());
+    } catch (err) {
+        if (!reportedFailure) {
+            reportedFailure = true;
+            console.error(`Failed to write the coverage reports to ${directory}: ${err.message}`);
+        }
+    }
+}
+
+export function takeCoverage() {
+    writeCoverage();
+}
+
+// Writes the final reports, no more get written afterwards
+export function stopCoverage() {
+    writeCoverage();
+    stopped = true;
+}
@@ -0,0 +1,3 @@
+// JS module collecting the counters of the JS modules instrumented by
+// `generate-wrapper-crate --coverage`, and writing the Istanbul JSON and LCOV reports
+pub const COVERAGE_JS: &str = include_str!("coverage.js");
@@ -0,0 +1,317 @@
+// CPU profiles built from the samples of the native sampler
+//
+// Every sample is the stack captured in QuickJS' `Error.prototype.stack` format. Frames are
+// identified by function name and script, so different anonymous functions of the same script
+// share a node. The call frame position of a function is the first position a sample observed in
+// it, as the start position of the function itself is not known.
+
+import {
+    monotonic_time_us,
+    profile_data,
+    start_profiling,
+    stop_profiling,
+} from '__wasm_rquickjs_builtin/cpu_profiler_native';
+import { writeFileSync } from 'node:fs';
+
+export const DEFAULT_SAMPLING_INTERVAL_US = 1000;
+
+const PROGRAM_SAMPLE = -1;
+const IDLE_SAMPLE = -2;
+
+const FRAME_PATTERN = /^\s*at (.*) \((.*)\)$/;
+const POSITION_PATTERN = /^(.*):(\d+):(\d+)$/;
+// Location of a bytecode function frame whose current position was not recorded
+const MISSING_LOCATION = 'missing';
+
+// The builtin error shim replaces `Error`, but the stack trace accessors stay on the native one
+function nativeErrorConstructor() {
+    let constructor = globalThis.Error;
+    while (constructor && !Object.prototype.hasOwnProperty.call(constructor, 'prepareStackTrace')) {
+        constructor = Object.getPrototypeOf(constructor);
+    }
+    return constructor || globalThis.Error;
+}
+
+function parseFrame(line) {
+    const frame = FRAME_PATTERN.exec(line);
+    if (!frame) {
+        return undefined;
+    }
+    const functionName = frame[1] === '<anonymous>' ? '' : frame[1];
+    const position = POSITION_PATTERN.exec(frame[2]);
+    if (!position) {
+        const url = frame[2] === MISSING_LOCATION ? undefined : '';
+        return { functionName, url, line: 0, column: 0 };
+    }
+    return { functionName, url: position[1], line: Number(position[2]), column: Number(position[3]) };
+}
+
+function parseStack(stack) {
+    return stack.split('\n').map(parseFrame).filter(frame => frame !== undefined);
+}
+
+function callFrame(functionName, scriptId, url, lineNumber, columnNumber) {
+    return { functionName, scriptId, url, lineNumber, columnNumber };
+}
+
+class ProfileBuilder {
+    constructor() {
+        this.nodes = [];
+        this.nodeIndex = new Map();
+        this.scriptIds = new Map();
+        this.positions = new Map();
+        this.functionScripts = new Map();
+        this.positionTicks = new Map();
+        this.root = this.addNode(undefined, callFrame('(root)', '0', '', -1, -1));
+    }
+
+    addNode(parent, frame) {
+        const node = { id: this.nodes.length + 1, callFrame: frame, hitCount: 0, children: [] };
+        this.nodes.push(node);
+        if (parent) {
+            parent.children.push(node.id);
+        }
+        return node;
+    }
+
+    scriptId(url) {
+        if (url === '') {
+            return '0';
+        }
+        let id = this.scriptIds.get(url);
+        if (id === undefined) {
+            id = String(this.scriptIds.size + 1);
+            this.scriptIds.set(url, id);
+        }
+        return id;
+    }
+
+    child(parent, frame) {
+        const functionKey = `${frame.functionName}\n${frame.url}`;
+        const key = `${parent.id}\n${functionKey}`;
+        let node = this.nodeIndex.get(key);
+        if (!node) {
+            let position = this.positions.get(functionKey);
+            if (!position) {
+                position = { lineNumber: frame.line - 1, columnNumber: frame.column - 1 };
+                this.positions.set(functionKey, position);
+            }
+            node = this.addNode(parent, callFrame(
+                frame.functionName,
+                this.scriptId(frame.url),
+                frame.url,
+                position.lineNumber,
+                position.columnNumber,
+            ));
+            this.nodeIndex.set(key, node);
+        }
+        return node;
+    }
+
+    special(name) {
+        return this.child(this.root, { functionName: name, url: '', line: 0, column: 0 });
+    }
+
+    // The leaf node of a captured stack, whose frames are listed innermost first, and the line
+    // the innermost frame was executing
+    stackNode(stack) {
+        const frames = parseStack(stack);
+        for (const frame of frames) {
+            if (frame.url === undefined) {
+                frame.url = this.functionScripts.get(frame.functionName) ?? '';
+            }
+        }
+        let node = this.root;
+        for (let i = frames.length - 1; i >= 0; i--) {
+            node = this.child(node, frames[i]);
+        }
+        return { node, line: frames.length > 0 ? frames[0].line : 0 };
+    }
+
+    tick(node, line) {
+        node.hitCount++;
+        if (line > 0) {
+            let ticks = this.positionTicks.get(node);
+            if (!ticks) {
+                ticks = new Map();
+                this.positionTicks.set(node, ticks);
+            }
+            ticks.set(line, (ticks.get(line) || 0) + 1);
+        }
+    }
+
+    build(data, startTime) {
+        // Frames without a position are attributed to the script of a function with the same name
+        for (const stack of data.stacks) {
+            for (const frame of parseStack(stack)) {
+                if (frame.url && !this.functionScripts.has(frame.functionName)) {
+                    this.functionScripts.set(frame.functionName, frame.url);
+                }
+            }
+        }
+
+        const stackNodes = new Map();
+        const samples = [];
+        const timeDeltas = [];
+        let previous = startTime;
+        for (let i = 0; i < data.samples.length; i++) {
+            const sample = data.samples[i];
+            let leaf;
+            if (sample === PROGRAM_SAMPLE) {
+                leaf = { node: this.special('(program)'), line: 0 };
+            } else if (sample === IDLE_SAMPLE) {
+                leaf = { node: this.special('(idle)'), line: 0 };
+            } else {
+                leaf = stackNodes.get(sample);
+                if (!leaf) {
+                    leaf = this.stackNode(data.stacks[sample]);
+                    stackNodes.set(sample, leaf);
+                }
+            }
+            this.tick(leaf.node, leaf.line);
+            samples.push(leaf.node.id);
+            timeDeltas.push(data.timestamps[i] - previous);
+            previous = data.timestamps[i];
+        }
+
+        for (const [node, ticks] of this.positionTicks) {
+            node.positionTicks = [...ticks].map(([line, count]) => ({ line, ticks: count }));
+        }
+        return {
+            nodes: this.nodes,
+            startTime,
+            endTime: data.endTime,
+            samples,
+            timeDeltas,
+        };
+    }
+}
+
+/**
+ * Builds a Chrome `.cpuprofile` (the `Profiler.Profile` type of the DevTools protocol) from the
+ * samples returned by the native `profile_data`.
+ */
+export function buildCpuProfile(data, startTime) {
+    return new ProfileBuilder().build(data, startTime);
+}
+
+/**
+ * Converts a `.cpuprofile` into the speedscope file format.
+ */
+export function toSpeedscope(profile, name) {
+    const frames = [];
+    const frameIndex = new Map();
+    const parents = new Map();
+    const nodes = new Map();
+    for (const node of profile.nodes) {
+        nodes.set(node.id, node);
+        for (const child of node.children) {
+            parents.set(child, node.id);
+        }
+    }
+
+    function frameOf(node) {
+        const { functionName, url, lineNumber, columnNumber } = node.callFrame;
+        const key = `${functionName}\n${url}\n${lineNumber}\n${columnNumber}`;
+        let index = frameIndex.get(key);
+        if (index === undefined) {
+            const frame = { name: functionName || '(anonymous)' };
+            if (url !== '') {
+                frame.file = url;
+                frame.line = lineNumber + 1;
+                frame.col = columnNumber + 1;
+            }
+            index = frames.length;
+            frames.push(frame);
+            frameIndex.set(key, index);
+        }
+        return index;
+    }
+
+    const stacks = new Map();
+    function stackOf(id) {
+        let stack = stacks.get(id);
+        if (!stack) {
+            stack = [];
+            for (let current = id; parents.has(current); current = parents.get(current)) {
+                stack.push(frameOf(nodes.get(current)));
+            }
+            stack.reverse();
+            stacks.set(id, stack);
+        }
+        return stack;
+    }
+
+    const samples = [];
+    const weights = [];
+    let timestamp = profile.startTime;
+    for (let i = 0; i < profile.samples.length; i++) {
+        timestamp += profile.timeDeltas[i];
+        const next = i + 1 < profile.samples.length ? timestamp + profile.timeDeltas[i + 1] : profile.endTime;
+        samples.push(stackOf(profile.samples[i]));
+        weights.push(Math.max(0, next - timestamp));
+    }
+
+    return {
+        $schema: 'https://www.speedscope.app/file-format-schema.json',
+        shared: { frames },
+        profiles: [{
+            type: 'sampled',
+            name,
+            unit: 'microseconds',
+            startValue: profile.startTime,
+            endValue: profile.endTime,
+            samples,
+            weights,
+        }],
+        name,
+        activeProfileIndex: 0,
+        exporter: 'wasm-rquickjs',
+    };
+}
+
+/**
+ * A recording of the shared sampler, from its start until `stop()`
+ */
+export class Recording {
+    constructor(intervalUs = DEFAULT_SAMPLING_INTERVAL_US) {
+        this.offset = start_profiling(nativeErrorConstructor(), intervalUs);
+        this.startTime = monotonic_time_us();
+        this.stopped = false;
+    }
+
+    stop() {
+        if (this.stopped) {
+            return undefined;
+        }
+        const data = profile_data(this.offset);
+        stop_profiling();
+        this.stopped = true;
+        return buildCpuProfile(data, this.startTime);
+    }
+}
+
+// The recording started from the environment, which covers the whole component run
+let environmentRecording;
+
+export function startProfiling(intervalUs) {
+    if (!environmentRecording) {
+        environmentRecording = new Recording(intervalUs ?? DEFAULT_SAMPLING_INTERVAL_US);
+    }
+}
+
+/**
+ * Writes the profile recorded so far by the environment recording to `path`, in the speedscope
+ * format if the file name ends with `.speedscope.json` and as a `.cpuprofile` otherwise.
+ */
+export function writeProfile(path) {
+    const data = profile_data(0);
+    if (!data) {
+        return;
+    }
+    const profile = buildCpuProfile(data, data.startTime);
+    const document = path.endsWith('.speedscope.json')
+        ? toSpeedscope(profile, path.slice(path.lastIndexOf('/') + 1))
+        : profile;
+    writeFileSync(path, JSON.stringify(document));
+}
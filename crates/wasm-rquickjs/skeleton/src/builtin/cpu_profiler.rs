@@ -0,0 +1,339 @@
+// Sampling CPU profiler. QuickJS calls the runtime's interrupt handler periodically while
+// executing bytecode; when the sampling interval elapsed, the handler captures the current call
+// stack by creating an error object. The JS side turns the samples into .cpuprofile or speedscope
+// documents.
+use crate::internal::{JsState, format_caught_error, is_wizer_active};
+use rquickjs::{CatchResultExt, Function, Module, Object, async_with, qjs};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Environment variable naming the file the profile of the whole component run is written to
+const PROFILE_ENV_VAR: &str = "WASM_RQUICKJS_CPU_PROF";
+/// Environment variable overriding the sampling interval in microseconds
+const INTERVAL_ENV_VAR: &str = "WASM_RQUICKJS_CPU_PROF_INTERVAL";
+
+/// Maximum number of frames captured per sample
+const MAX_STACK_DEPTH: i32 = 64;
+
+/// Sample recorded for time spent outside of JS code during an exported call
+const PROGRAM_SAMPLE: i32 = -1;
+/// Sample recorded for time spent between exported calls
+const IDLE_SAMPLE: i32 = -2;
+
+struct Sampler {
+    ctx: *mut qjs::JSContext,
+    /// The native `Error` constructor owning the `stackTraceLimit` and `prepareStackTrace` accessors
+    error_constructor: qjs::JSValue,
+    interval_us: u64,
+    start_us: u64,
+    last_sample_us: u64,
+    suspended: bool,
+    consumers: usize,
+    stack_ids: HashMap<String, i32>,
+    stacks: Vec<String>,
+    samples: Vec<i32>,
+    timestamps: Vec<u64>,
+}
+
+impl Sampler {
+    fn push(&mut self, sample: i32, timestamp: u64) {
+        self.samples.push(sample);
+        self.timestamps.push(timestamp);
+        self.last_sample_us = timestamp;
+    }
+
+    fn tick(&mut self) {
+        if self.suspended {
+            return;
+        }
+        let now = monotonic_us();
+        let elapsed = now.saturating_sub(self.last_sample_us);
+        if elapsed < self.interval_us {
+            return;
+        }
+        // No JS code ran for a while, so the runtime was busy in native code
+        if elapsed >= 2 * self.interval_us && self.samples.last() != Some(&IDLE_SAMPLE) {
+            self.push(PROGRAM_SAMPLE, self.last_sample_us + self.interval_us);
+        }
+        if let Some(stack) = unsafe { self.capture_stack() } {
+            let next_id = self.stacks.len() as i32;
+            let id = *self.stack_ids.entry(stack).or_insert_with_key(|stack| {
+                self.stacks.push(stack.clone());
+                next_id
+            });
+            self.push(id, now);
+        }
+    }
+
+    /// Captures the current stack in QuickJS' `Error.prototype.stack` format.
+    ///
+    /// `Error.prepareStackTrace` is disabled while capturing, so no JS code runs from within the
+    /// interrupt handler.
+    unsafe fn capture_stack(&self) -> Option<String> {
+        unsafe {
+            let ctx = self.ctx;
+            let prepare =
+                qjs::JS_GetPropertyStr(ctx, self.error_constructor, c"prepareStackTrace".as_ptr());
+            let has_prepare = qjs::JS_IsFunction(ctx, prepare);
+            if has_prepare {
+                qjs::JS_SetPropertyStr(
+                    ctx,
+                    self.error_constructor,
+                    c"prepareStackTrace".as_ptr(),
+                    qjs::JS_UNDEFINED,
+                );
+            }
+            let limit =
+                qjs::JS_GetPropertyStr(ctx, self.error_constructor, c"stackTraceLimit".as_ptr());
+            qjs::JS_SetPropertyStr(
+                ctx,
+                self.error_constructor,
+                c"stackTraceLimit".as_ptr(),
+                qjs::JS_NewNumber(ctx, MAX_STACK_DEPTH as f64),
+            );
+
+            let error = qjs::JS_NewError(ctx);
+            let stack = qjs::JS_GetPropertyStr(ctx, error, c"stack".as_ptr());
+            let result = if qjs::JS_IsString(stack) {
+                let mut len = 0;
+                let ptr = qjs::JS_ToCStringLen2(ctx, &mut len, stack, false);
+                if ptr.is_null() {
+                    None
+                } else {
+                    let bytes = std::slice::from_raw_parts(ptr as *const u8, len as usize);
+                    let result = String::from_utf8_lossy(bytes).into_owned();
+                    qjs::JS_FreeCString(ctx, ptr);
+                    Some(result)
+                }
+            } else {
+                None
+            };
+            qjs::JS_FreeValue(ctx, stack);
+            qjs::JS_FreeValue(ctx, error);
+
+            qjs::JS_SetPropertyStr(
+                ctx,
+                self.error_constructor,
+                c"stackTraceLimit".as_ptr(),
+                limit,
+            );
+            if has_prepare {
+                qjs::JS_SetPropertyStr(
+                    ctx,
+                    self.error_constructor,
+                    c"prepareStackTrace".as_ptr(),
+                    prepare,
+                );
+            } else {
+                qjs::JS_FreeValue(ctx, prepare);
+            }
+            result
+        }
+    }
+}
+
+thread_local! {
+    static SAMPLER: RefCell<Option<Sampler>> = const { RefCell::new(None) };
+    static ENVIRONMENT_PROFILE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+unsafe extern "C" fn interrupt_handler(
+    _rt: *mut qjs::JSRuntime,
+    _opaque: *mut std::ffi::c_void,
+) -> std::ffi::c_int {
+    SAMPLER.with(|sampler| {
+        // A getter invoked while capturing the stack can run JS code and re-enter the handler
+        if let Ok(mut sampler) = sampler.try_borrow_mut()
+            && let Some(sampler) = sampler.as_mut()
+        {
+            sampler.tick();
+        }
+    });
+    0
+}
+
+fn monotonic_us() -> u64 {
+    wasip2::clocks::monotonic_clock::now() / 1000
+}
+
+/// Marks the start of an exported call, ending the idle period started by [`suspend`]
+pub fn resume() {
+    SAMPLER.with_borrow_mut(|sampler| {
+        if let Some(sampler) = sampler.as_mut()
+            && sampler.suspended
+        {
+            sampler.suspended = false;
+            sampler.push(PROGRAM_SAMPLE, monotonic_us());
+        }
+    });
+}
+
+/// Marks the end of an exported call. No samples are taken until the next [`resume`].
+pub fn suspend() {
+    SAMPLER.with_borrow_mut(|sampler| {
+        if let Some(sampler) = sampler.as_mut()
+            && !sampler.suspended
+        {
+            sampler.suspended = true;
+            sampler.push(IDLE_SAMPLE, monotonic_us());
+        }
+    });
+}
+
+/// Starts profiling the whole component run if `WASM_RQUICKJS_CPU_PROF` is set.
+///
+/// Does nothing during Wizer pre-initialization, where the environment is not available.
+pub async fn start_environment_profile(state: &JsState) {
+    if is_wizer_active() || ENVIRONMENT_PROFILE.with_borrow(|path| path.is_some()) {
+        return;
+    }
+    let Ok(path) = std::env::var(PROFILE_ENV_VAR) else {
+        return;
+    };
+    let interval_us = std::env::var(INTERVAL_ENV_VAR)
+        .ok()
+        .and_then(|interval| interval.parse::<f64>().ok());
+
+    async_with!(state.ctx => |ctx| {
+        let module: Object = Module::import(&ctx, "__wasm_rquickjs_builtin/cpu_profiler")
+            .catch(&ctx)
+            .and_then(|promise| promise.finish().catch(&ctx))
+            .unwrap_or_else(|e| panic!("Failed to import the CPU profiler:\n{}", format_caught_error(e)));
+        let start: Function = module.get("startProfiling").expect("startProfiling not found");
+        start
+            .call::<_, ()>((interval_us,))
+            .catch(&ctx)
+            .unwrap_or_else(|e| panic!("Failed to start the CPU profiler:\n{}", format_caught_error(e)));
+    })
+    .await;
+    ENVIRONMENT_PROFILE.set(Some(path));
+}
+
+/// Writes the profile started by [`start_environment_profile`] so far, replacing the file
+/// written after the previous exported call.
+pub async fn write_environment_profile(state: &JsState) {
+    let Some(path) = ENVIRONMENT_PROFILE.with_borrow(|path| path.clone()) else {
+        return;
+    };
+
+    async_with!(state.ctx => |ctx| {
+        let result = Module::import(&ctx, "__wasm_rquickjs_builtin/cpu_profiler")
+            .catch(&ctx)
+            .and_then(|promise| promise.finish::<Object>().catch(&ctx))
+            .and_then(|module| {
+                module
+                    .get::<_, Function>("writeProfile")
+                    .catch(&ctx)?
+                    .call::<_, ()>((path.as_str(),))
+                    .catch(&ctx)
+            });
+        if let Err(e) = result {
+            eprintln!("Failed to write the CPU profile to {path}:\n{}", format_caught_error(e));
+        }
+    })
+    .await;
+}
+
+#[rquickjs::module]
+pub mod native_module {
+    use super::{SAMPLER, Sampler, interrupt_handler, monotonic_us};
+    use rquickjs::{Ctx, Object, Value, qjs};
+    use std::collections::HashMap;
+
+    /// Starts the sampler, or joins it if it is already running, and returns the index of the
+    /// first sample belonging to the new recording.
+    #[rquickjs::function]
+    pub fn start_profiling(ctx: Ctx<'_>, error_constructor: Value<'_>, interval_us: f64) -> usize {
+        SAMPLER.with_borrow_mut(|sampler| {
+            if let Some(sampler) = sampler.as_mut() {
+                sampler.consumers += 1;
+                return sampler.samples.len();
+            }
+            let now = monotonic_us();
+            unsafe {
+                let raw_ctx = qjs::JS_DupContext(ctx.as_raw().as_ptr());
+                *sampler = Some(Sampler {
+                    ctx: raw_ctx,
+                    error_constructor: qjs::JS_DupValue(raw_ctx, error_constructor.as_raw()),
+                    interval_us: interval_us.max(1.0) as u64,
+                    start_us: now,
+                    last_sample_us: now,
+                    suspended: false,
+                    consumers: 1,
+                    stack_ids: HashMap::new(),
+                    stacks: Vec::new(),
+                    samples: Vec::new(),
+                    timestamps: Vec::new(),
+                });
+                qjs::JS_SetInterruptHandler(
+                    qjs::JS_GetRuntime(raw_ctx),
+                    Some(interrupt_handler),
+                    std::ptr::null_mut(),
+                );
+            }
+            0
+        })
+    }
+
+    /// Leaves the sampler, stopping it when no other recording is using it
+    #[rquickjs::function]
+    pub fn stop_profiling() {
+        let stopped = SAMPLER.with_borrow_mut(|sampler| {
+            let running = sampler.as_mut()?;
+            running.consumers -= 1;
+            if running.consumers > 0 {
+                return None;
+            }
+            sampler.take()
+        });
+        if let Some(sampler) = stopped {
+            unsafe {
+                qjs::JS_SetInterruptHandler(
+                    qjs::JS_GetRuntime(sampler.ctx),
+                    None,
+                    std::ptr::null_mut(),
+                );
+                qjs::JS_FreeValue(sampler.ctx, sampler.error_constructor);
+                qjs::JS_FreeContext(sampler.ctx);
+            }
+        }
+    }
+
+    /// The samples recorded since the sample with the given index, with `-1` marking time spent
+    /// in native code and `-2` time spent between exported calls.
+    #[rquickjs::function]
+    pub fn profile_data<'js>(ctx: Ctx<'js>, from: usize) -> rquickjs::Result<Option<Object<'js>>> {
+        SAMPLER.with_borrow(|sampler| {
+            let Some(sampler) = sampler.as_ref() else {
+                return Ok(None);
+            };
+            let from = from.min(sampler.samples.len());
+            let result = Object::new(ctx)?;
+            result.set("startTime", sampler.start_us as f64)?;
+            result.set("endTime", monotonic_us() as f64)?;
+            result.set("stacks", sampler.stacks.clone())?;
+            result.set("samples", sampler.samples[from..].to_vec())?;
+            result.set(
+                "timestamps",
+                sampler.timestamps[from..]
+                    .iter()
+                    .map(|timestamp| *timestamp as f64)
+                    .collect::<Vec<_>>(),
+            )?;
+            Ok(Some(result))
+        })
+    }
+
+    #[rquickjs::function]
+    pub fn is_profiling() -> bool {
+        SAMPLER.with_borrow(|sampler| sampler.is_some())
+    }
+
+    #[rquickjs::function]
+    pub fn monotonic_time_us() -> f64 {
+        monotonic_us() as f64
+    }
+}
+
+// JS module building CPU profiles from the samples
+pub const CPU_PROFILER_JS: &str = include_str!("cpu_profiler.js");
@@ -0,0 +1,43 @@
+import * as deterministicNative from '__wasm_rquickjs_builtin/deterministic_native';
+
+const OriginalDate = globalThis.Date;
+
+function Date(...args) {
+    if (new.target === undefined) {
+        return new OriginalDate(deterministicNative.now_ms()).toString();
+    }
+    return Reflect.construct(OriginalDate, args.length === 0 ? [deterministicNative.now_ms()] : args, new.target);
+}
+
+for (const key of Reflect.ownKeys(OriginalDate)) {
+    if (key !== 'prototype' && key !== 'name') {
+        Object.defineProperty(Date, key, Object.getOwnPropertyDescriptor(OriginalDate, key));
+    }
+}
+Object.defineProperty(Date, 'prototype', { value: OriginalDate.prototype, writable: false });
+Object.defineProperty(OriginalDate.prototype, 'constructor', {
+    value: Date,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+});
+Object.defineProperty(Date, 'now', {
+    value: function now() {
+        return deterministicNative.now_ms();
+    },
+    writable: true,
+    enumerable: false,
+    configurable: true,
+});
+
+globalThis.Date = Date;
+
+// QuickJS seeds `Math.random` from the real clock whenever a context is created
+Object.defineProperty(Math, 'random', {
+    value: function random() {
+        return deterministicNative.random();
+    },
+    writable: true,
+    enumerable: false,
+    configurable: true,
+});
@@ -0,0 +1,200 @@
+use crate::internal::{get_js_state, is_wizer_active};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::poll_fn;
+use std::task::{Poll, Waker};
+
+/// Wall-clock time the virtual clock starts from (2024-01-01T00:00:00Z)
+const VIRTUAL_EPOCH_MS: u64 = 1_704_067_200_000;
+
+/// Environment variable overriding the default seed of all randomness sources
+const SEED_ENV_VAR: &str = "WASM_RQUICKJS_SEED";
+const DEFAULT_SEED: u64 = 0;
+
+/// Virtual time base driving timers, `Date` and `process.hrtime`. Time only moves forward
+/// when a timer fires, jumping straight to the timer's due time.
+#[derive(Default)]
+struct VirtualClock {
+    elapsed_ms: u64,
+    next_seq: u64,
+    /// Pending timers keyed by (due time, scheduling order)
+    timers: BTreeMap<(u64, u64), PendingTimer>,
+    /// Set while the runtime drains the remaining tasks after an exported call
+    draining: bool,
+}
+
+struct PendingTimer {
+    timer_key: usize,
+    waker: Option<Waker>,
+}
+
+impl VirtualClock {
+    /// The timer that is allowed to fire next, if any.
+    ///
+    /// Timers fire strictly in due time order. While draining, unref'd timers only fire if a
+    /// ref'd timer is still pending, so they never keep the event loop alive on their own.
+    fn next_due(&self) -> Option<(u64, u64)> {
+        let (slot, _) = self.timers.first_key_value()?;
+        if !self.draining {
+            return Some(*slot);
+        }
+        let unrefed_timers = get_js_state().unrefed_timers.borrow();
+        let any_refed = self
+            .timers
+            .values()
+            .any(|timer| !unrefed_timers.contains(&timer.timer_key));
+        any_refed.then_some(*slot)
+    }
+
+    fn wake_next_due(&mut self) {
+        if let Some(slot) = self.next_due()
+            && let Some(waker) = self
+                .timers
+                .get_mut(&slot)
+                .and_then(|timer| timer.waker.take())
+        {
+            waker.wake();
+        }
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<VirtualClock> = RefCell::new(VirtualClock::default());
+    static RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Removes a timer from the virtual clock when it fired or its task got aborted
+struct TimerSlotGuard((u64, u64));
+
+impl Drop for TimerSlotGuard {
+    fn drop(&mut self) {
+        CLOCK.with_borrow_mut(|clock| {
+            clock.timers.remove(&self.0);
+            clock.wake_next_due();
+        });
+    }
+}
+
+/// Waits until `delay_ms` of virtual time passed for the timer identified by `timer_key`.
+pub async fn sleep(timer_key: usize, delay_ms: u64) {
+    let slot = CLOCK.with_borrow_mut(|clock| {
+        let slot = (clock.elapsed_ms + delay_ms, clock.next_seq);
+        clock.next_seq += 1;
+        clock.timers.insert(
+            slot,
+            PendingTimer {
+                timer_key,
+                waker: None,
+            },
+        );
+        // A new timer may be due earlier than the previous head, or may be the first ref'd one
+        clock.wake_next_due();
+        slot
+    });
+    let _guard = TimerSlotGuard(slot);
+
+    // Once a timer is next in line it yields once more, so tasks that are already runnable
+    // (I/O completions, other spawned work) get polled before virtual time moves forward.
+    let mut yielded = false;
+    poll_fn(|cx| {
+        CLOCK.with_borrow_mut(|clock| {
+            if clock.next_due() == Some(slot) {
+                if yielded {
+                    clock.elapsed_ms = clock.elapsed_ms.max(slot.0);
+                    return Poll::Ready(());
+                }
+                yielded = true;
+                cx.waker().wake_by_ref();
+            } else {
+                yielded = false;
+                if let Some(timer) = clock.timers.get_mut(&slot) {
+                    timer.waker = Some(cx.waker().clone());
+                }
+            }
+            Poll::Pending
+        })
+    })
+    .await
+}
+
+/// Re-evaluates which timer may fire next, after a timer got ref'd or unref'd
+pub fn wake_next_due_timer() {
+    CLOCK.with_borrow_mut(|clock| clock.wake_next_due());
+}
+
+/// Marks whether the runtime is draining the event loop after an exported call
+pub fn set_draining(draining: bool) {
+    CLOCK.with_borrow_mut(|clock| {
+        clock.draining = draining;
+        clock.wake_next_due();
+    });
+}
+
+/// Milliseconds since the Unix epoch according to the virtual clock
+pub fn now_ms() -> u64 {
+    VIRTUAL_EPOCH_MS + CLOCK.with_borrow(|clock| clock.elapsed_ms)
+}
+
+/// Nanoseconds since the Unix epoch according to the virtual clock
+#[cfg(feature = "temporal")]
+pub fn wall_clock_ns() -> i128 {
+    now_ms() as i128 * 1_000_000
+}
+
+/// Monotonic nanoseconds since the runtime started, according to the virtual clock
+pub fn monotonic_ns() -> u64 {
+    CLOCK.with_borrow(|clock| clock.elapsed_ms) * 1_000_000
+}
+
+/// Fills `dest` from the seeded random number generator.
+///
+/// The seed is read from `WASM_RQUICKJS_SEED` on first use. When the first use happens during
+/// Wizer pre-initialization the environment is not available, and the default seed is used.
+pub fn fill_random_bytes(dest: &mut [u8]) {
+    RNG.with_borrow_mut(|rng| {
+        rng.get_or_insert_with(|| {
+            let seed = if is_wizer_active() {
+                DEFAULT_SEED
+            } else {
+                std::env::var(SEED_ENV_VAR)
+                    .ok()
+                    .and_then(|seed| seed.parse().ok())
+                    .unwrap_or(DEFAULT_SEED)
+            };
+            StdRng::seed_from_u64(seed)
+        })
+        .fill_bytes(dest)
+    });
+}
+
+/// Uniformly distributed number in `[0, 1)` from the seeded random number generator
+pub fn random() -> f64 {
+    let mut bytes = [0u8; 8];
+    fill_random_bytes(&mut bytes);
+    // The 53 most significant bits fill the mantissa of a double exactly
+    (u64::from_le_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[rquickjs::module]
+pub mod native_module {
+    #[rquickjs::function]
+    pub fn now_ms() -> f64 {
+        super::now_ms() as f64
+    }
+
+    #[rquickjs::function]
+    pub fn random() -> f64 {
+        super::random()
+    }
+}
+
+// JS module replacing the global `Date` with one reading the virtual clock, and `Math.random`
+// with one reading the seeded random number generator
+pub const DETERMINISTIC_JS: &str = include_str!("deterministic.js");
+
+// Imported before every other builtin so their module-level `Date.now()` calls are virtual too
+pub const WIRE_JS: &str = r#"
+        import '__wasm_rquickjs_builtin/deterministic';
+    "#;
@@ -0,0 +1,27 @@
+use rand::RngCore;
+
+pub async fn sleep(_timer_key: usize, delay_ms: u64) {
+    wstd::task::sleep(wstd::time::Duration::from_millis(delay_ms)).await;
+}
+
+pub fn wake_next_due_timer() {}
+
+pub fn set_draining(_draining: bool) {}
+
+#[cfg(feature = "temporal")]
+pub fn wall_clock_ns() -> i128 {
+    let now = wasip2::clocks::wall_clock::now();
+    now.seconds as i128 * 1_000_000_000 + now.nanoseconds as i128
+}
+
+pub fn monotonic_ns() -> u64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    let origin = ORIGIN.get_or_init(Instant::now);
+    origin.elapsed().as_nanos() as u64
+}
+
+pub fn fill_random_bytes(dest: &mut [u8]) {
+    rand::rng().fill_bytes(dest);
+}
@@ -1,8 +1,12 @@
-// node:dns implementation backed by wasi:sockets/ip-name-lookup
+// node:dns implementation. Address lookups are backed by wasi:sockets/ip-name-lookup, record
+// queries by the stub resolver in dns_resolver.js.
 import { resolve as native_resolve } from '__wasm_rquickjs_builtin/dns_native';
-import { isIP, isIPv4, isIPv6 } from 'node:net';
+import {
+    DnsClient, formatAnswers, makeDnsError, reverseName,
+} from '__wasm_rquickjs_builtin/dns_resolver';
+import { isIP } from 'node:net';
 
-const NOT_SUPPORTED_ERROR_MSG = 'dns record type queries are not supported in WebAssembly environment';
+const NOT_SUPPORTED_ERROR_MSG = 'is not supported in WebAssembly environment';
 
 // Error codes
 export const NODATA = 'ENODATA';
@@ -36,20 +40,25 @@ export const V4MAPPED = 8;
 export const ALL = 16;
 
 let _defaultResultOrder = 'verbatim';
-const VALID_RRTYPES = new Set([
-    'A', 'AAAA', 'ANY', 'CAA', 'CNAME', 'MX', 'NAPTR', 'NS', 'PTR', 'SOA', 'SRV', 'TXT', 'TLSA',
-]);
-
-function makeDnsError(code, hostname, syscall) {
-    const msg = syscall
-        ? `${syscall} ${code} ${hostname}`
-        : `${code} ${hostname}`;
-    const err = new Error(msg);
-    err.code = code;
-    err.hostname = hostname;
-    if (syscall) err.syscall = syscall;
-    return err;
-}
+const RESOLVE_SYSCALLS = {
+    A: 'queryA',
+    AAAA: 'queryAaaa',
+    ANY: 'queryAny',
+    CAA: 'queryCaa',
+    CNAME: 'queryCname',
+    MX: 'queryMx',
+    NAPTR: 'queryNaptr',
+    NS: 'queryNs',
+    PTR: 'queryPtr',
+    SOA: 'querySoa',
+    SRV: 'querySrv',
+    TXT: 'queryTxt',
+    TLSA: 'queryTlsa',
+};
+const VALID_RRTYPES = new Set(Object.keys(RESOLVE_SYSCALLS));
+
+// Servers and options used by the module-level resolve functions
+const defaultClient = new DnsClient();
 
 function parseNativeError(e, hostname, syscall) {
     try {
@@ -60,6 +69,14 @@ function parseNativeError(e, hostname, syscall) {
     }
 }
 
+function validateHostname(hostname, name = 'hostname') {
+    if (typeof hostname !== 'string') {
+        const err = new TypeError(`The "${name}" argument must be of type string. Received ${typeof hostname}`);
+        err.code = 'ERR_INVALID_ARG_TYPE';
+        throw err;
+    }
+}
+
 function invalidRrtypeError(rrtype) {
     const err = new TypeError(`The argument 'rrtype' is invalid. Received '${rrtype}'`);
     err.code = 'ERR_INVALID_ARG_VALUE';
@@ -184,43 +201,59 @@ export function lookup(hostname, optionsOrCallback, callback) {
     })();
 }
 
-export function resolve(hostname, rrtypeOrCallback, callback) {
-    let rrtype = 'A';
-    let cb;
-    let hasRrtype = false;
-    if (typeof rrtypeOrCallback === 'function') {
-        cb = rrtypeOrCallback;
-    } else {
-        hasRrtype = rrtypeOrCallback !== undefined;
-        rrtype = hasRrtype ? rrtypeOrCallback : 'A';
-        cb = callback;
+// Resolves records of `rrtype` through `client`. Without configured servers, A and AAAA
+// queries fall back to wasi:sockets/ip-name-lookup (which reports no TTLs).
+async function queryRecords(client, hostname, rrtype, ttl) {
+    const syscall = RESOLVE_SYSCALLS[rrtype];
+    if (!client.hasServers() && (rrtype === 'A' || rrtype === 'AAAA')) {
+        return lookupByFamily(hostname, rrtype === 'A' ? 4 : 6, syscall, ttl);
     }
-
-    if (hasRrtype && !VALID_RRTYPES.has(rrtype)) {
-        throw invalidRrtypeError(rrtype);
+    const records = formatAnswers(await client.query(hostname, rrtype, syscall), rrtype, ttl);
+    if (records === undefined || records.length === 0) {
+        throw makeDnsError('ENODATA', hostname, syscall);
     }
+    return records;
+}
 
-    if (typeof cb !== 'function') {
-        throw new TypeError('callback must be a function');
+async function lookupByFamily(hostname, family, syscall, ttl) {
+    let results;
+    try {
+        results = await native_resolve(hostname);
+    } catch (e) {
+        throw parseNativeError(e, hostname, syscall);
     }
+    results = results.filter(r => r.family === family);
+    if (results.length === 0) {
+        throw makeDnsError('ENODATA', hostname, syscall);
+    }
+    return ttl
+        ? results.map(r => ({ address: r.address, ttl: 0 }))
+        : results.map(r => r.address);
+}
 
-    switch (rrtype) {
-        case 'A':
-            resolve4(hostname, cb);
-            break;
-        case 'AAAA':
-            resolve6(hostname, cb);
-            break;
-        default:
-            queueMicrotask(() => cb(Object.assign(
-                new Error(`queryA${rrtype} ${NOT_SUPPORTED_ERROR_MSG}`),
-                { code: 'ENOTIMP', hostname }
-            )));
-            break;
+async function queryReverse(client, ip) {
+    if (!client.hasServers() && (ip === '127.0.0.1' || ip === '::1')) {
+        return ['localhost'];
+    }
+    const name = reverseName(ip);
+    if (name === null) {
+        throw makeDnsError('EINVAL', ip, 'getHostByAddr');
     }
+    const records = formatAnswers(await client.query(name, 'PTR', 'getHostByAddr'), 'PTR', false);
+    if (records.length === 0) {
+        throw makeDnsError('ENOTFOUND', ip, 'getHostByAddr');
+    }
+    return records;
+}
+
+function callbackify(promise, callback) {
+    promise.then(
+        (result) => callback(null, result),
+        (err) => callback(err),
+    );
 }
 
-function resolveByFamily(hostname, familyFilter, syscall, optionsOrCallback, callback) {
+function resolveRecords(client, rrtype, hostname, optionsOrCallback, callback) {
     let options = {};
     let cb;
     if (typeof optionsOrCallback === 'function') {
@@ -229,73 +262,83 @@ function resolveByFamily(hostname, familyFilter, syscall, optionsOrCallback, cal
         options = optionsOrCallback || {};
         cb = callback;
     }
+    validateHostname(hostname, 'name');
     if (typeof cb !== 'function') {
         throw new TypeError('callback must be a function');
     }
+    callbackify(queryRecords(client, hostname, rrtype, !!options.ttl), cb);
+}
 
-    (async () => {
-        try {
-            let results = await native_resolve(hostname);
-            results = results.filter(r => r.family === familyFilter);
-            if (results.length === 0) {
-                cb(makeDnsError('ENODATA', hostname, syscall));
-                return;
-            }
-            if (options.ttl) {
-                cb(null, results.map(r => ({ address: r.address, ttl: 0 })));
-            } else {
-                cb(null, results.map(r => r.address));
-            }
-        } catch (e) {
-            cb(parseNativeError(e, hostname, syscall));
+function resolveAnyType(client, hostname, rrtypeOrCallback, callback) {
+    let rrtype = 'A';
+    let cb;
+    if (typeof rrtypeOrCallback === 'function') {
+        cb = rrtypeOrCallback;
+    } else {
+        if (rrtypeOrCallback !== undefined) {
+            rrtype = rrtypeOrCallback;
         }
-    })();
+        cb = callback;
+    }
+
+    if (!VALID_RRTYPES.has(rrtype)) {
+        throw invalidRrtypeError(rrtype);
+    }
+
+    resolveRecords(client, rrtype, hostname, cb);
 }
 
-export function resolve4(hostname, optionsOrCallback, callback) {
-    resolveByFamily(hostname, 4, 'queryA', optionsOrCallback, callback);
+function reverseWith(client, ip, callback) {
+    validateHostname(ip, 'ip');
+    if (typeof callback !== 'function') {
+        throw new TypeError('callback must be a function');
+    }
+    callbackify(queryReverse(client, ip), callback);
 }
 
-export function resolve6(hostname, optionsOrCallback, callback) {
-    resolveByFamily(hostname, 6, 'queryAaaa', optionsOrCallback, callback);
+function resolvePromise(client, rrtype, hostname, options) {
+    validateHostname(hostname, 'name');
+    return queryRecords(client, hostname, rrtype, !!options?.ttl);
 }
 
-function unsupportedRecordType(syscall) {
-    return function (hostname, callback) {
-        if (typeof callback !== 'function') {
-            throw new TypeError('callback must be a function');
-        }
-        queueMicrotask(() => callback(Object.assign(
-            new Error(`${syscall} ${NOT_SUPPORTED_ERROR_MSG}`),
-            { code: 'ENOTIMP', hostname }
-        )));
-    };
+function resolvePromiseAnyType(client, hostname, rrtype) {
+    if (!VALID_RRTYPES.has(rrtype || 'A')) {
+        throw invalidRrtypeError(rrtype);
+    }
+    return resolvePromise(client, rrtype || 'A', hostname);
 }
 
-export const resolveAny = unsupportedRecordType('queryAny');
-export const resolveCname = unsupportedRecordType('queryCname');
-export const resolveCaa = unsupportedRecordType('queryCaa');
-export const resolveMx = unsupportedRecordType('queryMx');
-export const resolveNaptr = unsupportedRecordType('queryNaptr');
-export const resolveNs = unsupportedRecordType('queryNs');
-export const resolvePtr = unsupportedRecordType('queryPtr');
-export const resolveSoa = unsupportedRecordType('querySoa');
-export const resolveSrv = unsupportedRecordType('querySrv');
-export const resolveTxt = unsupportedRecordType('queryTxt');
-export const resolveTlsa = unsupportedRecordType('queryTlsa');
+function reversePromise(client, ip) {
+    validateHostname(ip, 'ip');
+    return queryReverse(client, ip);
+}
+
+export function resolve(hostname, rrtypeOrCallback, callback) {
+    resolveAnyType(defaultClient, hostname, rrtypeOrCallback, callback);
+}
+
+export function resolve4(hostname, optionsOrCallback, callback) {
+    resolveRecords(defaultClient, 'A', hostname, optionsOrCallback, callback);
+}
+
+export function resolve6(hostname, optionsOrCallback, callback) {
+    resolveRecords(defaultClient, 'AAAA', hostname, optionsOrCallback, callback);
+}
+
+export function resolveAny(hostname, callback) { resolveRecords(defaultClient, 'ANY', hostname, callback); }
+export function resolveCname(hostname, callback) { resolveRecords(defaultClient, 'CNAME', hostname, callback); }
+export function resolveCaa(hostname, callback) { resolveRecords(defaultClient, 'CAA', hostname, callback); }
+export function resolveMx(hostname, callback) { resolveRecords(defaultClient, 'MX', hostname, callback); }
+export function resolveNaptr(hostname, callback) { resolveRecords(defaultClient, 'NAPTR', hostname, callback); }
+export function resolveNs(hostname, callback) { resolveRecords(defaultClient, 'NS', hostname, callback); }
+export function resolvePtr(hostname, callback) { resolveRecords(defaultClient, 'PTR', hostname, callback); }
+export function resolveSoa(hostname, callback) { resolveRecords(defaultClient, 'SOA', hostname, callback); }
+export function resolveSrv(hostname, callback) { resolveRecords(defaultClient, 'SRV', hostname, callback); }
+export function resolveTxt(hostname, callback) { resolveRecords(defaultClient, 'TXT', hostname, callback); }
+export function resolveTlsa(hostname, callback) { resolveRecords(defaultClient, 'TLSA', hostname, callback); }
 
 export function reverse(ip, callback) {
-    if (typeof callback !== 'function') {
-        throw new TypeError('callback must be a function');
-    }
-    if (ip === '127.0.0.1' || ip === '::1') {
-        queueMicrotask(() => callback(null, ['localhost']));
-        return;
-    }
-    queueMicrotask(() => callback(Object.assign(
-        new Error(`getHostByAddr ${NOT_SUPPORTED_ERROR_MSG}`),
-        { code: 'ENOTIMP', hostname: ip }
-    )));
+    reverseWith(defaultClient, ip, callback);
 }
 
 export function lookupService(address, port, callback) {
@@ -308,17 +351,12 @@ export function lookupService(address, port, callback) {
     )));
 }
 
-let _servers = [];
-
 export function setServers(servers) {
-    if (!Array.isArray(servers)) {
-        throw new TypeError('servers must be an array');
-    }
-    _servers = servers.slice();
+    defaultClient.setServers(servers);
 }
 
 export function getServers() {
-    return _servers.slice();
+    return defaultClient.getServers();
 }
 
 export function setDefaultResultOrder(order) {
@@ -332,31 +370,27 @@ export function getDefaultResultOrder() {
     return _defaultResultOrder;
 }
 
-// Resolver class
+// Resolver class. New resolvers start out with the servers of the default resolver.
 export class Resolver {
     constructor(options) {
-        this._servers = [];
-        this._timeout = options?.timeout ?? -1;
-        this._tries = options?.tries ?? 4;
+        this._client = new DnsClient(options);
+        this._client.setServers(defaultClient.getServers());
     }
 
     cancel() {
-        // No-op: no outstanding queries to cancel in sync WASI model
+        this._client.cancel();
     }
 
     getServers() {
-        return this._servers.slice();
+        return this._client.getServers();
     }
 
     setServers(servers) {
-        if (!Array.isArray(servers)) {
-            throw new TypeError('servers must be an array');
-        }
-        this._servers = servers.slice();
+        this._client.setServers(servers);
     }
 
-    setLocalAddress(_ipv4, _ipv6) {
-        // No-op: WASI does not support setting source address
+    setLocalAddress(ipv4, ipv6) {
+        this._client.setLocalAddress(ipv4, ipv6);
     }
 
     lookup(hostname, options, callback) {
@@ -364,35 +398,29 @@ export class Resolver {
     }
 
     resolve(hostname, rrtype, callback) {
-        return resolve(hostname, rrtype, callback);
+        return resolveAnyType(this._client, hostname, rrtype, callback);
     }
 
     resolve4(hostname, options, callback) {
-        return resolve4(hostname, options, callback);
+        return resolveRecords(this._client, 'A', hostname, options, callback);
     }
 
     resolve6(hostname, options, callback) {
-        return resolve6(hostname, options, callback);
-    }
-
-    resolveAny(hostname, callback) { return resolveAny(hostname, callback); }
-    resolveCname(hostname, callback) { return resolveCname(hostname, callback); }
-    resolveCaa(hostname, callback) { return resolveCaa(hostname, callback); }
-    resolveMx(hostname, callback) { return resolveMx(hostname, callback); }
-    resolveNaptr(hostname, callback) { return resolveNaptr(hostname, callback); }
-    resolveNs(hostname, callback) { return resolveNs(hostname, callback); }
-    resolvePtr(hostname, callback) { return resolvePtr(hostname, callback); }
-    resolveSoa(hostname, callback) { return resolveSoa(hostname, callback); }
-    resolveSrv(hostname, callback) { return resolveSrv(hostname, callback); }
-    resolveTxt(hostname, callback) { return resolveTxt(hostname, callback); }
-    reverse(ip, callback) { return reverse(ip, callback); }
-}
+        return resolveRecords(this._client, 'AAAA', hostname, options, callback);
+    }
 
-function _unsupportedPromise(syscall, hostname) {
-    return Promise.reject(Object.assign(
-        new Error(`${syscall} ${NOT_SUPPORTED_ERROR_MSG}`),
-        { code: 'ENOTIMP', hostname }
-    ));
+    resolveAny(hostname, callback) { return resolveRecords(this._client, 'ANY', hostname, callback); }
+    resolveCname(hostname, callback) { return resolveRecords(this._client, 'CNAME', hostname, callback); }
+    resolveCaa(hostname, callback) { return resolveRecords(this._client, 'CAA', hostname, callback); }
+    resolveMx(hostname, callback) { return resolveRecords(this._client, 'MX', hostname, callback); }
+    resolveNaptr(hostname, callback) { return resolveRecords(this._client, 'NAPTR', hostname, callback); }
+    resolveNs(hostname, callback) { return resolveRecords(this._client, 'NS', hostname, callback); }
+    resolvePtr(hostname, callback) { return resolveRecords(this._client, 'PTR', hostname, callback); }
+    resolveSoa(hostname, callback) { return resolveRecords(this._client, 'SOA', hostname, callback); }
+    resolveSrv(hostname, callback) { return resolveRecords(this._client, 'SRV', hostname, callback); }
+    resolveTxt(hostname, callback) { return resolveRecords(this._client, 'TXT', hostname, callback); }
+    resolveTlsa(hostname, callback) { return resolveRecords(this._client, 'TLSA', hostname, callback); }
+    reverse(ip, callback) { return reverseWith(this._client, ip, callback); }
 }
 
 // Promise-based API
@@ -420,55 +448,21 @@ export const promises = {
         });
     },
 
-    resolve(hostname, rrtype) {
-        if (!VALID_RRTYPES.has(rrtype || 'A')) {
-            throw invalidRrtypeError(rrtype);
-        }
-        return new Promise((res, reject) => {
-            resolve(hostname, rrtype || 'A', (err, addresses) => {
-                if (err) return reject(err);
-                res(addresses);
-            });
-        });
-    },
-
-    resolve4(hostname, options) {
-        return new Promise((res, reject) => {
-            resolve4(hostname, options || {}, (err, addresses) => {
-                if (err) return reject(err);
-                res(addresses);
-            });
-        });
-    },
-
-    resolve6(hostname, options) {
-        return new Promise((res, reject) => {
-            resolve6(hostname, options || {}, (err, addresses) => {
-                if (err) return reject(err);
-                res(addresses);
-            });
-        });
-    },
-
-    resolveAny(hostname) { return _unsupportedPromise('queryAny', hostname); },
-    resolveCname(hostname) { return _unsupportedPromise('queryCname', hostname); },
-    resolveCaa(hostname) { return _unsupportedPromise('queryCaa', hostname); },
-    resolveMx(hostname) { return _unsupportedPromise('queryMx', hostname); },
-    resolveNaptr(hostname) { return _unsupportedPromise('queryNaptr', hostname); },
-    resolveNs(hostname) { return _unsupportedPromise('queryNs', hostname); },
-    resolvePtr(hostname) { return _unsupportedPromise('queryPtr', hostname); },
-    resolveSoa(hostname) { return _unsupportedPromise('querySoa', hostname); },
-    resolveSrv(hostname) { return _unsupportedPromise('querySrv', hostname); },
-    resolveTxt(hostname) { return _unsupportedPromise('queryTxt', hostname); },
-
-    reverse(ip) {
-        return new Promise((resolve, reject) => {
-            reverse(ip, (err, domains) => {
-                if (err) return reject(err);
-                resolve(domains);
-            });
-        });
-    },
+    resolve(hostname, rrtype) { return resolvePromiseAnyType(defaultClient, hostname, rrtype); },
+    resolve4(hostname, options) { return resolvePromise(defaultClient, 'A', hostname, options); },
+    resolve6(hostname, options) { return resolvePromise(defaultClient, 'AAAA', hostname, options); },
+    resolveAny(hostname) { return resolvePromise(defaultClient, 'ANY', hostname); },
+    resolveCname(hostname) { return resolvePromise(defaultClient, 'CNAME', hostname); },
+    resolveCaa(hostname) { return resolvePromise(defaultClient, 'CAA', hostname); },
+    resolveMx(hostname) { return resolvePromise(defaultClient, 'MX', hostname); },
+    resolveNaptr(hostname) { return resolvePromise(defaultClient, 'NAPTR', hostname); },
+    resolveNs(hostname) { return resolvePromise(defaultClient, 'NS', hostname); },
+    resolvePtr(hostname) { return resolvePromise(defaultClient, 'PTR', hostname); },
+    resolveSoa(hostname) { return resolvePromise(defaultClient, 'SOA', hostname); },
+    resolveSrv(hostname) { return resolvePromise(defaultClient, 'SRV', hostname); },
+    resolveTxt(hostname) { return resolvePromise(defaultClient, 'TXT', hostname); },
+    resolveTlsa(hostname) { return resolvePromise(defaultClient, 'TLSA', hostname); },
+    reverse(ip) { return reversePromise(defaultClient, ip); },
 
     setServers,
     getServers,
@@ -477,35 +471,32 @@ export const promises = {
 
     Resolver: class PromiseResolver {
         constructor(options) {
-            this._servers = [];
-            this._timeout = options?.timeout ?? -1;
-            this._tries = options?.tries ?? 4;
+            this._client = new DnsClient(options);
+            this._client.setServers(defaultClient.getServers());
         }
 
-        cancel() {}
+        cancel() { this._client.cancel(); }
 
-        getServers() { return this._servers.slice(); }
-        setServers(servers) {
-            if (!Array.isArray(servers)) throw new TypeError('servers must be an array');
-            this._servers = servers.slice();
-        }
-        setLocalAddress(_ipv4, _ipv6) {}
+        getServers() { return this._client.getServers(); }
+        setServers(servers) { this._client.setServers(servers); }
+        setLocalAddress(ipv4, ipv6) { this._client.setLocalAddress(ipv4, ipv6); }
 
         lookup(hostname, options) { return promises.lookup(hostname, options); }
-        resolve(hostname, rrtype) { return promises.resolve(hostname, rrtype); }
-        resolve4(hostname, options) { return promises.resolve4(hostname, options); }
-        resolve6(hostname, options) { return promises.resolve6(hostname, options); }
-        resolveAny(hostname) { return promises.resolveAny(hostname); }
-        resolveCname(hostname) { return promises.resolveCname(hostname); }
-        resolveCaa(hostname) { return promises.resolveCaa(hostname); }
-        resolveMx(hostname) { return promises.resolveMx(hostname); }
-        resolveNaptr(hostname) { return promises.resolveNaptr(hostname); }
-        resolveNs(hostname) { return promises.resolveNs(hostname); }
-        resolvePtr(hostname) { return promises.resolvePtr(hostname); }
-        resolveSoa(hostname) { return promises.resolveSoa(hostname); }
-        resolveSrv(hostname) { return promises.resolveSrv(hostname); }
-        resolveTxt(hostname) { return promises.resolveTxt(hostname); }
-        reverse(ip) { return promises.reverse(ip); }
+        resolve(hostname, rrtype) { return resolvePromiseAnyType(this._client, hostname, rrtype); }
+        resolve4(hostname, options) { return resolvePromise(this._client, 'A', hostname, options); }
+        resolve6(hostname, options) { return resolvePromise(this._client, 'AAAA', hostname, options); }
+        resolveAny(hostname) { return resolvePromise(this._client, 'ANY', hostname); }
+        resolveCname(hostname) { return resolvePromise(this._client, 'CNAME', hostname); }
+        resolveCaa(hostname) { return resolvePromise(this._client, 'CAA', hostname); }
+        resolveMx(hostname) { return resolvePromise(this._client, 'MX', hostname); }
+        resolveNaptr(hostname) { return resolvePromise(this._client, 'NAPTR', hostname); }
+        resolveNs(hostname) { return resolvePromise(this._client, 'NS', hostname); }
+        resolvePtr(hostname) { return resolvePromise(this._client, 'PTR', hostname); }
+        resolveSoa(hostname) { return resolvePromise(this._client, 'SOA', hostname); }
+        resolveSrv(hostname) { return resolvePromise(this._client, 'SRV', hostname); }
+        resolveTxt(hostname) { return resolvePromise(this._client, 'TXT', hostname); }
+        resolveTlsa(hostname) { return resolvePromise(this._client, 'TLSA', hostname); }
+        reverse(ip) { return reversePromise(this._client, ip); }
     },
 };
 
@@ -127,6 +127,7 @@ async fn resolve_impl(ctx: &Ctx<'_>, hostname: &str) -> rquickjs::Result<Vec<Dns
 
 pub const DNS_JS: &str = include_str!("dns.js");
 pub const DNS_PROMISES_JS: &str = include_str!("dns_promises.js");
+pub const DNS_RESOLVER_JS: &str = include_str!("dns_resolver.js");
 
 pub const REEXPORT_JS: &str = r#"export * from 'node:dns'; export { default } from 'node:dns';"#;
 pub const REEXPORT_PROMISES_JS: &str =
@@ -18,6 +18,7 @@ export const resolvePtr = promises.resolvePtr;
 export const resolveSoa = promises.resolveSoa;
 export const resolveSrv = promises.resolveSrv;
 export const resolveTxt = promises.resolveTxt;
+export const resolveTlsa = promises.resolveTlsa;
 export const reverse = promises.reverse;
 export const setServers = promises.setServers;
 export const getServers = promises.getServers;
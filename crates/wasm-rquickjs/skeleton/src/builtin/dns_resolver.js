@@ -0,0 +1,612 @@
+// Stub resolver for node:dns record queries. Queries are encoded in the DNS wire format
+// (RFC 1035) and sent over node:dgram to the configured servers, retrying over node:net when
+// a UDP response comes back truncated.
+import { Buffer } from 'node:buffer';
+import dgram from 'node:dgram';
+import net from 'node:net';
+import { domainToASCII } from 'node:url';
+
+const DEFAULT_PORT = 53;
+const DEFAULT_TIMEOUT = 2000;
+const DEFAULT_TRIES = 4;
+
+export const RRTYPES = {
+    A: 1,
+    NS: 2,
+    CNAME: 5,
+    SOA: 6,
+    PTR: 12,
+    MX: 15,
+    TXT: 16,
+    AAAA: 28,
+    SRV: 33,
+    NAPTR: 35,
+    TLSA: 52,
+    ANY: 255,
+    CAA: 257,
+};
+
+const RRTYPE_NAMES = Object.fromEntries(Object.entries(RRTYPES).map(([name, code]) => [code, name]));
+
+// Response codes mapped to the error codes c-ares reports for them
+const RCODE_ERRORS = {
+    1: 'EFORMERR',
+    2: 'ESERVFAIL',
+    3: 'ENOTFOUND',
+    4: 'ENOTIMP',
+    5: 'EREFUSED',
+};
+
+export function makeDnsError(code, hostname, syscall) {
+    const msg = syscall
+        ? `${syscall} ${code} ${hostname}`
+        : `${code} ${hostname}`;
+    const err = new Error(msg);
+    err.code = code;
+    err.hostname = hostname;
+    if (syscall) err.syscall = syscall;
+    return err;
+}
+
+function invalidIpAddressError(server) {
+    const err = new TypeError(`Invalid IP address: ${server}`);
+    err.code = 'ERR_INVALID_IP_ADDRESS';
+    return err;
+}
+
+// Parses a setServers() entry: an IPv4 or IPv6 address, optionally with a port
+// ("1.2.3.4:5353", "[::1]:5353").
+function parseServer(server) {
+    if (typeof server !== 'string') {
+        const err = new TypeError(`The "servers[]" argument must be of type string. Received ${typeof server}`);
+        err.code = 'ERR_INVALID_ARG_TYPE';
+        throw err;
+    }
+    let address = server;
+    let port = DEFAULT_PORT;
+    const bracketed = /^\[([^\]]+)\](?::(\d+))?$/.exec(server);
+    if (bracketed) {
+        address = bracketed[1];
+        if (bracketed[2] !== undefined) port = Number(bracketed[2]);
+    } else if (!net.isIPv6(server)) {
+        const withPort = /^([^:]+):(\d+)$/.exec(server);
+        if (withPort) {
+            address = withPort[1];
+            port = Number(withPort[2]);
+        }
+    }
+    const family = net.isIP(address);
+    if (family === 0 || (bracketed && family !== 6) || port > 65535) {
+        throw invalidIpAddressError(server);
+    }
+    return { address, port, family };
+}
+
+function formatServer({ address, port, family }) {
+    if (port === DEFAULT_PORT) return address;
+    return family === 6 ? `[${address}]:${port}` : `${address}:${port}`;
+}
+
+export function reverseName(ip) {
+    const family = net.isIP(ip);
+    if (family === 4) {
+        return `${ip.split('.').reverse().join('.')}.in-addr.arpa`;
+    }
+    if (family === 6) {
+        const bytes = parseIPv6(ip);
+        const nibbles = [];
+        for (let i = bytes.length - 1; i >= 0; i--) {
+            nibbles.push((bytes[i] & 0x0f).toString(16), (bytes[i] >> 4).toString(16));
+        }
+        return `${nibbles.join('.')}.ip6.arpa`;
+    }
+    return null;
+}
+
+function parseIPv6(ip) {
+    let address = ip;
+    const bytes = new Uint8Array(16);
+    // Embedded IPv4 suffix (::ffff:1.2.3.4)
+    const lastColon = address.lastIndexOf(':');
+    const tail = address.slice(lastColon + 1);
+    let v4 = null;
+    if (tail.includes('.')) {
+        v4 = tail.split('.').map(Number);
+        address = `${address.slice(0, lastColon + 1)}0:0`;
+    }
+    const [head, rest] = address.split('::');
+    const headGroups = head ? head.split(':') : [];
+    const restGroups = rest !== undefined && rest !== '' ? rest.split(':') : [];
+    const groups = rest === undefined
+        ? headGroups
+        : [...headGroups, ...new Array(8 - headGroups.length - restGroups.length).fill('0'), ...restGroups];
+    groups.forEach((group, i) => {
+        const value = parseInt(group, 16);
+        bytes[i * 2] = value >> 8;
+        bytes[i * 2 + 1] = value & 0xff;
+    });
+    if (v4) bytes.set(v4, 12);
+    return bytes;
+}
+
+function formatIPv6(bytes) {
+    const groups = [];
+    for (let i = 0; i < 16; i += 2) {
+        groups.push((bytes[i] << 8) | bytes[i + 1]);
+    }
+    // Compress the longest run of at least two zero groups (RFC 5952)
+    let bestStart = -1;
+    let bestLength = 1;
+    for (let i = 0; i < 8;) {
+        if (groups[i] !== 0) {
+            i++;
+            continue;
+        }
+        let j = i;
+        while (j < 8 && groups[j] === 0) j++;
+        if (j - i > bestLength) {
+            bestStart = i;
+            bestLength = j - i;
+        }
+        i = j;
+    }
+    const hex = groups.map(g => g.toString(16));
+    if (bestStart === -1) return hex.join(':');
+    const head = hex.slice(0, bestStart).join(':');
+    const tail = hex.slice(bestStart + bestLength).join(':');
+    return `${head}::${tail}`;
+}
+
+function encodeName(hostname) {
+    let name = hostname;
+    if (/[^\u0000-\u007f]/.test(name)) {
+        name = domainToASCII(name) || name;
+    }
+    if (name.endsWith('.')) name = name.slice(0, -1);
+    const parts = [];
+    let length = 1;
+    if (name !== '') {
+        for (const label of name.split('.')) {
+            const bytes = Buffer.from(label, 'latin1');
+            if (bytes.length === 0 || bytes.length > 63) return null;
+            parts.push(Buffer.from([bytes.length]), bytes);
+            length += bytes.length + 1;
+        }
+    }
+    if (length > 255) return null;
+    parts.push(Buffer.from([0]));
+    return Buffer.concat(parts);
+}
+
+export function encodeQuery(id, hostname, rrtype) {
+    const name = encodeName(hostname);
+    if (name === null) return null;
+    const header = Buffer.alloc(12);
+    header.writeUInt16BE(id, 0);
+    header.writeUInt16BE(0x0100, 2); // RD
+    header.writeUInt16BE(1, 4); // QDCOUNT
+    const question = Buffer.alloc(4);
+    question.writeUInt16BE(RRTYPES[rrtype], 0);
+    question.writeUInt16BE(1, 2); // IN
+    return Buffer.concat([header, name, question]);
+}
+
+class ResponseReader {
+    constructor(buffer) {
+        this.buffer = buffer;
+        this.offset = 0;
+    }
+
+    ensure(length) {
+        if (this.offset + length > this.buffer.length) {
+            throw new RangeError('truncated DNS message');
+        }
+    }
+
+    u8() {
+        this.ensure(1);
+        return this.buffer[this.offset++];
+    }
+
+    u16() {
+        this.ensure(2);
+        const value = this.buffer.readUInt16BE(this.offset);
+        this.offset += 2;
+        return value;
+    }
+
+    u32() {
+        this.ensure(4);
+        const value = this.buffer.readUInt32BE(this.offset);
+        this.offset += 4;
+        return value;
+    }
+
+    bytes(length) {
+        this.ensure(length);
+        const value = this.buffer.subarray(this.offset, this.offset + length);
+        this.offset += length;
+        return value;
+    }
+
+    characterString() {
+        return this.bytes(this.u8()).toString('latin1');
+    }
+
+    // Reads a possibly compressed domain name
+    name() {
+        const labels = [];
+        let offset = this.offset;
+        let jumped = false;
+        let jumps = 0;
+        for (;;) {
+            if (offset >= this.buffer.length) throw new RangeError('truncated DNS message');
+            const length = this.buffer[offset];
+            if ((length & 0xc0) === 0xc0) {
+                if (offset + 1 >= this.buffer.length || ++jumps > 64) {
+                    throw new RangeError('invalid DNS name compression');
+                }
+                const pointer = ((length & 0x3f) << 8) | this.buffer[offset + 1];
+                if (!jumped) this.offset = offset + 2;
+                jumped = true;
+                offset = pointer;
+                continue;
+            }
+            offset += 1;
+            if (length === 0) break;
+            if (offset + length > this.buffer.length) throw new RangeError('truncated DNS message');
+            labels.push(this.buffer.toString('latin1', offset, offset + length));
+            offset += length;
+        }
+        if (!jumped) this.offset = offset;
+        return labels.join('.');
+    }
+}
+
+function decodeRecordData(reader, type, end) {
+    switch (type) {
+        case RRTYPES.A:
+            return { address: Array.from(reader.bytes(4)).join('.') };
+        case RRTYPES.AAAA:
+            return { address: formatIPv6(reader.bytes(16)) };
+        case RRTYPES.CNAME:
+        case RRTYPES.NS:
+        case RRTYPES.PTR:
+            return { value: reader.name() };
+        case RRTYPES.MX:
+            return { priority: reader.u16(), exchange: reader.name() };
+        case RRTYPES.TXT: {
+            const entries = [];
+            while (reader.offset < end) entries.push(reader.characterString());
+            return { entries };
+        }
+        case RRTYPES.SRV:
+            return { priority: reader.u16(), weight: reader.u16(), port: reader.u16(), name: reader.name() };
+        case RRTYPES.SOA:
+            return {
+                nsname: reader.name(),
+                hostmaster: reader.name(),
+                serial: reader.u32(),
+                refresh: reader.u32(),
+                retry: reader.u32(),
+                expire: reader.u32(),
+                minttl: reader.u32(),
+            };
+        case RRTYPES.NAPTR:
+            return {
+                order: reader.u16(),
+                preference: reader.u16(),
+                flags: reader.characterString(),
+                service: reader.characterString(),
+                regexp: reader.characterString(),
+                replacement: reader.name(),
+            };
+        case RRTYPES.TLSA: {
+            const certUsage = reader.u8();
+            const selector = reader.u8();
+            const match = reader.u8();
+            const data = reader.bytes(end - reader.offset);
+            return {
+                certUsage,
+                selector,
+                match,
+                data: data.buffer.slice(data.byteOffset, data.byteOffset + data.length),
+            };
+        }
+        case RRTYPES.CAA: {
+            const flags = reader.u8();
+            const tag = reader.characterString();
+            const value = reader.bytes(end - reader.offset).toString('latin1');
+            return { critical: flags, [tag]: value };
+        }
+        default:
+            return null;
+    }
+}
+
+// Decodes a response into { id, truncated, rcode, answers }, where each answer carries its
+// numeric type, ttl and decoded record data.
+export function decodeResponse(buffer) {
+    const reader = new ResponseReader(buffer);
+    const id = reader.u16();
+    const flags = reader.u16();
+    const qdcount = reader.u16();
+    const ancount = reader.u16();
+    reader.u16(); // NSCOUNT
+    reader.u16(); // ARCOUNT
+    const response = {
+        id,
+        isResponse: (flags & 0x8000) !== 0,
+        truncated: (flags & 0x0200) !== 0,
+        rcode: flags & 0x000f,
+        answers: [],
+    };
+    if (response.truncated) return response;
+    for (let i = 0; i < qdcount; i++) {
+        reader.name();
+        reader.u16();
+        reader.u16();
+    }
+    for (let i = 0; i < ancount; i++) {
+        reader.name();
+        const type = reader.u16();
+        reader.u16(); // CLASS
+        const ttl = reader.u32();
+        const rdlength = reader.u16();
+        const end = reader.offset + rdlength;
+        reader.ensure(rdlength);
+        const data = decodeRecordData(reader, type, end);
+        reader.offset = end;
+        if (data !== null) {
+            response.answers.push({ type, ttl, data });
+        }
+    }
+    return response;
+}
+
+// Shapes the answers of a response the way node:dns returns them for the given record type
+export function formatAnswers(answers, rrtype, ttl) {
+    if (rrtype === 'ANY') {
+        return answers.map(({ type, ttl, data }) => {
+            const name = RRTYPE_NAMES[type];
+            switch (type) {
+                case RRTYPES.A:
+                case RRTYPES.AAAA:
+                    return { address: data.address, ttl, type: name };
+                default:
+                    return { ...data, type: name };
+            }
+        });
+    }
+    const matching = answers.filter(answer => answer.type === RRTYPES[rrtype]);
+    switch (rrtype) {
+        case 'A':
+        case 'AAAA':
+            return matching.map(answer => (ttl
+                ? { address: answer.data.address, ttl: answer.ttl }
+                : answer.data.address));
+        case 'CNAME':
+        case 'NS':
+        case 'PTR':
+            return matching.map(answer => answer.data.value);
+        case 'TXT':
+            return matching.map(answer => answer.data.entries);
+        case 'SOA':
+            return matching.length > 0 ? matching[0].data : undefined;
+        default:
+            return matching.map(answer => answer.data);
+    }
+}
+
+class QueryTimeout extends Error {}
+
+function randomId() {
+    return Math.floor(Math.random() * 0x10000);
+}
+
+// Sends one query over UDP and resolves with the raw response
+function queryUdp(server, query, id, timeout, localAddress, pending) {
+    return new Promise((resolve, reject) => {
+        const socket = dgram.createSocket(server.family === 6 ? 'udp6' : 'udp4');
+        let timer = null;
+        const finish = (err, response) => {
+            if (timer !== null) clearTimeout(timer);
+            pending.delete(cancel);
+            try {
+                socket.close();
+            } catch (_) {
+                // already closed
+            }
+            if (err) reject(err);
+            else resolve(response);
+        };
+        const cancel = (err) => finish(err);
+        pending.add(cancel);
+
+        socket.on('error', err => finish(err));
+        socket.on('message', (msg, rinfo) => {
+            if (rinfo.port !== server.port || msg.length < 2 || msg.readUInt16BE(0) !== id) {
+                return;
+            }
+            finish(null, msg);
+        });
+        const send = () => {
+            socket.send(query, server.port, server.address, (err) => {
+                if (err) finish(err);
+            });
+        };
+        if (localAddress) {
+            socket.bind(0, localAddress, send);
+        } else {
+            send();
+        }
+        timer = setTimeout(() => finish(new QueryTimeout()), timeout);
+    });
+}
+
+// Sends one query over TCP (two-byte length prefix) and resolves with the raw response
+function queryTcp(server, query, timeout, pending) {
+    return new Promise((resolve, reject) => {
+        const socket = net.createConnection({ host: server.address, port: server.port });
+        let received = Buffer.alloc(0);
+        let timer = null;
+        let done = false;
+        const finish = (err, response) => {
+            if (done) return;
+            done = true;
+            if (timer !== null) clearTimeout(timer);
+            pending.delete(cancel);
+            socket.destroy();
+            if (err) reject(err);
+            else resolve(response);
+        };
+        const cancel = (err) => finish(err);
+        pending.add(cancel);
+
+        socket.on('connect', () => {
+            const prefix = Buffer.alloc(2);
+            prefix.writeUInt16BE(query.length, 0);
+            socket.write(Buffer.concat([prefix, query]));
+        });
+        socket.on('data', (chunk) => {
+            received = Buffer.concat([received, chunk]);
+            if (received.length >= 2) {
+                const length = received.readUInt16BE(0);
+                if (received.length >= length + 2) {
+                    finish(null, received.subarray(2, length + 2));
+                }
+            }
+        });
+        socket.on('error', err => finish(err));
+        socket.on('close', () => finish(new Error('connection closed before a response was received')));
+        timer = setTimeout(() => finish(new QueryTimeout()), timeout);
+    });
+}
+
+// Server list, options and in-flight queries of one dns.Resolver (or of the module-level
+// functions).
+export class DnsClient {
+    constructor(options) {
+        const timeout = options?.timeout ?? -1;
+        const tries = options?.tries ?? DEFAULT_TRIES;
+        if (!Number.isInteger(timeout) || timeout < -1) {
+            const err = new RangeError(`The value of "options.timeout" is out of range. Received ${timeout}`);
+            err.code = 'ERR_OUT_OF_RANGE';
+            throw err;
+        }
+        if (!Number.isInteger(tries) || tries < 1) {
+            const err = new RangeError(`The value of "options.tries" is out of range. Received ${tries}`);
+            err.code = 'ERR_OUT_OF_RANGE';
+            throw err;
+        }
+        this.servers = [];
+        this.timeout = timeout === -1 ? DEFAULT_TIMEOUT : timeout;
+        this.tries = tries;
+        this.localAddress = { ipv4: undefined, ipv6: undefined };
+        this.pending = new Set();
+    }
+
+    hasServers() {
+        return this.servers.length > 0;
+    }
+
+    getServers() {
+        return this.servers.map(formatServer);
+    }
+
+    setServers(servers) {
+        if (!Array.isArray(servers)) {
+            const err = new TypeError('The "servers" argument must be an instance of Array.');
+            err.code = 'ERR_INVALID_ARG_TYPE';
+            throw err;
+        }
+        this.servers = servers.map(parseServer);
+    }
+
+    setLocalAddress(ipv4, ipv6) {
+        if (ipv4 !== undefined && !net.isIPv4(ipv4)) {
+            throw invalidIpAddressError(ipv4);
+        }
+        if (ipv6 !== undefined && !net.isIPv6(ipv6)) {
+            throw invalidIpAddressError(ipv6);
+        }
+        this.localAddress = { ipv4, ipv6 };
+    }
+
+    cancel() {
+        for (const cancel of [...this.pending]) {
+            cancel(Object.assign(new Error('query cancelled'), { code: 'ECANCELLED' }));
+        }
+    }
+
+    // Resolves with the decoded answers for `hostname`, trying each server in turn `tries`
+    // times. Rejects with a node:dns style error (syscall `syscall`).
+    async query(hostname, rrtype, syscall) {
+        if (this.servers.length === 0) {
+            throw makeDnsError('ECONNREFUSED', hostname, syscall);
+        }
+        const id = randomId();
+        const query = encodeQuery(id, hostname, rrtype);
+        if (query === null) {
+            throw makeDnsError('EBADNAME', hostname, syscall);
+        }
+        let lastCode = 'ETIMEOUT';
+        for (let attempt = 0; attempt < this.tries; attempt++) {
+            for (const server of this.servers) {
+                let response;
+                try {
+                    response = await this.exchange(server, query, id);
+                } catch (e) {
+                    if (e.code === 'ECANCELLED') {
+                        throw makeDnsError('ECANCELLED', hostname, syscall);
+                    }
+                    lastCode = transportErrorCode(e);
+                    continue;
+                }
+                if (response.id !== id || !response.isResponse) {
+                    lastCode = 'EBADRESP';
+                    continue;
+                }
+                const code = RCODE_ERRORS[response.rcode];
+                if (code === 'ESERVFAIL' || code === 'EREFUSED' || code === 'ENOTIMP') {
+                    // Another server may be able to answer
+                    lastCode = code;
+                    continue;
+                }
+                if (code !== undefined) {
+                    throw makeDnsError(code, hostname, syscall);
+                }
+                return response.answers;
+            }
+        }
+        throw makeDnsError(lastCode, hostname, syscall);
+    }
+
+    // Queries one server over UDP, falling back to TCP if the response is truncated
+    async exchange(server, query, id) {
+        const localAddress = server.family === 6 ? this.localAddress.ipv6 : this.localAddress.ipv4;
+        const response = decodeResponse(
+            await queryUdp(server, query, id, this.timeout, localAddress, this.pending),
+        );
+        if (!response.truncated) {
+            return response;
+        }
+        return decodeResponse(await queryTcp(server, query, this.timeout, this.pending));
+    }
+}
+
+function transportErrorCode(e) {
+    if (e instanceof QueryTimeout) return 'ETIMEOUT';
+    if (e instanceof RangeError) return 'EBADRESP';
+    return 'ECONNREFUSED';
+}
+
+export default {
+    RRTYPES,
+    DnsClient,
+    makeDnsError,
+    reverseName,
+    encodeQuery,
+    decodeResponse,
+    formatAnswers,
+};
@@ -8,12 +8,21 @@
 //   - Error handlers run outside their domain (stack is unwound before handler)
 //
 import EventEmitter from 'node:events';
+import { registerContextSlot, setContextSlot } from '__wasm_rquickjs_builtin/async_context';
 
 export const _stack = [];
 export let active = null;
 
+// Callbacks run in a captured async context (promise reactions, the continuations of async
+// functions, nextTick callbacks and timers) enter the domain that was active when it was captured.
+const _contextSlot = registerContextSlot({
+    enter: (domain) => domain.enter(),
+    exit: (domain) => domain.exit(),
+});
+
 function updateActive() {
     active = _stack.length > 0 ? _stack[_stack.length - 1] : null;
+    setContextSlot(_contextSlot, active);
     if (globalThis.process) {
         // Node.js sets process.domain to undefined when no domain is active
         // (after domain module has been loaded), not null.
@@ -23,6 +23,7 @@ import {
     validateMkdtempPrefix as _validateMkdtempPrefix,
     makeAbortError,
 } from '__wasm_rquickjs_builtin/internal/fs/shared';
+import { AsyncResource, _queueAsyncCallback } from '__wasm_rquickjs_builtin/async_hooks';
 
 const kIoMaxLength = 2 ** 31 - 1;
 
@@ -318,6 +319,12 @@ function validateCallback(cb) {
     }
 }
 
+// Completes a request of the callback API asynchronously, as the callback of an FSREQCALLBACK
+// async resource like in Node.js
+function queueRequest(complete) {
+    _queueAsyncCallback(new AsyncResource('FSREQCALLBACK', { requireManualDestroy: true }), complete);
+}
+
 function validateOpendirOptions(options) {
     if (options === undefined || options === null || typeof options === 'string') {
         return;
@@ -561,7 +568,7 @@ internalFsBinding.readdir = function readdir(path, encoding, withFileTypes, req)
     const runReaddir = () => readDirViaNativeBinding(path, !!withFileTypes);
 
     if (req && typeof req === 'object') {
-        queueMicrotask(() => {
+        queueRequest(() => {
             try {
                 req.oncomplete(null, runReaddir());
             } catch (err) {
@@ -717,7 +724,7 @@ export class Dir {
         }
         const withPendingOp = (action, onError) => {
             this._pendingAsyncOps += 1;
-            queueMicrotask(() => {
+            queueRequest(() => {
                 try {
                     this._assertNotClosed();
                     action();
@@ -754,7 +761,7 @@ export class Dir {
         };
         if (typeof cb === 'function') {
             this._pendingAsyncOps += 1;
-            queueMicrotask(() => {
+            queueRequest(() => {
                 try {
                     closeInternal();
                     cb(null);
@@ -768,7 +775,7 @@ export class Dir {
         }
         return new Promise((resolve, reject) => {
             this._pendingAsyncOps += 1;
-            queueMicrotask(() => {
+            queueRequest(() => {
                 try {
                     closeInternal();
                     resolve();
@@ -1635,7 +1642,7 @@ export function readFile(path, optionsOrCallback, callback) {
     if (signal && signal.aborted) {
         const e = new DOMException('The operation was aborted', 'AbortError');
         e.name = 'AbortError';
-        queueMicrotask(() => cb(e));
+        queueRequest(() => cb(e));
         return;
     }
     if (signal) {
@@ -1645,7 +1652,7 @@ export function readFile(path, optionsOrCallback, callback) {
             cb(e);
         }, { once: true });
     }
-    queueMicrotask(() => {
+    queueRequest(() => {
         if (signal && signal.aborted) {
             const e = new DOMException('The operation was aborted', 'AbortError');
             e.name = 'AbortError';
@@ -1686,7 +1693,7 @@ export function writeFile(path, data, optionsOrCallback, callback) {
     if (signal && signal.aborted) {
         const e = new DOMException('The operation was aborted', 'AbortError');
         e.name = 'AbortError';
-        queueMicrotask(() => cb(e));
+        queueRequest(() => cb(e));
         return;
     }
     if (signal) {
@@ -1696,7 +1703,7 @@ export function writeFile(path, data, optionsOrCallback, callback) {
             cb(e);
         }, { once: true });
     }
-    queueMicrotask(() => {
+    queueRequest(() => {
         if (signal && signal.aborted) {
             const e = new DOMException('The operation was aborted', 'AbortError');
             e.name = 'AbortError';
@@ -1741,7 +1748,7 @@ export function appendFile(path, data, optionsOrCallback, callback) {
     validateFlush(flush);
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const appendOpts = flush !== undefined ? Object.assign({}, opts, { flush: undefined }) : opts;
             appendFileSync(path, data, appendOpts);
@@ -1787,7 +1794,7 @@ export function open(path, flagsOrCallback, modeOrCallback, callback) {
     mode = validateMode(mode, 'mode', 0o666);
     mode = mode & ~process.umask();
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = native.fs_open(pathToString(path), flags, mode);
             if (result.error) {
@@ -1812,7 +1819,7 @@ export function close(fd, callback) {
         callback = function() {};
     }
     const cb = callback;
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             closeSync(fd);
             cb(null);
@@ -1929,7 +1936,7 @@ export function write(fd, bufferOrString, offsetOrPosition, lengthOrEncoding, po
             cb = positionOrCallback || callback;
         }
         validateCallback(cb);
-        queueMicrotask(() => {
+        queueRequest(() => {
             try {
                 const written = writeSync(fd, bufferOrString, offsetOrPosition, lengthOrEncoding);
                 cb(null, written, bufferOrString);
@@ -2038,7 +2045,7 @@ export function write(fd, bufferOrString, offsetOrPosition, lengthOrEncoding, po
     }
 
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const written = writeSync(fd, bufferOrString, offset, length, position);
             cb(null, written, bufferOrString);
@@ -2056,7 +2063,7 @@ export function stat(path, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = statSync(path, optionsOrCallback);
             cb(null, result);
@@ -2074,7 +2081,7 @@ export function lstat(path, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = lstatSync(path, optionsOrCallback);
             cb(null, result);
@@ -2092,7 +2099,7 @@ export function statfs(path, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = statfsSync(path, optionsOrCallback);
             cb(null, result);
@@ -2110,7 +2117,7 @@ export function fstat(fd, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = fstatSync(fd, optionsOrCallback);
             cb(null, result);
@@ -2134,7 +2141,7 @@ export function ftruncate(fd, lenOrCallback, callback) {
         cb = callback;
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             ftruncateSync(fd, len);
             cb(null);
@@ -2146,7 +2153,7 @@ export function ftruncate(fd, lenOrCallback, callback) {
 
 export function fsync(fd, callback) {
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             fsyncSync(fd);
             callback(null);
@@ -2158,7 +2165,7 @@ export function fsync(fd, callback) {
 
 export function fdatasync(fd, callback) {
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             fdatasyncSync(fd);
             callback(null);
@@ -2266,7 +2273,7 @@ export function access(path, modeOrCallback, callback) {
         cb = callback;
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             accessSync(path, mode);
             cb(null);
@@ -2283,7 +2290,7 @@ export function exists(path, callback) {
             { code: 'ERR_INVALID_ARG_TYPE' }
         );
     }
-    queueMicrotask(() => {
+    queueRequest(() => {
         callback(existsSync(path));
     });
 }
@@ -2307,7 +2314,7 @@ export function realpath(path, optionsOrCallback, callback) {
             cb(err);
         }
     } else {
-        queueMicrotask(() => {
+        queueRequest(() => {
             try {
                 const result = realpathSync(path, opts);
                 cb(null, result);
@@ -2328,7 +2335,7 @@ function realpathNative(path, optionsOrCallback, callback) {
     if (opts.encoding) validateEncoding(opts.encoding, 'encoding', true);
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = realpathSyncNative(path, opts);
             cb(null, result);
@@ -2357,7 +2364,7 @@ export function truncate(path, lenOrCallback, callback) {
         cb = callback;
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             truncateSync(path, len);
             cb(null);
@@ -2379,7 +2386,7 @@ export function copyFile(src, dest, modeOrCallback, callback) {
         cb = callback;
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             copyFileSync(src, dest, mode);
             cb(null);
@@ -2393,7 +2400,7 @@ export function link(existingPath, newPath, callback) {
     validatePath(existingPath, 'existingPath');
     validatePath(newPath, 'newPath');
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             linkSync(existingPath, newPath);
             callback(null);
@@ -2413,7 +2420,7 @@ export function symlink(target, path, typeOrCallback, callback) {
         cb = callback;
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             symlinkSync(target, path);
             cb(null);
@@ -2433,7 +2440,7 @@ export function readlink(path, optionsOrCallback, callback) {
     if (opts.encoding) validateEncoding(opts.encoding, 'encoding', true);
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = readlinkSync(path, opts);
             cb(null, result);
@@ -2446,7 +2453,7 @@ export function readlink(path, optionsOrCallback, callback) {
 export function chmod(path, mode, callback) {
     validatePath(path);
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             chmodSync(path, mode);
             callback(null);
@@ -2460,7 +2467,7 @@ export function fchmod(fd, mode, callback) {
     validateFd(fd);
     mode = validateMode(mode, 'mode', undefined);
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             fchmodSync(fd, mode);
             callback(null);
@@ -2472,7 +2479,7 @@ export function fchmod(fd, mode, callback) {
 
 export function lchmod(path, mode, callback) {
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             lchmodSync(path, mode);
             callback(null);
@@ -2487,7 +2494,7 @@ export function chown(path, uid, gid, callback) {
     validateUid(uid, 'uid');
     validateUid(gid, 'gid');
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             chownSync(path, uid, gid);
             callback(null);
@@ -2502,7 +2509,7 @@ export function fchown(fd, uid, gid, callback) {
     validateUid(uid, 'uid');
     validateUid(gid, 'gid');
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             fchownSync(fd, uid, gid);
             callback(null);
@@ -2517,7 +2524,7 @@ export function lchown(path, uid, gid, callback) {
     validateUid(uid, 'uid');
     validateUid(gid, 'gid');
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             lchownSync(path, uid, gid);
             callback(null);
@@ -2530,7 +2537,7 @@ export function lchown(path, uid, gid, callback) {
 export function utimes(path, atime, mtime, callback) {
     validatePath(path);
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             utimesSync(path, atime, mtime);
             callback(null);
@@ -2543,7 +2550,7 @@ export function utimes(path, atime, mtime, callback) {
 export function futimes(fd, atime, mtime, callback) {
     validateFd(fd);
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             futimesSync(fd, atime, mtime);
             callback(null);
@@ -2556,7 +2563,7 @@ export function futimes(fd, atime, mtime, callback) {
 export function lutimes(path, atime, mtime, callback) {
     validatePath(path);
     validateCallback(callback);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             lutimesSync(path, atime, mtime);
             callback(null);
@@ -2571,9 +2578,9 @@ export function unlink(path, callback) {
     validateCallback(callback);
     const error = native.unlink(pathToString(path));
     if (error) {
-        queueMicrotask(() => callback(createSystemError(error)));
+        queueRequest(() => callback(createSystemError(error)));
     } else {
-        queueMicrotask(() => callback(null));
+        queueRequest(() => callback(null));
     }
 }
 
@@ -2585,9 +2592,9 @@ export function rename(oldPath, newPath, callback) {
     const newPathString = pathToString(newPath);
     const error = native.rename(oldPathString, newPathString);
     if (error) {
-        queueMicrotask(() => callback(createSystemError(error)));
+        queueRequest(() => callback(createSystemError(error)));
     } else {
-        queueMicrotask(() => callback(null));
+        queueRequest(() => callback(null));
     }
 }
 
@@ -2608,7 +2615,7 @@ export function mkdir(path, optionsOrCallback, callback) {
     const pathString = pathToString(path);
     const firstCreatedPath = getFirstCreatedPath(pathString, recursive);
 
-    queueMicrotask(() => {
+    queueRequest(() => {
         const error = native.fs_mkdir(pathString, recursive, mode);
         if (error) {
             cb(createSystemError(error));
@@ -2626,7 +2633,7 @@ export function rmdir(path, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             rmdirSync(path, optionsOrCallback);
             cb(null);
@@ -2644,7 +2651,7 @@ export function rm(path, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             rmSync(path, optionsOrCallback);
             cb(null);
@@ -2664,7 +2671,7 @@ export function mkdtemp(prefix, optionsOrCallback, callback) {
     if (opts.encoding) validateEncoding(opts.encoding, 'encoding', true);
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = mkdtempSync(prefix, opts);
             cb(null, result);
@@ -2683,7 +2690,7 @@ export function opendir(path, optionsOrCallback, callback) {
     validateOpendirOptions(optionsOrCallback);
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             const result = opendirSync(path, optionsOrCallback);
             cb(null, result);
@@ -3645,7 +3652,7 @@ export function readv(fd, buffers, positionOrCallback, callback) {
         }
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             let totalRead = 0;
             for (const buf of buffers) {
@@ -3685,7 +3692,7 @@ export function writev(fd, buffers, positionOrCallback, callback) {
         }
     }
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             let totalWritten = 0;
             for (const buf of buffers) {
@@ -3781,7 +3788,7 @@ export function cp(src, dest, optionsOrCallback, callback) {
     }
     const cb = callback;
     validateCallback(cb);
-    queueMicrotask(() => {
+    queueRequest(() => {
         try {
             cpSync(src, dest, optionsOrCallback);
             cb(null);
@@ -19,6 +19,12 @@ pub const WIRE_JS: &str = r#"
             // where running it while async function generator states are on
             // the call stack can cause use-after-free of closure variables.
             // Observable cleanup (pre_gc hooks) still runs synchronously.
-            globalThis.setTimeout(__wasm_rquickjs_gc, 0);
+            globalThis.setTimeout(() => {
+                const start = Date.now();
+                __wasm_rquickjs_gc();
+                if (typeof globalThis.__wasm_rquickjs_post_gc === 'function') {
+                    globalThis.__wasm_rquickjs_post_gc(start, Date.now());
+                }
+            }, 0);
         };
     "#;
@@ -2,6 +2,7 @@ import * as httpNative from '__wasm_rquickjs_builtin/http_native'
 import {formDataToBlob} from '__wasm_rquickjs_builtin/http_form_data';
 import {DOMException} from '__wasm_rquickjs_builtin/abort_controller';
 import * as base64 from 'base64-js';
+import { _emitDestroy, _emitInit, _runInAsyncScope, executionAsyncId } from '__wasm_rquickjs_builtin/async_hooks';
 
 // Partially based on the implementation in wasmedge-quickjs
 // Partially based on https://github.com/JakeChampion/fetch/blob/main/fetch.js
@@ -16,143 +17,158 @@ import * as base64 from 'base64-js';
 // Matching Node's behavior is more important than enforcing the web spec's
 // "fetch is not a constructor" rule for our compatibility goals.
 export function fetch(resource, options = {}) {
-    return (async () => {
-        let method;
-        let rawHeaders = {};
-        let version = options.version || 'HTTP/1.1';
-        let mode;
-        let referer;
-        let referrerPolicy;
-        let credentials;
-        let redirect;
-        let body;
-        let url;
-        let signal;
-
-        if (typeof resource === 'object' && resource instanceof Request) {
-            method = resource.method.toUpperCase();
-            const headers = resource.headers;
-            if (!headers.has('Accept')) {
-                headers.set('Accept', '*/*');
-            }
-            for (const [name, value] of headers.entries()) {
-                rawHeaders[name] = value;
-            }
-
-            mode = options.mode || resource.mode;
-            referer = options.referrer || resource.referrer;
-            referrerPolicy = options.referrerPolicy || resource.referrerPolicy;
-            // let cache = options.cache || resource.cache; // cache not used in native yet
-            credentials = options.credentials || resource.credentials;
-            redirect = options.redirect || resource.redirect || 'follow';
-            signal = options.signal || resource.signal;
+    // The request is a FETCH async resource, triggering the promises created while sending it and
+    // destroyed once it settled
+    const asyncResource = new FetchRequest();
+    const triggerAsyncId = executionAsyncId();
+    const asyncId = _emitInit('FETCH', asyncResource, triggerAsyncId);
+    return _runInAsyncScope(asyncId, triggerAsyncId, asyncResource, async () => {
+        try {
+            return await sendFetch(resource, options);
+        } finally {
+            _emitDestroy(asyncId);
+        }
+    });
+}
 
-            if (resource._bodyUsed) {
-                throw new TypeError("Request body is already used");
-            }
-            resource._bodyUsed = true;
-            body = resource._body;
-            url = resource.url;
-        } else {
-            method = (options.method || 'GET').toUpperCase();
-            const headers = new Headers(options.headers || {});
-            if (!headers.has('Accept')) {
-                headers.set('Accept', '*/*');
-            }
-            for (const [name, value] of headers.entries()) {
-                rawHeaders[name] = value;
-            }
+class FetchRequest {}
+
+async function sendFetch(resource, options) {
+    let method;
+    let rawHeaders = {};
+    let version = options.version || 'HTTP/1.1';
+    let mode;
+    let referer;
+    let referrerPolicy;
+    let credentials;
+    let redirect;
+    let body;
+    let url;
+    let signal;
+
+    if (typeof resource === 'object' && resource instanceof Request) {
+        method = resource.method.toUpperCase();
+        const headers = resource.headers;
+        if (!headers.has('Accept')) {
+            headers.set('Accept', '*/*');
+        }
+        for (const [name, value] of headers.entries()) {
+            rawHeaders[name] = value;
+        }
 
-            mode = options.mode || 'cors';
-            referer = options.referrer || 'about:client';
-            referrerPolicy = options.referrerPolicy || 'strict-origin-when-cross-origin';
-            // let cache = options.cache || 'default';
-            credentials = options.credentials || 'same-origin';
-            redirect = options.redirect || 'follow';
-            signal = options.signal;
+        mode = options.mode || resource.mode;
+        referer = options.referrer || resource.referrer;
+        referrerPolicy = options.referrerPolicy || resource.referrerPolicy;
+        // let cache = options.cache || resource.cache; // cache not used in native yet
+        credentials = options.credentials || resource.credentials;
+        redirect = options.redirect || resource.redirect || 'follow';
+        signal = options.signal || resource.signal;
 
-            body = options.body;
-            url = String(resource);
+        if (resource._bodyUsed) {
+            throw new TypeError("Request body is already used");
         }
-
-        // Check if signal is already aborted
-        if (signal && signal.aborted) {
-            throw signal.reason || new DOMException('The operation was aborted.', 'AbortError');
+        resource._bodyUsed = true;
+        body = resource._body;
+        url = resource.url;
+    } else {
+        method = (options.method || 'GET').toUpperCase();
+        const headers = new Headers(options.headers || {});
+        if (!headers.has('Accept')) {
+            headers.set('Accept', '*/*');
         }
-
-        if (body instanceof FormData) {
-            const blob = formDataToBlob(body);
-            if (blob.type && blob.type !== '') {
-                rawHeaders['content-type'] = blob.type;
-            }
-            body = await blob.arrayBuffer();
-        } else if (body instanceof Blob) {
-            if (body.type && body.type !== '') {
-                rawHeaders['content-type'] = body.type;
-            }
-            body = await body.arrayBuffer();
+        for (const [name, value] of headers.entries()) {
+            rawHeaders[name] = value;
         }
 
-        // Create the fetch promise
-        let fetchPromise;
-        if (body instanceof ReadableStream) {
-            let bodyCreator;
-            if (body.locked) throw new TypeError("ReadableStream is locked");
-            let used = false;
-            bodyCreator = () => {
-                if (used) throw new TypeError("Disturbed stream");
-                used = true;
-                return body;
-            };
+        mode = options.mode || 'cors';
+        referer = options.referrer || 'about:client';
+        referrerPolicy = options.referrerPolicy || 'strict-origin-when-cross-origin';
+        // let cache = options.cache || 'default';
+        credentials = options.credentials || 'same-origin';
+        redirect = options.redirect || 'follow';
+        signal = options.signal;
 
-            fetchPromise = streamingRequest(
-                url, method, rawHeaders, version, mode, referer, referrerPolicy, credentials, redirect,
-                bodyCreator
-            );
-        } else {
-            // Simple request
-            const request = new httpNative.HttpRequest(
-                url,
-                method,
-                rawHeaders,
-                version,
-                mode,
-                referer,
-                referrerPolicy,
-                credentials,
-                redirect
-            );
+        body = options.body;
+        url = String(resource);
+    }
 
-            if (!body) {
-                // no body
-            } else if (body instanceof ArrayBuffer) {
-                request.arrayBufferBody(body);
-            } else if (body instanceof DataView) {
-                request.uint8ArrayBody(new Uint8Array(body.buffer, body.byteOffset, body.byteLength));
-            } else if (body instanceof Uint8Array) {
-                request.uint8ArrayBody(body);
-            } else if (body instanceof URLSearchParams) {
-                request.addHeader('Content-Type', 'application/x-www-form-urlencoded');
-                request.stringBody(body.toString());
-            } else if (typeof body === 'string' || body instanceof String) {
-                request.stringBody(body);
-            } else {
-                console.warn('Unsupported body type');
-            }
+    // Check if signal is already aborted
+    if (signal && signal.aborted) {
+        throw signal.reason || new DOMException('The operation was aborted.', 'AbortError');
+    }
 
-            fetchPromise = (async () => {
-                const nativeResponse = await request.simpleSend();
-                return new Response(nativeResponse, request.url, credentials);
-            })();
+    if (body instanceof FormData) {
+        const blob = formDataToBlob(body);
+        if (blob.type && blob.type !== '') {
+            rawHeaders['content-type'] = blob.type;
+        }
+        body = await blob.arrayBuffer();
+    } else if (body instanceof Blob) {
+        if (body.type && body.type !== '') {
+            rawHeaders['content-type'] = body.type;
         }
+        body = await body.arrayBuffer();
+    }
 
-        // If signal is provided, wrap the promise to support abort
-        if (signal) {
-            fetchPromise = abortableFetch(fetchPromise, signal);
+    // Create the fetch promise
+    let fetchPromise;
+    if (body instanceof ReadableStream) {
+        let bodyCreator;
+        if (body.locked) throw new TypeError("ReadableStream is locked");
+        let used = false;
+        bodyCreator = () => {
+            if (used) throw new TypeError("Disturbed stream");
+            used = true;
+            return body;
+        };
+
+        fetchPromise = streamingRequest(
+            url, method, rawHeaders, version, mode, referer, referrerPolicy, credentials, redirect,
+            bodyCreator
+        );
+    } else {
+        // Simple request
+        const request = new httpNative.HttpRequest(
+            url,
+            method,
+            rawHeaders,
+            version,
+            mode,
+            referer,
+            referrerPolicy,
+            credentials,
+            redirect
+        );
+
+        if (!body) {
+            // no body
+        } else if (body instanceof ArrayBuffer) {
+            request.arrayBufferBody(body);
+        } else if (body instanceof DataView) {
+            request.uint8ArrayBody(new Uint8Array(body.buffer, body.byteOffset, body.byteLength));
+        } else if (body instanceof Uint8Array) {
+            request.uint8ArrayBody(body);
+        } else if (body instanceof URLSearchParams) {
+            request.addHeader('Content-Type', 'application/x-www-form-urlencoded');
+            request.stringBody(body.toString());
+        } else if (typeof body === 'string' || body instanceof String) {
+            request.stringBody(body);
+        } else {
+            console.warn('Unsupported body type');
         }
 
-        return fetchPromise;
-    })();
+        fetchPromise = (async () => {
+            const nativeResponse = await request.simpleSend();
+            return new Response(nativeResponse, request.url, credentials);
+        })();
+    }
+
+    // If signal is provided, wrap the promise to support abort
+    if (signal) {
+        fetchPromise = abortableFetch(fetchPromise, signal);
+    }
+
+    return fetchPromise;
 }
 
 function abortableFetch(fetchPromise, signal) {
@@ -22,6 +22,8 @@ use std::collections::HashMap;
 use std::rc::{Rc, Weak};
 use wstd::runtime::AsyncPollable;
 
+use super::zlib::ContentDecoder;
+
 /// Request mode - defines the cross-origin behavior
 #[derive(Debug, Clone, Copy, PartialEq, Eq, rquickjs::class::Trace, rquickjs::JsLifetime)]
 pub enum RequestMode {
@@ -401,6 +403,7 @@ impl HttpRequest {
 
         // Apply credentials filtering based on credentials mode
         apply_credentials_filtering(request.headers_mut(), self.credentials, &self.url);
+        apply_default_accept_encoding(request.headers_mut());
 
         // Apply referrer policy and set Referer header if appropriate
         if let Some(referer_header_value) =
@@ -513,6 +516,7 @@ impl HttpRequest {
 
             // Apply credentials filtering based on credentials mode
             apply_credentials_filtering(request.headers_mut(), self.credentials, &self.url);
+            apply_default_accept_encoding(request.headers_mut());
 
             // Apply referrer policy and set Referer header if appropriate
             if let Some(referer_header_value) =
@@ -679,6 +683,8 @@ pub struct HttpResponse {
     status: golem_wasi_http::StatusCode,
     is_opaque: bool,
     redirected: bool,
+    /// The `Content-Encoding` of the native body, if it is decoded when read
+    content_encoding: Option<String>,
 }
 
 impl Default for HttpResponse {
@@ -697,6 +703,7 @@ impl HttpResponse {
             status: golem_wasi_http::StatusCode::OK,
             is_opaque: false,
             redirected: false,
+            content_encoding: None,
         }
     }
 
@@ -715,12 +722,24 @@ impl HttpResponse {
 
         let status = response.status();
 
+        let content_encoding = response
+            .headers()
+            .get_all(HeaderName::from_bytes(b"content-encoding").expect("valid header name"))
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let content_encoding = ContentDecoder::from_content_encoding(&content_encoding)
+            .is_some()
+            .then_some(content_encoding);
+
         HttpResponse {
             body_source: ResponseBodySource::Native(Box::new(response)),
             headers,
             status,
             is_opaque: false,
             redirected: false,
+            content_encoding,
         }
     }
 
@@ -773,42 +792,8 @@ impl HttpResponse {
             .to_string()
     }
 
-    #[allow(clippy::await_holding_refcell_ref)]
     pub async fn array_buffer<'js>(&mut self, ctx: Ctx<'js>) -> rquickjs::Result<ArrayBuffer<'js>> {
-        let source = std::mem::replace(&mut self.body_source, ResponseBodySource::Consumed);
-        let bytes = match source {
-            ResponseBodySource::Bytes(body_bytes) => body_bytes,
-            ResponseBodySource::Native(response) => response
-                .bytes()
-                .await
-                .map_err(|_| Exception::throw_message(&ctx, "failed to read response body"))?
-                .to_vec(),
-            ResponseBodySource::Shared(shared) => {
-                let mut shared = shared.borrow_mut();
-                if let Some(response) = shared.response.take() {
-                    // Native response was not read yet, read it now
-                    let bytes = response
-                        .bytes()
-                        .await
-                        .map_err(|_| {
-                            Exception::throw_message(&ctx, "failed to read response body")
-                        })?
-                        .to_vec();
-                    shared.buffer = bytes.clone();
-                    shared.finished = true;
-                    bytes
-                } else {
-                    // Response already read and buffered
-                    shared.buffer.clone()
-                }
-            }
-            ResponseBodySource::Consumed => {
-                return Err(Exception::throw_message(
-                    &ctx,
-                    "The response has already been consumed",
-                ));
-            }
-        };
+        let bytes = self.read_body(&ctx).await?;
 
         let ctx_clone = ctx.clone();
         ArrayBuffer::new(ctx, bytes).map_err(move |_| {
@@ -821,20 +806,18 @@ impl HttpResponse {
 
     pub fn stream<'js>(&mut self, ctx: Ctx<'js>) -> rquickjs::Result<ResponseBodyStream> {
         let source = std::mem::replace(&mut self.body_source, ResponseBodySource::Consumed);
-        match source {
-            ResponseBodySource::Bytes(body_bytes) => Ok(ResponseBodyStream {
-                stream: Some(BodySource::Bytes(std::io::Cursor::new(body_bytes))),
-            }),
+        let stream = match source {
+            ResponseBodySource::Bytes(body_bytes) => {
+                BodySource::Bytes(std::io::Cursor::new(body_bytes))
+            }
             ResponseBodySource::Native(mut response) => {
                 let (stream, body) = response.get_raw_input_stream();
 
-                Ok(ResponseBodyStream {
-                    stream: Some(BodySource::Native {
-                        stream,
-                        body,
-                        response,
-                    }),
-                })
+                BodySource::Native {
+                    stream,
+                    body,
+                    response,
+                }
             }
             ResponseBodySource::Shared(rc_shared) => {
                 let mut shared = rc_shared.borrow_mut();
@@ -849,46 +832,49 @@ impl HttpResponse {
                     };
 
                     // Read the body into the buffer as it is consumed
-                    Ok(ResponseBodyStream {
-                        stream: Some(BodySource::SharedNative {
-                            shared_stream: Rc::new(RefCell::new(shared_stream)),
-                            position: 0,
-                        }),
-                    })
+                    BodySource::SharedNative {
+                        shared_stream: Rc::new(RefCell::new(shared_stream)),
+                        position: 0,
+                    }
                 } else if let Some(stream) = &shared.stream {
                     if shared.finished {
                         // Response was streaming but now finished
-                        Ok(ResponseBodyStream {
-                            stream: Some(BodySource::Bytes(std::io::Cursor::new(
-                                shared.buffer.clone(),
-                            ))),
-                        })
+                        BodySource::Bytes(std::io::Cursor::new(shared.buffer.clone()))
                     } else {
                         // Response is still streaming
-                        Ok(ResponseBodyStream {
-                            stream: Some(BodySource::SharedNative {
-                                shared_stream: stream.clone(),
-                                position: 0,
-                            }),
-                        })
+                        BodySource::SharedNative {
+                            shared_stream: stream.clone(),
+                            position: 0,
+                        }
                     }
                 } else {
-                    Ok(ResponseBodyStream {
-                        stream: Some(BodySource::Bytes(std::io::Cursor::new(
-                            shared.buffer.clone(),
-                        ))),
-                    })
+                    BodySource::Bytes(std::io::Cursor::new(shared.buffer.clone()))
                 }
             }
-            ResponseBodySource::Consumed => Err(Exception::throw_message(
-                &ctx,
-                "The response has already been consumed",
-            )),
-        }
+            ResponseBodySource::Consumed => {
+                return Err(Exception::throw_message(
+                    &ctx,
+                    "The response has already been consumed",
+                ));
+            }
+        };
+
+        Ok(ResponseBodyStream {
+            stream: Some(stream),
+            decoder: self
+                .content_encoding
+                .as_deref()
+                .and_then(ContentDecoder::from_content_encoding),
+        })
     }
 
     #[allow(clippy::await_holding_refcell_ref)]
     pub async fn text<'js>(&mut self, ctx: Ctx<'js>) -> rquickjs::Result<String> {
+        if self.content_encoding.is_some() {
+            let bytes = self.read_body(&ctx).await?;
+            return Ok(String::from_utf8_lossy(&bytes).to_string());
+        }
+
         let source = std::mem::replace(&mut self.body_source, ResponseBodySource::Consumed);
         match source {
             ResponseBodySource::Bytes(body_bytes) => {
@@ -927,6 +913,7 @@ impl HttpResponse {
             status: golem_wasi_http::StatusCode::INTERNAL_SERVER_ERROR,
             is_opaque: false,
             redirected: false,
+            content_encoding: None,
         }
     }
 
@@ -946,6 +933,7 @@ impl HttpResponse {
             status: status_code,
             is_opaque: false,
             redirected: false,
+            content_encoding: None,
         }
     }
 
@@ -968,6 +956,7 @@ impl HttpResponse {
             status: status_code,
             is_opaque: false,
             redirected: false,
+            content_encoding: None,
         }
     }
 
@@ -1005,6 +994,57 @@ impl HttpResponse {
             status: self.status,
             is_opaque: self.is_opaque,
             redirected: self.redirected,
+            content_encoding: self.content_encoding.clone(),
+        }
+    }
+}
+
+impl HttpResponse {
+    /// Reads the whole body, decoding it according to its `Content-Encoding` if needed
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn read_body<'js>(&mut self, ctx: &Ctx<'js>) -> rquickjs::Result<Vec<u8>> {
+        let source = std::mem::replace(&mut self.body_source, ResponseBodySource::Consumed);
+        let bytes = match source {
+            ResponseBodySource::Bytes(body_bytes) => body_bytes,
+            ResponseBodySource::Native(response) => response
+                .bytes()
+                .await
+                .map_err(|_| Exception::throw_message(ctx, "failed to read response body"))?
+                .to_vec(),
+            ResponseBodySource::Shared(shared) => {
+                let mut shared = shared.borrow_mut();
+                if let Some(response) = shared.response.take() {
+                    // Native response was not read yet, read it now
+                    let bytes = response
+                        .bytes()
+                        .await
+                        .map_err(|_| Exception::throw_message(ctx, "failed to read response body"))?
+                        .to_vec();
+                    shared.buffer = bytes.clone();
+                    shared.finished = true;
+                    bytes
+                } else {
+                    // Response already read and buffered
+                    shared.buffer.clone()
+                }
+            }
+            ResponseBodySource::Consumed => {
+                return Err(Exception::throw_message(
+                    ctx,
+                    "The response has already been consumed",
+                ));
+            }
+        };
+
+        match self
+            .content_encoding
+            .as_deref()
+            .and_then(ContentDecoder::from_content_encoding)
+        {
+            Some(decoder) => decoder
+                .decode_all(&bytes)
+                .map_err(|_| Exception::throw_message(ctx, "failed to decode response body")),
+            None => Ok(bytes),
         }
     }
 }
@@ -1057,6 +1097,8 @@ pub enum BodySource {
 pub struct ResponseBodyStream {
     #[qjs(skip_trace)]
     stream: Option<BodySource>,
+    #[qjs(skip_trace)]
+    decoder: Option<ContentDecoder>,
 }
 
 impl Default for ResponseBodyStream {
@@ -1069,7 +1111,10 @@ impl Default for ResponseBodyStream {
 impl ResponseBodyStream {
     #[qjs(constructor)]
     pub fn new() -> Self {
-        ResponseBodyStream { stream: None }
+        ResponseBodyStream {
+            stream: None,
+            decoder: None,
+        }
     }
 
     #[qjs(get, rename = "type")]
@@ -1077,60 +1122,84 @@ impl ResponseBodyStream {
         "bytes".to_string()
     }
 
-    #[allow(clippy::await_holding_refcell_ref)]
     pub async fn pull<'js>(
         &mut self,
         ctx: Ctx<'js>,
     ) -> List<(Option<TypedArray<'js, u8>>, Option<String>)> {
+        let chunk = loop {
+            match self.read_chunk().await {
+                Ok(Some(chunk)) => match self.decoder.as_mut() {
+                    Some(decoder) => match decoder.push(&chunk) {
+                        // Not enough input to produce decoded output yet
+                        Ok(decoded) if decoded.is_empty() => continue,
+                        Ok(decoded) => break Ok(Some(decoded)),
+                        Err(err) => break Err(format!("Failed to decode response body: {err}")),
+                    },
+                    None => break Ok(Some(chunk)),
+                },
+                Ok(None) => match self.decoder.take() {
+                    Some(mut decoder) => match decoder.finish() {
+                        Ok(rest) => {
+                            // Serve the remaining decoded output before signaling the end
+                            self.stream = Some(BodySource::Bytes(std::io::Cursor::new(rest)));
+                        }
+                        Err(err) => break Err(format!("Failed to decode response body: {err}")),
+                    },
+                    None => break Ok(None),
+                },
+                Err(err) => break Err(err),
+            }
+        };
+
+        match chunk {
+            Ok(Some(chunk)) => match TypedArray::new_copy(ctx, chunk) {
+                Ok(js_array) => List((Some(js_array), None)),
+                Err(_) => List((
+                    None,
+                    Some("Failed to create TypedArray from response body chunk".to_string()),
+                )),
+            },
+            Ok(None) => List((None, None)),
+            Err(err) => List((None, Some(err))),
+        }
+    }
+}
+
+impl ResponseBodyStream {
+    /// Reads the next raw chunk of the body, returning `None` at the end of the body
+    #[allow(clippy::await_holding_refcell_ref)]
+    async fn read_chunk(&mut self) -> Result<Option<Vec<u8>>, String> {
+        const CHUNK_SIZE: u64 = 4096;
+
         let (result, stream) = match self.stream.take() {
             Some(BodySource::Native {
                 stream,
                 body,
                 response,
             }) => {
-                const CHUNK_SIZE: u64 = 4096;
                 let pollable = stream.subscribe();
                 AsyncPollable::new(pollable).wait_for().await;
 
                 match stream.read(CHUNK_SIZE) {
-                    Ok(chunk) => match TypedArray::new_copy(ctx.clone(), chunk) {
-                        Ok(js_array) => (
-                            List((Some(js_array), None)),
-                            Some(BodySource::Native {
-                                stream,
-                                body,
-                                response,
-                            }),
-                        ),
-                        Err(_) => (
-                            List((
-                                None,
-                                Some(
-                                    "Failed to create TypedArray from response body chunk"
-                                        .to_string(),
-                                ),
-                            )),
-                            Some(BodySource::Native {
-                                stream,
-                                body,
-                                response,
-                            }),
-                        ),
-                    },
+                    Ok(chunk) => (
+                        Ok(Some(chunk)),
+                        Some(BodySource::Native {
+                            stream,
+                            body,
+                            response,
+                        }),
+                    ),
                     Err(StreamError::Closed) => {
                         // No more data to read, close the stream
                         drop(stream);
                         drop(body);
                         drop(response);
-                        (List((None, None)), None)
+                        (Ok(None), None)
                     }
                     Err(StreamError::LastOperationFailed(err)) => (
-                        List((
-                            None,
-                            Some(format!(
-                                "Failed to read response body: {}",
-                                err.to_debug_string()
-                            )),
+                        Err(format!(
+                            "Failed to read response body: {}",
+                            err.to_debug_string()
                         )),
                         Some(BodySource::Native {
                             stream,
@@ -1153,104 +1222,64 @@ impl ResponseBodyStream {
                 let buffer_len = shared.buffer.len();
 
                 if position < buffer_len {
-                    let chunk = &shared.buffer[position..];
-                    let chunk_len = chunk.len();
-                    let chunk_array = TypedArray::new_copy(ctx.clone(), chunk).unwrap();
+                    let chunk = shared.buffer[position..].to_vec();
                     (
-                        List((Some(chunk_array), None)),
+                        Ok(Some(chunk)),
                         Some(BodySource::SharedNative {
                             shared_stream: rc_shared_stream.clone(),
-                            position: position + chunk_len,
+                            position: buffer_len,
                         }),
                     )
+                } else if shared.finished {
+                    (Ok(None), None)
                 } else {
-                    if shared.finished {
-                        (List((None, None)), None)
-                    } else {
-                        const CHUNK_SIZE: u64 = 4096;
-                        let pollable = shared_stream.stream.subscribe();
-                        AsyncPollable::new(pollable).wait_for().await;
-
-                        match shared_stream.stream.read(CHUNK_SIZE) {
-                            Ok(chunk) => {
-                                let chunk_len = chunk.len();
-                                shared.buffer.extend_from_slice(&chunk);
-
-                                match TypedArray::new_copy(ctx.clone(), chunk) {
-                                    Ok(js_array) => {
-                                        (List((Some(js_array), None)), Some(BodySource::SharedNative {
-                            shared_stream: rc_shared_stream.clone(),
-                            position: position + chunk_len,
-                        }))
-                                    },
-                                    Err(_) => (List((
-                                        None,
-                                        Some(
-                                            "Failed to create TypedArray from response body chunk"
-                                                .to_string(),
-                                        ),
-                                    )), Some(BodySource::SharedNative {
-                            shared_stream: rc_shared_stream.clone(),
-                            position: position + chunk_len,
-                        })),
-                                }
-                            }
-                            Err(StreamError::Closed) => {
-                                // No more data to read, close the stream
-                                shared.finished = true;
-                                (List((None, None)), None)
-                            }
-                            Err(StreamError::LastOperationFailed(err)) => (
-                                List((
-                                    None,
-                                    Some(format!(
-                                        "Failed to read response body: {}",
-                                        err.to_debug_string()
-                                    )),
-                                )),
+                    let pollable = shared_stream.stream.subscribe();
+                    AsyncPollable::new(pollable).wait_for().await;
+
+                    match shared_stream.stream.read(CHUNK_SIZE) {
+                        Ok(chunk) => {
+                            shared.buffer.extend_from_slice(&chunk);
+                            let position = position + chunk.len();
+                            (
+                                Ok(Some(chunk)),
                                 Some(BodySource::SharedNative {
                                     shared_stream: rc_shared_stream.clone(),
                                     position,
                                 }),
-                            ),
+                            )
+                        }
+                        Err(StreamError::Closed) => {
+                            // No more data to read, close the stream
+                            shared.finished = true;
+                            (Ok(None), None)
                         }
+                        Err(StreamError::LastOperationFailed(err)) => (
+                            Err(format!(
+                                "Failed to read response body: {}",
+                                err.to_debug_string()
+                            )),
+                            Some(BodySource::SharedNative {
+                                shared_stream: rc_shared_stream.clone(),
+                                position,
+                            }),
+                        ),
                     }
                 }
             }
             Some(BodySource::Bytes(mut cursor)) => {
-                let mut buf = [0u8; 4096];
+                let mut buf = [0u8; CHUNK_SIZE as usize];
                 match std::io::Read::read(&mut cursor, &mut buf) {
-                    Ok(0) => {
-                        // EOF
-                        (List((None, None)), None)
-                    }
-                    Ok(n) => match TypedArray::new_copy(ctx.clone(), &buf[..n]) {
-                        Ok(js_array) => (
-                            List((Some(js_array), None)),
-                            Some(BodySource::Bytes(cursor)),
-                        ),
-                        Err(_) => (
-                            List((
-                                None,
-                                Some(
-                                    "Failed to create TypedArray from response body chunk"
-                                        .to_string(),
-                                ),
-                            )),
-                            Some(BodySource::Bytes(cursor)),
-                        ),
-                    },
+                    // EOF
+                    Ok(0) => (Ok(None), None),
+                    Ok(n) => (Ok(Some(buf[..n].to_vec())), Some(BodySource::Bytes(cursor))),
                     Err(err) => (
-                        List((None, Some(format!("Failed to read response body: {}", err)))),
+                        Err(format!("Failed to read response body: {}", err)),
                         Some(BodySource::Bytes(cursor)),
                     ),
                 }
             }
             None => (
-                List((
-                    None,
-                    Some("Response body stream has already been consumed".to_string()),
-                )),
+                Err("Response body stream has already been consumed".to_string()),
                 None,
             ),
         };
@@ -1459,6 +1488,16 @@ fn apply_credentials_filtering(
     }
 }
 
+/// Advertises the supported content codings unless the request sets `Accept-Encoding` itself
+fn apply_default_accept_encoding(headers: &mut golem_wasi_http::header::HeaderMap) {
+    if let Some(accept_encoding) = super::zlib::ACCEPT_ENCODING {
+        let name = HeaderName::from_bytes(b"accept-encoding").expect("valid header name");
+        if !headers.contains_key(&name) {
+            headers.insert(name, HeaderValue::from_static(accept_encoding));
+        }
+    }
+}
+
 pub const HTTP_JS: &str = include_str!("http.js");
 pub const FETCH_BLOB_JS: &str = include_str!("fetch-blob-4.0.0.js");
 pub const FORMDATA_JS: &str = include_str!("formdata-polyfill-4.0.10.js");
@@ -1,31 +1,126 @@
-// node:inspector stub implementation
-// Most functions are no-ops since there is no debugger in WASM environment.
-// Session.post throws because it cannot communicate with a non-existent inspector.
+// node:inspector implementation
+// There is no debugger in WASM environment, so most functions are no-ops. Sessions only
+// implement the Profiler domain's CPU profiling commands, backed by the runtime's sampling
+// profiler; other commands fail like unknown commands do in V8.
 
 import EventEmitter from 'node:events';
+import { DEFAULT_SAMPLING_INTERVAL_US, Recording } from '__wasm_rquickjs_builtin/cpu_profiler';
+
+function nodeError(code, message) {
+    const err = new Error(message);
+    err.code = code;
+    return err;
+}
+
+// Error of a command the inspector rejected, with V8's JSON-RPC error code
+function commandError(code, message) {
+    return nodeError('ERR_INSPECTOR_COMMAND', `Inspector error ${code}: ${message}`);
+}
+
+const SERVER_ERROR = -32000;
+const METHOD_NOT_FOUND = -32601;
 
 export class Session extends EventEmitter {
+    #connected = false;
+    #profilerEnabled = false;
+    #samplingInterval = DEFAULT_SAMPLING_INTERVAL_US;
+    #recording = undefined;
+
     constructor() {
         super();
     }
 
-    connect() {}
+    connect() {
+        if (this.#connected) {
+            throw nodeError('ERR_INSPECTOR_ALREADY_CONNECTED', 'The inspector session is already connected');
+        }
+        this.#connected = true;
+    }
 
-    connectToMainThread() {}
+    connectToMainThread() {
+        this.connect();
+    }
 
-    disconnect() {}
+    disconnect() {
+        if (!this.#connected) {
+            return;
+        }
+        this.#disableProfiler();
+        this.#connected = false;
+    }
 
     post(method, params, callback) {
+        if (typeof method !== 'string') {
+            const err = new TypeError(`The "method" argument must be of type string. Received ${typeof method}`);
+            err.code = 'ERR_INVALID_ARG_TYPE';
+            throw err;
+        }
         if (typeof params === 'function') {
             callback = params;
             params = undefined;
         }
-        const err = new Error('inspector is not available');
+        if (!this.#connected) {
+            throw nodeError('ERR_INSPECTOR_NOT_CONNECTED', 'Session is not connected');
+        }
+
+        let error = null;
+        let result;
+        try {
+            result = this.#dispatch(method, params || {});
+        } catch (err) {
+            error = err;
+        }
         if (typeof callback === 'function') {
-            callback(err);
+            callback(error, result);
         } else {
-            return Promise.reject(err);
+            return error ? Promise.reject(error) : Promise.resolve(result);
+        }
+    }
+
+    #dispatch(method, params) {
+        switch (method) {
+            case 'Profiler.enable':
+                this.#profilerEnabled = true;
+                return {};
+            case 'Profiler.disable':
+                this.#disableProfiler();
+                return {};
+            case 'Profiler.setSamplingInterval':
+                if (this.#recording) {
+                    throw commandError(SERVER_ERROR, 'Cannot change sampling interval when profiling.');
+                }
+                if (typeof params.interval !== 'number' || !(params.interval > 0)) {
+                    throw commandError(SERVER_ERROR, 'Invalid parameters: interval must be a positive number');
+                }
+                this.#samplingInterval = params.interval;
+                return {};
+            case 'Profiler.start':
+                if (!this.#profilerEnabled) {
+                    throw commandError(SERVER_ERROR, 'Profiler is not enabled');
+                }
+                if (!this.#recording) {
+                    this.#recording = new Recording(this.#samplingInterval);
+                }
+                return {};
+            case 'Profiler.stop': {
+                if (!this.#recording) {
+                    throw commandError(SERVER_ERROR, 'No recording profiles found');
+                }
+                const profile = this.#recording.stop();
+                this.#recording = undefined;
+                return { profile };
+            }
+            default:
+                throw commandError(METHOD_NOT_FOUND, `'${method}' wasn't found`);
+        }
+    }
+
+    #disableProfiler() {
+        if (this.#recording) {
+            this.#recording.stop();
+            this.#recording = undefined;
         }
+        this.#profilerEnabled = false;
     }
 }
 
@@ -1,4 +1,4 @@
-// inspector module - JavaScript-only stub (no native functions needed)
+// inspector module - JavaScript-only, its profiler is provided by the cpu_profiler builtin
 pub const INSPECTOR_JS: &str = include_str!("inspector.js");
 
 // Re-export for aliases
@@ -374,6 +374,15 @@ export class ERR_HTTP_INVALID_HEADER_VALUE extends TypeError {
     }
 }
 
+export class ERR_HTTP_REQUEST_TIMEOUT extends Error {
+    constructor() {
+        super(
+            `Request timeout`,
+        );
+        addCodeToMessage(this, "ERR_HTTP_REQUEST_TIMEOUT");
+    }
+}
+
 export class ERR_HTTP_TRAILER_INVALID extends Error {
     constructor() {
         super(
@@ -1342,6 +1351,22 @@ export class ERR_IPC_CHANNEL_CLOSED extends Error {
     }
 }
 
+export class ERR_INVALID_STATE extends Error {
+    constructor(message) {
+        super(`Invalid state: ${message}`);
+        addCodeToMessage(this, "ERR_INVALID_STATE");
+    }
+}
+
+export class ERR_TEST_FAILURE extends Error {
+    constructor(error, failureType) {
+        super(error?.message ?? String(error));
+        this.failureType = failureType;
+        this.cause = error;
+        addCodeToMessage(this, "ERR_TEST_FAILURE");
+    }
+}
+
 export const codes = Object.freeze({
     ERR_AMBIGUOUS_ARGUMENT,
     ERR_ASSERT_SNAPSHOT_NOT_SUPPORTED,
@@ -1378,6 +1403,7 @@ export const codes = Object.freeze({
     ERR_HTTP_BODY_NOT_ALLOWED,
     ERR_HTTP_CONTENT_LENGTH_MISMATCH,
     ERR_HTTP_HEADERS_SENT,
+    ERR_HTTP_REQUEST_TIMEOUT,
     ERR_HTTP_SOCKET_ASSIGNED,
     ERR_HTTP_INVALID_HEADER_VALUE,
     ERR_HTTP_TRAILER_INVALID,
@@ -1393,6 +1419,7 @@ export const codes = Object.freeze({
     ERR_INVALID_HTTP_TOKEN,
     ERR_UNESCAPED_CHARACTERS,
     ERR_INVALID_RETURN_VALUE,
+    ERR_INVALID_STATE,
     ERR_INVALID_THIS,
     ERR_METHOD_NOT_IMPLEMENTED,
     ERR_MISSING_ARGS,
@@ -1412,6 +1439,7 @@ export const codes = Object.freeze({
     ERR_STREAM_UNSHIFT_AFTER_END_EVENT,
     ERR_STREAM_WRITE_AFTER_END,
     ERR_SYSTEM_ERROR,
+    ERR_TEST_FAILURE,
     ERR_UNAVAILABLE_DURING_EXIT,
     ERR_UNKNOWN_ENCODING,
 });
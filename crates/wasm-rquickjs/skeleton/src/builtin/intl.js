@@ -1,4 +1,10 @@
-import { intl_dtf_resolve_fields, intl_validate_timezone, intl_collator_compare, intl_segment } from "__wasm_rquickjs_builtin/intl_native";
+import {
+    intl_dtf_resolve_fields, intl_validate_timezone, intl_collator_compare, intl_segment,
+    intl_icu_languages, intl_icu_canonicalize, intl_icu_number_symbols, intl_icu_currency_pattern,
+    intl_icu_plural_select, intl_icu_plural_select_range, intl_icu_plural_categories,
+    intl_icu_list_format, intl_icu_collator_compare, intl_icu_segment, intl_icu_date_pattern,
+    intl_icu_date_symbols, intl_icu_relative_time,
+} from "__wasm_rquickjs_builtin/intl_native";
 
 const MONTH_NAMES_LONG = [
     "January", "February", "March", "April", "May", "June",
@@ -43,6 +49,67 @@ function toTimestamp(date) {
     throw new TypeError("Invalid date");
 }
 
+// ─── Locale data ───────────────────────────────────────────────────────────────
+//
+// With the `intl-full` feature the component carries ICU4X data for the languages selected at
+// generation time, and every formatter below resolves its locale against them. Without it the
+// language list is empty and everything formats as en-US using the built-in tables above.
+
+const DEFAULT_LOCALE = "en-US";
+const ICU_LANGUAGES = intl_icu_languages();
+const ICU_ENABLED = ICU_LANGUAGES.length > 0;
+const ICU_CACHE = new Map();
+
+function icuCached(key, load) {
+    let value = ICU_CACHE.get(key);
+    if (value === undefined) {
+        value = load();
+        ICU_CACHE.set(key, value);
+    }
+    return value;
+}
+
+function canonicalizeLocaleList(locales) {
+    if (locales === undefined) return [];
+    const list = typeof locales === "string" || !Array.isArray(locales) ? [locales] : locales;
+    const result = [];
+    for (const tag of list) {
+        if (typeof tag !== "string" && (typeof tag !== "object" || tag === null)) {
+            throw new TypeError("Language ID should be string or object.");
+        }
+        const canonical = intl_icu_canonicalize(String(tag));
+        if (canonical === undefined) {
+            throw new RangeError("Incorrect locale information provided");
+        }
+        if (!result.includes(canonical)) result.push(canonical);
+    }
+    return result;
+}
+
+function isLocaleAvailable(locale) {
+    return ICU_LANGUAGES.includes(locale.split("-")[0]);
+}
+
+// Picks the first requested locale with baked-in data, dropping Unicode extensions.
+function resolveLocale(locales) {
+    if (!ICU_ENABLED) return DEFAULT_LOCALE;
+    for (const locale of canonicalizeLocaleList(locales)) {
+        const base = locale.split("-u-")[0];
+        if (isLocaleAvailable(base)) return base;
+    }
+    return DEFAULT_LOCALE;
+}
+
+function supportedLocalesOf(locales) {
+    if (!ICU_ENABLED) return [DEFAULT_LOCALE];
+    return canonicalizeLocaleList(locales).filter(isLocaleAvailable);
+}
+
+function localizeDigits(str, digits) {
+    if (digits === "0123456789") return str;
+    return str.replace(/[0-9]/g, d => digits[d.charCodeAt(0) - 48]);
+}
+
 // ─── DateTimeFormat ────────────────────────────────────────────────────────────
 
 function resolveDateStyleOptions(dateStyle) {
@@ -135,7 +202,83 @@ function convertHour(hour24, hour12Flag, hourCycle) {
     return { hour: h, dayPeriod: period };
 }
 
+// Builds a CLDR skeleton from the requested fields, in the canonical field order.
+function dateSkeleton(fo, hourCycle) {
+    let skeleton = "";
+    if (fo.era) skeleton += { long: "GGGG", narrow: "GGGGG" }[fo.era] || "G";
+    if (fo.year) skeleton += fo.year === "2-digit" ? "yy" : "y";
+    if (fo.month) {
+        skeleton += { "2-digit": "MM", short: "MMM", long: "MMMM", narrow: "MMMMM" }[fo.month] || "M";
+    }
+    if (fo.day) skeleton += fo.day === "2-digit" ? "dd" : "d";
+    if (fo.weekday) skeleton += { long: "EEEE", narrow: "EEEEE" }[fo.weekday] || "EEE";
+    if (fo.hour) {
+        const ch = hourCycle === "h11" || hourCycle === "h12" ? "h" : "H";
+        skeleton += fo.hour === "2-digit" ? ch + ch : ch;
+    }
+    if (fo.minute) skeleton += fo.minute === "2-digit" ? "mm" : "m";
+    if (fo.second) skeleton += fo.second === "2-digit" ? "ss" : "s";
+    return skeleton;
+}
+
+function numericField(value, minDigits) {
+    return String(value).padStart(minDigits, "0");
+}
+
+// Formats one pattern field (e.g. "MMMM") using the locale's date symbols.
+function formatDateField(field, r, fo, symbols, hourCycle) {
+    const len = field.length;
+    const width = len === 4 ? "long" : len === 5 ? "narrow" : "short";
+    switch (field[0]) {
+        case "G":
+            return { type: "era", value: symbols.eras[width][r.year > 0 ? 1 : 0] };
+        case "y":
+            return {
+                type: "year",
+                value: len === 2 || fo.year === "2-digit"
+                    ? pad2(r.year % 100)
+                    : numericField(r.year, len),
+            };
+        case "M":
+        case "L":
+            if (len <= 2) {
+                return { type: "month", value: numericField(r.month, fo.month === "2-digit" ? 2 : len) };
+            }
+            return {
+                type: "month",
+                value: (field[0] === "L" ? symbols.standaloneMonths : symbols.months)[width][r.month - 1],
+            };
+        case "d":
+            return { type: "day", value: numericField(r.day, fo.day === "2-digit" ? 2 : len) };
+        case "E":
+        case "c":
+        case "e":
+            // chrono weekday: 1=Mon..7=Sun; symbols: 0=Sun..6=Sat
+            return { type: "weekday", value: symbols.weekdays[width][r.weekday % 7] };
+        case "a":
+        case "b":
+        case "B":
+            return { type: "dayPeriod", value: symbols.dayPeriods[width][r.hour < 12 ? 0 : 1] };
+        case "h":
+        case "H":
+        case "K":
+        case "k": {
+            const { hour } = convertHour(r.hour, undefined, hourCycle);
+            return { type: "hour", value: numericField(hour, fo.hour === "2-digit" ? 2 : len) };
+        }
+        case "m":
+            return { type: "minute", value: numericField(r.minute, Math.max(len, fo.minute === "2-digit" ? 2 : 1)) };
+        case "s":
+            return { type: "second", value: numericField(r.second, Math.max(len, fo.second === "2-digit" ? 2 : 1)) };
+        default:
+            return { type: "literal", value: field };
+    }
+}
+
 class DateTimeFormatImpl {
+    #locale;
+    #symbols;
+    #pattern;
     #timeZone;
     #hour12;
     #hourCycle;
@@ -172,10 +315,30 @@ class DateTimeFormatImpl {
             }
         }
 
-        // Determine hour12 default for en-US when hour is present
+        this.#locale = resolveLocale(locales);
+        if (ICU_ENABLED) {
+            const locale = this.#locale;
+            this.#symbols = icuCached("date:" + locale, () => JSON.parse(intl_icu_date_symbols(locale)));
+        }
+
+        // Determine the hour12 default from the locale (en-US without locale data) when hour is present
         if (this.#fieldOpts.hour !== undefined && this.#hour12 === undefined && !this.#hourCycle) {
-            this.#hour12 = true;
+            this.#hour12 = this.#symbols ? this.#symbols.hourCycle === "h12" : true;
         }
+
+        if (ICU_ENABLED) {
+            const locale = this.#locale;
+            const skeleton = dateSkeleton(this.#fieldOpts, this.#resolvedHourCycle());
+            this.#pattern = icuCached(
+                "pattern:" + locale + ":" + skeleton,
+                () => JSON.parse(intl_icu_date_pattern(locale, skeleton))
+            );
+        }
+    }
+
+    #resolvedHourCycle() {
+        if (this.#hourCycle) return this.#hourCycle;
+        return this.#hour12 ? "h12" : "h23";
     }
 
     #resolve(date) {
@@ -188,8 +351,36 @@ class DateTimeFormatImpl {
         return { year, month, day, hour, minute, second, weekday, utcOffsetMinutes };
     }
 
+    #formatToPartsWithPattern(r) {
+        const fo = this.#fieldOpts;
+        const hourCycle = this.#resolvedHourCycle();
+        const locale = this.#locale;
+        const digits = icuCached("number:" + locale, () => JSON.parse(intl_icu_number_symbols(locale))).digits;
+        const parts = [];
+        for (const [kind, text] of this.#pattern) {
+            if (kind === "l") {
+                parts.push({ type: "literal", value: text });
+                continue;
+            }
+            const part = formatDateField(text, r, fo, this.#symbols, hourCycle);
+            if (part.type !== "literal") part.value = localizeDigits(part.value, digits);
+            parts.push(part);
+        }
+        if (fo.timeZoneName) {
+            parts.push({ type: "literal", value: " " });
+            const tzDisplay = fo.timeZoneName === "long"
+                ? this.#timeZone.replace(/_/g, " ")
+                : this.#timeZone;
+            parts.push({ type: "timeZoneName", value: tzDisplay });
+        }
+        return parts;
+    }
+
     formatToParts(date) {
         const r = this.#resolve(date);
+        if (this.#pattern) {
+            return this.#formatToPartsWithPattern(r);
+        }
         const fo = this.#fieldOpts;
         const parts = [];
         let needDateTimeSep = false;
@@ -306,7 +497,7 @@ class DateTimeFormatImpl {
 
     resolvedOptions() {
         const ro = {
-            locale: "en-US",
+            locale: this.#locale,
             calendar: "gregory",
             numberingSystem: "latn",
             timeZone: this.#timeZone,
@@ -333,8 +524,8 @@ class DateTimeFormatImpl {
         return ro;
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -350,18 +541,35 @@ Object.defineProperty(DateTimeFormatImpl.prototype, Symbol.toStringTag, {
 
 // ─── NumberFormat ───────────────────────────────────────────────────────────────
 
-function groupIntegerPart(intStr) {
+function groupIntegerPart(intStr, primary = 3, secondary = 3, minGrouping = 1) {
+    if (intStr.length < primary + minGrouping) return [intStr];
     const parts = [];
     let i = intStr.length;
+    let size = primary;
     while (i > 0) {
-        const start = Math.max(0, i - 3);
+        const start = Math.max(0, i - size);
         parts.unshift(intStr.slice(start, i));
         i = start;
+        size = secondary || primary;
+    }
+    return parts;
+}
+
+// Splits a localized affix such as "\u00a0%" into literal and percentSign parts.
+function percentAffixParts(affix, percentSign) {
+    const parts = [];
+    for (const [i, text] of affix.split("%").entries()) {
+        if (i > 0) parts.push({ type: "percentSign", value: percentSign });
+        if (text) parts.push({ type: "literal", value: text });
     }
     return parts;
 }
 
 function formatDecimalNumber(absVal, minInt, minFrac, maxFrac, useGrouping) {
+    return formatDecimalDigits(absVal, minInt, minFrac, maxFrac, useGrouping ? groupIntegerPart : null);
+}
+
+function formatDecimalDigits(absVal, minInt, minFrac, maxFrac, group) {
     let fixed = absVal.toFixed(maxFrac);
     let [intPart, fracPart] = fixed.split(".");
 
@@ -377,12 +585,15 @@ function formatDecimalNumber(absVal, minInt, minFrac, maxFrac, useGrouping) {
         }
     }
 
-    const intGroups = useGrouping ? groupIntegerPart(intPart) : [intPart];
+    const intGroups = group ? group(intPart) : [intPart];
 
     return { intGroups, fracPart: fracPart || "" };
 }
 
 class NumberFormatImpl {
+    #locale;
+    #symbols;
+    #currencyPattern;
     #style;
     #currency;
     #currencyDisplay;
@@ -427,9 +638,90 @@ class NumberFormatImpl {
         }
 
         this.#useGrouping = opts.useGrouping !== undefined ? opts.useGrouping : true;
+
+        this.#locale = resolveLocale(locales);
+        if (ICU_ENABLED) {
+            const locale = this.#locale;
+            this.#symbols = icuCached("number:" + locale, () => JSON.parse(intl_icu_number_symbols(locale)));
+            if (this.#style === "currency") {
+                const code = this.#currency.toUpperCase();
+                const narrow = this.#currencyDisplay === "narrowSymbol";
+                this.#currencyPattern = icuCached(
+                    "currency:" + locale + ":" + code + ":" + narrow,
+                    () => JSON.parse(intl_icu_currency_pattern(locale, code, narrow))
+                );
+            }
+        }
+    }
+
+    #formatToPartsWithSymbols(number) {
+        const sym = this.#symbols;
+        let val = Number(number);
+        const isNeg = val < 0;
+        if (isNeg) val = -val;
+        if (this.#style === "percent") val = val * 100;
+
+        const group = this.#useGrouping
+            ? intStr => groupIntegerPart(intStr, sym.primaryGroup, sym.secondaryGroup, sym.minGrouping)
+            : null;
+        const { intGroups, fracPart } = formatDecimalDigits(
+            val,
+            this.#minimumIntegerDigits,
+            this.#minimumFractionDigits,
+            this.#maximumFractionDigits,
+            group
+        );
+        const numberParts = [];
+        for (let i = 0; i < intGroups.length; i++) {
+            if (i > 0) numberParts.push({ type: "group", value: sym.group });
+            numberParts.push({ type: "integer", value: localizeDigits(intGroups[i], sym.digits) });
+        }
+        if (fracPart.length > 0) {
+            numberParts.push({ type: "decimal", value: sym.decimal });
+            numberParts.push({ type: "fraction", value: localizeDigits(fracPart, sym.digits) });
+        }
+
+        let parts;
+        if (this.#style === "currency") {
+            const code = this.#currency.toUpperCase();
+            const display = this.#currencyDisplay;
+            const currency = display === "symbol" || display === "narrowSymbol"
+                ? this.#currencyPattern.symbol
+                : code;
+            parts = [];
+            for (const piece of this.#currencyPattern.pattern.split(/(\{[01]\})/)) {
+                if (piece === "{0}") parts.push(...numberParts);
+                else if (piece === "{1}") parts.push({ type: "currency", value: currency });
+                else if (piece) parts.push({ type: "literal", value: piece });
+            }
+        } else if (this.#style === "percent") {
+            parts = [
+                ...percentAffixParts(sym.percentPrefix, sym.percentSign),
+                ...numberParts,
+                ...percentAffixParts(sym.percentSuffix, sym.percentSign),
+            ];
+        } else {
+            parts = numberParts;
+        }
+
+        const showPlus = this.#signDisplay === "always" || (this.#signDisplay === "exceptZero" && val > 0);
+        let prefix = "";
+        let suffix = "";
+        let signType = null;
+        if (isNeg && this.#signDisplay !== "never") {
+            [prefix, suffix, signType] = [sym.minusPrefix, sym.minusSuffix, "minusSign"];
+        } else if (!isNeg && showPlus) {
+            [prefix, suffix, signType] = [sym.plusPrefix, sym.plusSuffix, "plusSign"];
+        }
+        if (prefix) parts.unshift({ type: signType, value: prefix });
+        if (suffix) parts.push({ type: signType, value: suffix });
+        return parts;
     }
 
     formatToParts(number) {
+        if (this.#symbols) {
+            return this.#formatToPartsWithSymbols(number);
+        }
         const parts = [];
         let val = Number(number);
         const isNeg = val < 0;
@@ -494,7 +786,7 @@ class NumberFormatImpl {
 
     resolvedOptions() {
         const ro = {
-            locale: "en-US",
+            locale: this.#locale,
             numberingSystem: "latn",
             style: this.#style,
             minimumIntegerDigits: this.#minimumIntegerDigits,
@@ -511,8 +803,8 @@ class NumberFormatImpl {
         return ro;
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -529,12 +821,14 @@ Object.defineProperty(NumberFormatImpl.prototype, Symbol.toStringTag, {
 // ─── Collator ──────────────────────────────────────────────────────────────────
 
 class CollatorImpl {
+    #locale;
     #sensitivity;
     #numeric;
     #ignorePunctuation;
     #usage;
     #caseFirst;
     #collation;
+    #boundCompare;
 
     constructor(locales, options) {
         const opts = options || {};
@@ -544,9 +838,29 @@ class CollatorImpl {
         this.#usage = opts.usage || "sort";
         this.#caseFirst = opts.caseFirst || "false";
         this.#collation = opts.collation || "default";
+        this.#locale = resolveLocale(locales);
+    }
+
+    // Like the spec's getter, returns a function bound to this collator so it can be passed to sort()
+    get compare() {
+        if (this.#boundCompare === undefined) {
+            this.#boundCompare = (a, b) => this.#compare(a, b);
+        }
+        return this.#boundCompare;
     }
 
-    compare(a, b) {
+    #compare(a, b) {
+        if (ICU_ENABLED) {
+            return intl_icu_collator_compare(
+                this.#locale,
+                String(a),
+                String(b),
+                this.#sensitivity,
+                this.#numeric,
+                this.#caseFirst,
+                this.#ignorePunctuation
+            );
+        }
         return intl_collator_compare(
             String(a),
             String(b),
@@ -558,7 +872,7 @@ class CollatorImpl {
 
     resolvedOptions() {
         return {
-            locale: "en-US",
+            locale: this.#locale,
             usage: this.#usage,
             sensitivity: this.#sensitivity,
             ignorePunctuation: this.#ignorePunctuation,
@@ -568,8 +882,8 @@ class CollatorImpl {
         };
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -586,6 +900,7 @@ Object.defineProperty(CollatorImpl.prototype, Symbol.toStringTag, {
 // ─── PluralRules ───────────────────────────────────────────────────────────────
 
 class PluralRulesImpl {
+    #locale;
     #type;
     #minimumIntegerDigits;
     #minimumFractionDigits;
@@ -599,10 +914,28 @@ class PluralRulesImpl {
             ? opts.minimumFractionDigits : 0;
         this.#maximumFractionDigits = opts.maximumFractionDigits !== undefined
             ? opts.maximumFractionDigits : 3;
+        this.#locale = resolveLocale(locales);
+    }
+
+    // Plural operands depend on the visible fraction digits, so rules see the formatted number.
+    #decimalString(n) {
+        const val = Number(n);
+        const { intGroups, fracPart } = formatDecimalDigits(
+            Math.abs(val),
+            this.#minimumIntegerDigits,
+            this.#minimumFractionDigits,
+            this.#maximumFractionDigits,
+            null
+        );
+        return (val < 0 ? "-" : "") + intGroups[0] + (fracPart ? "." + fracPart : "");
     }
 
     select(n) {
         const val = Number(n);
+        if (ICU_ENABLED) {
+            if (!Number.isFinite(val)) return "other";
+            return intl_icu_plural_select(this.#locale, this.#type, this.#decimalString(val));
+        }
         if (this.#type === "ordinal") {
             const abs = Math.abs(val);
             const mod10 = abs % 10;
@@ -616,25 +949,39 @@ class PluralRulesImpl {
         return val === 1 ? "one" : "other";
     }
 
-    selectRange(_start, _end) {
+    selectRange(start, end) {
+        if (ICU_ENABLED) {
+            return intl_icu_plural_select_range(
+                this.#locale,
+                this.#type,
+                this.#decimalString(start),
+                this.#decimalString(end)
+            );
+        }
         return "other";
     }
 
     resolvedOptions() {
+        let pluralCategories;
+        if (ICU_ENABLED) {
+            pluralCategories = intl_icu_plural_categories(this.#locale, this.#type);
+        } else {
+            pluralCategories = this.#type === "ordinal"
+                ? ["few", "one", "other", "two"]
+                : ["one", "other"];
+        }
         return {
-            locale: "en-US",
+            locale: this.#locale,
             type: this.#type,
             minimumIntegerDigits: this.#minimumIntegerDigits,
             minimumFractionDigits: this.#minimumFractionDigits,
             maximumFractionDigits: this.#maximumFractionDigits,
-            pluralCategories: this.#type === "ordinal"
-                ? ["few", "one", "other", "two"]
-                : ["one", "other"],
+            pluralCategories,
         };
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -669,6 +1016,7 @@ const LIST_PATTERNS = {
 };
 
 class ListFormatImpl {
+    #locale;
     #type;
     #style;
     #patterns;
@@ -686,6 +1034,7 @@ class ListFormatImpl {
         }
 
         this.#patterns = LIST_PATTERNS[this.#type][this.#style];
+        this.#locale = resolveLocale(locales);
     }
 
     format(list) {
@@ -702,6 +1051,11 @@ class ListFormatImpl {
 
     formatToParts(list) {
         const items = Array.from(list).map(item => String(item));
+        if (ICU_ENABLED) {
+            return JSON.parse(intl_icu_list_format(this.#locale, this.#type, this.#style, items))
+                .filter(([type, value]) => type !== "literal" || value !== "")
+                .map(([type, value]) => ({ type, value }));
+        }
         const parts = [];
 
         if (items.length === 0) return parts;
@@ -729,11 +1083,11 @@ class ListFormatImpl {
     }
 
     resolvedOptions() {
-        return { locale: "en-US", type: this.#type, style: this.#style };
+        return { locale: this.#locale, type: this.#type, style: this.#style };
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -750,6 +1104,7 @@ Object.defineProperty(ListFormatImpl.prototype, Symbol.toStringTag, {
 // ─── Segmenter ─────────────────────────────────────────────────────────────────
 
 class SegmenterImpl {
+    #locale;
     #granularity;
 
     constructor(locales, options) {
@@ -759,22 +1114,25 @@ class SegmenterImpl {
         if (!["grapheme", "word", "sentence"].includes(this.#granularity)) {
             throw new RangeError("Invalid granularity: " + this.#granularity);
         }
+        this.#locale = resolveLocale(locales);
     }
 
     segment(string) {
         const str = String(string);
         const granularity = this.#granularity;
         // Call native segmentation — returns JSON array of [utf16_index, segment, isWordLike]
-        const rawSegments = JSON.parse(intl_segment(str, granularity));
+        const rawSegments = JSON.parse(ICU_ENABLED
+            ? intl_icu_segment(str, granularity)
+            : intl_segment(str, granularity));
         return new Segments(str, rawSegments, granularity);
     }
 
     resolvedOptions() {
-        return { locale: "en-US", granularity: this.#granularity };
+        return { locale: this.#locale, granularity: this.#granularity };
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -860,6 +1218,7 @@ const RTF_AUTO = {
 const RTF_VALID_UNITS = ["year", "quarter", "month", "week", "day", "hour", "minute", "second"];
 
 class RelativeTimeFormatImpl {
+    #locale;
     #numeric;
     #style;
 
@@ -874,6 +1233,38 @@ class RelativeTimeFormatImpl {
         if (!["long", "short", "narrow"].includes(this.#style)) {
             throw new RangeError("Invalid style: " + this.#style);
         }
+        this.#locale = resolveLocale(locales);
+    }
+
+    #formatToPartsWithData(n, unit) {
+        const locale = this.#locale;
+        const style = this.#style;
+        const data = icuCached(
+            "relative:" + locale + ":" + style + ":" + unit,
+            () => JSON.parse(intl_icu_relative_time(locale, style, unit))
+        );
+        if (this.#numeric === "auto" && Number.isInteger(n)) {
+            const special = data.relatives[String(n)];
+            if (special !== undefined) {
+                return [{ type: "literal", value: special }];
+            }
+        }
+
+        const absVal = Math.abs(n);
+        const isPast = n < 0 || Object.is(n, -0);
+        const category = new PluralRulesImpl(locale).select(absVal);
+        const patterns = isPast ? data.past : data.future;
+        const pattern = patterns[category] || patterns.other;
+        const [before, after] = pattern.split("{0}");
+        const parts = [];
+        if (before) parts.push({ type: "literal", value: before });
+        if (after !== undefined) {
+            for (const part of new NumberFormatImpl(locale).formatToParts(absVal)) {
+                parts.push({ ...part, unit });
+            }
+            if (after) parts.push({ type: "literal", value: after });
+        }
+        return parts;
     }
 
     format(value, unit) {
@@ -887,6 +1278,10 @@ class RelativeTimeFormatImpl {
             throw new RangeError("Invalid value: " + value);
         }
 
+        if (ICU_ENABLED) {
+            return this.#formatToPartsWithData(n, canonicalUnit);
+        }
+
         // numeric: "auto" — try special forms
         if (this.#numeric === "auto") {
             const autoMap = RTF_AUTO[canonicalUnit];
@@ -921,11 +1316,11 @@ class RelativeTimeFormatImpl {
     }
 
     resolvedOptions() {
-        return { locale: "en-US", numeric: this.#numeric, style: this.#style, numberingSystem: "latn" };
+        return { locale: this.#locale, numeric: this.#numeric, style: this.#style, numberingSystem: "latn" };
     }
 
-    static supportedLocalesOf() {
-        return ["en-US"];
+    static supportedLocalesOf(locales) {
+        return supportedLocalesOf(locales);
     }
 }
 
@@ -954,6 +1349,7 @@ Object.defineProperty(RelativeTimeFormatImpl.prototype, Symbol.toStringTag, {
 // ─── Static helpers ────────────────────────────────────────────────────────────
 
 function getCanonicalLocales(locales) {
+    if (ICU_ENABLED) return canonicalizeLocaleList(locales);
     if (locales === undefined || locales === null) return [];
     const list = Array.isArray(locales) ? locales : [locales];
     if (list.length === 0) return [];
@@ -1004,8 +1400,9 @@ String.prototype.localeCompare = function(that, locales, options) {
 };
 
 // Polyfill Date.prototype.toLocaleString / toLocaleDateString / toLocaleTimeString
-// to delegate to Intl.DateTimeFormat when options (especially timeZone) are provided.
-// QuickJS's native implementations ignore the options parameter.
+// to delegate to Intl.DateTimeFormat when options (especially timeZone) are provided,
+// or a locale is requested and locale data is available.
+// QuickJS's native implementations ignore the locales and options parameters.
 const _toLocaleDefaults = {
     year: "numeric", month: "numeric", day: "numeric",
     hour: "numeric", minute: "2-digit", second: "2-digit",
@@ -1026,10 +1423,21 @@ function _hasDateTimeFields(opts) {
     return false;
 }
 
+// Returns the DateTimeFormat options to delegate with, or null to use the native implementation.
+function _toLocaleOptions(locales, options, defaults) {
+    if (options !== undefined && options !== null && typeof options === "object") {
+        return _hasDateTimeFields(options) ? options : { ...defaults, ...options };
+    }
+    if (ICU_ENABLED && locales !== undefined) {
+        return defaults;
+    }
+    return null;
+}
+
 const _origToLocaleString = Date.prototype.toLocaleString;
 Date.prototype.toLocaleString = function(locales, options) {
-    if (options !== undefined && options !== null && typeof options === "object") {
-        const opts = _hasDateTimeFields(options) ? options : { ..._toLocaleDefaults, ...options };
+    const opts = _toLocaleOptions(locales, options, _toLocaleDefaults);
+    if (opts !== null) {
         return new DateTimeFormatImpl(locales, opts).format(this);
     }
     return _origToLocaleString.call(this);
@@ -1037,8 +1445,8 @@ Date.prototype.toLocaleString = function(locales, options) {
 
 const _origToLocaleDateString = Date.prototype.toLocaleDateString;
 Date.prototype.toLocaleDateString = function(locales, options) {
-    if (options !== undefined && options !== null && typeof options === "object") {
-        const opts = _hasDateTimeFields(options) ? options : { ..._toLocaleDateDefaults, ...options };
+    const opts = _toLocaleOptions(locales, options, _toLocaleDateDefaults);
+    if (opts !== null) {
         return new DateTimeFormatImpl(locales, opts).format(this);
     }
     return _origToLocaleDateString.call(this);
@@ -1046,12 +1454,20 @@ Date.prototype.toLocaleDateString = function(locales, options) {
 
 const _origToLocaleTimeString = Date.prototype.toLocaleTimeString;
 Date.prototype.toLocaleTimeString = function(locales, options) {
-    if (options !== undefined && options !== null && typeof options === "object") {
-        const opts = _hasDateTimeFields(options) ? options : { ..._toLocaleTimeDefaults, ...options };
+    const opts = _toLocaleOptions(locales, options, _toLocaleTimeDefaults);
+    if (opts !== null) {
         return new DateTimeFormatImpl(locales, opts).format(this);
     }
     return _origToLocaleTimeString.call(this);
 };
 
+// With locale data, Number.prototype.toLocaleString formats through Intl.NumberFormat too.
+if (ICU_ENABLED) {
+    const _numberValueOf = Number.prototype.valueOf;
+    Number.prototype.toLocaleString = function(locales, options) {
+        return new NumberFormatImpl(locales, options).format(_numberValueOf.call(this));
+    };
+}
+
 export { Intl };
 export default Intl;
@@ -4,6 +4,7 @@ use chrono::{DateTime, Datelike, Timelike, Utc};
 
 #[rquickjs::module]
 pub mod native_module {
+    use super::super::intl_icu as icu;
     use rquickjs::prelude::*;
 
     // Returns flat tuple: (year, month, day, hour, minute, second, weekday, utc_offset_minutes, error)
@@ -53,6 +54,140 @@ pub mod native_module {
     pub fn intl_segment(text: String, granularity: String) -> String {
         super::segment_impl(&text, &granularity)
     }
+
+    // The intl_icu_* functions expose the ICU4X locale data baked in with the `intl-full`
+    // feature. Without it intl_icu_languages() is empty and the others are never called.
+    // Structured results are JSON-encoded like intl_segment's.
+
+    #[rquickjs::function]
+    pub fn intl_icu_languages() -> Vec<String> {
+        icu::languages()
+    }
+
+    #[rquickjs::function]
+    pub fn intl_icu_canonicalize(tag: String) -> Option<String> {
+        icu::canonicalize(&tag)
+    }
+
+    #[rquickjs::function]
+    pub fn intl_icu_number_symbols(ctx: Ctx<'_>, locale: String) -> rquickjs::Result<String> {
+        icu::number_symbols(&locale).map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    #[rquickjs::function]
+    pub fn intl_icu_currency_pattern(
+        ctx: Ctx<'_>,
+        locale: String,
+        code: String,
+        narrow: bool,
+    ) -> rquickjs::Result<String> {
+        icu::currency_pattern(&locale, &code, narrow).map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    // kind: "cardinal" | "ordinal"; numbers are passed as decimal strings
+    #[rquickjs::function]
+    pub fn intl_icu_plural_select(
+        ctx: Ctx<'_>,
+        locale: String,
+        kind: String,
+        value: String,
+    ) -> rquickjs::Result<String> {
+        icu::plural_select(&locale, &kind, &value).map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    #[rquickjs::function]
+    pub fn intl_icu_plural_select_range(
+        ctx: Ctx<'_>,
+        locale: String,
+        kind: String,
+        start: String,
+        end: String,
+    ) -> rquickjs::Result<String> {
+        icu::plural_select_range(&locale, &kind, &start, &end)
+            .map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    #[rquickjs::function]
+    pub fn intl_icu_plural_categories(
+        ctx: Ctx<'_>,
+        locale: String,
+        kind: String,
+    ) -> rquickjs::Result<Vec<String>> {
+        icu::plural_categories(&locale, &kind).map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    // Returns [[part_type, text], ...] where part_type is "literal" or "element"
+    #[rquickjs::function]
+    pub fn intl_icu_list_format(
+        ctx: Ctx<'_>,
+        locale: String,
+        kind: String,
+        style: String,
+        items: Vec<String>,
+    ) -> rquickjs::Result<String> {
+        icu::list_format(&locale, &kind, &style, &items)
+            .map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    #[rquickjs::function]
+    #[allow(clippy::too_many_arguments)]
+    pub fn intl_icu_collator_compare(
+        ctx: Ctx<'_>,
+        locale: String,
+        a: String,
+        b: String,
+        sensitivity: String,
+        numeric: bool,
+        case_first: String,
+        ignore_punctuation: bool,
+    ) -> rquickjs::Result<i32> {
+        icu::collator_compare(
+            &locale,
+            &a,
+            &b,
+            &sensitivity,
+            numeric,
+            &case_first,
+            ignore_punctuation,
+        )
+        .map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    // Same output as intl_segment, using the ICU4X segmenters (dictionary-based for CJK words)
+    #[rquickjs::function]
+    pub fn intl_icu_segment(text: String, granularity: String) -> String {
+        icu::segment(&text, &granularity)
+    }
+
+    // Returns the best pattern for a CLDR skeleton as [["f", "MMMM"], ["l", " "], ...]
+    #[rquickjs::function]
+    pub fn intl_icu_date_pattern(
+        ctx: Ctx<'_>,
+        locale: String,
+        skeleton: String,
+    ) -> rquickjs::Result<String> {
+        icu::date_pattern(&locale, &skeleton).map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    #[rquickjs::function]
+    pub fn intl_icu_date_symbols(ctx: Ctx<'_>, locale: String) -> rquickjs::Result<String> {
+        icu::date_symbols(&locale).map_err(|err| super::throw_range(&ctx, err))
+    }
+
+    // Returns {relatives: {"-1": ...}, past: {one: "... {0} ..."}, future: {...}}
+    #[rquickjs::function]
+    pub fn intl_icu_relative_time(
+        ctx: Ctx<'_>,
+        locale: String,
+        style: String,
+        unit: String,
+    ) -> rquickjs::Result<String> {
+        icu::relative_time(&locale, &style, &unit).map_err(|err| super::throw_range(&ctx, err))
+    }
+}
+
+fn throw_range(ctx: &rquickjs::Ctx<'_>, message: String) -> rquickjs::Error {
+    rquickjs::Exception::throw_range(ctx, &message)
 }
 
 struct DtfResolved {
@@ -288,7 +423,7 @@ fn segment_impl(text: &str, granularity: &str) -> String {
     result
 }
 
-fn json_escape(s: &str) -> String {
+pub(super) fn json_escape(s: &str) -> String {
     let mut escaped = String::with_capacity(s.len());
     for c in s.chars() {
         match c {
@@ -0,0 +1,652 @@
+//! ICU4X-backed locale data for the `intl-full` feature.
+//!
+//! The data blob is produced by the skeleton's build script from the locales selected at
+//! generation time. Formatting itself stays in `intl.js`; these helpers hand it the localized
+//! symbols, patterns and rule results it needs.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex};
+
+use fixed_decimal::FixedDecimal;
+use icu_collator::{
+    AlternateHandling, CaseFirst, CaseLevel, Collator, CollatorOptions, Numeric, Strength,
+};
+use icu_datetime::fields::{Field, FieldLength, FieldSymbol};
+use icu_datetime::options::components;
+use icu_datetime::pattern::PatternItem;
+use icu_datetime::pattern::runtime::{Pattern, PatternPlurals};
+use icu_datetime::provider::calendar::{
+    DateSkeletonPatternsV1Marker, GregorianDateLengthsV1Marker, GregorianDateSymbolsV1Marker,
+    TimeLengthsV1Marker, TimeSymbolsV1Marker, months,
+};
+use icu_datetime::skeleton::{BestSkeleton, create_best_pattern_for_fields};
+use icu_decimal::provider::DecimalSymbolsV1Marker;
+use icu_experimental::dimension::provider::currency::{
+    CurrencyEssentialsV1Marker, PatternSelection, PlaceholderValue,
+};
+use icu_experimental::dimension::provider::percent::PercentEssentialsV1Marker;
+use icu_experimental::relativetime::provider::*;
+use icu_list::{ListFormatter, ListLength};
+use icu_locid::Locale;
+use icu_locid::extensions::unicode::{key, value};
+use icu_plurals::{PluralCategory, PluralRuleType, PluralRules, PluralRulesWithRanges};
+use icu_provider::prelude::*;
+use icu_provider::serde::DeserializingBufferProvider;
+use icu_provider_adapters::fallback::LocaleFallbackProvider;
+use icu_provider_blob::BlobDataProvider;
+use icu_segmenter::{GraphemeClusterSegmenter, SentenceSegmenter, WordSegmenter};
+use writeable::{Part, PartsWrite};
+
+use super::intl::json_escape;
+
+static DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/intl_data.postcard"));
+
+include!(concat!(env!("OUT_DIR"), "/intl_languages.rs"));
+
+static PROVIDER: LazyLock<LocaleFallbackProvider<BlobDataProvider>> = LazyLock::new(|| {
+    let blob = BlobDataProvider::try_new_from_static_blob(DATA).expect("invalid Intl data blob");
+    LocaleFallbackProvider::try_new_with_buffer_provider(blob)
+        .expect("Intl data blob is missing the locale fallback data")
+});
+
+static COLLATORS: LazyLock<Mutex<HashMap<String, Collator>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static WORD_SEGMENTER: LazyLock<WordSegmenter> = LazyLock::new(|| {
+    WordSegmenter::try_new_auto_with_buffer_provider(&*PROVIDER)
+        .expect("Intl data blob is missing the word segmenter data")
+});
+
+pub fn languages() -> Vec<String> {
+    BAKED_LANGUAGES
+        .iter()
+        .map(|language| language.to_string())
+        .collect()
+}
+
+pub fn canonicalize(tag: &str) -> Option<String> {
+    Locale::from_str(tag).ok().map(|locale| locale.to_string())
+}
+
+fn data_locale(locale: &str) -> Result<DataLocale, String> {
+    Locale::from_str(locale)
+        .map(|locale| DataLocale::from(&locale))
+        .map_err(|err| format!("Invalid language tag: {locale} ({err})"))
+}
+
+fn load<M>(locale: &DataLocale) -> Result<DataPayload<M>, String>
+where
+    M: KeyedDataMarker,
+    for<'a> DeserializingBufferProvider<'a, LocaleFallbackProvider<BlobDataProvider>>:
+        DataProvider<M>,
+{
+    PROVIDER
+        .as_deserializing()
+        .load(DataRequest {
+            locale,
+            metadata: Default::default(),
+        })
+        .and_then(DataResponse::take_payload)
+        .map_err(|err| err.to_string())
+}
+
+pub fn number_symbols(locale: &str) -> Result<String, String> {
+    let locale = data_locale(locale)?;
+    let symbols = load::<DecimalSymbolsV1Marker>(&locale)?;
+    let symbols = symbols.get();
+    let percent = load::<PercentEssentialsV1Marker>(&locale)?;
+    let percent = percent.get();
+    let (percent_prefix, percent_suffix) = split_number_pattern(&percent.standard);
+    let digits: String = symbols.digits.iter().collect();
+    Ok(format!(
+        "{{\"decimal\":\"{}\",\"group\":\"{}\",\"minusPrefix\":\"{}\",\"minusSuffix\":\"{}\",\"plusPrefix\":\"{}\",\"plusSuffix\":\"{}\",\"primaryGroup\":{},\"secondaryGroup\":{},\"minGrouping\":{},\"digits\":\"{}\",\"percentPrefix\":\"{}\",\"percentSuffix\":\"{}\",\"percentSign\":\"{}\"}}",
+        json_escape(&symbols.decimal_separator),
+        json_escape(&symbols.grouping_separator),
+        json_escape(&symbols.minus_sign_affixes.prefix),
+        json_escape(&symbols.minus_sign_affixes.suffix),
+        json_escape(&symbols.plus_sign_affixes.prefix),
+        json_escape(&symbols.plus_sign_affixes.suffix),
+        symbols.grouping_sizes.primary,
+        symbols.grouping_sizes.secondary,
+        symbols.grouping_sizes.min_grouping,
+        json_escape(&digits),
+        json_escape(&percent_prefix),
+        json_escape(&percent_suffix),
+        json_escape(&percent.percent_sign_symbol),
+    ))
+}
+
+/// Splits a CLDR number pattern such as `#,##0 %` into the text before and after the number.
+fn split_number_pattern(pattern: &str) -> (String, String) {
+    let pattern = pattern.split(';').next().unwrap_or_default();
+    let is_number = |c: char| matches!(c, '#' | '0' | ',' | '.');
+    match (pattern.find(is_number), pattern.rfind(is_number)) {
+        (Some(start), Some(end)) => (pattern[..start].to_string(), pattern[end + 1..].to_string()),
+        _ => (String::new(), String::new()),
+    }
+}
+
+pub fn currency_pattern(locale: &str, code: &str, narrow: bool) -> Result<String, String> {
+    let locale = data_locale(locale)?;
+    let essentials = load::<CurrencyEssentialsV1Marker>(&locale)?;
+    let essentials = essentials.get();
+    let iso = tinystr::TinyAsciiStr::<3>::from_str(code)
+        .map_err(|_| format!("Invalid currency code: {code}"))?;
+    let config = essentials
+        .pattern_config_map
+        .get_copied(&iso.to_unvalidated())
+        .unwrap_or(essentials.default_pattern_config);
+    let (selection, placeholder) = if narrow {
+        (
+            config.narrow_pattern_selection,
+            config.narrow_placeholder_value,
+        )
+    } else {
+        (
+            config.short_pattern_selection,
+            config.short_placeholder_value,
+        )
+    };
+    let symbol = match placeholder {
+        Some(PlaceholderValue::Index(index)) => essentials
+            .placeholders
+            .get(index.into())
+            .unwrap_or(code)
+            .to_string(),
+        Some(PlaceholderValue::ISO) | None => code.to_string(),
+    };
+    let pattern = match selection {
+        PatternSelection::Standard => essentials.standard_pattern.as_ref(),
+        PatternSelection::StandardAlphaNextToNumber => {
+            essentials.standard_alpha_next_to_number_pattern.as_ref()
+        }
+    }
+    .ok_or_else(|| "Missing currency pattern".to_string())?;
+    let pattern = pattern.interpolate_to_string(("{0}", "{1}"));
+    Ok(format!(
+        "{{\"pattern\":\"{}\",\"symbol\":\"{}\"}}",
+        json_escape(&pattern),
+        json_escape(&symbol)
+    ))
+}
+
+fn plural_rule_type(kind: &str) -> PluralRuleType {
+    if kind == "ordinal" {
+        PluralRuleType::Ordinal
+    } else {
+        PluralRuleType::Cardinal
+    }
+}
+
+fn plural_category_name(category: PluralCategory) -> &'static str {
+    match category {
+        PluralCategory::Zero => "zero",
+        PluralCategory::One => "one",
+        PluralCategory::Two => "two",
+        PluralCategory::Few => "few",
+        PluralCategory::Many => "many",
+        PluralCategory::Other => "other",
+    }
+}
+
+fn parse_decimal(value: &str) -> Result<FixedDecimal, String> {
+    FixedDecimal::from_str(value).map_err(|_| format!("Invalid number: {value}"))
+}
+
+pub fn plural_select(locale: &str, kind: &str, value: &str) -> Result<String, String> {
+    let locale = data_locale(locale)?;
+    let rules =
+        PluralRules::try_new_with_buffer_provider(&*PROVIDER, &locale, plural_rule_type(kind))
+            .map_err(|err| err.to_string())?;
+    Ok(plural_category_name(rules.category_for(&parse_decimal(value)?)).to_string())
+}
+
+pub fn plural_select_range(
+    locale: &str,
+    kind: &str,
+    start: &str,
+    end: &str,
+) -> Result<String, String> {
+    let locale = data_locale(locale)?;
+    let rules = PluralRulesWithRanges::try_new_with_buffer_provider(
+        &*PROVIDER,
+        &locale,
+        plural_rule_type(kind),
+    )
+    .map_err(|err| err.to_string())?;
+    let category = rules.category_for_range(&parse_decimal(start)?, &parse_decimal(end)?);
+    Ok(plural_category_name(category).to_string())
+}
+
+pub fn plural_categories(locale: &str, kind: &str) -> Result<Vec<String>, String> {
+    let locale = data_locale(locale)?;
+    let rules =
+        PluralRules::try_new_with_buffer_provider(&*PROVIDER, &locale, plural_rule_type(kind))
+            .map_err(|err| err.to_string())?;
+    let mut categories: Vec<String> = rules
+        .categories()
+        .map(|category| plural_category_name(category).to_string())
+        .collect();
+    categories.sort();
+    Ok(categories)
+}
+
+/// Collects the output of a [`writeable::Writeable`] as `[type, value]` pairs.
+#[derive(Default)]
+struct PartsCollector {
+    output: String,
+    parts: Vec<(&'static str, usize, usize)>,
+}
+
+impl std::fmt::Write for PartsCollector {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.output.push_str(s);
+        Ok(())
+    }
+}
+
+impl PartsWrite for PartsCollector {
+    type SubPartsWrite = Self;
+
+    fn with_part(
+        &mut self,
+        part: Part,
+        mut f: impl FnMut(&mut Self::SubPartsWrite) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        let start = self.output.len();
+        f(self)?;
+        self.parts.push((part.value, start, self.output.len()));
+        Ok(())
+    }
+}
+
+pub fn list_format(
+    locale: &str,
+    kind: &str,
+    style: &str,
+    items: &[String],
+) -> Result<String, String> {
+    use writeable::Writeable;
+
+    let locale = data_locale(locale)?;
+    let length = match style {
+        "short" => ListLength::Short,
+        "narrow" => ListLength::Narrow,
+        _ => ListLength::Wide,
+    };
+    let formatter = match kind {
+        "disjunction" => {
+            ListFormatter::try_new_or_with_length_with_buffer_provider(&*PROVIDER, &locale, length)
+        }
+        "unit" => ListFormatter::try_new_unit_with_length_with_buffer_provider(
+            &*PROVIDER, &locale, length,
+        ),
+        _ => {
+            ListFormatter::try_new_and_with_length_with_buffer_provider(&*PROVIDER, &locale, length)
+        }
+    }
+    .map_err(|err| err.to_string())?;
+
+    let mut collector = PartsCollector::default();
+    formatter
+        .format(items.iter().map(String::as_str))
+        .write_to_parts(&mut collector)
+        .map_err(|err| err.to_string())?;
+
+    let mut result = String::from("[");
+    for (i, (kind, start, end)) in collector.parts.iter().enumerate() {
+        if i > 0 {
+            result.push(',');
+        }
+        let _ = write!(
+            result,
+            "[\"{kind}\",\"{}\"]",
+            json_escape(&collector.output[*start..*end])
+        );
+    }
+    result.push(']');
+    Ok(result)
+}
+
+pub fn collator_compare(
+    locale: &str,
+    a: &str,
+    b: &str,
+    sensitivity: &str,
+    numeric: bool,
+    case_first: &str,
+    ignore_punctuation: bool,
+) -> Result<i32, String> {
+    let cache_key = format!("{locale}|{sensitivity}|{numeric}|{case_first}|{ignore_punctuation}");
+    let mut collators = COLLATORS.lock().unwrap();
+    if !collators.contains_key(&cache_key) {
+        let mut options = CollatorOptions::new();
+        options.strength = Some(match sensitivity {
+            "base" | "case" => Strength::Primary,
+            "accent" => Strength::Secondary,
+            _ => Strength::Tertiary,
+        });
+        if sensitivity == "case" {
+            options.case_level = Some(CaseLevel::On);
+        }
+        options.numeric = Some(if numeric { Numeric::On } else { Numeric::Off });
+        options.case_first = Some(match case_first {
+            "upper" => CaseFirst::UpperFirst,
+            "lower" => CaseFirst::LowerFirst,
+            _ => CaseFirst::Off,
+        });
+        if ignore_punctuation {
+            options.alternate_handling = Some(AlternateHandling::Shifted);
+        }
+        let collator =
+            Collator::try_new_with_buffer_provider(&*PROVIDER, &data_locale(locale)?, options)
+                .map_err(|err| err.to_string())?;
+        collators.insert(cache_key.clone(), collator);
+    }
+    Ok(collators[&cache_key].compare(a, b) as i32)
+}
+
+pub fn segment(text: &str, granularity: &str) -> String {
+    let utf16: Vec<u16> = text.encode_utf16().collect();
+    let mut boundaries: Vec<(usize, bool)> = Vec::new();
+    match granularity {
+        "word" => {
+            let mut iter = WORD_SEGMENTER.segment_utf16(&utf16);
+            while let Some(boundary) = iter.next() {
+                boundaries.push((boundary, iter.is_word_like()));
+            }
+        }
+        "sentence" => {
+            let segmenter = SentenceSegmenter::try_new_with_buffer_provider(&*PROVIDER)
+                .expect("Intl data blob is missing the sentence segmenter data");
+            boundaries.extend(segmenter.segment_utf16(&utf16).map(|b| (b, false)));
+        }
+        _ => {
+            let segmenter = GraphemeClusterSegmenter::try_new_with_buffer_provider(&*PROVIDER)
+                .expect("Intl data blob is missing the grapheme segmenter data");
+            boundaries.extend(segmenter.segment_utf16(&utf16).map(|b| (b, false)));
+        }
+    }
+
+    let mut result = String::from("[");
+    let mut start = 0;
+    for (end, is_word_like) in boundaries {
+        if end == 0 {
+            continue;
+        }
+        if start > 0 {
+            result.push(',');
+        }
+        let segment = String::from_utf16_lossy(&utf16[start..end]);
+        let _ = write!(
+            result,
+            "[{start},\"{}\",{is_word_like}]",
+            json_escape(&segment)
+        );
+        start = end;
+    }
+    result.push(']');
+    result
+}
+
+fn gregorian_locale(locale: &str) -> Result<DataLocale, String> {
+    let mut locale = data_locale(locale)?;
+    locale.set_unicode_ext(key!("ca"), value!("gregory"));
+    Ok(locale)
+}
+
+/// Resolves a CLDR skeleton such as `yMMMdjm` into the best localized pattern.
+pub fn date_pattern(locale: &str, skeleton: &str) -> Result<String, String> {
+    let fields = parse_skeleton(skeleton)?;
+    let locale = gregorian_locale(locale)?;
+    let skeletons = load::<DateSkeletonPatternsV1Marker>(&locale)?;
+    let lengths = load::<GregorianDateLengthsV1Marker>(&locale)?;
+    let best = create_best_pattern_for_fields(
+        skeletons.get(),
+        &lengths.get().length_combinations,
+        &fields,
+        &components::Bag::empty(),
+        false,
+    );
+    let patterns = match best {
+        BestSkeleton::AllFieldsMatch(patterns) | BestSkeleton::MissingOrExtraFields(patterns) => {
+            patterns
+        }
+        BestSkeleton::NoMatch => return Err(format!("No date pattern matches '{skeleton}'")),
+    };
+    let pattern = match patterns {
+        PatternPlurals::SinglePattern(pattern) => pattern,
+        PatternPlurals::MultipleVariants(variants) => variants.other,
+    };
+    Ok(pattern_json(&pattern))
+}
+
+/// Encodes pattern items as `[["f", "MMMM"], ["l", " "], ...]` so `intl.js` needs no pattern parser.
+fn pattern_json(pattern: &Pattern) -> String {
+    let mut items: Vec<(bool, String)> = Vec::new();
+    for item in pattern.items.iter() {
+        match item {
+            PatternItem::Field(field) => {
+                let len = match field.length {
+                    FieldLength::One | FieldLength::NumericOverride(_) => 1,
+                    FieldLength::TwoDigit => 2,
+                    FieldLength::Abbreviated => 3,
+                    FieldLength::Wide => 4,
+                    FieldLength::Narrow => 5,
+                    FieldLength::Six => 6,
+                    FieldLength::Fixed(len) => len as usize,
+                };
+                items.push((true, char::from(field.symbol).to_string().repeat(len)));
+            }
+            PatternItem::Literal(ch) => match items.last_mut() {
+                Some((false, text)) => text.push(ch),
+                _ => items.push((false, ch.to_string())),
+            },
+        }
+    }
+    let items: Vec<String> = items
+        .iter()
+        .map(|(is_field, text)| {
+            format!(
+                "[\"{}\",\"{}\"]",
+                if *is_field { "f" } else { "l" },
+                json_escape(text)
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn parse_skeleton(skeleton: &str) -> Result<Vec<Field>, String> {
+    let chars: Vec<char> = skeleton.chars().collect();
+    let mut fields = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+        let mut len = 1;
+        while i + len < chars.len() && chars[i + len] == ch {
+            len += 1;
+        }
+        let symbol =
+            FieldSymbol::try_from(ch).map_err(|_| format!("Unsupported skeleton field '{ch}'"))?;
+        let length = match len {
+            1 => FieldLength::One,
+            2 => FieldLength::TwoDigit,
+            3 => FieldLength::Abbreviated,
+            4 => FieldLength::Wide,
+            _ => FieldLength::Narrow,
+        };
+        fields.push(Field { symbol, length });
+        i += len;
+    }
+    Ok(fields)
+}
+
+fn json_string_array<'a>(values: impl IntoIterator<Item = &'a str>) -> String {
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|value| format!("\"{}\"", json_escape(value)))
+        .collect();
+    format!("[{}]", values.join(","))
+}
+
+fn month_names(symbols: &months::SymbolsV1) -> String {
+    json_string_array((1..=12).map(|month| {
+        let code = icu_calendar::types::MonthCode(
+            tinystr::TinyAsciiStr::from_str(&format!("M{month:02}")).unwrap(),
+        );
+        symbols.get(code).unwrap_or_default()
+    }))
+}
+
+/// Returns the Gregorian month, weekday, era and day period names plus the preferred hour cycle.
+pub fn date_symbols(locale: &str) -> Result<String, String> {
+    let locale = gregorian_locale(locale)?;
+    let date = load::<GregorianDateSymbolsV1Marker>(&locale)?;
+    let date = date.get();
+    let time = load::<TimeSymbolsV1Marker>(&locale)?;
+    let time = time.get();
+    let lengths = load::<TimeLengthsV1Marker>(&locale)?;
+
+    let months = &date.months;
+    let stand_alone_months = months.stand_alone.as_ref();
+    let weekdays = &date.weekdays.format;
+    let era = |map: &zerovec::ZeroMap<'_, zerovec::ule::UnvalidatedStr, str>, key: &str| {
+        map.get(key.into()).unwrap_or_default().to_string()
+    };
+    let periods = &time.day_periods.format;
+    let hour_cycle = match lengths.get().preferred_hour_cycle {
+        icu_datetime::pattern::CoarseHourCycle::H11H12 => "h12",
+        icu_datetime::pattern::CoarseHourCycle::H23H24 => "h23",
+    };
+
+    Ok(format!(
+        "{{\"months\":{{\"narrow\":{},\"short\":{},\"long\":{}}},\"standaloneMonths\":{{\"narrow\":{},\"short\":{},\"long\":{}}},\"weekdays\":{{\"narrow\":{},\"short\":{},\"long\":{}}},\"eras\":{{\"narrow\":[\"{}\",\"{}\"],\"short\":[\"{}\",\"{}\"],\"long\":[\"{}\",\"{}\"]}},\"dayPeriods\":{{\"narrow\":[\"{}\",\"{}\"],\"short\":[\"{}\",\"{}\"],\"long\":[\"{}\",\"{}\"]}},\"hourCycle\":\"{hour_cycle}\"}}",
+        month_names(&months.format.narrow),
+        month_names(&months.format.abbreviated),
+        month_names(&months.format.wide),
+        month_names(
+            stand_alone_months
+                .and_then(|s| s.narrow.as_ref())
+                .unwrap_or(&months.format.narrow)
+        ),
+        month_names(
+            stand_alone_months
+                .and_then(|s| s.abbreviated.as_ref())
+                .unwrap_or(&months.format.abbreviated)
+        ),
+        month_names(
+            stand_alone_months
+                .and_then(|s| s.wide.as_ref())
+                .unwrap_or(&months.format.wide)
+        ),
+        json_string_array(weekdays.narrow.0.iter().map(|s| &**s)),
+        json_string_array(weekdays.abbreviated.0.iter().map(|s| &**s)),
+        json_string_array(weekdays.wide.0.iter().map(|s| &**s)),
+        json_escape(&era(&date.eras.narrow, "bce")),
+        json_escape(&era(&date.eras.narrow, "ce")),
+        json_escape(&era(&date.eras.abbr, "bce")),
+        json_escape(&era(&date.eras.abbr, "ce")),
+        json_escape(&era(&date.eras.names, "bce")),
+        json_escape(&era(&date.eras.names, "ce")),
+        json_escape(&periods.narrow.am),
+        json_escape(&periods.narrow.pm),
+        json_escape(&periods.abbreviated.am),
+        json_escape(&periods.abbreviated.pm),
+        json_escape(&periods.wide.am),
+        json_escape(&periods.wide.pm),
+    ))
+}
+
+macro_rules! relative_time_data {
+    ($locale:expr, $style:expr, $unit:expr, $($s:literal, $u:literal => $marker:ty),+ $(,)?) => {
+        match ($style, $unit) {
+            $(($s, $u) => relative_time_json(load::<$marker>($locale)?.get()),)+
+            _ => return Err(format!("Invalid unit: {}", $unit)),
+        }
+    };
+}
+
+/// Returns the relative time patterns for one unit and style.
+pub fn relative_time(locale: &str, style: &str, unit: &str) -> Result<String, String> {
+    let locale = data_locale(locale)?;
+    Ok(relative_time_data!(&locale, style, unit,
+        "long", "second" => LongSecondRelativeTimeFormatDataV1Marker,
+        "long", "minute" => LongMinuteRelativeTimeFormatDataV1Marker,
+        "long", "hour" => LongHourRelativeTimeFormatDataV1Marker,
+        "long", "day" => LongDayRelativeTimeFormatDataV1Marker,
+        "long", "week" => LongWeekRelativeTimeFormatDataV1Marker,
+        "long", "month" => LongMonthRelativeTimeFormatDataV1Marker,
+        "long", "quarter" => LongQuarterRelativeTimeFormatDataV1Marker,
+        "long", "year" => LongYearRelativeTimeFormatDataV1Marker,
+        "short", "second" => ShortSecondRelativeTimeFormatDataV1Marker,
+        "short", "minute" => ShortMinuteRelativeTimeFormatDataV1Marker,
+        "short", "hour" => ShortHourRelativeTimeFormatDataV1Marker,
+        "short", "day" => ShortDayRelativeTimeFormatDataV1Marker,
+        "short", "week" => ShortWeekRelativeTimeFormatDataV1Marker,
+        "short", "month" => ShortMonthRelativeTimeFormatDataV1Marker,
+        "short", "quarter" => ShortQuarterRelativeTimeFormatDataV1Marker,
+        "short", "year" => ShortYearRelativeTimeFormatDataV1Marker,
+        "narrow", "second" => NarrowSecondRelativeTimeFormatDataV1Marker,
+        "narrow", "minute" => NarrowMinuteRelativeTimeFormatDataV1Marker,
+        "narrow", "hour" => NarrowHourRelativeTimeFormatDataV1Marker,
+        "narrow", "day" => NarrowDayRelativeTimeFormatDataV1Marker,
+        "narrow", "week" => NarrowWeekRelativeTimeFormatDataV1Marker,
+        "narrow", "month" => NarrowMonthRelativeTimeFormatDataV1Marker,
+        "narrow", "quarter" => NarrowQuarterRelativeTimeFormatDataV1Marker,
+        "narrow", "year" => NarrowYearRelativeTimeFormatDataV1Marker,
+    ))
+}
+
+fn relative_time_json(data: &RelativeTimePatternDataV1) -> String {
+    let mut relatives = String::from("{");
+    for (i, (offset, text)) in data.relatives.iter().enumerate() {
+        if i > 0 {
+            relatives.push(',');
+        }
+        let offset = <i8 as zerovec::ule::AsULE>::from_unaligned(*offset);
+        let _ = write!(relatives, "\"{offset}\":\"{}\"", json_escape(text));
+    }
+    relatives.push('}');
+
+    format!(
+        "{{\"relatives\":{relatives},\"past\":{},\"future\":{}}}",
+        plural_patterns_json(&data.past),
+        plural_patterns_json(&data.future)
+    )
+}
+
+fn plural_patterns_json(mapping: &PluralRulesCategoryMapping) -> String {
+    let mut result = String::from("{");
+    let categories = [
+        ("zero", mapping.zero.as_ref()),
+        ("one", mapping.one.as_ref()),
+        ("two", mapping.two.as_ref()),
+        ("few", mapping.few.as_ref()),
+        ("many", mapping.many.as_ref()),
+        ("other", Some(&mapping.other)),
+    ];
+    let mut first = true;
+    for (name, pattern) in categories {
+        let Some(pattern) = pattern else { continue };
+        if !first {
+            result.push(',');
+        }
+        first = false;
+        let text = if pattern.index == 255 {
+            pattern.pattern.to_string()
+        } else {
+            let index = pattern.index as usize;
+            format!(
+                "{}{{0}}{}",
+                &pattern.pattern[..index],
+                &pattern.pattern[index..]
+            )
+        };
+        let _ = write!(result, "\"{name}\":\"{}\"", json_escape(&text));
+    }
+    result.push('}');
+    result
+}
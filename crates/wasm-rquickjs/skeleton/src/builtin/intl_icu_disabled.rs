@@ -0,0 +1,76 @@
+//! Stand-ins for the ICU4X helpers when the `intl-full` feature is disabled.
+//!
+//! No locale data is baked in, so `languages()` is empty and `intl.js` keeps to its built-in
+//! en-US formatting without calling any of the other helpers.
+
+const DISABLED: &str = "Locale data is not available without the 'intl-full' feature";
+
+pub fn languages() -> Vec<String> {
+    Vec::new()
+}
+
+pub fn canonicalize(_tag: &str) -> Option<String> {
+    None
+}
+
+pub fn number_symbols(_locale: &str) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn currency_pattern(_locale: &str, _code: &str, _narrow: bool) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn plural_select(_locale: &str, _kind: &str, _value: &str) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn plural_select_range(
+    _locale: &str,
+    _kind: &str,
+    _start: &str,
+    _end: &str,
+) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn plural_categories(_locale: &str, _kind: &str) -> Result<Vec<String>, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn list_format(
+    _locale: &str,
+    _kind: &str,
+    _style: &str,
+    _items: &[String],
+) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn collator_compare(
+    _locale: &str,
+    _a: &str,
+    _b: &str,
+    _sensitivity: &str,
+    _numeric: bool,
+    _case_first: &str,
+    _ignore_punctuation: bool,
+) -> Result<i32, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn segment(_text: &str, _granularity: &str) -> String {
+    String::from("[]")
+}
+
+pub fn date_pattern(_locale: &str, _skeleton: &str) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn date_symbols(_locale: &str) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
+
+pub fn relative_time(_locale: &str, _style: &str, _unit: &str) -> Result<String, String> {
+    Err(DISABLED.to_string())
+}
@@ -2,13 +2,27 @@ use std::fmt::Write;
 
 mod abort_controller;
 mod assert;
-mod async_hooks;
+pub(crate) mod async_context;
+pub(crate) mod async_hooks;
 mod base64;
 mod buffer;
 mod child_process;
 mod cluster;
 mod console;
 mod constants;
+pub(crate) mod coverage;
+pub(crate) mod cpu_profiler;
+
+#[cfg(feature = "deterministic")]
+pub(crate) mod deterministic;
+
+#[cfg(not(feature = "deterministic"))]
+mod deterministic_disabled;
+#[cfg(not(feature = "deterministic"))]
+pub(crate) mod deterministic {
+    pub use super::deterministic_disabled::*;
+}
+
 mod dgram;
 mod diagnostics_channel;
 mod dns;
@@ -36,6 +50,17 @@ mod inspector;
 mod internal;
 mod internal_binding_util;
 mod intl;
+
+#[cfg(feature = "intl-full")]
+mod intl_icu;
+
+#[cfg(not(feature = "intl-full"))]
+mod intl_icu_disabled;
+#[cfg(not(feature = "intl-full"))]
+mod intl_icu {
+    pub use super::intl_icu_disabled::*;
+}
+
 mod module;
 mod net;
 mod socket_helpers;
@@ -53,7 +78,7 @@ mod node_http {
 mod node_test;
 mod os;
 mod path;
-mod perf_hooks;
+pub(crate) mod perf_hooks;
 mod process;
 mod punycode;
 mod querystring;
@@ -62,14 +87,20 @@ mod repl;
 mod stream;
 mod string_decoder;
 mod structured_clone;
+
+#[cfg(feature = "temporal")]
+mod temporal;
+
 mod timeout;
 mod timers;
 mod tls;
 mod trace_events;
 mod tty;
 mod url;
+mod url_pattern;
 mod util;
 mod v8;
+pub(crate) mod v8_heap_snapshot;
 mod vm;
 #[cfg(feature = "crypto")]
 mod web_crypto;
@@ -83,6 +114,15 @@ mod web_crypto {
 
 #[cfg(feature = "golem")]
 mod websocket;
+
+#[cfg(not(feature = "golem"))]
+mod websocket_tcp;
+#[cfg(not(feature = "golem"))]
+mod websocket {
+    pub use super::websocket_tcp::*;
+}
+
+mod websocket_protocol;
 mod webstreams;
 mod worker_threads;
 
@@ -151,6 +191,7 @@ pub fn add_module_resolvers(
         .with_module("node:child_process")
         .with_module("child_process")
         .with_module("node:test")
+        .with_module("node:test/reporters")
         .with_module("node:module")
         .with_module("module")
         .with_module("__wasm_rquickjs_builtin/process_native")
@@ -168,6 +209,8 @@ pub fn add_module_resolvers(
         .with_module("__wasm_rquickjs_builtin/url")
         .with_module("node:url")
         .with_module("url")
+        .with_module("__wasm_rquickjs_builtin/url_pattern_native")
+        .with_module("__wasm_rquickjs_builtin/url_pattern")
         .with_module("node:events")
         .with_module("events")
         .with_module("node:stream")
@@ -196,6 +239,10 @@ pub fn add_module_resolvers(
         .with_module("node:vm")
         .with_module("vm")
         .with_module("__wasm_rquickjs_builtin/structured_clone")
+        .with_module("__wasm_rquickjs_builtin/async_context_native")
+        .with_module("__wasm_rquickjs_builtin/async_context")
+        .with_module("__wasm_rquickjs_builtin/async_hooks_native")
+        .with_module("__wasm_rquickjs_builtin/async_hooks")
         .with_module("node:async_hooks")
         .with_module("async_hooks")
         .with_module("node:cluster")
@@ -208,6 +255,7 @@ pub fn add_module_resolvers(
         .with_module("node:diagnostics_channel")
         .with_module("diagnostics_channel")
         .with_module("__wasm_rquickjs_builtin/dns_native")
+        .with_module("__wasm_rquickjs_builtin/dns_resolver")
         .with_module("node:dns")
         .with_module("dns")
         .with_module("node:dns/promises")
@@ -218,10 +266,15 @@ pub fn add_module_resolvers(
         .with_module("http2")
         .with_module("node:https")
         .with_module("https")
+        .with_module("__wasm_rquickjs_builtin/coverage")
+        .with_module("__wasm_rquickjs_builtin/cpu_profiler_native")
+        .with_module("__wasm_rquickjs_builtin/cpu_profiler")
         .with_module("node:inspector")
         .with_module("inspector")
         .with_module("__wasm_rquickjs_builtin/node_http_native")
         .with_module("__wasm_rquickjs_builtin/node_http_server")
+        .with_module("__wasm_rquickjs_builtin/node_http_websocket_server")
+        .with_module("wasm-rquickjs:websocket-server")
         .with_module("node:_http_common")
         .with_module("_http_common")
         .with_module("node:_http_agent")
@@ -231,6 +284,7 @@ pub fn add_module_resolvers(
         .with_module("__wasm_rquickjs_builtin/net_native")
         .with_module("node:net")
         .with_module("net")
+        .with_module("__wasm_rquickjs_builtin/perf_hooks_native")
         .with_module("node:perf_hooks")
         .with_module("perf_hooks")
         .with_module("node:readline")
@@ -247,23 +301,39 @@ pub fn add_module_resolvers(
         .with_module("tls")
         .with_module("node:tty")
         .with_module("tty")
+        .with_module("__wasm_rquickjs_builtin/v8_native")
         .with_module("node:v8")
         .with_module("v8")
+        .with_module("__wasm_rquickjs_builtin/worker_threads_native")
         .with_module("node:worker_threads")
         .with_module("worker_threads")
         .with_module("__wasm_rquickjs_builtin/zlib_native")
+        .with_module("__wasm_rquickjs_builtin/compression_streams")
         .with_module("node:zlib")
         .with_module("zlib")
         // SQLite - only node:sqlite, no bare "sqlite" (matches Node.js behavior)
         .with_module("__wasm_rquickjs_builtin/sqlite_native")
-        .with_module("node:sqlite");
+        .with_module("node:sqlite")
+        .with_module("__wasm_rquickjs_builtin/websocket_protocol_native")
+        .with_module("__wasm_rquickjs_builtin/websocket_protocol")
+        .with_module("__wasm_rquickjs_builtin/websocket_connect")
+        .with_module("__wasm_rquickjs_builtin/websocket");
 
     #[cfg(feature = "golem")]
     let resolver = resolver
         .with_module("__wasm_rquickjs_builtin/diagnostics_channel_native")
         .with_module("__wasm_rquickjs_builtin/diagnostics_channel_golem")
-        .with_module("__wasm_rquickjs_builtin/websocket_native")
-        .with_module("__wasm_rquickjs_builtin/websocket");
+        .with_module("__wasm_rquickjs_builtin/websocket_native");
+
+    #[cfg(feature = "deterministic")]
+    let resolver = resolver
+        .with_module("__wasm_rquickjs_builtin/deterministic_native")
+        .with_module("__wasm_rquickjs_builtin/deterministic");
+
+    #[cfg(feature = "temporal")]
+    let resolver = resolver
+        .with_module("__wasm_rquickjs_builtin/temporal_native")
+        .with_module("__wasm_rquickjs_builtin/temporal");
 
     internal::add_to_resolver(resolver)
 }
@@ -310,11 +380,23 @@ pub fn module_loader() -> (
             internal_binding_util::js_native_module,
         )
         .with_module("__wasm_rquickjs_builtin/url_native", url::js_native_module)
+        .with_module(
+            "__wasm_rquickjs_builtin/url_pattern_native",
+            url_pattern::js_native_module,
+        )
         .with_module(
             "__wasm_rquickjs_builtin/web_crypto_native",
             web_crypto::js_native_module,
         )
         .with_module("__wasm_rquickjs_builtin/vm_native", vm::js_native_module)
+        .with_module(
+            "__wasm_rquickjs_builtin/async_context_native",
+            async_context::js_native_module,
+        )
+        .with_module(
+            "__wasm_rquickjs_builtin/async_hooks_native",
+            async_hooks::js_native_module,
+        )
         .with_module(
             "__wasm_rquickjs_builtin/zlib_native",
             zlib::js_native_module,
@@ -324,6 +406,15 @@ pub fn module_loader() -> (
             dgram::js_native_module,
         )
         .with_module("__wasm_rquickjs_builtin/dns_native", dns::js_native_module)
+        .with_module("__wasm_rquickjs_builtin/v8_native", v8::js_native_module)
+        .with_module(
+            "__wasm_rquickjs_builtin/cpu_profiler_native",
+            cpu_profiler::js_native_module,
+        )
+        .with_module(
+            "__wasm_rquickjs_builtin/perf_hooks_native",
+            perf_hooks::js_native_module,
+        )
         .with_module(
             "__wasm_rquickjs_builtin/node_http_native",
             node_http::js_native_module,
@@ -336,6 +427,14 @@ pub fn module_loader() -> (
         .with_module(
             "__wasm_rquickjs_builtin/string_decoder_native",
             string_decoder::js_native_module,
+        )
+        .with_module(
+            "__wasm_rquickjs_builtin/websocket_protocol_native",
+            websocket_protocol::js_native_module,
+        )
+        .with_module(
+            "__wasm_rquickjs_builtin/worker_threads_native",
+            worker_threads::js_native_module,
         );
 
     #[cfg(feature = "golem")]
@@ -349,6 +448,18 @@ pub fn module_loader() -> (
             websocket::js_native_module,
         );
 
+    #[cfg(feature = "deterministic")]
+    let native_loader = native_loader.with_module(
+        "__wasm_rquickjs_builtin/deterministic_native",
+        deterministic::js_native_module,
+    );
+
+    #[cfg(feature = "temporal")]
+    let native_loader = native_loader.with_module(
+        "__wasm_rquickjs_builtin/temporal_native",
+        temporal::js_native_module,
+    );
+
     let builtin_loader = rquickjs::loader::BuiltinLoader::default()
         .with_module(
             "__wasm_rquickjs_builtin/abort_controller",
@@ -392,6 +503,7 @@ pub fn module_loader() -> (
         .with_module("node:child_process", child_process::CHILD_PROCESS_JS)
         .with_module("child_process", child_process::REEXPORT_JS)
         .with_module("node:test", node_test::TEST_JS)
+        .with_module("node:test/reporters", node_test::TEST_REPORTERS_JS)
         .with_module("node:module", module::MODULE_JS)
         .with_module("module", module::REEXPORT_JS)
         .with_module("node:process", process::PROCESS_JS)
@@ -407,6 +519,10 @@ pub fn module_loader() -> (
         .with_module("__wasm_rquickjs_builtin/url", url::URL_JS)
         .with_module("node:url", url::URL_JS)
         .with_module("url", url::REEXPORT_JS)
+        .with_module(
+            "__wasm_rquickjs_builtin/url_pattern",
+            url_pattern::URL_PATTERN_JS,
+        )
         .with_module("node:events", events::EVENTS_JS)
         .with_module("events", events::REEXPORT_JS)
         .with_module("node:stream", stream::STREAM_JS)
@@ -434,7 +550,15 @@ pub fn module_loader() -> (
             "__wasm_rquickjs_builtin/structured_clone",
             structured_clone::STRUCTURED_CLONE_JS,
         )
-        .with_module("node:async_hooks", async_hooks::ASYNC_HOOKS_JS)
+        .with_module(
+            "__wasm_rquickjs_builtin/async_context",
+            async_context::ASYNC_CONTEXT_JS,
+        )
+        .with_module(
+            "__wasm_rquickjs_builtin/async_hooks",
+            async_hooks::ASYNC_HOOKS_JS,
+        )
+        .with_module("node:async_hooks", async_hooks::NODE_ASYNC_HOOKS_JS)
         .with_module("async_hooks", async_hooks::REEXPORT_JS)
         .with_module("node:cluster", cluster::CLUSTER_JS)
         .with_module("cluster", cluster::REEXPORT_JS)
@@ -447,6 +571,7 @@ pub fn module_loader() -> (
             diagnostics_channel::DIAGNOSTICS_CHANNEL_JS,
         )
         .with_module("diagnostics_channel", diagnostics_channel::REEXPORT_JS)
+        .with_module("__wasm_rquickjs_builtin/dns_resolver", dns::DNS_RESOLVER_JS)
         .with_module("node:dns", dns::DNS_JS)
         .with_module("dns", dns::REEXPORT_JS)
         .with_module("node:dns/promises", dns::DNS_PROMISES_JS)
@@ -457,6 +582,14 @@ pub fn module_loader() -> (
             "__wasm_rquickjs_builtin/node_http_server",
             node_http::NODE_HTTP_SERVER_JS,
         )
+        .with_module(
+            "__wasm_rquickjs_builtin/node_http_websocket_server",
+            node_http::NODE_HTTP_WEBSOCKET_SERVER_JS,
+        )
+        .with_module(
+            "wasm-rquickjs:websocket-server",
+            node_http::WEBSOCKET_SERVER_REEXPORT_JS,
+        )
         .with_module("node:_http_common", node_http::HTTP_COMMON_JS)
         .with_module("_http_common", node_http::HTTP_COMMON_JS)
         .with_module("node:_http_agent", node_http::HTTP_AGENT_JS)
@@ -467,6 +600,11 @@ pub fn module_loader() -> (
         .with_module("http2", http2::REEXPORT_JS)
         .with_module("node:https", https::HTTPS_JS)
         .with_module("https", https::REEXPORT_JS)
+        .with_module("__wasm_rquickjs_builtin/coverage", coverage::COVERAGE_JS)
+        .with_module(
+            "__wasm_rquickjs_builtin/cpu_profiler",
+            cpu_profiler::CPU_PROFILER_JS,
+        )
         .with_module("node:inspector", inspector::INSPECTOR_JS)
         .with_module("inspector", inspector::REEXPORT_JS)
         .with_module("node:net", net::NET_JS)
@@ -492,22 +630,48 @@ pub fn module_loader() -> (
         .with_module("node:worker_threads", worker_threads::WORKER_THREADS_JS)
         .with_module("worker_threads", worker_threads::REEXPORT_JS)
         .with_module("node:zlib", zlib::ZLIB_JS)
+        .with_module(
+            "__wasm_rquickjs_builtin/compression_streams",
+            zlib::COMPRESSION_STREAMS_JS,
+        )
         .with_module("zlib", zlib::REEXPORT_JS)
-        .with_module("node:sqlite", sqlite::SQLITE_JS);
+        .with_module("node:sqlite", sqlite::SQLITE_JS)
+        .with_module(
+            "__wasm_rquickjs_builtin/websocket_protocol",
+            websocket_protocol::WEBSOCKET_PROTOCOL_JS,
+        )
+        .with_module(
+            "__wasm_rquickjs_builtin/websocket_connect",
+            websocket::WEBSOCKET_CONNECT_JS,
+        )
+        .with_module("__wasm_rquickjs_builtin/websocket", websocket::WEBSOCKET_JS);
 
     #[cfg(feature = "golem")]
     let builtin_loader = builtin_loader
         .with_module(
             "__wasm_rquickjs_builtin/diagnostics_channel_golem",
             diagnostics_channel::DIAGNOSTICS_CHANNEL_GOLEM_JS,
-        )
-        .with_module("__wasm_rquickjs_builtin/websocket", websocket::WEBSOCKET_JS);
+        );
+
+    #[cfg(feature = "deterministic")]
+    let builtin_loader = builtin_loader.with_module(
+        "__wasm_rquickjs_builtin/deterministic",
+        deterministic::DETERMINISTIC_JS,
+    );
+
+    #[cfg(feature = "temporal")]
+    let builtin_loader =
+        builtin_loader.with_module("__wasm_rquickjs_builtin/temporal", temporal::TEMPORAL_JS);
 
     (native_loader, builtin_loader, internal::module_loader())
 }
 
 pub fn wire_builtins() -> String {
     let mut result = String::new();
+
+    #[cfg(feature = "deterministic")]
+    writeln!(result, "{}", deterministic::WIRE_JS).unwrap();
+
     writeln!(result, "{}", events::WIRE_JS).unwrap();
     writeln!(result, "{}", abort_controller::WIRE_JS).unwrap();
     writeln!(result, "{}", base64::WIRE_JS).unwrap();
@@ -517,9 +681,13 @@ pub fn wire_builtins() -> String {
     writeln!(result, "{}", gc::WIRE_JS).unwrap();
     writeln!(result, "{}", http::WIRE_JS).unwrap();
     writeln!(result, "{}", webstreams::WIRE_JS).unwrap();
+    writeln!(result, "{}", zlib::WIRE_JS).unwrap();
     writeln!(result, "{}", encoding::WIRE_JS).unwrap();
     writeln!(result, "{}", intl::WIRE_JS).unwrap();
+    #[cfg(feature = "temporal")]
+    writeln!(result, "{}", temporal::WIRE_JS).unwrap();
     writeln!(result, "{}", url::WIRE_JS).unwrap();
+    writeln!(result, "{}", url_pattern::WIRE_JS).unwrap();
     writeln!(result, "{}", web_crypto::WIRE_JS).unwrap();
     writeln!(result, "{}", process::WIRE_JS).unwrap();
     writeln!(result, "{}", structured_clone::WIRE_JS).unwrap();
@@ -533,7 +701,6 @@ pub fn wire_builtins() -> String {
     #[cfg(feature = "golem")]
     writeln!(result, "{}", diagnostics_channel::GOLEM_WIRE_JS).unwrap();
 
-    #[cfg(feature = "golem")]
     writeln!(result, "{}", websocket::WIRE_JS).unwrap();
 
     result
@@ -48,6 +48,7 @@ import * as v8 from 'node:v8';
 import * as worker_threads from 'node:worker_threads';
 import * as zlib from 'node:zlib';
 import * as sqlite from 'node:sqlite';
+import * as testReporters from 'node:test/reporters';
 import * as internalHttp from '__wasm_rquickjs_builtin/internal/http';
 import { ERR_INVALID_ARG_TYPE } from '__wasm_rquickjs_builtin/internal/errors';
 import * as internalErrors from '__wasm_rquickjs_builtin/internal/errors';
@@ -131,6 +132,7 @@ const v8Cjs = cjsExport(v8);
 const workerThreadsCjs = cjsExport(worker_threads);
 const zlibCjs = cjsExport(zlib);
 const sqliteCjs = cjsExport(sqlite);
+const testReportersCjs = cjsExport(testReporters);
 const internalHttpCjs = cjsExport(internalHttp);
 const internalFsUtilsCjs = cjsExport(internalFsUtils);
 const internalUrlCjs = cjsExport(internalUrl);
@@ -241,6 +243,7 @@ registerBuiltin(builtinModuleMap, 'v8', v8Cjs);
 registerBuiltin(builtinModuleMap, 'worker_threads', workerThreadsCjs);
 registerBuiltin(builtinModuleMap, 'zlib', zlibCjs);
 builtinModuleMap['node:sqlite'] = sqliteCjs;
+builtinModuleMap['node:test/reporters'] = testReportersCjs;
 registerBuiltin(builtinModuleMap, 'util/types', utilTypes);
 builtinModuleMap['_stream_readable'] = streamCjs && streamCjs.Readable;
 builtinModuleMap['_stream_writable'] = streamCjs && streamCjs.Writable;
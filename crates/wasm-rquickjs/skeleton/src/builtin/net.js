@@ -14,6 +14,7 @@ import {
     ERR_SOCKET_BAD_PORT,
 } from '__wasm_rquickjs_builtin/internal/errors';
 import { validateAbortSignal } from '__wasm_rquickjs_builtin/internal/validators';
+import { _emitDestroy, _emitInit, _runInAsyncScope, executionAsyncId } from '__wasm_rquickjs_builtin/async_hooks';
 
 const customInspectSymbol = Symbol.for('nodejs.util.inspect.custom');
 const structuredCloneSymbol = Symbol.for('__wasm_rquickjs.structuredClone');
@@ -112,13 +113,26 @@ function deferred(fn) {
     }
 }
 
+// The handle of a socket is its TCPWRAP async resource, running the callbacks of its events
 function createHandleWrap() {
-    return {
+    const triggerAsyncId = executionAsyncId();
+    const wrap = {
+        triggerAsyncId,
+        asyncId: 0,
+        getAsyncId() {
+            return this.asyncId;
+        },
         setKeepAlive() {},
         set_keep_alive() {},
         set_no_delay() {},
         close() {},
     };
+    wrap.asyncId = _emitInit('TCPWRAP', wrap, triggerAsyncId);
+    return wrap;
+}
+
+function runInHandleScope(handle, fn) {
+    return _runInAsyncScope(handle.asyncId, handle.triggerAsyncId, handle, fn);
 }
 
 function forwardNativeHandle(wrap, handle) {
@@ -730,8 +744,9 @@ Socket.prototype.connect = function connect(...args) {
         })();
     };
 
+    const handleWrap = this._handle;
     const doConnect = (ip, addressFamily) => {
-        connectAttempt(ip, addressFamily, (err, handle) => {
+        connectAttempt(ip, addressFamily, (err, handle) => runInHandleScope(handleWrap, () => {
             if (!this.connecting || this.destroyed) {
                 if (handle) {
                     try {
@@ -748,7 +763,7 @@ Socket.prototype.connect = function connect(...args) {
             }
 
             completeConnection(handle);
-        });
+        }));
     };
 
     const normalizeLookupEntries = (address, resolvedFamily) => {
@@ -1000,6 +1015,33 @@ Socket.prototype._drainPendingReadChunks = function _drainPendingReadChunks() {
     }
 };
 
+// Pushes a chunk read from the handle, returning whether to keep reading
+Socket.prototype._onReadChunk = function _onReadChunk(chunk) {
+    if (chunk === null || chunk === undefined) {
+        if (this._netPaused) {
+            this._pendingReadChunks.push(null);
+        } else {
+            this.push(null);
+            this.read(0);
+        }
+        return false;
+    }
+    this.bytesRead += chunk.length;
+    this._resetTimeout();
+    const buffer = Buffer.from(chunk);
+    if (this._netPaused) {
+        this._pendingReadChunks.push(buffer);
+        this._reading = false;
+        return false;
+    }
+    const keepGoing = this.push(buffer);
+    if (!keepGoing) {
+        this._reading = false;
+        return false;
+    }
+    return true;
+};
+
 Socket.prototype._startPollLoop = function _startPollLoop() {
     const token = ++this._readToken;
     (async () => {
@@ -1009,28 +1051,7 @@ Socket.prototype._startPollLoop = function _startPollLoop() {
                 const chunk = await this._handle.read(16384);
                 this._readInFlight = false;
                 if (token !== this._readToken) break;
-                if (chunk === null || chunk === undefined) {
-                    if (this._netPaused) {
-                        this._pendingReadChunks.push(null);
-                    } else {
-                        this.push(null);
-                        this.read(0);
-                    }
-                    break;
-                }
-                this.bytesRead += chunk.length;
-                this._resetTimeout();
-                const buffer = Buffer.from(chunk);
-                if (this._netPaused) {
-                    this._pendingReadChunks.push(buffer);
-                    this._reading = false;
-                    break;
-                }
-                const keepGoing = this.push(buffer);
-                if (!keepGoing) {
-                    this._reading = false;
-                    break;
-                }
+                if (!runInHandleScope(this._handle, () => this._onReadChunk(chunk))) break;
             } catch (e) {
                 this._readInFlight = false;
                 if (token !== this._readToken) break;
@@ -1099,6 +1120,7 @@ Socket.prototype._destroy = function _destroy(err, callback) {
     }
     if (this._handle) {
         this._handle.close();
+        _emitDestroy(this._handle.asyncId);
         this._handle = null;
     }
     this.connecting = false;
@@ -1300,6 +1322,7 @@ Server.prototype.listen = function listen(...args) {
         const doIpcListen = (ip, family) => {
             try {
                 this._handle = create_tcp_listener(family);
+                this._initHandleAsyncResource();
                 this._handle.bind_sync(ip, 0);
                 this._handle.set_backlog(ipcBacklog);
                 this._handle.listen_sync();
@@ -1361,6 +1384,7 @@ Server.prototype.listen = function listen(...args) {
     const doListen = (ip, family) => {
         try {
             this._handle = create_tcp_listener(family);
+            this._initHandleAsyncResource();
             this._handle.bind_sync(ip, port);
             this._handle.set_backlog(backlog);
             this._handle.listen_sync();
@@ -1393,9 +1417,17 @@ Server.prototype.listen = function listen(...args) {
     return this;
 };
 
+// The listening handle of a server is its TCPSERVERWRAP async resource, running the callbacks of
+// its connections
+Server.prototype._initHandleAsyncResource = function _initHandleAsyncResource() {
+    this._triggerAsyncId = executionAsyncId();
+    this._asyncId = _emitInit('TCPSERVERWRAP', this._handle, this._triggerAsyncId);
+};
+
 Server.prototype._closeHandle = function _closeHandle() {
     if (!this._handle) return;
     this._handle.close();
+    _emitDestroy(this._asyncId);
     this._handle = null;
 };
 
@@ -1436,6 +1468,58 @@ Server.prototype._wakeAcceptLoop = function _wakeAcceptLoop() {
     })();
 };
 
+// Sets up an accepted connection and emits it
+Server.prototype._onConnection = function _onConnection(clientHandle, addr, port, family) {
+    if (this.maxConnections && this._connections >= this.maxConnections) {
+        clientHandle.close();
+        this.emit('drop', {
+            localAddress: this._localAddress,
+            localPort: this._localPort,
+            localFamily: this._localFamily,
+            remoteAddress: addr,
+            remotePort: port,
+            remoteFamily: family,
+        });
+        return;
+    }
+
+    const socket = new Socket({ allowHalfOpen: this.allowHalfOpen });
+    socket._handle = createHandleWrap();
+    forwardNativeHandle(socket._handle, clientHandle);
+    socket.server = this;
+    socket.connecting = false;
+    socket.readable = true;
+    socket.writable = true;
+    socket.remoteAddress = addr;
+    socket.remotePort = port;
+    socket.remoteFamily = family;
+    try {
+        const [la, lp, lf] = clientHandle.local_address();
+        socket.localAddress = la;
+        socket.localPort = lp;
+        socket.localFamily = lf;
+    } catch (_) {}
+
+    if (this._noDelay) socket.setNoDelay(true);
+    if (this._keepAlive) socket.setKeepAlive(true, this._keepAliveInitialDelay);
+    if (this._pauseOnConnect) socket.pause();
+
+    this._connections++;
+    socket.on('close', () => {
+        this._connections--;
+        if (this._unrefed && this._connections === 0 && this.listening) {
+            this.close();
+        }
+        this._maybeEmitClose();
+    });
+
+    this.emit('connection', socket);
+
+    if (!this._pauseOnConnect) {
+        socket.read(0);
+    }
+};
+
 Server.prototype._acceptLoop = function _acceptLoop() {
     const token = ++this._acceptToken;
     this._acceptLoopActive = true;
@@ -1445,55 +1529,7 @@ Server.prototype._acceptLoop = function _acceptLoop() {
                 try {
                     const [clientHandle, addr, port, family] = await this._handle.accept();
                     if (token !== this._acceptToken) { clientHandle.close(); break; }
-
-                    if (this.maxConnections && this._connections >= this.maxConnections) {
-                        clientHandle.close();
-                        this.emit('drop', {
-                            localAddress: this._localAddress,
-                            localPort: this._localPort,
-                            localFamily: this._localFamily,
-                            remoteAddress: addr,
-                            remotePort: port,
-                            remoteFamily: family,
-                        });
-                        continue;
-                    }
-
-                    const socket = new Socket({ allowHalfOpen: this.allowHalfOpen });
-                    socket._handle = createHandleWrap();
-                    forwardNativeHandle(socket._handle, clientHandle);
-                    socket.server = this;
-                    socket.connecting = false;
-                    socket.readable = true;
-                    socket.writable = true;
-                    socket.remoteAddress = addr;
-                    socket.remotePort = port;
-                    socket.remoteFamily = family;
-                    try {
-                        const [la, lp, lf] = clientHandle.local_address();
-                        socket.localAddress = la;
-                        socket.localPort = lp;
-                        socket.localFamily = lf;
-                    } catch (_) {}
-
-                    if (this._noDelay) socket.setNoDelay(true);
-                    if (this._keepAlive) socket.setKeepAlive(true, this._keepAliveInitialDelay);
-                    if (this._pauseOnConnect) socket.pause();
-
-                    this._connections++;
-                    socket.on('close', () => {
-                        this._connections--;
-                        if (this._unrefed && this._connections === 0 && this.listening) {
-                            this.close();
-                        }
-                        this._maybeEmitClose();
-                    });
-
-                    this.emit('connection', socket);
-
-                    if (!this._pauseOnConnect) {
-                        socket.read(0);
-                    }
+                    _runInAsyncScope(this._asyncId, this._triggerAsyncId, this._handle, this._onConnection, this, [clientHandle, addr, port, family]);
                 } catch (e) {
                     if (token !== this._acceptToken) break;
                     this.emit('error', parseNativeError(e));
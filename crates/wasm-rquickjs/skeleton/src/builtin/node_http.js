@@ -650,7 +650,9 @@ function parseRawHttpResponse(raw) {
     return { httpVersion, statusCode, statusMessage, headers, body };
 }
 
-function _readHttpResponseFromSocket(socket) {
+// Reads the final response; interim 1xx responses (other than 101) are passed to
+// `onInformation` as they arrive
+function _readHttpResponseFromSocket(socket, onInformation) {
     return new Promise((resolve, reject) => {
         let buffer = '';
         let headersParsed = false;
@@ -702,6 +704,16 @@ function _readHttpResponseFromSocket(socket) {
                     statusMessage: match[3] || '',
                     headers,
                 };
+
+                if (parsedResult.statusCode >= 100 && parsedResult.statusCode < 200 && parsedResult.statusCode !== 101) {
+                    buffer = buffer.substring(headerEndPos + 4);
+                    contentLength = -1;
+                    isChunked = false;
+                    if (onInformation) {
+                        onInformation(parsedResult);
+                    }
+                    return tryComplete();
+                }
                 headersParsed = true;
             }
 
@@ -1824,6 +1836,18 @@ export class ClientRequest extends OutgoingMessage {
         this._refreshHeaderString();
     }
 
+    _emitInformation(info) {
+        if (info.statusCode === 100) {
+            this.emit('continue');
+        }
+        const parsedHeaders = parseIncomingHeaders(info.headers, this._joinDuplicateHeaders);
+        const information = { statusCode: info.statusCode, statusMessage: info.statusMessage };
+        applyHttpVersion(information, info.httpVersion);
+        information.headers = parsedHeaders.headers;
+        information.rawHeaders = parsedHeaders.rawHeaders;
+        this.emit('information', information);
+    }
+
     _emitCloseOnce() {
         if (this._closeEmitted) {
             return;
@@ -2478,7 +2502,7 @@ export class ClientRequest extends OutgoingMessage {
             const isConnect = this.method === 'CONNECT';
             const parsed = isConnect
                 ? await _readConnectResponseHeaders(socket)
-                : await _readHttpResponseFromSocket(socket);
+                : await _readHttpResponseFromSocket(socket, (info) => this._emitInformation(info));
 
             if (this.aborted || this.destroyed) {
                 return;
@@ -749,6 +749,9 @@ async fn write_all_to_stream<'js>(
 
 pub const NODE_HTTP_JS: &str = include_str!("node_http.js");
 pub const NODE_HTTP_SERVER_JS: &str = include_str!("node_http_server.js");
+pub const NODE_HTTP_WEBSOCKET_SERVER_JS: &str = include_str!("node_http_websocket_server.js");
+// Not part of Node.js, so it is exposed from its own module instead of `node:http`
+pub const WEBSOCKET_SERVER_REEXPORT_JS: &str = r#"export * from '__wasm_rquickjs_builtin/node_http_websocket_server'; export { default } from '__wasm_rquickjs_builtin/node_http_websocket_server';"#;
 pub const HTTP_COMMON_JS: &str = include_str!("node_http_common.js");
 pub const HTTP_AGENT_JS: &str = include_str!("node_http_agent.js");
 pub const REEXPORT_JS: &str = r#"export * from 'node:http'; export { default } from 'node:http';"#;
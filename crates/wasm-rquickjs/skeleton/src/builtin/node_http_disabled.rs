@@ -12,6 +12,14 @@ export const createServer = notAvailable;
 export default { createServer };
 "#;
 
+pub const NODE_HTTP_WEBSOCKET_SERVER_JS: &str = r#"
+const msg = 'WebSocketServer is not available (node-http feature is not enabled)';
+export class WebSocketServer { constructor() { throw new Error(msg); } }
+export default { WebSocketServer };
+"#;
+
+pub const WEBSOCKET_SERVER_REEXPORT_JS: &str = r#"export * from '__wasm_rquickjs_builtin/node_http_websocket_server'; export { default } from '__wasm_rquickjs_builtin/node_http_websocket_server';"#;
+
 pub const HTTP_AGENT_JS: &str = r#"
 import { Agent } from 'node:http';
 export { Agent };
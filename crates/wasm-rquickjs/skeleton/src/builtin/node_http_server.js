@@ -3,7 +3,7 @@ import { Server as NetServer } from 'node:net';
 import { EventEmitter } from 'node:events';
 import { Buffer } from 'node:buffer';
 import Readable from '__wasm_rquickjs_builtin/internal/streams/readable';
-import { ERR_HTTP_BODY_NOT_ALLOWED, ERR_HTTP_CONTENT_LENGTH_MISMATCH, ERR_HTTP_HEADERS_SENT, ERR_HTTP_SOCKET_ASSIGNED, ERR_INVALID_ARG_TYPE, ERR_INVALID_ARG_VALUE } from '__wasm_rquickjs_builtin/internal/errors';
+import { ERR_HTTP_BODY_NOT_ALLOWED, ERR_HTTP_CONTENT_LENGTH_MISMATCH, ERR_HTTP_HEADERS_SENT, ERR_HTTP_REQUEST_TIMEOUT, ERR_HTTP_SOCKET_ASSIGNED, ERR_INVALID_ARG_TYPE, ERR_INVALID_ARG_VALUE } from '__wasm_rquickjs_builtin/internal/errors';
 // STATUS_CODES is duplicated here to avoid circular dependency with node:http
 const STATUS_CODES = {
     100: 'Continue', 101: 'Switching Protocols', 102: 'Processing', 103: 'Early Hints',
@@ -38,6 +38,8 @@ const AWAITING_RESPONSE = 4;
 const CRLF = Buffer.from('\r\n');
 const HEADER_END = Buffer.from('\r\n\r\n');
 
+const CONTINUE_EXPECTATION = /(?:^|\W)100-continue(?:$|\W)/i;
+
 // ===== Header helpers =====
 
 const COMMA_JOIN_HEADERS = new Set([
@@ -139,6 +141,7 @@ function ServerIncomingMessage(socket, method, url, httpVersion, rawHeaderPairs,
 
     this.complete = false;
     this.aborted = false;
+    this.upgrade = false;
     this.trailers = {};
     this.trailersDistinct = {};
     this._timeout = null;
@@ -154,6 +157,7 @@ ServerIncomingMessage.prototype._read = function _read() {
 ServerIncomingMessage.prototype.setTimeout = function setTimeout(ms, cb) {
     this._timeout = ms;
     if (cb) this.once('timeout', cb);
+    this.socket.setTimeout(ms);
     return this;
 };
 
@@ -218,6 +222,9 @@ function ServerResponse(req, options) {
     this._keepAlive = false;
     this._keepAliveTimeout = 5000;
     this._keepAliveMaxRequests = 0;
+    this._expectContinue = false;
+    this._sent100 = false;
+    this._timeout = null;
     this._sentContentLength = false;
     this._headersSentWire = false;
     this._rejectNonStandardBodyWrites = !!(options && options.rejectNonStandardBodyWrites);
@@ -552,6 +559,11 @@ ServerResponse.prototype._buildHeaderString = function _buildHeaderString() {
         : [];
     const userSaysClose = userConnectionTokens.includes('close');
     const userSaysKeepAlive = userConnectionTokens.includes('keep-alive');
+    // A client expecting 100-continue that did not get it may or may not send the
+    // body, so the connection cannot be reused (matches Node.js)
+    if (this._expectContinue && !this._sent100) {
+        this._keepAlive = false;
+    }
     const canKeepAlive = !!this._keepAlive;
     // The user may narrow a keep-alive response to close, but must not widen
     // a close response to keep-alive: header semantics must match the actual
@@ -564,7 +576,7 @@ ServerResponse.prototype._buildHeaderString = function _buildHeaderString() {
         head += 'Connection: ' + (effectiveKeepAlive ? 'keep-alive' : 'close') + '\r\n';
     }
 
-    if (effectiveKeepAlive && this.getHeader('keep-alive') === undefined) {
+    if (effectiveKeepAlive && this._keepAliveTimeout !== 0 && this.getHeader('keep-alive') === undefined) {
         const timeoutMs = typeof this._keepAliveTimeout === 'number' && this._keepAliveTimeout >= 0
             ? this._keepAliveTimeout
             : 5000;
@@ -705,7 +717,7 @@ ServerResponse.prototype.end = function end(data, encoding, cb) {
         } else {
             // writeHead() was called or explicit CL/TE set: send headers separately,
             // letting chunked encoding handle the body (matches Node.js behavior).
-            if (!data && !this.hasHeader('content-length') && !this.hasHeader('transfer-encoding')) {
+            if (!data && this.statusCode !== 101 && !this.hasHeader('content-length') && !this.hasHeader('transfer-encoding')) {
                 this.setHeader('Content-Length', 0);
             }
             this._sendHeaders();
@@ -775,12 +787,13 @@ ServerResponse.prototype._writeRaw = function _writeRaw(data, encoding, callback
     return this.socket.write(chunk);
 };
 
-ServerResponse.prototype.writeContinue = function writeContinue() {
-    this.socket.write(Buffer.from('HTTP/1.1 100 Continue\r\n\r\n'));
+ServerResponse.prototype.writeContinue = function writeContinue(cb) {
+    this._writeRaw('HTTP/1.1 100 Continue\r\n\r\n', 'latin1', cb);
+    this._sent100 = true;
 };
 
-ServerResponse.prototype.writeProcessing = function writeProcessing() {
-    this.socket.write(Buffer.from('HTTP/1.1 102 Processing\r\n\r\n'));
+ServerResponse.prototype.writeProcessing = function writeProcessing(cb) {
+    this._writeRaw('HTTP/1.1 102 Processing\r\n\r\n', 'latin1', cb);
 };
 
 const LINK_HEADER_REGEX = /^<[^>]*>(\s*;\s*[^;]+)*$/;
@@ -849,7 +862,9 @@ ServerResponse.prototype.addTrailers = function addTrailers() {
 };
 
 ServerResponse.prototype.setTimeout = function setTimeout(ms, cb) {
+    this._timeout = ms;
     if (cb) this.once('timeout', cb);
+    if (this.socket) this.socket.setTimeout(ms);
     return this;
 };
 
@@ -892,18 +907,53 @@ function createConnectionParser(server, socket) {
         shouldKeepAliveAfterResponse: false,
         requestsServed: 0,
         detached: false,
+        // When the first byte of the request being received arrived (0 if none is), and
+        // whether its headers are complete; checked against headersTimeout / requestTimeout
+        requestStart: 0,
+        headersComplete: false,
+        requestTimedOut: onRequestTimeout,
     };
 
     const keepAlive = computeKeepAlive(null, '1.1');
 
-    // Install a single timeout handler for idle keep-alive connections
+    // Install a single timeout handler for idle keep-alive connections and inactive
+    // requests; like Node.js the socket is destroyed unless someone handles the timeout
     socket.on('timeout', function onIdleTimeout() {
-        const handled = server.emit('timeout', socket);
-        if (!handled) {
+        if (state.detached) return;
+        const req = state.req;
+        const reqHandled = req !== null && !req.complete && req.emit('timeout', socket);
+        const resHandled = state.res !== null && state.res.emit('timeout', socket);
+        const serverHandled = server.emit('timeout', socket);
+        if (!reqHandled && !resHandled && !serverHandled) {
             socket.destroy();
         }
     });
 
+    // The inactivity timeout while a request is in progress: the one set through
+    // req.setTimeout() / res.setTimeout(), or else server.timeout
+    function activeRequestTimeout() {
+        if (state.res._timeout !== null) return state.res._timeout;
+        if (state.req !== null && state.req._timeout !== null) return state.req._timeout;
+        return server.timeout || 0;
+    }
+
+    function onRequestTimeout() {
+        state.requestStart = 0;
+        const err = new ERR_HTTP_REQUEST_TIMEOUT();
+        if (!server.emit('clientError', err, socket)) {
+            if (socket.writable && !(state.res !== null && state.res.headersSent)) {
+                socket.write(Buffer.from('HTTP/1.1 408 Request Timeout\r\nConnection: close\r\n\r\n'));
+            }
+            socket.destroySoon();
+        }
+    }
+
+    // Called once the current request (headers and body) has been fully received
+    function requestReceived() {
+        state.headersComplete = false;
+        state.requestStart = state.buffer.length > 0 ? Date.now() : 0;
+    }
+
     socket.on('data', function onData(data) {
         if (state.detached) return;
 
@@ -916,13 +966,16 @@ function createConnectionParser(server, socket) {
         // Clear idle keep-alive timeout on new data
         socket.setTimeout(0);
 
+        if (state.requestStart === 0) {
+            state.requestStart = Date.now();
+        }
         state.buffer = Buffer.concat([state.buffer, data]);
         parseLoop();
 
         // Track active request inactivity via server.setTimeout().
         // Keep-alive idle timeout remains managed after responses finish.
         if (state.res && !state.responseFinished) {
-            socket.setTimeout(server.timeout || 0);
+            socket.setTimeout(activeRequestTimeout());
         }
     });
 
@@ -993,6 +1046,19 @@ function createConnectionParser(server, socket) {
         }
     });
 
+    // Hands the socket over to a 'connect' / 'upgrade' listener, or to a request handler
+    // that answered with 101 Switching Protocols. The socket stops being an HTTP connection
+    // of the server; data arriving before the new owner starts reading stays buffered.
+    function detachSocket() {
+        state.detached = true;
+        state.req = null;
+        state.res = null;
+        state.requestStart = 0;
+        server._httpConnections.delete(state);
+        socket.setTimeout(0);
+        socket.readableFlowing = null;
+    }
+
     function maybeFinalizeResponse() {
         if (!state.responseFinished) {
             return false;
@@ -1034,11 +1100,32 @@ function createConnectionParser(server, socket) {
             return true;
         }
 
-        // Set idle timeout for keep-alive connections
-        socket.setTimeout(server.keepAliveTimeout || 5000);
+        // Set idle timeout for keep-alive connections (0 disables it)
+        socket.setTimeout(server.keepAliveTimeout);
         return true;
     }
 
+    // Emits a parsed request, answering an `Expect` header first like Node.js does
+    function dispatchRequest(req, res) {
+        const expect = req.headers.expect;
+        if (expect === undefined || req.httpVersion !== '1.1') {
+            server.emit('request', req, res);
+        } else if (CONTINUE_EXPECTATION.test(expect)) {
+            res._expectContinue = true;
+            if (server.listenerCount('checkContinue') > 0) {
+                server.emit('checkContinue', req, res);
+            } else {
+                res.writeContinue();
+                server.emit('request', req, res);
+            }
+        } else if (server.listenerCount('checkExpectation') > 0) {
+            server.emit('checkExpectation', req, res);
+        } else {
+            res.writeHead(417);
+            res.end();
+        }
+    }
+
     function parseLoop() {
         let progress = true;
         while (progress) {
@@ -1052,6 +1139,7 @@ function createConnectionParser(server, socket) {
                 const headerBlock = state.buffer.slice(0, idx).toString('utf8');
                 state.buffer = state.buffer.slice(idx + 4);
 
+                state.headersComplete = true;
                 const parsed = parseRequestHeaders(headerBlock);
                 if (!parsed) {
                     server.emit('clientError', new Error('HPE_INVALID_REQUEST'), socket);
@@ -1073,10 +1161,9 @@ function createConnectionParser(server, socket) {
                 if (parsed.method === 'CONNECT') {
                     const head = state.buffer.length > 0 ? Buffer.from(state.buffer) : Buffer.alloc(0);
                     state.buffer = Buffer.alloc(0);
-                    state.detached = true;
-                    state.req = null;
-                    state.res = null;
+                    detachSocket();
                     req.complete = true;
+                    req.upgrade = true;
 
                     if (server.listenerCount('connect') > 0) {
                         server.emit('connect', req, socket, head);
@@ -1087,16 +1174,19 @@ function createConnectionParser(server, socket) {
                     return;
                 }
 
-                // Upgrade request: emit 'upgrade' event before host header check
+                // Upgrade request: emit 'upgrade' event before host header check.
+                // Like llhttp, a request only asks for an upgrade if it has both an Upgrade
+                // header and the 'upgrade' Connection option.
                 const connHeader = req.headers.connection;
-                const isUpgrade = connHeader && connHeader.toLowerCase().split(',').some(t => t.trim() === 'upgrade');
+                const isUpgrade = req.headers.upgrade !== undefined &&
+                    connHeader !== undefined &&
+                    connHeader.toLowerCase().split(',').some(t => t.trim() === 'upgrade');
                 if (isUpgrade && server.listenerCount('upgrade') > 0) {
                     const head = state.buffer.length > 0 ? Buffer.from(state.buffer) : Buffer.alloc(0);
                     state.buffer = Buffer.alloc(0);
-                    state.detached = true;
-                    state.req = null;
-                    state.res = null;
+                    detachSocket();
                     req.complete = true;
+                    req.upgrade = true;
                     server.emit('upgrade', req, socket, head);
                     return;
                 }
@@ -1133,6 +1223,17 @@ function createConnectionParser(server, socket) {
 
                 // Set up finish handler for request sequencing
                 res.on('finish', function onFinish() {
+                    if (res.statusCode === 101 && isUpgrade) {
+                        // The request handler switched protocols; whatever follows the
+                        // request on the connection belongs to the new protocol
+                        const rest = state.buffer;
+                        state.buffer = Buffer.alloc(0);
+                        detachSocket();
+                        if (rest.length > 0) {
+                            socket.unshift(rest);
+                        }
+                        return;
+                    }
                     state.responseFinished = true;
                     state.shouldKeepAliveAfterResponse = res._keepAlive && !server._closeRequested;
                     maybeFinalizeResponse();
@@ -1168,11 +1269,13 @@ function createConnectionParser(server, socket) {
                     req.complete = true;
                     requestHasNoBody = true;
                     // Keep parsing pipelined requests even if earlier responses
-                    // have not finished yet.
-                    state.state = IDLE;
+                    // have not finished yet. The bytes after an upgrade request are
+                    // only HTTP if the response does not switch protocols.
+                    state.state = isUpgrade ? AWAITING_RESPONSE : IDLE;
+                    requestReceived();
                 }
 
-                server.emit('request', req, res);
+                dispatchRequest(req, res);
                 if (requestHasNoBody) {
                     // Emit EOF after request handlers had a chance to attach `end` listeners.
                     Promise.resolve().then(function () {
@@ -1196,6 +1299,7 @@ function createConnectionParser(server, socket) {
                     state.req.complete = true;
                     state.req.push(null);
                     state.state = AWAITING_RESPONSE;
+                    requestReceived();
                     maybeFinalizeResponse();
                 }
                 progress = true;
@@ -1210,6 +1314,7 @@ function createConnectionParser(server, socket) {
                     state.req.complete = true;
                     state.req.push(null);
                     state.state = AWAITING_RESPONSE;
+                    requestReceived();
                     maybeFinalizeResponse();
                     progress = true;
                 } else if (result === 'error') {
@@ -1386,6 +1491,22 @@ function computeKeepAlive(connectionHeader, httpVersion) {
 
 // ===== HTTP Server (extends net.Server) =====
 
+// Like Node.js, headersTimeout and requestTimeout are enforced by sweeping the connections
+// every connectionsCheckingInterval instead of with a timer per request
+function checkConnections(server) {
+    const now = Date.now();
+    for (const conn of server._httpConnections) {
+        if (conn.requestStart === 0) continue;
+        const elapsed = now - conn.requestStart;
+        const headersExpired = !conn.headersComplete && server.headersTimeout > 0 &&
+            elapsed > server.headersTimeout;
+        const requestExpired = server.requestTimeout > 0 && elapsed > server.requestTimeout;
+        if (headersExpired || requestExpired) {
+            conn.requestTimedOut();
+        }
+    }
+}
+
 function Server(options, requestListener) {
     if (!(this instanceof Server)) return new Server(options, requestListener);
 
@@ -1420,6 +1541,8 @@ function Server(options, requestListener) {
     this.headersTimeout = 60000;
     this.requestTimeout = 300000;
     this.maxRequestsPerSocket = 0;
+    this.connectionsCheckingInterval = options.connectionsCheckingInterval ?? 30000;
+    this._connectionsCheckingTimer = null;
     this._rejectNonStandardBodyWrites = !!options.rejectNonStandardBodyWrites;
     this._requireHostHeader = options.requireHostHeader !== false;
     this._joinDuplicateHeaders = !!options.joinDuplicateHeaders;
@@ -1457,8 +1580,22 @@ function Server(options, requestListener) {
     }
 
     const self = this;
-    this.on('listening', function () { _registerServer(self); });
-    this.on('close', function () { _unregisterServer(self); });
+    this.on('listening', function () {
+        _registerServer(self);
+        if (self.connectionsCheckingInterval > 0) {
+            self._connectionsCheckingTimer = setInterval(function () {
+                checkConnections(self);
+            }, self.connectionsCheckingInterval);
+            self._connectionsCheckingTimer.unref();
+        }
+    });
+    this.on('close', function () {
+        _unregisterServer(self);
+        if (self._connectionsCheckingTimer !== null) {
+            clearInterval(self._connectionsCheckingTimer);
+            self._connectionsCheckingTimer = null;
+        }
+    });
     this.on('connection', function connectionListener(socket) {
         // Force-close idle connections to prevent WASI resource exhaustion.
         // In WASM, each socket consumes limited resources (pollables, streams),
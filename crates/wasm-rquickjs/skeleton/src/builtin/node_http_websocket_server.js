@@ -0,0 +1,424 @@
+// WebSocket server on top of the node:http 'upgrade' event.
+// The API follows the `ws` package's WebSocketServer, which most Node.js WebSocket servers build on.
+// Exposed as the `wasm-rquickjs:websocket-server` module, as it is not part of Node.js.
+import { EventEmitter } from 'node:events';
+import { Buffer } from 'node:buffer';
+import { createServer } from '__wasm_rquickjs_builtin/node_http_server';
+import {
+    OPCODE_TEXT,
+    OPCODE_BINARY,
+    OPCODE_CLOSE,
+    OPCODE_PING,
+    OPCODE_PONG,
+    CLOSE_NO_STATUS,
+    CLOSE_ABNORMAL,
+    FrameParser,
+    WebSocketProtocolError,
+    acceptKey,
+    encodeClosePayload,
+    encodeFrame,
+} from '__wasm_rquickjs_builtin/websocket_protocol';
+
+const CONNECTING = 0;
+const OPEN = 1;
+const CLOSING = 2;
+const CLOSED = 3;
+
+// How long to wait for the peer to answer a close frame before dropping the connection
+const CLOSE_TIMEOUT = 30000;
+
+const KEY_REGEX = /^[+/0-9A-Za-z]{22}==$/;
+const TOKEN_REGEX = /^[!#$%&'*+\-.^_`|~0-9A-Za-z]+$/;
+
+const HANDSHAKE_STATUS_TEXT = {
+    400: 'Bad Request',
+    405: 'Method Not Allowed',
+    426: 'Upgrade Required',
+};
+
+function abortHandshake(socket, code, message, headers) {
+    if (socket.destroyed) return;
+    const body = message || HANDSHAKE_STATUS_TEXT[code];
+    const lines = [
+        `HTTP/1.1 ${code} ${HANDSHAKE_STATUS_TEXT[code]}`,
+        'Connection: close',
+        'Content-Type: text/html',
+        `Content-Length: ${Buffer.byteLength(body)}`,
+    ];
+    for (const [name, value] of Object.entries(headers || {})) {
+        lines.push(`${name}: ${value}`);
+    }
+    socket.end(lines.join('\r\n') + '\r\n\r\n' + body);
+}
+
+function parseProtocols(header) {
+    const protocols = new Set();
+    if (header === undefined) {
+        return protocols;
+    }
+    for (const token of header.split(',')) {
+        const protocol = token.trim();
+        if (!TOKEN_REGEX.test(protocol) || protocols.has(protocol)) {
+            return null;
+        }
+        protocols.add(protocol);
+    }
+    return protocols;
+}
+
+/**
+ * The server side of an accepted WebSocket connection.
+ *
+ * Emits `message` (data, isBinary), `ping` (data), `pong` (data), `error` (err) and
+ * `close` (code, reason) where `data` and `reason` are Buffers.
+ */
+export class ServerWebSocket extends EventEmitter {
+    constructor(socket, protocol, head, maxPayload) {
+        super();
+        this.protocol = protocol;
+        this.extensions = '';
+        this._socket = socket;
+        this._readyState = OPEN;
+        this._parser = new FrameParser({ expectMasked: true, maxPayload });
+        this._closeFrameSent = false;
+        this._closeFrameReceived = false;
+        this._closeCode = CLOSE_ABNORMAL;
+        this._closeReason = Buffer.alloc(0);
+        this._closeTimer = null;
+
+        socket.setTimeout(0);
+        if (typeof socket.setNoDelay === 'function') {
+            socket.setNoDelay(true);
+        }
+        socket.on('data', (chunk) => this._onData(chunk));
+        socket.on('end', () => {
+            if (this._readyState === OPEN) {
+                this._readyState = CLOSING;
+            }
+            socket.end();
+        });
+        socket.on('error', (err) => {
+            if (this.listenerCount('error') > 0) {
+                this.emit('error', err);
+            }
+            socket.destroy();
+        });
+        socket.on('close', () => this._onSocketClose());
+        if (head && head.length > 0) {
+            this._onData(head);
+        }
+    }
+
+    get readyState() { return this._readyState; }
+    get bufferedAmount() { return this._socket.writableLength || 0; }
+
+    _onData(chunk) {
+        if (this._readyState === CLOSED) return;
+        let messages;
+        try {
+            messages = this._parser.push(chunk);
+        } catch (e) {
+            this._fail(e);
+            return;
+        }
+        for (const { type, data } of messages) {
+            if (type === 'text') {
+                this.emit('message', Buffer.from(data, 'utf8'), false);
+            } else if (type === 'binary') {
+                this.emit('message', data, true);
+            } else if (type === 'ping') {
+                this._writeFrame(OPCODE_PONG, data);
+                this.emit('ping', data);
+            } else if (type === 'pong') {
+                this.emit('pong', data);
+            } else if (type === 'close') {
+                this._closeFrameReceived = true;
+                this._closeCode = data.code;
+                this._closeReason = Buffer.from(data.reason, 'utf8');
+                this._sendCloseFrame(data.code === CLOSE_NO_STATUS ? undefined : data.code, '');
+                // The server closes the TCP connection once both close frames are exchanged
+                this._readyState = CLOSING;
+                this._socket.end();
+                return;
+            }
+        }
+    }
+
+    _fail(err) {
+        if (err instanceof WebSocketProtocolError) {
+            this._sendCloseFrame(err.closeCode, '');
+            this._closeCode = err.closeCode;
+        }
+        this._readyState = CLOSING;
+        if (this.listenerCount('error') > 0) {
+            this.emit('error', err);
+        }
+        this._socket.end();
+    }
+
+    _onSocketClose() {
+        if (this._closeTimer !== null) {
+            clearTimeout(this._closeTimer);
+            this._closeTimer = null;
+        }
+        if (this._readyState === CLOSED) return;
+        this._readyState = CLOSED;
+        this.emit('close', this._closeCode, this._closeReason);
+    }
+
+    _writeFrame(opcode, payload, cb) {
+        if (this._socket.destroyed || !this._socket.writable) {
+            if (typeof cb === 'function') {
+                cb(new Error('WebSocket is not open'));
+            }
+            return;
+        }
+        this._socket.write(encodeFrame(opcode, payload, false), cb);
+    }
+
+    _sendCloseFrame(code, reason) {
+        if (this._closeFrameSent) return;
+        this._closeFrameSent = true;
+        this._writeFrame(OPCODE_CLOSE, encodeClosePayload(code, reason));
+    }
+
+    _checkOpen(cb) {
+        if (this._readyState === OPEN) {
+            return true;
+        }
+        const err = new Error(`WebSocket is not open: readyState ${this._readyState}`);
+        if (typeof cb === 'function') {
+            process.nextTick(cb, err);
+            return false;
+        }
+        throw err;
+    }
+
+    send(data, options, cb) {
+        if (typeof options === 'function') {
+            cb = options;
+            options = {};
+        }
+        if (!this._checkOpen(cb)) return;
+        const binary = options && options.binary !== undefined
+            ? !!options.binary
+            : typeof data !== 'string';
+        this._writeFrame(binary ? OPCODE_BINARY : OPCODE_TEXT, typeof data === 'number' ? String(data) : data, cb);
+    }
+
+    ping(data, mask, cb) {
+        if (typeof data === 'function') {
+            cb = data;
+            data = undefined;
+        } else if (typeof mask === 'function') {
+            cb = mask;
+        }
+        if (!this._checkOpen(cb)) return;
+        this._writeFrame(OPCODE_PING, data, cb);
+    }
+
+    pong(data, mask, cb) {
+        if (typeof data === 'function') {
+            cb = data;
+            data = undefined;
+        } else if (typeof mask === 'function') {
+            cb = mask;
+        }
+        if (!this._checkOpen(cb)) return;
+        this._writeFrame(OPCODE_PONG, data, cb);
+    }
+
+    close(code, reason) {
+        if (this._readyState === CLOSED) return;
+        if (code !== undefined && code !== 1000 && (code < 3000 || code > 4999)) {
+            throw new TypeError('First argument must be a valid error code number');
+        }
+        if (reason !== undefined && Buffer.byteLength(String(reason)) > 123) {
+            throw new RangeError('The message must not be greater than 123 bytes');
+        }
+        if (this._readyState === CLOSING) {
+            if (this._closeFrameSent && this._closeFrameReceived) {
+                this._socket.end();
+            }
+            return;
+        }
+        this._readyState = CLOSING;
+        this._sendCloseFrame(code, reason === undefined ? '' : String(reason));
+        this._closeTimer = setTimeout(() => this._socket.destroy(), CLOSE_TIMEOUT);
+        this._closeTimer.unref();
+    }
+
+    terminate() {
+        if (this._readyState === CLOSED) return;
+        this._readyState = CLOSING;
+        this._socket.destroy();
+    }
+}
+
+ServerWebSocket.CONNECTING = CONNECTING;
+ServerWebSocket.OPEN = OPEN;
+ServerWebSocket.CLOSING = CLOSING;
+ServerWebSocket.CLOSED = CLOSED;
+
+/**
+ * Accepts WebSocket connections on a node:http server.
+ *
+ * Exactly one of `port` (creates and listens on its own HTTP server), `server` (attaches to
+ * the server's 'upgrade' event) or `noServer` (upgrades are passed to `handleUpgrade` by the
+ * application) must be given. Accepted connections are emitted as `connection` (ws, req).
+ */
+export class WebSocketServer extends EventEmitter {
+    constructor(options, callback) {
+        super();
+        options = {
+            maxPayload: 100 * 1024 * 1024,
+            clientTracking: true,
+            handleProtocols: null,
+            path: null,
+            ...options,
+        };
+        const targets = [options.port != null, !!options.server, !!options.noServer];
+        if (targets.filter(Boolean).length !== 1) {
+            throw new TypeError(
+                'One and only one of the "port", "server", or "noServer" options must be specified'
+            );
+        }
+        this.options = options;
+        this.clients = options.clientTracking ? new Set() : undefined;
+        this._server = null;
+        this._ownsServer = false;
+        this._closed = false;
+
+        if (options.port != null) {
+            this._server = createServer((req, res) => {
+                const body = HANDSHAKE_STATUS_TEXT[426];
+                res.writeHead(426, { 'Content-Length': body.length, 'Content-Type': 'text/plain' });
+                res.end(body);
+            });
+            this._ownsServer = true;
+            this._server.listen(options.port, options.host, callback);
+        } else if (options.server) {
+            this._server = options.server;
+        }
+
+        if (this._server !== null) {
+            this._onListening = () => this.emit('listening');
+            this._onError = (err) => this.emit('error', err);
+            this._onUpgrade = (req, socket, head) => {
+                this.handleUpgrade(req, socket, head, (ws) => this.emit('connection', ws, req));
+            };
+            this._server.on('listening', this._onListening);
+            this._server.on('error', this._onError);
+            this._server.on('upgrade', this._onUpgrade);
+        }
+    }
+
+    address() {
+        if (this._server === null) {
+            throw new Error('The server is operating in "noServer" mode');
+        }
+        return this._server.address();
+    }
+
+    shouldHandle(req) {
+        if (this.options.path) {
+            const index = req.url.indexOf('?');
+            const pathname = index === -1 ? req.url : req.url.slice(0, index);
+            return pathname === this.options.path;
+        }
+        return true;
+    }
+
+    /** Completes the opening handshake for an 'upgrade' request and calls `cb(ws, req)` */
+    handleUpgrade(req, socket, head, cb) {
+        socket.on('error', () => socket.destroy());
+
+        if (this._closed) {
+            abortHandshake(socket, 400, 'The WebSocket server is closed');
+            return;
+        }
+        if (!this.shouldHandle(req)) {
+            abortHandshake(socket, 400);
+            return;
+        }
+        if (req.method !== 'GET') {
+            abortHandshake(socket, 405, 'Invalid HTTP method');
+            return;
+        }
+        const upgrade = req.headers.upgrade;
+        if (upgrade === undefined || upgrade.toLowerCase() !== 'websocket') {
+            abortHandshake(socket, 400, 'Invalid Upgrade header');
+            return;
+        }
+        const key = req.headers['sec-websocket-key'];
+        if (key === undefined || !KEY_REGEX.test(key)) {
+            abortHandshake(socket, 400, 'Missing or invalid Sec-WebSocket-Key header');
+            return;
+        }
+        const version = req.headers['sec-websocket-version'];
+        if (version !== '13') {
+            abortHandshake(socket, 426, 'Unsupported WebSocket version', { 'Sec-WebSocket-Version': '13' });
+            return;
+        }
+        const offered = parseProtocols(req.headers['sec-websocket-protocol']);
+        if (offered === null) {
+            abortHandshake(socket, 400, 'Invalid Sec-WebSocket-Protocol header');
+            return;
+        }
+
+        let protocol = '';
+        if (offered.size > 0) {
+            const selected = this.options.handleProtocols
+                ? this.options.handleProtocols(offered, req)
+                : offered.values().next().value;
+            if (selected) {
+                protocol = String(selected);
+            }
+        }
+
+        const headers = [
+            'HTTP/1.1 101 Switching Protocols',
+            'Upgrade: websocket',
+            'Connection: Upgrade',
+            `Sec-WebSocket-Accept: ${acceptKey(key)}`,
+        ];
+        if (protocol !== '') {
+            headers.push(`Sec-WebSocket-Protocol: ${protocol}`);
+        }
+        this.emit('headers', headers, req);
+        socket.write(headers.join('\r\n') + '\r\n\r\n');
+
+        const ws = new ServerWebSocket(socket, protocol, head, this.options.maxPayload);
+        if (this.clients) {
+            this.clients.add(ws);
+            ws.on('close', () => this.clients.delete(ws));
+        }
+        cb(ws, req);
+    }
+
+    close(cb) {
+        if (typeof cb === 'function') {
+            this.once('close', cb);
+        }
+        if (this._closed) {
+            process.nextTick(() => this.emit('close'));
+            return;
+        }
+        this._closed = true;
+
+        if (this._server === null) {
+            process.nextTick(() => this.emit('close'));
+            return;
+        }
+        this._server.removeListener('listening', this._onListening);
+        this._server.removeListener('error', this._onError);
+        this._server.removeListener('upgrade', this._onUpgrade);
+        if (this._ownsServer) {
+            this._server.close(() => this.emit('close'));
+        } else {
+            process.nextTick(() => this.emit('close'));
+        }
+    }
+}
+
+export default { WebSocketServer, ServerWebSocket };
@@ -1,2 +1,5 @@
 // node:test module - JavaScript-only (no native functions needed)
 pub const TEST_JS: &str = include_str!("test.js");
+
+// node:test/reporters module - the built-in TAP, spec, dot and JUnit reporters
+pub const TEST_REPORTERS_JS: &str = include_str!("test_reporters.js");
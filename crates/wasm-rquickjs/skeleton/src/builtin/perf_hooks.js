@@ -1,5 +1,14 @@
 // node:perf_hooks - partial implementation
 
+import { exported_call_count, NativeHistogram } from '__wasm_rquickjs_builtin/perf_hooks_native';
+import {
+    ERR_INVALID_ARG_TYPE,
+    ERR_INVALID_ARG_VALUE,
+    ERR_MISSING_ARGS,
+    ERR_OUT_OF_RANGE,
+} from '__wasm_rquickjs_builtin/internal/errors';
+import { validateInteger, validateNumber, validateObject } from '__wasm_rquickjs_builtin/internal/validators';
+
 const _timeOrigin = Date.now();
 const _marks = [];
 const _measures = [];
@@ -13,20 +22,27 @@ function findMark(name) {
 }
 
 class PerformanceEntry {
-    constructor(name, entryType, startTime, duration) {
+    constructor(name, entryType, startTime, duration, detail) {
         this.name = name;
         this.entryType = entryType;
         this.startTime = startTime;
         this.duration = duration;
+        if (detail !== undefined) {
+            this.detail = detail;
+        }
     }
 
     toJSON() {
-        return {
+        const json = {
             name: this.name,
             entryType: this.entryType,
             startTime: this.startTime,
             duration: this.duration,
         };
+        if (this.detail !== undefined) {
+            json.detail = this.detail;
+        }
+        return json;
     }
 }
 
@@ -63,14 +79,16 @@ const performance = {
 
     mark(name, options) {
         const startTime = options?.startTime ?? this.now();
-        const entry = new PerformanceEntry(name, 'mark', startTime, 0);
+        const entry = new PerformanceEntry(name, 'mark', startTime, 0, options?.detail);
         _marks.push(entry);
+        enqueue(entry);
         return entry;
     },
 
     measure(name, startMarkOrOptions, endMark) {
         let startTime = 0;
         let endTime = this.now();
+        let detail;
 
         if (typeof startMarkOrOptions === 'string') {
             startTime = findMark(startMarkOrOptions).startTime;
@@ -91,11 +109,13 @@ const performance = {
             if (startMarkOrOptions.duration !== undefined) {
                 endTime = startTime + startMarkOrOptions.duration;
             }
+            detail = startMarkOrOptions.detail;
         }
 
         const duration = endTime - startTime;
-        const entry = new PerformanceEntry(name, 'measure', startTime, duration);
+        const entry = new PerformanceEntry(name, 'measure', startTime, duration, detail);
         _measures.push(entry);
+        enqueue(entry);
         return entry;
     },
 
@@ -121,6 +141,8 @@ const performance = {
         return this.getEntries().filter(e => e.entryType === type);
     },
 
+    timerify,
+
     nodeTiming,
 
     toJSON() {
@@ -128,38 +150,418 @@ const performance = {
     },
 };
 
+// Entry types of PerformanceObserver. 'function' entries come from `performance.timerify`, and
+// 'gc' entries from explicit `gc()` calls, as the collections QuickJS triggers by itself cannot be
+// observed.
+const SUPPORTED_ENTRY_TYPES = Object.freeze(['function', 'gc', 'mark', 'measure']);
+
+const _observers = new Set();
+let _dispatchPending = false;
+
+function isObserved(entryType) {
+    for (const observer of _observers) {
+        if (observer._entryTypes.has(entryType)) {
+            return true;
+        }
+    }
+    return false;
+}
+
+// Queues the entry for the observers of its type, which are called in a later turn of the event
+// loop with all the entries queued until then
+function enqueue(entry) {
+    let queued = false;
+    for (const observer of _observers) {
+        if (observer._entryTypes.has(entry.entryType)) {
+            observer._buffer.push(entry);
+            queued = true;
+        }
+    }
+    if (queued && !_dispatchPending) {
+        _dispatchPending = true;
+        setImmediate(dispatch);
+    }
+}
+
+function dispatch() {
+    _dispatchPending = false;
+    for (const observer of [..._observers]) {
+        const entries = observer.takeRecords();
+        if (entries.length > 0) {
+            observer._callback.call(observer, new PerformanceObserverEntryList(entries), observer);
+        }
+    }
+}
+
+class PerformanceObserverEntryList {
+    #entries;
+
+    constructor(entries) {
+        this.#entries = entries;
+    }
+
+    getEntries() {
+        return [...this.#entries];
+    }
+
+    getEntriesByType(type) {
+        return this.#entries.filter(e => e.entryType === type);
+    }
+
+    getEntriesByName(name, type) {
+        return this.#entries.filter(e =>
+            e.name === name && (type === undefined || e.entryType === type)
+        );
+    }
+}
+
 class PerformanceObserver {
     constructor(callback) {
+        if (typeof callback !== 'function') {
+            throw new ERR_INVALID_ARG_TYPE('callback', 'Function', callback);
+        }
         this._callback = callback;
+        this._entryTypes = new Set();
+        this._buffer = [];
     }
 
-    observe() {}
+    observe(options = {}) {
+        validateObject(options, 'options');
+        const { entryTypes, type, buffered } = options;
+        if (entryTypes === undefined && type === undefined) {
+            throw new ERR_MISSING_ARGS('options.entryTypes', 'options.type');
+        }
+        if (entryTypes !== undefined && type !== undefined) {
+            throw new ERR_INVALID_ARG_VALUE('options.entryTypes', entryTypes,
+                'options.entryTypes can not set with options.type together');
+        }
+
+        if (entryTypes !== undefined) {
+            if (!Array.isArray(entryTypes)) {
+                throw new ERR_INVALID_ARG_TYPE('options.entryTypes', 'string[]', entryTypes);
+            }
+            this._entryTypes.clear();
+            for (const entryType of entryTypes) {
+                if (SUPPORTED_ENTRY_TYPES.includes(entryType)) {
+                    this._entryTypes.add(entryType);
+                }
+            }
+        } else {
+            if (!SUPPORTED_ENTRY_TYPES.includes(type)) {
+                return;
+            }
+            this._entryTypes.add(type);
+            if (buffered) {
+                for (const entry of performance.getEntriesByType(type)) {
+                    this._buffer.push(entry);
+                }
+                if (this._buffer.length > 0 && !_dispatchPending) {
+                    _dispatchPending = true;
+                    setImmediate(dispatch);
+                }
+            }
+        }
+
+        if (this._entryTypes.size > 0) {
+            _observers.add(this);
+        } else {
+            this.disconnect();
+        }
+    }
 
-    disconnect() {}
+    disconnect() {
+        _observers.delete(this);
+        this._entryTypes.clear();
+        this._buffer = [];
+    }
 
     takeRecords() {
-        return [];
+        const records = this._buffer;
+        this._buffer = [];
+        return records;
     }
 
     static get supportedEntryTypes() {
-        return ['mark', 'measure'];
+        return SUPPORTED_ENTRY_TYPES;
+    }
+}
+
+const _timerified = new WeakMap();
+
+function timerify(fn, options = {}) {
+    if (typeof fn !== 'function') {
+        throw new ERR_INVALID_ARG_TYPE('fn', 'Function', fn);
+    }
+    validateObject(options, 'options');
+    const { histogram } = options;
+    if (histogram !== undefined && !(histogram instanceof RecordableHistogram)) {
+        throw new ERR_INVALID_ARG_TYPE('options.histogram', 'RecordableHistogram', histogram);
+    }
+    if (histogram === undefined && _timerified.has(fn)) {
+        return _timerified.get(fn);
+    }
+
+    function complete(start, args) {
+        const duration = performance.now() - start;
+        if (histogram !== undefined) {
+            histogram.record(Math.max(1, Math.ceil(duration * 1e6)));
+        }
+        if (isObserved('function')) {
+            enqueue(new PerformanceEntry(fn.name, 'function', start, duration, args));
+        }
+    }
+
+    function timerified(...args) {
+        const start = performance.now();
+        if (new.target !== undefined) {
+            const result = Reflect.construct(fn, args, fn);
+            complete(start, args);
+            return result;
+        }
+        const result = Reflect.apply(fn, this, args);
+        if (typeof result?.finally === 'function') {
+            return result.finally(() => complete(start, args));
+        }
+        complete(start, args);
+        return result;
+    }
+
+    Object.defineProperties(timerified, {
+        length: { configurable: false, enumerable: true, value: fn.length },
+        name: { configurable: false, enumerable: true, value: `timerified ${fn.name}` },
+    });
+    if (histogram === undefined) {
+        _timerified.set(fn, timerified);
+    }
+    return timerified;
+}
+
+const constants = {
+    NODE_PERFORMANCE_GC_MAJOR: 4,
+    NODE_PERFORMANCE_GC_MINOR: 1,
+    NODE_PERFORMANCE_GC_INCREMENTAL: 8,
+    NODE_PERFORMANCE_GC_WEAKCB: 16,
+    NODE_PERFORMANCE_GC_FLAGS_NO: 0,
+    NODE_PERFORMANCE_GC_FLAGS_CONSTRUCT_RETAINED: 2,
+    NODE_PERFORMANCE_GC_FLAGS_FORCED: 4,
+    NODE_PERFORMANCE_GC_FLAGS_SYNCHRONOUS_PHANTOM_PROCESSING: 8,
+    NODE_PERFORMANCE_GC_FLAGS_ALL_AVAILABLE_GARBAGE: 16,
+    NODE_PERFORMANCE_GC_FLAGS_ALL_EXTERNAL_MEMORY: 32,
+    NODE_PERFORMANCE_GC_FLAGS_SCHEDULE_IDLE: 64,
+};
+
+// Called by `gc()` after each collection with its start and end time in milliseconds since the
+// epoch
+globalThis.__wasm_rquickjs_post_gc = function postGc(start, end) {
+    if (isObserved('gc')) {
+        enqueue(new PerformanceEntry('gc', 'gc', start - _timeOrigin, end - start, {
+            kind: constants.NODE_PERFORMANCE_GC_MAJOR,
+            flags: constants.NODE_PERFORMANCE_GC_FLAGS_FORCED,
+        }));
+    }
+};
+
+// Histograms, backed by a native HDR histogram. Values are integers, nanoseconds for the
+// event loop delay and timerify histograms.
+
+class Histogram {
+    #map = new Map();
+
+    constructor(handle) {
+        this._handle = handle;
+    }
+
+    get count() {
+        return this._handle.count();
+    }
+
+    get countBigInt() {
+        return BigInt(this.count);
+    }
+
+    get min() {
+        return this._handle.min();
+    }
+
+    get minBigInt() {
+        return this.count === 0 ? 9223372036854775807n : BigInt(this.min);
+    }
+
+    get max() {
+        return this._handle.max();
+    }
+
+    get maxBigInt() {
+        return BigInt(this.max);
+    }
+
+    get mean() {
+        return this._handle.mean();
+    }
+
+    get stddev() {
+        return this._handle.stddev();
+    }
+
+    get exceeds() {
+        return this._handle.exceeds();
+    }
+
+    get exceedsBigInt() {
+        return BigInt(this.exceeds);
+    }
+
+    percentile(percentile) {
+        validateNumber(percentile, 'percentile');
+        if (Number.isNaN(percentile) || percentile <= 0 || percentile > 100) {
+            throw new ERR_OUT_OF_RANGE('percentile', '> 0 && <= 100', percentile);
+        }
+        return this._handle.percentile(percentile);
+    }
+
+    percentileBigInt(percentile) {
+        return BigInt(this.percentile(percentile));
+    }
+
+    get percentiles() {
+        this.#map.clear();
+        const pairs = this._handle.percentiles();
+        for (let i = 0; i < pairs.length; i += 2) {
+            this.#map.set(pairs[i], pairs[i + 1]);
+        }
+        return this.#map;
+    }
+
+    get percentilesBigInt() {
+        const map = new Map();
+        for (const [percentile, value] of this.percentiles) {
+            map.set(percentile, BigInt(value));
+        }
+        return map;
+    }
+
+    reset() {
+        this._handle.reset();
+    }
+
+    toJSON() {
+        return {
+            count: this.count,
+            min: this.min,
+            max: this.max,
+            mean: this.mean,
+            exceeds: this.exceeds,
+            stddev: this.stddev,
+            percentiles: Object.fromEntries(this.percentiles),
+        };
+    }
+
+    [Symbol.for('nodejs.util.inspect.custom')](depth, options, inspect) {
+        if (depth < 0) {
+            return this;
+        }
+        return `${this.constructor.name} ${inspect(this.toJSON(), { ...options, depth: options.depth == null ? null : options.depth - 1 })}`;
     }
 }
 
-function monitorEventLoopDelay() {
-    throw new Error('monitorEventLoopDelay is not supported in WebAssembly environment');
+class RecordableHistogram extends Histogram {
+    record(val) {
+        if (typeof val === 'bigint') {
+            if (val < 1n) {
+                throw new ERR_OUT_OF_RANGE('val', '>= 1', val);
+            }
+            this._handle.record(Number(val));
+            return;
+        }
+        validateInteger(val, 'val', 1);
+        this._handle.record(val);
+    }
+
+    // Records the nanoseconds elapsed since the previous call
+    recordDelta() {
+        this._handle.record_delta();
+    }
+
+    add(other) {
+        if (!(other instanceof RecordableHistogram)) {
+            throw new ERR_INVALID_ARG_TYPE('other', 'RecordableHistogram', other);
+        }
+        this._handle.add(other._handle);
+    }
+}
+
+function createHistogram(options = {}) {
+    validateObject(options, 'options');
+    const { lowest = 1, highest = Number.MAX_SAFE_INTEGER, figures = 3 } = options;
+    if (typeof lowest !== 'bigint') {
+        validateInteger(lowest, 'options.lowest', 1, Number.MAX_SAFE_INTEGER);
+    }
+    if (typeof highest !== 'bigint') {
+        validateInteger(highest, 'options.highest', 2 * Number(lowest), Number.MAX_SAFE_INTEGER);
+    } else if (highest < 2n * BigInt(lowest)) {
+        throw new ERR_INVALID_ARG_VALUE('options.highest', highest);
+    }
+    validateInteger(figures, 'options.figures', 1, 5);
+    return new RecordableHistogram(new NativeHistogram(Number(lowest), Number(highest), figures));
+}
+
+// Histogram of the time between the ticks of a `resolution` ms interval timer, which grows
+// beyond `resolution` when the timers of the executor are delayed by running code. The time
+// between exported calls is not counted, as no code runs then.
+class ELDHistogram extends Histogram {
+    #resolution;
+    #timer = undefined;
+    #exportedCalls = 0;
+
+    constructor(resolution) {
+        super(new NativeHistogram(1, Number.MAX_SAFE_INTEGER, 3));
+        this.#resolution = resolution;
+    }
+
+    enable() {
+        if (this.#timer !== undefined) {
+            return false;
+        }
+        this.#exportedCalls = exported_call_count();
+        this._handle.start_delta();
+        this.#timer = setInterval(() => this.#tick(), this.#resolution);
+        this.#timer.unref();
+        return true;
+    }
+
+    disable() {
+        if (this.#timer === undefined) {
+            return false;
+        }
+        clearInterval(this.#timer);
+        this.#timer = undefined;
+        return true;
+    }
+
+    #tick() {
+        const exportedCalls = exported_call_count();
+        if (exportedCalls !== this.#exportedCalls) {
+            this.#exportedCalls = exportedCalls;
+            this._handle.start_delta();
+        } else {
+            this._handle.record_delta();
+        }
+    }
 }
 
-function createHistogram() {
-    throw new Error('createHistogram is not supported in WebAssembly environment');
+function monitorEventLoopDelay(options = {}) {
+    validateObject(options, 'options');
+    const { resolution = 10 } = options;
+    validateInteger(resolution, 'options.resolution', 1);
+    return new ELDHistogram(resolution);
 }
 
-const constants = {};
 
 export {
     performance,
     PerformanceEntry,
     PerformanceObserver,
+    PerformanceObserverEntryList,
     monitorEventLoopDelay,
     createHistogram,
     constants,
@@ -169,6 +571,7 @@ export default {
     performance,
     PerformanceEntry,
     PerformanceObserver,
+    PerformanceObserverEntryList,
     monitorEventLoopDelay,
     createHistogram,
     constants,
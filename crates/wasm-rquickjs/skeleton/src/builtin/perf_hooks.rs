@@ -1,4 +1,166 @@
-// perf_hooks module - JavaScript-only implementation (no native functions needed)
+use rquickjs::class::Trace;
+use rquickjs::{Class, Ctx, Exception, JsLifetime};
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static EXPORTED_CALLS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Marks the start of an exported call, so interval histograms do not count the time between
+/// exported calls as event loop delay
+pub fn exported_call_started() {
+    EXPORTED_CALLS.set(EXPORTED_CALLS.get() + 1);
+}
+
+/// HDR histogram behind `createHistogram` and `monitorEventLoopDelay`, following the semantics of
+/// the HdrHistogram_c based histogram of Node
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct NativeHistogram {
+    #[qjs(skip_trace)]
+    histogram: RefCell<hdrhistogram::Histogram<u64>>,
+    /// Number of recorded values above the highest trackable value
+    #[qjs(skip_trace)]
+    exceeds: Cell<u64>,
+    /// Timestamp of the previous `record_delta` call in nanoseconds
+    #[qjs(skip_trace)]
+    previous_delta: Cell<Option<u64>>,
+}
+
+#[rquickjs::methods]
+impl NativeHistogram {
+    #[qjs(constructor)]
+    pub fn new(ctx: Ctx<'_>, lowest: f64, highest: f64, figures: u8) -> rquickjs::Result<Self> {
+        let histogram =
+            hdrhistogram::Histogram::new_with_bounds(lowest as u64, highest as u64, figures)
+                .map_err(|e| Exception::throw_range(&ctx, &format!("Invalid histogram: {e}")))?;
+        Ok(Self {
+            histogram: RefCell::new(histogram),
+            exceeds: Cell::new(0),
+            previous_delta: Cell::new(None),
+        })
+    }
+
+    pub fn count(&self) -> f64 {
+        self.histogram.borrow().len() as f64
+    }
+
+    /// The minimum recorded value, or the maximum 64-bit integer if there is none
+    pub fn min(&self) -> f64 {
+        let histogram = self.histogram.borrow();
+        if histogram.is_empty() {
+            i64::MAX as f64
+        } else {
+            histogram.min() as f64
+        }
+    }
+
+    pub fn max(&self) -> f64 {
+        self.histogram.borrow().max() as f64
+    }
+
+    pub fn mean(&self) -> f64 {
+        let histogram = self.histogram.borrow();
+        if histogram.is_empty() {
+            f64::NAN
+        } else {
+            histogram.mean()
+        }
+    }
+
+    pub fn stddev(&self) -> f64 {
+        let histogram = self.histogram.borrow();
+        if histogram.is_empty() {
+            f64::NAN
+        } else {
+            histogram.stdev()
+        }
+    }
+
+    pub fn exceeds(&self) -> f64 {
+        self.exceeds.get() as f64
+    }
+
+    pub fn percentile(&self, percentile: f64) -> f64 {
+        self.histogram.borrow().value_at_percentile(percentile) as f64
+    }
+
+    /// The percentiles of the recorded values at the percentile ticks of HdrHistogram (0, 50,
+    /// 75, 87.5, ... and 100), as a flat list of percentile and value pairs
+    pub fn percentiles(&self) -> Vec<f64> {
+        self.histogram
+            .borrow()
+            .iter_quantiles(1)
+            .flat_map(|value| {
+                [
+                    value.quantile_iterated_to() * 100.0,
+                    value.value_iterated_to() as f64,
+                ]
+            })
+            .collect()
+    }
+
+    pub fn record(&self, value: f64) {
+        if self.histogram.borrow_mut().record(value as u64).is_err() {
+            self.exceeds.set(self.exceeds.get() + 1);
+        }
+    }
+
+    /// Records the nanoseconds elapsed since the previous call. The first call only starts the
+    /// measurement.
+    pub fn record_delta(&self) {
+        let now = crate::builtin::deterministic::monotonic_ns();
+        if let Some(previous) = self.previous_delta.replace(Some(now)) {
+            self.record(now.saturating_sub(previous) as f64);
+        }
+    }
+
+    /// Starts a new delta measurement, discarding the time since the previous `record_delta`
+    pub fn start_delta(&self) {
+        self.previous_delta
+            .set(Some(crate::builtin::deterministic::monotonic_ns()));
+    }
+
+    pub fn add(&self, other: Class<'_, NativeHistogram>) -> rquickjs::Result<()> {
+        let other = other.borrow();
+        // Copied first, as `other` may be this histogram
+        let values = other.histogram.borrow().clone();
+        let exceeds = other.exceeds.get();
+        let mut histogram = self.histogram.borrow_mut();
+        for value in values.iter_recorded() {
+            if histogram
+                .record_n(value.value_iterated_to(), value.count_at_value())
+                .is_err()
+            {
+                self.exceeds
+                    .set(self.exceeds.get() + value.count_at_value());
+            }
+        }
+        self.exceeds.set(self.exceeds.get() + exceeds);
+        Ok(())
+    }
+
+    pub fn reset(&self) {
+        self.histogram.borrow_mut().reset();
+        self.exceeds.set(0);
+        self.previous_delta.set(None);
+    }
+}
+
+#[rquickjs::module]
+pub mod native_module {
+    use super::EXPORTED_CALLS;
+
+    pub use super::NativeHistogram;
+
+    /// Number of exported calls started so far
+    #[rquickjs::function]
+    pub fn exported_call_count() -> f64 {
+        EXPORTED_CALLS.get() as f64
+    }
+}
+
+// JS functions for the node:perf_hooks implementation
 pub const PERF_HOOKS_JS: &str = include_str!("perf_hooks.js");
 
 // Re-export for aliases
@@ -10,6 +10,9 @@ import {
 } from '__wasm_rquickjs_builtin/process_native';
 
 import EventEmitter from 'node:events';
+import { captureContext, runInContext } from '__wasm_rquickjs_builtin/async_context';
+import { _emitDestroy, _emitInit, _runInAsyncScope, executionAsyncId } from '__wasm_rquickjs_builtin/async_hooks';
+import { _setInternalTimeout } from '__wasm_rquickjs_builtin/timeout';
 
 function _invalidArgTypeHelper(value) {
     if (value == null) return ' Received ' + String(value);
@@ -331,6 +334,12 @@ function __wasm_rquickjs_handleUncaughtError(err, domain) {
         return;
     }
 
+    // Inside a worker, an uncaught exception terminates the worker and is reported to its parent
+    if (typeof globalThis.__wasm_rquickjs_worker_fatal_error === 'function') {
+        globalThis.__wasm_rquickjs_worker_fatal_error(err);
+        return;
+    }
+
     if (typeof console !== 'undefined') {
         console.error(err);
     }
@@ -346,7 +355,11 @@ function __drainNextTickQueue() {
             if (entry.domain) {
                 entry.domain.enter();
             }
-            entry.callback.apply(undefined, entry.args);
+            try {
+                _runInAsyncScope(entry.asyncId, entry.triggerAsyncId, entry, runInContext, undefined, [entry.context, entry.callback, undefined, entry.args]);
+            } finally {
+                _emitDestroy(entry.asyncId);
+            }
             if (entry.domain) {
                 entry.domain.exit();
             }
@@ -364,13 +377,9 @@ function __requestNextTickWakeup() {
         return;
     }
     __nextTickWakeupScheduled = true;
-    if (typeof globalThis.setTimeout === 'function') {
-        globalThis.setTimeout(function __nextTickWakeup() {
-            __drainNextTickQueue();
-        }, 0);
-    } else {
-        Promise.resolve().then(__drainNextTickQueue);
-    }
+    _setInternalTimeout(function __nextTickWakeup() {
+        __drainNextTickQueue();
+    }, 0);
 }
 
 // Expose the drain function so that timer callbacks can drain pending
@@ -385,7 +394,9 @@ process.nextTick = function processNextTick(callback, ...args) {
             'The "callback" argument must be of type function.' + _invalidArgTypeHelper(callback));
     }
     const domain = process.domain || null;
-    __nextTickQueue.push({ callback, args, domain });
+    const entry = { callback, args, domain, context: captureContext(), asyncId: 0, triggerAsyncId: executionAsyncId() };
+    entry.asyncId = _emitInit('TickObject', entry, entry.triggerAsyncId);
+    __nextTickQueue.push(entry);
     __requestNextTickWakeup();
 };
 
@@ -5,7 +5,6 @@ pub mod native_module {
     use std::collections::HashMap;
     use std::io::Write;
     use std::path::PathBuf;
-    use std::time::Instant;
 
     #[rquickjs::function]
     pub fn memory_usage(ctx: Ctx<'_>) -> Vec<i64> {
@@ -68,10 +67,7 @@ pub mod native_module {
 
     #[rquickjs::function]
     pub fn hrtime_ns() -> u64 {
-        use std::sync::OnceLock;
-        static ORIGIN: OnceLock<Instant> = OnceLock::new();
-        let origin = ORIGIN.get_or_init(Instant::now);
-        origin.elapsed().as_nanos() as u64
+        crate::builtin::deterministic::monotonic_ns()
     }
 }
 
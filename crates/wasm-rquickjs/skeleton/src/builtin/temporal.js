@@ -0,0 +1,179 @@
+import {
+    Instant, PlainDate, PlainTime, PlainDateTime, PlainYearMonth, PlainMonthDay, ZonedDateTime,
+    Duration, now_instant, utc_epoch_milliseconds,
+} from "__wasm_rquickjs_builtin/temporal_native";
+
+// The Temporal types are native classes on top of temporal_rs. This module adds what needs the
+// rest of the runtime: toLocaleString through Intl.DateTimeFormat, Temporal.Now and the
+// namespace object itself.
+
+// The time zone of Temporal.Now when none is given; components have no local time zone
+const SYSTEM_TIME_ZONE = "UTC";
+
+const DATE_DEFAULTS = { year: "numeric", month: "numeric", day: "numeric" };
+const TIME_DEFAULTS = { hour: "numeric", minute: "2-digit", second: "2-digit" };
+const FORMAT_FIELDS = [
+    "weekday", "era", "year", "month", "day", "dayPeriod", "hour", "minute", "second",
+    "fractionalSecondDigits", "timeZoneName", "dateStyle", "timeStyle",
+];
+
+function getOptionsObject(options) {
+    if (options === undefined) {
+        return {};
+    }
+    if (options === null || (typeof options !== "object" && typeof options !== "function")) {
+        throw new TypeError("Options must be an object");
+    }
+    return { ...options };
+}
+
+function toLocaleStringWith(epochMs, locales, options, defaults, timeZone) {
+    if (!Number.isFinite(epochMs) || Math.abs(epochMs) > 8.64e15) {
+        return undefined;
+    }
+    const resolved = getOptionsObject(options);
+    const hasFields = FORMAT_FIELDS.some((key) => resolved[key] !== undefined);
+    const formatOptions = hasFields ? resolved : { ...defaults, ...resolved };
+    formatOptions.timeZone = timeZone;
+    return new Intl.DateTimeFormat(locales, formatOptions).format(new Date(epochMs));
+}
+
+// Plain types have no time zone, so their wall-clock time is formatted as UTC
+function definePlainToLocaleString(constructor, defaults) {
+    const toJSON = constructor.prototype.toJSON;
+    defineNonEnumerable(constructor.prototype, {
+        toLocaleString(locales, options) {
+            const fallback = toJSON.call(this);
+            if (options !== null && typeof options === "object" && options.timeZone !== undefined) {
+                throw new TypeError("timeZone option is not allowed for plain types");
+            }
+            return toLocaleStringWith(utc_epoch_milliseconds(this), locales, options, defaults,
+                "UTC") ?? fallback;
+        },
+    });
+}
+
+function defineNonEnumerable(target, source) {
+    for (const key of Reflect.ownKeys(source)) {
+        Object.defineProperty(target, key, {
+            value: source[key], writable: true, enumerable: false, configurable: true,
+        });
+    }
+    return target;
+}
+
+// The native classes define their methods as enumerable; built-in methods are not
+function makeNonEnumerable(target) {
+    for (const key of Reflect.ownKeys(target)) {
+        const descriptor = Object.getOwnPropertyDescriptor(target, key);
+        if (descriptor.enumerable && descriptor.configurable) {
+            Object.defineProperty(target, key, { ...descriptor, enumerable: false });
+        }
+    }
+}
+
+function defineToStringTag(target, tag) {
+    Object.defineProperty(target, Symbol.toStringTag, {
+        value: tag, writable: false, enumerable: false, configurable: true,
+    });
+}
+
+for (const constructor of [
+    Instant, PlainDate, PlainTime, PlainDateTime, PlainYearMonth, PlainMonthDay, ZonedDateTime,
+    Duration,
+]) {
+    makeNonEnumerable(constructor);
+    makeNonEnumerable(constructor.prototype);
+    defineToStringTag(constructor.prototype, `Temporal.${constructor.name}`);
+}
+
+definePlainToLocaleString(PlainDate, DATE_DEFAULTS);
+definePlainToLocaleString(PlainTime, TIME_DEFAULTS);
+definePlainToLocaleString(PlainDateTime, { ...DATE_DEFAULTS, ...TIME_DEFAULTS });
+definePlainToLocaleString(PlainYearMonth, { year: "numeric", month: "numeric" });
+definePlainToLocaleString(PlainMonthDay, { month: "numeric", day: "numeric" });
+
+const instantToJSON = Instant.prototype.toJSON;
+const zonedToJSON = ZonedDateTime.prototype.toJSON;
+const durationToJSON = Duration.prototype.toJSON;
+
+defineNonEnumerable(Instant.prototype, {
+    toLocaleString(locales, options) {
+        const fallback = instantToJSON.call(this);
+        return toLocaleStringWith(this.epochMilliseconds, locales, options,
+            { ...DATE_DEFAULTS, ...TIME_DEFAULTS }, undefined) ?? fallback;
+    },
+});
+
+defineNonEnumerable(ZonedDateTime.prototype, {
+    toLocaleString(locales, options) {
+        const fallback = zonedToJSON.call(this);
+        if (options !== null && typeof options === "object" && options.timeZone !== undefined) {
+            throw new TypeError("timeZone option is not allowed; use withTimeZone() instead");
+        }
+        let epochMs = this.epochMilliseconds;
+        const defaults = { ...DATE_DEFAULTS, ...TIME_DEFAULTS };
+        let timeZone = this.timeZoneId;
+        if (timeZone.startsWith("+") || timeZone.startsWith("-")) {
+            // Format the wall-clock time as UTC, since Intl only knows named time zones
+            epochMs += this.offsetNanoseconds / 1_000_000;
+            timeZone = "UTC";
+        } else {
+            defaults.timeZoneName = "short";
+        }
+        return toLocaleStringWith(epochMs, locales, options, defaults, timeZone) ?? fallback;
+    },
+});
+
+defineNonEnumerable(Duration.prototype, {
+    toLocaleString(locales, options) {
+        const fallback = durationToJSON.call(this);
+        if (typeof Intl.DurationFormat === "function") {
+            return new Intl.DurationFormat(locales, options).format(this);
+        }
+        return fallback;
+    },
+});
+
+function systemZonedDateTime(temporalTimeZoneLike) {
+    const timeZone = temporalTimeZoneLike === undefined ? SYSTEM_TIME_ZONE : temporalTimeZoneLike;
+    return now_instant().toZonedDateTimeISO(timeZone);
+}
+
+const Now = defineNonEnumerable({}, {
+    instant() {
+        return now_instant();
+    },
+    timeZoneId() {
+        return SYSTEM_TIME_ZONE;
+    },
+    zonedDateTimeISO(temporalTimeZoneLike) {
+        return systemZonedDateTime(temporalTimeZoneLike);
+    },
+    plainDateTimeISO(temporalTimeZoneLike) {
+        return systemZonedDateTime(temporalTimeZoneLike).toPlainDateTime();
+    },
+    plainDateISO(temporalTimeZoneLike) {
+        return systemZonedDateTime(temporalTimeZoneLike).toPlainDate();
+    },
+    plainTimeISO(temporalTimeZoneLike) {
+        return systemZonedDateTime(temporalTimeZoneLike).toPlainTime();
+    },
+});
+defineToStringTag(Now, "Temporal.Now");
+
+const Temporal = defineNonEnumerable({}, {
+    Instant, PlainDate, PlainTime, PlainDateTime, PlainYearMonth, PlainMonthDay, ZonedDateTime,
+    Duration, Now,
+});
+defineToStringTag(Temporal, "Temporal");
+
+defineNonEnumerable(Date.prototype, {
+    toTemporalInstant() {
+        const epochMs = Date.prototype.getTime.call(this);
+        return Instant.fromEpochMilliseconds(epochMs);
+    },
+});
+
+export { Temporal };
+export default Temporal;
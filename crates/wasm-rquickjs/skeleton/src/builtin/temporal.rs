@@ -0,0 +1,3321 @@
+use chrono::{DateTime, LocalResult, NaiveDateTime, Offset, TimeZone as _};
+use chrono_tz::{GapInfo, Tz};
+use rquickjs::convert::Coerced;
+use rquickjs::prelude::Opt;
+use rquickjs::{Ctx, Exception, FromJs, Function, Object, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock, Mutex};
+use temporal_rs::error::ErrorKind;
+use temporal_rs::fields::{CalendarFields, ZonedDateTimeFields};
+use temporal_rs::options::{
+    DifferenceSettings, Disambiguation, OffsetDisambiguation, Overflow, RelativeTo,
+    RoundingIncrement, RoundingMode, RoundingOptions, ToStringRoundingOptions, Unit,
+};
+use temporal_rs::parsers::Precision;
+use temporal_rs::partial::{PartialDate, PartialDuration, PartialTime, PartialZonedDateTime};
+use temporal_rs::provider::{
+    CandidateEpochNanoseconds, EpochNanosecondsAndOffset, GapEntryOffsets, TimeZoneId,
+    TimeZoneProvider, TransitionDirection, UtcOffsetSeconds,
+};
+use temporal_rs::unix_time::EpochNanoseconds;
+use temporal_rs::{
+    Calendar, Duration, Instant, MonthCode, PlainDate, PlainDateTime, PlainMonthDay, PlainTime,
+    PlainYearMonth, TemporalError, TimeZone, TinyAsciiStr, UtcOffset, ZonedDateTime,
+};
+use timezone_provider::provider::{IsoDateTime, ResolvedId, TimeZoneNormalizer};
+use timezone_provider::{CompiledNormalizer, TimeZoneProviderError};
+
+use native_module::{
+    JsDuration, JsInstant, JsPlainDate, JsPlainDateTime, JsPlainMonthDay, JsPlainTime,
+    JsPlainYearMonth, JsZonedDateTime,
+};
+
+// The Temporal types, implemented on temporal_rs. The JS module only assembles them into the
+// `Temporal` namespace and adds what needs the rest of the runtime, like `toLocaleString`.
+#[rquickjs::module(rename = "camelCase")]
+pub mod native_module {
+    use super::{
+        OrThrow, PROVIDER, bigint_from_i128, calendar_from_value, date_fields, difference_settings,
+        disambiguation_option, duration_from_value, fraction_digits_option, get_options,
+        instant_from_value, ordering, overflow_option, plain_date_from_value,
+        plain_date_time_from_value, plain_month_day_from_value, plain_time_from_value,
+        plain_year_month_from_value, reject_temporal_like, relative_to_option, rounding_options,
+        string_option, time_fields, time_zone_from_value, to_integer_if_integral,
+        to_integer_with_truncation, to_string_options, unit_option, with_fields, year_month_fields,
+        zoned_date_time_from_value,
+    };
+    use rquickjs::class::Trace;
+    use rquickjs::prelude::*;
+    use rquickjs::{BigInt, Ctx, Exception, JsLifetime, Object, Result, Value};
+    use temporal_rs::options::{
+        DisplayCalendar, DisplayOffset, DisplayTimeZone, OffsetDisambiguation, Overflow, Unit,
+    };
+    use temporal_rs::provider::TransitionDirection;
+    use temporal_rs::{
+        Calendar, Duration, Instant, PlainDate, PlainDateTime, PlainMonthDay, PlainTime,
+        PlainYearMonth, TimeZone, ZonedDateTime,
+    };
+
+    // Returns the current time from the WASI wall clock, or from the virtual clock with the
+    // `deterministic` feature
+    #[rquickjs::function]
+    pub fn now_instant(ctx: Ctx<'_>) -> rquickjs::Result<JsInstant> {
+        let ns = crate::builtin::deterministic::wall_clock_ns();
+        Ok(JsInstant {
+            inner: Instant::try_new(ns).or_throw(&ctx)?,
+        })
+    }
+
+    // Returns the milliseconds since the epoch of a plain date or time read as UTC wall-clock
+    // time, which is how `toLocaleString` formats them with Intl.DateTimeFormat
+    #[rquickjs::function]
+    pub fn utc_epoch_milliseconds(value: Value<'_>) -> Option<f64> {
+        let object = value.as_object()?;
+        let ns = if let Some(date) = object.as_class::<JsPlainDate>() {
+            date.borrow().inner.epoch_ns_for_utc()
+        } else if let Some(time) = object.as_class::<JsPlainTime>() {
+            time.borrow().inner.epoch_ns_for_utc()
+        } else if let Some(date_time) = object.as_class::<JsPlainDateTime>() {
+            date_time.borrow().inner.epoch_ns_for_utc()
+        } else if let Some(year_month) = object.as_class::<JsPlainYearMonth>() {
+            year_month.borrow().inner.epoch_ns_for_utc()
+        } else if let Some(month_day) = object.as_class::<JsPlainMonthDay>() {
+            month_day.borrow().inner.epoch_ns_for_utc()
+        } else {
+            return None;
+        };
+        Some(ns.0.div_euclid(1_000_000) as f64)
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "Instant")]
+    pub struct JsInstant {
+        #[qjs(skip_trace)]
+        pub(super) inner: Instant,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsInstant {
+        #[qjs(constructor)]
+        pub fn new<'js>(ctx: Ctx<'js>, epoch_nanoseconds: Opt<Value<'js>>) -> Result<Self> {
+            let ns = super::to_bigint_i128(&ctx, super::opt_value(&ctx, epoch_nanoseconds))?;
+            Ok(Self {
+                inner: Instant::try_new(ns).or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(ctx: Ctx<'js>, item: Opt<Value<'js>>) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            Ok(Self {
+                inner: instant_from_value(&ctx, item)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from_epoch_milliseconds<'js>(
+            ctx: Ctx<'js>,
+            epoch_milliseconds: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let ms = super::to_number(&ctx, &super::opt_value(&ctx, epoch_milliseconds))?;
+            if !ms.is_finite() || ms.trunc() != ms {
+                return Err(Exception::throw_range(
+                    &ctx,
+                    "epochMilliseconds must be an integer",
+                ));
+            }
+            Ok(Self {
+                inner: Instant::from_epoch_milliseconds(ms as i64).or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from_epoch_nanoseconds<'js>(
+            ctx: Ctx<'js>,
+            epoch_nanoseconds: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            Self::new(ctx, epoch_nanoseconds)
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(ctx: Ctx<'js>, one: Value<'js>, two: Value<'js>) -> Result<i32> {
+            let one = instant_from_value(&ctx, one)?;
+            let two = instant_from_value(&ctx, two)?;
+            Ok(ordering(one.cmp(&two)))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn epoch_milliseconds(&self) -> f64 {
+            self.inner.epoch_milliseconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn epoch_nanoseconds<'js>(&self, ctx: Ctx<'js>) -> Result<BigInt<'js>> {
+            bigint_from_i128(&ctx, self.inner.as_i128())
+        }
+
+        pub fn add<'js>(&self, ctx: Ctx<'js>, duration: Value<'js>) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            Ok(Self {
+                inner: self.inner.add(&duration).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(&self, ctx: Ctx<'js>, duration: Value<'js>) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            Ok(Self {
+                inner: self.inner.subtract(&duration).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn until<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = instant_from_value(&ctx, other)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.until(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn since<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = instant_from_value(&ctx, other)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.since(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn round<'js>(&self, ctx: Ctx<'js>, round_to: Opt<Value<'js>>) -> Result<Self> {
+            let options = rounding_options(&ctx, round_to)?;
+            Ok(Self {
+                inner: self.inner.round(options).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            Ok(self.inner == instant_from_value(&ctx, other)?)
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let rounding = to_string_options(&ctx, &options)?;
+            let time_zone = match &options {
+                Some(options) => {
+                    let value: Value = options.get("timeZone")?;
+                    if value.is_undefined() {
+                        None
+                    } else {
+                        Some(time_zone_from_value(&ctx, value)?)
+                    }
+                }
+                None => None,
+            };
+            self.inner
+                .to_ixdtf_string_with_provider(time_zone, rounding, &PROVIDER)
+                .or_throw(&ctx)
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self, ctx: Ctx<'_>) -> Result<String> {
+            self.inner
+                .to_ixdtf_string_with_provider(None, Default::default(), &PROVIDER)
+                .or_throw(&ctx)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+
+        #[qjs(rename = "toZonedDateTimeISO")]
+        pub fn to_zoned_date_time_iso<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            time_zone: Value<'js>,
+        ) -> Result<JsZonedDateTime> {
+            let time_zone = time_zone_from_value(&ctx, time_zone)?;
+            Ok(JsZonedDateTime {
+                inner: self
+                    .inner
+                    .to_zoned_date_time_iso_with_provider(time_zone, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "PlainDate")]
+    pub struct JsPlainDate {
+        #[qjs(skip_trace)]
+        pub(super) inner: PlainDate,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsPlainDate {
+        #[qjs(constructor)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            iso_year: Opt<Value<'js>>,
+            iso_month: Opt<Value<'js>>,
+            iso_day: Opt<Value<'js>>,
+            calendar: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let year = super::integer_argument(&ctx, iso_year)?;
+            let month = super::integer_argument(&ctx, iso_month)?;
+            let day = super::integer_argument(&ctx, iso_day)?;
+            let calendar = super::calendar_argument(&ctx, calendar)?;
+            Ok(Self {
+                inner: PlainDate::try_new(
+                    super::to_i32(&ctx, year)?,
+                    super::to_u8(&ctx, month)?,
+                    super::to_u8(&ctx, day)?,
+                    calendar,
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(
+            ctx: Ctx<'js>,
+            item: Opt<Value<'js>>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            let options = get_options(&ctx, options)?;
+            Ok(Self {
+                inner: plain_date_from_value(&ctx, item, &options)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(ctx: Ctx<'js>, one: Value<'js>, two: Value<'js>) -> Result<i32> {
+            let one = plain_date_from_value(&ctx, one, &None)?;
+            let two = plain_date_from_value(&ctx, two, &None)?;
+            Ok(ordering(one.compare_iso(&two)))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn calendar_id(&self) -> &'static str {
+            self.inner.calendar().identifier()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era(&self) -> Option<String> {
+            self.inner.era().map(|era| era.to_string())
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era_year(&self) -> Option<i32> {
+            self.inner.era_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year(&self) -> i32 {
+            self.inner.year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month(&self) -> u8 {
+            self.inner.month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month_code(&self) -> String {
+            self.inner.month_code().as_str().to_string()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day(&self) -> u8 {
+            self.inner.day()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day_of_week(&self) -> u16 {
+            self.inner.day_of_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day_of_year(&self) -> u16 {
+            self.inner.day_of_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn week_of_year(&self) -> Option<u8> {
+            self.inner.week_of_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year_of_week(&self) -> Option<i32> {
+            self.inner.year_of_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_week(&self) -> u16 {
+            self.inner.days_in_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_month(&self) -> u16 {
+            self.inner.days_in_month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_year(&self) -> u16 {
+            self.inner.days_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn months_in_year(&self) -> u16 {
+            self.inner.months_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn in_leap_year(&self) -> bool {
+            self.inner.in_leap_year()
+        }
+
+        pub fn to_plain_year_month(&self, ctx: Ctx<'_>) -> Result<JsPlainYearMonth> {
+            Ok(JsPlainYearMonth {
+                inner: self.inner.to_plain_year_month().or_throw(&ctx)?,
+            })
+        }
+
+        pub fn to_plain_month_day(&self, ctx: Ctx<'_>) -> Result<JsPlainMonthDay> {
+            Ok(JsPlainMonthDay {
+                inner: self.inner.to_plain_month_day().or_throw(&ctx)?,
+            })
+        }
+
+        pub fn add<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self.inner.add(&duration, overflow).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self.inner.subtract(&duration, overflow).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            temporal_date_like: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = reject_temporal_like(&ctx, temporal_date_like)?;
+            let fields = with_fields(&ctx, &item, self.inner.calendar(), date_fields())?;
+            let options = get_options(&ctx, options)?;
+            let overflow = overflow_option(&ctx, &options)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with(fields.calendar_fields, overflow)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with_calendar<'js>(&self, ctx: Ctx<'js>, calendar: Value<'js>) -> Result<Self> {
+            let calendar = calendar_from_value(&ctx, calendar)?;
+            Ok(Self {
+                inner: self.inner.with_calendar(calendar),
+            })
+        }
+
+        pub fn until<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_date_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.until(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn since<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_date_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.since(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            Ok(self.inner == plain_date_from_value(&ctx, other, &None)?)
+        }
+
+        pub fn to_plain_date_time<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            time: Opt<Value<'js>>,
+        ) -> Result<JsPlainDateTime> {
+            let time = super::optional_plain_time(&ctx, time)?;
+            Ok(JsPlainDateTime {
+                inner: self.inner.to_plain_date_time(time).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn to_zoned_date_time<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            item: Value<'js>,
+        ) -> Result<JsZonedDateTime> {
+            let (time_zone, time) = match item.as_object() {
+                Some(object) if object.as_class::<JsZonedDateTime>().is_none() => {
+                    let time_zone: Value = object.get("timeZone")?;
+                    if time_zone.is_undefined() {
+                        (time_zone_from_value(&ctx, item)?, None)
+                    } else {
+                        let time_zone = time_zone_from_value(&ctx, time_zone)?;
+                        let time: Value = object.get("plainTime")?;
+                        (
+                            time_zone,
+                            super::optional_plain_time(&ctx, Opt(Some(time)))?,
+                        )
+                    }
+                }
+                _ => (time_zone_from_value(&ctx, item)?, None),
+            };
+            Ok(JsZonedDateTime {
+                inner: self
+                    .inner
+                    .to_zoned_date_time_with_provider(time_zone, time, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let calendar_name = super::calendar_name_option(&ctx, &options)?;
+            Ok(self.inner.to_ixdtf_string(calendar_name))
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self) -> String {
+            self.inner.to_ixdtf_string(DisplayCalendar::Auto)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "PlainTime")]
+    pub struct JsPlainTime {
+        #[qjs(skip_trace)]
+        pub(super) inner: PlainTime,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsPlainTime {
+        #[qjs(constructor)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            hour: Opt<Value<'js>>,
+            minute: Opt<Value<'js>>,
+            second: Opt<Value<'js>>,
+            millisecond: Opt<Value<'js>>,
+            microsecond: Opt<Value<'js>>,
+            nanosecond: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let hour = super::integer_argument(&ctx, hour)?;
+            let minute = super::integer_argument(&ctx, minute)?;
+            let second = super::integer_argument(&ctx, second)?;
+            let millisecond = super::integer_argument(&ctx, millisecond)?;
+            let microsecond = super::integer_argument(&ctx, microsecond)?;
+            let nanosecond = super::integer_argument(&ctx, nanosecond)?;
+            Ok(Self {
+                inner: PlainTime::try_new(
+                    super::to_u8(&ctx, hour)?,
+                    super::to_u8(&ctx, minute)?,
+                    super::to_u8(&ctx, second)?,
+                    super::to_u16(&ctx, millisecond)?,
+                    super::to_u16(&ctx, microsecond)?,
+                    super::to_u16(&ctx, nanosecond)?,
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(
+            ctx: Ctx<'js>,
+            item: Opt<Value<'js>>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            let options = get_options(&ctx, options)?;
+            Ok(Self {
+                inner: plain_time_from_value(&ctx, item, &options)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(ctx: Ctx<'js>, one: Value<'js>, two: Value<'js>) -> Result<i32> {
+            let one = plain_time_from_value(&ctx, one, &None)?;
+            let two = plain_time_from_value(&ctx, two, &None)?;
+            Ok(ordering(one.cmp(&two)))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn hour(&self) -> u8 {
+            self.inner.hour()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn minute(&self) -> u8 {
+            self.inner.minute()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn second(&self) -> u8 {
+            self.inner.second()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn millisecond(&self) -> u16 {
+            self.inner.millisecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn microsecond(&self) -> u16 {
+            self.inner.microsecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn nanosecond(&self) -> u16 {
+            self.inner.nanosecond()
+        }
+
+        pub fn add<'js>(&self, ctx: Ctx<'js>, duration: Value<'js>) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            Ok(Self {
+                inner: self.inner.add(&duration).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(&self, ctx: Ctx<'js>, duration: Value<'js>) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            Ok(Self {
+                inner: self.inner.subtract(&duration).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            temporal_time_like: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = reject_temporal_like(&ctx, temporal_time_like)?;
+            let fields = with_fields(&ctx, &item, &Calendar::ISO, time_fields())?;
+            let options = get_options(&ctx, options)?;
+            let overflow = overflow_option(&ctx, &options)?;
+            fields.check_overflow(&ctx, overflow)?;
+            Ok(Self {
+                inner: self.inner.with(fields.time, overflow).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn until<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_time_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.until(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn since<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_time_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.since(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn round<'js>(&self, ctx: Ctx<'js>, round_to: Opt<Value<'js>>) -> Result<Self> {
+            let options = rounding_options(&ctx, round_to)?;
+            Ok(Self {
+                inner: self.inner.round(options).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            Ok(self.inner == plain_time_from_value(&ctx, other, &None)?)
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let rounding = to_string_options(&ctx, &options)?;
+            self.inner.to_ixdtf_string(rounding).or_throw(&ctx)
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self, ctx: Ctx<'_>) -> Result<String> {
+            self.inner
+                .to_ixdtf_string(Default::default())
+                .or_throw(&ctx)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "PlainDateTime")]
+    pub struct JsPlainDateTime {
+        #[qjs(skip_trace)]
+        pub(super) inner: PlainDateTime,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsPlainDateTime {
+        #[qjs(constructor)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            iso_year: Opt<Value<'js>>,
+            iso_month: Opt<Value<'js>>,
+            iso_day: Opt<Value<'js>>,
+            hour: Opt<Value<'js>>,
+            minute: Opt<Value<'js>>,
+            second: Opt<Value<'js>>,
+            millisecond: Opt<Value<'js>>,
+            microsecond: Opt<Value<'js>>,
+            nanosecond: Opt<Value<'js>>,
+            calendar: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let year = super::integer_argument(&ctx, iso_year)?;
+            let month = super::integer_argument(&ctx, iso_month)?;
+            let day = super::integer_argument(&ctx, iso_day)?;
+            let hour = super::integer_argument(&ctx, hour)?;
+            let minute = super::integer_argument(&ctx, minute)?;
+            let second = super::integer_argument(&ctx, second)?;
+            let millisecond = super::integer_argument(&ctx, millisecond)?;
+            let microsecond = super::integer_argument(&ctx, microsecond)?;
+            let nanosecond = super::integer_argument(&ctx, nanosecond)?;
+            let calendar = super::calendar_argument(&ctx, calendar)?;
+            Ok(Self {
+                inner: PlainDateTime::try_new(
+                    super::to_i32(&ctx, year)?,
+                    super::to_u8(&ctx, month)?,
+                    super::to_u8(&ctx, day)?,
+                    super::to_u8(&ctx, hour)?,
+                    super::to_u8(&ctx, minute)?,
+                    super::to_u8(&ctx, second)?,
+                    super::to_u16(&ctx, millisecond)?,
+                    super::to_u16(&ctx, microsecond)?,
+                    super::to_u16(&ctx, nanosecond)?,
+                    calendar,
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(
+            ctx: Ctx<'js>,
+            item: Opt<Value<'js>>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            let options = get_options(&ctx, options)?;
+            Ok(Self {
+                inner: plain_date_time_from_value(&ctx, item, &options)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(ctx: Ctx<'js>, one: Value<'js>, two: Value<'js>) -> Result<i32> {
+            let one = plain_date_time_from_value(&ctx, one, &None)?;
+            let two = plain_date_time_from_value(&ctx, two, &None)?;
+            Ok(ordering(one.compare_iso(&two)))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn calendar_id(&self) -> &'static str {
+            self.inner.calendar().identifier()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era(&self) -> Option<String> {
+            self.inner.era().map(|era| era.to_string())
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era_year(&self) -> Option<i32> {
+            self.inner.era_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year(&self) -> i32 {
+            self.inner.year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month(&self) -> u8 {
+            self.inner.month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month_code(&self) -> String {
+            self.inner.month_code().as_str().to_string()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day(&self) -> u8 {
+            self.inner.day()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn hour(&self) -> u8 {
+            self.inner.hour()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn minute(&self) -> u8 {
+            self.inner.minute()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn second(&self) -> u8 {
+            self.inner.second()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn millisecond(&self) -> u16 {
+            self.inner.millisecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn microsecond(&self) -> u16 {
+            self.inner.microsecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn nanosecond(&self) -> u16 {
+            self.inner.nanosecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day_of_week(&self) -> u16 {
+            self.inner.day_of_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day_of_year(&self) -> u16 {
+            self.inner.day_of_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn week_of_year(&self) -> Option<u8> {
+            self.inner.week_of_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year_of_week(&self) -> Option<i32> {
+            self.inner.year_of_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_week(&self) -> u16 {
+            self.inner.days_in_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_month(&self) -> u16 {
+            self.inner.days_in_month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_year(&self) -> u16 {
+            self.inner.days_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn months_in_year(&self) -> u16 {
+            self.inner.months_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn in_leap_year(&self) -> bool {
+            self.inner.in_leap_year()
+        }
+
+        pub fn with<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            temporal_date_time_like: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = reject_temporal_like(&ctx, temporal_date_time_like)?;
+            let names = super::FieldNames {
+                time: true,
+                ..date_fields()
+            };
+            let fields = with_fields(&ctx, &item, self.inner.calendar(), names)?;
+            let options = get_options(&ctx, options)?;
+            let overflow = overflow_option(&ctx, &options)?;
+            fields.check_overflow(&ctx, overflow)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with(fields.date_time_fields(), overflow)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with_plain_time<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            plain_time: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let time = super::optional_plain_time(&ctx, plain_time)?;
+            Ok(Self {
+                inner: self.inner.with_time(time).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with_calendar<'js>(&self, ctx: Ctx<'js>, calendar: Value<'js>) -> Result<Self> {
+            let calendar = calendar_from_value(&ctx, calendar)?;
+            Ok(Self {
+                inner: self.inner.with_calendar(calendar),
+            })
+        }
+
+        pub fn add<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self.inner.add(&duration, overflow).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self.inner.subtract(&duration, overflow).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn until<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_date_time_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.until(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn since<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_date_time_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.since(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn round<'js>(&self, ctx: Ctx<'js>, round_to: Opt<Value<'js>>) -> Result<Self> {
+            let options = rounding_options(&ctx, round_to)?;
+            Ok(Self {
+                inner: self.inner.round(options).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            Ok(self.inner == plain_date_time_from_value(&ctx, other, &None)?)
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let calendar_name = super::calendar_name_option(&ctx, &options)?;
+            let rounding = to_string_options(&ctx, &options)?;
+            self.inner
+                .to_ixdtf_string(rounding, calendar_name)
+                .or_throw(&ctx)
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self, ctx: Ctx<'_>) -> Result<String> {
+            self.inner
+                .to_ixdtf_string(Default::default(), DisplayCalendar::Auto)
+                .or_throw(&ctx)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+
+        pub fn to_zoned_date_time<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            time_zone: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsZonedDateTime> {
+            let time_zone = time_zone_from_value(&ctx, time_zone)?;
+            let options = get_options(&ctx, options)?;
+            let disambiguation = disambiguation_option(&ctx, &options)?.unwrap_or_default();
+            Ok(JsZonedDateTime {
+                inner: self
+                    .inner
+                    .to_zoned_date_time_with_provider(time_zone, disambiguation, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn to_plain_date(&self) -> JsPlainDate {
+            JsPlainDate {
+                inner: self.inner.to_plain_date(),
+            }
+        }
+
+        pub fn to_plain_time(&self) -> JsPlainTime {
+            JsPlainTime {
+                inner: self.inner.to_plain_time(),
+            }
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "PlainYearMonth")]
+    pub struct JsPlainYearMonth {
+        #[qjs(skip_trace)]
+        pub(super) inner: PlainYearMonth,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsPlainYearMonth {
+        #[qjs(constructor)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            iso_year: Opt<Value<'js>>,
+            iso_month: Opt<Value<'js>>,
+            calendar: Opt<Value<'js>>,
+            reference_iso_day: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let year = super::integer_argument(&ctx, iso_year)?;
+            let month = super::integer_argument(&ctx, iso_month)?;
+            let calendar = super::calendar_argument(&ctx, calendar)?;
+            let day = match reference_iso_day.0 {
+                Some(day) if !day.is_undefined() => {
+                    Some(super::to_u8(&ctx, to_integer_with_truncation(&ctx, &day)?)?)
+                }
+                _ => None,
+            };
+            Ok(Self {
+                inner: PlainYearMonth::try_new(
+                    super::to_i32(&ctx, year)?,
+                    super::to_u8(&ctx, month)?,
+                    day,
+                    calendar,
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(
+            ctx: Ctx<'js>,
+            item: Opt<Value<'js>>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            let options = get_options(&ctx, options)?;
+            Ok(Self {
+                inner: plain_year_month_from_value(&ctx, item, &options)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(ctx: Ctx<'js>, one: Value<'js>, two: Value<'js>) -> Result<i32> {
+            let one = plain_year_month_from_value(&ctx, one, &None)?;
+            let two = plain_year_month_from_value(&ctx, two, &None)?;
+            Ok(ordering(one.compare_iso(&two)))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn calendar_id(&self) -> &'static str {
+            self.inner.calendar().identifier()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era(&self) -> Option<String> {
+            self.inner.era().map(|era| era.to_string())
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era_year(&self) -> Option<i32> {
+            self.inner.era_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year(&self) -> i32 {
+            self.inner.year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month(&self) -> u8 {
+            self.inner.month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month_code(&self) -> String {
+            self.inner.month_code().as_str().to_string()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_year(&self) -> u16 {
+            self.inner.days_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_month(&self) -> u16 {
+            self.inner.days_in_month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn months_in_year(&self) -> u16 {
+            self.inner.months_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn in_leap_year(&self) -> bool {
+            self.inner.in_leap_year()
+        }
+
+        pub fn with<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            temporal_year_month_like: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = reject_temporal_like(&ctx, temporal_year_month_like)?;
+            let fields = with_fields(&ctx, &item, self.inner.calendar(), year_month_fields())?;
+            let options = get_options(&ctx, options)?;
+            let overflow = overflow_option(&ctx, &options)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with(fields.calendar_fields.into(), overflow)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn add<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .add(&duration, overflow.unwrap_or_default())
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .subtract(&duration, overflow.unwrap_or_default())
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn until<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_year_month_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.until(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn since<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = plain_year_month_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self.inner.since(&other, settings).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            Ok(self.inner == plain_year_month_from_value(&ctx, other, &None)?)
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let calendar_name = super::calendar_name_option(&ctx, &options)?;
+            Ok(self.inner.to_ixdtf_string(calendar_name))
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self) -> String {
+            self.inner.to_ixdtf_string(DisplayCalendar::Auto)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+
+        pub fn to_plain_date<'js>(&self, ctx: Ctx<'js>, item: Value<'js>) -> Result<JsPlainDate> {
+            let Some(item) = item.into_object() else {
+                return Err(Exception::throw_type(&ctx, "Expected an object with a day"));
+            };
+            let names = super::FieldNames {
+                day: true,
+                ..Default::default()
+            };
+            let fields = super::prepare_fields(&ctx, &item, self.inner.calendar(), names)?;
+            Ok(JsPlainDate {
+                inner: self
+                    .inner
+                    .to_plain_date(Some(fields.calendar_fields))
+                    .or_throw(&ctx)?,
+            })
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "PlainMonthDay")]
+    pub struct JsPlainMonthDay {
+        #[qjs(skip_trace)]
+        pub(super) inner: PlainMonthDay,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsPlainMonthDay {
+        #[qjs(constructor)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            iso_month: Opt<Value<'js>>,
+            iso_day: Opt<Value<'js>>,
+            calendar: Opt<Value<'js>>,
+            reference_iso_year: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let month = super::integer_argument(&ctx, iso_month)?;
+            let day = super::integer_argument(&ctx, iso_day)?;
+            let calendar = super::calendar_argument(&ctx, calendar)?;
+            let year = match reference_iso_year.0 {
+                Some(year) if !year.is_undefined() => Some(super::to_i32(
+                    &ctx,
+                    to_integer_with_truncation(&ctx, &year)?,
+                )?),
+                _ => None,
+            };
+            Ok(Self {
+                inner: PlainMonthDay::new_with_overflow(
+                    super::to_u8(&ctx, month)?,
+                    super::to_u8(&ctx, day)?,
+                    calendar,
+                    Overflow::Reject,
+                    year,
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(
+            ctx: Ctx<'js>,
+            item: Opt<Value<'js>>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            let options = get_options(&ctx, options)?;
+            Ok(Self {
+                inner: plain_month_day_from_value(&ctx, item, &options)?,
+            })
+        }
+
+        #[qjs(get, configurable)]
+        pub fn calendar_id(&self) -> &'static str {
+            self.inner.calendar_id()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month_code(&self) -> String {
+            self.inner.month_code().as_str().to_string()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day(&self) -> u8 {
+            self.inner.day()
+        }
+
+        pub fn with<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            temporal_month_day_like: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = reject_temporal_like(&ctx, temporal_month_day_like)?;
+            let fields = with_fields(&ctx, &item, self.inner.calendar(), date_fields())?;
+            let options = get_options(&ctx, options)?;
+            let overflow = overflow_option(&ctx, &options)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with(fields.calendar_fields, overflow)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            Ok(self.inner == plain_month_day_from_value(&ctx, other, &None)?)
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let calendar_name = super::calendar_name_option(&ctx, &options)?;
+            Ok(self.inner.to_ixdtf_string(calendar_name))
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self) -> String {
+            self.inner.to_ixdtf_string(DisplayCalendar::Auto)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+
+        pub fn to_plain_date<'js>(&self, ctx: Ctx<'js>, item: Value<'js>) -> Result<JsPlainDate> {
+            let Some(item) = item.into_object() else {
+                return Err(Exception::throw_type(
+                    &ctx,
+                    "Expected an object with a year",
+                ));
+            };
+            let names = super::FieldNames {
+                year: true,
+                ..Default::default()
+            };
+            let fields = super::prepare_fields(&ctx, &item, self.inner.calendar(), names)?;
+            Ok(JsPlainDate {
+                inner: self
+                    .inner
+                    .to_plain_date(Some(fields.calendar_fields))
+                    .or_throw(&ctx)?,
+            })
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "ZonedDateTime")]
+    pub struct JsZonedDateTime {
+        #[qjs(skip_trace)]
+        pub(super) inner: ZonedDateTime,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsZonedDateTime {
+        #[qjs(constructor)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            epoch_nanoseconds: Opt<Value<'js>>,
+            time_zone: Opt<Value<'js>>,
+            calendar: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let ns = super::to_bigint_i128(&ctx, super::opt_value(&ctx, epoch_nanoseconds))?;
+            let time_zone = super::opt_value(&ctx, time_zone);
+            let Some(time_zone) = time_zone.as_string() else {
+                return Err(Exception::throw_type(&ctx, "Time zone must be a string"));
+            };
+            let time_zone =
+                TimeZone::try_from_identifier_str_with_provider(&time_zone.to_string()?, &PROVIDER)
+                    .or_throw(&ctx)?;
+            let calendar = super::calendar_argument(&ctx, calendar)?;
+            Ok(Self {
+                inner: ZonedDateTime::try_new_with_provider(ns, time_zone, calendar, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(
+            ctx: Ctx<'js>,
+            item: Opt<Value<'js>>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            let options = get_options(&ctx, options)?;
+            Ok(Self {
+                inner: zoned_date_time_from_value(&ctx, item, &options)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(ctx: Ctx<'js>, one: Value<'js>, two: Value<'js>) -> Result<i32> {
+            let one = zoned_date_time_from_value(&ctx, one, &None)?;
+            let two = zoned_date_time_from_value(&ctx, two, &None)?;
+            Ok(ordering(one.compare_instant(&two)))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn calendar_id(&self) -> &'static str {
+            self.inner.calendar().identifier()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn time_zone_id(&self, ctx: Ctx<'_>) -> Result<String> {
+            self.inner
+                .time_zone()
+                .identifier_with_provider(&PROVIDER)
+                .or_throw(&ctx)
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era(&self) -> Option<String> {
+            self.inner.era().map(|era| era.to_string())
+        }
+
+        #[qjs(get, configurable)]
+        pub fn era_year(&self) -> Option<i32> {
+            self.inner.era_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year(&self) -> i32 {
+            self.inner.year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month(&self) -> u8 {
+            self.inner.month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn month_code(&self) -> String {
+            self.inner.month_code().as_str().to_string()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day(&self) -> u8 {
+            self.inner.day()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn hour(&self) -> u8 {
+            self.inner.hour()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn minute(&self) -> u8 {
+            self.inner.minute()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn second(&self) -> u8 {
+            self.inner.second()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn millisecond(&self) -> u16 {
+            self.inner.millisecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn microsecond(&self) -> u16 {
+            self.inner.microsecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn nanosecond(&self) -> u16 {
+            self.inner.nanosecond()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn epoch_milliseconds(&self) -> f64 {
+            self.inner.epoch_milliseconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn epoch_nanoseconds<'js>(&self, ctx: Ctx<'js>) -> Result<BigInt<'js>> {
+            bigint_from_i128(&ctx, self.inner.epoch_nanoseconds().as_i128())
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day_of_week(&self) -> u16 {
+            self.inner.day_of_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn day_of_year(&self) -> u16 {
+            self.inner.day_of_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn week_of_year(&self) -> Option<u8> {
+            self.inner.week_of_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn year_of_week(&self) -> Option<i32> {
+            self.inner.year_of_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn hours_in_day(&self, ctx: Ctx<'_>) -> Result<f64> {
+            self.inner
+                .hours_in_day_with_provider(&PROVIDER)
+                .or_throw(&ctx)
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_week(&self) -> u16 {
+            self.inner.days_in_week()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_month(&self) -> u16 {
+            self.inner.days_in_month()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days_in_year(&self) -> u16 {
+            self.inner.days_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn months_in_year(&self) -> u16 {
+            self.inner.months_in_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn in_leap_year(&self) -> bool {
+            self.inner.in_leap_year()
+        }
+
+        #[qjs(get, configurable)]
+        pub fn offset_nanoseconds(&self) -> f64 {
+            self.inner.offset_nanoseconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn offset(&self) -> String {
+            self.inner.offset()
+        }
+
+        pub fn with<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            temporal_zoned_date_time_like: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let item = reject_temporal_like(&ctx, temporal_zoned_date_time_like)?;
+            let names = super::FieldNames {
+                time: true,
+                offset: true,
+                ..date_fields()
+            };
+            let fields = with_fields(&ctx, &item, self.inner.calendar(), names)?;
+            let options = get_options(&ctx, options)?;
+            let disambiguation = disambiguation_option(&ctx, &options)?;
+            let offset = super::offset_option(&ctx, &options)?;
+            let overflow = overflow_option(&ctx, &options)?;
+            fields.check_overflow(&ctx, overflow)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with_with_provider(
+                        fields.zoned_date_time_fields(),
+                        disambiguation,
+                        offset.or(Some(OffsetDisambiguation::Prefer)),
+                        overflow,
+                        &PROVIDER,
+                    )
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with_plain_time<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            plain_time: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let time = super::optional_plain_time(&ctx, plain_time)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with_plain_time_and_provider(time, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with_time_zone<'js>(&self, ctx: Ctx<'js>, time_zone: Value<'js>) -> Result<Self> {
+            let time_zone = time_zone_from_value(&ctx, time_zone)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .with_time_zone_with_provider(time_zone, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn with_calendar<'js>(&self, ctx: Ctx<'js>, calendar: Value<'js>) -> Result<Self> {
+            let calendar = calendar_from_value(&ctx, calendar)?;
+            Ok(Self {
+                inner: self.inner.with_calendar(calendar),
+            })
+        }
+
+        pub fn add<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .add_with_provider(&duration, overflow, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            duration: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let duration = duration_from_value(&ctx, duration)?;
+            let overflow = overflow_option(&ctx, &get_options(&ctx, options)?)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .subtract_with_provider(&duration, overflow, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn until<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = zoned_date_time_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self
+                    .inner
+                    .until_with_provider(&other, settings, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn since<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            other: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<JsDuration> {
+            let other = zoned_date_time_from_value(&ctx, other, &None)?;
+            let settings = difference_settings(&ctx, &get_options(&ctx, options)?)?;
+            Ok(JsDuration {
+                inner: self
+                    .inner
+                    .since_with_provider(&other, settings, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn round<'js>(&self, ctx: Ctx<'js>, round_to: Opt<Value<'js>>) -> Result<Self> {
+            let options = rounding_options(&ctx, round_to)?;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .round_with_provider(options, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn equals<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<bool> {
+            let other = zoned_date_time_from_value(&ctx, other, &None)?;
+            self.inner
+                .equals_with_provider(&other, &PROVIDER)
+                .or_throw(&ctx)
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let calendar_name = super::calendar_name_option(&ctx, &options)?;
+            let precision = fraction_digits_option(&ctx, &options)?;
+            let display_offset: Option<DisplayOffset> =
+                super::parse_option(&ctx, string_option(&ctx, &options, "offset")?, "offset")?;
+            let mut rounding = to_string_options(&ctx, &options)?;
+            rounding.precision = precision;
+            let display_time_zone: Option<DisplayTimeZone> = super::parse_option(
+                &ctx,
+                string_option(&ctx, &options, "timeZoneName")?,
+                "timeZoneName",
+            )?;
+            self.inner
+                .to_ixdtf_string_with_provider(
+                    display_offset.unwrap_or_default(),
+                    display_time_zone.unwrap_or_default(),
+                    calendar_name,
+                    rounding,
+                    &PROVIDER,
+                )
+                .or_throw(&ctx)
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self, ctx: Ctx<'_>) -> Result<String> {
+            self.inner
+                .to_ixdtf_string_with_provider(
+                    DisplayOffset::Auto,
+                    DisplayTimeZone::Auto,
+                    DisplayCalendar::Auto,
+                    Default::default(),
+                    &PROVIDER,
+                )
+                .or_throw(&ctx)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+
+        pub fn start_of_day(&self, ctx: Ctx<'_>) -> Result<Self> {
+            Ok(Self {
+                inner: self
+                    .inner
+                    .start_of_day_with_provider(&PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn get_time_zone_transition<'js>(
+            &self,
+            ctx: Ctx<'js>,
+            direction: Opt<Value<'js>>,
+        ) -> Result<Value<'js>> {
+            let direction = super::opt_value(&ctx, direction);
+            let options = if direction.is_string() {
+                let options = Object::new(ctx.clone())?;
+                options.set("direction", direction)?;
+                Some(options)
+            } else if direction.is_undefined() {
+                return Err(Exception::throw_type(&ctx, "direction is required"));
+            } else {
+                get_options(&ctx, Opt(Some(direction)))?
+            };
+            let direction: Option<TransitionDirection> = super::parse_option(
+                &ctx,
+                string_option(&ctx, &options, "direction")?,
+                "direction",
+            )?;
+            let Some(direction) = direction else {
+                return Err(Exception::throw_range(&ctx, "direction is required"));
+            };
+            match self
+                .inner
+                .get_time_zone_transition_with_provider(direction, &PROVIDER)
+                .or_throw(&ctx)?
+            {
+                Some(inner) => Self { inner }.into_js(&ctx),
+                None => Ok(Value::new_null(ctx)),
+            }
+        }
+
+        pub fn to_instant(&self) -> JsInstant {
+            JsInstant {
+                inner: self.inner.to_instant(),
+            }
+        }
+
+        pub fn to_plain_date(&self) -> JsPlainDate {
+            JsPlainDate {
+                inner: self.inner.to_plain_date(),
+            }
+        }
+
+        pub fn to_plain_time(&self) -> JsPlainTime {
+            JsPlainTime {
+                inner: self.inner.to_plain_time(),
+            }
+        }
+
+        pub fn to_plain_date_time(&self) -> JsPlainDateTime {
+            JsPlainDateTime {
+                inner: self.inner.to_plain_date_time(),
+            }
+        }
+    }
+
+    #[derive(JsLifetime, Trace)]
+    #[rquickjs::class(rename = "Duration")]
+    pub struct JsDuration {
+        #[qjs(skip_trace)]
+        pub(super) inner: Duration,
+    }
+
+    #[rquickjs::methods(rename_all = "camelCase")]
+    impl JsDuration {
+        #[qjs(constructor)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn new<'js>(
+            ctx: Ctx<'js>,
+            years: Opt<Value<'js>>,
+            months: Opt<Value<'js>>,
+            weeks: Opt<Value<'js>>,
+            days: Opt<Value<'js>>,
+            hours: Opt<Value<'js>>,
+            minutes: Opt<Value<'js>>,
+            seconds: Opt<Value<'js>>,
+            milliseconds: Opt<Value<'js>>,
+            microseconds: Opt<Value<'js>>,
+            nanoseconds: Opt<Value<'js>>,
+        ) -> Result<Self> {
+            let mut fields = [0.0; 10];
+            let values = [
+                years,
+                months,
+                weeks,
+                days,
+                hours,
+                minutes,
+                seconds,
+                milliseconds,
+                microseconds,
+                nanoseconds,
+            ];
+            for (field, value) in fields.iter_mut().zip(values) {
+                if let Some(value) = value.0.filter(|value| !value.is_undefined()) {
+                    *field = to_integer_if_integral(&ctx, &value)?;
+                }
+            }
+            let [
+                years,
+                months,
+                weeks,
+                days,
+                hours,
+                minutes,
+                seconds,
+                milliseconds,
+                microseconds,
+                nanoseconds,
+            ] = fields;
+            Ok(Self {
+                inner: Duration::new(
+                    years as i64,
+                    months as i64,
+                    weeks as i64,
+                    days as i64,
+                    hours as i64,
+                    minutes as i64,
+                    seconds as i64,
+                    milliseconds as i64,
+                    microseconds as i128,
+                    nanoseconds as i128,
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn from<'js>(ctx: Ctx<'js>, item: Opt<Value<'js>>) -> Result<Self> {
+            let item = super::opt_value(&ctx, item);
+            Ok(Self {
+                inner: duration_from_value(&ctx, item)?,
+            })
+        }
+
+        #[qjs(static)]
+        pub fn compare<'js>(
+            ctx: Ctx<'js>,
+            one: Value<'js>,
+            two: Value<'js>,
+            options: Opt<Value<'js>>,
+        ) -> Result<i32> {
+            let one = duration_from_value(&ctx, one)?;
+            let two = duration_from_value(&ctx, two)?;
+            let options = get_options(&ctx, options)?;
+            let relative_to = relative_to_option(&ctx, &options)?;
+            Ok(ordering(
+                one.compare_with_provider(&two, relative_to, &PROVIDER)
+                    .or_throw(&ctx)?,
+            ))
+        }
+
+        #[qjs(get, configurable)]
+        pub fn years(&self) -> f64 {
+            self.inner.years() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn months(&self) -> f64 {
+            self.inner.months() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn weeks(&self) -> f64 {
+            self.inner.weeks() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn days(&self) -> f64 {
+            self.inner.days() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn hours(&self) -> f64 {
+            self.inner.hours() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn minutes(&self) -> f64 {
+            self.inner.minutes() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn seconds(&self) -> f64 {
+            self.inner.seconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn milliseconds(&self) -> f64 {
+            self.inner.milliseconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn microseconds(&self) -> f64 {
+            self.inner.microseconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn nanoseconds(&self) -> f64 {
+            self.inner.nanoseconds() as f64
+        }
+
+        #[qjs(get, configurable)]
+        pub fn sign(&self) -> i32 {
+            self.inner.sign() as i32
+        }
+
+        #[qjs(get, configurable)]
+        pub fn blank(&self) -> bool {
+            self.inner.is_zero()
+        }
+
+        pub fn with<'js>(&self, ctx: Ctx<'js>, duration_like: Value<'js>) -> Result<Self> {
+            let Some(item) = duration_like.into_object() else {
+                return Err(Exception::throw_type(
+                    &ctx,
+                    "Expected a duration-like object",
+                ));
+            };
+            let partial = super::partial_duration(&ctx, &item)?;
+            let duration = &self.inner;
+            Ok(Self {
+                inner: Duration::new(
+                    partial.years.unwrap_or(duration.years()),
+                    partial.months.unwrap_or(duration.months()),
+                    partial.weeks.unwrap_or(duration.weeks()),
+                    partial.days.unwrap_or(duration.days()),
+                    partial.hours.unwrap_or(duration.hours()),
+                    partial.minutes.unwrap_or(duration.minutes()),
+                    partial.seconds.unwrap_or(duration.seconds()),
+                    partial.milliseconds.unwrap_or(duration.milliseconds()),
+                    partial.microseconds.unwrap_or(duration.microseconds()),
+                    partial.nanoseconds.unwrap_or(duration.nanoseconds()),
+                )
+                .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn negated(&self) -> Self {
+            Self {
+                inner: self.inner.negated(),
+            }
+        }
+
+        pub fn abs(&self) -> Self {
+            Self {
+                inner: self.inner.abs(),
+            }
+        }
+
+        pub fn add<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<Self> {
+            let other = duration_from_value(&ctx, other)?;
+            Ok(Self {
+                inner: self.inner.add(&other).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn subtract<'js>(&self, ctx: Ctx<'js>, other: Value<'js>) -> Result<Self> {
+            let other = duration_from_value(&ctx, other)?;
+            Ok(Self {
+                inner: self.inner.subtract(&other).or_throw(&ctx)?,
+            })
+        }
+
+        pub fn round<'js>(&self, ctx: Ctx<'js>, round_to: Opt<Value<'js>>) -> Result<Self> {
+            let round_to = super::opt_value(&ctx, round_to);
+            let options = super::string_or_options(&ctx, round_to, "smallestUnit")?;
+            let largest_unit = unit_option(&ctx, &options, "largestUnit")?;
+            let relative_to = relative_to_option(&ctx, &options)?;
+            let increment = super::rounding_increment_option(&ctx, &options)?;
+            let rounding_mode = super::rounding_mode_option(&ctx, &options)?;
+            let smallest_unit = unit_option(&ctx, &options, "smallestUnit")?;
+            if largest_unit.is_none() && smallest_unit.is_none() {
+                return Err(Exception::throw_range(
+                    &ctx,
+                    "One of smallestUnit or largestUnit is required",
+                ));
+            }
+            let mut options = temporal_rs::options::RoundingOptions::default();
+            options.largest_unit = largest_unit;
+            options.smallest_unit = smallest_unit;
+            options.rounding_mode = rounding_mode;
+            options.increment = increment;
+            Ok(Self {
+                inner: self
+                    .inner
+                    .round_with_provider(options, relative_to, &PROVIDER)
+                    .or_throw(&ctx)?,
+            })
+        }
+
+        pub fn total<'js>(&self, ctx: Ctx<'js>, total_of: Opt<Value<'js>>) -> Result<f64> {
+            let total_of = super::opt_value(&ctx, total_of);
+            if total_of.is_undefined() {
+                return Err(Exception::throw_type(&ctx, "Options are required"));
+            }
+            let options = super::string_or_options(&ctx, total_of, "unit")?;
+            let relative_to = relative_to_option(&ctx, &options)?;
+            let Some(unit): Option<Unit> = unit_option(&ctx, &options, "unit")? else {
+                return Err(Exception::throw_range(&ctx, "unit is required"));
+            };
+            Ok(self
+                .inner
+                .total_with_provider(unit, relative_to, &PROVIDER)
+                .or_throw(&ctx)?
+                .as_inner())
+        }
+
+        pub fn to_string<'js>(&self, ctx: Ctx<'js>, options: Opt<Value<'js>>) -> Result<String> {
+            let options = get_options(&ctx, options)?;
+            let rounding = to_string_options(&ctx, &options)?;
+            self.inner.as_temporal_string(rounding).or_throw(&ctx)
+        }
+
+        #[qjs(rename = "toJSON")]
+        pub fn to_json(&self, ctx: Ctx<'_>) -> Result<String> {
+            self.inner
+                .as_temporal_string(Default::default())
+                .or_throw(&ctx)
+        }
+
+        pub fn value_of(&self, ctx: Ctx<'_>) -> Result<()> {
+            Err(super::no_value_of(&ctx))
+        }
+    }
+}
+
+/// The time zone provider of the Temporal types, see [`ChronoTzProvider`]
+static PROVIDER: ChronoTzProvider = ChronoTzProvider;
+
+const NS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Range of epoch seconds passed to chrono, which supports a smaller range than Temporal.
+/// Offsets are constant this far from the present, so clamping does not change the results.
+const CHRONO_MIN_SECONDS: i64 = -8_000_000_000_000;
+const CHRONO_MAX_SECONDS: i64 = 8_000_000_000_000;
+
+/// Range searched for offset transitions. The earliest transition of the database is in 1844.
+/// chrono-tz expands the recurring daylight saving rules of the database up to 2100 and keeps
+/// the last offset of each zone from then on, so there are no transitions after 2100: the
+/// offsets reported for later instants are that last offset, and `getTimeZoneTransition`
+/// consistently finds no transition after it.
+const FIRST_TRANSITION_SEARCH_SECONDS: i64 = -5_364_662_400; // 1800-01-01T00:00:00Z
+const LAST_TRANSITION_SEARCH_SECONDS: i64 = 4_133_980_800; // 2101-01-01T00:00:00Z
+
+/// Step used when collecting the transitions of a zone. Transitions less than a step apart
+/// that return to the previous offset would be skipped; the closest ones in the database are
+/// almost seven days apart, and no zone changes its offset twice within a day.
+const TRANSITION_SEARCH_STEP: i64 = 86_400;
+
+const UNKNOWN_TIME_ZONE: TimeZoneProviderError =
+    TimeZoneProviderError::Range("Unknown time zone identifier");
+
+/// Serves the time zone rules of chrono-tz, the database of the `timezone` feature, to
+/// temporal_rs. Identifiers are looked up case-insensitively and canonicalized through the IANA
+/// identifier index of `timezone_provider`; the resolved id is the zone's index in
+/// `chrono_tz::TZ_VARIANTS`.
+struct ChronoTzProvider;
+
+impl ChronoTzProvider {
+    fn resolve(&self, id: TimeZoneId) -> Result<Tz, TimeZoneProviderError> {
+        chrono_tz::TZ_VARIANTS
+            .get(id.resolved.0)
+            .copied()
+            .ok_or(UNKNOWN_TIME_ZONE)
+    }
+}
+
+impl TimeZoneProvider for ChronoTzProvider {
+    fn get(&self, ident: &[u8]) -> Result<TimeZoneId, TimeZoneProviderError> {
+        let normalized = CompiledNormalizer.normalized(ident)?;
+        let tz = Tz::from_str(CompiledNormalizer.identifier(normalized)?)
+            .map_err(|_| UNKNOWN_TIME_ZONE)?;
+        Ok(TimeZoneId {
+            normalized,
+            resolved: ResolvedId(tz as usize),
+        })
+    }
+
+    fn identifier(&self, id: TimeZoneId) -> Result<Cow<'_, str>, TimeZoneProviderError> {
+        CompiledNormalizer
+            .identifier(id.normalized)
+            .map(Cow::Borrowed)
+    }
+
+    fn canonicalized(&self, id: TimeZoneId) -> Result<TimeZoneId, TimeZoneProviderError> {
+        Ok(TimeZoneId {
+            normalized: CompiledNormalizer.canonicalized(id.normalized)?,
+            resolved: id.resolved,
+        })
+    }
+
+    fn candidate_nanoseconds_for_local_epoch_nanoseconds(
+        &self,
+        id: TimeZoneId,
+        local_datetime: IsoDateTime,
+    ) -> Result<CandidateEpochNanoseconds, TimeZoneProviderError> {
+        let tz = self.resolve(id)?;
+        let local_ns = local_datetime.as_nanoseconds().0;
+        let local = naive(local_ns.div_euclid(NS_PER_SECOND) as i64);
+        let candidate = |offset: i32| EpochNanosecondsAndOffset {
+            ns: EpochNanoseconds(local_ns - offset as i128 * NS_PER_SECOND),
+            offset: UtcOffsetSeconds(offset as i64),
+        };
+        Ok(match tz.offset_from_local_datetime(&local) {
+            LocalResult::Single(offset) => {
+                CandidateEpochNanoseconds::One(candidate(offset.fix().local_minus_utc()))
+            }
+            LocalResult::Ambiguous(first, second) => {
+                let first = candidate(first.fix().local_minus_utc());
+                let second = candidate(second.fix().local_minus_utc());
+                if first.ns.0 <= second.ns.0 {
+                    CandidateEpochNanoseconds::Two([first, second])
+                } else {
+                    CandidateEpochNanoseconds::Two([second, first])
+                }
+            }
+            LocalResult::None => {
+                let gap = GapInfo::new(&local, &tz);
+                let (Some((_, before)), Some(after)) =
+                    gap.map(|gap| (gap.begin, gap.end)).unwrap_or_default()
+                else {
+                    return Err(TimeZoneProviderError::Assert("Gap without a transition"));
+                };
+                CandidateEpochNanoseconds::Zero(GapEntryOffsets {
+                    offset_before: UtcOffsetSeconds(before.fix().local_minus_utc() as i64),
+                    offset_after: UtcOffsetSeconds(after.offset().fix().local_minus_utc() as i64),
+                    transition_epoch: EpochNanoseconds(after.timestamp() as i128 * NS_PER_SECOND),
+                })
+            }
+        })
+    }
+
+    fn transition_nanoseconds_for_utc_epoch_nanoseconds(
+        &self,
+        id: TimeZoneId,
+        epoch_nanoseconds: i128,
+    ) -> Result<UtcOffsetSeconds, TimeZoneProviderError> {
+        let tz = self.resolve(id)?;
+        let seconds = epoch_nanoseconds.div_euclid(NS_PER_SECOND) as i64;
+        Ok(UtcOffsetSeconds(offset_at(tz, seconds) as i64))
+    }
+
+    fn get_time_zone_transition(
+        &self,
+        id: TimeZoneId,
+        epoch_nanoseconds: i128,
+        direction: TransitionDirection,
+    ) -> Result<Option<EpochNanoseconds>, TimeZoneProviderError> {
+        if is_fixed_offset(id)? {
+            return Ok(None);
+        }
+        let transitions = transitions(self.resolve(id)?);
+        let transition = match direction {
+            TransitionDirection::Next => {
+                let index = transitions
+                    .partition_point(|&t| t as i128 * NS_PER_SECOND <= epoch_nanoseconds);
+                transitions.get(index)
+            }
+            TransitionDirection::Previous => {
+                let index = transitions
+                    .partition_point(|&t| (t as i128 * NS_PER_SECOND) < epoch_nanoseconds);
+                index
+                    .checked_sub(1)
+                    .and_then(|index| transitions.get(index))
+            }
+        };
+        Ok(transition.map(|&t| EpochNanoseconds(t as i128 * NS_PER_SECOND)))
+    }
+}
+
+/// UTC and the `Etc/` zones have a single offset, so they need no transition table
+fn is_fixed_offset(id: TimeZoneId) -> Result<bool, TimeZoneProviderError> {
+    let canonical = CompiledNormalizer.canonicalized(id.normalized)?;
+    let name = CompiledNormalizer.identifier(canonical)?;
+    Ok(name == "UTC" || name.starts_with("Etc/"))
+}
+
+/// The offset transitions of the zones used so far, in epoch seconds
+static TRANSITIONS: LazyLock<Mutex<HashMap<Tz, Arc<[i64]>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn transitions(tz: Tz) -> Arc<[i64]> {
+    TRANSITIONS
+        .lock()
+        .unwrap()
+        .entry(tz)
+        .or_insert_with(|| collect_transitions(tz).into())
+        .clone()
+}
+
+/// chrono-tz keeps its transition table private, so it is rebuilt from the offsets of the zone,
+/// once per zone: each change of offset between two steps is narrowed down to the second
+fn collect_transitions(tz: Tz) -> Vec<i64> {
+    let mut transitions = Vec::new();
+    let mut start = FIRST_TRANSITION_SEARCH_SECONDS;
+    let mut offset = offset_at(tz, start);
+    while start < LAST_TRANSITION_SEARCH_SECONDS {
+        let end = start + TRANSITION_SEARCH_STEP;
+        let end_offset = offset_at(tz, end);
+        if end_offset != offset {
+            // The transition is in (start, end]
+            let (mut lo, mut hi) = (start, end);
+            while hi - lo > 1 {
+                let mid = lo + (hi - lo) / 2;
+                if offset_at(tz, mid) == offset {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            transitions.push(hi);
+            offset = end_offset;
+        }
+        start = end;
+    }
+    transitions
+}
+
+fn naive(seconds: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(seconds.clamp(CHRONO_MIN_SECONDS, CHRONO_MAX_SECONDS), 0)
+        .expect("clamped timestamp is in range")
+        .naive_utc()
+}
+
+fn offset_at(tz: Tz, epoch_seconds: i64) -> i32 {
+    tz.offset_from_utc_datetime(&naive(epoch_seconds))
+        .fix()
+        .local_minus_utc()
+}
+
+/// Throws a temporal_rs error as the exception the spec requires: a TypeError for type errors,
+/// a RangeError for everything else
+trait OrThrow<T> {
+    fn or_throw(self, ctx: &Ctx<'_>) -> rquickjs::Result<T>;
+}
+
+impl<T> OrThrow<T> for Result<T, TemporalError> {
+    fn or_throw(self, ctx: &Ctx<'_>) -> rquickjs::Result<T> {
+        self.map_err(|err| match err.kind() {
+            ErrorKind::Type => Exception::throw_type(ctx, err.into_message()),
+            _ => Exception::throw_range(ctx, err.into_message()),
+        })
+    }
+}
+
+fn no_value_of(ctx: &Ctx<'_>) -> rquickjs::Error {
+    Exception::throw_type(
+        ctx,
+        "Temporal objects cannot be converted to primitives, use compare() or equals()",
+    )
+}
+
+fn ordering(ordering: std::cmp::Ordering) -> i32 {
+    ordering as i32
+}
+
+fn opt_value<'js>(ctx: &Ctx<'js>, value: Opt<Value<'js>>) -> Value<'js> {
+    value.0.unwrap_or_else(|| Value::new_undefined(ctx.clone()))
+}
+
+fn to_number<'js>(ctx: &Ctx<'js>, value: &Value<'js>) -> rquickjs::Result<f64> {
+    Coerced::<f64>::from_js(ctx, value.clone()).map(|number| number.0)
+}
+
+fn to_integer_with_truncation<'js>(ctx: &Ctx<'js>, value: &Value<'js>) -> rquickjs::Result<f64> {
+    let number = to_number(ctx, value)?;
+    if number.is_nan() {
+        Ok(0.0)
+    } else if number.is_infinite() {
+        Err(Exception::throw_range(ctx, "Value must be finite"))
+    } else {
+        Ok(number.trunc())
+    }
+}
+
+fn to_positive_integer_with_truncation<'js>(
+    ctx: &Ctx<'js>,
+    value: &Value<'js>,
+) -> rquickjs::Result<f64> {
+    let integer = to_integer_with_truncation(ctx, value)?;
+    if integer <= 0.0 {
+        return Err(Exception::throw_range(ctx, "Value must be positive"));
+    }
+    Ok(integer)
+}
+
+fn to_integer_if_integral<'js>(ctx: &Ctx<'js>, value: &Value<'js>) -> rquickjs::Result<f64> {
+    let number = to_number(ctx, value)?;
+    if !number.is_finite() || number.trunc() != number {
+        return Err(Exception::throw_range(ctx, "Value must be an integer"));
+    }
+    Ok(number)
+}
+
+/// A constructor argument converted with ToIntegerWithTruncation, undefined being 0
+fn integer_argument<'js>(ctx: &Ctx<'js>, value: Opt<Value<'js>>) -> rquickjs::Result<f64> {
+    to_integer_with_truncation(ctx, &opt_value(ctx, value))
+}
+
+fn to_i32(ctx: &Ctx<'_>, value: f64) -> rquickjs::Result<i32> {
+    if value < i32::MIN as f64 || value > i32::MAX as f64 {
+        return Err(Exception::throw_range(ctx, "Value out of range"));
+    }
+    Ok(value as i32)
+}
+
+fn to_u8(ctx: &Ctx<'_>, value: f64) -> rquickjs::Result<u8> {
+    if !(0.0..=u8::MAX as f64).contains(&value) {
+        return Err(Exception::throw_range(ctx, "Value out of range"));
+    }
+    Ok(value as u8)
+}
+
+fn to_u16(ctx: &Ctx<'_>, value: f64) -> rquickjs::Result<u16> {
+    if !(0.0..=u16::MAX as f64).contains(&value) {
+        return Err(Exception::throw_range(ctx, "Value out of range"));
+    }
+    Ok(value as u16)
+}
+
+/// ToBigInt, which unlike `BigInt()` rejects numbers
+fn to_bigint_i128<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<i128> {
+    if value.is_number() {
+        return Err(Exception::throw_type(
+            ctx,
+            "Expected a BigInt, got a number",
+        ));
+    }
+    let bigint: Value = ctx.globals().get::<_, Function>("BigInt")?.call((value,))?;
+    let digits = Coerced::<String>::from_js(ctx, bigint)?.0;
+    digits
+        .parse()
+        .map_err(|_| Exception::throw_range(ctx, "Epoch nanoseconds out of range"))
+}
+
+fn bigint_from_i128<'js>(ctx: &Ctx<'js>, value: i128) -> rquickjs::Result<rquickjs::BigInt<'js>> {
+    ctx.globals()
+        .get::<_, Function>("BigInt")?
+        .call((value.to_string(),))
+}
+
+/// A string, or an object converted with ToString, as required for month codes, eras and offsets
+fn to_primitive_string<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    what: &str,
+) -> rquickjs::Result<String> {
+    if let Some(string) = value.as_string() {
+        string.to_string()
+    } else if value.is_object() {
+        Ok(Coerced::<String>::from_js(ctx, value)?.0)
+    } else {
+        Err(Exception::throw_type(
+            ctx,
+            &format!("{what} must be a string"),
+        ))
+    }
+}
+
+fn get_options<'js>(
+    ctx: &Ctx<'js>,
+    options: Opt<Value<'js>>,
+) -> rquickjs::Result<Option<Object<'js>>> {
+    match options.0 {
+        None => Ok(None),
+        Some(value) if value.is_undefined() => Ok(None),
+        Some(value) => match value.into_object() {
+            Some(object) => Ok(Some(object)),
+            None => Err(Exception::throw_type(ctx, "Options must be an object")),
+        },
+    }
+}
+
+/// The options of `round()` and `total()`, which may be given as the string of a single option
+fn string_or_options<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    key: &str,
+) -> rquickjs::Result<Option<Object<'js>>> {
+    if value.is_string() {
+        let options = Object::new(ctx.clone())?;
+        options.set(key, value)?;
+        Ok(Some(options))
+    } else if value.is_undefined() {
+        Err(Exception::throw_type(ctx, "Options are required"))
+    } else {
+        get_options(ctx, Opt(Some(value)))
+    }
+}
+
+fn string_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+    key: &str,
+) -> rquickjs::Result<Option<String>> {
+    let Some(options) = options else {
+        return Ok(None);
+    };
+    let value: Value = options.get(key)?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    Ok(Some(Coerced::<String>::from_js(ctx, value)?.0))
+}
+
+fn parse_option<T: FromStr>(
+    ctx: &Ctx<'_>,
+    value: Option<String>,
+    key: &str,
+) -> rquickjs::Result<Option<T>> {
+    value
+        .map(|value| {
+            value.parse().map_err(|_| {
+                Exception::throw_range(ctx, &format!("Invalid value for {key}: {value}"))
+            })
+        })
+        .transpose()
+}
+
+fn overflow_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Option<Overflow>> {
+    parse_option(ctx, string_option(ctx, options, "overflow")?, "overflow")
+}
+
+fn disambiguation_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Option<Disambiguation>> {
+    parse_option(
+        ctx,
+        string_option(ctx, options, "disambiguation")?,
+        "disambiguation",
+    )
+}
+
+fn offset_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Option<OffsetDisambiguation>> {
+    parse_option(ctx, string_option(ctx, options, "offset")?, "offset")
+}
+
+fn calendar_name_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<temporal_rs::options::DisplayCalendar> {
+    Ok(parse_option(
+        ctx,
+        string_option(ctx, options, "calendarName")?,
+        "calendarName",
+    )?
+    .unwrap_or_default())
+}
+
+fn unit_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+    key: &str,
+) -> rquickjs::Result<Option<Unit>> {
+    parse_option(ctx, string_option(ctx, options, key)?, key)
+}
+
+fn rounding_mode_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Option<RoundingMode>> {
+    parse_option(
+        ctx,
+        string_option(ctx, options, "roundingMode")?,
+        "roundingMode",
+    )
+}
+
+fn rounding_increment_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Option<RoundingIncrement>> {
+    let Some(options) = options else {
+        return Ok(None);
+    };
+    let value: Value = options.get("roundingIncrement")?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    let increment = to_number(ctx, &value)?;
+    if !increment.is_finite() {
+        return Err(Exception::throw_range(
+            ctx,
+            "roundingIncrement must be finite",
+        ));
+    }
+    let increment = increment.trunc();
+    if !(1.0..=1e9).contains(&increment) {
+        return Err(Exception::throw_range(
+            ctx,
+            "roundingIncrement is out of range",
+        ));
+    }
+    RoundingIncrement::try_new(increment as u32)
+        .map(Some)
+        .or_throw(ctx)
+}
+
+/// Reads the `fractionalSecondDigits` option: "auto" or an integer from 0 to 9
+fn fraction_digits_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Precision> {
+    let Some(options) = options else {
+        return Ok(Precision::Auto);
+    };
+    let value: Value = options.get("fractionalSecondDigits")?;
+    if value.is_undefined() {
+        return Ok(Precision::Auto);
+    }
+    if !value.is_number() {
+        let string = Coerced::<String>::from_js(ctx, value)?.0;
+        if string == "auto" {
+            return Ok(Precision::Auto);
+        }
+        return Err(Exception::throw_range(
+            ctx,
+            &format!("Invalid value for fractionalSecondDigits: {string}"),
+        ));
+    }
+    let digits = to_number(ctx, &value)?;
+    if !digits.is_finite() || !(0.0..10.0).contains(&digits.floor()) {
+        return Err(Exception::throw_range(
+            ctx,
+            "fractionalSecondDigits must be from 0 to 9",
+        ));
+    }
+    Ok(Precision::Digit(digits.floor() as u8))
+}
+
+fn to_string_options<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<ToStringRoundingOptions> {
+    let precision = fraction_digits_option(ctx, options)?;
+    let rounding_mode = rounding_mode_option(ctx, options)?;
+    let smallest_unit = unit_option(ctx, options, "smallestUnit")?;
+    Ok(ToStringRoundingOptions {
+        precision,
+        smallest_unit,
+        rounding_mode,
+    })
+}
+
+fn difference_settings<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<DifferenceSettings> {
+    let mut settings = DifferenceSettings::default();
+    settings.largest_unit = unit_option(ctx, options, "largestUnit")?;
+    settings.increment = rounding_increment_option(ctx, options)?;
+    settings.rounding_mode = rounding_mode_option(ctx, options)?;
+    settings.smallest_unit = unit_option(ctx, options, "smallestUnit")?;
+    Ok(settings)
+}
+
+/// The options of `round()` on the types other than Duration, where `smallestUnit` is required
+fn rounding_options<'js>(
+    ctx: &Ctx<'js>,
+    round_to: Opt<Value<'js>>,
+) -> rquickjs::Result<RoundingOptions> {
+    let round_to = opt_value(ctx, round_to);
+    let options = string_or_options(ctx, round_to, "smallestUnit")?;
+    let mut rounding = RoundingOptions::default();
+    rounding.increment = rounding_increment_option(ctx, &options)?;
+    rounding.rounding_mode = rounding_mode_option(ctx, &options)?;
+    rounding.smallest_unit = unit_option(ctx, &options, "smallestUnit")?;
+    if rounding.smallest_unit.is_none() {
+        return Err(Exception::throw_range(ctx, "smallestUnit is required"));
+    }
+    Ok(rounding)
+}
+
+/// Reads the `relativeTo` option of Duration: a PlainDate or a ZonedDateTime, or something
+/// convertible to one of them
+fn relative_to_option<'js>(
+    ctx: &Ctx<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<Option<RelativeTo>> {
+    let Some(options) = options else {
+        return Ok(None);
+    };
+    let value: Value = options.get("relativeTo")?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(object) = value.as_object() {
+        if let Some(zoned) = object.as_class::<JsZonedDateTime>() {
+            return Ok(Some(zoned.borrow().inner.clone().into()));
+        }
+        if let Some(date) = object.as_class::<JsPlainDate>() {
+            return Ok(Some(date.borrow().inner.clone().into()));
+        }
+        if let Some(date_time) = object.as_class::<JsPlainDateTime>() {
+            return Ok(Some(date_time.borrow().inner.to_plain_date().into()));
+        }
+        let calendar = calendar_from_item(ctx, object)?;
+        let names = FieldNames {
+            time: true,
+            offset: true,
+            time_zone: true,
+            ..date_fields()
+        };
+        let fields = prepare_fields(ctx, object, &calendar, names)?;
+        return Ok(Some(if fields.time_zone.is_some() {
+            let partial = PartialZonedDateTime {
+                fields: fields.zoned_date_time_fields(),
+                timezone: fields.time_zone,
+                calendar,
+            };
+            ZonedDateTime::from_partial_with_provider(
+                partial,
+                None,
+                None,
+                Some(OffsetDisambiguation::Reject),
+                &PROVIDER,
+            )
+            .or_throw(ctx)?
+            .into()
+        } else {
+            PlainDate::from_partial(
+                PartialDate {
+                    calendar_fields: fields.calendar_fields,
+                    calendar,
+                },
+                None,
+            )
+            .or_throw(ctx)?
+            .into()
+        }));
+    }
+    let Some(string) = value.as_string() else {
+        return Err(Exception::throw_type(
+            ctx,
+            "relativeTo must be a string or an object",
+        ));
+    };
+    RelativeTo::try_from_str_with_provider(&string.to_string()?, &PROVIDER)
+        .map(Some)
+        .or_throw(ctx)
+}
+
+/// The calendar of a Temporal object, if the value is one that has a calendar
+fn calendar_of(object: &Object<'_>) -> Option<Calendar> {
+    if let Some(date) = object.as_class::<JsPlainDate>() {
+        Some(date.borrow().inner.calendar().clone())
+    } else if let Some(date_time) = object.as_class::<JsPlainDateTime>() {
+        Some(date_time.borrow().inner.calendar().clone())
+    } else if let Some(year_month) = object.as_class::<JsPlainYearMonth>() {
+        Some(year_month.borrow().inner.calendar().clone())
+    } else if let Some(month_day) = object.as_class::<JsPlainMonthDay>() {
+        Some(month_day.borrow().inner.calendar().clone())
+    } else {
+        object
+            .as_class::<JsZonedDateTime>()
+            .map(|zoned| zoned.borrow().inner.calendar().clone())
+    }
+}
+
+/// ToTemporalCalendarIdentifier: a Temporal object with a calendar, a calendar identifier or an
+/// ISO string with a calendar annotation
+fn calendar_from_value<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Calendar> {
+    if let Some(calendar) = value.as_object().and_then(calendar_of) {
+        return Ok(calendar);
+    }
+    let Some(string) = value.as_string() else {
+        return Err(Exception::throw_type(ctx, "Calendar must be a string"));
+    };
+    Calendar::from_str(&string.to_string()?).or_throw(ctx)
+}
+
+/// The optional calendar argument of the constructors, which must be a calendar identifier
+fn calendar_argument<'js>(ctx: &Ctx<'js>, calendar: Opt<Value<'js>>) -> rquickjs::Result<Calendar> {
+    let calendar = opt_value(ctx, calendar);
+    if calendar.is_undefined() {
+        return Ok(Calendar::ISO);
+    }
+    let Some(string) = calendar.as_string() else {
+        return Err(Exception::throw_type(ctx, "Calendar must be a string"));
+    };
+    Calendar::try_from_utf8(string.to_string()?.as_bytes()).or_throw(ctx)
+}
+
+/// GetTemporalCalendarIdentifierWithISODefault: the `calendar` property of a property bag
+fn calendar_from_item<'js>(ctx: &Ctx<'js>, item: &Object<'js>) -> rquickjs::Result<Calendar> {
+    if let Some(calendar) = calendar_of(item) {
+        return Ok(calendar);
+    }
+    let calendar: Value = item.get("calendar")?;
+    if calendar.is_undefined() {
+        return Ok(Calendar::ISO);
+    }
+    calendar_from_value(ctx, calendar)
+}
+
+/// ToTemporalTimeZoneIdentifier: a ZonedDateTime, a time zone identifier, an offset or an ISO
+/// string with a time zone annotation
+fn time_zone_from_value<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<TimeZone> {
+    if let Some(zoned) = value
+        .as_object()
+        .and_then(|object| object.as_class::<JsZonedDateTime>())
+    {
+        return Ok(*zoned.borrow().inner.time_zone());
+    }
+    let Some(string) = value.as_string() else {
+        return Err(Exception::throw_type(ctx, "Time zone must be a string"));
+    };
+    TimeZone::try_from_str_with_provider(&string.to_string()?, &PROVIDER).or_throw(ctx)
+}
+
+/// The fields a property bag is read for
+#[derive(Clone, Copy, Default)]
+struct FieldNames {
+    year: bool,
+    month: bool,
+    day: bool,
+    time: bool,
+    offset: bool,
+    time_zone: bool,
+}
+
+fn date_fields() -> FieldNames {
+    FieldNames {
+        year: true,
+        month: true,
+        day: true,
+        ..Default::default()
+    }
+}
+
+fn year_month_fields() -> FieldNames {
+    FieldNames {
+        year: true,
+        month: true,
+        ..Default::default()
+    }
+}
+
+fn time_fields() -> FieldNames {
+    FieldNames {
+        time: true,
+        ..Default::default()
+    }
+}
+
+/// The fields read from a property bag
+#[derive(Default)]
+struct Fields {
+    calendar_fields: CalendarFields,
+    time: PartialTime,
+    offset: Option<UtcOffset>,
+    time_zone: Option<TimeZone>,
+    /// Whether any of the fields was present
+    any: bool,
+    /// A time field was negative, which only `overflow: "constrain"` accepts
+    negative_time: bool,
+}
+
+impl Fields {
+    fn check_overflow(&self, ctx: &Ctx<'_>, overflow: Option<Overflow>) -> rquickjs::Result<()> {
+        if self.negative_time && overflow == Some(Overflow::Reject) {
+            return Err(Exception::throw_range(ctx, "Time field out of range"));
+        }
+        Ok(())
+    }
+
+    fn date_time_fields(&self) -> temporal_rs::fields::DateTimeFields {
+        temporal_rs::fields::DateTimeFields {
+            calendar_fields: self.calendar_fields.clone(),
+            time: self.time,
+        }
+    }
+
+    fn zoned_date_time_fields(&self) -> ZonedDateTimeFields {
+        ZonedDateTimeFields {
+            calendar_fields: self.calendar_fields.clone(),
+            time: self.time,
+            offset: self.offset,
+        }
+    }
+}
+
+fn era_from_value<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<TinyAsciiStr<19>> {
+    let era = to_primitive_string(ctx, value, "era")?;
+    TinyAsciiStr::try_from_str(&era).map_err(|_| Exception::throw_range(ctx, "Invalid era"))
+}
+
+/// PrepareCalendarFields: reads the fields of a property bag in alphabetical order, converting
+/// each one as the spec requires
+fn prepare_fields<'js>(
+    ctx: &Ctx<'js>,
+    item: &Object<'js>,
+    calendar: &Calendar,
+    names: FieldNames,
+) -> rquickjs::Result<Fields> {
+    let mut fields = Fields::default();
+    let has_eras = names.year && !calendar.is_iso();
+    let time_field = |fields: &mut Fields, key: &str| -> rquickjs::Result<Option<f64>> {
+        let value: Value = item.get(key)?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+        fields.any = true;
+        let value = to_integer_with_truncation(ctx, &value)?;
+        fields.negative_time |= value < 0.0;
+        Ok(Some(value))
+    };
+
+    if names.day {
+        let value: Value = item.get("day")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            fields.calendar_fields.day =
+                Some(to_positive_integer_with_truncation(ctx, &value)? as u8);
+        }
+    }
+    if has_eras {
+        let value: Value = item.get("era")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            fields.calendar_fields.era = Some(era_from_value(ctx, value)?);
+        }
+        let value: Value = item.get("eraYear")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            fields.calendar_fields.era_year = Some(to_integer_with_truncation(ctx, &value)? as i32);
+        }
+    }
+    if names.time {
+        fields.time.hour = time_field(&mut fields, "hour")?.map(|value| value as u8);
+        fields.time.microsecond = time_field(&mut fields, "microsecond")?.map(|value| value as u16);
+        fields.time.millisecond = time_field(&mut fields, "millisecond")?.map(|value| value as u16);
+        fields.time.minute = time_field(&mut fields, "minute")?.map(|value| value as u8);
+    }
+    if names.month {
+        let value: Value = item.get("month")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            fields.calendar_fields.month =
+                Some(to_positive_integer_with_truncation(ctx, &value)? as u8);
+        }
+        let value: Value = item.get("monthCode")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            let month_code = to_primitive_string(ctx, value, "monthCode")?;
+            fields.calendar_fields.month_code =
+                Some(MonthCode::from_str(&month_code).or_throw(ctx)?);
+        }
+    }
+    if names.time {
+        fields.time.nanosecond = time_field(&mut fields, "nanosecond")?.map(|value| value as u16);
+    }
+    if names.offset {
+        let value: Value = item.get("offset")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            let offset = to_primitive_string(ctx, value, "offset")?;
+            fields.offset = Some(UtcOffset::from_str(&offset).or_throw(ctx)?);
+        }
+    }
+    if names.time {
+        fields.time.second = time_field(&mut fields, "second")?.map(|value| value as u8);
+    }
+    if names.time_zone {
+        let value: Value = item.get("timeZone")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            fields.time_zone = Some(time_zone_from_value(ctx, value)?);
+        }
+    }
+    if names.year {
+        let value: Value = item.get("year")?;
+        if !value.is_undefined() {
+            fields.any = true;
+            fields.calendar_fields.year = Some(to_integer_with_truncation(ctx, &value)? as i32);
+        }
+    }
+    Ok(fields)
+}
+
+fn empty_fields_error(ctx: &Ctx<'_>) -> rquickjs::Error {
+    Exception::throw_type(ctx, "At least one field is required")
+}
+
+/// The fields of the property bag passed to `with()`, at least one of which must be present
+fn with_fields<'js>(
+    ctx: &Ctx<'js>,
+    item: &Object<'js>,
+    calendar: &Calendar,
+    names: FieldNames,
+) -> rquickjs::Result<Fields> {
+    let fields = prepare_fields(ctx, item, calendar, names)?;
+    if !fields.any {
+        return Err(empty_fields_error(ctx));
+    }
+    Ok(fields)
+}
+
+/// RejectTemporalLikeObject: `with()` takes plain property bags only
+fn reject_temporal_like<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Object<'js>> {
+    let Some(item) = value.into_object() else {
+        return Err(Exception::throw_type(ctx, "Expected an object"));
+    };
+    if calendar_of(&item).is_some()
+        || item.as_class::<JsPlainTime>().is_some()
+        || item.as_class::<JsInstant>().is_some()
+        || item.as_class::<JsDuration>().is_some()
+    {
+        return Err(Exception::throw_type(ctx, "Expected a plain object"));
+    }
+    let calendar: Value = item.get("calendar")?;
+    let time_zone: Value = item.get("timeZone")?;
+    if !calendar.is_undefined() || !time_zone.is_undefined() {
+        return Err(Exception::throw_type(
+            ctx,
+            "calendar and timeZone cannot be changed with with()",
+        ));
+    }
+    Ok(item)
+}
+
+/// The fields of a duration-like property bag, at least one of which must be present
+fn partial_duration<'js>(ctx: &Ctx<'js>, item: &Object<'js>) -> rquickjs::Result<PartialDuration> {
+    let mut partial = PartialDuration::default();
+    let field = |key: &str| -> rquickjs::Result<Option<f64>> {
+        let value: Value = item.get(key)?;
+        if value.is_undefined() {
+            return Ok(None);
+        }
+        to_integer_if_integral(ctx, &value).map(Some)
+    };
+    partial.days = field("days")?.map(|value| value as i64);
+    partial.hours = field("hours")?.map(|value| value as i64);
+    partial.microseconds = field("microseconds")?.map(|value| value as i128);
+    partial.milliseconds = field("milliseconds")?.map(|value| value as i64);
+    partial.minutes = field("minutes")?.map(|value| value as i64);
+    partial.months = field("months")?.map(|value| value as i64);
+    partial.nanoseconds = field("nanoseconds")?.map(|value| value as i128);
+    partial.seconds = field("seconds")?.map(|value| value as i64);
+    partial.weeks = field("weeks")?.map(|value| value as i64);
+    partial.years = field("years")?.map(|value| value as i64);
+    if partial.is_empty() {
+        return Err(Exception::throw_type(
+            ctx,
+            "At least one duration field is required",
+        ));
+    }
+    Ok(partial)
+}
+
+/// ToTemporalDuration
+fn duration_from_value<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Duration> {
+    if let Some(object) = value.as_object() {
+        if let Some(duration) = object.as_class::<JsDuration>() {
+            return Ok(duration.borrow().inner);
+        }
+        let partial = partial_duration(ctx, object)?;
+        return Duration::from_partial_duration(partial).or_throw(ctx);
+    }
+    let Some(string) = value.as_string() else {
+        return Err(Exception::throw_type(
+            ctx,
+            "Expected a Temporal.Duration or a string",
+        ));
+    };
+    Duration::from_str(&string.to_string()?).or_throw(ctx)
+}
+
+/// ToTemporalInstant
+fn instant_from_value<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> rquickjs::Result<Instant> {
+    let value = if let Some(object) = value.as_object() {
+        if let Some(instant) = object.as_class::<JsInstant>() {
+            return Ok(instant.borrow().inner);
+        }
+        if let Some(zoned) = object.as_class::<JsZonedDateTime>() {
+            return Ok(zoned.borrow().inner.to_instant());
+        }
+        Coerced::<String>::from_js(ctx, value)?.0
+    } else if let Some(string) = value.as_string() {
+        string.to_string()?
+    } else {
+        return Err(Exception::throw_type(
+            ctx,
+            "Expected a Temporal.Instant or a string",
+        ));
+    };
+    Instant::from_str(&value).or_throw(ctx)
+}
+
+/// The string form of a value converted to a Temporal type, which must be a string
+fn item_string<'js>(ctx: &Ctx<'js>, value: &Value<'js>, what: &str) -> rquickjs::Result<String> {
+    match value.as_string() {
+        Some(string) => string.to_string(),
+        None => Err(Exception::throw_type(
+            ctx,
+            &format!("Expected a Temporal.{what}, a property bag or a string"),
+        )),
+    }
+}
+
+/// ToTemporalDate
+fn plain_date_from_value<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<PlainDate> {
+    if let Some(object) = value.as_object() {
+        if let Some(date) = object.as_class::<JsPlainDate>() {
+            overflow_option(ctx, options)?;
+            return Ok(date.borrow().inner.clone());
+        }
+        if let Some(date_time) = object.as_class::<JsPlainDateTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(date_time.borrow().inner.to_plain_date());
+        }
+        if let Some(zoned) = object.as_class::<JsZonedDateTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(zoned.borrow().inner.to_plain_date());
+        }
+        let calendar = calendar_from_item(ctx, object)?;
+        let fields = prepare_fields(ctx, object, &calendar, date_fields())?;
+        let overflow = overflow_option(ctx, options)?;
+        let partial = PartialDate {
+            calendar_fields: fields.calendar_fields,
+            calendar,
+        };
+        return PlainDate::from_partial(partial, overflow).or_throw(ctx);
+    }
+    let string = item_string(ctx, &value, "PlainDate")?;
+    let date = PlainDate::from_str(&string).or_throw(ctx)?;
+    overflow_option(ctx, options)?;
+    Ok(date)
+}
+
+/// ToTemporalTime
+fn plain_time_from_value<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<PlainTime> {
+    if let Some(object) = value.as_object() {
+        if let Some(time) = object.as_class::<JsPlainTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(time.borrow().inner);
+        }
+        if let Some(date_time) = object.as_class::<JsPlainDateTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(date_time.borrow().inner.to_plain_time());
+        }
+        if let Some(zoned) = object.as_class::<JsZonedDateTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(zoned.borrow().inner.to_plain_time());
+        }
+        let fields = prepare_fields(ctx, object, &Calendar::ISO, time_fields())?;
+        if !fields.any {
+            return Err(empty_fields_error(ctx));
+        }
+        let overflow = overflow_option(ctx, options)?;
+        fields.check_overflow(ctx, overflow)?;
+        return PlainTime::from_partial(fields.time, overflow).or_throw(ctx);
+    }
+    let string = item_string(ctx, &value, "PlainTime")?;
+    let time = PlainTime::from_str(&string).or_throw(ctx)?;
+    overflow_option(ctx, options)?;
+    Ok(time)
+}
+
+/// The optional PlainTime argument of `toPlainDateTime()` and `withPlainTime()`
+fn optional_plain_time<'js>(
+    ctx: &Ctx<'js>,
+    time: Opt<Value<'js>>,
+) -> rquickjs::Result<Option<PlainTime>> {
+    let time = opt_value(ctx, time);
+    if time.is_undefined() {
+        return Ok(None);
+    }
+    plain_time_from_value(ctx, time, &None).map(Some)
+}
+
+/// ToTemporalDateTime
+fn plain_date_time_from_value<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<PlainDateTime> {
+    if let Some(object) = value.as_object() {
+        if let Some(date_time) = object.as_class::<JsPlainDateTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(date_time.borrow().inner.clone());
+        }
+        if let Some(zoned) = object.as_class::<JsZonedDateTime>() {
+            overflow_option(ctx, options)?;
+            return Ok(zoned.borrow().inner.to_plain_date_time());
+        }
+        if let Some(date) = object.as_class::<JsPlainDate>() {
+            overflow_option(ctx, options)?;
+            return date.borrow().inner.to_plain_date_time(None).or_throw(ctx);
+        }
+        let calendar = calendar_from_item(ctx, object)?;
+        let names = FieldNames {
+            time: true,
+            ..date_fields()
+        };
+        let fields = prepare_fields(ctx, object, &calendar, names)?;
+        let overflow = overflow_option(ctx, options)?;
+        fields.check_overflow(ctx, overflow)?;
+        let partial = temporal_rs::partial::PartialDateTime {
+            fields: fields.date_time_fields(),
+            calendar,
+        };
+        return PlainDateTime::from_partial(partial, overflow).or_throw(ctx);
+    }
+    let string = item_string(ctx, &value, "PlainDateTime")?;
+    let date_time = PlainDateTime::from_str(&string).or_throw(ctx)?;
+    overflow_option(ctx, options)?;
+    Ok(date_time)
+}
+
+/// ToTemporalYearMonth
+fn plain_year_month_from_value<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<PlainYearMonth> {
+    if let Some(object) = value.as_object() {
+        if let Some(year_month) = object.as_class::<JsPlainYearMonth>() {
+            overflow_option(ctx, options)?;
+            return Ok(year_month.borrow().inner.clone());
+        }
+        let calendar = calendar_from_item(ctx, object)?;
+        let fields = prepare_fields(ctx, object, &calendar, year_month_fields())?;
+        let overflow = overflow_option(ctx, options)?;
+        let partial = temporal_rs::partial::PartialYearMonth {
+            calendar_fields: fields.calendar_fields.into(),
+            calendar,
+        };
+        return PlainYearMonth::from_partial(partial, overflow).or_throw(ctx);
+    }
+    let string = item_string(ctx, &value, "PlainYearMonth")?;
+    let year_month = PlainYearMonth::from_str(&string).or_throw(ctx)?;
+    overflow_option(ctx, options)?;
+    Ok(year_month)
+}
+
+/// ToTemporalMonthDay
+fn plain_month_day_from_value<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<PlainMonthDay> {
+    if let Some(object) = value.as_object() {
+        if let Some(month_day) = object.as_class::<JsPlainMonthDay>() {
+            overflow_option(ctx, options)?;
+            return Ok(month_day.borrow().inner.clone());
+        }
+        let calendar = calendar_from_item(ctx, object)?;
+        let fields = prepare_fields(ctx, object, &calendar, date_fields())?;
+        let overflow = overflow_option(ctx, options)?;
+        let partial = PartialDate {
+            calendar_fields: fields.calendar_fields,
+            calendar,
+        };
+        return PlainMonthDay::from_partial(partial, overflow).or_throw(ctx);
+    }
+    let string = item_string(ctx, &value, "PlainMonthDay")?;
+    let month_day = PlainMonthDay::from_str(&string).or_throw(ctx)?;
+    overflow_option(ctx, options)?;
+    Ok(month_day)
+}
+
+/// ToTemporalZonedDateTime
+fn zoned_date_time_from_value<'js>(
+    ctx: &Ctx<'js>,
+    value: Value<'js>,
+    options: &Option<Object<'js>>,
+) -> rquickjs::Result<ZonedDateTime> {
+    if let Some(object) = value.as_object() {
+        if let Some(zoned) = object.as_class::<JsZonedDateTime>() {
+            disambiguation_option(ctx, options)?;
+            offset_option(ctx, options)?;
+            overflow_option(ctx, options)?;
+            return Ok(zoned.borrow().inner.clone());
+        }
+        let calendar = calendar_from_item(ctx, object)?;
+        let names = FieldNames {
+            time: true,
+            offset: true,
+            time_zone: true,
+            ..date_fields()
+        };
+        let fields = prepare_fields(ctx, object, &calendar, names)?;
+        if fields.time_zone.is_none() {
+            return Err(Exception::throw_type(ctx, "timeZone is required"));
+        }
+        let disambiguation = disambiguation_option(ctx, options)?;
+        let offset = offset_option(ctx, options)?;
+        let overflow = overflow_option(ctx, options)?;
+        fields.check_overflow(ctx, overflow)?;
+        let partial = PartialZonedDateTime {
+            fields: fields.zoned_date_time_fields(),
+            timezone: fields.time_zone,
+            calendar,
+        };
+        return ZonedDateTime::from_partial_with_provider(
+            partial,
+            overflow,
+            disambiguation,
+            offset.or(Some(OffsetDisambiguation::Reject)),
+            &PROVIDER,
+        )
+        .or_throw(ctx);
+    }
+    let string = item_string(ctx, &value, "ZonedDateTime")?;
+    let disambiguation = disambiguation_option(ctx, options)?;
+    let offset = offset_option(ctx, options)?;
+    overflow_option(ctx, options)?;
+    ZonedDateTime::from_utf8_with_provider(
+        string.as_bytes(),
+        disambiguation.unwrap_or_default(),
+        offset.unwrap_or(OffsetDisambiguation::Reject),
+        &PROVIDER,
+    )
+    .or_throw(ctx)
+}
+
+// Assembles the Temporal namespace from the native classes
+pub const TEMPORAL_JS: &str = include_str!("temporal.js");
+
+pub const WIRE_JS: &str = r#"
+        import * as __wasm_rquickjs_temporal from '__wasm_rquickjs_builtin/temporal';
+        globalThis.Temporal = __wasm_rquickjs_temporal.Temporal;
+    "#;
@@ -2,10 +2,35 @@
 // Provides test(), describe(), it(), suite(), and lifecycle hooks.
 // Tests run synchronously/eagerly when called. Failures are collected
 // and an aggregate error is thrown after all tests in a suite complete.
+// Every test also reports TestsStream events, which run() streams to
+// the reporters of node:test/reporters.
 
 import assert from 'node:assert';
-import { ERR_INVALID_ARG_TYPE, ERR_INVALID_ARG_VALUE } from '__wasm_rquickjs_builtin/internal/errors';
-import { validateNumber, validateInteger } from '__wasm_rquickjs_builtin/internal/validators';
+import * as fs from 'node:fs';
+import * as path from 'node:path';
+import process from 'node:process';
+import { Readable } from 'node:stream';
+import {
+    _disableMockTimers,
+    _enableMockTimers,
+    _mockNow,
+    _runAllMockTimers,
+    _setMockTime,
+    _tickMockTimers,
+} from '__wasm_rquickjs_builtin/timeout';
+import {
+    ERR_INVALID_ARG_TYPE,
+    ERR_INVALID_ARG_VALUE,
+    ERR_INVALID_STATE,
+    ERR_TEST_FAILURE,
+} from '__wasm_rquickjs_builtin/internal/errors';
+import {
+    validateArray,
+    validateFunction,
+    validateInteger,
+    validateNumber,
+    validateObject,
+} from '__wasm_rquickjs_builtin/internal/validators';
 
 let currentSuite = null;
 // Check for globalThis-based filter (set by test harness before file execution)
@@ -40,6 +65,9 @@ function SuiteContext(name, parent, filePath) {
     this.afterFns = [];
     this.beforeEachFns = [];
     this.afterEachFns = [];
+    this._nesting = parent ? parent._nesting + 1 : -1;
+    this._subtestCount = 0;
+    this._report = undefined;
 }
 
 Object.defineProperty(SuiteContext.prototype, 'fullName', {
@@ -85,6 +113,10 @@ function TestContext(name, parent, filePath) {
     this.mock = new MockTracker();
     this._planCount = undefined;
     this._assertionCount = 0;
+    this._nesting = parent ? parent._nesting + 1 : 0;
+    this._subtestCount = 0;
+    this._snapshotCount = 0;
+    this._report = undefined;
 
     // Build t.assert: copy assert methods excluding AssertionError, CallTracker, strict,
     // and add snapshot/fileSnapshot per Node.js spec.
@@ -99,11 +131,11 @@ function TestContext(name, parent, filePath) {
             tAssert[key] = wrapAssertForPlan(assert[key], self);
         }
     }
-    tAssert.snapshot = wrapAssertForPlan(function snapshot(_value, _options) {
-        throw new Error('snapshot is not supported in this context');
+    tAssert.snapshot = wrapAssertForPlan(function snapshot(value, options) {
+        assertSnapshot(self, value, options);
     }, self);
-    tAssert.fileSnapshot = wrapAssertForPlan(function fileSnapshot(_value, _path) {
-        throw new Error('fileSnapshot is not supported in this context');
+    tAssert.fileSnapshot = wrapAssertForPlan(function fileSnapshot(value, filePath, options) {
+        assertFileSnapshot(value, filePath, options);
     }, self);
     // Apply custom assertions registered via testAssertions.register()
     const customKeys = Object.keys(_customAssertions);
@@ -140,16 +172,48 @@ TestContext.prototype.todo = function (msg) {
 
 TestContext.prototype.test = function (name, optionsOrFn, maybeFn) {
     const parsed = parseTestArgs(name, optionsOrFn, maybeFn);
-    const fn = parsed.fn;
     const parentTest = this;
+    if (isFilteredOut(parsed.name, parentTest)) {
+        return Promise.resolve();
+    }
+    const report = startReport(parsed.name, parentTest, parentTest.filePath);
 
     // Handle skip
     if (isSkipOption(parsed.options.skip)) {
+        completeReport(report, {
+            status: 'skip',
+            message: typeof parsed.options.skip === 'string' ? parsed.options.skip : ''
+        }, false);
         return Promise.resolve();
     }
 
     const childCtx = new TestContext(parsed.name, parentTest);
-    const restoreMocks = function () { childCtx.mock.restoreAll(); };
+    report.context = childCtx;
+    childCtx._report = report;
+    const isTodo = parsed.options.todo === true || typeof parsed.options.todo === 'string';
+
+    return runSubtest(childCtx, parsed.fn).then(function () {
+        if (childCtx._skipMessage !== undefined) {
+            completeReport(report, { status: 'skip', message: childCtx._skipMessage }, false);
+        } else if (isTodo) {
+            completeReport(report, { status: 'todo', message: typeof parsed.options.todo === 'string' ? parsed.options.todo : '' }, false);
+        } else {
+            completeReport(report, { status: 'pass' }, false);
+        }
+    }, function (e) {
+        if (e instanceof SkipError) {
+            completeReport(report, { status: 'skip', message: e.message }, false);
+        } else if (e instanceof TodoError || isTodo) {
+            completeReport(report, { status: 'todo', message: e instanceof TodoError ? e.message : '' }, false);
+        } else {
+            completeReport(report, { status: 'fail', error: e }, false);
+        }
+        throw e;
+    });
+};
+
+function runSubtest(childCtx, fn) {
+    const restoreMocks = function () { childCtx.mock.reset(); };
 
     try {
         if (fn.length >= 2) {
@@ -187,7 +251,7 @@ TestContext.prototype.test = function (name, optionsOrFn, maybeFn) {
         }
         return Promise.reject(e);
     }
-};
+}
 
 TestContext.prototype.before = function (fn) {
     this._beforeFns.push(fn);
@@ -330,6 +394,161 @@ function runHookListSafe(hooks) {
     }
 }
 
+// --- Reporting ---
+//
+// Tests and suites report the events of a Node.js TestsStream: test:start
+// when they start, then test:plan for their subtests, test:pass or test:fail
+// and test:diagnostic when they complete. The events go to the stream of the
+// active run(), or are kept until a run() without files replays them.
+
+let _currentRun = null;
+// Number of run() streams not finished yet
+let _activeRuns = 0;
+let _unreportedEvents = [];
+
+function hrtimeMs() {
+    return Number(process.hrtime.bigint()) / 1e6;
+}
+
+// Async top-level tests run concurrently, but like in Node.js their events
+// are reported one top-level test after the other.
+const _topLevelReports = [];
+
+function dispatchTestEvent(type, data) {
+    if (_currentRun !== null) {
+        _currentRun._emitEvent(type, data);
+    } else {
+        _unreportedEvents.push({ type: type, data: data });
+    }
+}
+
+function emitTestEvent(report, type, data) {
+    const root = report.root;
+    if (_topLevelReports[0] !== root) {
+        root.events.push({ type: type, data: data });
+    } else {
+        dispatchTestEvent(type, data);
+    }
+}
+
+function finishTopLevelReport(report) {
+    report.finished = true;
+    while (_topLevelReports.length > 0) {
+        const next = _topLevelReports[0];
+        const events = next.events;
+        next.events = [];
+        for (let i = 0; i < events.length; i++) {
+            dispatchTestEvent(events[i].type, events[i].data);
+        }
+        if (!next.finished) {
+            break;
+        }
+        _topLevelReports.shift();
+    }
+}
+
+function reportedFile(parent) {
+    if (parent.filePath !== undefined) {
+        return parent.filePath;
+    }
+    return _currentRun !== null ? _currentRun._file : undefined;
+}
+
+function testFullName(name, parent) {
+    const parentName = parent.fullName;
+    return parentName ? `${parentName} > ${name}` : name;
+}
+
+function startReport(name, parent, filePath) {
+    const report = {
+        name: name,
+        fullName: testFullName(name, parent),
+        nesting: parent._nesting + 1,
+        testNumber: ++parent._subtestCount,
+        file: filePath !== undefined ? filePath : reportedFile(parent),
+        parent: parent._report,
+        context: null,
+        failedSubtests: 0,
+        start: hrtimeMs()
+    };
+    if (report.parent === undefined) {
+        report.root = report;
+        report.events = [];
+        report.finished = false;
+        _topLevelReports.push(report);
+    } else {
+        report.root = report.parent.root;
+    }
+    emitTestEvent(report, 'test:start', { name: name, nesting: report.nesting, file: report.file });
+    return report;
+}
+
+function completeReport(report, result, isSuite) {
+    const data = {
+        name: report.name,
+        nesting: report.nesting,
+        testNumber: report.testNumber,
+        details: { duration_ms: hrtimeMs() - report.start },
+        file: report.file
+    };
+    if (isSuite) {
+        data.details.type = 'suite';
+    }
+
+    const context = report.context;
+    if (context !== null && context._subtestCount > 0) {
+        emitTestEvent(report, 'test:plan', { nesting: report.nesting + 1, count: context._subtestCount, file: report.file });
+    }
+
+    let type = 'test:pass';
+    const status = result && result.status;
+    if (status === 'skip') {
+        data.skip = result.message || true;
+    } else if (status === 'todo') {
+        data.todo = result.message || true;
+    } else if (status === 'fail') {
+        type = 'test:fail';
+        if (report.failedSubtests > 0) {
+            const count = report.failedSubtests;
+            data.details.error = new ERR_TEST_FAILURE(
+                new Error(`${count} subtest${count === 1 ? '' : 's'} failed`),
+                'subtestsFailed'
+            );
+        } else {
+            data.details.error = new ERR_TEST_FAILURE(result.error, isSuite ? 'hookFailed' : 'testCodeFailure');
+        }
+        if (report.parent !== undefined) {
+            report.parent.failedSubtests++;
+        }
+    }
+    emitTestEvent(report, type, data);
+    recordTestResult(report, type, data);
+
+    if (context !== null && context._diagnostics !== undefined) {
+        for (let i = 0; i < context._diagnostics.length; i++) {
+            emitTestEvent(report, 'test:diagnostic', { nesting: report.nesting, message: context._diagnostics[i], file: report.file });
+        }
+    }
+    if (report.root === report) {
+        finishTopLevelReport(report);
+    }
+}
+
+function reportResult(report, result, isSuite) {
+    if (result.status === 'async' || result.status === 'async-suite') {
+        result.promise = result.promise.then(function (resolved) {
+            completeReport(report, resolved && resolved.status ? resolved : { status: 'pass' }, isSuite);
+            return resolved;
+        }, function (e) {
+            completeReport(report, { status: 'fail', error: e }, isSuite);
+            throw e;
+        });
+    } else {
+        completeReport(report, result, isSuite);
+    }
+    return result;
+}
+
 // --- Sentinel errors ---
 
 function SkipError(message) {
@@ -409,6 +628,15 @@ function parseTestArgs(nameOrOpts, optionsOrFn, maybeFn) {
 // --- Run a single test ---
 
 function runTest(parsed, parentSuite) {
+    if (isFilteredOut(parsed.name, parentSuite)) {
+        return { status: 'filtered', name: parsed.name };
+    }
+    const moduleContext = parsed.moduleContext;
+    const report = startReport(parsed.name, parentSuite, moduleContext ? moduleContext.filename : undefined);
+    return reportResult(report, runTestBody(parsed, parentSuite, report), false);
+}
+
+function runTestBody(parsed, parentSuite, report) {
     const name = parsed.name;
     const options = parsed.options;
     const fn = parsed.fn;
@@ -437,8 +665,9 @@ function runTest(parsed, parentSuite) {
     // Handle todo
     const isTodo = options.todo === true || typeof options.todo === 'string';
 
-    const filePath = moduleContext ? moduleContext.filename : undefined;
-    const ctx = new TestContext(name, parentSuite, filePath);
+    const ctx = new TestContext(name, parentSuite, report.file);
+    report.context = ctx;
+    ctx._report = report;
 
     // Collect beforeEach from parent suite chain
     const beforeEachFns = parentSuite ? parentSuite.collectBeforeEach() : [];
@@ -446,7 +675,7 @@ function runTest(parsed, parentSuite) {
 
     const cleanup = function () {
         runHookListSafe(ctx._afterFns);
-        ctx.mock.restoreAll();
+        ctx.mock.reset();
     };
 
     try {
@@ -560,6 +789,11 @@ function runTest(parsed, parentSuite) {
 // --- Run a suite ---
 
 function runSuite(name, options, fn, parentSuite, moduleContext) {
+    const report = startReport(name, parentSuite, moduleContext ? moduleContext.filename : undefined);
+    return reportResult(report, runSuiteBody(name, options, fn, parentSuite, report), true);
+}
+
+function runSuiteBody(name, options, fn, parentSuite, report) {
     // Handle skip
     if (isSkipOption(options.skip)) {
         return { status: 'skip', name: name };
@@ -567,8 +801,9 @@ function runSuite(name, options, fn, parentSuite, moduleContext) {
 
     const isTodo = options.todo === true || typeof options.todo === 'string';
 
-    const filePath = moduleContext ? moduleContext.filename : undefined;
-    const suite = new SuiteContext(name, parentSuite, filePath);
+    const suite = new SuiteContext(name, parentSuite, report.file);
+    report.context = suite;
+    suite._report = report;
     const prevSuite = currentSuite;
     currentSuite = suite;
 
@@ -714,6 +949,9 @@ function shouldSkipByFilter() {
 }
 
 function test(nameOrOpts, optionsOrFn, maybeFn) {
+    if (collectTopLevel(test, arguments)) {
+        return Promise.resolve(undefined);
+    }
     const parsed = parseTestArgs(nameOrOpts, optionsOrFn, maybeFn);
 
     if (currentSuite) {
@@ -727,7 +965,8 @@ function test(nameOrOpts, optionsOrFn, maybeFn) {
         return Promise.resolve(undefined);
     }
 
-    // Top-level test — run immediately
+    // Top-level test — run immediately. Inside run(), failures are only
+    // reported to its stream, like in Node.js.
     const result = runTest(parsed, rootSuite);
     if (result.status === 'async') {
         const p = result.promise.then(function (resolved) {
@@ -737,15 +976,21 @@ function test(nameOrOpts, optionsOrFn, maybeFn) {
             return undefined;
         });
         _pendingTestPromises.push(p);
+        if (_activeRuns > 0) {
+            return p.then(function () {}, function () {});
+        }
         return p;
     }
-    if (result.status === 'fail') {
+    if (result.status === 'fail' && _activeRuns === 0) {
         throw result.error;
     }
     return Promise.resolve(undefined);
 }
 
 test.skip = function (nameOrOpts, optionsOrFn, maybeFn) {
+    if (collectTopLevel(test.skip, arguments)) {
+        return Promise.resolve(undefined);
+    }
     const parsed = parseTestArgs(nameOrOpts, optionsOrFn, maybeFn);
     parsed.options.skip = true;
 
@@ -753,11 +998,15 @@ test.skip = function (nameOrOpts, optionsOrFn, maybeFn) {
         currentSuite.tests.push(parsed);
         return Promise.resolve(undefined);
     }
-    // Top-level skip — no-op (no failure)
+    // Top-level skip — only reported
+    runTest(parsed, rootSuite);
     return Promise.resolve(undefined);
 };
 
 test.todo = function (nameOrOpts, optionsOrFn, maybeFn) {
+    if (collectTopLevel(test.todo, arguments)) {
+        return Promise.resolve(undefined);
+    }
     const parsed = parseTestArgs(nameOrOpts, optionsOrFn, maybeFn);
     parsed.options.todo = true;
 
@@ -776,6 +1025,9 @@ test.only = function (nameOrOpts, optionsOrFn, maybeFn) {
 };
 
 function describe(nameOrOpts, optionsOrFn, maybeFn) {
+    if (collectTopLevel(describe, arguments)) {
+        return;
+    }
     const parsed = parseTestArgs(nameOrOpts, optionsOrFn, maybeFn);
 
     if (currentSuite) {
@@ -805,12 +1057,15 @@ function describe(nameOrOpts, optionsOrFn, maybeFn) {
                 }
             }));
         }
-    } else if (result.status === 'fail') {
+    } else if (result.status === 'fail' && _activeRuns === 0) {
         throw result.error;
     }
 }
 
 describe.skip = function (nameOrOpts, optionsOrFn, maybeFn) {
+    if (collectTopLevel(describe.skip, arguments)) {
+        return;
+    }
     const parsed = parseTestArgs(nameOrOpts, optionsOrFn, maybeFn);
     parsed.options.skip = true;
 
@@ -824,10 +1079,14 @@ describe.skip = function (nameOrOpts, optionsOrFn, maybeFn) {
         });
         return;
     }
-    // Top-level skip suite — no-op
+    // Top-level skip suite — only reported
+    runSuite(parsed.name, parsed.options, parsed.fn, rootSuite, parsed.moduleContext);
 };
 
 describe.todo = function (nameOrOpts, optionsOrFn, maybeFn) {
+    if (collectTopLevel(describe.todo, arguments)) {
+        return;
+    }
     const parsed = parseTestArgs(nameOrOpts, optionsOrFn, maybeFn);
     parsed.options.todo = true;
 
@@ -1214,6 +1473,9 @@ MockTracker.prototype.restoreAll = function () {
 
 MockTracker.prototype.reset = function () {
     this.restoreAll();
+    if (this._timers !== undefined) {
+        this._timers.reset();
+    }
 };
 
 MockTracker.prototype.getter = function (obj, methodName, implementation, options) {
@@ -1282,13 +1544,480 @@ MockTracker.prototype.module = function module(specifier, options) {
     return handle;
 };
 
-MockTracker.prototype.timers = { enable: function () {}, reset: function () {}, tick: function () {} };
+Object.defineProperty(MockTracker.prototype, 'timers', {
+    get: function () {
+        if (this._timers === undefined) {
+            this._timers = new MockTimers();
+        }
+        return this._timers;
+    },
+    configurable: true
+});
+
+// --- MockTimers ---
+//
+// The mocked timer APIs are scheduled on the fake clock of the timeout builtin,
+// so `node:timers` and `node:timers/promises` follow it as well.
+
+const SUPPORTED_MOCK_TIMER_APIS = ['setInterval', 'setTimeout', 'setImmediate', 'Date'];
+
+function createMockDate(RealDate) {
+    function MockDate(...args) {
+        if (new.target === undefined) {
+            return new RealDate(_mockNow()).toString();
+        }
+        return Reflect.construct(RealDate, args.length === 0 ? [_mockNow()] : args, new.target);
+    }
+    Object.setPrototypeOf(MockDate, RealDate);
+    MockDate.prototype = RealDate.prototype;
+    MockDate.now = function now() {
+        return _mockNow();
+    };
+    return MockDate;
+}
+
+class MockTimers {
+    #enabled = false;
+    #realDate = undefined;
+
+    #assertEnabled() {
+        if (!this.#enabled) {
+            throw new ERR_INVALID_STATE('You should enable MockTimers first by calling the .enable function');
+        }
+    }
+
+    enable(options = {}) {
+        if (this.#enabled) {
+            throw new ERR_INVALID_STATE('MockTimers is already enabled!');
+        }
+        validateObject(options, 'options');
+        const apis = options.apis !== undefined ? options.apis : SUPPORTED_MOCK_TIMER_APIS;
+        validateArray(apis, 'options.apis');
+        for (let i = 0; i < apis.length; i++) {
+            if (!SUPPORTED_MOCK_TIMER_APIS.includes(apis[i])) {
+                throw new ERR_INVALID_ARG_VALUE('options.apis', apis[i], `option ${apis[i]} is not supported`);
+            }
+        }
+        let now = options.now !== undefined ? options.now : 0;
+        if (now instanceof Date) {
+            now = now.getTime();
+        }
+        validateNumber(now, 'initialTime');
+        if (now < 0) {
+            throw new ERR_INVALID_ARG_VALUE('initialTime', now, 'must be a positive integer');
+        }
+
+        if (!_enableMockTimers(apis, now)) {
+            throw new ERR_INVALID_STATE('Timers are already mocked by another MockTracker');
+        }
+        if (apis.includes('Date')) {
+            this.#realDate = globalThis.Date;
+            globalThis.Date = createMockDate(this.#realDate);
+        }
+        this.#enabled = true;
+    }
+
+    tick(milliseconds = 1) {
+        this.#assertEnabled();
+        validateNumber(milliseconds, 'time');
+        if (milliseconds < 0) {
+            throw new ERR_INVALID_ARG_VALUE('time', milliseconds, 'must be a positive number');
+        }
+        _tickMockTimers(milliseconds);
+    }
+
+    runAll() {
+        this.#assertEnabled();
+        _runAllMockTimers();
+    }
+
+    setTime(milliseconds) {
+        this.#assertEnabled();
+        validateNumber(milliseconds, 'milliseconds');
+        _setMockTime(milliseconds);
+    }
+
+    reset() {
+        if (!this.#enabled) {
+            return;
+        }
+        _disableMockTimers();
+        if (this.#realDate !== undefined) {
+            globalThis.Date = this.#realDate;
+            this.#realDate = undefined;
+        }
+        this.#enabled = false;
+    }
+
+    [Symbol.dispose]() {
+        this.reset();
+    }
+}
 
 const mock = new MockTracker();
 
-function run() {
-    // Stub — no-op for now
-    return { on: function () { return this; }, once: function () { return this; } };
+// --- Snapshots ---
+//
+// Snapshot files use the Node.js format: a CommonJS module assigning the
+// serialized values to `exports`. They are written when the
+// NODE_TEST_UPDATE_SNAPSHOTS environment variable is set.
+
+let _snapshotSerializers = [function (value) { return JSON.stringify(value, null, 2); }];
+let _resolveSnapshotPath = defaultResolveSnapshotPath;
+const _snapshotFiles = new Map();
+
+// Next to the test file, or in NODE_TEST_SNAPSHOT_DIR (by default the root of
+// the preopened directory) for tests of embedded modules.
+function defaultResolveSnapshotPath(testFile) {
+    if (typeof testFile === 'string' && path.isAbsolute(testFile)) {
+        return `${testFile}.snapshot`;
+    }
+    const directory = process.env.NODE_TEST_SNAPSHOT_DIR || '/';
+    return path.join(directory, `${typeof testFile === 'string' ? path.basename(testFile) : 'test'}.snapshot`);
+}
+
+function shouldUpdateSnapshots() {
+    const update = process.env.NODE_TEST_UPDATE_SNAPSHOTS;
+    return (update !== undefined && update !== '' && update !== '0' && update !== 'false') ||
+        process.execArgv.includes('--test-update-snapshots');
+}
+
+function validateFunctionArray(value, name) {
+    validateArray(value, name);
+    for (let i = 0; i < value.length; i++) {
+        validateFunction(value[i], `${name}[${i}]`);
+    }
+}
+
+function templateEscape(str) {
+    return str.replace(/\\/g, '\\\\').replace(/`/g, '\\`').replace(/\$\{/g, '\\${');
+}
+
+function serializeSnapshot(value, serializers) {
+    let serialized = value;
+    for (let i = 0; i < serializers.length; i++) {
+        serialized = serializers[i](serialized);
+    }
+    if (typeof serialized !== 'string') {
+        throw new ERR_INVALID_STATE('The provided serializers did not generate a string.');
+    }
+    return serialized;
+}
+
+function loadSnapshotFile(snapshotPath) {
+    let snapshots = _snapshotFiles.get(snapshotPath);
+    if (snapshots === undefined) {
+        snapshots = new Map();
+        if (fs.existsSync(snapshotPath)) {
+            const exports = Object.create(null);
+            new Function('exports', fs.readFileSync(snapshotPath, 'utf8'))(exports);
+            for (const key of Object.keys(exports)) {
+                snapshots.set(key, exports[key]);
+            }
+        }
+        _snapshotFiles.set(snapshotPath, snapshots);
+    }
+    return snapshots;
+}
+
+function writeSnapshotFile(snapshotPath, snapshots) {
+    const entries = [...snapshots.keys()].sort().map(function (key) {
+        return `exports[\`${templateEscape(key)}\`] = \`${templateEscape(snapshots.get(key))}\`;\n`;
+    });
+    fs.mkdirSync(path.dirname(snapshotPath), { recursive: true });
+    fs.writeFileSync(snapshotPath, entries.join('\n'));
+}
+
+function missingSnapshotError(what) {
+    return new ERR_INVALID_STATE(
+        `Missing ${what}. Missing snapshots can be generated by rerunning with the ` +
+        'NODE_TEST_UPDATE_SNAPSHOTS environment variable set to 1.'
+    );
+}
+
+function assertSnapshot(ctx, value, options = {}) {
+    validateObject(options, 'options');
+    const serializers = options.serializers !== undefined ? options.serializers : _snapshotSerializers;
+    validateFunctionArray(serializers, 'options.serializers');
+
+    const snapshotPath = _resolveSnapshotPath(ctx.filePath);
+    if (typeof snapshotPath !== 'string') {
+        throw new ERR_INVALID_STATE(`Invalid snapshot filename '${snapshotPath}'.`);
+    }
+    const snapshots = loadSnapshotFile(snapshotPath);
+    const key = `${ctx.fullName} ${++ctx._snapshotCount}`;
+    const actual = `\n${serializeSnapshot(value, serializers)}\n`;
+
+    if (shouldUpdateSnapshots()) {
+        snapshots.set(key, actual);
+        writeSnapshotFile(snapshotPath, snapshots);
+        return;
+    }
+    if (!snapshots.has(key)) {
+        throw missingSnapshotError(`snapshot '${key}' in ${snapshotPath}`);
+    }
+    assert.strictEqual(actual, snapshots.get(key));
+}
+
+function assertFileSnapshot(value, filePath, options = {}) {
+    if (typeof filePath !== 'string') {
+        throw new ERR_INVALID_ARG_TYPE('path', 'string', filePath);
+    }
+    validateObject(options, 'options');
+    const serializers = options.serializers !== undefined
+        ? options.serializers
+        : [function (v) { return typeof v === 'string' ? v : JSON.stringify(v, null, 2); }];
+    validateFunctionArray(serializers, 'options.serializers');
+
+    const actual = serializeSnapshot(value, serializers);
+    if (shouldUpdateSnapshots()) {
+        fs.mkdirSync(path.dirname(filePath), { recursive: true });
+        fs.writeFileSync(filePath, actual);
+        return;
+    }
+    if (!fs.existsSync(filePath)) {
+        throw missingSnapshotError(`snapshot file ${filePath}`);
+    }
+    assert.strictEqual(actual, fs.readFileSync(filePath, 'utf8'));
+}
+
+const snapshot = {
+    setDefaultSnapshotSerializers: function setDefaultSnapshotSerializers(serializers) {
+        validateFunctionArray(serializers, 'serializers');
+        _snapshotSerializers = serializers.slice();
+    },
+    setResolveSnapshotPath: function setResolveSnapshotPath(fn) {
+        validateFunction(fn, 'fn');
+        _resolveSnapshotPath = fn;
+    }
+};
+
+// --- run() ---
+//
+// Tests run in this realm, as with `isolation: 'none'` in Node.js: `files` are
+// imported one after the other, and the events of their tests streamed. Without
+// `files`, the tests that already ran are reported, followed by the pending ones.
+
+class TestsStream extends Readable {
+    constructor() {
+        super({ objectMode: true, highWaterMark: Number.MAX_SAFE_INTEGER });
+        this._file = undefined;
+        this._success = true;
+        this._counts = { tests: 0, suites: 0, passed: 0, failed: 0, cancelled: 0, skipped: 0, todo: 0, topLevel: 0 };
+    }
+
+    _read() {}
+
+    _emitEvent(type, data) {
+        if (type === 'test:pass' || type === 'test:fail') {
+            this._count(type, data);
+        }
+        this.emit(type, data);
+        this.push({ type: type, data: data });
+    }
+
+    _count(type, data) {
+        const counts = this._counts;
+        if (data.nesting === 0) {
+            counts.topLevel++;
+            if (type === 'test:fail' && data.todo === undefined) {
+                this._success = false;
+            }
+        }
+        if (data.details.type === 'suite') {
+            counts.suites++;
+            return;
+        }
+        counts.tests++;
+        if (data.skip !== undefined) {
+            counts.skipped++;
+        } else if (data.todo !== undefined) {
+            counts.todo++;
+        } else if (type === 'test:fail') {
+            counts.failed++;
+            this._success = false;
+        } else {
+            counts.passed++;
+        }
+    }
+}
+
+let _runQueue = Promise.resolve();
+
+async function settlePendingTests() {
+    while (_pendingTestPromises.length > 0) {
+        const promises = _pendingTestPromises;
+        _pendingTestPromises = [];
+        await Promise.allSettled(promises);
+    }
+}
+
+async function runFile(stream, file) {
+    const specifier = file.startsWith('./') || file.startsWith('../') ? path.resolve(file) : file;
+    stream._file = specifier;
+    try {
+        await import(specifier);
+        await settlePendingTests();
+    } catch (e) {
+        const report = startReport(specifier, rootSuite, specifier);
+        completeReport(report, { status: 'fail', error: e }, false);
+    }
+    stream._file = undefined;
+}
+
+async function executeRun(stream, files) {
+    const start = hrtimeMs();
+    _currentRun = stream;
+    try {
+        if (files === undefined) {
+            const events = _unreportedEvents;
+            _unreportedEvents = [];
+            for (let i = 0; i < events.length; i++) {
+                stream._emitEvent(events[i].type, events[i].data);
+            }
+            await settlePendingTests();
+        } else {
+            rootSuite._subtestCount = 0;
+            for (let i = 0; i < files.length; i++) {
+                await runFile(stream, files[i]);
+            }
+        }
+
+        const counts = stream._counts;
+        const duration = hrtimeMs() - start;
+        stream._emitEvent('test:plan', { nesting: 0, count: counts.topLevel });
+        const diagnostics = [
+            ['tests', counts.tests],
+            ['suites', counts.suites],
+            ['pass', counts.passed],
+            ['fail', counts.failed],
+            ['cancelled', counts.cancelled],
+            ['skipped', counts.skipped],
+            ['todo', counts.todo],
+            ['duration_ms', duration]
+        ];
+        for (let i = 0; i < diagnostics.length; i++) {
+            stream._emitEvent('test:diagnostic', { nesting: 0, message: `${diagnostics[i][0]} ${diagnostics[i][1]}` });
+        }
+        stream._emitEvent('test:summary', {
+            success: stream._success,
+            counts: Object.assign({}, counts),
+            duration_ms: duration,
+            file: undefined
+        });
+        stream.push(null);
+    } catch (e) {
+        stream.destroy(e);
+    } finally {
+        _currentRun = null;
+        _activeRuns--;
+    }
+}
+
+function run(options = {}) {
+    validateObject(options, 'options');
+    const files = options.files;
+    if (files !== undefined) {
+        validateArray(files, 'options.files');
+        for (let i = 0; i < files.length; i++) {
+            if (typeof files[i] !== 'string') {
+                throw new ERR_INVALID_ARG_TYPE(`options.files[${i}]`, 'string', files[i]);
+            }
+        }
+    }
+
+    const stream = new TestsStream();
+    _activeRuns++;
+    _runQueue = _runQueue.then(function () {
+        return executeRun(stream, files);
+    });
+    return stream;
+}
+
+// --- Collected tests ---
+//
+// With the `run-tests` export of the generated component, top-level tests and
+// suites are only registered while the JS modules are evaluated. The export
+// runs them one after the other, and returns the result of each test.
+
+let _collectedTests = null;
+let _testResults = null;
+let _testNamePattern = null;
+
+function _collectTests() {
+    _collectedTests = [];
+}
+
+function collectTopLevel(register, args) {
+    if (_collectedTests === null || currentSuite) {
+        return false;
+    }
+    _collectedTests.push({ register: register, args: args });
+    return true;
+}
+
+function isFilteredOut(name, parent) {
+    return _testNamePattern !== null && !_testNamePattern.test(testFullName(name, parent));
+}
+
+function failureMessage(error) {
+    if (error instanceof Error) {
+        return error.message;
+    }
+    return String(error);
+}
+
+// Suites are only listed when they did not just pass or fail because of their tests
+function recordTestResult(report, type, data) {
+    if (_testResults === null) {
+        return;
+    }
+    const error = data.details.error;
+    const isSuite = data.details.type === 'suite';
+    let status;
+    if (data.skip !== undefined) {
+        status = 'skip';
+    } else if (data.todo !== undefined) {
+        status = 'todo';
+    } else if (type === 'test:fail') {
+        if (isSuite && error.failureType === 'subtestsFailed') {
+            return;
+        }
+        status = 'fail';
+    } else if (isSuite) {
+        return;
+    } else {
+        status = 'pass';
+    }
+    _testResults.push({
+        name: report.fullName,
+        status: status,
+        durationMs: data.details.duration_ms,
+        failure: type === 'test:fail' ? failureMessage(error.cause) : undefined
+    });
+}
+
+async function _runCollectedTests(filter) {
+    const collected = _collectedTests !== null ? _collectedTests : [];
+    _collectedTests = null;
+    _testNamePattern = filter === undefined || filter === null ? null : new RegExp(filter);
+    _testResults = [];
+    try {
+        for (let i = 0; i < collected.length; i++) {
+            try {
+                collected[i].register.apply(undefined, collected[i].args);
+                await _awaitPendingTests();
+            } catch (e) {
+                // Failures are part of the results
+            }
+        }
+        return _testResults;
+    } finally {
+        // Registered again, so the tests can be run more than once
+        _collectedTests = collected;
+        _testResults = null;
+        _testNamePattern = null;
+    }
 }
 
 function __setFilterIndex(idx) {
@@ -1320,8 +2049,11 @@ export {
     afterEach,
     mock,
     run,
+    snapshot,
     testAssertionsModule as assert,
     _awaitPendingTests,
+    _collectTests,
+    _runCollectedTests,
     __setFilterIndex,
     __clearFilter
 };
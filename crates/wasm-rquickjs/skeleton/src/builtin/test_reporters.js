@@ -0,0 +1,364 @@
+// node:test/reporters — formats the events of a node:test TestsStream, as
+// returned by run(). `tap`, `dot` and `junit` are async generators and `spec`
+// is a Transform, all usable with `stream.compose()`.
+
+import { Transform } from 'node:stream';
+import { inspect } from 'node:util';
+import { hostname } from 'node:os';
+
+const kInspectOptions = { colors: false, breakLength: Infinity };
+
+function errorCause(error) {
+    return error && error.code === 'ERR_TEST_FAILURE' && error.cause !== undefined ? error.cause : error;
+}
+
+function indentLines(text, indent) {
+    return text.split('\n').map(line => `${indent}${line}`).join('\n');
+}
+
+function formatDuration(data) {
+    return `(${data.details.duration_ms}ms)`;
+}
+
+function directive(data) {
+    if (data.skip !== undefined) {
+        return ` # SKIP${typeof data.skip === 'string' && data.skip.length > 0 ? ` ${data.skip}` : ''}`;
+    }
+    if (data.todo !== undefined) {
+        return ` # TODO${typeof data.todo === 'string' && data.todo.length > 0 ? ` ${data.todo}` : ''}`;
+    }
+    return '';
+}
+
+// --- TAP ---
+
+const kTapEscapes = [
+    [/\\/g, '\\\\'],
+    [/#/g, '\\#'],
+    [/\n/g, '\\n'],
+    [/\t/g, '\\t'],
+    [/\r/g, '\\r'],
+    [/\f/g, '\\f'],
+    [/\v/g, '\\v'],
+];
+
+function tapEscape(text) {
+    let escaped = String(text);
+    for (const [pattern, replacement] of kTapEscapes) {
+        escaped = escaped.replace(pattern, replacement);
+    }
+    return escaped;
+}
+
+function tapIndent(nesting) {
+    return '    '.repeat(nesting);
+}
+
+function yamlValue(value, indent) {
+    if (typeof value === 'string') {
+        if (value.includes('\n')) {
+            return `|-\n${indentLines(value, `${indent}  `)}`;
+        }
+        return `'${value.replace(/'/g, "''")}'`;
+    }
+    return inspect(value, kInspectOptions);
+}
+
+function tapYaml(data) {
+    const indent = `${tapIndent(data.nesting)}  `;
+    const lines = [`duration_ms: ${data.details.duration_ms}`];
+    if (data.details.type !== undefined) {
+        lines.push(`type: '${data.details.type}'`);
+    }
+
+    const error = data.details.error;
+    if (error !== undefined) {
+        const cause = errorCause(error);
+        if (error.failureType !== undefined) {
+            lines.push(`failureType: '${error.failureType}'`);
+        }
+        const message = cause instanceof Error ? cause.message : inspect(cause, kInspectOptions);
+        lines.push(`error: ${yamlValue(message, indent)}`);
+        if (cause && cause.code !== undefined) {
+            lines.push(`code: ${yamlValue(String(cause.code), indent)}`);
+        }
+        if (cause instanceof Error) {
+            lines.push(`name: '${cause.name}'`);
+            if ('expected' in cause) {
+                lines.push(`expected: ${yamlValue(cause.expected, indent)}`);
+            }
+            if ('actual' in cause) {
+                lines.push(`actual: ${yamlValue(cause.actual, indent)}`);
+            }
+            if (cause.operator !== undefined) {
+                lines.push(`operator: '${cause.operator}'`);
+            }
+            const stack = typeof cause.stack === 'string'
+                ? cause.stack.split('\n').map(line => line.trim()).filter(line => line.startsWith('at ')).join('\n')
+                : '';
+            if (stack.length > 0) {
+                lines.push(`stack: |-\n${indentLines(stack, `${indent}  `)}`);
+            }
+        }
+    }
+
+    return `${indent}---\n${lines.map(line => `${indent}${line}`).join('\n')}\n${indent}...\n`;
+}
+
+async function* tap(source) {
+    yield 'TAP version 13\n';
+    for await (const { type, data } of source) {
+        switch (type) {
+            case 'test:start':
+                yield `${tapIndent(data.nesting)}# Subtest: ${tapEscape(data.name)}\n`;
+                break;
+            case 'test:pass':
+            case 'test:fail': {
+                const status = type === 'test:pass' ? 'ok' : 'not ok';
+                yield `${tapIndent(data.nesting)}${status} ${data.testNumber} - ${tapEscape(data.name)}${directive(data)}\n`;
+                yield tapYaml(data);
+                break;
+            }
+            case 'test:plan':
+                yield `${tapIndent(data.nesting)}1..${data.count}\n`;
+                break;
+            case 'test:diagnostic':
+                yield `${tapIndent(data.nesting)}# ${tapEscape(data.message)}\n`;
+                break;
+        }
+    }
+}
+
+// --- spec and dot ---
+
+const kSymbols = {
+    'test:pass': '✔ ',
+    'test:fail': '✖ ',
+    'test:diagnostic': 'ℹ ',
+    skip: '﹣ ',
+    suite: '▶ ',
+};
+
+function specIndent(nesting) {
+    return '  '.repeat(nesting);
+}
+
+function formatError(error, indent) {
+    const cause = errorCause(error);
+    return `${indentLines(inspect(cause, kInspectOptions), indent)}\n`;
+}
+
+function formatTestResult(type, data, prefix = '') {
+    const indent = specIndent(data.nesting);
+    const symbol = data.skip !== undefined || data.todo !== undefined ? kSymbols.skip : kSymbols[type];
+    let output = `${prefix}${indent}${symbol}${data.name} ${formatDuration(data)}${directive(data)}\n`;
+    const error = data.details.error;
+    if (type === 'test:fail' && error !== undefined && error.failureType !== 'subtestsFailed') {
+        output += formatError(error, `${indent}  `);
+    }
+    return output;
+}
+
+function formatFailedTests(failedTests) {
+    if (failedTests.length === 0) {
+        return '';
+    }
+    let output = `\n${kSymbols['test:fail']}failing tests:\n\n`;
+    for (const data of failedTests) {
+        output += formatTestResult('test:fail', { ...data, nesting: 0 });
+    }
+    return output;
+}
+
+class SpecReporter extends Transform {
+    #stack = [];
+    #failedTests = [];
+
+    constructor() {
+        super({ writableObjectMode: true });
+    }
+
+    #handleEvent({ type, data }) {
+        switch (type) {
+            case 'test:start':
+                this.#stack.push({ name: data.name, nesting: data.nesting, printed: false });
+                return '';
+            case 'test:pass':
+            case 'test:fail': {
+                const index = this.#stack.findLastIndex(entry => entry.nesting === data.nesting);
+                if (index !== -1) {
+                    this.#stack.splice(index);
+                }
+                let headers = '';
+                for (const entry of this.#stack) {
+                    if (!entry.printed) {
+                        headers += `${specIndent(entry.nesting)}${kSymbols.suite}${entry.name}\n`;
+                        entry.printed = true;
+                    }
+                }
+                if (type === 'test:fail' && data.details.error?.failureType !== 'subtestsFailed') {
+                    this.#failedTests.push(data);
+                }
+                return formatTestResult(type, data, headers);
+            }
+            case 'test:diagnostic':
+                return `${specIndent(data.nesting)}${kSymbols['test:diagnostic']}${data.message}\n`;
+            default:
+                return '';
+        }
+    }
+
+    _transform(event, encoding, callback) {
+        callback(null, this.#handleEvent(event));
+    }
+
+    _flush(callback) {
+        callback(null, formatFailedTests(this.#failedTests));
+    }
+}
+
+async function* dot(source) {
+    let count = 0;
+    const failedTests = [];
+    for await (const { type, data } of source) {
+        if (type === 'test:pass') {
+            yield '.';
+        } else if (type === 'test:fail') {
+            yield 'X';
+            if (data.details.error?.failureType !== 'subtestsFailed') {
+                failedTests.push(data);
+            }
+        } else {
+            continue;
+        }
+        if (++count === 20) {
+            yield '\n';
+            count = 0;
+        }
+    }
+    yield '\n';
+    yield formatFailedTests(failedTests);
+}
+
+// --- JUnit ---
+
+function escapeXml(value) {
+    return String(value)
+        .replace(/&/g, '&amp;')
+        .replace(/</g, '&lt;')
+        .replace(/>/g, '&gt;')
+        .replace(/"/g, '&quot;')
+        .replace(/'/g, '&apos;');
+}
+
+function xmlAttributes(attrs) {
+    return Object.entries(attrs)
+        .filter(([, value]) => value !== undefined)
+        .map(([key, value]) => ` ${key}="${escapeXml(value)}"`)
+        .join('');
+}
+
+function junitNode(node, level) {
+    const indent = '\t'.repeat(level);
+    if (node.tag === 'comment') {
+        return `${indent}<!-- ${escapeXml(node.comment)} -->\n`;
+    }
+    if (node.children.length === 0 && node.body === undefined) {
+        return `${indent}<${node.tag}${xmlAttributes(node.attrs)}/>\n`;
+    }
+    let output = `${indent}<${node.tag}${xmlAttributes(node.attrs)}>\n`;
+    if (node.body !== undefined) {
+        output += `${indentLines(escapeXml(node.body), `${indent}\t`)}\n`;
+    }
+    for (const child of node.children) {
+        output += junitNode(child, level + 1);
+    }
+    return `${output}${indent}</${node.tag}>\n`;
+}
+
+function completeJunitNode(node, type, data) {
+    const time = (data.details.duration_ms / 1000).toFixed(6);
+    if (node.children.length > 0) {
+        const testcases = node.children.filter(child => child.tag !== 'comment');
+        node.tag = 'testsuite';
+        node.attrs = {
+            name: data.name,
+            time,
+            disabled: 0,
+            errors: 0,
+            tests: testcases.length,
+            failures: testcases.filter(child => child.failed).length,
+            skipped: testcases.filter(child => child.skipped).length,
+            hostname: hostname(),
+        };
+    } else {
+        node.attrs = { name: data.name, time, classname: 'test' };
+    }
+
+    if (data.skip !== undefined || data.todo !== undefined) {
+        node.skipped = true;
+        const skipType = data.skip !== undefined ? 'skipped' : 'todo';
+        const message = data.skip !== undefined ? data.skip : data.todo;
+        node.children.push({
+            tag: 'skipped',
+            attrs: { type: skipType, message: typeof message === 'string' ? message : '' },
+            children: [],
+        });
+    }
+    if (type === 'test:fail' && data.todo === undefined) {
+        const error = data.details.error;
+        node.failed = true;
+        if (node.tag === 'testcase') {
+            node.children.push({
+                tag: 'failure',
+                attrs: { type: error?.failureType, message: errorCause(error)?.message ?? String(error) },
+                body: inspect(errorCause(error), kInspectOptions),
+                children: [],
+            });
+        }
+    }
+}
+
+async function* junit(source) {
+    yield '<?xml version="1.0" encoding="utf-8"?>\n';
+    yield '<testsuites>\n';
+    const stack = [];
+    const root = { children: [] };
+    for await (const { type, data } of source) {
+        const parent = stack.length > 0 ? stack[stack.length - 1] : root;
+        switch (type) {
+            case 'test:start': {
+                const node = { tag: 'testcase', attrs: {}, children: [], nesting: data.nesting };
+                parent.children.push(node);
+                stack.push(node);
+                break;
+            }
+            case 'test:pass':
+            case 'test:fail': {
+                const node = stack.pop();
+                if (node !== undefined) {
+                    completeJunitNode(node, type, data);
+                    if (stack.length === 0) {
+                        yield junitNode(node, 1);
+                    }
+                }
+                break;
+            }
+            case 'test:diagnostic':
+                if (stack.length === 0) {
+                    yield junitNode({ tag: 'comment', comment: data.message }, 1);
+                } else {
+                    parent.children.push({ tag: 'comment', comment: data.message });
+                }
+                break;
+        }
+    }
+    yield '</testsuites>\n';
+}
+
+// Subclass named like the Node.js export, so `new spec()` works
+const spec = class spec extends SpecReporter {};
+
+export { tap, spec, dot, junit };
+
+export default { tap, spec, dot, junit };
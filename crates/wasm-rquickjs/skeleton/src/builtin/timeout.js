@@ -1,5 +1,16 @@
 import * as timeoutNative from '__wasm_rquickjs_builtin/timeout_native'
-import { _captureContext, _restoreContext } from 'node:async_hooks'
+import { _captureContext, _emitDestroy, _emitInit, _restoreContext, _runInAsyncScope, executionAsyncId } from '__wasm_rquickjs_builtin/async_hooks'
+
+// Timers of this realm that have not fired or been cleared yet. Worker realms use it to
+// find out whether they still have work to do and to cancel everything on termination.
+const activeTimers = new Set();
+let timerActivityHook = null;
+
+function notifyTimerActivity() {
+    if (timerActivityHook !== null) {
+        timerActivityHook();
+    }
+}
 
 class Timeout {
     constructor(id, callback, delay, args, isInterval) {
@@ -13,6 +24,9 @@ class Timeout {
         this.__idleTimeout = delay;
         this.__onTimeout = callback;
         this._repeat = isInterval ? delay : null;
+        this._asyncId = 0;
+        this._triggerAsyncId = 0;
+        this._asyncDestroyed = true;
     }
 
     get _idleTimeout() {
@@ -59,6 +73,7 @@ class Timeout {
             const bound = this._bound || this._callback.bind(this);
             this._bound = bound;
             this._id = timeoutNative.schedule(bound, this._delay, this._isInterval, this._args);
+            activeTimers.add(this);
         }
         return this;
     }
@@ -67,6 +82,72 @@ class Timeout {
         if (!this._destroyed) {
             this._destroyed = true;
             timeoutNative.clear_schedule(this._id);
+            activeTimers.delete(this);
+            emitTimerDestroy(this);
+            notifyTimerActivity();
+        }
+        return this;
+    }
+
+    [Symbol.toPrimitive]() {
+        return this._id;
+    }
+
+    [Symbol.dispose]() {
+        this.close();
+    }
+}
+
+// Emits the async hooks `destroy` event of a timer once it will not run anymore
+function emitTimerDestroy(timeout) {
+    if (!timeout._asyncDestroyed) {
+        timeout._asyncDestroyed = true;
+        _emitDestroy(timeout._asyncId);
+    }
+}
+
+// Fake clock of node:test's `mock.timers`. While it is enabled, the mocked APIs schedule their
+// callbacks on it instead of the executor, and they only run when the clock is advanced.
+let mockClock = null;
+
+class MockTimeout {
+    constructor(clock, id, callback, delay, args, isInterval) {
+        this._clock = clock;
+        this._id = id;
+        this._destroyed = false;
+        this._refed = true;
+        this._callback = callback;
+        this._delay = delay;
+        this._args = args;
+        this._isInterval = isInterval;
+        this._runAt = clock.now + delay;
+    }
+
+    ref() {
+        this._refed = true;
+        return this;
+    }
+
+    unref() {
+        this._refed = false;
+        return this;
+    }
+
+    hasRef() {
+        return this._refed && !this._destroyed;
+    }
+
+    refresh() {
+        if (!this._destroyed) {
+            this._runAt = this._clock.now + this._delay;
+        }
+        return this;
+    }
+
+    close() {
+        if (!this._destroyed) {
+            this._destroyed = true;
+            this._clock.timers.delete(this._id);
         }
         return this;
     }
@@ -80,6 +161,79 @@ class Timeout {
     }
 }
 
+function isMocked(api) {
+    return mockClock !== null && mockClock.apis.has(api);
+}
+
+function scheduleMockTimeout(callback, delay, args, isInterval) {
+    const id = timeoutNative.reserve_id();
+    // Intervals of 0 ms would never let the clock advance
+    const timeout = new MockTimeout(mockClock, id, callback, isInterval ? Math.max(delay, 1) : delay, args, isInterval);
+    mockClock.timers.set(id, timeout);
+    return timeout;
+}
+
+// Runs the mocked timers due until `target`, in the order of their due time and creation
+function advanceMockClock(clock, target) {
+    for (;;) {
+        let next = null;
+        for (const timeout of clock.timers.values()) {
+            if (timeout._runAt <= target && (next === null || timeout._runAt < next._runAt)) {
+                next = timeout;
+            }
+        }
+        if (next === null) {
+            break;
+        }
+        clock.now = Math.max(clock.now, next._runAt);
+        if (next._isInterval) {
+            next._runAt += next._delay;
+        } else {
+            next.close();
+        }
+        next._callback.apply(next, next._args);
+    }
+    clock.now = Math.max(clock.now, target);
+}
+
+export function _enableMockTimers(apis, now) {
+    if (mockClock !== null) {
+        return false;
+    }
+    mockClock = { apis: new Set(apis), now, timers: new Map() };
+    return true;
+}
+
+export function _disableMockTimers() {
+    if (mockClock !== null) {
+        for (const timeout of mockClock.timers.values()) {
+            timeout._destroyed = true;
+        }
+        mockClock = null;
+    }
+}
+
+export function _mockNow() {
+    return mockClock.now;
+}
+
+export function _setMockTime(now) {
+    mockClock.now = now;
+}
+
+export function _tickMockTimers(milliseconds) {
+    advanceMockClock(mockClock, mockClock.now + milliseconds);
+}
+
+export function _runAllMockTimers() {
+    const clock = mockClock;
+    let last = clock.now;
+    for (const timeout of clock.timers.values()) {
+        last = Math.max(last, timeout._runAt);
+    }
+    advanceMockClock(clock, last);
+}
+
 function validateCallback(callback) {
     if (typeof callback !== 'function') {
         const err = new TypeError('The "callback" argument must be of type function. Received ' + (callback === null ? 'null' : typeof callback));
@@ -104,7 +258,7 @@ function normalizeTimerDelay(time) {
     return Math.trunc(delay);
 }
 
-function scheduleTimeout(callback, time, args, isInterval) {
+function scheduleTimeout(type, callback, time, args, isInterval) {
     const snapshot = _captureContext();
     const wrapped = function(...a) {
         const currentId = this._id;
@@ -115,7 +269,10 @@ function scheduleTimeout(callback, time, args, isInterval) {
             if (globalThis.__wasm_rquickjs_drainNextTick) {
                 globalThis.__wasm_rquickjs_drainNextTick();
             }
-            return _restoreContext(snapshot, callback, this, a);
+            if (this._asyncId === 0) {
+                return _restoreContext(snapshot, callback, this, a);
+            }
+            return _runInAsyncScope(this._asyncId, this._triggerAsyncId, this, _restoreContext, undefined, [snapshot, callback, this, a]);
         } catch (e) {
             if (globalThis.__wasm_rquickjs_handleUncaughtError) {
                 globalThis.__wasm_rquickjs_handleUncaughtError(e);
@@ -130,41 +287,75 @@ function scheduleTimeout(callback, time, args, isInterval) {
                 if (!this._refed) {
                     timeoutNative.unref_schedule(nextId);
                 }
+            } else if (!isInterval && this._id === currentId) {
+                activeTimers.delete(this);
+                emitTimerDestroy(this);
             }
+            notifyTimerActivity();
         }
     };
     const timeout = new Timeout(0, wrapped, time, args, isInterval);
+    if (type !== null) {
+        timeout._triggerAsyncId = executionAsyncId();
+        timeout._asyncId = _emitInit(type, timeout, timeout._triggerAsyncId);
+        timeout._asyncDestroyed = false;
+    }
     const bound = wrapped.bind(timeout);
     const id = timeoutNative.schedule(bound, time, isInterval, args);
     timeout._id = id;
     timeout._bound = bound;
+    activeTimers.add(timeout);
     return timeout;
 }
 
 export function setTimeout(callback, time, ...args) {
     validateCallback(callback);
-    return scheduleTimeout(callback, normalizeTimerDelay(time), args, false);
+    if (isMocked('setTimeout')) {
+        return scheduleMockTimeout(callback, normalizeTimerDelay(time), args, false);
+    }
+    return scheduleTimeout('Timeout', callback, normalizeTimerDelay(time), args, false);
 }
 
 export function setInterval(callback, time, ...args) {
     validateCallback(callback);
-    return scheduleTimeout(callback, normalizeTimerDelay(time), args, true);
+    if (isMocked('setInterval')) {
+        return scheduleMockTimeout(callback, normalizeTimerDelay(time), args, true);
+    }
+    return scheduleTimeout('Timeout', callback, normalizeTimerDelay(time), args, true);
 }
 
 export function setImmediate(callback, ...args) {
     validateCallback(callback);
-    return scheduleTimeout(callback, 0, args, false);
+    if (isMocked('setImmediate')) {
+        return scheduleMockTimeout(callback, 0, args, false);
+    }
+    return scheduleTimeout('Immediate', callback, 0, args, false);
+}
+
+// Schedules a timer of the runtime itself, which cannot be mocked and is not an async resource
+export function _setInternalTimeout(callback, time) {
+    return scheduleTimeout(null, callback, time, [], false);
 }
 
 export function clearTimeout(id) {
     if (id == null) return;
-    if (id instanceof Timeout) {
+    if (id instanceof Timeout || id instanceof MockTimeout) {
         id.close();
         return;
     }
     if (typeof id === 'number' || typeof id === 'string') {
         const numId = +id;
+        if (mockClock !== null && mockClock.timers.has(numId)) {
+            mockClock.timers.get(numId).close();
+            return;
+        }
         if (numId >= 0 && Number.isFinite(numId)) {
+            for (const timeout of activeTimers) {
+                if (timeout._id === numId) {
+                    timeout.close();
+                    return;
+                }
+            }
             timeoutNative.clear_schedule(numId);
         }
     }
@@ -176,3 +367,22 @@ export const clearImmediate = clearTimeout;
 export function getRefTimerCount() {
     return timeoutNative.ref_timer_count();
 }
+
+export function _hasRefedTimers() {
+    for (const timeout of activeTimers) {
+        if (timeout.hasRef()) {
+            return true;
+        }
+    }
+    return false;
+}
+
+export function _clearAllTimers() {
+    for (const timeout of [...activeTimers]) {
+        timeout.close();
+    }
+}
+
+export function _setTimerActivityHook(hook) {
+    timerActivityHook = hook;
+}
@@ -41,6 +41,13 @@ pub mod native_module {
         key
     }
 
+    /// Takes an id from the counter of [`schedule`] without scheduling anything, for the timers
+    /// of node:test's mock clock, so that they never share an id with a real timer
+    #[rquickjs::function]
+    pub fn reserve_id() -> usize {
+        get_js_state().last_abort_id.fetch_add(1, Ordering::Relaxed)
+    }
+
     #[rquickjs::function]
     pub fn clear_schedule(timeout_id: usize) {
         let state = get_js_state();
@@ -55,12 +62,14 @@ pub mod native_module {
     pub fn unref_schedule(timeout_id: usize) {
         let state = get_js_state();
         state.unrefed_timers.borrow_mut().insert(timeout_id);
+        crate::builtin::deterministic::wake_next_due_timer();
     }
 
     #[rquickjs::function]
     pub fn ref_schedule(timeout_id: usize) {
         let state = get_js_state();
         state.unrefed_timers.borrow_mut().remove(&timeout_id);
+        crate::builtin::deterministic::wake_next_due_timer();
     }
 
     #[rquickjs::function]
@@ -95,10 +104,8 @@ async fn scheduled_task(
     args: Persistent<Vec<Value<'static>>>,
     timer_key: usize,
 ) {
-    let duration = wstd::time::Duration::from_millis(delay as u64);
-
     loop {
-        wstd::task::sleep(duration).await;
+        crate::builtin::deterministic::sleep(timer_key, delay as u64).await;
 
         run_scheduled_task(ctx.clone(), code_or_fn.clone(), args.clone())
             .catch(&ctx)
@@ -1,6 +1,7 @@
 import { URL } from '__wasm_rquickjs_builtin/url_native';
 import * as querystring from 'node:querystring';
 import { ERR_INVALID_ARG_TYPE, ERR_MISSING_ARGS } from '__wasm_rquickjs_builtin/internal/errors';
+import { URLPattern } from '__wasm_rquickjs_builtin/url_pattern';
 
 const __URLSearchParams__ = "__URLSearchParams__";
 const __URLSearchParamsURL__ = "__URLSearchParamsURL__";
@@ -665,7 +666,7 @@ Object.defineProperty(URL.prototype, Symbol.toStringTag, {
 
 // --- node:url module APIs ---
 
-export { URL };
+export { URL, URLPattern };
 
 function makeNodeError(code, Type, message) {
     const err = new Type(message);
@@ -1512,6 +1513,7 @@ export function domainToUnicode(domain) {
 
 export default {
     URL,
+    URLPattern,
     URLSearchParams,
     fileURLToPath,
     pathToFileURL,
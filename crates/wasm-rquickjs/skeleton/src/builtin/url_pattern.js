@@ -0,0 +1,187 @@
+import {
+    url_pattern_parse, url_pattern_process_match_input,
+} from '__wasm_rquickjs_builtin/url_pattern_native';
+
+// URLPattern (https://urlpattern.spec.whatwg.org/). The native side parses patterns and
+// canonicalizes URLs; the regular expressions it generates are compiled and run here.
+
+const COMPONENTS = [
+    'protocol', 'username', 'password', 'hostname', 'port', 'pathname', 'search', 'hash',
+];
+
+// URLPatternInit members in the order WebIDL reads them (lexicographic)
+const INIT_MEMBERS = [
+    'baseURL', 'hash', 'hostname', 'password', 'pathname', 'port', 'protocol', 'search', 'username',
+];
+
+const customInspectSymbol = Symbol.for('nodejs.util.inspect.custom');
+
+// Converts a (USVString or URLPatternInit) argument
+function toURLPatternInput(input) {
+    if (input === null || (typeof input !== 'object' && typeof input !== 'function')) {
+        return String(input);
+    }
+    const init = {};
+    for (const member of INIT_MEMBERS) {
+        const value = input[member];
+        if (value !== undefined) {
+            init[member] = String(value);
+        }
+    }
+    return init;
+}
+
+function toURLPatternOptions(options) {
+    if (options === undefined || options === null) {
+        return { ignoreCase: false };
+    }
+    if (typeof options !== 'object' && typeof options !== 'function') {
+        throw new TypeError('URLPattern options must be an object');
+    }
+    return { ignoreCase: Boolean(options.ignoreCase) };
+}
+
+function requirePattern(value) {
+    if (!value || !(value instanceof URLPattern)) {
+        const err = new TypeError('Value of "this" must be of type URLPattern');
+        err.code = 'ERR_INVALID_THIS';
+        throw err;
+    }
+    return value;
+}
+
+export class URLPattern {
+    #components;
+    #hasRegExpGroups;
+
+    constructor(input = {}, baseURLOrOptions = undefined, options = undefined) {
+        let baseURL;
+        if (typeof baseURLOrOptions === 'string' || options !== undefined) {
+            baseURL = String(baseURLOrOptions);
+        } else {
+            options = baseURLOrOptions;
+        }
+        const convertedInput = toURLPatternInput(input);
+        const { ignoreCase } = toURLPatternOptions(options);
+        const parsed = url_pattern_parse(convertedInput, baseURL, ignoreCase);
+        const flags = ignoreCase ? 'vi' : 'v';
+        this.#components = {};
+        for (const name of COMPONENTS) {
+            const component = parsed[name];
+            let regexp;
+            try {
+                regexp = new RegExp(component.regexpString, flags);
+            } catch (e) {
+                throw new TypeError(`Invalid URLPattern: ${name} has an invalid regular expression: ${e.message}`);
+            }
+            this.#components[name] = {
+                patternString: component.patternString,
+                regexp,
+                groupNameList: component.groupNameList,
+            };
+        }
+        this.#hasRegExpGroups = parsed.hasRegExpGroups;
+    }
+
+    get protocol() {
+        return this.#components.protocol.patternString;
+    }
+
+    get username() {
+        return this.#components.username.patternString;
+    }
+
+    get password() {
+        return this.#components.password.patternString;
+    }
+
+    get hostname() {
+        return this.#components.hostname.patternString;
+    }
+
+    get port() {
+        return this.#components.port.patternString;
+    }
+
+    get pathname() {
+        return this.#components.pathname.patternString;
+    }
+
+    get search() {
+        return this.#components.search.patternString;
+    }
+
+    get hash() {
+        return this.#components.hash.patternString;
+    }
+
+    get hasRegExpGroups() {
+        return this.#hasRegExpGroups;
+    }
+
+    test(input = {}, baseURL = undefined) {
+        requirePattern(this);
+        const values = this.#processMatchInput(input, baseURL);
+        if (values === undefined) {
+            return false;
+        }
+        for (const name of COMPONENTS) {
+            this.#components[name].regexp.lastIndex = 0;
+            if (!this.#components[name].regexp.test(values[name])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    exec(input = {}, baseURL = undefined) {
+        requirePattern(this);
+        const convertedInput = toURLPatternInput(input);
+        const values = this.#processMatchInput(convertedInput, baseURL);
+        if (values === undefined) {
+            return null;
+        }
+        const result = {
+            inputs: baseURL === undefined ? [convertedInput] : [convertedInput, String(baseURL)],
+        };
+        for (const name of COMPONENTS) {
+            const { regexp, groupNameList } = this.#components[name];
+            regexp.lastIndex = 0;
+            const match = regexp.exec(values[name]);
+            if (match === null) {
+                return null;
+            }
+            const groups = {};
+            groupNameList.forEach((groupName, i) => {
+                groups[groupName] = match[i + 1];
+            });
+            result[name] = { input: values[name], groups };
+        }
+        return result;
+    }
+
+    #processMatchInput(input, baseURL) {
+        const convertedInput = toURLPatternInput(input);
+        const convertedBaseURL = baseURL === undefined ? undefined : String(baseURL);
+        return url_pattern_process_match_input(convertedInput, convertedBaseURL) ?? undefined;
+    }
+
+    [customInspectSymbol](depth, opts, inspect) {
+        const fields = {};
+        for (const name of COMPONENTS) {
+            fields[name] = this.#components[name].patternString;
+        }
+        fields.hasRegExpGroups = this.#hasRegExpGroups;
+        if (depth < 0) return '[URLPattern]';
+        return `URLPattern ${inspect ? inspect(fields, opts) : JSON.stringify(fields)}`;
+    }
+}
+
+Object.defineProperty(URLPattern.prototype, Symbol.toStringTag, {
+    value: 'URLPattern',
+    writable: false,
+    enumerable: false,
+    configurable: true,
+});
+
+export default URLPattern;
@@ -0,0 +1,187 @@
+use rquickjs::{Ctx, Exception, Object, Value};
+use std::borrow::Cow;
+use urlpattern::quirks::{self, StringOrInit, UrlPatternComponent, UrlPatternInit};
+use urlpattern::regexp::RegExp;
+use urlpattern::{RegexSyntax, UrlPatternOptions};
+
+// Native functions for the URLPattern implementation. Parsing and canonicalization are done by
+// the `urlpattern` crate on top of `url`, while the generated regular expressions are compiled
+// and executed on the JS side, so custom regexp groups get ECMAScript semantics.
+#[rquickjs::module]
+pub mod native_module {
+    use rquickjs::{Ctx, Object, Value};
+
+    // Parses a constructor string or an init dictionary (whose members are already strings or
+    // undefined) into an object with a { patternString, regexpString, groupNameList } entry per
+    // component and a hasRegExpGroups flag. Throws a TypeError for invalid patterns.
+    #[rquickjs::function]
+    pub fn url_pattern_parse<'js>(
+        ctx: Ctx<'js>,
+        input: Value<'js>,
+        base_url: Option<String>,
+        ignore_case: bool,
+    ) -> rquickjs::Result<Object<'js>> {
+        super::parse(&ctx, input, base_url, ignore_case)
+    }
+
+    // Canonicalizes the input of test() and exec() into an object with the eight component
+    // strings, or returns undefined if the input is not a valid URL. Throws a TypeError if a
+    // base URL is given together with an init dictionary.
+    #[rquickjs::function]
+    pub fn url_pattern_process_match_input<'js>(
+        ctx: Ctx<'js>,
+        input: Value<'js>,
+        base_url: Option<String>,
+    ) -> rquickjs::Result<Option<Object<'js>>> {
+        super::process_match_input(&ctx, input, base_url)
+    }
+}
+
+/// Stands in for the JS `RegExp` the patterns are compiled to: it carries the generated source,
+/// which `url_pattern.js` validates and matches with. Parsing only runs it to check whether the
+/// protocol component matches a special scheme, which the `regex` crate handles for the
+/// alternations used there.
+struct JsRegExp(String);
+
+impl RegExp for JsRegExp {
+    fn syntax() -> RegexSyntax {
+        RegexSyntax::EcmaScript
+    }
+
+    fn parse(pattern: &str, _flags: &str, _force_eval: bool) -> Result<Self, ()> {
+        Ok(JsRegExp(pattern.to_string()))
+    }
+
+    fn matches<'a>(&self, text: &'a str) -> Option<Vec<Option<&'a str>>> {
+        let regex = regex::Regex::new(&self.0).ok()?;
+        let captures = regex.captures(text)?;
+        Some(
+            captures
+                .iter()
+                .skip(1)
+                .map(|c| c.map(|m| m.as_str()))
+                .collect(),
+        )
+    }
+
+    fn pattern_string(&self) -> &str {
+        &self.0
+    }
+}
+
+const COMPONENTS: [&str; 8] = [
+    "protocol", "username", "password", "hostname", "port", "pathname", "search", "hash",
+];
+
+fn parse<'js>(
+    ctx: &Ctx<'js>,
+    input: Value<'js>,
+    base_url: Option<String>,
+    ignore_case: bool,
+) -> rquickjs::Result<Object<'js>> {
+    let input = to_string_or_init(input)?;
+    let options = UrlPatternOptions {
+        regex_syntax: RegexSyntax::EcmaScript,
+        ignore_case,
+    };
+    let pattern = quirks::process_construct_pattern_input(input, base_url.as_deref())
+        .and_then(|init| quirks::parse_pattern::<JsRegExp>(init, options))
+        .map_err(|err| type_error(ctx, &err))?;
+
+    let result = Object::new(ctx.clone())?;
+    let components = [
+        &pattern.protocol,
+        &pattern.username,
+        &pattern.password,
+        &pattern.hostname,
+        &pattern.port,
+        &pattern.pathname,
+        &pattern.search,
+        &pattern.hash,
+    ];
+    for (name, component) in COMPONENTS.iter().zip(components) {
+        result.set(*name, component_to_object(ctx, component)?)?;
+    }
+    result.set("hasRegExpGroups", pattern.has_regexp_groups)?;
+    Ok(result)
+}
+
+fn process_match_input<'js>(
+    ctx: &Ctx<'js>,
+    input: Value<'js>,
+    base_url: Option<String>,
+) -> rquickjs::Result<Option<Object<'js>>> {
+    let input = to_string_or_init(input)?;
+    let Some((match_input, _)) = quirks::process_match_input(input, base_url.as_deref())
+        .map_err(|err| type_error(ctx, &err))?
+    else {
+        return Ok(None);
+    };
+    let Some(values) = quirks::parse_match_input(match_input) else {
+        return Ok(None);
+    };
+
+    let result = Object::new(ctx.clone())?;
+    let values = [
+        values.protocol,
+        values.username,
+        values.password,
+        values.hostname,
+        values.port,
+        values.pathname,
+        values.search,
+        values.hash,
+    ];
+    for (name, value) in COMPONENTS.iter().zip(values) {
+        result.set(*name, value)?;
+    }
+    Ok(Some(result))
+}
+
+fn to_string_or_init(input: Value<'_>) -> rquickjs::Result<StringOrInit<'static>> {
+    if let Some(string) = input.as_string() {
+        return Ok(StringOrInit::String(Cow::Owned(string.to_string()?)));
+    }
+    let mut init = UrlPatternInit::default();
+    if let Some(object) = input.as_object() {
+        init.protocol = object.get("protocol")?;
+        init.username = object.get("username")?;
+        init.password = object.get("password")?;
+        init.hostname = object.get("hostname")?;
+        init.port = object.get("port")?;
+        init.pathname = object.get("pathname")?;
+        init.search = object.get("search")?;
+        init.hash = object.get("hash")?;
+        init.base_url = object.get("baseURL")?;
+    }
+    Ok(StringOrInit::Init(init))
+}
+
+fn component_to_object<'js>(
+    ctx: &Ctx<'js>,
+    component: &UrlPatternComponent,
+) -> rquickjs::Result<Object<'js>> {
+    let object = Object::new(ctx.clone())?;
+    object.set("patternString", component.pattern_string.as_str())?;
+    object.set("regexpString", component.regexp_string.as_str())?;
+    object.set("groupNameList", component.group_name_list.clone())?;
+    Ok(object)
+}
+
+fn type_error(ctx: &Ctx<'_>, err: &urlpattern::Error) -> rquickjs::Error {
+    Exception::throw_type(ctx, &format!("Invalid URLPattern: {err}"))
+}
+
+// JS implementation of the URLPattern class
+pub const URL_PATTERN_JS: &str = include_str!("url_pattern.js");
+
+// JS code wiring URLPattern into the global context
+pub const WIRE_JS: &str = r#"
+        import { URLPattern as __wasm_rquickjs_URLPattern } from '__wasm_rquickjs_builtin/url_pattern';
+        Object.defineProperty(globalThis, 'URLPattern', {
+            value: __wasm_rquickjs_URLPattern,
+            writable: true,
+            enumerable: false,
+            configurable: true,
+        });
+    "#;
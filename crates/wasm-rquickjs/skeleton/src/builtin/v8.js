@@ -1,18 +1,42 @@
-// Minimal v8 module stub for Node.js compatibility
-// Most v8 internals are not available in QuickJS/WASM
+// v8 module for Node.js compatibility
+// Heap statistics and snapshots are backed by the memory usage counters and the object graph of
+// the QuickJS runtime; the other V8 internals are not available in QuickJS/WASM
+import { memory_usage, take_heap_snapshot, V8Reader, V8Writer } from '__wasm_rquickjs_builtin/v8_native';
+import {
+    stopCoverage as stopCoverageReports,
+    takeCoverage as takeCoverageReports,
+} from '__wasm_rquickjs_builtin/coverage';
+import { Buffer } from 'node:buffer';
+import { writeFileSync } from 'node:fs';
+import { Readable } from 'node:stream';
+
+// Without a memory limit set on the runtime, the heap is bounded by the 32-bit address space
+const ADDRESS_SPACE_SIZE = 2 ** 32;
+
+// The highest malloced memory seen by getHeapStatistics so far
+let peakMallocedMemory = 0;
+
+function heapSizeLimit(usage) {
+    return usage.malloc_limit > 0 && usage.malloc_limit < ADDRESS_SPACE_SIZE
+        ? usage.malloc_limit
+        : ADDRESS_SPACE_SIZE;
+}
 
 export function getHeapStatistics() {
+    const usage = memory_usage();
+    const limit = heapSizeLimit(usage);
+    peakMallocedMemory = Math.max(peakMallocedMemory, usage.malloc_size);
     return {
-        total_heap_size: 0,
+        total_heap_size: usage.malloc_size,
         total_heap_size_executable: 0,
-        total_physical_size: 0,
-        total_available_size: 0,
-        used_heap_size: 0,
-        heap_size_limit: 0,
-        malloced_memory: 0,
-        peak_malloced_memory: 0,
+        total_physical_size: usage.linear_memory_size || usage.malloc_size,
+        total_available_size: Math.max(0, limit - usage.malloc_size),
+        used_heap_size: usage.memory_used_size,
+        heap_size_limit: limit,
+        malloced_memory: usage.malloc_size,
+        peak_malloced_memory: peakMallocedMemory,
         does_zap_garbage: 0,
-        number_of_native_contexts: 0,
+        number_of_native_contexts: 1,
         number_of_detached_contexts: 0,
         total_global_handles_size: 0,
         used_global_handles_size: 0,
@@ -20,18 +44,39 @@ export function getHeapStatistics() {
     };
 }
 
+// QuickJS does not have heap spaces like V8, so its memory usage categories are reported instead
 export function getHeapSpaceStatistics() {
-    return [];
+    const usage = memory_usage();
+    const spaces = [
+        ['atom_space', usage.atom_size],
+        ['string_space', usage.str_size],
+        ['object_space', usage.obj_size + usage.prop_size],
+        ['shape_space', usage.shape_size],
+        ['code_space', usage.js_func_size + usage.js_func_code_size + usage.js_func_pc2line_size],
+        ['array_space', usage.fast_array_elements * 8],
+    ];
+    // Array buffer contents, closure variables and the internal state of other objects
+    const categorized = spaces.reduce((sum, [, size]) => sum + size, 0);
+    spaces.push(['other_space', Math.max(0, usage.memory_used_size - categorized)]);
+    return spaces.map(([space_name, size]) => ({
+        space_name,
+        space_size: size,
+        space_used_size: size,
+        space_available_size: 0,
+        physical_space_size: size,
+    }));
 }
 
-export function getHeapSnapshot() {
-    throw new Error('v8.getHeapSnapshot is not supported in WASM environment');
+export function getHeapSnapshot(options) {
+    return Readable.from([Buffer.from(take_heap_snapshot())], { objectMode: false });
 }
 
 export function getHeapCodeStatistics() {
+    const usage = memory_usage();
+    const bytecodeSize = usage.js_func_code_size + usage.js_func_pc2line_size;
     return {
-        code_and_metadata_size: 0,
-        bytecode_and_metadata_size: 0,
+        code_and_metadata_size: usage.js_func_size + bytecodeSize,
+        bytecode_and_metadata_size: bytecodeSize,
         external_script_source_size: 0,
         cpu_profiler_metadata_size: 0,
     };
@@ -44,40 +89,960 @@ export function setFlagsFromString(flags) {
     // No-op: V8 flags cannot be set in QuickJS/WASM
 }
 
-export function writeHeapSnapshot() {
-    throw new Error('v8.writeHeapSnapshot is not supported in WASM environment');
+let heapSnapshotSequence = 0;
+
+function defaultHeapSnapshotFileName() {
+    const now = new Date();
+    const pad = (value, length = 2) => String(value).padStart(length, '0');
+    const date = `${now.getFullYear()}${pad(now.getMonth() + 1)}${pad(now.getDate())}`;
+    const time = `${pad(now.getHours())}${pad(now.getMinutes())}${pad(now.getSeconds())}`;
+    heapSnapshotSequence++;
+    return `Heap.${date}.${time}.${globalThis.process?.pid ?? 0}.0.${pad(heapSnapshotSequence, 3)}.heapsnapshot`;
+}
+
+export function writeHeapSnapshot(filename, options) {
+    if (filename !== undefined && typeof filename !== 'string') {
+        const err = new TypeError(`The "path" argument must be of type string. Received ${typeof filename}`);
+        err.code = 'ERR_INVALID_ARG_TYPE';
+        throw err;
+    }
+    const path = filename ?? defaultHeapSnapshotFileName();
+    writeFileSync(path, take_heap_snapshot());
+    return path;
 }
 
+// Coverage is only collected for modules instrumented by `generate-wrapper-crate --coverage`.
+// `takeCoverage` writes the Istanbul JSON and LCOV reports instead of V8 coverage.
 export function takeCoverage() {
-    // No-op
+    takeCoverageReports();
 }
 
 export function stopCoverage() {
-    // No-op
+    stopCoverageReports();
 }
 
-export function serialize(value) {
-    throw new Error('v8.serialize is not supported in WASM environment');
+// v8.Serializer / v8.Deserializer implement the V8 ValueSerializer wire format (version 15),
+// compatible with what Node writes and reads. The object graph is walked here; the byte-level
+// encoding is done by the native V8Writer / V8Reader.
+
+const TAG = {
+    VERIFY_OBJECT_COUNT: 0x3F, // '?'
+    THE_HOLE: 0x2D, // '-'
+    UNDEFINED: 0x5F, // '_'
+    NULL: 0x30, // '0'
+    TRUE: 0x54, // 'T'
+    FALSE: 0x46, // 'F'
+    INT32: 0x49, // 'I'
+    UINT32: 0x55, // 'U'
+    DOUBLE: 0x4E, // 'N'
+    BIGINT: 0x5A, // 'Z'
+    UTF8_STRING: 0x53, // 'S'
+    ONE_BYTE_STRING: 0x22, // '"'
+    TWO_BYTE_STRING: 0x63, // 'c'
+    OBJECT_REFERENCE: 0x5E, // '^'
+    BEGIN_JS_OBJECT: 0x6F, // 'o'
+    END_JS_OBJECT: 0x7B, // '{'
+    BEGIN_SPARSE_JS_ARRAY: 0x61, // 'a'
+    END_SPARSE_JS_ARRAY: 0x40, // '@'
+    BEGIN_DENSE_JS_ARRAY: 0x41, // 'A'
+    END_DENSE_JS_ARRAY: 0x24, // '$'
+    DATE: 0x44, // 'D'
+    TRUE_OBJECT: 0x79, // 'y'
+    FALSE_OBJECT: 0x78, // 'x'
+    NUMBER_OBJECT: 0x6E, // 'n'
+    BIGINT_OBJECT: 0x7A, // 'z'
+    STRING_OBJECT: 0x73, // 's'
+    REGEXP: 0x52, // 'R'
+    BEGIN_JS_MAP: 0x3B, // ';'
+    END_JS_MAP: 0x3A, // ':'
+    BEGIN_JS_SET: 0x27, // '\''
+    END_JS_SET: 0x2C, // ','
+    ARRAY_BUFFER: 0x42, // 'B'
+    RESIZABLE_ARRAY_BUFFER: 0x7E, // '~'
+    ARRAY_BUFFER_TRANSFER: 0x74, // 't'
+    ARRAY_BUFFER_VIEW: 0x56, // 'V'
+    SHARED_ARRAY_BUFFER: 0x75, // 'u'
+    HOST_OBJECT: 0x5C, // '\\'
+    ERROR: 0x72, // 'r'
+};
+
+// Sub-tags of serialized errors
+const ERROR_TAG = {
+    EVAL_ERROR_PROTOTYPE: 0x45, // 'E'
+    RANGE_ERROR_PROTOTYPE: 0x52, // 'R'
+    REFERENCE_ERROR_PROTOTYPE: 0x46, // 'F'
+    SYNTAX_ERROR_PROTOTYPE: 0x53, // 'S'
+    TYPE_ERROR_PROTOTYPE: 0x54, // 'T'
+    URI_ERROR_PROTOTYPE: 0x55, // 'U'
+    MESSAGE: 0x6D, // 'm'
+    CAUSE: 0x63, // 'c'
+    STACK: 0x73, // 's'
+    END: 0x2E, // '.'
+};
+
+const ERROR_PROTOTYPE_TAGS = new Map([
+    ['EvalError', ERROR_TAG.EVAL_ERROR_PROTOTYPE],
+    ['RangeError', ERROR_TAG.RANGE_ERROR_PROTOTYPE],
+    ['ReferenceError', ERROR_TAG.REFERENCE_ERROR_PROTOTYPE],
+    ['SyntaxError', ERROR_TAG.SYNTAX_ERROR_PROTOTYPE],
+    ['TypeError', ERROR_TAG.TYPE_ERROR_PROTOTYPE],
+    ['URIError', ERROR_TAG.URI_ERROR_PROTOTYPE],
+]);
+
+const ERROR_CONSTRUCTORS = new Map([
+    [ERROR_TAG.EVAL_ERROR_PROTOTYPE, EvalError],
+    [ERROR_TAG.RANGE_ERROR_PROTOTYPE, RangeError],
+    [ERROR_TAG.REFERENCE_ERROR_PROTOTYPE, ReferenceError],
+    [ERROR_TAG.SYNTAX_ERROR_PROTOTYPE, SyntaxError],
+    [ERROR_TAG.TYPE_ERROR_PROTOTYPE, TypeError],
+    [ERROR_TAG.URI_ERROR_PROTOTYPE, URIError],
+]);
+
+// Sub-tags of array buffer views (kArrayBufferView)
+const VIEW_TAGS = [
+    [Int8Array, 0x62], // 'b'
+    [Uint8Array, 0x42], // 'B'
+    [Uint8ClampedArray, 0x43], // 'C'
+    [Int16Array, 0x77], // 'w'
+    [Uint16Array, 0x57], // 'W'
+    [Int32Array, 0x64], // 'd'
+    [Uint32Array, 0x44], // 'D'
+    [Float32Array, 0x66], // 'f'
+    [Float64Array, 0x46], // 'F'
+    [BigInt64Array, 0x71], // 'q'
+    [BigUint64Array, 0x51], // 'Q'
+    [DataView, 0x3F], // '?'
+];
+
+// RegExp flags in the order of their bits in the wire format
+const REGEXP_FLAGS = ['g', 'i', 'm', 'y', 'u', 's', 'l', 'd', 'v'];
+
+const TypedArrayPrototype = Object.getPrototypeOf(Uint8Array.prototype);
+const typedArrayTag = Object.getOwnPropertyDescriptor(TypedArrayPrototype, Symbol.toStringTag).get;
+
+function isTypedArray(value) {
+    return typedArrayTag.call(value) !== undefined;
 }
 
-export function deserialize(buffer) {
-    throw new Error('v8.deserialize is not supported in WASM environment');
+// Brand checks that do not depend on the prototype chain or Symbol.toStringTag
+function hasBrand(value, method) {
+    try {
+        method.call(value);
+        return true;
+    } catch (_) {
+        return false;
+    }
+}
+
+const mapSize = Object.getOwnPropertyDescriptor(Map.prototype, 'size').get;
+const setSize = Object.getOwnPropertyDescriptor(Set.prototype, 'size').get;
+const arrayBufferByteLength = Object.getOwnPropertyDescriptor(ArrayBuffer.prototype, 'byteLength').get;
+const sharedArrayBufferByteLength = typeof SharedArrayBuffer === 'function'
+    ? Object.getOwnPropertyDescriptor(SharedArrayBuffer.prototype, 'byteLength').get
+    : undefined;
+const regExpSource = Object.getOwnPropertyDescriptor(RegExp.prototype, 'source').get;
+const regExpFlags = Object.getOwnPropertyDescriptor(RegExp.prototype, 'flags').get;
+
+function isArrayIndex(key) {
+    if (key === '0') return true;
+    if (!/^[1-9][0-9]*$/.test(key)) return false;
+    return Number(key) < 4294967295;
+}
+
+function isWellFormed(string) {
+    if (typeof string.isWellFormed === 'function') {
+        return string.isWellFormed();
+    }
+    return !/[\uD800-\uDBFF](?![\uDC00-\uDFFF])|(?:^|[^\uD800-\uDBFF])[\uDC00-\uDFFF]/.test(string);
+}
+
+function describe(value) {
+    if (typeof value === 'function') {
+        return String(value);
+    }
+    if (typeof value === 'symbol') {
+        return value.toString();
+    }
+    let name = 'Object';
+    try {
+        const ctor = value.constructor;
+        if (typeof ctor === 'function' && ctor.name) name = ctor.name;
+    } catch (_) {
+        // ignore
+    }
+    return `#<${name}>`;
+}
+
+function invalidArgType(name, expected) {
+    const err = new TypeError(`${name} must be ${expected}`);
+    err.code = 'ERR_INVALID_ARG_TYPE';
+    return err;
 }
 
 class Serializer {
-    constructor() {
-        throw new Error('v8.Serializer is not supported in WASM environment');
+    #writer = new V8Writer();
+    #ids = new Map();
+    #nextId = 0;
+    #treatArrayBufferViewsAsHostObjects = false;
+    #transferredArrayBuffers = new Map();
+
+    writeHeader() {
+        this.#writer.write_header();
+    }
+
+    writeValue(value) {
+        this.#writeObject(value);
+        return true;
+    }
+
+    releaseBuffer() {
+        const bytes = this.#writer.release_buffer();
+        return Buffer.from(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+    }
+
+    transferArrayBuffer(id, arrayBuffer) {
+        if (!(arrayBuffer instanceof ArrayBuffer) &&
+            !(typeof SharedArrayBuffer === 'function' && arrayBuffer instanceof SharedArrayBuffer)) {
+            throw invalidArgType('arrayBuffer', 'an ArrayBuffer or SharedArrayBuffer');
+        }
+        this.#transferredArrayBuffers.set(arrayBuffer, id >>> 0);
+    }
+
+    writeUint32(value) {
+        this.#writer.write_varint(value >>> 0);
+    }
+
+    writeUint64(hi, lo) {
+        this.#writer.write_varint64(hi >>> 0, lo >>> 0);
+    }
+
+    writeDouble(value) {
+        this.#writer.write_double(Number(value));
+    }
+
+    writeRawBytes(source) {
+        if (!ArrayBuffer.isView(source)) {
+            throw invalidArgType('source', 'a TypedArray or a DataView');
+        }
+        this.#writer.write_raw_bytes(new Uint8Array(source.buffer, source.byteOffset, source.byteLength));
+    }
+
+    _setTreatArrayBufferViewsAsHostObjects(value) {
+        this.#treatArrayBufferViewsAsHostObjects = Boolean(value);
+    }
+
+    #throwDataCloneError(message) {
+        throw this._getDataCloneError(message);
+    }
+
+    #writeObject(value) {
+        switch (typeof value) {
+            case 'undefined':
+                this.#writer.write_tag(TAG.UNDEFINED);
+                return;
+            case 'boolean':
+                this.#writer.write_tag(value ? TAG.TRUE : TAG.FALSE);
+                return;
+            case 'number':
+                this.#writeNumber(value);
+                return;
+            case 'bigint':
+                this.#writer.write_tag(TAG.BIGINT);
+                this.#writeBigInt(value);
+                return;
+            case 'string':
+                this.#writeString(value);
+                return;
+            case 'symbol':
+            case 'function':
+                this.#throwDataCloneError(`${describe(value)} could not be cloned.`);
+                return;
+            default:
+                break;
+        }
+        if (value === null) {
+            this.#writer.write_tag(TAG.NULL);
+            return;
+        }
+        // Array buffer views have their buffer serialized first, before they get an ID
+        if (ArrayBuffer.isView(value) && !this.#ids.has(value) &&
+            !this.#treatArrayBufferViewsAsHostObjects) {
+            this.#writeReceiver(value.buffer);
+        }
+        this.#writeReceiver(value);
+    }
+
+    #writeNumber(value) {
+        if (Number.isInteger(value) && value >= -0x80000000 && value <= 0x7FFFFFFF && !Object.is(value, -0)) {
+            this.#writer.write_tag(TAG.INT32);
+            this.#writer.write_zigzag(value);
+        } else {
+            this.#writer.write_tag(TAG.DOUBLE);
+            this.#writer.write_double(value);
+        }
+    }
+
+    #writeBigInt(value) {
+        const negative = value < 0n;
+        this.#writer.write_bigint(negative, (negative ? -value : value).toString(16));
+    }
+
+    #writeString(value) {
+        if (isWellFormed(value)) {
+            this.#writer.write_string(value);
+        } else {
+            const units = new Uint16Array(value.length);
+            for (let i = 0; i < value.length; i++) {
+                units[i] = value.charCodeAt(i);
+            }
+            this.#writer.write_two_byte_string(units);
+        }
+    }
+
+    #writeReceiver(value) {
+        const existing = this.#ids.get(value);
+        if (existing !== undefined) {
+            this.#writer.write_tag(TAG.OBJECT_REFERENCE);
+            this.#writer.write_varint(existing);
+            return;
+        }
+        this.#ids.set(value, this.#nextId++);
+
+        if (Array.isArray(value)) {
+            this.#writeArray(value);
+        } else if (ArrayBuffer.isView(value)) {
+            if (this.#treatArrayBufferViewsAsHostObjects) {
+                this.#writeHostObject(value);
+            } else {
+                this.#writeArrayBufferView(value);
+            }
+        } else if (hasBrand(value, arrayBufferByteLength)) {
+            this.#writeArrayBuffer(value);
+        } else if (sharedArrayBufferByteLength && hasBrand(value, sharedArrayBufferByteLength)) {
+            this.#writeSharedArrayBuffer(value);
+        } else if (hasBrand(value, Date.prototype.getTime)) {
+            this.#writer.write_tag(TAG.DATE);
+            this.#writer.write_double(Date.prototype.getTime.call(value));
+        } else if (hasBrand(value, regExpSource)) {
+            this.#writeRegExp(value);
+        } else if (hasBrand(value, mapSize)) {
+            this.#writeMap(value);
+        } else if (hasBrand(value, setSize)) {
+            this.#writeSet(value);
+        } else if (hasBrand(value, Number.prototype.valueOf)) {
+            this.#writer.write_tag(TAG.NUMBER_OBJECT);
+            this.#writer.write_double(Number.prototype.valueOf.call(value));
+        } else if (hasBrand(value, Boolean.prototype.valueOf)) {
+            this.#writer.write_tag(Boolean.prototype.valueOf.call(value) ? TAG.TRUE_OBJECT : TAG.FALSE_OBJECT);
+        } else if (hasBrand(value, String.prototype.valueOf)) {
+            this.#writer.write_tag(TAG.STRING_OBJECT);
+            this.#writeString(String.prototype.valueOf.call(value));
+        } else if (hasBrand(value, BigInt.prototype.valueOf)) {
+            this.#writer.write_tag(TAG.BIGINT_OBJECT);
+            this.#writeBigInt(BigInt.prototype.valueOf.call(value));
+        } else if (value instanceof Error) {
+            this.#writeError(value);
+        } else if (value instanceof Promise || value instanceof WeakMap || value instanceof WeakSet ||
+            (typeof WeakRef === 'function' && value instanceof WeakRef)) {
+            this.#throwDataCloneError(`${describe(value)} could not be cloned.`);
+        } else {
+            this.#writer.write_tag(TAG.BEGIN_JS_OBJECT);
+            const count = this.#writeProperties(value, Object.keys(value));
+            this.#writer.write_tag(TAG.END_JS_OBJECT);
+            this.#writer.write_varint(count);
+        }
+    }
+
+    #writeProperties(object, keys) {
+        let count = 0;
+        for (const key of keys) {
+            // Skip properties removed by getters of earlier properties
+            if (!Object.prototype.hasOwnProperty.call(object, key)) continue;
+            if (isArrayIndex(key)) {
+                this.#writeNumber(Number(key));
+            } else {
+                this.#writeString(key);
+            }
+            this.#writeObject(object[key]);
+            count++;
+        }
+        return count;
+    }
+
+    #writeArray(array) {
+        const length = array.length;
+        const keys = Object.keys(array);
+        let indexCount = 0;
+        for (const key of keys) {
+            if (isArrayIndex(key)) indexCount++;
+        }
+        if (indexCount === length) {
+            // Packed arrays are written densely, followed by their other properties
+            this.#writer.write_tag(TAG.BEGIN_DENSE_JS_ARRAY);
+            this.#writer.write_varint(length);
+            for (let i = 0; i < length; i++) {
+                this.#writeObject(array[i]);
+            }
+            const count = this.#writeProperties(array, keys.filter(key => !isArrayIndex(key)));
+            this.#writer.write_tag(TAG.END_DENSE_JS_ARRAY);
+            this.#writer.write_varint(count);
+            this.#writer.write_varint(length);
+        } else {
+            this.#writer.write_tag(TAG.BEGIN_SPARSE_JS_ARRAY);
+            this.#writer.write_varint(length);
+            const count = this.#writeProperties(array, keys);
+            this.#writer.write_tag(TAG.END_SPARSE_JS_ARRAY);
+            this.#writer.write_varint(count);
+            this.#writer.write_varint(length);
+        }
+    }
+
+    #writeRegExp(regexp) {
+        this.#writer.write_tag(TAG.REGEXP);
+        this.#writeString(regExpSource.call(regexp));
+        const flags = regExpFlags.call(regexp);
+        let bits = 0;
+        REGEXP_FLAGS.forEach((flag, i) => {
+            if (flags.includes(flag)) bits |= 1 << i;
+        });
+        this.#writer.write_varint(bits);
+    }
+
+    #writeMap(map) {
+        const entries = Array.from(Map.prototype.entries.call(map));
+        this.#writer.write_tag(TAG.BEGIN_JS_MAP);
+        for (const [key, value] of entries) {
+            this.#writeObject(key);
+            this.#writeObject(value);
+        }
+        this.#writer.write_tag(TAG.END_JS_MAP);
+        this.#writer.write_varint(entries.length * 2);
+    }
+
+    #writeSet(set) {
+        const values = Array.from(Set.prototype.values.call(set));
+        this.#writer.write_tag(TAG.BEGIN_JS_SET);
+        for (const value of values) {
+            this.#writeObject(value);
+        }
+        this.#writer.write_tag(TAG.END_JS_SET);
+        this.#writer.write_varint(values.length);
+    }
+
+    #writeArrayBuffer(arrayBuffer) {
+        const transferId = this.#transferredArrayBuffers.get(arrayBuffer);
+        if (transferId !== undefined) {
+            this.#writer.write_tag(TAG.ARRAY_BUFFER_TRANSFER);
+            this.#writer.write_varint(transferId);
+            return;
+        }
+        if (arrayBuffer.detached) {
+            this.#throwDataCloneError('An ArrayBuffer is detached and could not be cloned.');
+        }
+        const bytes = new Uint8Array(arrayBuffer);
+        if (arrayBuffer.resizable) {
+            this.#writer.write_tag(TAG.RESIZABLE_ARRAY_BUFFER);
+            this.#writer.write_varint(bytes.length);
+            this.#writer.write_varint(arrayBuffer.maxByteLength);
+        } else {
+            this.#writer.write_tag(TAG.ARRAY_BUFFER);
+            this.#writer.write_varint(bytes.length);
+        }
+        this.#writer.write_raw_bytes(bytes);
+    }
+
+    #writeSharedArrayBuffer(sharedArrayBuffer) {
+        if (typeof this._getSharedArrayBufferId !== 'function') {
+            this.#throwDataCloneError(`${describe(sharedArrayBuffer)} could not be cloned.`);
+        }
+        const id = this._getSharedArrayBufferId(sharedArrayBuffer);
+        this.#writer.write_tag(TAG.SHARED_ARRAY_BUFFER);
+        this.#writer.write_varint(id >>> 0);
+    }
+
+    #writeArrayBufferView(view) {
+        const entry = VIEW_TAGS.find(([ctor]) => view instanceof ctor);
+        if (!entry) {
+            this.#throwDataCloneError(`${describe(view)} could not be cloned.`);
+        }
+        this.#writer.write_tag(TAG.ARRAY_BUFFER_VIEW);
+        this.#writer.write_varint(entry[1]);
+        this.#writer.write_varint(view.byteOffset);
+        this.#writer.write_varint(view.byteLength);
+        this.#writer.write_varint(0); // flags
+    }
+
+    #writeHostObject(value) {
+        this.#writer.write_tag(TAG.HOST_OBJECT);
+        if (typeof this._writeHostObject !== 'function') {
+            this.#throwDataCloneError(`Unserializable host object: ${describe(value)}`);
+        }
+        this._writeHostObject(value);
+    }
+
+    #writeError(error) {
+        this.#writer.write_tag(TAG.ERROR);
+        const prototypeTag = ERROR_PROTOTYPE_TAGS.get(String(error.name));
+        if (prototypeTag !== undefined) {
+            this.#writer.write_varint(prototypeTag);
+        }
+        const message = Object.getOwnPropertyDescriptor(error, 'message');
+        if (message && 'value' in message) {
+            this.#writer.write_varint(ERROR_TAG.MESSAGE);
+            this.#writeString(String(message.value));
+        }
+        const cause = Object.getOwnPropertyDescriptor(error, 'cause');
+        if (cause && 'value' in cause) {
+            this.#writer.write_varint(ERROR_TAG.CAUSE);
+            this.#writeObject(cause.value);
+        }
+        const stack = error.stack;
+        if (typeof stack === 'string') {
+            this.#writer.write_varint(ERROR_TAG.STACK);
+            this.#writeString(stack);
+        }
+        this.#writer.write_varint(ERROR_TAG.END);
     }
 }
 
+Serializer.prototype._getDataCloneError = Error;
+
+function deserializeError() {
+    return new Error('Unable to deserialize cloned data.');
+}
+
 class Deserializer {
+    #reader;
+    #version = 0;
+    #objects = [];
+    #nextId = 0;
+    #transferredArrayBuffers = new Map();
+
+    constructor(buffer) {
+        if (!ArrayBuffer.isView(buffer)) {
+            throw invalidArgType('buffer', 'a TypedArray or a DataView');
+        }
+        this.buffer = buffer;
+        this.#reader = new V8Reader(new Uint8Array(buffer.buffer, buffer.byteOffset, buffer.byteLength));
+    }
+
+    readHeader() {
+        this.#version = this.#reader.read_header();
+        return true;
+    }
+
+    readValue() {
+        return this.#readObject();
+    }
+
+    getWireFormatVersion() {
+        return this.#version;
+    }
+
+    transferArrayBuffer(id, arrayBuffer) {
+        if (ArrayBuffer.isView(arrayBuffer)) {
+            arrayBuffer = arrayBuffer.buffer;
+        }
+        if (!(arrayBuffer instanceof ArrayBuffer) &&
+            !(typeof SharedArrayBuffer === 'function' && arrayBuffer instanceof SharedArrayBuffer)) {
+            throw invalidArgType('arrayBuffer', 'an ArrayBuffer or SharedArrayBuffer');
+        }
+        this.#transferredArrayBuffers.set(id >>> 0, arrayBuffer);
+    }
+
+    readUint32() {
+        return this.#reader.read_varint();
+    }
+
+    readUint64() {
+        return this.#reader.read_varint64();
+    }
+
+    readDouble() {
+        return this.#reader.read_double();
+    }
+
+    _readRawBytes(length) {
+        return this.#reader.skip_raw_bytes(length >>> 0);
+    }
+
+    readRawBytes(length) {
+        const offset = this._readRawBytes(length);
+        return Buffer.from(this.buffer.buffer, this.buffer.byteOffset + offset, length);
+    }
+
+    #readObject() {
+        let result = this.#readObjectInternal();
+        if (result instanceof ArrayBuffer && this.#reader.peek_tag() === TAG.ARRAY_BUFFER_VIEW) {
+            this.#reader.read_tag();
+            result = this.#readArrayBufferView(result);
+        }
+        return result;
+    }
+
+    #readObjectInternal() {
+        const tag = this.#reader.read_tag();
+        switch (tag) {
+            case TAG.VERIFY_OBJECT_COUNT:
+                this.#reader.read_varint();
+                return this.#readObject();
+            case TAG.UNDEFINED:
+                return undefined;
+            case TAG.NULL:
+                return null;
+            case TAG.TRUE:
+                return true;
+            case TAG.FALSE:
+                return false;
+            case TAG.INT32:
+                return this.#reader.read_zigzag();
+            case TAG.UINT32:
+                return this.#reader.read_varint();
+            case TAG.DOUBLE:
+                return this.#reader.read_double();
+            case TAG.BIGINT:
+                return this.#readBigInt();
+            case TAG.UTF8_STRING:
+                return this.#reader.read_utf8_string();
+            case TAG.ONE_BYTE_STRING:
+                return this.#reader.read_one_byte_string();
+            case TAG.TWO_BYTE_STRING:
+                return this.#readTwoByteString();
+            case TAG.OBJECT_REFERENCE: {
+                const id = this.#reader.read_varint();
+                if (id >= this.#objects.length || this.#objects[id] === undefined) {
+                    throw deserializeError();
+                }
+                return this.#objects[id].value;
+            }
+            case TAG.BEGIN_JS_OBJECT:
+                return this.#readJSObject();
+            case TAG.BEGIN_SPARSE_JS_ARRAY:
+                return this.#readSparseArray();
+            case TAG.BEGIN_DENSE_JS_ARRAY:
+                return this.#readDenseArray();
+            case TAG.DATE:
+                return this.#addObject(new Date(this.#reader.read_double()));
+            case TAG.TRUE_OBJECT:
+                return this.#addObject(new Boolean(true));
+            case TAG.FALSE_OBJECT:
+                return this.#addObject(new Boolean(false));
+            case TAG.NUMBER_OBJECT:
+                return this.#addObject(new Number(this.#reader.read_double()));
+            case TAG.BIGINT_OBJECT:
+                return this.#addObject(Object(this.#readBigInt()));
+            case TAG.STRING_OBJECT:
+                return this.#addObject(new String(this.#readString()));
+            case TAG.REGEXP:
+                return this.#readRegExp();
+            case TAG.BEGIN_JS_MAP:
+                return this.#readMap();
+            case TAG.BEGIN_JS_SET:
+                return this.#readSet();
+            case TAG.ARRAY_BUFFER: {
+                const bytes = this.#reader.read_raw_bytes(this.#reader.read_varint());
+                return this.#addObject(bytes.buffer);
+            }
+            case TAG.RESIZABLE_ARRAY_BUFFER: {
+                const byteLength = this.#reader.read_varint();
+                const maxByteLength = this.#reader.read_varint();
+                if (byteLength > maxByteLength) throw deserializeError();
+                const arrayBuffer = new ArrayBuffer(byteLength, { maxByteLength });
+                new Uint8Array(arrayBuffer).set(this.#reader.read_raw_bytes(byteLength));
+                return this.#addObject(arrayBuffer);
+            }
+            case TAG.ARRAY_BUFFER_TRANSFER: {
+                const arrayBuffer = this.#transferredArrayBuffers.get(this.#reader.read_varint());
+                if (arrayBuffer === undefined) throw deserializeError();
+                return this.#addObject(arrayBuffer);
+            }
+            case TAG.ERROR:
+                return this.#readError();
+            case TAG.HOST_OBJECT:
+                return this.#readHostObject();
+            default:
+                throw deserializeError();
+        }
+    }
+
+    // Reserves the next object ID; `#setObject` fills it in once the object exists
+    #reserveId() {
+        const id = this.#nextId++;
+        this.#objects[id] = undefined;
+        return id;
+    }
+
+    #setObject(id, value) {
+        this.#objects[id] = { value };
+        return value;
+    }
+
+    #addObject(value) {
+        return this.#setObject(this.#reserveId(), value);
+    }
+
+    #readBigInt() {
+        const hex = this.#reader.read_bigint();
+        return hex.startsWith('-') ? -BigInt(`0x${hex.slice(1)}`) : BigInt(`0x${hex}`);
+    }
+
+    #readTwoByteString() {
+        const result = this.#reader.read_two_byte_string();
+        if (typeof result === 'string') return result;
+        let string = '';
+        for (let i = 0; i < result.length; i += 0x2000) {
+            string += String.fromCharCode.apply(null, result.subarray(i, i + 0x2000));
+        }
+        return string;
+    }
+
+    #readString() {
+        const value = this.#readObject();
+        if (typeof value !== 'string') throw deserializeError();
+        return value;
+    }
+
+    #readProperties(object, endTag) {
+        let count = 0;
+        for (;;) {
+            const tag = this.#reader.peek_tag();
+            if (tag === undefined) throw deserializeError();
+            if (tag === endTag) {
+                this.#reader.read_tag();
+                return count;
+            }
+            const key = this.#readObject();
+            if (typeof key !== 'string' && typeof key !== 'number') throw deserializeError();
+            const value = this.#readObject();
+            Object.defineProperty(object, key, {
+                value, writable: true, enumerable: true, configurable: true,
+            });
+            count++;
+        }
+    }
+
+    #readJSObject() {
+        const object = this.#addObject({});
+        const count = this.#readProperties(object, TAG.END_JS_OBJECT);
+        if (this.#reader.read_varint() !== count) throw deserializeError();
+        return object;
+    }
+
+    #readSparseArray() {
+        const length = this.#reader.read_varint();
+        const array = this.#addObject(new Array(length));
+        const count = this.#readProperties(array, TAG.END_SPARSE_JS_ARRAY);
+        if (this.#reader.read_varint() !== count) throw deserializeError();
+        if (this.#reader.read_varint() !== length) throw deserializeError();
+        return array;
+    }
+
+    #readDenseArray() {
+        const length = this.#reader.read_varint();
+        // Filled in index order (rather than preallocated) so that the array stays packed
+        const array = this.#addObject([]);
+        for (let i = 0; i < length; i++) {
+            if (this.#reader.peek_tag() === TAG.THE_HOLE) {
+                this.#reader.read_tag();
+                continue;
+            }
+            const value = this.#readObject();
+            // Before version 11, undefined in a dense array stood for a hole
+            if (value === undefined && this.#version < 11) continue;
+            array[i] = value;
+        }
+        array.length = length;
+        const count = this.#readProperties(array, TAG.END_DENSE_JS_ARRAY);
+        if (this.#reader.read_varint() !== count) throw deserializeError();
+        if (this.#reader.read_varint() !== length) throw deserializeError();
+        return array;
+    }
+
+    #readRegExp() {
+        const id = this.#reserveId();
+        const source = this.#readString();
+        const bits = this.#reader.read_varint();
+        let flags = '';
+        for (const flag of ['d', 'g', 'i', 'm', 's', 'u', 'v', 'y']) {
+            if (bits & (1 << REGEXP_FLAGS.indexOf(flag))) flags += flag;
+        }
+        if (bits >= 1 << REGEXP_FLAGS.length || bits & (1 << REGEXP_FLAGS.indexOf('l'))) {
+            throw deserializeError();
+        }
+        let regexp;
+        try {
+            regexp = new RegExp(source, flags);
+        } catch (_) {
+            throw deserializeError();
+        }
+        return this.#setObject(id, regexp);
+    }
+
+    #readMap() {
+        const map = this.#addObject(new Map());
+        let length = 0;
+        while (this.#reader.peek_tag() !== TAG.END_JS_MAP) {
+            if (this.#reader.peek_tag() === undefined) throw deserializeError();
+            const key = this.#readObject();
+            const value = this.#readObject();
+            map.set(key, value);
+            length += 2;
+        }
+        this.#reader.read_tag();
+        if (this.#reader.read_varint() !== length) throw deserializeError();
+        return map;
+    }
+
+    #readSet() {
+        const set = this.#addObject(new Set());
+        let length = 0;
+        while (this.#reader.peek_tag() !== TAG.END_JS_SET) {
+            if (this.#reader.peek_tag() === undefined) throw deserializeError();
+            set.add(this.#readObject());
+            length++;
+        }
+        this.#reader.read_tag();
+        if (this.#reader.read_varint() !== length) throw deserializeError();
+        return set;
+    }
+
+    #readArrayBufferView(arrayBuffer) {
+        const subtag = this.#reader.read_varint();
+        const byteOffset = this.#reader.read_varint();
+        const byteLength = this.#reader.read_varint();
+        if (this.#version >= 14) {
+            this.#reader.read_varint(); // flags
+        }
+        const entry = VIEW_TAGS.find(([, tag]) => tag === subtag);
+        if (!entry || byteOffset + byteLength > arrayBuffer.byteLength) {
+            throw deserializeError();
+        }
+        const [ctor] = entry;
+        if (ctor === DataView) {
+            return this.#addObject(new DataView(arrayBuffer, byteOffset, byteLength));
+        }
+        const elementSize = ctor.BYTES_PER_ELEMENT;
+        if (byteOffset % elementSize !== 0 || byteLength % elementSize !== 0) {
+            throw deserializeError();
+        }
+        return this.#addObject(new ctor(arrayBuffer, byteOffset, byteLength / elementSize));
+    }
+
+    #readError() {
+        const id = this.#reserveId();
+        let ctor = Error;
+        let message;
+        let hasCause = false;
+        let cause;
+        let stack;
+        for (;;) {
+            const tag = this.#reader.read_varint();
+            if (tag === ERROR_TAG.END) break;
+            if (ERROR_CONSTRUCTORS.has(tag)) {
+                ctor = ERROR_CONSTRUCTORS.get(tag);
+            } else if (tag === ERROR_TAG.MESSAGE) {
+                message = this.#readString();
+            } else if (tag === ERROR_TAG.CAUSE) {
+                hasCause = true;
+                cause = this.#readObject();
+            } else if (tag === ERROR_TAG.STACK) {
+                stack = this.#readString();
+            } else {
+                throw deserializeError();
+            }
+        }
+        const error = hasCause ? new ctor(message, { cause }) : new ctor(message);
+        if (stack !== undefined) {
+            Object.defineProperty(error, 'stack', {
+                value: stack, writable: true, enumerable: false, configurable: true,
+            });
+        }
+        return this.#setObject(id, error);
+    }
+
+    #readHostObject() {
+        const id = this.#reserveId();
+        if (typeof this._readHostObject !== 'function') {
+            throw deserializeError();
+        }
+        const object = this._readHostObject();
+        if (object === null || (typeof object !== 'object' && typeof object !== 'function')) {
+            throw deserializeError();
+        }
+        return this.#setObject(id, object);
+    }
+}
+
+// ArrayBufferView types as indexed in Node's host object encoding; 10 is Buffer
+const HOST_OBJECT_VIEW_TYPES = [
+    Int8Array, Uint8Array, Uint8ClampedArray, Int16Array, Uint16Array, Int32Array,
+    Uint32Array, Float32Array, Float64Array, DataView, Buffer, BigInt64Array, BigUint64Array,
+];
+
+function hostObjectTypeIndex(view) {
+    if (view instanceof DataView) return 9;
+    const tag = typedArrayTag.call(view);
+    return HOST_OBJECT_VIEW_TYPES.findIndex((ctor, i) => i !== 10 && ctor.name === tag);
+}
+
+class DefaultSerializer extends Serializer {
     constructor() {
-        throw new Error('v8.Deserializer is not supported in WASM environment');
+        super();
+        this._setTreatArrayBufferViewsAsHostObjects(true);
+    }
+
+    _writeHostObject(abView) {
+        // Node serializes ArrayBufferViews (including Buffers) as host objects so that the
+        // rest of their (possibly pooled) ArrayBuffer is not included
+        let i = 10; // Buffer
+        if (abView.constructor !== Buffer) {
+            i = hostObjectTypeIndex(abView);
+            if (i === -1) {
+                throw new this._getDataCloneError(`Unserializable host object: ${describe(abView)}`);
+            }
+        }
+        this.writeUint32(i);
+        this.writeUint32(abView.byteLength);
+        this.writeRawBytes(new Uint8Array(abView.buffer, abView.byteOffset, abView.byteLength));
+    }
+}
+
+class DefaultDeserializer extends Deserializer {
+    _readHostObject() {
+        const typeIndex = this.readUint32();
+        const ctor = HOST_OBJECT_VIEW_TYPES[typeIndex];
+        if (ctor === undefined) {
+            throw deserializeError();
+        }
+        const byteLength = this.readUint32();
+        const byteOffset = this._readRawBytes(byteLength);
+        const BYTES_PER_ELEMENT = ctor.BYTES_PER_ELEMENT || 1;
+
+        const offset = this.buffer.byteOffset + byteOffset;
+        if (offset % BYTES_PER_ELEMENT === 0) {
+            return ctor === Buffer
+                ? Buffer.from(this.buffer.buffer, offset, byteLength)
+                : new ctor(this.buffer.buffer, offset, byteLength / BYTES_PER_ELEMENT);
+        }
+        // Copy to an aligned buffer first
+        const copy = new Uint8Array(byteLength);
+        copy.set(new Uint8Array(this.buffer.buffer, offset, byteLength));
+        return new ctor(copy.buffer, 0, byteLength / BYTES_PER_ELEMENT);
     }
 }
 
-class DefaultSerializer extends Serializer {}
-class DefaultDeserializer extends Deserializer {}
+export function serialize(value) {
+    const serializer = new DefaultSerializer();
+    serializer.writeHeader();
+    serializer.writeValue(value);
+    return serializer.releaseBuffer();
+}
+
+export function deserialize(buffer) {
+    const deserializer = new DefaultDeserializer(buffer);
+    deserializer.readHeader();
+    return deserializer.readValue();
+}
 
 export { Serializer, Deserializer, DefaultSerializer, DefaultDeserializer };
 
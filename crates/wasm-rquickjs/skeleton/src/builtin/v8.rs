@@ -1,2 +1,415 @@
+use rquickjs::class::Trace;
+use rquickjs::prelude::List;
+use rquickjs::{Ctx, Exception, IntoJs, JsLifetime, TypedArray, Value};
+use std::cell::{Cell, RefCell};
+
+// Native side of node:v8.
+//
+// For v8.Serializer / v8.Deserializer it implements the byte-level encoding of the V8
+// ValueSerializer wire format. Walking the object graph (object identities, property order,
+// host objects) is done in v8.js, which drives these writers and readers tag by tag.
+//
+// For the heap statistics and snapshots it exposes the memory usage counters of the QuickJS
+// runtime and the heap snapshot writer of v8_heap_snapshot.rs.
+#[rquickjs::module]
+pub mod native_module {
+    use rquickjs::{Ctx, Object};
+
+    pub use super::{V8Reader, V8Writer};
+
+    /// Returns the memory usage counters of the QuickJS runtime (`JS_ComputeMemoryUsage`),
+    /// with the size of the WebAssembly linear memory as `linear_memory_size`
+    #[rquickjs::function]
+    pub fn memory_usage<'js>(ctx: Ctx<'js>) -> rquickjs::Result<Object<'js>> {
+        let mut usage = std::mem::MaybeUninit::<rquickjs::qjs::JSMemoryUsage>::zeroed();
+        let usage = unsafe {
+            let rt = rquickjs::qjs::JS_GetRuntime(ctx.as_raw().as_ptr());
+            rquickjs::qjs::JS_ComputeMemoryUsage(rt, usage.as_mut_ptr());
+            usage.assume_init()
+        };
+
+        let result = Object::new(ctx)?;
+        result.set("malloc_size", usage.malloc_size as f64)?;
+        result.set("malloc_limit", usage.malloc_limit as f64)?;
+        result.set("memory_used_size", usage.memory_used_size as f64)?;
+        result.set("malloc_count", usage.malloc_count as f64)?;
+        result.set("memory_used_count", usage.memory_used_count as f64)?;
+        result.set("atom_count", usage.atom_count as f64)?;
+        result.set("atom_size", usage.atom_size as f64)?;
+        result.set("str_count", usage.str_count as f64)?;
+        result.set("str_size", usage.str_size as f64)?;
+        result.set("obj_count", usage.obj_count as f64)?;
+        result.set("obj_size", usage.obj_size as f64)?;
+        result.set("prop_count", usage.prop_count as f64)?;
+        result.set("prop_size", usage.prop_size as f64)?;
+        result.set("shape_count", usage.shape_count as f64)?;
+        result.set("shape_size", usage.shape_size as f64)?;
+        result.set("js_func_count", usage.js_func_count as f64)?;
+        result.set("js_func_size", usage.js_func_size as f64)?;
+        result.set("js_func_code_size", usage.js_func_code_size as f64)?;
+        result.set("js_func_pc2line_count", usage.js_func_pc2line_count as f64)?;
+        result.set("js_func_pc2line_size", usage.js_func_pc2line_size as f64)?;
+        result.set("c_func_count", usage.c_func_count as f64)?;
+        result.set("array_count", usage.array_count as f64)?;
+        result.set("fast_array_count", usage.fast_array_count as f64)?;
+        result.set("fast_array_elements", usage.fast_array_elements as f64)?;
+        result.set("binary_object_count", usage.binary_object_count as f64)?;
+        result.set("binary_object_size", usage.binary_object_size as f64)?;
+        result.set("linear_memory_size", super::linear_memory_size() as f64)?;
+        Ok(result)
+    }
+
+    /// Takes a heap snapshot of the runtime and returns it as `.heapsnapshot` JSON
+    #[rquickjs::function]
+    pub fn take_heap_snapshot(ctx: Ctx<'_>) -> String {
+        crate::builtin::v8_heap_snapshot::take_heap_snapshot(&ctx)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn linear_memory_size() -> usize {
+    core::arch::wasm32::memory_size(0) * 65536
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn linear_memory_size() -> usize {
+    0
+}
+
+/// The wire format version written by `writeHeader` (the one used by Node 20 and later)
+const LATEST_VERSION: u32 = 15;
+
+const TAG_VERSION: u8 = 0xFF;
+const TAG_PADDING: u8 = 0x00;
+const TAG_ONE_BYTE_STRING: u8 = b'"';
+const TAG_TWO_BYTE_STRING: u8 = b'c';
+
+const DESERIALIZE_ERROR: &str = "Unable to deserialize cloned data.";
+const VERSION_ERROR: &str =
+    "Unable to deserialize cloned data due to invalid or unsupported version.";
+
+fn varint_size(mut value: u64) -> usize {
+    let mut size = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        size += 1;
+    }
+    size
+}
+
+fn push_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn push_two_byte_string(buffer: &mut Vec<u8>, units: impl ExactSizeIterator<Item = u16>) {
+    let byte_length = (units.len() * 2) as u64;
+    // The string data has to start at an even offset
+    if (buffer.len() + 1 + varint_size(byte_length)) & 1 != 0 {
+        buffer.push(TAG_PADDING);
+    }
+    buffer.push(TAG_TWO_BYTE_STRING);
+    push_varint(buffer, byte_length);
+    for unit in units {
+        buffer.extend_from_slice(&unit.to_le_bytes());
+    }
+}
+
+#[derive(Trace, JsLifetime, Default)]
+#[rquickjs::class]
+pub struct V8Writer {
+    #[qjs(skip_trace)]
+    buffer: RefCell<Vec<u8>>,
+}
+
+#[rquickjs::methods]
+impl V8Writer {
+    #[qjs(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_header(&self) {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.push(TAG_VERSION);
+        push_varint(&mut buffer, LATEST_VERSION as u64);
+    }
+
+    pub fn write_tag(&self, tag: u8) {
+        self.buffer.borrow_mut().push(tag);
+    }
+
+    pub fn write_varint(&self, value: u32) {
+        push_varint(&mut self.buffer.borrow_mut(), value as u64);
+    }
+
+    pub fn write_varint64(&self, hi: u32, lo: u32) {
+        push_varint(
+            &mut self.buffer.borrow_mut(),
+            ((hi as u64) << 32) | lo as u64,
+        );
+    }
+
+    pub fn write_zigzag(&self, value: i32) {
+        let encoded = ((value << 1) ^ (value >> 31)) as u32;
+        push_varint(&mut self.buffer.borrow_mut(), encoded as u64);
+    }
+
+    pub fn write_double(&self, value: f64) {
+        self.buffer
+            .borrow_mut()
+            .extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a tagged string, as a one-byte (Latin-1) string if every character fits,
+    /// otherwise as a two-byte (UTF-16LE) string.
+    pub fn write_string(&self, value: String) {
+        let mut buffer = self.buffer.borrow_mut();
+        if value.chars().all(|c| (c as u32) <= 0xFF) {
+            buffer.push(TAG_ONE_BYTE_STRING);
+            push_varint(&mut buffer, value.chars().count() as u64);
+            buffer.extend(value.chars().map(|c| c as u8));
+        } else {
+            let units = value.encode_utf16().collect::<Vec<_>>();
+            push_two_byte_string(&mut buffer, units.into_iter());
+        }
+    }
+
+    /// Writes a tagged two-byte string from UTF-16 code units, for strings with lone
+    /// surrogates that cannot be passed as a Rust string.
+    pub fn write_two_byte_string(&self, units: TypedArray<'_, u16>) -> rquickjs::Result<()> {
+        let units: &[u16] = units.as_ref();
+        let units = units.to_vec();
+        push_two_byte_string(&mut self.buffer.borrow_mut(), units.into_iter());
+        Ok(())
+    }
+
+    /// Writes the bitfield and digits of a BigInt given its sign and magnitude in hexadecimal
+    pub fn write_bigint(
+        &self,
+        ctx: Ctx<'_>,
+        negative: bool,
+        magnitude: String,
+    ) -> rquickjs::Result<()> {
+        let digits = magnitude.trim_start_matches('0');
+        let mut bytes = Vec::with_capacity(digits.len().div_ceil(2));
+        let mut end = digits.len();
+        while end > 0 {
+            let start = end.saturating_sub(2);
+            let byte = u8::from_str_radix(&digits[start..end], 16)
+                .map_err(|_| Exception::throw_type(&ctx, "Invalid BigInt magnitude"))?;
+            bytes.push(byte);
+            end = start;
+        }
+        bytes.resize(bytes.len().div_ceil(8) * 8, 0);
+
+        let mut buffer = self.buffer.borrow_mut();
+        let sign = if negative && !bytes.is_empty() { 1 } else { 0 };
+        push_varint(&mut buffer, ((bytes.len() as u64) << 1) | sign);
+        buffer.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    pub fn write_raw_bytes(&self, bytes: TypedArray<'_, u8>) {
+        let bytes = bytes
+            .as_bytes()
+            .expect("the Uint8Array passed to writeRawBytes is detached");
+        self.buffer.borrow_mut().extend_from_slice(bytes);
+    }
+
+    /// Returns the written bytes and resets the writer
+    pub fn release_buffer<'js>(&self, ctx: Ctx<'js>) -> rquickjs::Result<TypedArray<'js, u8>> {
+        let bytes = std::mem::take(&mut *self.buffer.borrow_mut());
+        TypedArray::new(ctx, bytes)
+    }
+}
+
+#[derive(Trace, JsLifetime)]
+#[rquickjs::class]
+pub struct V8Reader {
+    #[qjs(skip_trace)]
+    data: Vec<u8>,
+    #[qjs(skip_trace)]
+    position: Cell<usize>,
+}
+
+impl V8Reader {
+    fn error(ctx: &Ctx<'_>) -> rquickjs::Error {
+        Exception::throw_message(ctx, DESERIALIZE_ERROR)
+    }
+
+    fn read_byte(&self, ctx: &Ctx<'_>) -> rquickjs::Result<u8> {
+        let position = self.position.get();
+        let byte = *self.data.get(position).ok_or_else(|| Self::error(ctx))?;
+        self.position.set(position + 1);
+        Ok(byte)
+    }
+
+    fn read_bytes(&self, ctx: &Ctx<'_>, length: usize) -> rquickjs::Result<&[u8]> {
+        let position = self.position.get();
+        let end = position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Self::error(ctx))?;
+        self.position.set(end);
+        Ok(&self.data[position..end])
+    }
+
+    fn read_varint_u64(&self, ctx: &Ctx<'_>) -> rquickjs::Result<u64> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_byte(ctx)?;
+            if shift < 64 {
+                value |= ((byte & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn read_length(&self, ctx: &Ctx<'_>) -> rquickjs::Result<usize> {
+        let length = self.read_varint_u64(ctx)?;
+        usize::try_from(length).map_err(|_| Self::error(ctx))
+    }
+}
+
+#[rquickjs::methods]
+impl V8Reader {
+    #[qjs(constructor)]
+    pub fn new(bytes: TypedArray<'_, u8>) -> Self {
+        let data = bytes
+            .as_bytes()
+            .expect("the Uint8Array passed to the v8 Deserializer is detached")
+            .to_vec();
+        Self {
+            data,
+            position: Cell::new(0),
+        }
+    }
+
+    /// Reads the version header, returning the wire format version (0 if there is none)
+    pub fn read_header(&self, ctx: Ctx<'_>) -> rquickjs::Result<u32> {
+        if self.data.get(self.position.get()) != Some(&TAG_VERSION) {
+            return Ok(0);
+        }
+        self.position.set(self.position.get() + 1);
+        let version = self.read_varint_u64(&ctx)?;
+        if version > LATEST_VERSION as u64 {
+            return Err(Exception::throw_message(&ctx, VERSION_ERROR));
+        }
+        Ok(version as u32)
+    }
+
+    /// Returns the next tag without consuming it (skipping padding), or undefined at the end
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data[self.position.get().min(self.data.len())..]
+            .iter()
+            .copied()
+            .find(|byte| *byte != TAG_PADDING)
+    }
+
+    pub fn read_tag(&self, ctx: Ctx<'_>) -> rquickjs::Result<u8> {
+        loop {
+            let tag = self.read_byte(&ctx)?;
+            if tag != TAG_PADDING {
+                return Ok(tag);
+            }
+        }
+    }
+
+    pub fn read_varint(&self, ctx: Ctx<'_>) -> rquickjs::Result<u32> {
+        let value = self.read_varint_u64(&ctx)?;
+        u32::try_from(value).map_err(|_| Self::error(&ctx))
+    }
+
+    pub fn read_varint64(&self, ctx: Ctx<'_>) -> rquickjs::Result<List<(u32, u32)>> {
+        let value = self.read_varint_u64(&ctx)?;
+        Ok(List(((value >> 32) as u32, value as u32)))
+    }
+
+    pub fn read_zigzag(&self, ctx: Ctx<'_>) -> rquickjs::Result<i32> {
+        let value = self.read_varint(ctx)?;
+        Ok(((value >> 1) as i32) ^ -((value & 1) as i32))
+    }
+
+    pub fn read_double(&self, ctx: Ctx<'_>) -> rquickjs::Result<f64> {
+        let bytes = self.read_bytes(&ctx, 8)?;
+        Ok(f64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+    }
+
+    pub fn read_one_byte_string(&self, ctx: Ctx<'_>) -> rquickjs::Result<String> {
+        let length = self.read_length(&ctx)?;
+        let bytes = self.read_bytes(&ctx, length)?;
+        Ok(bytes.iter().map(|b| *b as char).collect())
+    }
+
+    pub fn read_utf8_string(&self, ctx: Ctx<'_>) -> rquickjs::Result<String> {
+        let length = self.read_length(&ctx)?;
+        let bytes = self.read_bytes(&ctx, length)?;
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Reads a two-byte string. Returns the code units as a Uint16Array if they are not valid
+    /// UTF-16 (lone surrogates), so the caller can build the string itself.
+    pub fn read_two_byte_string<'js>(&self, ctx: Ctx<'js>) -> rquickjs::Result<Value<'js>> {
+        let length = self.read_length(&ctx)?;
+        if length % 2 != 0 {
+            return Err(Self::error(&ctx));
+        }
+        let units = self
+            .read_bytes(&ctx, length)?
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        match String::from_utf16(&units) {
+            Ok(string) => string.into_js(&ctx),
+            Err(_) => TypedArray::new(ctx.clone(), units)?.into_js(&ctx),
+        }
+    }
+
+    /// Reads the bitfield and digits of a BigInt, returning its value in hexadecimal with a
+    /// leading `-` if it is negative
+    pub fn read_bigint(&self, ctx: Ctx<'_>) -> rquickjs::Result<String> {
+        let bitfield = self.read_varint_u64(&ctx)?;
+        let length = usize::try_from(bitfield >> 1).map_err(|_| Self::error(&ctx))?;
+        let bytes = self.read_bytes(&ctx, length)?;
+        let mut digits = bytes
+            .iter()
+            .rev()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+            .trim_start_matches('0')
+            .to_string();
+        if digits.is_empty() {
+            digits.push('0');
+        } else if bitfield & 1 == 1 {
+            digits.insert(0, '-');
+        }
+        Ok(digits)
+    }
+
+    pub fn read_raw_bytes<'js>(
+        &self,
+        ctx: Ctx<'js>,
+        length: u32,
+    ) -> rquickjs::Result<TypedArray<'js, u8>> {
+        let bytes = self.read_bytes(&ctx, length as usize)?.to_vec();
+        TypedArray::new(ctx, bytes)
+    }
+
+    /// Skips `length` raw bytes, returning the offset they start at
+    pub fn skip_raw_bytes(&self, ctx: Ctx<'_>, length: u32) -> rquickjs::Result<u32> {
+        let position = self.position.get();
+        self.read_bytes(&ctx, length as usize)?;
+        Ok(position as u32)
+    }
+}
+
 pub const V8_JS: &str = include_str!("v8.js");
 pub const REEXPORT_JS: &str = r#"export * from 'node:v8'; export { default } from 'node:v8';"#;
@@ -0,0 +1,598 @@
+use super::intl::json_escape;
+use rquickjs::{Ctx, qjs};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{CStr, c_void};
+use std::fmt::Write;
+
+// Heap snapshots of the QuickJS runtime in the Chrome DevTools `.heapsnapshot` format.
+//
+// The nodes are the GC objects of the runtime (objects, function bytecode, shapes, closure
+// variables, async function states and contexts) as reported by `JS_WalkHeap`, with the
+// references between them as edges. Strings, symbols and bigints are not GC objects, they get a
+// node when a property, element or variable refers to them. Objects that cannot be reached from
+// any context are held by native code (or are cycles the GC did not collect yet), they are
+// attached to a "(native handles)" node. The memory not attributed to any node shows up as a
+// single "(QuickJS internals)" node, so the snapshot still adds up to the total heap usage.
+
+/// The heap walking API that `rquickjs-sys/quickjs.patch` adds to QuickJS, which the bundled
+/// bindings of rquickjs-sys do not declare
+mod ffi {
+    use rquickjs::qjs::{JSAtom, JSRuntime, JSValue};
+    use std::ffi::c_void;
+
+    pub const JS_HEAP_NODE_OBJECT: u32 = 0;
+    pub const JS_HEAP_NODE_FUNCTION_BYTECODE: u32 = 1;
+    pub const JS_HEAP_NODE_SHAPE: u32 = 2;
+    pub const JS_HEAP_NODE_VAR_REF: u32 = 3;
+    pub const JS_HEAP_NODE_ASYNC_FUNCTION: u32 = 4;
+    pub const JS_HEAP_NODE_CONTEXT: u32 = 5;
+
+    pub const JS_HEAP_EDGE_PROPERTY: u32 = 0;
+    pub const JS_HEAP_EDGE_ELEMENT: u32 = 1;
+    pub const JS_HEAP_EDGE_GETTER: u32 = 2;
+    pub const JS_HEAP_EDGE_SETTER: u32 = 3;
+    pub const JS_HEAP_EDGE_PROTOTYPE: u32 = 4;
+    pub const JS_HEAP_EDGE_SHAPE: u32 = 5;
+    pub const JS_HEAP_EDGE_CLOSURE_VAR: u32 = 6;
+    pub const JS_HEAP_EDGE_VALUE: u32 = 7;
+
+    pub type JSHeapNodeFunc = unsafe extern "C" fn(
+        opaque: *mut c_void,
+        id: *const c_void,
+        node_type: u32,
+        name: JSValue,
+        self_size: usize,
+    );
+    pub type JSHeapEdgeFunc = unsafe extern "C" fn(
+        opaque: *mut c_void,
+        edge_type: u32,
+        name: JSAtom,
+        index: u32,
+        to: *const c_void,
+        value: JSValue,
+    );
+
+    unsafe extern "C" {
+        pub fn JS_WalkHeap(
+            rt: *mut JSRuntime,
+            node_func: JSHeapNodeFunc,
+            edge_func: JSHeapEdgeFunc,
+            opaque: *mut c_void,
+        );
+    }
+}
+
+const NODE_TYPES: [&str; 15] = [
+    "hidden",
+    "array",
+    "string",
+    "object",
+    "code",
+    "closure",
+    "regexp",
+    "number",
+    "native",
+    "synthetic",
+    "concatenated string",
+    "sliced string",
+    "symbol",
+    "bigint",
+    "object shape",
+];
+const EDGE_TYPES: [&str; 7] = [
+    "context", "element", "property", "internal", "hidden", "shortcut", "weak",
+];
+
+const NODE_FIELDS: [&str; 7] = [
+    "type",
+    "name",
+    "id",
+    "self_size",
+    "edge_count",
+    "trace_node_id",
+    "detachedness",
+];
+const EDGE_FIELDS: [&str; 3] = ["type", "name_or_index", "to_node"];
+
+const NODE_HIDDEN: u8 = 0;
+const NODE_STRING: u8 = 2;
+const NODE_OBJECT: u8 = 3;
+const NODE_CODE: u8 = 4;
+const NODE_CLOSURE: u8 = 5;
+const NODE_REGEXP: u8 = 6;
+const NODE_SYNTHETIC: u8 = 9;
+const NODE_SYMBOL: u8 = 12;
+const NODE_BIGINT: u8 = 13;
+const NODE_OBJECT_SHAPE: u8 = 14;
+
+const EDGE_CONTEXT: u8 = 0;
+const EDGE_ELEMENT: u8 = 1;
+const EDGE_PROPERTY: u8 = 2;
+const EDGE_INTERNAL: u8 = 3;
+const EDGE_HIDDEN: u8 = 4;
+const EDGE_SHORTCUT: u8 = 5;
+
+// Estimated sizes (in bytes) of the QuickJS structures of values which are not GC objects
+const STRING_HEADER_SIZE: usize = 16;
+const SYMBOL_SIZE: usize = 32;
+const BIGINT_SIZE: usize = 16;
+
+// Indices of the synthetic nodes, which come first like in V8. Their IDs are odd like in V8,
+// the IDs of the other nodes are derived from their (even) addresses, which stay the same
+// across snapshots as long as the objects are alive.
+const ROOT: usize = 0;
+const GC_ROOTS: usize = 1;
+const NATIVE_HANDLES: usize = 2;
+const INTERNALS: usize = 3;
+
+struct Node {
+    node_type: u8,
+    name: usize,
+    id: u64,
+    self_size: usize,
+    edges: Vec<Edge>,
+}
+
+struct Edge {
+    edge_type: u8,
+    /// The index of the name in the string table, or the element index
+    name_or_index: usize,
+    to: EdgeTarget,
+}
+
+enum EdgeTarget {
+    Node(usize),
+    /// A GC object by address; all of them are known once the walk is over
+    Address(usize),
+}
+
+struct HeapSnapshotBuilder<'js> {
+    ctx: Ctx<'js>,
+    strings: Vec<String>,
+    string_indices: HashMap<String, usize>,
+    /// String table indices of the names of atoms and of string values, by atom and by address
+    atom_names: HashMap<qjs::JSAtom, usize>,
+    value_names: HashMap<usize, usize>,
+    nodes: Vec<Node>,
+    /// Nodes of GC objects and of the strings, symbols and bigints they refer to, by address
+    node_indices: HashMap<usize, usize>,
+    /// The node whose edges `JS_WalkHeap` is reporting
+    current: usize,
+    contexts: Vec<usize>,
+    global_address: usize,
+}
+
+impl<'js> HeapSnapshotBuilder<'js> {
+    fn new(ctx: Ctx<'js>) -> Self {
+        let global_address = unsafe { qjs::JS_VALUE_GET_PTR(ctx.globals().as_raw()) } as usize;
+        let mut builder = Self {
+            ctx,
+            strings: Vec::new(),
+            string_indices: HashMap::new(),
+            atom_names: HashMap::new(),
+            value_names: HashMap::new(),
+            nodes: Vec::new(),
+            node_indices: HashMap::new(),
+            current: ROOT,
+            contexts: Vec::new(),
+            global_address,
+        };
+        builder.add_node(NODE_SYNTHETIC, "", 1, 0);
+        builder.add_node(NODE_SYNTHETIC, "(GC roots)", 3, 0);
+        builder.add_node(NODE_SYNTHETIC, "(native handles)", 5, 0);
+        builder.add_node(NODE_HIDDEN, "(QuickJS internals)", 7, 0);
+        builder
+    }
+
+    fn string(&mut self, value: &str) -> usize {
+        if let Some(index) = self.string_indices.get(value) {
+            return *index;
+        }
+        let index = self.strings.len();
+        self.strings.push(value.to_string());
+        self.string_indices.insert(value.to_string(), index);
+        index
+    }
+
+    fn add_node(&mut self, node_type: u8, name: &str, id: u64, self_size: usize) -> usize {
+        let name = self.string(name);
+        self.push_node(node_type, name, id, self_size)
+    }
+
+    fn push_node(&mut self, node_type: u8, name: usize, id: u64, self_size: usize) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            node_type,
+            name,
+            id,
+            self_size,
+            edges: Vec::new(),
+        });
+        index
+    }
+
+    fn add_edge(&mut self, from: usize, edge_type: u8, name_or_index: usize, to: EdgeTarget) {
+        self.nodes[from].edges.push(Edge {
+            edge_type,
+            name_or_index,
+            to,
+        });
+    }
+
+    /// The string table index of a string value, by address so that every string is only
+    /// converted once
+    unsafe fn value_name(&mut self, value: qjs::JSValue) -> usize {
+        let address = unsafe { qjs::JS_VALUE_GET_PTR(value) } as usize;
+        if let Some(index) = self.value_names.get(&address) {
+            return *index;
+        }
+        let name = unsafe { self.string_contents(value) };
+        let index = self.string(&name);
+        self.value_names.insert(address, index);
+        index
+    }
+
+    fn atom_name(&mut self, atom: qjs::JSAtom) -> usize {
+        if let Some(index) = self.atom_names.get(&atom) {
+            return *index;
+        }
+        let raw_ctx = self.ctx.as_raw().as_ptr();
+        let name = unsafe {
+            let value = qjs::JS_AtomToValue(raw_ctx, atom);
+            let is_symbol = qjs::JS_IsSymbol(value);
+            qjs::JS_FreeValue(raw_ctx, value);
+            // The description of symbols
+            let string = qjs::JS_AtomToString(raw_ctx, atom);
+            let name = self.string_contents(string);
+            qjs::JS_FreeValue(raw_ctx, string);
+            if is_symbol {
+                format!("<symbol {name}>")
+            } else {
+                name
+            }
+        };
+        let index = self.string(&name);
+        self.atom_names.insert(atom, index);
+        index
+    }
+
+    /// Converts a string value without changing its reference count
+    unsafe fn string_contents(&self, value: qjs::JSValue) -> String {
+        let raw_ctx = self.ctx.as_raw().as_ptr();
+        unsafe {
+            let ptr = qjs::JS_ToCStringLen(raw_ctx, std::ptr::null_mut(), value);
+            if ptr.is_null() {
+                return String::new();
+            }
+            let contents = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            qjs::JS_FreeCString(raw_ctx, ptr);
+            contents
+        }
+    }
+
+    fn node_reported(&mut self, address: usize, kind: u32, name: qjs::JSValue, size: usize) {
+        let raw_ctx = self.ctx.as_raw().as_ptr();
+        let name = if unsafe { qjs::JS_IsString(name) } {
+            Some(unsafe { self.value_name(name) })
+        } else {
+            None
+        };
+        let (node_type, name) = match kind {
+            ffi::JS_HEAP_NODE_OBJECT => {
+                let object = qjs::JS_MKPTR(qjs::JS_TAG_OBJECT, address as *mut c_void);
+                let node_type = if unsafe { qjs::JS_IsFunction(raw_ctx, object) } {
+                    NODE_CLOSURE
+                } else if unsafe { qjs::JS_IsRegExp(object) } {
+                    NODE_REGEXP
+                } else {
+                    NODE_OBJECT
+                };
+                let name = if address == self.global_address {
+                    self.string("global")
+                } else {
+                    name.unwrap_or_else(|| self.string("Object"))
+                };
+                (node_type, name)
+            }
+            ffi::JS_HEAP_NODE_FUNCTION_BYTECODE => (
+                NODE_CODE,
+                name.unwrap_or_else(|| self.string("(anonymous)")),
+            ),
+            ffi::JS_HEAP_NODE_SHAPE => (NODE_OBJECT_SHAPE, self.string("system / Map")),
+            ffi::JS_HEAP_NODE_VAR_REF => (NODE_HIDDEN, self.string("system / Context")),
+            ffi::JS_HEAP_NODE_ASYNC_FUNCTION => {
+                (NODE_HIDDEN, self.string("system / AsyncFunctionState"))
+            }
+            ffi::JS_HEAP_NODE_CONTEXT => (NODE_HIDDEN, self.string("system / NativeContext")),
+            _ => (NODE_HIDDEN, self.string("(unknown)")),
+        };
+        self.current = self.push_node(node_type, name, node_id(address), size);
+        self.node_indices.insert(address, self.current);
+        if kind == ffi::JS_HEAP_NODE_CONTEXT {
+            self.contexts.push(self.current);
+        }
+    }
+
+    fn edge_reported(
+        &mut self,
+        edge_type: u32,
+        name: qjs::JSAtom,
+        index: u32,
+        to: *const c_void,
+        value: qjs::JSValue,
+    ) {
+        let to = if to.is_null() {
+            match unsafe { self.value_node(value) } {
+                Some(to) => to,
+                None => return,
+            }
+        } else {
+            EdgeTarget::Address(to as usize)
+        };
+        let (edge_type, name_or_index) = match edge_type {
+            ffi::JS_HEAP_EDGE_PROPERTY => (EDGE_PROPERTY, self.atom_name(name)),
+            ffi::JS_HEAP_EDGE_ELEMENT => (EDGE_ELEMENT, index as usize),
+            ffi::JS_HEAP_EDGE_GETTER | ffi::JS_HEAP_EDGE_SETTER => {
+                let prefix = if edge_type == ffi::JS_HEAP_EDGE_GETTER {
+                    "get"
+                } else {
+                    "set"
+                };
+                let name = self.atom_name(name);
+                let name = format!("{prefix} {}", self.strings[name]);
+                (EDGE_PROPERTY, self.string(&name))
+            }
+            ffi::JS_HEAP_EDGE_PROTOTYPE => (EDGE_PROPERTY, self.string("__proto__")),
+            ffi::JS_HEAP_EDGE_SHAPE => (EDGE_INTERNAL, self.string("map")),
+            ffi::JS_HEAP_EDGE_CLOSURE_VAR => (EDGE_CONTEXT, self.atom_name(name)),
+            ffi::JS_HEAP_EDGE_VALUE => (EDGE_INTERNAL, self.string("value")),
+            _ => (EDGE_HIDDEN, index as usize),
+        };
+        self.add_edge(self.current, edge_type, name_or_index, to);
+    }
+
+    /// The target of a reference to a value: GC objects by address, and a node for strings,
+    /// symbols and bigints, which are not GC objects. Other values are not stored on the heap.
+    unsafe fn value_node(&mut self, value: qjs::JSValue) -> Option<EdgeTarget> {
+        let tag = unsafe { qjs::JS_VALUE_GET_TAG(value) };
+        let address = match tag {
+            qjs::JS_TAG_OBJECT
+            | qjs::JS_TAG_FUNCTION_BYTECODE
+            | qjs::JS_TAG_STRING
+            | qjs::JS_TAG_SYMBOL
+            | qjs::JS_TAG_BIG_INT => unsafe { qjs::JS_VALUE_GET_PTR(value) as usize },
+            _ => return None,
+        };
+        if matches!(tag, qjs::JS_TAG_OBJECT | qjs::JS_TAG_FUNCTION_BYTECODE) {
+            return Some(EdgeTarget::Address(address));
+        }
+        if let Some(index) = self.node_indices.get(&address) {
+            return Some(EdgeTarget::Node(*index));
+        }
+        let index = match tag {
+            qjs::JS_TAG_STRING => {
+                let name = unsafe { self.value_name(value) };
+                let size = STRING_HEADER_SIZE + self.strings[name].len();
+                self.push_node(NODE_STRING, name, node_id(address), size)
+            }
+            qjs::JS_TAG_SYMBOL => {
+                self.add_node(NODE_SYMBOL, "symbol", node_id(address), SYMBOL_SIZE)
+            }
+            _ => self.add_node(NODE_BIGINT, "bigint", node_id(address), BIGINT_SIZE),
+        };
+        self.node_indices.insert(address, index);
+        Some(EdgeTarget::Node(index))
+    }
+
+    fn build(mut self) -> String {
+        let raw_ctx = self.ctx.as_raw().as_ptr();
+        unsafe {
+            let rt = qjs::JS_GetRuntime(raw_ctx);
+            ffi::JS_WalkHeap(
+                rt,
+                node_reported,
+                edge_reported,
+                &mut self as *mut Self as *mut c_void,
+            );
+        }
+        self.resolve_edges();
+
+        let global = self.node_indices.get(&self.global_address).copied();
+        self.add_edge(ROOT, EDGE_ELEMENT, 1, EdgeTarget::Node(GC_ROOTS));
+        if let Some(global) = global {
+            let name = self.string("global");
+            self.add_edge(ROOT, EDGE_SHORTCUT, name, EdgeTarget::Node(global));
+        }
+        for (i, context) in std::mem::take(&mut self.contexts).into_iter().enumerate() {
+            self.add_edge(GC_ROOTS, EDGE_ELEMENT, i + 1, EdgeTarget::Node(context));
+        }
+        let count = self.nodes[GC_ROOTS].edges.len();
+        self.add_edge(
+            GC_ROOTS,
+            EDGE_ELEMENT,
+            count + 1,
+            EdgeTarget::Node(NATIVE_HANDLES),
+        );
+        self.add_edge(
+            GC_ROOTS,
+            EDGE_ELEMENT,
+            count + 2,
+            EdgeTarget::Node(INTERNALS),
+        );
+        self.attach_unreachable();
+
+        let mut usage = std::mem::MaybeUninit::<qjs::JSMemoryUsage>::zeroed();
+        let usage = unsafe {
+            qjs::JS_ComputeMemoryUsage(qjs::JS_GetRuntime(raw_ctx), usage.as_mut_ptr());
+            usage.assume_init()
+        };
+        let walked_size: usize = self.nodes.iter().map(|node| node.self_size).sum();
+        self.nodes[INTERNALS].self_size =
+            (usage.memory_used_size.max(0) as usize).saturating_sub(walked_size);
+
+        self.serialize()
+    }
+
+    /// Turns the references to GC objects by address into node indices, dropping the ones to
+    /// objects that were not reported as nodes
+    fn resolve_edges(&mut self) {
+        for node in 0..self.nodes.len() {
+            let edges = std::mem::take(&mut self.nodes[node].edges);
+            self.nodes[node].edges = edges
+                .into_iter()
+                .filter_map(|edge| {
+                    let to = match edge.to {
+                        EdgeTarget::Address(address) => *self.node_indices.get(&address)?,
+                        EdgeTarget::Node(index) => index,
+                    };
+                    Some(Edge {
+                        to: EdgeTarget::Node(to),
+                        ..edge
+                    })
+                })
+                .collect();
+        }
+    }
+
+    /// Attaches the nodes which cannot be reached from the root to the native handles node, so
+    /// that DevTools shows all of them
+    fn attach_unreachable(&mut self) {
+        let mut reached = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([ROOT]);
+        let mut attached = 0;
+        for start in 0..self.nodes.len() {
+            if !reached[start] && start != ROOT {
+                attached += 1;
+                self.add_edge(
+                    NATIVE_HANDLES,
+                    EDGE_ELEMENT,
+                    attached,
+                    EdgeTarget::Node(start),
+                );
+                queue.push_back(start);
+            }
+            while let Some(node) = queue.pop_front() {
+                if reached[node] {
+                    continue;
+                }
+                reached[node] = true;
+                for edge in &self.nodes[node].edges {
+                    if let EdgeTarget::Node(to) = edge.to
+                        && !reached[to]
+                    {
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+    }
+
+    fn serialize(&self) -> String {
+        let node_count = self.nodes.len();
+        let edge_count: usize = self.nodes.iter().map(|node| node.edges.len()).sum();
+
+        let mut json = String::new();
+        json.push_str(r#"{"snapshot":{"meta":{"node_fields":"#);
+        push_string_array(&mut json, &NODE_FIELDS);
+        json.push_str(r#","node_types":["#);
+        push_string_array(&mut json, &NODE_TYPES);
+        json.push_str(r#","string","number","number","number","number","number"],"edge_fields":"#);
+        push_string_array(&mut json, &EDGE_FIELDS);
+        json.push_str(r#","edge_types":["#);
+        push_string_array(&mut json, &EDGE_TYPES);
+        json.push_str(r#","string_or_number","node"],"#);
+        json.push_str(r#""trace_function_info_fields":["function_id","name","script_name","script_id","line","column"],"#);
+        json.push_str(
+            r#""trace_node_fields":["id","function_info_index","count","size","children"],"#,
+        );
+        json.push_str(r#""sample_fields":["timestamp_us","last_assigned_id"],"#);
+        json.push_str(r#""location_fields":["object_index","script_id","line","column"]},"#);
+        let _ = write!(
+            json,
+            r#""node_count":{node_count},"edge_count":{edge_count},"trace_function_count":0}},"nodes":["#
+        );
+        for (i, node) in self.nodes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                "{},{},{},{},{},0,0",
+                node.node_type,
+                node.name,
+                node.id,
+                node.self_size,
+                node.edges.len()
+            );
+        }
+        json.push_str(r#"],"edges":["#);
+        let mut first = true;
+        for edge in self.nodes.iter().flat_map(|node| &node.edges) {
+            let EdgeTarget::Node(to) = edge.to else {
+                continue;
+            };
+            if !first {
+                json.push(',');
+            }
+            first = false;
+            let _ = write!(
+                json,
+                "{},{},{}",
+                edge.edge_type,
+                edge.name_or_index,
+                to * NODE_FIELDS.len()
+            );
+        }
+        json.push_str(
+            r#"],"trace_function_infos":[],"trace_tree":[],"samples":[],"locations":[],"strings":"#,
+        );
+        push_string_array(&mut json, &self.strings);
+        json.push('}');
+        json
+    }
+}
+
+fn node_id(address: usize) -> u64 {
+    address as u64 * 2 + 1
+}
+
+fn push_string_array<S: AsRef<str>>(json: &mut String, values: &[S]) {
+    json.push('[');
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push('"');
+        json.push_str(&json_escape(value.as_ref()));
+        json.push('"');
+    }
+    json.push(']');
+}
+
+unsafe extern "C" fn node_reported(
+    opaque: *mut c_void,
+    id: *const c_void,
+    node_type: u32,
+    name: qjs::JSValue,
+    self_size: usize,
+) {
+    let builder = unsafe { &mut *(opaque as *mut HeapSnapshotBuilder) };
+    builder.node_reported(id as usize, node_type, name, self_size);
+}
+
+unsafe extern "C" fn edge_reported(
+    opaque: *mut c_void,
+    edge_type: u32,
+    name: qjs::JSAtom,
+    index: u32,
+    to: *const c_void,
+    value: qjs::JSValue,
+) {
+    let builder = unsafe { &mut *(opaque as *mut HeapSnapshotBuilder) };
+    builder.edge_reported(edge_type, name, index, to, value);
+}
+
+/// Takes a heap snapshot of the runtime and returns it as `.heapsnapshot` JSON
+pub fn take_heap_snapshot(ctx: &Ctx<'_>) -> String {
+    HeapSnapshotBuilder::new(ctx.clone()).build()
+}
@@ -1,7 +1,9 @@
-import { eval_in_new_context as evalInNewContext } from '__wasm_rquickjs_builtin/vm_native';
+import { create_context as createRealm } from '__wasm_rquickjs_builtin/vm_native';
 
-let contextIdCounter = 1;
-const contextSymbol = Symbol('vm.context');
+// Every contextified object is backed by its own realm. Properties of the contextified object
+// are forwarded to the realm's global object through accessors, and globals created by the
+// evaluated code are copied back to the contextified object after each evaluation.
+const contexts = new WeakMap();
 const identifierPattern = /^[$A-Z_a-z][$0-9A-Z_a-z]*$/;
 const moduleNamespaceExportsSymbol = Symbol.for('wasm-rquickjs.vm.namespaceExports');
 const moduleNamespaceBindingsSymbol = Symbol.for('wasm-rquickjs.vm.namespaceBindings');
@@ -168,26 +170,115 @@ function createModuleNamespace(module) {
     });
 }
 
-function createIndirectEvalSource(code) {
-    return '(0, eval)(' + JSON.stringify(code) + ')';
+function contextify(sandbox) {
+    const { global, evaluate } = createRealm();
+    const context = {
+        sandbox,
+        global,
+        evaluate,
+        intrinsics: new Set(Reflect.ownKeys(global)),
+        // Forwarded key -> the realm's own descriptor it shadows, if any
+        forwarded: new Map(),
+    };
+    contexts.set(sandbox, context);
+    return context;
 }
 
-export function runInNewContext(code, sandbox, options) {
-    if (code === undefined || code === null) code = '';
-    code = String(code);
+function forwardSandboxProperties(context) {
+    const { sandbox, global, forwarded } = context;
 
-    const keys = [];
-    const values = [];
+    for (const [key, shadowed] of forwarded) {
+        if (!Object.prototype.hasOwnProperty.call(sandbox, key)) {
+            delete global[key];
+            if (shadowed !== undefined) {
+                Object.defineProperty(global, key, shadowed);
+            }
+            forwarded.delete(key);
+        }
+    }
 
-    if (sandbox && typeof sandbox === 'object') {
-        const sandboxKeys = Object.keys(sandbox);
-        for (let i = 0; i < sandboxKeys.length; i++) {
-            keys.push(sandboxKeys[i]);
-            values.push(sandbox[sandboxKeys[i]]);
+    for (const key of Reflect.ownKeys(sandbox)) {
+        if (forwarded.has(key)) {
+            continue;
+        }
+        const own = Reflect.getOwnPropertyDescriptor(global, key);
+        if (own !== undefined && !own.configurable) {
+            // `var` and function declarations of earlier evaluations can only be kept in sync by value
+            if (own.writable && !Object.is(own.value, sandbox[key])) {
+                global[key] = sandbox[key];
+            }
+            continue;
+        }
+        Object.defineProperty(global, key, {
+            get() {
+                return sandbox[key];
+            },
+            set(value) {
+                sandbox[key] = value;
+            },
+            enumerable: Object.prototype.propertyIsEnumerable.call(sandbox, key),
+            configurable: true,
+        });
+        forwarded.set(key, context.intrinsics.has(key) ? own : undefined);
+    }
+}
+
+function copyGlobalsToSandbox(context) {
+    const { sandbox, global, intrinsics, forwarded } = context;
+
+    for (const key of forwarded.keys()) {
+        if (!Object.prototype.hasOwnProperty.call(global, key)) {
+            // Deleted by the evaluated code
+            delete sandbox[key];
+            forwarded.delete(key);
+        }
+    }
+
+    let moved = false;
+    for (const key of Reflect.ownKeys(global)) {
+        if (intrinsics.has(key) || forwarded.has(key)) {
+            continue;
+        }
+        const descriptor = Reflect.getOwnPropertyDescriptor(global, key);
+        const existing = Reflect.getOwnPropertyDescriptor(sandbox, key);
+        let copied = false;
+        if (existing === undefined || existing.configurable) {
+            copied = Reflect.defineProperty(sandbox, key, { ...descriptor, configurable: true });
+        } else if ('value' in descriptor && existing.writable) {
+            copied = Reflect.set(sandbox, key, descriptor.value);
+        }
+        if (copied && descriptor.configurable) {
+            delete global[key];
+            moved = true;
         }
     }
 
-    return evalInNewContext(createIndirectEvalSource(code), keys, values);
+    if (moved) {
+        // Replace the moved globals with accessors, so that writes from callbacks running
+        // after this evaluation reach the sandbox too
+        forwardSandboxProperties(context);
+    }
+}
+
+function evaluateInContext(context, code, options) {
+    const filename = typeof options === 'string'
+        ? options
+        : (options && options.filename !== undefined ? String(options.filename) : 'evalmachine.<anonymous>');
+
+    forwardSandboxProperties(context);
+    try {
+        return context.evaluate(code, filename);
+    } finally {
+        copyGlobalsToSandbox(context);
+    }
+}
+
+export function runInNewContext(code, sandbox, options) {
+    if (code === undefined || code === null) code = '';
+    code = String(code);
+
+    const context = createContext(sandbox);
+    return evaluateInContext(contexts.get(context), code, options);
 }
 
 export function createContext(sandbox) {
@@ -197,12 +288,14 @@ export function createContext(sandbox) {
     if (typeof sandbox !== 'object') {
         throw new TypeError('sandbox must be an object');
     }
-    sandbox[contextSymbol] = contextIdCounter++;
+    if (!contexts.has(sandbox)) {
+        contextify(sandbox);
+    }
     return sandbox;
 }
 
 export function isContext(obj) {
-    return obj != null && typeof obj === 'object' && contextSymbol in obj;
+    return obj != null && typeof obj === 'object' && contexts.has(obj);
 }
 
 export function runInContext(code, context, options) {
@@ -212,17 +305,7 @@ export function runInContext(code, context, options) {
     if (code === undefined || code === null) code = '';
     code = String(code);
 
-    const keys = [];
-    const values = [];
-    for (const k of Object.keys(context)) {
-        if (typeof context[contextSymbol] !== 'undefined' && k === String(contextSymbol)) {
-            continue;
-        }
-        keys.push(k);
-        values.push(context[k]);
-    }
-
-    return evalInNewContext(createIndirectEvalSource(code), keys, values);
+    return evaluateInContext(contexts.get(context), code, options);
 }
 
 export function runInThisContext(code, options) {
@@ -233,6 +316,10 @@ export function runInThisContext(code, options) {
 
 export function compileFunction(code, params, options) {
     params = params || [];
+    if (options && isContext(options.parsingContext)) {
+        const source = '(function (' + params.join(', ') + ') {\n' + code + '\n})';
+        return evaluateInContext(contexts.get(options.parsingContext), source, options);
+    }
     return new Function(...params, code);
 }
 
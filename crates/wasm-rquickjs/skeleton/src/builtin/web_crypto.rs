@@ -1,4 +1,3 @@
-use rand::RngCore;
 use rquickjs::TypedArray;
 use std::collections::HashMap;
 use std::slice;
@@ -731,7 +730,7 @@ fn des3_wrap_encrypt(key: &[u8], plaintext: &[u8]) -> Option<Vec<u8>> {
     let checksum = sha1.finalize();
 
     let mut random_iv = [0u8; 8];
-    rand::rng().fill_bytes(&mut random_iv);
+    crate::builtin::deterministic::fill_random_bytes(&mut random_iv);
 
     let mut payload = Vec::with_capacity(plaintext.len() + 8);
     payload.extend_from_slice(plaintext);
@@ -2998,7 +2997,7 @@ fn generate_key_pair_impl(
     let (priv_key, pub_key) = match algorithm {
         "ed25519" => {
             let mut bytes = [0u8; 32];
-            rand::rng().fill_bytes(&mut bytes);
+            crate::builtin::deterministic::fill_random_bytes(&mut bytes);
             let sk = Ed25519SigningKey::from_bytes(&bytes);
             let pk = Ed25519VerifyingKey::from(&sk);
             (KeyData::Ed25519Private(sk), KeyData::Ed25519Public(pk))
@@ -3008,7 +3007,7 @@ fn generate_key_pair_impl(
             match curve {
                 "prime256v1" | "P-256" | "p256" => {
                     let mut bytes = [0u8; 32];
-                    rand::rng().fill_bytes(&mut bytes);
+                    crate::builtin::deterministic::fill_random_bytes(&mut bytes);
                     let sk = p256::ecdsa::SigningKey::from_bytes((&bytes).into()).ok()?;
                     let pk = *sk.verifying_key();
                     (KeyData::EcP256Private(sk), KeyData::EcP256Public(pk))
@@ -3016,7 +3015,7 @@ fn generate_key_pair_impl(
                 #[cfg(feature = "crypto-full")]
                 "secp384r1" | "P-384" | "p384" => {
                     let mut bytes = [0u8; 48];
-                    rand::rng().fill_bytes(&mut bytes);
+                    crate::builtin::deterministic::fill_random_bytes(&mut bytes);
                     let sk = p384::ecdsa::SigningKey::from_bytes((&bytes).into()).ok()?;
                     let pk = *sk.verifying_key();
                     (KeyData::EcP384Private(sk), KeyData::EcP384Public(pk))
@@ -3024,7 +3023,7 @@ fn generate_key_pair_impl(
                 #[cfg(feature = "crypto-full")]
                 "secp256k1" => {
                     let mut bytes = [0u8; 32];
-                    rand::rng().fill_bytes(&mut bytes);
+                    crate::builtin::deterministic::fill_random_bytes(&mut bytes);
                     let sk = k256::ecdsa::SigningKey::from_bytes((&bytes).into()).ok()?;
                     let pk = *sk.verifying_key();
                     (KeyData::EcK256Private(sk), KeyData::EcK256Public(pk))
@@ -3174,7 +3173,7 @@ fn encrypt_traditional_pem(
 
     // Generate random IV
     let mut iv_bytes = [0u8; 16];
-    rand::rng().fill_bytes(&mut iv_bytes);
+    crate::builtin::deterministic::fill_random_bytes(&mut iv_bytes);
 
     // Derive key using EVP_BytesToKey (salt = first 8 bytes of IV)
     let (key, _) = evp_bytes_to_key(passphrase, &iv_bytes[..8], key_len, 0);
@@ -4970,7 +4969,7 @@ fn dh_generate_keys_impl(id: u32) -> Option<Vec<u8>> {
         let p_minus_2 = &state.p - BigUint::from(2u32);
         let p_byte_len = state.p_len;
         let mut buf = vec![0u8; p_byte_len];
-        rand::rng().fill_bytes(&mut buf);
+        crate::builtin::deterministic::fill_random_bytes(&mut buf);
         let candidate = BigUint::from_bytes_be(&buf);
         let priv_key = (candidate % &p_minus_2) + BigUint::from(2u32);
         state.priv_key = Some(priv_key);
@@ -5172,7 +5171,7 @@ fn ecdh_generate_keys_impl(id: u32) -> Option<Vec<u8>> {
     match state {
         EcdhState::P256 { sk, pk } => {
             let mut bytes = [0u8; 32];
-            rand::rng().fill_bytes(&mut bytes);
+            crate::builtin::deterministic::fill_random_bytes(&mut bytes);
             let secret = p256::SecretKey::from_slice(&bytes).ok()?;
             let public = secret.public_key();
             let encoded = public.to_encoded_point(false);
@@ -5183,7 +5182,7 @@ fn ecdh_generate_keys_impl(id: u32) -> Option<Vec<u8>> {
         #[cfg(feature = "crypto-full")]
         EcdhState::P384 { sk, pk } => {
             let mut bytes = [0u8; 48];
-            rand::rng().fill_bytes(&mut bytes);
+            crate::builtin::deterministic::fill_random_bytes(&mut bytes);
             let secret = p384::SecretKey::from_slice(&bytes).ok()?;
             let public = secret.public_key();
             let encoded = public.to_encoded_point(false);
@@ -5194,7 +5193,7 @@ fn ecdh_generate_keys_impl(id: u32) -> Option<Vec<u8>> {
         #[cfg(feature = "crypto-full")]
         EcdhState::K256 { sk, pk } => {
             let mut bytes = [0u8; 32];
-            rand::rng().fill_bytes(&mut bytes);
+            crate::builtin::deterministic::fill_random_bytes(&mut bytes);
             let secret = k256::SecretKey::from_slice(&bytes).ok()?;
             let public = secret.public_key();
             let encoded = public.to_encoded_point(false);
@@ -5408,8 +5407,11 @@ pub mod native_module {
 
     #[rquickjs::function]
     pub fn random_uuid_v4_string() -> String {
-        let uuid = uuid::Uuid::new_v4();
-        uuid.to_string()
+        let mut bytes = [0u8; 16];
+        crate::builtin::deterministic::fill_random_bytes(&mut bytes);
+        uuid::Builder::from_random_bytes(bytes)
+            .into_uuid()
+            .to_string()
     }
 
     #[rquickjs::function]
@@ -5493,15 +5495,13 @@ pub mod native_module {
 
     #[rquickjs::function]
     pub fn random_bytes(len: u32) -> Vec<u8> {
-        use rand::RngCore;
         let mut buf = vec![0u8; len as usize];
-        rand::rng().fill_bytes(&mut buf);
+        crate::builtin::deterministic::fill_random_bytes(&mut buf);
         buf
     }
 
     #[rquickjs::function]
     pub fn random_int_range(min: f64, max: f64) -> Option<f64> {
-        use rand::RngCore;
         let min_i = min as i64;
         let max_i = max as i64;
         if min_i >= max_i {
@@ -5509,7 +5509,7 @@ pub mod native_module {
         }
         let range = (max_i - min_i) as u64;
         let mut buf = [0u8; 8];
-        rand::rng().fill_bytes(&mut buf);
+        crate::builtin::deterministic::fill_random_bytes(&mut buf);
         let random_val = u64::from_le_bytes(buf);
         let result = min_i + (random_val % range) as i64;
         Some(result as f64)
@@ -6178,7 +6178,7 @@ pub mod native_module {
 fn randomize_typed_array<V>(array: TypedArray<V>) {
     if let Some(raw) = array.as_raw() {
         let slice = unsafe { slice::from_raw_parts_mut(raw.ptr.as_ptr(), raw.len) };
-        rand::rng().fill_bytes(slice);
+        crate::builtin::deterministic::fill_random_bytes(slice);
     }
 }
 
@@ -1,11 +1,10 @@
-use rand::RngCore;
 use rquickjs::TypedArray;
 use std::slice;
 
 fn randomize_typed_array<V>(array: TypedArray<V>) {
     if let Some(raw) = array.as_raw() {
         let slice = unsafe { slice::from_raw_parts_mut(raw.ptr.as_ptr(), raw.len) };
-        rand::rng().fill_bytes(slice);
+        crate::builtin::deterministic::fill_random_bytes(slice);
     }
 }
 
@@ -15,21 +14,22 @@ pub mod native_module {
 
     #[rquickjs::function]
     pub fn random_uuid_v4_string() -> String {
-        let uuid = uuid::Uuid::new_v4();
-        uuid.to_string()
+        let mut bytes = [0u8; 16];
+        crate::builtin::deterministic::fill_random_bytes(&mut bytes);
+        uuid::Builder::from_random_bytes(bytes)
+            .into_uuid()
+            .to_string()
     }
 
     #[rquickjs::function]
     pub fn random_bytes(len: u32) -> Vec<u8> {
-        use rand::RngCore;
         let mut buf = vec![0u8; len as usize];
-        rand::rng().fill_bytes(&mut buf);
+        crate::builtin::deterministic::fill_random_bytes(&mut buf);
         buf
     }
 
     #[rquickjs::function]
     pub fn random_int_range(min: f64, max: f64) -> Option<f64> {
-        use rand::RngCore;
         let min_i = min as i64;
         let max_i = max as i64;
         if min_i >= max_i {
@@ -37,7 +37,7 @@ pub mod native_module {
         }
         let range = (max_i - min_i) as u64;
         let mut buf = [0u8; 8];
-        rand::rng().fill_bytes(&mut buf);
+        crate::builtin::deterministic::fill_random_bytes(&mut buf);
         let random_val = u64::from_le_bytes(buf);
         let result = min_i + (random_val % range) as i64;
         Some(result as f64)
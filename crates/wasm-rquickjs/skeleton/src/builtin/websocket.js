@@ -1,4 +1,4 @@
-import { ws_connect } from '__wasm_rquickjs_builtin/websocket_native';
+import { ws_connect } from '__wasm_rquickjs_builtin/websocket_connect';
 import { Event as NodeEvent } from 'node:events';
 
 // readyState constants
@@ -38,6 +38,15 @@ function normalizeWebSocketUrl(url) {
     return url;
 }
 
+// Connections opened over wasi:sockets report the subprotocol selected by the server,
+// golem:websocket connections do not expose it
+function negotiatedProtocol(connection, protocols) {
+    if (typeof connection.protocol === 'string') {
+        return connection.protocol;
+    }
+    return protocols.length > 0 ? protocols[0] : '';
+}
+
 const MESSAGE_EVENT_STATE = Symbol('MessageEvent.state');
 
 function getMessageEventState(event) {
@@ -174,13 +183,11 @@ class WebSocket {
     }
 
     _connectAsync() {
-        Promise.resolve().then(() => {
+        Promise.resolve().then(async () => {
             try {
-                this._connection = ws_connect(this._url, this._protocols);
+                this._connection = await ws_connect(this._url, this._protocols);
                 this._readyState = OPEN;
-                if (this._protocols.length > 0) {
-                    this._protocol = this._protocols[0];
-                }
+                this._protocol = negotiatedProtocol(this._connection, this._protocols);
                 this._dispatch('open', { type: 'open' });
                 this._startReceiveLoop();
             } catch (e) {
@@ -457,9 +464,9 @@ class WebSocketStream {
         this._writableStarted = false;
 
         // Connect asynchronously
-        Promise.resolve().then(() => {
+        Promise.resolve().then(async () => {
             try {
-                this._connection = ws_connect(this._url, this._protocols);
+                this._connection = await ws_connect(this._url, this._protocols);
             } catch (e) {
                 const err = new Error(e.message || String(e));
                 rejectOpened(err);
@@ -503,12 +510,10 @@ class WebSocketStream {
                 }
             });
 
-            const negotiatedProtocol = this._protocols.length > 0 ? this._protocols[0] : '';
-
             resolveOpened({
                 readable,
                 writable,
-                protocol: negotiatedProtocol,
+                protocol: negotiatedProtocol(conn, this._protocols),
                 extensions: '',
             });
         });
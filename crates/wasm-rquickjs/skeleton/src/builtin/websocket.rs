@@ -1,3 +1,6 @@
+// WebSocket connections provided by golem:websocket/client, used with the golem feature.
+// Without it they are implemented on top of wasi:sockets, see `websocket_tcp.rs`.
+
 use golem_websocket::{Error as WsError, Message, WebsocketConnection};
 use rquickjs::class::Trace;
 use rquickjs::{Ctx, Exception, JsLifetime};
@@ -175,6 +178,10 @@ impl WsConnection {
 
 pub const WEBSOCKET_JS: &str = include_str!("websocket.js");
 
+// Source of `ws_connect` for `WebSocket` and `WebSocketStream`
+pub const WEBSOCKET_CONNECT_JS: &str =
+    r#"export { ws_connect } from '__wasm_rquickjs_builtin/websocket_native';"#;
+
 pub const WIRE_JS: &str = r#"
     import {
         WebSocket as __WebSocket,
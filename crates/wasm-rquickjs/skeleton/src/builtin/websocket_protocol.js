@@ -0,0 +1,269 @@
+// RFC 6455 framing shared by the wasi:sockets based WebSocket client and server
+import { Buffer } from 'node:buffer';
+import { ws_accept_key, ws_decode_utf8 } from '__wasm_rquickjs_builtin/websocket_protocol_native';
+
+export const OPCODE_CONTINUATION = 0x0;
+export const OPCODE_TEXT = 0x1;
+export const OPCODE_BINARY = 0x2;
+export const OPCODE_CLOSE = 0x8;
+export const OPCODE_PING = 0x9;
+export const OPCODE_PONG = 0xa;
+
+// Close codes used when failing a connection (RFC 6455 section 7.4.1)
+export const CLOSE_NORMAL = 1000;
+export const CLOSE_PROTOCOL_ERROR = 1002;
+export const CLOSE_NO_STATUS = 1005;
+export const CLOSE_ABNORMAL = 1006;
+export const CLOSE_INVALID_PAYLOAD = 1007;
+export const CLOSE_MESSAGE_TOO_BIG = 1009;
+
+const DEFAULT_MAX_PAYLOAD = 100 * 1024 * 1024;
+
+export class WebSocketProtocolError extends Error {
+    constructor(message, closeCode = CLOSE_PROTOCOL_ERROR) {
+        super(message);
+        this.name = 'WebSocketProtocolError';
+        this.closeCode = closeCode;
+    }
+}
+
+function randomBytes(length) {
+    const bytes = new Uint8Array(length);
+    crypto.getRandomValues(bytes);
+    return bytes;
+}
+
+/** Generates a random `Sec-WebSocket-Key` */
+export function generateKey() {
+    return Buffer.from(randomBytes(16)).toString('base64');
+}
+
+/** Computes the `Sec-WebSocket-Accept` value answering a `Sec-WebSocket-Key` */
+export function acceptKey(key) {
+    return ws_accept_key(String(key));
+}
+
+function toBytes(data) {
+    if (typeof data === 'string') {
+        return Buffer.from(data, 'utf8');
+    }
+    if (data instanceof ArrayBuffer) {
+        return new Uint8Array(data);
+    }
+    if (ArrayBuffer.isView(data)) {
+        return new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+    }
+    return Buffer.from(String(data), 'utf8');
+}
+
+/**
+ * Encodes a single frame. Clients must mask every frame they send, servers must not mask
+ * any (RFC 6455 section 5.1).
+ */
+export function encodeFrame(opcode, payload, mask, fin = true) {
+    const data = payload === undefined ? new Uint8Array(0) : toBytes(payload);
+    const length = data.length;
+    const lengthBytes = length < 126 ? 0 : length < 0x10000 ? 2 : 8;
+    const headerLength = 2 + lengthBytes + (mask ? 4 : 0);
+    const frame = Buffer.alloc(headerLength + length);
+
+    frame[0] = (fin ? 0x80 : 0) | opcode;
+    if (lengthBytes === 0) {
+        frame[1] = length;
+    } else if (lengthBytes === 2) {
+        frame[1] = 126;
+        frame.writeUInt16BE(length, 2);
+    } else {
+        frame[1] = 127;
+        frame.writeUInt32BE(Math.floor(length / 0x100000000), 2);
+        frame.writeUInt32BE(length >>> 0, 6);
+    }
+
+    if (mask) {
+        frame[1] |= 0x80;
+        const maskingKey = randomBytes(4);
+        frame.set(maskingKey, 2 + lengthBytes);
+        for (let i = 0; i < length; i++) {
+            frame[headerLength + i] = data[i] ^ maskingKey[i & 3];
+        }
+    } else {
+        frame.set(data, headerLength);
+    }
+    return frame;
+}
+
+/** Encodes the body of a close frame; a missing code produces an empty body */
+export function encodeClosePayload(code, reason) {
+    if (code === undefined || code === null) {
+        return new Uint8Array(0);
+    }
+    const reasonBytes = Buffer.from(reason || '', 'utf8');
+    const payload = Buffer.alloc(2 + reasonBytes.length);
+    payload.writeUInt16BE(code, 0);
+    payload.set(reasonBytes, 2);
+    return payload;
+}
+
+function isValidCloseCode(code) {
+    return (code >= 1000 && code <= 1003) || (code >= 1007 && code <= 1014) ||
+        (code >= 3000 && code <= 4999);
+}
+
+function decodeUtf8(bytes) {
+    const text = ws_decode_utf8(bytes);
+    if (text == null) {
+        throw new WebSocketProtocolError('Invalid UTF-8 in text frame', CLOSE_INVALID_PAYLOAD);
+    }
+    return text;
+}
+
+/**
+ * Incrementally parses frames received from the peer and reassembles fragmented messages.
+ *
+ * `push` returns the complete messages found so far as `{ type, data }` records, where `type`
+ * is one of `text`, `binary`, `ping`, `pong` or `close` (with `data` being `{ code, reason }`),
+ * and throws a `WebSocketProtocolError` when the peer violates the protocol.
+ */
+export class FrameParser {
+    constructor(options = {}) {
+        this._expectMasked = !!options.expectMasked;
+        this._maxPayload = options.maxPayload || DEFAULT_MAX_PAYLOAD;
+        this._buffer = Buffer.alloc(0);
+        this._fragments = null;
+        this._fragmentsOpcode = 0;
+        this._fragmentsLength = 0;
+    }
+
+    push(chunk) {
+        this._buffer = this._buffer.length === 0 ? Buffer.from(chunk) : Buffer.concat([this._buffer, chunk]);
+        const messages = [];
+        let frame;
+        while ((frame = this._nextFrame()) !== null) {
+            const message = this._handleFrame(frame);
+            if (message !== null) {
+                messages.push(message);
+            }
+        }
+        return messages;
+    }
+
+    _nextFrame() {
+        const buf = this._buffer;
+        if (buf.length < 2) return null;
+
+        const fin = (buf[0] & 0x80) !== 0;
+        const rsv = buf[0] & 0x70;
+        const opcode = buf[0] & 0x0f;
+        const masked = (buf[1] & 0x80) !== 0;
+        let length = buf[1] & 0x7f;
+        let offset = 2;
+
+        if (rsv !== 0) {
+            throw new WebSocketProtocolError('Reserved bits must be clear');
+        }
+        if (masked !== this._expectMasked) {
+            throw new WebSocketProtocolError(masked ? 'Received a masked frame' : 'Received an unmasked frame');
+        }
+        if (length === 126) {
+            if (buf.length < offset + 2) return null;
+            length = buf.readUInt16BE(offset);
+            offset += 2;
+        } else if (length === 127) {
+            if (buf.length < offset + 8) return null;
+            const high = buf.readUInt32BE(offset);
+            if (high > 0x1fffff) {
+                throw new WebSocketProtocolError('Frame too large', CLOSE_MESSAGE_TOO_BIG);
+            }
+            length = high * 0x100000000 + buf.readUInt32BE(offset + 4);
+            offset += 8;
+        }
+        if (opcode >= OPCODE_CLOSE) {
+            if (!fin) {
+                throw new WebSocketProtocolError('Control frames must not be fragmented');
+            }
+            if (length > 125) {
+                throw new WebSocketProtocolError('Control frame payload too large');
+            }
+        }
+        if (length > this._maxPayload) {
+            throw new WebSocketProtocolError('Frame too large', CLOSE_MESSAGE_TOO_BIG);
+        }
+
+        let maskingKey = null;
+        if (masked) {
+            if (buf.length < offset + 4) return null;
+            maskingKey = buf.subarray(offset, offset + 4);
+            offset += 4;
+        }
+        if (buf.length < offset + length) return null;
+
+        const payload = Buffer.from(buf.subarray(offset, offset + length));
+        if (maskingKey !== null) {
+            for (let i = 0; i < payload.length; i++) {
+                payload[i] ^= maskingKey[i & 3];
+            }
+        }
+        this._buffer = buf.subarray(offset + length);
+        return { fin, opcode, payload };
+    }
+
+    _handleFrame({ fin, opcode, payload }) {
+        switch (opcode) {
+            case OPCODE_TEXT:
+            case OPCODE_BINARY:
+                if (this._fragments !== null) {
+                    throw new WebSocketProtocolError('Expected a continuation frame');
+                }
+                if (fin) {
+                    return this._message(opcode, payload);
+                }
+                this._fragments = [payload];
+                this._fragmentsOpcode = opcode;
+                this._fragmentsLength = payload.length;
+                return null;
+            case OPCODE_CONTINUATION: {
+                if (this._fragments === null) {
+                    throw new WebSocketProtocolError('Unexpected continuation frame');
+                }
+                this._fragments.push(payload);
+                this._fragmentsLength += payload.length;
+                if (this._fragmentsLength > this._maxPayload) {
+                    throw new WebSocketProtocolError('Message too large', CLOSE_MESSAGE_TOO_BIG);
+                }
+                if (!fin) {
+                    return null;
+                }
+                const data = Buffer.concat(this._fragments, this._fragmentsLength);
+                const messageOpcode = this._fragmentsOpcode;
+                this._fragments = null;
+                return this._message(messageOpcode, data);
+            }
+            case OPCODE_CLOSE: {
+                if (payload.length === 0) {
+                    return { type: 'close', data: { code: CLOSE_NO_STATUS, reason: '' } };
+                }
+                if (payload.length === 1) {
+                    throw new WebSocketProtocolError('Invalid close frame payload');
+                }
+                const code = payload.readUInt16BE(0);
+                if (!isValidCloseCode(code)) {
+                    throw new WebSocketProtocolError('Invalid close code ' + code);
+                }
+                return { type: 'close', data: { code, reason: decodeUtf8(payload.subarray(2)) } };
+            }
+            case OPCODE_PING:
+                return { type: 'ping', data: payload };
+            case OPCODE_PONG:
+                return { type: 'pong', data: payload };
+            default:
+                throw new WebSocketProtocolError('Unknown opcode ' + opcode);
+        }
+    }
+
+    _message(opcode, payload) {
+        if (opcode === OPCODE_TEXT) {
+            return { type: 'text', data: decodeUtf8(payload) };
+        }
+        return { type: 'binary', data: payload };
+    }
+}
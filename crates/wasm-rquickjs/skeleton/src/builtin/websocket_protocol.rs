@@ -0,0 +1,35 @@
+// RFC 6455 helpers shared by the wasi:sockets based WebSocket client and server
+use base64ct::{Base64, Encoding};
+use sha1::{Digest, Sha1};
+
+#[rquickjs::module]
+pub mod native_module {
+    use rquickjs::TypedArray;
+
+    /// Computes the `Sec-WebSocket-Accept` value answering a `Sec-WebSocket-Key`
+    #[rquickjs::function]
+    pub fn ws_accept_key(key: String) -> String {
+        super::accept_key(&key)
+    }
+
+    /// Decodes the payload of a text message, returning `None` if it is not valid UTF-8
+    #[rquickjs::function]
+    pub fn ws_decode_utf8(data: TypedArray<'_, u8>) -> Option<String> {
+        let bytes = data
+            .as_bytes()
+            .expect("the Uint8Array passed to wsDecodeUtf8 is detached");
+        std::str::from_utf8(bytes).ok().map(str::to_string)
+    }
+}
+
+pub const WEBSOCKET_PROTOCOL_JS: &str = include_str!("websocket_protocol.js");
+
+/// The GUID appended to the client key before hashing (RFC 6455 section 1.3)
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+fn accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.trim().as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    Base64::encode_string(&hasher.finalize())
+}
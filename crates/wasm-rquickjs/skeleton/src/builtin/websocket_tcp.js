@@ -0,0 +1,257 @@
+// WebSocket client connections over wasi:sockets, used by `WebSocket` and `WebSocketStream`
+// when the golem feature (and with it golem:websocket/client) is not available.
+// Connections expose the same interface as the golem-backed native `WsConnection`.
+import * as net from 'node:net';
+import { Buffer } from 'node:buffer';
+import {
+    OPCODE_TEXT,
+    OPCODE_BINARY,
+    OPCODE_CLOSE,
+    OPCODE_PONG,
+    CLOSE_NO_STATUS,
+    FrameParser,
+    WebSocketProtocolError,
+    acceptKey,
+    encodeClosePayload,
+    encodeFrame,
+    generateKey,
+} from '__wasm_rquickjs_builtin/websocket_protocol';
+
+const MAX_HANDSHAKE_RESPONSE = 64 * 1024;
+
+function connectionError(message) {
+    return new Error('WebSocket connection failed: ' + message);
+}
+
+function parseHandshakeResponse(head) {
+    const lines = head.split('\r\n');
+    const statusMatch = /^HTTP\/1\.1 (\d{3})(?: (.*))?$/.exec(lines[0]);
+    if (!statusMatch) {
+        throw connectionError('invalid handshake response status line');
+    }
+    const headers = new Map();
+    for (const line of lines.slice(1)) {
+        const colon = line.indexOf(':');
+        if (colon <= 0) {
+            throw connectionError('invalid handshake response header');
+        }
+        const name = line.slice(0, colon).trim().toLowerCase();
+        const value = line.slice(colon + 1).trim();
+        headers.set(name, headers.has(name) ? headers.get(name) + ', ' + value : value);
+    }
+    return { status: Number(statusMatch[1]), statusText: statusMatch[2] || '', headers };
+}
+
+function headerTokens(value) {
+    return (value || '').split(',').map(token => token.trim().toLowerCase()).filter(Boolean);
+}
+
+function validateHandshake(response, key, protocols) {
+    if (response.status !== 101) {
+        throw connectionError(`unexpected response status ${response.status} ${response.statusText}`.trim());
+    }
+    const headers = response.headers;
+    if (!headerTokens(headers.get('upgrade')).includes('websocket')) {
+        throw connectionError('missing "Upgrade: websocket" response header');
+    }
+    if (!headerTokens(headers.get('connection')).includes('upgrade')) {
+        throw connectionError('missing "Connection: Upgrade" response header');
+    }
+    if (headers.get('sec-websocket-accept') !== acceptKey(key)) {
+        throw connectionError('invalid Sec-WebSocket-Accept response header');
+    }
+    if (headers.get('sec-websocket-extensions')) {
+        throw connectionError('the server selected an extension that was not requested');
+    }
+    const protocol = headers.get('sec-websocket-protocol') || '';
+    if (protocol !== '' && !protocols.includes(protocol)) {
+        throw connectionError(`the server selected the unrequested subprotocol '${protocol}'`);
+    }
+    return protocol;
+}
+
+class TcpWsConnection {
+    constructor(socket, protocol, initialData) {
+        this.protocol = protocol;
+        this._socket = socket;
+        this._parser = new FrameParser({ expectMasked: false });
+        this._received = [];
+        this._waiters = [];
+        this._closeSent = false;
+        this._finished = false;
+        this._final = undefined;
+
+        socket.on('data', (chunk) => this._onData(chunk));
+        socket.on('error', (err) => this._finish(['error', err.message || String(err)]));
+        socket.on('close', () => {
+            if (this._closeSent) {
+                this._finish(['closed', { code: CLOSE_NO_STATUS, reason: '' }]);
+            } else {
+                this._finish(['error', 'Connection closed without a close frame']);
+            }
+        });
+        if (initialData.length > 0) {
+            this._onData(initialData);
+        }
+    }
+
+    _onData(chunk) {
+        if (this._finished) return;
+        let messages;
+        try {
+            messages = this._parser.push(chunk);
+        } catch (e) {
+            if (e instanceof WebSocketProtocolError) {
+                this._sendClose(e.closeCode, '');
+            }
+            this._socket.destroy();
+            this._finish(['error', e.message]);
+            return;
+        }
+        for (const { type, data } of messages) {
+            if (type === 'text') {
+                this._deliver(['text', data]);
+            } else if (type === 'binary') {
+                const copy = new Uint8Array(data);
+                this._deliver(['binary', copy.buffer]);
+            } else if (type === 'ping') {
+                this._write(encodeFrame(OPCODE_PONG, data, true));
+            } else if (type === 'close') {
+                // Echo the close frame, then let the server close the TCP connection
+                this._sendClose(data.code === CLOSE_NO_STATUS ? undefined : data.code, '');
+                this._socket.end();
+                this._finish(['closed', data]);
+                return;
+            }
+        }
+    }
+
+    _deliver(result) {
+        if (this._waiters.length > 0) {
+            this._waiters.shift()(result);
+        } else {
+            this._received.push(result);
+        }
+    }
+
+    _finish(result) {
+        if (this._finished) return;
+        this._finished = true;
+        this._deliver(result);
+        // Every later receive() observes the same final result
+        this._final = result;
+        for (const waiter of this._waiters.splice(0)) {
+            waiter(result);
+        }
+    }
+
+    _write(frame) {
+        if (!this._socket.destroyed && this._socket.writable) {
+            this._socket.write(frame);
+        }
+    }
+
+    _sendClose(code, reason) {
+        if (this._closeSent) return;
+        this._closeSent = true;
+        this._write(encodeFrame(OPCODE_CLOSE, encodeClosePayload(code, reason), true));
+    }
+
+    _checkOpen() {
+        if (this._closeSent || this._finished) {
+            throw new Error('WebSocket is closed');
+        }
+    }
+
+    send_text(data) {
+        this._checkOpen();
+        this._write(encodeFrame(OPCODE_TEXT, String(data), true));
+    }
+
+    send_binary(data) {
+        this._checkOpen();
+        this._write(encodeFrame(OPCODE_BINARY, data, true));
+    }
+
+    receive() {
+        if (this._received.length > 0) {
+            return Promise.resolve(this._received.shift());
+        }
+        if (this._final !== undefined) {
+            return Promise.resolve(this._final);
+        }
+        return new Promise(resolve => this._waiters.push(resolve));
+    }
+
+    close(code, reason) {
+        if (this._finished) return;
+        this._sendClose(code, reason);
+        this._socket.end();
+    }
+}
+
+/**
+ * Opens a `ws://` connection, performing the RFC 6455 opening handshake.
+ * Resolves to a connection with `send_text`, `send_binary`, `receive` and `close` methods.
+ */
+export function ws_connect(url, protocols) {
+    return new Promise((resolve, reject) => {
+        const parsed = new URL(url);
+        if (parsed.protocol === 'wss:') {
+            throw connectionError('secure WebSocket (wss://) connections require the golem feature');
+        }
+        const host = parsed.hostname.startsWith('[') ? parsed.hostname.slice(1, -1) : parsed.hostname;
+        const port = parsed.port ? Number(parsed.port) : 80;
+        const key = generateKey();
+
+        const requestLines = [
+            `GET ${parsed.pathname || '/'}${parsed.search} HTTP/1.1`,
+            `Host: ${parsed.host}`,
+            'Upgrade: websocket',
+            'Connection: Upgrade',
+            `Sec-WebSocket-Key: ${key}`,
+            'Sec-WebSocket-Version: 13',
+        ];
+        if (protocols.length > 0) {
+            requestLines.push(`Sec-WebSocket-Protocol: ${protocols.join(', ')}`);
+        }
+
+        const socket = net.createConnection({ host, port });
+        let head = Buffer.alloc(0);
+
+        const fail = (err) => {
+            socket.removeAllListeners();
+            socket.on('error', () => {});
+            socket.destroy();
+            reject(err);
+        };
+        const onData = (chunk) => {
+            head = Buffer.concat([head, chunk]);
+            const end = head.indexOf('\r\n\r\n');
+            if (end === -1) {
+                if (head.length > MAX_HANDSHAKE_RESPONSE) {
+                    fail(connectionError('handshake response too large'));
+                }
+                return;
+            }
+            socket.removeListener('data', onData);
+            socket.removeListener('error', onError);
+            socket.removeListener('close', onClose);
+            let protocol;
+            try {
+                protocol = validateHandshake(parseHandshakeResponse(head.toString('latin1', 0, end)), key, protocols);
+            } catch (e) {
+                fail(e);
+                return;
+            }
+            resolve(new TcpWsConnection(socket, protocol, head.subarray(end + 4)));
+        };
+        const onError = (err) => fail(connectionError(err.message || String(err)));
+        const onClose = () => fail(connectionError('connection closed during the handshake'));
+
+        socket.on('connect', () => socket.write(requestLines.join('\r\n') + '\r\n\r\n'));
+        socket.on('data', onData);
+        socket.on('error', onError);
+        socket.on('close', onClose);
+    });
+}
@@ -0,0 +1,22 @@
+// WebSocket connections over wasi:sockets, used when the golem feature (and with it
+// golem:websocket/client) is not available. See `websocket.rs` for the golem-backed ones.
+
+pub const WEBSOCKET_JS: &str = include_str!("websocket.js");
+
+// Source of `ws_connect` for `WebSocket` and `WebSocketStream`
+pub const WEBSOCKET_CONNECT_JS: &str = include_str!("websocket_tcp.js");
+
+pub const WIRE_JS: &str = r#"
+    import {
+        WebSocket as __WebSocket,
+        WebSocketStream as __WebSocketStream,
+        MessageEvent as __WsMessageEvent,
+        CloseEvent as __WsCloseEvent,
+        ErrorEvent as __WsErrorEvent,
+    } from '__wasm_rquickjs_builtin/websocket';
+    globalThis.WebSocket = __WebSocket;
+    globalThis.WebSocketStream = __WebSocketStream;
+    globalThis.MessageEvent = __WsMessageEvent;
+    globalThis.CloseEvent = __WsCloseEvent;
+    globalThis.ErrorEvent = __WsErrorEvent;
+"#;
@@ -1,21 +1,38 @@
-// node:worker_threads compatibility shim for single-threaded WASM runtimes.
+// node:worker_threads for single-threaded WASM runtimes.
 //
-// This runtime does not support real worker threads: there is no separate
-// JavaScript context or thread of execution. The `Worker` class below is an
-// inert API stub — it validates some constructor / postMessage inputs, but it
-// does NOT execute worker code, deliver messages, or simulate worker
-// lifecycle events.
+// Every `Worker` runs in its own realm: a separate QuickJS context on the
+// shared runtime, with its own global object, builtins and module map. Workers
+// are cooperative - they are scheduled on the same executor as the main
+// context and interleave with it at await points and timer callbacks - and
+// talk to their parent through entangled MessagePorts. Messages crossing a
+// realm boundary are structured-cloned once more in the receiving realm, so
+// the receiver never sees objects created by another realm.
 //
-// Tests or applications that require real worker execution / isolation are
-// intentionally unsupported here and should be classified in node_compat as
-// `known-gap` or `wasi-impossible`, depending on whether the behavior is
-// fundamentally unavailable in this environment.
-
-import { _emitInit } from 'node:async_hooks';
+// A worker exits once its script has been evaluated and it has nothing left
+// that keeps it alive: no ref'd timers and no ref'd or listening ports.
+
+import EventEmitter from 'node:events';
+import { resolve as resolvePath } from 'node:path';
+import { _emitInit } from '__wasm_rquickjs_builtin/async_hooks';
+import {
+    _hasRefedTimers,
+    _clearAllTimers,
+    _setTimerActivityHook,
+} from '__wasm_rquickjs_builtin/timeout';
+import {
+    create_worker_context as createWorkerContext,
+    next_thread_id as nextThreadId,
+} from '__wasm_rquickjs_builtin/worker_threads_native';
 
 const NOT_SUPPORTED_ERROR = 'worker_threads is not supported in WebAssembly environment';
 const UNTRANSFERABLE_SYMBOL = Symbol.for('__wasm_rquickjs.untransferable');
 const FILE_HANDLE_IN_USE_SYMBOL = Symbol.for('__wasm_rquickjs.filehandleInUse');
+const STRUCTURED_CLONE_SYMBOL = Symbol.for('__wasm_rquickjs.structuredClone');
+// Brand shared by the MessagePort classes of all realms, so ports created by another
+// realm are still recognized in transfer lists.
+const MESSAGE_PORT_SYMBOL = Symbol.for('__wasm_rquickjs.messagePort');
+
+export const SHARE_ENV = Symbol.for('nodejs.worker_threads.SHARE_ENV');
 
 function createDataCloneError(message) {
     return new DOMException(message, 'DataCloneError');
@@ -33,6 +50,20 @@ function createClosedMessagePortError() {
     return error;
 }
 
+function createWorkerPathError(filename) {
+    const error = new TypeError(
+        'The worker script or module filename must be an absolute path or a relative path ' +
+        `starting with './' or '../'. Received "${filename}"`);
+    error.code = 'ERR_WORKER_PATH';
+    return error;
+}
+
+function createInvalidArgTypeError(message) {
+    const error = new TypeError(message);
+    error.code = 'ERR_INVALID_ARG_TYPE';
+    return error;
+}
+
 function isObjectLike(value) {
     return value !== null && (typeof value === 'object' || typeof value === 'function');
 }
@@ -57,8 +88,21 @@ function normalizeTransferList(transferListOrOptions) {
     return [...transferListOrOptions];
 }
 
-function ensureTransferListItemsAreTransferable(transferList) {
+function isMessagePort(value) {
+    return isObjectLike(value) && value[MESSAGE_PORT_SYMBOL] === true;
+}
+
+function ensureTransferListItemsAreTransferable(transferList, sourcePort) {
     for (const transferItem of transferList) {
+        if (isMessagePort(transferItem)) {
+            if (transferItem === sourcePort) {
+                throw createDataCloneError('Transfer list contains source port');
+            }
+            if (transferItem._isClosed()) {
+                throw createDataCloneError('MessagePort in transfer list is already detached');
+            }
+        }
+
         if (isObjectLike(transferItem) && transferItem[FILE_HANDLE_IN_USE_SYMBOL] === true) {
             throw createDataCloneError('Cannot transfer FileHandle while in use');
         }
@@ -69,9 +113,13 @@ function ensureTransferListItemsAreTransferable(transferList) {
     }
 }
 
+// Clones `value` for posting. Transferred MessagePorts are replaced by fresh ports that
+// take over their entanglement; these are returned in `ports` so the receiver can mark
+// them as delivered.
 function cloneMessagePayload(value, transferList) {
     const TRANSFERABLE_SIGNAL = Symbol.for('__wasm_rquickjs.transferableAbortSignal');
     const signalMap = new Map();
+    const transferredPorts = [];
     const remainingTransfers = [];
 
     for (const item of transferList) {
@@ -85,6 +133,10 @@ function cloneMessagePayload(value, transferList) {
                 }, { once: true });
                 signalMap.set(item, ac.signal);
             }
+        } else if (isMessagePort(item)) {
+            if (!transferredPorts.includes(item)) {
+                transferredPorts.push(item);
+            }
         } else {
             remainingTransfers.push(item);
         }
@@ -96,14 +148,60 @@ function cloneMessagePayload(value, transferList) {
         valueWasTransferableSignal = true;
     }
 
-    if (remainingTransfers.length === 0) {
-        if (valueWasTransferableSignal) {
-            return value;
+    const ports = transferredPorts.map((port) => port._prepareTransfer());
+    let committed = false;
+    try {
+        let payload;
+        if (remainingTransfers.length === 0) {
+            payload = valueWasTransferableSignal ? value : structuredClone(value);
+        } else {
+            payload = structuredClone(value, { transfer: remainingTransfers });
+        }
+        committed = true;
+        return { payload, ports };
+    } finally {
+        for (const port of transferredPorts) {
+            port._finishTransfer(committed);
         }
-        return structuredClone(value);
     }
+}
 
-    return structuredClone(value, { transfer: remainingTransfers });
+// Re-creates an error thrown in a worker realm as an error of this realm.
+function importError(error) {
+    if (!isObjectLike(error)) {
+        return error;
+    }
+    if (!(typeof error.message === 'string' && typeof error.name === 'string')) {
+        try {
+            return structuredClone(error);
+        } catch {
+            return error;
+        }
+    }
+    const Ctor = typeof globalThis[error.name] === 'function' &&
+        (globalThis[error.name] === Error || globalThis[error.name].prototype instanceof Error)
+        ? globalThis[error.name]
+        : Error;
+    const imported = new Ctor(error.message);
+    if (Ctor === Error && error.name !== 'Error') {
+        imported.name = error.name;
+    }
+    for (const key of Object.keys(error)) {
+        try {
+            imported[key] = structuredClone(error[key]);
+        } catch {
+            // Skip properties that cannot cross realms.
+        }
+    }
+    if (typeof error.stack === 'string') {
+        Object.defineProperty(imported, 'stack', {
+            value: error.stack,
+            writable: true,
+            enumerable: false,
+            configurable: true,
+        });
+    }
+    return imported;
 }
 
 function createListenerMap() {
@@ -150,71 +248,206 @@ function emitListeners(listeners, event, value) {
     }
 }
 
-export const isMainThread = true;
-export const parentPort = null;
-export const workerData = null;
-export const threadId = 0;
-export const resourceLimits = {};
+// Set by `create_worker_context` while the builtins of a worker realm are evaluated;
+// undefined in the main context.
+const workerInit = globalThis.__wasm_rquickjs_worker_init;
 
-export class Worker {
-    #closed = false;
-    #listeners = createListenerMap();
+export const isMainThread = workerInit === undefined;
+export const threadId = isMainThread ? 0 : workerInit.threadId;
+export const resourceLimits = isMainThread ? {} : { ...workerInit.resourceLimits };
+export let parentPort = null;
+export let workerData = null;
 
-    constructor(filename, options) {
-        // Validate transferList eagerly so callers see the same DataCloneError
-        // they would on real Node.js, even though no work is dispatched.
-        const transferList = normalizeTransferList(options?.transferList);
-        ensureTransferListItemsAreTransferable(transferList);
+const environmentData = new Map();
+
+// Ports of this realm that may keep the realm's worker alive.
+const livePorts = new Set();
+let portActivityHook = null;
 
-        this.filename = filename;
-        // NOTE: We intentionally do NOT execute `filename` inline in the main
-        // context. Doing so created a fake worker semantics (shared globals,
-        // shared `process`, no real terminate(), broken FileHandle transfer)
-        // that silently passed some tests by violating Node.js's isolation
-        // contract. Tests that depend on real worker execution are classified
-        // as `known-gap` in node_compat config instead.
+function notifyPortActivity() {
+    if (portActivityHook !== null) {
+        portActivityHook();
     }
+}
 
-    on(event, fn) {
-        addListener(this.#listeners, event, fn, false);
-        return this;
+function hasLivePorts() {
+    for (const port of livePorts) {
+        if (port._keepsAlive()) {
+            return true;
+        }
     }
+    return false;
+}
 
-    once(event, fn) {
-        addListener(this.#listeners, event, fn, true);
-        return this;
+function resolveWorkerEntry(filename, isEval) {
+    if (isEval) {
+        if (typeof filename !== 'string') {
+            throw createInvalidArgTypeError(
+                'The "filename" argument must be of type string when "eval" is true.');
+        }
+        return { code: filename, filename: '[worker eval]' };
+    }
+
+    let url = null;
+    if (filename instanceof URL) {
+        url = filename.href;
+    } else if (typeof filename !== 'string') {
+        throw createInvalidArgTypeError(
+            'The "filename" argument must be of type string or an instance of URL.');
+    } else if (filename.startsWith('file:') || filename.startsWith('data:')) {
+        url = new URL(filename).href;
+    }
+
+    if (url !== null) {
+        if (!url.startsWith('file:') && !url.startsWith('data:')) {
+            const error = new TypeError(
+                'The URL must be of scheme file or data. Received protocol ' +
+                `'${new URL(url).protocol}'`);
+            error.code = 'ERR_INVALID_URL_SCHEME';
+            throw error;
+        }
+        return { specifier: url, filename: url };
     }
 
-    removeListener(event, fn) {
-        removeListener(this.#listeners, event, fn);
-        return this;
+    if (!filename.startsWith('/') && !filename.startsWith('./') && !filename.startsWith('../')) {
+        throw createWorkerPathError(filename);
     }
+    const absolute = resolvePath(process.cwd(), filename);
+    const specifier = 'file://' + absolute.split('/').map(encodeURIComponent).join('/');
+    return { specifier, filename: absolute };
+}
 
-    off(event, fn) {
-        return this.removeListener(event, fn);
+function normalizeWorkerArgv(argv) {
+    if (argv === undefined) {
+        return [];
     }
+    if (!Array.isArray(argv)) {
+        throw createInvalidArgTypeError('The "options.argv" property must be an instance of Array.');
+    }
+    return argv.map(String);
+}
 
-    postMessage(value, transferListOrOptions) {
-        // No worker exists to deliver to. Validate the transfer list shape
-        // (so callers see the same TypeError / DataCloneError they would on
-        // real Node.js for clearly invalid inputs), but do NOT clone the
-        // payload — cloning with `transfer` would detach the caller's
-        // ArrayBuffers / disentangle their MessagePorts even though there is
-        // no recipient, which would silently consume caller state.
-        if (this.#closed) {
-            return;
+function normalizeWorkerEnv(env) {
+    if (env === undefined || env === SHARE_ENV) {
+        return { ...process.env };
+    }
+    if (!isObjectLike(env)) {
+        throw createInvalidArgTypeError('The "options.env" property must be of type object.');
+    }
+    const copy = {};
+    for (const [key, value] of Object.entries(env)) {
+        copy[key] = String(value);
+    }
+    return copy;
+}
+
+export class Worker extends EventEmitter {
+    #port;
+    #internals;
+    #exitCode = null;
+    #exitPromise;
+    #resolveExit;
+
+    constructor(filename, options = {}) {
+        super();
+        if (!isObjectLike(options)) {
+            throw createInvalidArgTypeError('The "options" argument must be of type object.');
         }
-        const transferList = normalizeTransferList(transferListOrOptions);
+        const entry = resolveWorkerEntry(filename, options.eval === true);
+        const argv = normalizeWorkerArgv(options.argv);
+        const env = normalizeWorkerEnv(options.env);
+        const transferList = normalizeTransferList(options.transferList);
         ensureTransferListItemsAreTransferable(transferList);
+        const { payload, ports } = cloneMessagePayload(options.workerData, transferList);
+
+        this.threadId = nextThreadId();
+        this.resourceLimits = { ...options.resourceLimits };
+        this.stdin = null;
+        this.stdout = null;
+        this.stderr = null;
+        this.#exitPromise = new Promise((resolve) => {
+            this.#resolveExit = resolve;
+        });
+
+        const init = {
+            threadId: this.threadId,
+            resourceLimits: this.resourceLimits,
+            internals: null,
+            onExit: (code) => this.#onExit(code),
+            onError: (error) => this.#onError(error),
+        };
+        createWorkerContext(init);
+        this.#internals = init.internals;
+
+        this.#port = new MessagePort();
+        this.#port._target = this.#internals.port;
+        this.#internals.port._target = this.#port;
+        this.#port.on('message', (value) => this.emit('message', value));
+        this.#port.on('messageerror', (error) => this.emit('messageerror', error));
+
+        const start = {
+            ...entry,
+            argv,
+            env,
+            workerData: payload,
+            transferredPorts: ports,
+            environmentData: [...environmentData].map(([key, value]) => [key, structuredClone(value)]),
+        };
+        queueMicrotask(() => {
+            if (this.#exitCode !== null) {
+                return;
+            }
+            this.emit('online');
+            this.#internals.start(start);
+        });
     }
 
-    ref() {}
+    #onError(error) {
+        const imported = importError(error);
+        queueMicrotask(() => {
+            if (this.listenerCount('error') > 0) {
+                this.emit('error', imported);
+            } else if (typeof globalThis.__wasm_rquickjs_handleUncaughtError === 'function') {
+                globalThis.__wasm_rquickjs_handleUncaughtError(imported);
+            } else {
+                throw imported;
+            }
+        });
+    }
 
-    unref() {}
+    #onExit(code) {
+        if (this.#exitCode !== null) {
+            return;
+        }
+        this.#exitCode = code;
+        this.#port.close();
+        // Queued after the delivery of the messages the worker posted before exiting.
+        queueMicrotask(() => {
+            this.emit('exit', code);
+            this.#resolveExit(code);
+        });
+    }
 
-    terminate() {
-        this.#closed = true;
-        return Promise.resolve(0);
+    postMessage(value, transferListOrOptions) {
+        this.#port.postMessage(value, transferListOrOptions);
+    }
+
+    ref() {
+        this.#port.ref();
+    }
+
+    unref() {
+        this.#port.unref();
+    }
+
+    terminate(callback) {
+        if (typeof callback === 'function') {
+            this.#exitPromise.then((code) => callback(null, code));
+        }
+        if (this.#exitCode === null) {
+            this.#internals.terminate();
+        }
+        return this.#exitPromise;
     }
 }
 
@@ -229,21 +462,45 @@ export class MessagePort {
     #onmessageerror = null;
     #closed = false;
     #pendingClose = false;
+    #started = false;
     #refed = false;
+    #unrefed = false;
     #queue = [];
     #draining = false;
     #listeners = createListenerMap();
+    // The port replacing this one while it is being transferred.
+    #movedTo = null;
+    // Set on ports created by a transfer until they reach the receiving side.
+    #inTransit = false;
 
     constructor() {
         _emitInit('MESSAGEPORT', this);
     }
 
+    get [MESSAGE_PORT_SYMBOL]() {
+        return true;
+    }
+
+    [STRUCTURED_CLONE_SYMBOL]() {
+        if (this.#movedTo !== null) {
+            return this.#movedTo;
+        }
+        if (this.#inTransit) {
+            return this;
+        }
+        throw createDataCloneError('Object that needs transfer was found in message but not listed in transferList');
+    }
+
     get onmessage() {
         return this.#onmessage;
     }
 
     set onmessage(fn) {
         this.#onmessage = typeof fn === 'function' ? fn : null;
+        if (this.#onmessage !== null) {
+            this.#track();
+            this.start();
+        }
     }
 
     get onmessageerror() {
@@ -254,7 +511,7 @@ export class MessagePort {
         this.#onmessageerror = typeof fn === 'function' ? fn : null;
     }
 
-    _enqueueDelivery(value, messageError) {
+    _enqueueDelivery(value, messageError, foreign, transferredPorts) {
         // Already-queued messages must still drain after close() per Node docs
         // example (https://nodejs.org/api/worker_threads.html#class-messageport),
         // so we only refuse new deposits once the port is fully closed *or*
@@ -262,19 +519,38 @@ export class MessagePort {
         if (this.#closed || this.#pendingClose) {
             return;
         }
-        this.#queue.push({ value, messageError: messageError === true });
-        if (this.#draining) {
+        let error = null;
+        if (foreign === true && messageError !== true) {
+            // The payload was cloned by the sender's realm; clone it again so the
+            // listeners only ever see objects of this realm.
+            try {
+                value = structuredClone(value);
+            } catch (cloneError) {
+                error = cloneError;
+            }
+        }
+        if (transferredPorts) {
+            for (const port of transferredPorts) {
+                port._arrive();
+            }
+        }
+        this.#queue.push({ value, messageError: messageError === true || error !== null, error });
+        this.#scheduleDrain();
+    }
+
+    #scheduleDrain() {
+        if (this.#draining || !this.#started || this.#queue.length === 0) {
             return;
         }
         this.#draining = true;
         Promise.resolve().then(() => {
-            while (this.#queue.length > 0) {
-                const { value: queuedValue, messageError: queuedMessageError } = this.#queue.shift();
+            while (this.#started && this.#queue.length > 0) {
+                const { value: queuedValue, messageError: queuedMessageError, error } = this.#queue.shift();
                 if (queuedMessageError) {
-                    const error = createTargetContextUnavailableError();
-                    emitListeners(this.#listeners, 'messageerror', error);
+                    const messageError = error ?? createTargetContextUnavailableError();
+                    emitListeners(this.#listeners, 'messageerror', messageError);
                     if (typeof this.#onmessageerror === 'function') {
-                        this.#onmessageerror({ data: error });
+                        this.#onmessageerror({ data: messageError });
                     }
                     continue;
                 }
@@ -285,6 +561,7 @@ export class MessagePort {
                 }
             }
             this.#draining = false;
+            notifyPortActivity();
         });
     }
 
@@ -297,12 +574,12 @@ export class MessagePort {
         }
 
         const transferList = normalizeTransferList(transferListOrOptions);
-        ensureTransferListItemsAreTransferable(transferList);
-        const payload = cloneMessagePayload(value, transferList);
+        ensureTransferListItemsAreTransferable(transferList, this);
+        const { payload, ports } = cloneMessagePayload(value, transferList);
 
         const target = this._target;
         if (target && typeof target._enqueueDelivery === 'function') {
-            target._enqueueDelivery(payload, false);
+            target._enqueueDelivery(payload, false, !(target instanceof MessagePort), ports);
         }
     }
 
@@ -316,33 +593,96 @@ export class MessagePort {
         // Disentangle synchronously so further postMessage() calls are dropped,
         // but defer the observable #closed flip and the 'close' event emission
         // to a microtask so callers like Node's `hasRef()` semantics still see
-        // the port as ref'd between close() and the close event.
-        const target = this._target;
-        this.#pendingClose = true;
-        if (target instanceof MessagePort) {
-            target.#pendingClose = true;
-        }
+        // the port as ref'd between close() and the close event. The counterpart
+        // may belong to another realm, so it is driven through its own methods.
+        const target = isMessagePort(this._target) ? this._target : null;
+        this._beginClose();
+        target?._beginClose();
         Promise.resolve().then(() => {
-            this.#closed = true;
-            this._target = null;
-            if (target instanceof MessagePort) {
-                target.#closed = true;
-                target._target = null;
-            }
-            emitListeners(this.#listeners, 'close');
-            if (target instanceof MessagePort) {
-                emitListeners(target.#listeners, 'close');
-            }
+            this._markClosed();
+            target?._markClosed();
+            this._emitClose();
+            target?._emitClose();
         });
     }
 
+    _beginClose() {
+        this.#pendingClose = true;
+    }
+
+    _markClosed() {
+        this.#closed = true;
+        this._target = null;
+        livePorts.delete(this);
+    }
+
+    _emitClose() {
+        emitListeners(this.#listeners, 'close');
+        notifyPortActivity();
+    }
+
+    _prepareTransfer() {
+        const moved = new MessagePort();
+        moved.#inTransit = true;
+        this.#movedTo = moved;
+        return moved;
+    }
+
+    _finishTransfer(committed) {
+        const moved = this.#movedTo;
+        this.#movedTo = null;
+        if (!committed) {
+            return;
+        }
+        // The replacement takes over the entanglement and any messages not yet
+        // received; this port is detached without emitting 'close'.
+        const target = this._target;
+        moved._target = target;
+        if (target) {
+            target._target = moved;
+        }
+        moved.#queue = this.#queue;
+        this.#queue = [];
+        this._target = null;
+        this.#closed = true;
+        livePorts.delete(this);
+    }
+
+    _arrive() {
+        this.#inTransit = false;
+    }
+
+    _receiveMessage() {
+        const index = this.#queue.findIndex((entry) => !entry.messageError);
+        if (index === -1) {
+            return undefined;
+        }
+        const [entry] = this.#queue.splice(index, 1);
+        return { message: entry.value };
+    }
+
+    _keepsAlive() {
+        return !this.#closed && !this.#pendingClose && !this.#unrefed &&
+            (this.#refed || this.#listeners.message.length > 0 || this.#onmessage !== null);
+    }
+
+    #track() {
+        if (!this.#closed && !this.#pendingClose) {
+            livePorts.add(this);
+        }
+    }
+
     ref() {
         this.#refed = true;
+        this.#unrefed = false;
+        this.#track();
         return this;
     }
 
     unref() {
         this.#refed = false;
+        this.#unrefed = true;
+        notifyPortActivity();
         return this;
     }
 
@@ -350,20 +690,36 @@ export class MessagePort {
         return !this.#closed && (this.#refed || this.#listeners.message.length > 0 || this.#listeners.close.length > 0);
     }
 
-    start() {}
+    start() {
+        if (!this.#started) {
+            this.#started = true;
+            this.#scheduleDrain();
+        }
+    }
 
     on(event, fn) {
         addListener(this.#listeners, event, fn, false);
+        if (event === 'message') {
+            this.#track();
+            this.start();
+        }
         return this;
     }
 
     once(event, fn) {
         addListener(this.#listeners, event, fn, true);
+        if (event === 'message') {
+            this.#track();
+            this.start();
+        }
         return this;
     }
 
     removeListener(event, fn) {
         removeListener(this.#listeners, event, fn);
+        if (event === 'message') {
+            notifyPortActivity();
+        }
         return this;
     }
 
@@ -499,24 +855,132 @@ export function moveMessagePortToContext(port) {
     return movedPort;
 }
 
-export function receiveMessageOnPort() {
-    throw new Error(NOT_SUPPORTED_ERROR);
+export function receiveMessageOnPort(port) {
+    if (!isMessagePort(port)) {
+        throw createInvalidArgTypeError('The "port" argument must be a MessagePort instance');
+    }
+    return port._receiveMessage();
 }
 
-export function getEnvironmentData() {
-    throw new Error(NOT_SUPPORTED_ERROR);
+export function getEnvironmentData(key) {
+    return environmentData.get(key);
 }
 
-export function setEnvironmentData() {
-    throw new Error(NOT_SUPPORTED_ERROR);
+export function setEnvironmentData(key, value) {
+    if (value === undefined) {
+        environmentData.delete(key);
+    } else {
+        environmentData.set(key, value);
+    }
+}
+
+// The worker side of a `Worker`, evaluated in the worker's realm. `start` runs the
+// worker script; the parent calls it once it has emitted 'online'.
+function createWorkerInternals(init) {
+    let scriptDone = false;
+    let exited = false;
+    let idleCheckScheduled = false;
+
+    function exitWorker(code) {
+        if (exited) {
+            return;
+        }
+        exited = true;
+        _setTimerActivityHook(null);
+        portActivityHook = null;
+        _clearAllTimers();
+        for (const port of [...livePorts]) {
+            port.close();
+        }
+        parentPort.close();
+        init.onExit(code);
+    }
+
+    function fail(error) {
+        if (exited || (isObjectLike(error) && error.__isProcessExit === true)) {
+            return;
+        }
+        init.onError(error);
+        exitWorker(1);
+    }
+
+    function scheduleIdleCheck() {
+        if (!scriptDone || exited || idleCheckScheduled) {
+            return;
+        }
+        idleCheckScheduled = true;
+        queueMicrotask(() => {
+            idleCheckScheduled = false;
+            if (!exited && !_hasRefedTimers() && !hasLivePorts()) {
+                process._runExitHandlers();
+                exitWorker(process.exitCode || 0);
+            }
+        });
+    }
+
+    function start(options) {
+        workerData = structuredClone(options.workerData);
+        for (const port of options.transferredPorts) {
+            port._arrive();
+        }
+        for (const [key, value] of options.environmentData) {
+            environmentData.set(key, structuredClone(value));
+        }
+        workerThreads.workerData = workerData;
+
+        for (const key of Object.keys(process.env)) {
+            delete process.env[key];
+        }
+        Object.assign(process.env, options.env);
+        process.argv = [process.argv[0] ?? '', options.filename, ...options.argv];
+
+        const exitProcess = process.exit;
+        process.exit = function exit(code) {
+            try {
+                exitProcess.call(process, code);
+            } finally {
+                exitWorker(process.exitCode || 0);
+            }
+        };
+        Object.defineProperty(globalThis, '__wasm_rquickjs_worker_fatal_error', {
+            value: fail,
+            writable: true,
+            enumerable: false,
+            configurable: true,
+        });
+        _setTimerActivityHook(scheduleIdleCheck);
+        portActivityHook = scheduleIdleCheck;
+
+        let evaluation;
+        try {
+            evaluation = options.code !== undefined
+                ? Promise.resolve((0, eval)(options.code))
+                : import(options.specifier);
+        } catch (error) {
+            evaluation = Promise.reject(error);
+        }
+        evaluation.then(() => {
+            scriptDone = true;
+            scheduleIdleCheck();
+        }, fail);
+    }
+
+    return {
+        port: parentPort,
+        start,
+        terminate() {
+            exitWorker(1);
+        },
+    };
 }
 
-export default {
+const workerThreads = {
     isMainThread,
     parentPort,
     workerData,
     threadId,
     resourceLimits,
+    SHARE_ENV,
     Worker,
     BroadcastChannel,
     MessagePort,
@@ -527,3 +991,11 @@ export default {
     getEnvironmentData,
     setEnvironmentData,
 };
+
+if (!isMainThread) {
+    parentPort = new MessagePort();
+    workerThreads.parentPort = parentPort;
+    workerInit.internals = createWorkerInternals(workerInit);
+}
+
+export default workerThreads;
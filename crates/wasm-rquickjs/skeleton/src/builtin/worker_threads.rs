@@ -1,4 +1,39 @@
-// worker_threads module - JavaScript-only stub (no native functions needed)
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// Native functions for the worker_threads implementation
+#[rquickjs::module(rename = "camelCase")]
+pub mod native_module {
+    use rquickjs::{Ctx, Object};
+
+    /// Create the realm of a `Worker`: a new context on the same runtime (and therefore
+    /// scheduled on the same executor) with the builtins wired in like in the main context.
+    /// `init` is visible to the realm's `node:worker_threads` module while it is evaluated,
+    /// which is how the worker learns its thread id and hands its internals back to the parent.
+    #[rquickjs::function]
+    pub fn create_worker_context<'js>(
+        ctx: Ctx<'js>,
+        init: Object<'js>,
+    ) -> rquickjs::Result<Object<'js>> {
+        let realm = crate::internal::new_realm(&ctx)?;
+        let global = realm.globals();
+        global.set(super::WORKER_INIT_KEY, init)?;
+        crate::internal::wire_realm(&realm)?;
+        global.remove(super::WORKER_INIT_KEY)?;
+        Ok(global)
+    }
+
+    /// Allocate a thread id, unique across all workers of the runtime, including nested ones.
+    #[rquickjs::function]
+    pub fn next_thread_id() -> u32 {
+        super::NEXT_THREAD_ID.fetch_add(1, super::Ordering::Relaxed)
+    }
+}
+
+const WORKER_INIT_KEY: &str = "__wasm_rquickjs_worker_init";
+
+static NEXT_THREAD_ID: AtomicU32 = AtomicU32::new(1);
+
+// JS functions for the worker_threads implementation
 pub const WORKER_THREADS_JS: &str = include_str!("worker_threads.js");
 
 // Re-export for aliases
@@ -17,6 +17,11 @@ import {
   brotli_stream_push,
   brotli_stream_pull,
   brotli_stream_close,
+  zstd_compress_sync as _zstd_compress_sync,
+  zstd_decompress_sync as _zstd_decompress_sync,
+  zstd_stream_new,
+  zstd_stream_push,
+  zstd_stream_close,
 } from '__wasm_rquickjs_builtin/zlib_native';
 
 // Capture buffer.kMaxLength at require('zlib') time, matching Node.js CJS behavior
@@ -85,6 +90,8 @@ const INFLATERAW = 6;
 const UNZIP = 7;
 const BROTLI_DECODE = 8;
 const BROTLI_ENCODE = 9;
+const ZSTD_COMPRESS = 10;
+const ZSTD_DECOMPRESS = 11;
 
 const BROTLI_OPERATION_PROCESS = 0;
 const BROTLI_OPERATION_FLUSH = 1;
@@ -150,6 +157,73 @@ const BROTLI_DECODER_ERROR_ALLOC_RING_BUFFER_2 = -27;
 const BROTLI_DECODER_ERROR_ALLOC_BLOCK_TYPE_TREES = -30;
 const BROTLI_DECODER_ERROR_UNREACHABLE = -31;
 
+const ZSTD_e_continue = 0;
+const ZSTD_e_flush = 1;
+const ZSTD_e_end = 2;
+
+const ZSTD_fast = 1;
+const ZSTD_dfast = 2;
+const ZSTD_greedy = 3;
+const ZSTD_lazy = 4;
+const ZSTD_lazy2 = 5;
+const ZSTD_btlazy2 = 6;
+const ZSTD_btopt = 7;
+const ZSTD_btultra = 8;
+const ZSTD_btultra2 = 9;
+
+const ZSTD_c_compressionLevel = 100;
+const ZSTD_c_windowLog = 101;
+const ZSTD_c_hashLog = 102;
+const ZSTD_c_chainLog = 103;
+const ZSTD_c_searchLog = 104;
+const ZSTD_c_minMatch = 105;
+const ZSTD_c_targetLength = 106;
+const ZSTD_c_strategy = 107;
+const ZSTD_c_enableLongDistanceMatching = 160;
+const ZSTD_c_ldmHashLog = 161;
+const ZSTD_c_ldmMinMatch = 162;
+const ZSTD_c_ldmBucketSizeLog = 163;
+const ZSTD_c_ldmHashRateLog = 164;
+const ZSTD_c_contentSizeFlag = 200;
+const ZSTD_c_checksumFlag = 201;
+const ZSTD_c_dictIDFlag = 202;
+const ZSTD_c_nbWorkers = 400;
+const ZSTD_c_jobSize = 401;
+const ZSTD_c_overlapLog = 402;
+
+const ZSTD_d_windowLogMax = 100;
+
+const ZSTD_CLEVEL_DEFAULT = 3;
+
+const ZSTD_error_no_error = 0;
+const ZSTD_error_GENERIC = 1;
+const ZSTD_error_prefix_unknown = 10;
+const ZSTD_error_version_unsupported = 12;
+const ZSTD_error_frameParameter_unsupported = 14;
+const ZSTD_error_frameParameter_windowTooLarge = 16;
+const ZSTD_error_corruption_detected = 20;
+const ZSTD_error_checksum_wrong = 22;
+const ZSTD_error_literals_headerWrong = 24;
+const ZSTD_error_dictionary_corrupted = 30;
+const ZSTD_error_dictionary_wrong = 32;
+const ZSTD_error_dictionaryCreation_failed = 34;
+const ZSTD_error_parameter_unsupported = 40;
+const ZSTD_error_parameter_combination_unsupported = 41;
+const ZSTD_error_parameter_outOfBound = 42;
+const ZSTD_error_tableLog_tooLarge = 44;
+const ZSTD_error_maxSymbolValue_tooLarge = 46;
+const ZSTD_error_maxSymbolValue_tooSmall = 48;
+const ZSTD_error_stabilityCondition_notRespected = 50;
+const ZSTD_error_stage_wrong = 60;
+const ZSTD_error_init_missing = 62;
+const ZSTD_error_memory_allocation = 64;
+const ZSTD_error_workSpace_tooSmall = 66;
+const ZSTD_error_dstSize_tooSmall = 70;
+const ZSTD_error_srcSize_wrong = 72;
+const ZSTD_error_dstBuffer_null = 74;
+const ZSTD_error_noForwardProgress_destFull = 80;
+const ZSTD_error_noForwardProgress_inputEmpty = 82;
+
 const ZLIB_VERNUM = 4816;
 
 export const constants = Object.freeze({
@@ -199,6 +273,8 @@ export const constants = Object.freeze({
   UNZIP,
   BROTLI_DECODE,
   BROTLI_ENCODE,
+  ZSTD_COMPRESS,
+  ZSTD_DECOMPRESS,
   BROTLI_OPERATION_PROCESS,
   BROTLI_OPERATION_FLUSH,
   BROTLI_OPERATION_FINISH,
@@ -258,6 +334,67 @@ export const constants = Object.freeze({
   BROTLI_DECODER_ERROR_ALLOC_RING_BUFFER_2,
   BROTLI_DECODER_ERROR_ALLOC_BLOCK_TYPE_TREES,
   BROTLI_DECODER_ERROR_UNREACHABLE,
+  ZSTD_e_continue,
+  ZSTD_e_flush,
+  ZSTD_e_end,
+  ZSTD_fast,
+  ZSTD_dfast,
+  ZSTD_greedy,
+  ZSTD_lazy,
+  ZSTD_lazy2,
+  ZSTD_btlazy2,
+  ZSTD_btopt,
+  ZSTD_btultra,
+  ZSTD_btultra2,
+  ZSTD_c_compressionLevel,
+  ZSTD_c_windowLog,
+  ZSTD_c_hashLog,
+  ZSTD_c_chainLog,
+  ZSTD_c_searchLog,
+  ZSTD_c_minMatch,
+  ZSTD_c_targetLength,
+  ZSTD_c_strategy,
+  ZSTD_c_enableLongDistanceMatching,
+  ZSTD_c_ldmHashLog,
+  ZSTD_c_ldmMinMatch,
+  ZSTD_c_ldmBucketSizeLog,
+  ZSTD_c_ldmHashRateLog,
+  ZSTD_c_contentSizeFlag,
+  ZSTD_c_checksumFlag,
+  ZSTD_c_dictIDFlag,
+  ZSTD_c_nbWorkers,
+  ZSTD_c_jobSize,
+  ZSTD_c_overlapLog,
+  ZSTD_d_windowLogMax,
+  ZSTD_CLEVEL_DEFAULT,
+  ZSTD_error_no_error,
+  ZSTD_error_GENERIC,
+  ZSTD_error_prefix_unknown,
+  ZSTD_error_version_unsupported,
+  ZSTD_error_frameParameter_unsupported,
+  ZSTD_error_frameParameter_windowTooLarge,
+  ZSTD_error_corruption_detected,
+  ZSTD_error_checksum_wrong,
+  ZSTD_error_literals_headerWrong,
+  ZSTD_error_dictionary_corrupted,
+  ZSTD_error_dictionary_wrong,
+  ZSTD_error_dictionaryCreation_failed,
+  ZSTD_error_parameter_unsupported,
+  ZSTD_error_parameter_combination_unsupported,
+  ZSTD_error_parameter_outOfBound,
+  ZSTD_error_tableLog_tooLarge,
+  ZSTD_error_maxSymbolValue_tooLarge,
+  ZSTD_error_maxSymbolValue_tooSmall,
+  ZSTD_error_stabilityCondition_notRespected,
+  ZSTD_error_stage_wrong,
+  ZSTD_error_init_missing,
+  ZSTD_error_memory_allocation,
+  ZSTD_error_workSpace_tooSmall,
+  ZSTD_error_dstSize_tooSmall,
+  ZSTD_error_srcSize_wrong,
+  ZSTD_error_dstBuffer_null,
+  ZSTD_error_noForwardProgress_destFull,
+  ZSTD_error_noForwardProgress_inputEmpty,
 });
 
 export const codes = Object.freeze({
@@ -505,6 +642,131 @@ function brotliParamsToJson(params) {
   return JSON.stringify(obj);
 }
 
+// ===== Validation for zstd options =====
+
+const ZSTD_COMPRESS_PARAMS = new Set([
+  ZSTD_c_compressionLevel, ZSTD_c_windowLog, ZSTD_c_hashLog, ZSTD_c_chainLog, ZSTD_c_searchLog,
+  ZSTD_c_minMatch, ZSTD_c_targetLength, ZSTD_c_strategy, ZSTD_c_enableLongDistanceMatching,
+  ZSTD_c_ldmHashLog, ZSTD_c_ldmMinMatch, ZSTD_c_ldmBucketSizeLog, ZSTD_c_ldmHashRateLog,
+  ZSTD_c_contentSizeFlag, ZSTD_c_checksumFlag, ZSTD_c_dictIDFlag, ZSTD_c_nbWorkers,
+  ZSTD_c_jobSize, ZSTD_c_overlapLog,
+]);
+const ZSTD_DECOMPRESS_PARAMS = new Set([ZSTD_d_windowLogMax]);
+
+// Bounds of the zstd library (ZSTD_minCLevel() / ZSTD_maxCLevel() and window log limits)
+const ZSTD_MIN_CLEVEL = -(1 << 17);
+const ZSTD_MAX_CLEVEL = 22;
+const ZSTD_WINDOWLOG_MIN = 10;
+const ZSTD_WINDOWLOG_MAX = 31;
+
+function validateZstdOptions(opts, mode) {
+  opts = opts || {};
+  const flush = opts.flush !== undefined ? opts.flush : ZSTD_e_continue;
+  const finishFlush = opts.finishFlush !== undefined ? opts.finishFlush : ZSTD_e_end;
+  const chunkSize = opts.chunkSize !== undefined ? opts.chunkSize : Z_DEFAULT_CHUNK;
+  const params = opts.params || {};
+  const info = !!opts.info;
+  const maxOutputLength = opts.maxOutputLength;
+
+  if (opts.flush !== undefined) {
+    validateRangeInt(opts.flush, 'options.flush', ZSTD_e_continue, ZSTD_e_end);
+  }
+  if (opts.finishFlush !== undefined) {
+    validateRangeInt(opts.finishFlush, 'options.finishFlush', ZSTD_e_continue, ZSTD_e_end);
+  }
+  if (opts.pledgedSrcSize !== undefined) {
+    validateNumber(opts.pledgedSrcSize, 'options.pledgedSrcSize');
+  }
+
+  const validParamKeys = mode === ZSTD_COMPRESS ? ZSTD_COMPRESS_PARAMS : ZSTD_DECOMPRESS_PARAMS;
+  for (const key of Object.keys(params)) {
+    const numKey = Number(key);
+    if (!validParamKeys.has(numKey) || key !== String(numKey)) {
+      throw makeRangeError('ERR_ZSTD_INVALID_PARAM',
+        `${key} is not a valid zstd parameter`);
+    }
+    const val = params[key];
+    if (typeof val !== 'number' && typeof val !== 'boolean') {
+      throw makeTypeError('ERR_INVALID_ARG_TYPE',
+        `The "${key}" property must be of type number. Received type ${typeof val} (${JSON.stringify(val)})`);
+    }
+  }
+
+  return { flush, finishFlush, chunkSize, params, info, maxOutputLength };
+}
+
+function validateZstdParams(params, mode) {
+  // Mirrors the bounds ZSTD_CCtx_setParameter / ZSTD_DCtx_setParameter enforce
+  const inRange = (key, min, max) => {
+    if (params[key] === undefined) return true;
+    const v = Number(params[key]);
+    return v === 0 || (v >= min && v <= max);
+  };
+  if (mode === ZSTD_DECOMPRESS) {
+    return inRange(ZSTD_d_windowLogMax, ZSTD_WINDOWLOG_MIN, ZSTD_WINDOWLOG_MAX);
+  }
+  return inRange(ZSTD_c_compressionLevel, ZSTD_MIN_CLEVEL, ZSTD_MAX_CLEVEL) &&
+    inRange(ZSTD_c_windowLog, ZSTD_WINDOWLOG_MIN, ZSTD_WINDOWLOG_MAX) &&
+    inRange(ZSTD_c_strategy, ZSTD_fast, ZSTD_btultra2) &&
+    inRange(ZSTD_c_checksumFlag, 0, 1) &&
+    inRange(ZSTD_c_contentSizeFlag, 0, 1) &&
+    inRange(ZSTD_c_dictIDFlag, 0, 1);
+}
+
+// ruzstd implements a single matcher comparable to level 1 with the ZSTD_fast strategy and a
+// 128 KiB window (window log 17). Parameters asking for more than that are rejected instead of
+// being silently ignored; the frame flags are applied to the produced frames.
+const ZSTD_MATCHER_WINDOW_LOG = 17;
+
+// Returns the first compression parameter that cannot be honored, or undefined
+function unsupportedZstdParam(params) {
+  const isSupported = (key, v) => {
+    switch (key) {
+      case ZSTD_c_compressionLevel:
+        return v <= ZSTD_CLEVEL_DEFAULT;
+      case ZSTD_c_windowLog:
+        return v === 0 || v >= ZSTD_MATCHER_WINDOW_LOG;
+      case ZSTD_c_strategy:
+        return v === 0 || v === ZSTD_fast;
+      case ZSTD_c_contentSizeFlag:
+      case ZSTD_c_checksumFlag:
+      case ZSTD_c_dictIDFlag:
+      case ZSTD_c_overlapLog:
+        // dictionaries are not supported, and the overlap only applies to worker threads
+        return true;
+      default:
+        // matcher tuning, long distance matching and worker threads
+        return v === 0;
+    }
+  };
+  for (const key of Object.keys(params)) {
+    if (!isSupported(Number(key), Number(params[key]))) return key;
+  }
+  return undefined;
+}
+
+function validateZstdCompressParams(params) {
+  if (!validateZstdParams(params, ZSTD_COMPRESS)) {
+    throw makeError('ERR_ZLIB_INITIALIZATION_FAILED', 'Initialization failed');
+  }
+  const key = unsupportedZstdParam(params);
+  if (key !== undefined) {
+    throw makeError('ERR_ZLIB_INITIALIZATION_FAILED',
+      `Initialization failed: zstd parameter ${key} = ${Number(params[key])} is not supported`);
+  }
+}
+
+function zstdParamsToJson(params, mode) {
+  const obj = {};
+  if (mode === ZSTD_COMPRESS) {
+    if (params[ZSTD_c_checksumFlag] !== undefined) obj.checksumFlag = Number(params[ZSTD_c_checksumFlag]);
+    if (params[ZSTD_c_contentSizeFlag] !== undefined) obj.contentSizeFlag = Number(params[ZSTD_c_contentSizeFlag]);
+  } else if (params[ZSTD_d_windowLogMax] !== undefined) {
+    obj.windowLogMax = Number(params[ZSTD_d_windowLogMax]);
+  }
+  return JSON.stringify(obj);
+}
+
 // ===== Zlib stream base class =====
 
 class ZlibBase extends Transform {
@@ -514,6 +776,7 @@ class ZlibBase extends Transform {
     this._opts = opts || {};
     this._handle = null;
     this._isBrotli = false;
+    this._isZstd = false;
     this._closed = false;
     this._bytesWritten = 0;
     this._finishFlush = Z_FINISH;
@@ -546,6 +809,8 @@ class ZlibBase extends Transform {
     if (this._handle !== null) {
       if (this._isBrotli) {
         brotli_stream_close(this._handle);
+      } else if (this._isZstd) {
+        zstd_stream_close(this._handle);
       } else {
         zlib_stream_close(this._handle);
       }
@@ -554,7 +819,7 @@ class ZlibBase extends Transform {
   }
 
   reset() {
-    if (this._handle !== null && !this._isBrotli) {
+    if (this._handle !== null && !this._isBrotli && !this._isZstd) {
       zlib_stream_reset(this._handle);
       this._bytesWritten = 0;
     }
@@ -566,7 +831,7 @@ class ZlibBase extends Transform {
       kind = undefined;
     }
     if (kind === undefined) {
-      kind = this._isBrotli ? BROTLI_OPERATION_FLUSH : Z_FULL_FLUSH;
+      kind = this._isBrotli ? BROTLI_OPERATION_FLUSH : this._isZstd ? ZSTD_e_flush : Z_FULL_FLUSH;
     }
     if (this._closed) {
       if (callback) setTimeout(callback, 0);
@@ -636,6 +901,13 @@ class ZlibBase extends Transform {
         return result ? Buffer.from(result) : Buffer.alloc(0);
       }
       return Buffer.alloc(0);
+    } else if (this._isZstd) {
+      const finish = flushFlag === ZSTD_e_end || flushFlag === Z_FINISH;
+      const result = zstd_stream_push(this._handle, data, finish ? ZSTD_e_end : ZSTD_e_continue);
+      if (result == null) {
+        throw makeError('ZSTD_error_corruption_detected', 'Zstd decompression failed');
+      }
+      return Buffer.from(result);
     } else {
       const result = zlib_stream_push(this._handle, data, flushFlag);
       return result ? Buffer.from(result) : Buffer.alloc(0);
@@ -673,12 +945,16 @@ class ZlibBase extends Transform {
      }
      if (this._isBrotli) {
        result = brotli_stream_push(this._handle, data, flush);
+     } else if (this._isZstd) {
+       result = zstd_stream_push(this._handle, data, flush);
      } else {
        result = zlib_stream_push(this._handle, data, flush || Z_NO_FLUSH);
      }
      if (result == null) {
        this._closeHandle();
-       callback(makeError('ERR_ZLIB_INITIALIZATION_FAILED', 'zlib error'));
+       callback(this._mode === ZSTD_DECOMPRESS
+         ? makeError('ZSTD_error_corruption_detected', 'Zstd decompression failed')
+         : makeError('ERR_ZLIB_INITIALIZATION_FAILED', 'zlib error'));
        return;
      }
      if (result.length > 0) {
@@ -700,6 +976,8 @@ class ZlibBase extends Transform {
       let result;
       if (this._isBrotli) {
         result = brotli_stream_push(this._handle, new Uint8Array(0), 2);
+      } else if (this._isZstd) {
+        result = zstd_stream_push(this._handle, new Uint8Array(0), ZSTD_e_end);
       } else {
         result = zlib_stream_push(this._handle, new Uint8Array(0), this._finishFlush);
       }
@@ -936,6 +1214,58 @@ class _BrotliDecompress extends ZlibBase {
   }
 }
 
+// ===== Zstd classes =====
+
+class _ZstdCompress extends ZlibBase {
+  constructor(opts) {
+    const validated = validateZstdOptions(opts, ZSTD_COMPRESS);
+    super(opts, ZSTD_COMPRESS);
+    this._isZstd = true;
+    this._zstdParams = validated.params;
+    this._finishFlush = validated.finishFlush;
+    this._flushFlag = validated.flush;
+    validateZstdCompressParams(this._zstdParams);
+  }
+  _initHandle() {
+    if (this._handle !== null) return;
+    this._handle = zstd_stream_new(0, zstdParamsToJson(this._zstdParams, ZSTD_COMPRESS));
+    assertHandle(this._handle);
+  }
+}
+
+class _ZstdDecompress extends ZlibBase {
+  constructor(opts) {
+    const validated = validateZstdOptions(opts, ZSTD_DECOMPRESS);
+    super(opts, ZSTD_DECOMPRESS);
+    this._isZstd = true;
+    this._zstdParams = validated.params;
+    this._finishFlush = validated.finishFlush;
+    this._flushFlag = validated.flush;
+    if (!validateZstdParams(this._zstdParams, ZSTD_DECOMPRESS)) {
+      throw makeError('ERR_ZLIB_INITIALIZATION_FAILED', 'Initialization failed');
+    }
+  }
+  _initHandle() {
+    if (this._handle !== null) return;
+    this._handle = zstd_stream_new(1, zstdParamsToJson(this._zstdParams, ZSTD_DECOMPRESS));
+    assertHandle(this._handle);
+  }
+  _flush(callback) {
+    if (this._handle === null) {
+      callback();
+      return;
+    }
+    // Blocks are decoded as soon as they arrive, so ending only checks for a truncated frame
+    const result = zstd_stream_push(this._handle, new Uint8Array(0), ZSTD_e_end);
+    this._closeHandle();
+    if (result == null) {
+      callback(makeError('ZSTD_error_corruption_detected', 'Zstd decompression failed'));
+      return;
+    }
+    callback();
+  }
+}
+
 // Wrapper function factory: creates a function that can be called with or
 // without `new`, and `instanceof` still works against the wrapper.
 // Also supports `Wrapper.call(this, opts)` for prototype inheritance patterns.
@@ -976,6 +1306,8 @@ export const InflateRaw = makeZlibWrapper(_InflateRaw);
 export const Unzip = makeZlibWrapper(_Unzip);
 export const BrotliCompress = makeZlibWrapper(_BrotliCompress);
 export const BrotliDecompress = makeZlibWrapper(_BrotliDecompress);
+export const ZstdCompress = makeZlibWrapper(_ZstdCompress);
+export const ZstdDecompress = makeZlibWrapper(_ZstdDecompress);
 
 // ===== Factory functions =====
 
@@ -988,6 +1320,8 @@ export function createInflateRaw(opts) { return new _InflateRaw(opts); }
 export function createUnzip(opts) { return new _Unzip(opts); }
 export function createBrotliCompress(opts) { return new _BrotliCompress(opts); }
 export function createBrotliDecompress(opts) { return new _BrotliDecompress(opts); }
+export function createZstdCompress(opts) { return new _ZstdCompress(opts); }
+export function createZstdDecompress(opts) { return new _ZstdDecompress(opts); }
 
 // ===== Sync convenience functions =====
 
@@ -1098,6 +1432,45 @@ export function brotliDecompressSync(data, opts) {
   return output;
 }
 
+export function zstdCompressSync(data, opts) {
+  const validated = validateZstdOptions(opts, ZSTD_COMPRESS);
+  validateZstdCompressParams(validated.params);
+  const buf = toBuffer(data);
+  const uint8 = toUint8Array(buf);
+  const result = _zstd_compress_sync(uint8, zstdParamsToJson(validated.params, ZSTD_COMPRESS));
+  if (result == null) {
+    throw makeError('ERR_ZLIB_INITIALIZATION_FAILED', 'Initialization failed');
+  }
+  const output = Buffer.from(result);
+  if (validated.info) {
+    return { buffer: output, engine: new _ZstdCompress(opts) };
+  }
+  return output;
+}
+
+export function zstdDecompressSync(data, opts) {
+  const validated = validateZstdOptions(opts, ZSTD_DECOMPRESS);
+  if (!validateZstdParams(validated.params, ZSTD_DECOMPRESS)) {
+    throw makeError('ERR_ZLIB_INITIALIZATION_FAILED', 'Initialization failed');
+  }
+  const maxLen = validated.maxOutputLength !== undefined ? validated.maxOutputLength : _getKMaxLength();
+  const buf = toBuffer(data);
+  const uint8 = toUint8Array(buf);
+  const result = _zstd_decompress_sync(uint8, zstdParamsToJson(validated.params, ZSTD_DECOMPRESS));
+  if (result == null) {
+    throw makeError('ZSTD_error_corruption_detected', 'Zstd decompression failed');
+  }
+  const output = Buffer.from(result);
+  if (output.length > maxLen) {
+    throw makeRangeError('ERR_BUFFER_TOO_LARGE',
+      `Cannot create a Buffer larger than ${maxLen} bytes`);
+  }
+  if (validated.info) {
+    return { buffer: output, engine: new _ZstdDecompress(opts) };
+  }
+  return output;
+}
+
 // ===== Async convenience functions =====
 
 function asyncConvenience(syncFn, data, opts, callback) {
@@ -1126,6 +1499,8 @@ export function inflateRaw(data, opts, callback) { asyncConvenience(inflateRawSy
 export function unzip(data, opts, callback) { asyncConvenience(unzipSync, data, opts, callback); }
 export function brotliCompress(data, opts, callback) { asyncConvenience(brotliCompressSync, data, opts, callback); }
 export function brotliDecompress(data, opts, callback) { asyncConvenience(brotliDecompressSync, data, opts, callback); }
+export function zstdCompress(data, opts, callback) { asyncConvenience(zstdCompressSync, data, opts, callback); }
+export function zstdDecompress(data, opts, callback) { asyncConvenience(zstdDecompressSync, data, opts, callback); }
 
 // ===== CRC32 =====
 
@@ -1165,6 +1540,8 @@ const _default = {
   Unzip,
   BrotliCompress,
   BrotliDecompress,
+  ZstdCompress,
+  ZstdDecompress,
 
   // Factory functions
   createGzip,
@@ -1176,6 +1553,8 @@ const _default = {
   createUnzip,
   createBrotliCompress,
   createBrotliDecompress,
+  createZstdCompress,
+  createZstdDecompress,
 
   // Sync
   gzipSync,
@@ -1187,6 +1566,8 @@ const _default = {
   unzipSync,
   brotliCompressSync,
   brotliDecompressSync,
+  zstdCompressSync,
+  zstdDecompressSync,
 
   // Async
   gzip,
@@ -1198,6 +1579,8 @@ const _default = {
   unzip,
   brotliCompress,
   brotliDecompress,
+  zstdCompress,
+  zstdDecompress,
 
   // Constants (directly accessible like Node.js)
   Z_NO_FLUSH,
@@ -1249,6 +1632,11 @@ const _default = {
   BROTLI_OPERATION_FLUSH,
   BROTLI_OPERATION_FINISH,
   BROTLI_OPERATION_EMIT_METADATA,
+  ZSTD_COMPRESS,
+  ZSTD_DECOMPRESS,
+  ZSTD_e_continue,
+  ZSTD_e_flush,
+  ZSTD_e_end,
   ZLIB_VERNUM,
 };
 
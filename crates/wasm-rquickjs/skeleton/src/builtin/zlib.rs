@@ -161,7 +161,7 @@ fn brotli_decompress_sync_impl(data: &[u8]) -> Option<Vec<u8>> {
     Some(output)
 }
 
-#[cfg(feature = "brotli")]
+#[cfg(any(feature = "brotli", feature = "zstd"))]
 fn parse_json_i32(params_json: &str, key: &str, default: i32) -> i32 {
     if let Some(pos) = params_json.find(key) {
         let rest = &params_json[pos + key.len()..];
@@ -235,6 +235,8 @@ enum ZlibStreamKind {
     Decompress {
         inner: Decompress,
         zlib_header: bool,
+        /// Whether the end of the deflate stream has been reached
+        ended: bool,
     },
     GzipCompress {
         encoder: flate2::write::GzEncoder<Vec<u8>>,
@@ -371,6 +373,7 @@ fn zlib_stream_new_impl(
         1 => ZlibStreamKind::Decompress {
             inner: Decompress::new(true),
             zlib_header: true,
+            ended: false,
         },
         // 2: gzip compress — streaming with flush support
         2 => ZlibStreamKind::GzipCompress {
@@ -392,6 +395,7 @@ fn zlib_stream_new_impl(
         5 => ZlibStreamKind::Decompress {
             inner: Decompress::new(false),
             zlib_header: false,
+            ended: false,
         },
         // 6: unzip (auto-detect gzip/zlib) — streaming decompression
         6 => ZlibStreamKind::GzipDecompress {
@@ -538,11 +542,12 @@ fn zlib_stream_push_impl(id: u32, data: &[u8], flush: i32) -> Option<Vec<u8>> {
                 Some(Vec::new())
             }
         }
-        ZlibStreamKind::Decompress { inner, .. } => {
+        ZlibStreamKind::Decompress { inner, ended, .. } => {
             let mut output = vec![0u8; data.len() + 1024];
             let flush_mode = map_flush_decompress(flush);
             let result = decompress_loop(inner, data, &mut output, flush_mode)?;
             output.truncate(result.output_len);
+            *ended |= result.stream_end;
             Some(output)
         }
         ZlibStreamKind::GzipCompress { encoder, .. } => {
@@ -721,9 +726,12 @@ fn zlib_stream_reset_impl(id: u32) -> bool {
                 buffer.clear();
             }
             ZlibStreamKind::Decompress {
-                inner, zlib_header, ..
+                inner,
+                zlib_header,
+                ended,
             } => {
                 *inner = Decompress::new(*zlib_header);
+                *ended = false;
             }
             ZlibStreamKind::GzipCompress { encoder, level } => {
                 *encoder = flate2::write::GzEncoder::new(Vec::new(), *level);
@@ -823,6 +831,30 @@ fn zlib_stream_bytes_written_impl(id: u32) -> u32 {
         .unwrap_or(0)
 }
 
+/// Whether a decompression stream has reached the end of its compressed data
+fn zlib_stream_ended_impl(id: u32) -> bool {
+    let streams = ZLIB_STREAMS.lock().unwrap();
+    let Some(stream) = streams.get(&id) else {
+        return false;
+    };
+    match &stream.kind {
+        ZlibStreamKind::Decompress { ended, .. } => *ended,
+        ZlibStreamKind::GzipDecompress {
+            header_buf,
+            header_parsed,
+            trailer_remaining,
+            ..
+        } => {
+            // A member's trailer has been fully consumed and no next member has started
+            stream.bytes_written > 0
+                && !*header_parsed
+                && header_buf.is_empty()
+                && *trailer_remaining == 0
+        }
+        _ => false,
+    }
+}
+
 // ===== Brotli streaming =====
 // Brotli streaming buffers all data and compresses/decompresses on finish.
 
@@ -995,6 +1027,533 @@ fn brotli_stream_bytes_written_impl(_id: u32) -> u32 {
     0
 }
 
+// ===== Zstandard =====
+// ruzstd only implements a matcher comparable to level 1, so zlib.js rejects the compression
+// parameters asking for more than that. The checksum and content size flags are applied by
+// rewriting the header of the produced frames. Streaming compression emits a complete frame on
+// every flush, and decoders treat concatenated frames as a single stream.
+
+/// Buffered input after which a streaming compressor emits a frame without being flushed
+#[cfg(feature = "zstd")]
+const ZSTD_MAX_BUFFERED_INPUT: usize = 1024 * 1024;
+
+/// Default `ZSTD_d_windowLogMax`, matching `ZSTD_WINDOWLOG_LIMIT_DEFAULT`
+#[cfg(feature = "zstd")]
+const ZSTD_DEFAULT_WINDOW_LOG_MAX: i32 = 27;
+
+/// The frame header flags selected by `ZSTD_c_checksumFlag` and `ZSTD_c_contentSizeFlag`
+#[cfg(feature = "zstd")]
+#[derive(Clone, Copy)]
+struct ZstdFrameFlags {
+    checksum: bool,
+    content_size: bool,
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdFrameFlags {
+    fn parse(params_json: &str) -> Self {
+        Self {
+            checksum: parse_json_i32(params_json, "\"checksumFlag\"", 0) != 0,
+            content_size: parse_json_i32(params_json, "\"contentSizeFlag\"", 1) != 0,
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_compress_frame(data: &[u8], flags: ZstdFrameFlags) -> Vec<u8> {
+    let frame =
+        ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest);
+    zstd_apply_frame_flags(frame, data.len() as u64, flags)
+}
+
+/// Rewrites the header of a frame produced by ruzstd, which always has a window descriptor, a
+/// content checksum and no content size
+#[cfg(feature = "zstd")]
+fn zstd_apply_frame_flags(mut frame: Vec<u8>, content_size: u64, flags: ZstdFrameFlags) -> Vec<u8> {
+    const FHD: usize = 4;
+    const CHECKSUM_FLAG: u8 = 0b100;
+
+    if !flags.checksum && frame[FHD] & CHECKSUM_FLAG != 0 {
+        frame[FHD] &= !CHECKSUM_FLAG;
+        frame.truncate(frame.len() - 4);
+    }
+    if flags.content_size {
+        // Frame_Content_Size_Flag 1..=3 selects a 2, 4 or 8 byte field after the window
+        // descriptor; the 2 byte form stores the size minus 256
+        let (size_flag, field) = match content_size {
+            256..=65791 => (1u8, (content_size - 256).to_le_bytes()[..2].to_vec()),
+            0..=0xFFFF_FFFF => (2u8, (content_size as u32).to_le_bytes().to_vec()),
+            _ => (3u8, content_size.to_le_bytes().to_vec()),
+        };
+        frame[FHD] |= size_flag << 6;
+        frame.splice(FHD + 2..FHD + 2, field);
+    }
+    frame
+}
+
+#[cfg(feature = "zstd")]
+fn parse_zstd_max_window_size(params_json: &str) -> u64 {
+    let window_log_max = parse_json_i32(params_json, "\"windowLogMax\"", 0);
+    let window_log_max = if window_log_max <= 0 {
+        ZSTD_DEFAULT_WINDOW_LOG_MAX
+    } else {
+        window_log_max.min(31)
+    };
+    1u64 << window_log_max
+}
+
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: u32 = 0xFD2F_B528;
+#[cfg(feature = "zstd")]
+const ZSTD_SKIPPABLE_MAGIC_MASK: u32 = 0xFFFF_FFF0;
+#[cfg(feature = "zstd")]
+const ZSTD_SKIPPABLE_MAGIC: u32 = 0x184D_2A50;
+
+/// Incrementally decodes a sequence of zstd frames. Input is buffered only until the frame
+/// header or block it completes is available, skippable frames are dropped as they arrive and
+/// content checksums are verified at the end of each frame.
+#[cfg(feature = "zstd")]
+struct ZstdDecoder {
+    decoder: ruzstd::decoding::FrameDecoder,
+    input: Vec<u8>,
+    state: ZstdDecoderState,
+}
+
+#[cfg(feature = "zstd")]
+enum ZstdDecoderState {
+    BetweenFrames,
+    Skipping { remaining: usize },
+    InFrame { checksum: bool },
+}
+
+#[cfg(feature = "zstd")]
+impl ZstdDecoder {
+    fn new(max_window_size: u64) -> Self {
+        let mut decoder = ruzstd::decoding::FrameDecoder::new();
+        decoder.set_max_window_size(max_window_size);
+        Self {
+            decoder,
+            input: Vec::new(),
+            state: ZstdDecoderState::BetweenFrames,
+        }
+    }
+
+    /// Decodes everything `data` completes, appending the output. Returns `None` on corrupt input
+    fn push(&mut self, data: &[u8], output: &mut Vec<u8>) -> Option<()> {
+        use ruzstd::decoding::BlockDecodingStrategy;
+
+        self.input.extend_from_slice(data);
+        let mut pos = 0;
+        loop {
+            let input = &self.input[pos..];
+            match self.state {
+                ZstdDecoderState::BetweenFrames => {
+                    let Some(magic) = input.get(..4) else { break };
+                    let magic = u32::from_le_bytes(magic.try_into().unwrap());
+                    if magic & ZSTD_SKIPPABLE_MAGIC_MASK == ZSTD_SKIPPABLE_MAGIC {
+                        let Some(length) = input.get(4..8) else { break };
+                        pos += 8;
+                        self.state = ZstdDecoderState::Skipping {
+                            remaining: u32::from_le_bytes(length.try_into().unwrap()) as usize,
+                        };
+                        continue;
+                    }
+                    if magic != ZSTD_MAGIC {
+                        return None;
+                    }
+                    let Some(&descriptor) = input.get(4) else {
+                        break;
+                    };
+                    let header_length = zstd_frame_header_length(descriptor);
+                    let Some(mut header) = input.get(..header_length) else {
+                        break;
+                    };
+                    self.decoder.reset(&mut header).ok()?;
+                    pos += header_length;
+                    self.state = ZstdDecoderState::InFrame {
+                        checksum: descriptor & 0b100 != 0,
+                    };
+                }
+                ZstdDecoderState::Skipping { remaining } => {
+                    let skipped = remaining.min(input.len());
+                    pos += skipped;
+                    if skipped < remaining {
+                        self.state = ZstdDecoderState::Skipping {
+                            remaining: remaining - skipped,
+                        };
+                        break;
+                    }
+                    self.state = ZstdDecoderState::BetweenFrames;
+                }
+                ZstdDecoderState::InFrame { checksum } => {
+                    let Some(header) = input.get(..3) else { break };
+                    let header = u32::from_le_bytes([header[0], header[1], header[2], 0]);
+                    let last_block = header & 1 != 0;
+                    let block_size = (header >> 3) as usize;
+                    // Raw and compressed blocks carry `block_size` bytes, RLE blocks a single one
+                    let content_length = match (header >> 1) & 0b11 {
+                        0 | 2 => block_size,
+                        1 => 1,
+                        _ => return None,
+                    };
+                    let block_length =
+                        3 + content_length + if last_block && checksum { 4 } else { 0 };
+                    let Some(mut block) = input.get(..block_length) else {
+                        break;
+                    };
+                    self.decoder
+                        .decode_blocks(&mut block, BlockDecodingStrategy::UptoBlocks(1))
+                        .ok()?;
+                    self.decoder.collect_to_writer(&mut *output).ok()?;
+                    pos += block_length;
+                    if last_block {
+                        if let (Some(expected), Some(actual)) = (
+                            self.decoder.get_checksum_from_data(),
+                            self.decoder.get_calculated_checksum(),
+                        ) && expected != actual
+                        {
+                            return None;
+                        }
+                        self.state = ZstdDecoderState::BetweenFrames;
+                    }
+                }
+            }
+        }
+        self.input.drain(..pos);
+        Some(())
+    }
+
+    /// Checks that the input did not end in the middle of a frame
+    fn finish(&self) -> Option<()> {
+        match self.state {
+            ZstdDecoderState::BetweenFrames if self.input.is_empty() => Some(()),
+            _ => None,
+        }
+    }
+}
+
+/// Length of a frame header, from its magic number up to the first block
+#[cfg(feature = "zstd")]
+fn zstd_frame_header_length(descriptor: u8) -> usize {
+    let single_segment = descriptor & 0b10_0000 != 0;
+    let window_descriptor = if single_segment { 0 } else { 1 };
+    let dictionary_id = [0, 1, 2, 4][(descriptor & 0b11) as usize];
+    let content_size = match descriptor >> 6 {
+        0 if single_segment => 1,
+        0 => 0,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+    5 + window_descriptor + dictionary_id + content_size
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_compress_sync_impl(data: &[u8], params_json: &str) -> Option<Vec<u8>> {
+    Some(zstd_compress_frame(
+        data,
+        ZstdFrameFlags::parse(params_json),
+    ))
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_decompress_sync_impl(data: &[u8], params_json: &str) -> Option<Vec<u8>> {
+    let mut decoder = ZstdDecoder::new(parse_zstd_max_window_size(params_json));
+    let mut output = Vec::new();
+    decoder.push(data, &mut output)?;
+    decoder.finish()?;
+    Some(output)
+}
+
+#[cfg(feature = "zstd")]
+struct ZstdStream {
+    kind: ZstdStreamKind,
+    bytes_written: u32,
+}
+
+#[cfg(feature = "zstd")]
+enum ZstdStreamKind {
+    Compress {
+        buffer: Vec<u8>,
+        frames_emitted: bool,
+        flags: ZstdFrameFlags,
+    },
+    Decompress(Box<ZstdDecoder>),
+}
+
+#[cfg(feature = "zstd")]
+static ZSTD_STREAMS: LazyLock<Mutex<HashMap<u32, ZstdStream>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(feature = "zstd")]
+fn zstd_stream_new_impl(mode: u8, params_json: &str) -> Option<u32> {
+    let kind = match mode {
+        0 => ZstdStreamKind::Compress {
+            buffer: Vec::new(),
+            frames_emitted: false,
+            flags: ZstdFrameFlags::parse(params_json),
+        },
+        1 => ZstdStreamKind::Decompress(Box::new(ZstdDecoder::new(parse_zstd_max_window_size(
+            params_json,
+        )))),
+        _ => return None,
+    };
+
+    let id = next_id();
+    ZSTD_STREAMS.lock().unwrap().insert(
+        id,
+        ZstdStream {
+            kind,
+            bytes_written: 0,
+        },
+    );
+    Some(id)
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_stream_push_impl(id: u32, data: &[u8], end_op: u8) -> Option<Vec<u8>> {
+    let mut streams = ZSTD_STREAMS.lock().unwrap();
+    let stream = streams.get_mut(&id)?;
+
+    stream.bytes_written += data.len() as u32;
+
+    match &mut stream.kind {
+        ZstdStreamKind::Compress {
+            buffer,
+            frames_emitted,
+            flags,
+        } => {
+            buffer.extend_from_slice(data);
+            // ZSTD_e_flush (1) and ZSTD_e_end (2) emit everything buffered so far; an ended
+            // stream always produces at least one (possibly empty) frame
+            let emit = match end_op {
+                1 => !buffer.is_empty(),
+                2 => !buffer.is_empty() || !*frames_emitted,
+                _ => buffer.len() >= ZSTD_MAX_BUFFERED_INPUT,
+            };
+            if emit {
+                *frames_emitted = true;
+                Some(zstd_compress_frame(&std::mem::take(buffer), *flags))
+            } else {
+                Some(Vec::new())
+            }
+        }
+        ZstdStreamKind::Decompress(decoder) => {
+            let mut output = Vec::new();
+            decoder.push(data, &mut output)?;
+            // ZSTD_e_end (2) marks the end of the compressed input
+            if end_op == 2 {
+                decoder.finish()?;
+            }
+            Some(output)
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_stream_close_impl(id: u32) -> bool {
+    ZSTD_STREAMS.lock().unwrap().remove(&id).is_some()
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_compress_sync_impl(_data: &[u8], _params_json: &str) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_decompress_sync_impl(_data: &[u8], _params_json: &str) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_stream_new_impl(_mode: u8, _params_json: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_stream_push_impl(_id: u32, _data: &[u8], _end_op: u8) -> Option<Vec<u8>> {
+    None
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_stream_close_impl(_id: u32) -> bool {
+    false
+}
+
+// ===== HTTP content decoding =====
+// Used by `fetch` to transparently decode `Content-Encoding` response bodies.
+
+/// The `Accept-Encoding` header value sent by `fetch` when the request does not set one
+#[cfg(all(feature = "fetch", feature = "brotli"))]
+pub const ACCEPT_ENCODING: Option<&str> = Some("gzip, deflate, br");
+#[cfg(all(feature = "fetch", not(feature = "brotli")))]
+pub const ACCEPT_ENCODING: Option<&str> = Some("gzip, deflate");
+
+#[cfg(feature = "fetch")]
+enum ContentDecoderStage {
+    Gzip(flate2::write::MultiGzDecoder<Vec<u8>>),
+    /// HTTP `deflate` is supposed to be zlib-wrapped, but some servers send raw deflate data,
+    /// so the format is decided by looking at the first two bytes of the body.
+    DeflatePending(Vec<u8>),
+    Zlib(flate2::write::ZlibDecoder<Vec<u8>>),
+    RawDeflate(flate2::write::DeflateDecoder<Vec<u8>>),
+    #[cfg(feature = "brotli")]
+    Brotli(Box<brotli::DecompressorWriter<Vec<u8>>>),
+}
+
+#[cfg(feature = "fetch")]
+impl ContentDecoderStage {
+    fn new(coding: &str) -> Option<Self> {
+        match coding {
+            "gzip" | "x-gzip" => Some(Self::Gzip(flate2::write::MultiGzDecoder::new(Vec::new()))),
+            "deflate" => Some(Self::DeflatePending(Vec::new())),
+            #[cfg(feature = "brotli")]
+            "br" => Some(Self::Brotli(Box::new(brotli::DecompressorWriter::new(
+                Vec::new(),
+                4096,
+            )))),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip(decoder) => {
+                decoder.write_all(data)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            Self::DeflatePending(buffer) => {
+                buffer.extend_from_slice(data);
+                if buffer.len() < 2 {
+                    return Ok(Vec::new());
+                }
+                let header = std::mem::take(buffer);
+                let is_zlib = header[0] & 0x0f == 8
+                    && (u16::from(header[0]) << 8 | u16::from(header[1])) % 31 == 0;
+                *self = if is_zlib {
+                    Self::Zlib(flate2::write::ZlibDecoder::new(Vec::new()))
+                } else {
+                    Self::RawDeflate(flate2::write::DeflateDecoder::new(Vec::new()))
+                };
+                self.push(&header)
+            }
+            Self::Zlib(decoder) => {
+                decoder.write_all(data)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            Self::RawDeflate(decoder) => {
+                decoder.write_all(data)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => {
+                decoder.write_all(data)?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+        }
+    }
+
+    fn finish(&mut self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Gzip(decoder) => {
+                decoder.try_finish()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            Self::DeflatePending(buffer) if buffer.is_empty() => Ok(Vec::new()),
+            Self::DeflatePending(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated deflate stream",
+            )),
+            Self::Zlib(decoder) => {
+                decoder.try_finish()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            Self::RawDeflate(decoder) => {
+                decoder.try_finish()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => {
+                decoder.close()?;
+                Ok(std::mem::take(decoder.get_mut()))
+            }
+        }
+    }
+}
+
+/// Incrementally decodes a response body according to its `Content-Encoding` header
+#[cfg(feature = "fetch")]
+pub struct ContentDecoder {
+    /// Stages in decoding order (the reverse of the order the codings were applied in)
+    stages: Vec<ContentDecoderStage>,
+    received_data: bool,
+}
+
+#[cfg(feature = "fetch")]
+impl ContentDecoder {
+    /// Creates a decoder for a (possibly comma separated) `Content-Encoding` header value.
+    ///
+    /// Returns `None` if no decoding is needed, or if any of the codings is not supported,
+    /// in which case the body is passed through as-is.
+    pub fn from_content_encoding(content_encoding: &str) -> Option<Self> {
+        let mut stages = Vec::new();
+        for coding in content_encoding.split(',') {
+            let coding = coding.trim().to_ascii_lowercase();
+            if coding.is_empty() || coding == "identity" {
+                continue;
+            }
+            stages.push(ContentDecoderStage::new(&coding)?);
+        }
+        if stages.is_empty() {
+            None
+        } else {
+            stages.reverse();
+            Some(Self {
+                stages,
+                received_data: false,
+            })
+        }
+    }
+
+    /// Decodes the next chunk of the body, returning whatever output is available so far
+    pub fn push(&mut self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.received_data = true;
+        let mut chunk = data.to_vec();
+        for stage in &mut self.stages {
+            if chunk.is_empty() {
+                break;
+            }
+            chunk = stage.push(&chunk)?;
+        }
+        Ok(chunk)
+    }
+
+    /// Signals the end of the body and returns the remaining output
+    pub fn finish(&mut self) -> std::io::Result<Vec<u8>> {
+        if !self.received_data {
+            // Empty bodies (HEAD requests, 204 and 304 responses) are not encoded at all
+            return Ok(Vec::new());
+        }
+        let mut chunk = Vec::new();
+        for stage in &mut self.stages {
+            let mut output = stage.push(&chunk)?;
+            output.extend(stage.finish()?);
+            chunk = output;
+        }
+        Ok(chunk)
+    }
+
+    /// Decodes a fully buffered body
+    pub fn decode_all(mut self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut output = self.push(data)?;
+        output.extend(self.finish()?);
+        Ok(output)
+    }
+}
+
 // ===== Native module =====
 
 #[rquickjs::module(rename = "camelCase")]
@@ -1101,6 +1660,11 @@ pub mod native_module {
         super::zlib_stream_bytes_written_impl(id)
     }
 
+    #[rquickjs::function]
+    pub fn zlib_stream_ended(id: u32) -> bool {
+        super::zlib_stream_ended_impl(id)
+    }
+
     // ===== Brotli streaming functions =====
 
     #[rquickjs::function]
@@ -1130,8 +1694,53 @@ pub mod native_module {
     pub fn brotli_stream_bytes_written(id: u32) -> u32 {
         super::brotli_stream_bytes_written_impl(id)
     }
+
+    // ===== Zstandard functions =====
+
+    #[rquickjs::function]
+    pub fn zstd_compress_sync(data: TypedArray<'_, u8>, params_json: String) -> Option<Vec<u8>> {
+        let input = data
+            .as_bytes()
+            .expect("the Uint8Array passed to zstdCompressSync is detached");
+        super::zstd_compress_sync_impl(input, &params_json)
+    }
+
+    #[rquickjs::function]
+    pub fn zstd_decompress_sync(data: TypedArray<'_, u8>, params_json: String) -> Option<Vec<u8>> {
+        let input = data
+            .as_bytes()
+            .expect("the Uint8Array passed to zstdDecompressSync is detached");
+        super::zstd_decompress_sync_impl(input, &params_json)
+    }
+
+    #[rquickjs::function]
+    pub fn zstd_stream_new(mode: u8, params_json: String) -> Option<u32> {
+        super::zstd_stream_new_impl(mode, &params_json)
+    }
+
+    #[rquickjs::function]
+    pub fn zstd_stream_push(id: u32, data: TypedArray<'_, u8>, end_op: u8) -> Option<Vec<u8>> {
+        let input = data
+            .as_bytes()
+            .expect("the Uint8Array passed to zstdStreamPush is detached");
+        super::zstd_stream_push_impl(id, input, end_op)
+    }
+
+    #[rquickjs::function]
+    pub fn zstd_stream_close(id: u32) -> bool {
+        super::zstd_stream_close_impl(id)
+    }
 }
 
 pub const ZLIB_JS: &str = include_str!("zlib.js");
 
+pub const COMPRESSION_STREAMS_JS: &str = include_str!("compression_streams.js");
+
 pub const REEXPORT_JS: &str = r#"export * from 'node:zlib'; export { default } from 'node:zlib';"#;
+
+// JS code wiring the compression streams into the global context
+pub const WIRE_JS: &str = r#"
+        import * as __wasm_rquickjs_compression_streams from '__wasm_rquickjs_builtin/compression_streams';
+        globalThis.CompressionStream = __wasm_rquickjs_compression_streams.CompressionStream;
+        globalThis.DecompressionStream = __wasm_rquickjs_compression_streams.DecompressionStream;
+    "#;
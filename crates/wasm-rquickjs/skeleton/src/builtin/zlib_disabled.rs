@@ -13,6 +13,8 @@ export const gunzipSync = notAvailable;
 export const unzipSync = notAvailable;
 export const brotliCompressSync = notAvailable;
 export const brotliDecompressSync = notAvailable;
+export const zstdCompressSync = notAvailable;
+export const zstdDecompressSync = notAvailable;
 export const createDeflate = notAvailable;
 export const createInflate = notAvailable;
 export const createDeflateRaw = notAvailable;
@@ -22,9 +24,42 @@ export const createGunzip = notAvailable;
 export const createUnzip = notAvailable;
 export const createBrotliCompress = notAvailable;
 export const createBrotliDecompress = notAvailable;
+export const createZstdCompress = notAvailable;
+export const createZstdDecompress = notAvailable;
 export const crc32 = notAvailable;
 export const constants = {};
-export default { deflateSync, inflateSync, deflateRawSync, inflateRawSync, gzipSync, gunzipSync, unzipSync, brotliCompressSync, brotliDecompressSync, createDeflate, createInflate, createDeflateRaw, createInflateRaw, createGzip, createGunzip, createUnzip, createBrotliCompress, createBrotliDecompress, crc32, constants };
+export default { deflateSync, inflateSync, deflateRawSync, inflateRawSync, gzipSync, gunzipSync, unzipSync, brotliCompressSync, brotliDecompressSync, zstdCompressSync, zstdDecompressSync, createDeflate, createInflate, createDeflateRaw, createInflateRaw, createGzip, createGunzip, createUnzip, createBrotliCompress, createBrotliDecompress, createZstdCompress, createZstdDecompress, crc32, constants };
 "#;
 
+pub const COMPRESSION_STREAMS_JS: &str = "";
+
+pub const WIRE_JS: &str = "";
+
+/// Without the zlib feature `fetch` does not ask for compressed responses
+#[cfg(feature = "fetch")]
+pub const ACCEPT_ENCODING: Option<&str> = None;
+
+/// Without the zlib feature response bodies are never decoded
+#[cfg(feature = "fetch")]
+pub enum ContentDecoder {}
+
+#[cfg(feature = "fetch")]
+impl ContentDecoder {
+    pub fn from_content_encoding(_content_encoding: &str) -> Option<Self> {
+        None
+    }
+
+    pub fn push(&mut self, _data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match *self {}
+    }
+
+    pub fn finish(&mut self) -> std::io::Result<Vec<u8>> {
+        match *self {}
+    }
+
+    pub fn decode_all(self, _data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {}
+    }
+}
+
 pub const REEXPORT_JS: &str = r#"export * from 'node:zlib'; export { default } from 'node:zlib';"#;
@@ -3,8 +3,8 @@ use futures_concurrency::future::Join;
 use rquickjs::function::{Args, Constructor};
 use rquickjs::loader::{BuiltinLoader, BuiltinResolver, FileResolver, Loader, Resolver};
 use rquickjs::{
-    AsyncContext, AsyncRuntime, CatchResultExt, Ctx, Error, Filter, FromJs, Function, Module,
-    Object, Persistent, Promise, String as JsString, Value, async_with,
+    AsyncContext, AsyncRuntime, CatchResultExt, Ctx, Error, Exception, Filter, FromJs, Function,
+    Module, Object, Persistent, Promise, String as JsString, Value, async_with,
 };
 use rquickjs::{CaughtError, prelude::*};
 use std::cell::RefCell;
@@ -1588,6 +1588,7 @@ pub struct JsState {
     pub exported_function_cache:
         RefCell<HashMap<&'static [&'static str], CachedExportedFunction>>,
     pub variant_case_tag_cache: RefCell<HashMap<&'static str, Persistent<JsString<'static>>>>,
+    pub resource_arity_cache: RefCell<HashMap<String, CachedArityCheck>>,
     pub last_resource_id: AtomicUsize,
     pub resource_drop_queue_tx: futures::channel::mpsc::UnboundedSender<usize>,
     pub resource_drop_queue_rx: RefCell<Option<futures::channel::mpsc::UnboundedReceiver<usize>>>,
@@ -1603,6 +1604,13 @@ pub struct CachedExportedFunction {
     parameter_count: usize,
 }
 
+/// Outcome of [`accepts_parameter_count`] for a resource constructor or method whose `length`
+/// differs from the WIT parameter count, valid while the class still has the same `function`
+pub struct CachedArityCheck {
+    function: Persistent<Object<'static>>,
+    accepted: bool,
+}
+
 /// Tracks which initialization phase the runtime is in.
 /// Used to support Wizer pre-initialization and guard against re-entrant
 /// `get_js_state()` calls during module evaluation (e.g. from `setTimeout`
@@ -1730,6 +1738,9 @@ impl JsState {
         )))
         .await;
 
+        rt.set_promise_hook(Some(Box::new(crate::builtin::async_hooks::promise_hook)))
+            .await;
+
         let (resource_drop_queue_tx, resource_drop_queue_rx) = futures::channel::mpsc::unbounded();
 
         let last_resource_id = AtomicUsize::new(1);
@@ -1738,6 +1749,7 @@ impl JsState {
             ctx,
             exported_function_cache: RefCell::new(HashMap::new()),
             variant_case_tag_cache: RefCell::new(HashMap::new()),
+            resource_arity_cache: RefCell::new(HashMap::new()),
             last_resource_id,
             resource_drop_queue_tx,
             resource_drop_queue_rx: RefCell::new(Some(resource_drop_queue_rx)),
@@ -1757,13 +1769,7 @@ impl JsState {
             Module::evaluate(
                 ctx.clone(),
                 "dispose",
-                format!(r#"
-                const dispose = Symbol.for("dispose");
-                globalThis.{DISPOSE_SYMBOL} = dispose;
-                Symbol.dispose = dispose;
-                const asyncDispose = Symbol.for("asyncDispose");
-                Symbol.asyncDispose = asyncDispose;
-                "#)
+                dispose_symbols_js(),
             ).catch(&ctx)
             .unwrap_or_else(|e| panic!("Failed to evaluate dispose module initialization:\n{}", format_caught_error(e)))
             .finish::<()>()
@@ -1800,6 +1806,20 @@ impl JsState {
     /// Must be called after init_engine().
     async fn init_user_module(&self) {
         async_with!(self.ctx => |ctx| {
+            if crate::RUN_TESTS_EXPORT {
+                // Top-level tests are only registered here, and run by the `run-tests` export
+                Module::evaluate(
+                    ctx.clone(),
+                    "__wasm_rquickjs_collect_tests",
+                    "import { _collectTests } from 'node:test'; _collectTests();",
+                )
+                .catch(&ctx)
+                .unwrap_or_else(|e| panic!("Failed to enable node:test test collection:\n{}", format_caught_error(e)))
+                .finish::<()>()
+                .catch(&ctx)
+                .unwrap_or_else(|e| panic!("Failed to finish enabling node:test test collection:\n{}", format_caught_error(e)));
+            }
+
             // Import the user module (now globalThis.require is available)
             Module::evaluate(
                 ctx.clone(),
@@ -1834,6 +1854,8 @@ impl JsState {
     /// init) find the already-published state instead of recursing.
     async fn finish_init(&self) {
         self.init_engine().await;
+        // Started after the builtins are wired, so the profile covers the user module evaluation
+        crate::builtin::cpu_profiler::start_environment_profile(self).await;
         self.init_user_module().await;
     }
 
@@ -1884,6 +1906,56 @@ impl JsState {
     }
 }
 
+/// Source of the module initializing the dispose symbols.
+fn dispose_symbols_js() -> String {
+    format!(
+        r#"
+        const dispose = Symbol.for("dispose");
+        globalThis.{DISPOSE_SYMBOL} = dispose;
+        Symbol.dispose = dispose;
+        const asyncDispose = Symbol.for("asyncDispose");
+        Symbol.asyncDispose = asyncDispose;
+        "#
+    )
+}
+
+/// Creates a new realm (a context with its own global object, intrinsics and module map) on
+/// the runtime `ctx` belongs to. The realm shares the heap, the module loader and the executor
+/// with all other contexts of the runtime, so values can be passed between them directly.
+///
+/// Meant to be called from native functions, where the runtime lock is already held.
+/// The realm stays alive for as long as any of its objects is reachable.
+pub fn new_realm<'js>(ctx: &Ctx<'js>) -> rquickjs::Result<Ctx<'js>> {
+    unsafe {
+        let rt = rquickjs::qjs::JS_GetRuntime(ctx.as_raw().as_ptr());
+        let raw_ctx =
+            std::ptr::NonNull::new(rquickjs::qjs::JS_NewContext(rt)).ok_or(Error::Unknown)?;
+        // Ctx::from_raw dups the context; we must free our original reference.
+        let realm = Ctx::from_raw(raw_ctx);
+        rquickjs::qjs::JS_FreeContext(raw_ctx.as_ptr());
+        Ok(realm)
+    }
+}
+
+/// Evaluates the dispose symbols and the builtin wiring in a realm created by [`new_realm`],
+/// giving it the same globals (`require`, `process`, timers, ...) as the main context. All
+/// builtin modules are instantiated anew for the realm.
+pub fn wire_realm(realm: &Ctx<'_>) -> rquickjs::Result<()> {
+    for (name, source) in [
+        ("dispose", dispose_symbols_js()),
+        (
+            "__wasm_rquickjs_init_wiring",
+            crate::builtin::wire_builtins(),
+        ),
+    ] {
+        // None of the builtins uses top-level await, so the evaluation has already settled
+        if let Some(result) = Module::evaluate(realm.clone(), name, source)?.result::<()>() {
+            result?;
+        }
+    }
+    Ok(())
+}
+
 fn abort_unrefed_timers(js_state: &JsState) {
     let unrefed = js_state.unrefed_timers.borrow().clone();
     let mut abort_handles = js_state.abort_handles.borrow_mut();
@@ -1909,9 +1981,17 @@ async fn run_pending_gc(js_state: &JsState) {
     }
 }
 
+/// Waits until all spawned tasks finished, except unref'd timers which get aborted.
+async fn drain_and_idle(js_state: &JsState) {
+    // With virtual time, unref'd timers must not advance the clock on their own while draining
+    crate::builtin::deterministic::set_draining(true);
+    drain_remaining_tasks(js_state).await;
+    crate::builtin::deterministic::set_draining(false);
+}
+
 /// Spawns a sentinel task that waits for all ref'd timers to complete,
 /// then aborts remaining unref'd timers so that `idle()` can return.
-async fn drain_and_idle(js_state: &JsState) {
+async fn drain_remaining_tasks(js_state: &JsState) {
     run_pending_gc(js_state).await;
     if js_state.unrefed_timers.borrow().is_empty() {
         js_state.rt.idle().await;
@@ -1975,6 +2055,9 @@ pub fn get_js_state() -> &'static JsState {
                 // Wizer snapshot restored — refresh argv/env from the real host.
                 let state = STATE.as_ref().unwrap();
                 block_on(JsState::refresh_process_env(state));
+                block_on(crate::builtin::cpu_profiler::start_environment_profile(
+                    state,
+                ));
                 INIT_PHASE = InitPhase::FullyInitialized;
             }
             InitPhase::Initializing | InitPhase::FullyInitialized => {
@@ -1985,10 +2068,32 @@ pub fn get_js_state() -> &'static JsState {
     }
 }
 
+/// With the `per-call-isolation` feature, makes sure the upcoming exported call starts from the
+/// state right after initialization: the first call takes a snapshot of it, the later ones restore
+/// it (unless `cabi_realloc` already did while the host was lowering the call's arguments).
+#[cfg(feature = "per-call-isolation")]
+fn isolate_exported_call() {
+    if crate::isolation::has_snapshot() {
+        crate::isolation::restore_snapshot_if_dirty();
+    } else {
+        // Makes sure the initial state exists (and a Wizer snapshot got its env refreshed), and
+        // lets the timers started by the initialization finish, so no host pollables are
+        // captured in the snapshot
+        let js_state = get_js_state();
+        block_on(drain_and_idle(js_state));
+        crate::isolation::take_snapshot();
+    }
+}
+
 pub fn async_exported_function<F: Future>(future: F) -> F::Output {
+    #[cfg(feature = "per-call-isolation")]
+    isolate_exported_call();
+
     let js_state = get_js_state();
+    crate::builtin::cpu_profiler::resume();
+    crate::builtin::perf_hooks::exported_call_started();
 
-    block_on(async move {
+    let result = block_on(async move {
         use futures::StreamExt;
 
         if let Some(mut resource_drop_queue_rx) = js_state.resource_drop_queue_rx.take() {
@@ -2018,7 +2123,15 @@ pub fn async_exported_function<F: Future>(future: F) -> F::Output {
             // This case will never happen because block_on does not allow reentry
             unreachable!()
         }
-    })
+    });
+
+    crate::builtin::cpu_profiler::suspend();
+    block_on(crate::builtin::cpu_profiler::write_environment_profile(
+        js_state,
+    ));
+    #[cfg(feature = "per-call-isolation")]
+    crate::isolation::mark_dirty();
+    result
 }
 
 pub async fn call_js_export<A, R>(
@@ -2037,6 +2150,7 @@ pub async fn call_js_export_returning_result<A, R, E>(
     wit_package: &'static str,
     function_path: &'static [&'static str],
     args: A,
+    error_catch_all_case: Option<&'static str>,
 ) -> crate::wrappers::JsResult<R, E>
 where
     A: for<'js> IntoArgs<'js>,
@@ -2049,14 +2163,112 @@ where
         args,
         |a| crate::wrappers::JsResult(Ok(a)),
         |ctx, value| {
-            FromJs::from_js(ctx, value.clone())
-                .ok()
+            map_thrown_error(ctx, value, error_catch_all_case)
                 .map(|e| crate::wrappers::JsResult(Err(e)))
         },
     )
     .await
 }
 
+/// Converts a value thrown by an exported function returning `result<_, E>` to `E`.
+///
+/// A thrown value already having the shape of `E` is used as it is. Otherwise, if the thrown
+/// value is an error object:
+/// - if `E` is `string`, the error's `message` is used
+/// - if `E` is a variant or enum, the error's `code` and then its `name` are matched against the
+///   case names, both as they are and converted to kebab-case (with and without an `Error`
+///   suffix, so `NotFoundError` matches a `not-found` case)
+/// - if nothing matched, the error is mapped to `error_catch_all_case` if there is one
+///
+/// A matched variant case's payload is taken from the error object itself if possible (so a
+/// record payload can pick up fields like `message` or `code`), otherwise from its message.
+fn map_thrown_error<'js, E: FromJs<'js>>(
+    ctx: &Ctx<'js>,
+    value: &Value<'js>,
+    error_catch_all_case: Option<&str>,
+) -> Option<E> {
+    if let Ok(error) = E::from_js(ctx, value.clone()) {
+        return Some(error);
+    }
+
+    let error = value.as_object()?;
+    let message: Option<JsString> = error.get("message").ok();
+
+    // No enum case can be named by an empty string, so this only succeeds for string-like types
+    let empty = JsString::from_str(ctx.clone(), "").ok()?;
+    if E::from_js(ctx, empty.into_value()).is_ok() {
+        return message.and_then(|message| E::from_js(ctx, message.into_value()).ok());
+    }
+
+    let mut candidates = Vec::new();
+    for key in ["code", "name"] {
+        if let Ok(Some(name)) = error.get::<_, Option<String>>(key) {
+            let kebab = to_kebab_case(&name);
+            if let Some(stripped) = kebab.strip_suffix("-error") {
+                candidates.push(stripped.to_string());
+            }
+            candidates.push(kebab);
+            candidates.push(name);
+        }
+    }
+    candidates.extend(error_catch_all_case.map(|case| case.to_string()));
+
+    candidates
+        .iter()
+        .find_map(|case| error_case_from_js(ctx, case, error, message.as_ref()))
+}
+
+/// Tries to construct an enum case or variant case named `case` of `E` from an error object.
+fn error_case_from_js<'js, E: FromJs<'js>>(
+    ctx: &Ctx<'js>,
+    case: &str,
+    error: &Object<'js>,
+    message: Option<&JsString<'js>>,
+) -> Option<E> {
+    let case_value = JsString::from_str(ctx.clone(), case).ok()?;
+    if let Ok(result) = E::from_js(ctx, case_value.clone().into_value()) {
+        return Some(result);
+    }
+
+    let payloads = [
+        Some(error.clone().into_value()),
+        message.map(|message| message.clone().into_value()),
+        None,
+    ];
+    payloads.into_iter().find_map(|payload| {
+        let obj = Object::new(ctx.clone()).ok()?;
+        obj.set(crate::wrappers::TAG, case_value.clone()).ok()?;
+        if let Some(payload) = payload {
+            obj.set(crate::wrappers::VALUE, payload).ok()?;
+        }
+        E::from_js(ctx, obj.into_value()).ok()
+    })
+}
+
+/// Converts a JS error name or code (`NotFoundError`, `HTTPError`, `E_NOT_FOUND`) to kebab-case
+fn to_kebab_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '_' || *c == '-' || c.is_whitespace() {
+            if !result.is_empty() && !result.ends_with('-') {
+                result.push('-');
+            }
+            continue;
+        }
+        if c.is_uppercase() && i > 0 && !result.is_empty() && !result.ends_with('-') {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower)
+            {
+                result.push('-');
+            }
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
+
 async fn call_js_export_internal<A, R, FR, TME>(
     wit_package: &'static str,
     function_path: &'static [&'static str],
@@ -2195,7 +2407,7 @@ fn get_cached_js_export<'js>(
                 function_path.join(".")
             )
         });
-    if parameter_count != expected_parameter_count {
+    if !accepts_parameter_count(&user_function_obj, parameter_count, expected_parameter_count) {
         panic!(
             "The WIT specification defines {} parameters,\nbut the exported JavaScript function got {} parameters (exported function {} in WIT package {})",
             expected_parameter_count,
@@ -2210,13 +2422,169 @@ fn get_cached_js_export<'js>(
         CachedExportedFunction {
             function: Persistent::save(ctx, user_function.clone()),
             parent: Persistent::save(ctx, parent.clone()),
-            parameter_count,
+            // The function got accepted for the WIT parameter count, even if its `length` differs
+            parameter_count: expected_parameter_count,
         },
     );
 
     (user_function, parent)
 }
 
+/// Checks whether an exported JS function (or class) declaring `parameter_count` parameters in
+/// its `length` can implement a WIT function with `expected` parameters.
+///
+/// `length` does not count default and rest parameters, so with the `relaxed-arity` feature a
+/// smaller `length` is accepted when the function's source declares such parameters.
+fn accepts_parameter_count(function: &Object<'_>, parameter_count: usize, expected: usize) -> bool {
+    if parameter_count == expected {
+        return true;
+    }
+
+    #[cfg(feature = "relaxed-arity")]
+    {
+        parameter_count < expected && has_optional_parameters(function)
+    }
+    #[cfg(not(feature = "relaxed-arity"))]
+    {
+        let _ = function;
+        false
+    }
+}
+
+/// [`accepts_parameter_count`] for the constructor or a method of an exported resource class,
+/// remembering the decision for `member` of `resource_path` so that the source of the function is
+/// only scanned once
+fn accepts_resource_parameter_count<'js>(
+    ctx: &Ctx<'js>,
+    resource_path: &[&str],
+    member: &str,
+    function: &Object<'js>,
+    parameter_count: usize,
+    expected: usize,
+) -> bool {
+    if parameter_count == expected {
+        return true;
+    }
+
+    let js_state = get_js_state();
+    let key = format!("{}#{member}", resource_path.join("."));
+    let cached = js_state
+        .resource_arity_cache
+        .borrow()
+        .get(&key)
+        .and_then(|cached| {
+            let cached_function = cached.function.clone().restore(ctx).ok()?;
+            (cached_function == *function).then_some(cached.accepted)
+        });
+    if let Some(accepted) = cached {
+        return accepted;
+    }
+
+    let accepted = accepts_parameter_count(function, parameter_count, expected);
+    js_state.resource_arity_cache.borrow_mut().insert(
+        key,
+        CachedArityCheck {
+            function: Persistent::save(ctx, function.clone()),
+            accepted,
+        },
+    );
+    accepted
+}
+
+/// Whether a JS function or class has default or rest parameters, based on its source
+#[cfg(feature = "relaxed-arity")]
+fn has_optional_parameters(function: &Object<'_>) -> bool {
+    let ctx = function.ctx();
+    let source = ctx
+        .globals()
+        .get::<_, Object>("Function")
+        .and_then(|constructor| constructor.get::<_, Object>("prototype"))
+        .and_then(|prototype| prototype.get::<_, Function>("toString"))
+        .and_then(|to_string| to_string.call::<_, String>((This(function.clone()),)));
+    match source {
+        Ok(source) => source_has_optional_parameters(&source),
+        Err(_) => false,
+    }
+}
+
+/// Scans the parameter list of a JS function's or class constructor's source for default values
+/// (`b = 1`) and rest parameters (`...args`) at the top level.
+#[cfg(feature = "relaxed-arity")]
+fn source_has_optional_parameters(source: &str) -> bool {
+    let source = source.trim_start();
+
+    // Bound functions and other native wrappers don't reveal their parameters, their `length` is
+    // derived from the wrapped function
+    if source.ends_with("[native code]\n}") {
+        return true;
+    }
+
+    let parameters_start = if source.starts_with("class") {
+        let body_start = source.find('{').unwrap_or(source.len());
+        let constructor = source
+            .match_indices("constructor")
+            .map(|(index, _)| index + "constructor".len())
+            .find(|index| source[*index..].trim_start().starts_with('('));
+        match constructor {
+            Some(index) => index + source[index..].find('(').unwrap_or(0),
+            // The implicit constructor of a derived class passes all its arguments to the parent
+            None => return source[..body_start].contains("extends"),
+        }
+    } else {
+        match source.find('(') {
+            // `x => ...` arrow functions have a single plain parameter
+            Some(index) if !source[..index].contains("=>") => index,
+            _ => return false,
+        }
+    };
+
+    let mut depth = 0usize;
+    let mut chars = source[parameters_start..].chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return false;
+                }
+            }
+            '\'' | '"' | '`' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => {
+                            chars.next();
+                        }
+                        _ if next == c => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '=' if depth == 1 => return true,
+            '.' if depth == 1 && chars.peek() == Some(&'.') => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
 pub fn variant_case_tag<'js>(
     ctx: &Ctx<'js>,
     name: &'static str,
@@ -2250,7 +2618,7 @@ where
         let constructor = constructor_obj.as_constructor().unwrap_or_else(|| panic!("Expected export {path} to be a class with a constructor", path = resource_path.join("."))).clone();
 
         let parameter_count = constructor_obj.get::<&str, usize>("length").unwrap_or_else(|_| panic!("Failed to get parameter count of exported constructor {}", resource_path.join(".")));
-        if parameter_count != args.num_args() {
+        if !accepts_resource_parameter_count(&ctx, resource_path, "constructor", &constructor_obj, parameter_count, args.num_args()) {
             panic!(
                 "The WIT specification defines {} parameters,\nbut the exported JavaScript constructor got {} parameters (exported constructor {} in WIT package {})",
                 args.num_args(),
@@ -2289,9 +2657,47 @@ where
 }
 
 pub fn get_free_resource_id() -> usize {
-    get_js_state()
-        .last_resource_id
-        .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    #[cfg(feature = "per-call-isolation")]
+    {
+        crate::isolation::next_resource_id()
+    }
+
+    #[cfg(not(feature = "per-call-isolation"))]
+    {
+        get_js_state()
+            .last_resource_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// Must be called right before creating the handle of a new exported resource instance, which
+/// wit-bindgen boxes. With `per-call-isolation` the handle's representation must outlive the
+/// snapshot restores, as the host may keep using the handle in later calls.
+pub fn prepare_exported_resource_handle() {
+    #[cfg(feature = "per-call-isolation")]
+    crate::isolation::allocate_next_in_arena();
+}
+
+/// Gets the JS instance of an exported resource from the resource table, throwing a JS error if
+/// the handle does not refer to a living instance.
+pub fn get_resource_instance<'js>(
+    ctx: &Ctx<'js>,
+    resource_id: usize,
+    class_name: &str,
+) -> rquickjs::Result<Object<'js>> {
+    let resource_table: Object = ctx.globals().get(RESOURCE_TABLE_NAME)?;
+    match resource_table.get::<_, Option<Object>>(resource_id.to_string())? {
+        Some(resource_instance) => Ok(resource_instance),
+        None => {
+            let message =
+                format!("Resource instance #{resource_id} of class {class_name} does not exist");
+            #[cfg(feature = "per-call-isolation")]
+            let message = format!(
+                "{message}: with per-call-isolation, exported resources only live until the end of the exported call that created them"
+            );
+            Err(Exception::throw_reference(ctx, &message))
+        }
+    }
 }
 
 pub async fn call_js_resource_method<A, R>(
@@ -2323,6 +2729,7 @@ pub async fn call_js_resource_method_returning_result<A, R, E>(
     resource_id: usize,
     name: &str,
     args: A,
+    error_catch_all_case: Option<&'static str>,
 ) -> crate::wrappers::JsResult<R, E>
 where
     A: for<'js> IntoArgs<'js>,
@@ -2337,8 +2744,7 @@ where
         args,
         |a| crate::wrappers::JsResult(Ok(a)),
         |ctx, value| {
-            FromJs::from_js(ctx, value.clone())
-                .ok()
+            map_thrown_error(ctx, value, error_catch_all_case)
                 .map(|e| crate::wrappers::JsResult(Err(e)))
         },
     )
@@ -2363,10 +2769,19 @@ where
     let js_state = get_js_state();
 
     let result: FR = async_with!(js_state.ctx => |ctx| {
-        let resource_table: Object = ctx.globals().get(RESOURCE_TABLE_NAME)
-            .expect("Failed to get the resource table");
-        let resource_instance: Object = resource_table.get(resource_id.to_string())
-            .unwrap_or_else(|_| panic!("Failed to get resource instance with id #{resource_id} of class {}", resource_path.join(".")));
+        let resource_instance = match get_resource_instance(&ctx, resource_id, &resource_path.join(".")) {
+            Ok(resource_instance) => resource_instance,
+            Err(Error::Exception) => {
+                let exception = ctx.catch();
+                if let Some(result) = try_map_exception(&ctx, &exception) {
+                    return result;
+                }
+                panic!("Exception during call of method {name} in {path}:\n{exception}", path=resource_path.join("."), exception = format_js_exception(&exception));
+            }
+            Err(e) => {
+                panic!("Failed to get resource instance with id #{resource_id} of class {}: {e:?}", resource_path.join("."));
+            }
+        };
 
         let method_obj: Object = resource_instance.get(name)
             .unwrap_or_else(|_| panic!("{}", dump_cannot_find_method(
@@ -2379,7 +2794,7 @@ where
         let method = method_obj.as_function().unwrap_or_else(|| panic!("Expected method {name} to be a function in class {}", resource_path.join("."))).clone();
 
         let parameter_count = method.get::<&str, usize>("length").unwrap_or_else(|_| panic!("Failed to get parameter count of exported method {name} in class {}", resource_path.join(".")));
-        if parameter_count != args.num_args() {
+        if !accepts_resource_parameter_count(&ctx, resource_path, name, &method, parameter_count, args.num_args()) {
             panic!(
                 "The WIT specification defines {} parameters,\nbut the exported JavaScript method got {} parameters (exported method {} of class {} representing a resource defined in WIT package {})",
                 args.num_args(),
@@ -2718,3 +3133,89 @@ pub fn wizer_initialize() {
 
     WIZER_ACTIVE.store(false, std::sync::atomic::Ordering::Relaxed);
 }
+
+/// Runs a debugging export on the JS context as it is. Unlike `async_exported_function`, it does
+/// not restore the `per-call-isolation` snapshot, so it sees the state left behind by the last
+/// call, and it is not counted as an exported call by the CPU profiler and `perf_hooks`.
+fn debug_exported_function<F: Future>(future: F) -> F::Output {
+    block_on(future)
+}
+
+/// Implements the optional `wasm-rquickjs-heap-snapshot` export: takes a heap snapshot of the
+/// JS runtime like `v8.getHeapSnapshot` and returns it as `.heapsnapshot` JSON.
+pub fn heap_snapshot() -> String {
+    debug_exported_function(async {
+        let js_state = get_js_state();
+        async_with!(js_state.ctx => |ctx| {
+            crate::builtin::v8_heap_snapshot::take_heap_snapshot(&ctx)
+        })
+        .await
+    })
+}
+
+/// Status of a `node:test` test run by the optional `run-tests` export
+pub enum TestStatus {
+    Pass,
+    Fail,
+    Skip,
+    Todo,
+}
+
+/// Result of a `node:test` test run by the optional `run-tests` export
+pub struct TestResult {
+    /// Names of the test and its ancestor suites and tests, separated by ` > `
+    pub name: String,
+    pub status: TestStatus,
+    pub duration_ms: f64,
+    pub failure: Option<String>,
+}
+
+/// Implements the optional `run-tests` export: runs the `node:test` tests registered while
+/// evaluating the JS modules, only the ones whose full name matches `filter` if given.
+pub fn run_tests(filter: Option<String>) -> Vec<TestResult> {
+    async_exported_function(async {
+        let js_state = get_js_state();
+        let results = async_with!(js_state.ctx => |ctx| {
+            let module: Object = Module::import(&ctx, "node:test")
+                .catch(&ctx)
+                .unwrap_or_else(|e| panic!("Failed to import node:test:\n{}", format_caught_error(e)))
+                .finish::<Object>()
+                .catch(&ctx)
+                .unwrap_or_else(|e| panic!("Failed to finish importing node:test:\n{}", format_caught_error(e)));
+            let run_collected_tests: Function = module
+                .get("_runCollectedTests")
+                .expect("Failed to get _runCollectedTests");
+            let promise: Promise = run_collected_tests
+                .call((filter,))
+                .catch(&ctx)
+                .unwrap_or_else(|e| panic!("Failed to run the tests:\n{}", format_caught_error(e)));
+            let results: Vec<Object> = promise
+                .into_future::<Vec<Object>>()
+                .await
+                .catch(&ctx)
+                .unwrap_or_else(|e| panic!("Failed to run the tests:\n{}", format_caught_error(e)));
+
+            results
+                .into_iter()
+                .map(|result| {
+                    let status: String = result.get("status").expect("Missing test status");
+                    TestResult {
+                        name: result.get("name").expect("Missing test name"),
+                        status: match status.as_str() {
+                            "pass" => TestStatus::Pass,
+                            "fail" => TestStatus::Fail,
+                            "skip" => TestStatus::Skip,
+                            "todo" => TestStatus::Todo,
+                            other => panic!("Unexpected test status: {other}"),
+                        },
+                        duration_ms: result.get("durationMs").expect("Missing test duration"),
+                        failure: result.get("failure").expect("Invalid test failure"),
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .await;
+        drain_and_idle(js_state).await;
+        results
+    })
+}
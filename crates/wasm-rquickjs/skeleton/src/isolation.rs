@@ -0,0 +1,383 @@
+//! Linear memory snapshots implementing the `per-call-isolation` feature.
+//!
+//! Right before the first exported call, when the builtins and the user module are initialized,
+//! the whole heap (the allocator's state, the QuickJS runtime and every Rust static) is copied
+//! into a snapshot buffer. Before each later exported call the heap is copied back from it, so
+//! every call starts from exactly the initialized state without re-running any JS.
+//!
+//! The restore must happen before the host lowers the arguments of the next call into our memory,
+//! so it is triggered from `cabi_realloc` as well as from the start of the exported call itself.
+//! A few things must survive a restore, and are kept outside the restored address ranges:
+//! - the bookkeeping of this module (`PERSISTENT`)
+//! - the snapshot buffer itself
+//! - the shadow stack, which holds the frames of the running call
+//! - the representations of exported resource handles, which the host may still hold and pass
+//!   back (or drop) in later calls. These are allocated from a fixed-size arena in `PERSISTENT`.
+//!
+//! The allocator grows the memory through `sbrk`, which is overridden here so that after a
+//! restore it hands out the pages above the snapshot's break again instead of growing the memory
+//! on every call.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ffi::c_void;
+use std::ptr;
+
+const PAGE_SIZE: usize = 65536;
+
+/// Maximum number of exported resource handles alive at the same time
+const ARENA_SLOTS: usize = 8192;
+
+/// Size of one arena slot; the representation of a resource handle is an `Option` of a struct
+/// holding the resource id
+const ARENA_SLOT_SIZE: usize = 16;
+
+/// [`restored_ranges`] splits the heap around at most two excluded ranges
+const MAX_RANGES: usize = 3;
+
+unsafe extern "C" {
+    /// End of the shadow stack, which the linker places before the static data
+    static __stack_high: u8;
+
+    fn write(fd: i32, buf: *const u8, len: usize) -> isize;
+    fn __wasilibc_populate_preopens();
+}
+
+#[repr(C, align(16))]
+struct ArenaSlot([u8; ARENA_SLOT_SIZE]);
+
+struct Persistent {
+    snapshot: *mut u8,
+    ranges: [(usize, usize); MAX_RANGES],
+    range_count: usize,
+    /// An exported call ran since the last restore
+    dirty: bool,
+    next_resource_id: usize,
+    /// The next allocation is the representation of an exported resource handle
+    allocate_in_arena: bool,
+    /// Index + 1 of the first free slot below `arena_used`, 0 if there is none
+    arena_free: usize,
+    arena_used: usize,
+    arena_live: usize,
+    arena: [ArenaSlot; ARENA_SLOTS],
+}
+
+static mut PERSISTENT: Persistent = Persistent {
+    snapshot: ptr::null_mut(),
+    ranges: [(0, 0); MAX_RANGES],
+    range_count: 0,
+    dirty: false,
+    next_resource_id: 1,
+    allocate_in_arena: false,
+    arena_free: 0,
+    arena_used: 0,
+    arena_live: 0,
+    arena: [const { ArenaSlot([0; ARENA_SLOT_SIZE]) }; ARENA_SLOTS],
+};
+
+/// The allocator's break. It lives in the restored memory, so a restore moves it back to where
+/// it was when the snapshot was taken.
+static mut BREAK: usize = 0;
+
+#[allow(static_mut_refs)]
+fn persistent() -> &'static mut Persistent {
+    unsafe { &mut PERSISTENT }
+}
+
+/// Replaces wasi-libc's `sbrk`, which always grows the memory. Pages between the break and the
+/// end of the memory are left over from calls discarded by a restore, and get reused (cleared,
+/// like fresh pages) before growing the memory.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sbrk(increment: isize) -> *mut c_void {
+    let memory_end = core::arch::wasm32::memory_size(0) * PAGE_SIZE;
+    unsafe {
+        if BREAK == 0 {
+            BREAK = memory_end;
+        }
+        let old_break = BREAK;
+        if increment == 0 {
+            return old_break as *mut c_void;
+        }
+        if increment < 0 {
+            return usize::MAX as *mut c_void;
+        }
+
+        let new_break = match old_break.checked_add(increment as usize) {
+            Some(new_break) => new_break,
+            None => return usize::MAX as *mut c_void,
+        };
+        if new_break > memory_end {
+            let pages = (new_break - memory_end).div_ceil(PAGE_SIZE);
+            if core::arch::wasm32::memory_grow(0, pages) == usize::MAX {
+                return usize::MAX as *mut c_void;
+            }
+        }
+        if old_break < memory_end {
+            ptr::write_bytes(
+                old_break as *mut u8,
+                0,
+                new_break.min(memory_end) - old_break,
+            );
+        }
+        BREAK = new_break;
+        old_break as *mut c_void
+    }
+}
+
+/// Replaces the `cabi_realloc` export, to restore the snapshot before the host starts lowering
+/// the arguments of the next exported call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn cabi_realloc(
+    old_ptr: *mut u8,
+    old_len: usize,
+    align: usize,
+    new_len: usize,
+) -> *mut u8 {
+    restore_snapshot_if_dirty();
+
+    let ptr = unsafe {
+        if old_len == 0 {
+            if new_len == 0 {
+                return align as *mut u8;
+            }
+            std::alloc::alloc(Layout::from_size_align_unchecked(new_len, align))
+        } else {
+            std::alloc::realloc(
+                old_ptr,
+                Layout::from_size_align_unchecked(old_len, align),
+                new_len,
+            )
+        }
+    };
+    if ptr.is_null() {
+        core::arch::wasm32::unreachable();
+    }
+    ptr
+}
+
+/// Whether the snapshot of the initialized state has been taken
+pub fn has_snapshot() -> bool {
+    !persistent().snapshot.is_null()
+}
+
+/// Copies the current state of the heap into the snapshot buffer. Must be called outside of any
+/// exported call's work, when the JS runtime is idle.
+pub fn take_snapshot() {
+    let state = persistent();
+    unsafe {
+        // wasi-libc gets the stdio streams and the preopened directories from the host on first
+        // use. Doing it now makes them part of the snapshot, instead of getting (and leaking)
+        // new handles in every call.
+        write(1, b"".as_ptr(), 0);
+        write(2, b"".as_ptr(), 0);
+        __wasilibc_populate_preopens();
+
+        // Makes the break part of the snapshot, even if the allocator did not need to grow yet
+        sbrk(0);
+    }
+
+    let mut len = unsafe { BREAK };
+    loop {
+        let layout = Layout::from_size_align(len, 16).expect("Invalid snapshot size");
+        let snapshot = unsafe { System.alloc(layout) };
+        if snapshot.is_null() {
+            panic!("Failed to allocate {len} bytes for the per-call-isolation snapshot");
+        }
+
+        let (ranges, range_count) = restored_ranges(snapshot as usize, len);
+        let total: usize = ranges[..range_count]
+            .iter()
+            .map(|(start, end)| end - start)
+            .sum();
+        if total <= len {
+            let mut offset = 0;
+            for (start, end) in &ranges[..range_count] {
+                unsafe {
+                    ptr::copy_nonoverlapping(
+                        *start as *const u8,
+                        snapshot.add(offset),
+                        end - start,
+                    );
+                }
+                offset += end - start;
+            }
+
+            state.snapshot = snapshot;
+            state.ranges = ranges;
+            state.range_count = range_count;
+            state.dirty = false;
+            return;
+        }
+
+        unsafe { System.dealloc(snapshot, layout) };
+        len = total + PAGE_SIZE;
+    }
+}
+
+/// Marks the heap as used by an exported call, so it gets restored before the next one.
+pub fn mark_dirty() {
+    if has_snapshot() {
+        persistent().dirty = true;
+    }
+}
+
+/// Copies the snapshot back if an exported call ran since the last restore.
+pub fn restore_snapshot_if_dirty() {
+    let state = persistent();
+    if !state.dirty {
+        return;
+    }
+
+    let mut offset = 0;
+    for (start, end) in &state.ranges[..state.range_count] {
+        unsafe {
+            ptr::copy_nonoverlapping(state.snapshot.add(offset), *start as *mut u8, end - start);
+        }
+        offset += end - start;
+    }
+    state.dirty = false;
+}
+
+/// The address ranges of the heap covered by the snapshot: everything between the end of the
+/// shadow stack and the break, except the snapshot buffer and `PERSISTENT`.
+fn restored_ranges(snapshot: usize, len: usize) -> ([(usize, usize); MAX_RANGES], usize) {
+    let persistent_start = &raw const PERSISTENT as usize;
+    let mut excluded = [
+        (snapshot, snapshot + len),
+        (persistent_start, persistent_start + size_of::<Persistent>()),
+    ];
+    excluded.sort();
+
+    let mut ranges = [(0, 0); MAX_RANGES];
+    let mut range_count = 0;
+    let mut start = &raw const __stack_high as usize;
+    let end = unsafe { BREAK };
+    for (excluded_start, excluded_end) in excluded {
+        if start < end && excluded_start > start {
+            ranges[range_count] = (start, excluded_start.min(end));
+            range_count += 1;
+        }
+        start = start.max(excluded_end);
+    }
+    if start < end {
+        ranges[range_count] = (start, end);
+        range_count += 1;
+    }
+    (ranges, range_count)
+}
+
+/// Allocates an id for a new exported resource instance. The counter is not restored, so an id
+/// is never reused by a later call while the host may still hold a handle using it.
+pub fn next_resource_id() -> usize {
+    let state = persistent();
+    let id = state.next_resource_id;
+    state.next_resource_id += 1;
+    id
+}
+
+/// Makes the next allocation come from the resource handle arena. Must be called right before
+/// wit-bindgen boxes the representation of a new exported resource handle, which the host may
+/// use in later calls.
+pub fn allocate_next_in_arena() {
+    let state = persistent();
+    if state.arena_live >= ARENA_SLOTS {
+        panic!(
+            "Cannot create more than {ARENA_SLOTS} exported resource handles alive at the same time with per-call-isolation"
+        );
+    }
+    state.allocate_in_arena = true;
+}
+
+fn arena_range() -> (usize, usize) {
+    let start = unsafe { &raw const PERSISTENT.arena } as usize;
+    (start, start + ARENA_SLOTS * ARENA_SLOT_SIZE)
+}
+
+fn arena_contains(ptr: *mut u8) -> bool {
+    let (start, end) = arena_range();
+    (start..end).contains(&(ptr as usize))
+}
+
+fn arena_alloc(layout: Layout) -> Option<*mut u8> {
+    let state = persistent();
+    if !std::mem::take(&mut state.allocate_in_arena)
+        || layout.size() > ARENA_SLOT_SIZE
+        || layout.align() > align_of::<ArenaSlot>()
+    {
+        return None;
+    }
+
+    let index = if state.arena_free != 0 {
+        let index = state.arena_free - 1;
+        state.arena_free = usize::from_le_bytes(
+            state.arena[index].0[..size_of::<usize>()]
+                .try_into()
+                .unwrap(),
+        );
+        index
+    } else if state.arena_used < ARENA_SLOTS {
+        state.arena_used += 1;
+        state.arena_used - 1
+    } else {
+        return None;
+    };
+    state.arena_live += 1;
+    Some(state.arena[index].0.as_mut_ptr())
+}
+
+fn arena_dealloc(ptr: *mut u8) {
+    let state = persistent();
+    let index = (ptr as usize - arena_range().0) / ARENA_SLOT_SIZE;
+    state.arena[index].0[..size_of::<usize>()].copy_from_slice(&state.arena_free.to_le_bytes());
+    state.arena_free = index + 1;
+    state.arena_live -= 1;
+}
+
+/// wasi-libc's allocator, except for the representations of exported resource handles, which
+/// come from the arena
+struct IsolatingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: IsolatingAllocator = IsolatingAllocator;
+
+unsafe impl GlobalAlloc for IsolatingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match arena_alloc(layout) {
+            Some(ptr) => ptr,
+            None => unsafe { System.alloc(layout) },
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        match arena_alloc(layout) {
+            Some(ptr) => {
+                unsafe { ptr::write_bytes(ptr, 0, layout.size()) };
+                ptr
+            }
+            None => unsafe { System.alloc_zeroed(layout) },
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if arena_contains(ptr) {
+            arena_dealloc(ptr);
+        } else {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if arena_contains(ptr) {
+            let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+            let new_ptr = unsafe { System.alloc(new_layout) };
+            if !new_ptr.is_null() {
+                unsafe {
+                    ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+                }
+                arena_dealloc(ptr);
+            }
+            new_ptr
+        } else {
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+}
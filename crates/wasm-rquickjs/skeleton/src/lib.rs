@@ -2,6 +2,8 @@
 
 mod builtin;
 pub mod internal;
+#[cfg(feature = "per-call-isolation")]
+mod isolation;
 mod modules;
 pub mod wrappers;
 
@@ -12,6 +14,8 @@ fn js_export_module() -> &'static str {
     JS_EXPORT_MODULE_SOURCE
 }
 
+static RUN_TESTS_EXPORT: bool = false;
+
 type GetModuleFn = Box<dyn (Fn() -> String) + Send + Sync>;
 
 static JS_ADDITIONAL_MODULES: std::sync::LazyLock<Vec<(&str, GetModuleFn)>> =
@@ -0,0 +1,566 @@
+//! Code coverage instrumentation of the embedded JS modules.
+//!
+//! The instrumented module counts the executed statements, functions and branches in the
+//! Istanbul coverage model. Counters are inserted into the original source text instead of
+//! regenerating it, so line numbers in stack traces stay the same. The counters are registered
+//! with the `__wasm_rquickjs_builtin/coverage` module, which writes the coverage reports.
+
+use anyhow::anyhow;
+use oxc_allocator::Allocator;
+use oxc_ast::ast::*;
+use oxc_ast_visit::{Visit, walk};
+use oxc_parser::Parser;
+use oxc_span::{GetSpan, SourceType, Span};
+use oxc_syntax::scope::ScopeFlags;
+use std::fmt::Write;
+
+/// Function of the instrumented module returning its counters
+const COUNTERS: &str = "__wasm_rquickjs_cov";
+
+/// Instruments the JS module `source` for code coverage, reporting it under `path`.
+pub fn instrument_module(source: &str, path: &str) -> anyhow::Result<String> {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, SourceType::mjs()).parse();
+    if let Some(error) = parsed.errors.first() {
+        return Err(anyhow!("Failed to parse {path}: {error}"));
+    }
+
+    let mut instrumenter = Instrumenter::new(source);
+    instrumenter.visit_program(&parsed.program);
+    Ok(instrumenter.finish(path))
+}
+
+/// Text inserted into the source at an offset
+struct Insertion {
+    offset: u32,
+    /// Closes a construct opened at an earlier offset, so it goes before any text opening a new
+    /// construct at the same offset
+    closing: bool,
+    text: String,
+}
+
+struct Function {
+    name: String,
+    decl: Span,
+    loc: Span,
+}
+
+struct Branch {
+    kind: &'static str,
+    loc: Span,
+    locations: Vec<Span>,
+}
+
+struct Instrumenter<'s> {
+    source: &'s str,
+    line_starts: Vec<usize>,
+    insertions: Vec<Insertion>,
+    statements: Vec<Span>,
+    functions: Vec<Function>,
+    branches: Vec<Branch>,
+    /// Name of the next visited function, for functions named by what they are assigned to
+    function_name: Option<String>,
+}
+
+impl<'s> Instrumenter<'s> {
+    fn new(source: &'s str) -> Self {
+        let mut line_starts = vec![0];
+        let mut chars = source.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\r' if chars.peek().is_some_and(|(_, next)| *next == '\n') => {}
+                '\n' | '\r' | '\u{2028}' | '\u{2029}' => line_starts.push(index + c.len_utf8()),
+                _ => {}
+            }
+        }
+
+        Self {
+            source,
+            line_starts,
+            insertions: Vec::new(),
+            statements: Vec::new(),
+            functions: Vec::new(),
+            branches: Vec::new(),
+            function_name: None,
+        }
+    }
+
+    fn open(&mut self, offset: u32, text: String) {
+        self.insertions.push(Insertion {
+            offset,
+            closing: false,
+            text,
+        });
+    }
+
+    fn close(&mut self, offset: u32, text: String) {
+        self.insertions.push(Insertion {
+            offset,
+            closing: true,
+            text,
+        });
+    }
+
+    fn statement_counter(&mut self, span: Span) -> String {
+        self.statements.push(span);
+        format!("{COUNTERS}().s[{}]++;", self.statements.len() - 1)
+    }
+
+    fn branch_counter(branch: usize, location: usize) -> String {
+        format!("{COUNTERS}().b[{branch}][{location}]++")
+    }
+
+    fn add_branch(&mut self, kind: &'static str, loc: Span, locations: Vec<Span>) -> usize {
+        self.branches.push(Branch {
+            kind,
+            loc,
+            locations,
+        });
+        self.branches.len() - 1
+    }
+
+    fn add_function(&mut self, name: Option<String>, decl: Span, loc: Span) -> usize {
+        let name = name
+            .or_else(|| self.function_name.take())
+            .unwrap_or_else(|| format!("(anonymous_{})", self.functions.len()));
+        self.function_name = None;
+        self.functions.push(Function { name, decl, loc });
+        self.functions.len() - 1
+    }
+
+    /// Visits an expression, counting its evaluations by turning it into `(counter, expression)`
+    fn visit_counted_expression<'a>(&mut self, expression: &Expression<'a>, counter: String) {
+        let span = expression.span();
+        self.open(span.start, format!("({counter}, "));
+        self.visit_expression(expression);
+        self.close(span.end, ")".to_string());
+    }
+
+    /// Visits the body of a compound statement which is not part of a statement list, wrapping
+    /// it into a block if needed to insert the counters.
+    fn visit_body<'a>(&mut self, body: &Statement<'a>, branch_counter: Option<String>) {
+        let mut counters = branch_counter.map(|c| c + ";").unwrap_or_default();
+        if let Statement::BlockStatement(block) = body {
+            if !counters.is_empty() {
+                self.open(block.span.start + 1, counters);
+            }
+            self.visit_block_statement(block);
+        } else {
+            if is_counted(body) {
+                counters.push_str(&self.statement_counter(body.span()));
+            }
+            self.open(body.span().start, format!("{{{counters}"));
+            self.visit_statement(body);
+            self.close(body.span().end, "}".to_string());
+        }
+    }
+
+    /// Offset in a function body where the code following the directive prologue starts
+    fn body_start(body: &FunctionBody<'_>) -> u32 {
+        body.directives
+            .last()
+            .map(|directive| directive.span.end)
+            .unwrap_or(body.span.start + 1)
+    }
+
+    fn position(&self, offset: u32) -> (usize, usize) {
+        let offset = offset as usize;
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let column = self.source[self.line_starts[line]..offset]
+            .encode_utf16()
+            .count();
+        (line + 1, column)
+    }
+
+    fn write_location(&self, out: &mut String, span: Span) {
+        let (start_line, start_column) = self.position(span.start);
+        let (end_line, end_column) = self.position(span.end);
+        let _ = write!(
+            out,
+            r#"{{"start":{{"line":{start_line},"column":{start_column}}},"end":{{"line":{end_line},"column":{end_column}}}}}"#
+        );
+    }
+
+    /// The statement, function and branch maps of the module in the Istanbul format, with the
+    /// maps being arrays indexed by the counter ids
+    fn coverage_maps(&self, path: &str) -> String {
+        let mut out = String::new();
+        let _ = write!(out, r#"{{"path":{},"statementMap":["#, json_string(path));
+        for (i, span) in self.statements.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            self.write_location(&mut out, *span);
+        }
+        out.push_str(r#"],"fnMap":["#);
+        for (i, function) in self.functions.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, r#"{{"name":{},"decl":"#, json_string(&function.name));
+            self.write_location(&mut out, function.decl);
+            out.push_str(r#","loc":"#);
+            self.write_location(&mut out, function.loc);
+            let _ = write!(out, r#","line":{}}}"#, self.position(function.decl.start).0);
+        }
+        out.push_str(r#"],"branchMap":["#);
+        for (i, branch) in self.branches.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(r#"{"loc":"#);
+            self.write_location(&mut out, branch.loc);
+            let _ = write!(out, r#","type":"{}","locations":["#, branch.kind);
+            for (j, location) in branch.locations.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                self.write_location(&mut out, *location);
+            }
+            let _ = write!(out, r#"],"line":{}}}"#, self.position(branch.loc.start).0);
+        }
+        out.push_str("]}");
+        out
+    }
+
+    /// Applies the insertions and appends the registration of the counters. Being hoisted, the
+    /// counter function can be used before the end of the module is evaluated.
+    fn finish(mut self, path: &str) -> String {
+        self.insertions
+            .sort_by_key(|insertion| (insertion.offset, !insertion.closing));
+
+        let mut result = String::with_capacity(self.source.len() * 2);
+        let mut copied = 0;
+        for insertion in &self.insertions {
+            let offset = insertion.offset as usize;
+            result.push_str(&self.source[copied..offset]);
+            result.push_str(&insertion.text);
+            copied = offset;
+        }
+        result.push_str(&self.source[copied..]);
+
+        let _ = write!(
+            result,
+            "\nimport {{ registerCoverage as __wasm_rquickjs_register_coverage }} from '__wasm_rquickjs_builtin/coverage';\n\
+             function {COUNTERS}() {{\n    \
+                 const counters = __wasm_rquickjs_register_coverage({});\n    \
+                 {COUNTERS} = () => counters;\n    \
+                 return counters;\n\
+             }}\n\
+             {COUNTERS}();\n",
+            self.coverage_maps(path)
+        );
+        result
+    }
+}
+
+impl<'a> Visit<'a> for Instrumenter<'_> {
+    fn visit_statements(&mut self, it: &oxc_allocator::Vec<'a, Statement<'a>>) {
+        for statement in it {
+            if is_counted(statement) {
+                let counter = self.statement_counter(statement.span());
+                self.open(statement.span().start, counter);
+            }
+            self.visit_statement(statement);
+        }
+    }
+
+    fn visit_if_statement(&mut self, it: &IfStatement<'a>) {
+        let alternate_span = it.alternate.as_ref().map_or(it.span, |alt| alt.span());
+        let branch = self.add_branch("if", it.span, vec![it.consequent.span(), alternate_span]);
+        self.visit_expression(&it.test);
+        self.visit_body(&it.consequent, Some(Self::branch_counter(branch, 0)));
+        match &it.alternate {
+            Some(alternate) => self.visit_body(alternate, Some(Self::branch_counter(branch, 1))),
+            None => {
+                let counter = Self::branch_counter(branch, 1);
+                self.close(it.span.end, format!(" else {{ {counter}; }}"));
+            }
+        }
+    }
+
+    fn visit_for_statement(&mut self, it: &ForStatement<'a>) {
+        if let Some(init) = &it.init {
+            self.visit_for_statement_init(init);
+        }
+        if let Some(test) = &it.test {
+            self.visit_expression(test);
+        }
+        if let Some(update) = &it.update {
+            self.visit_expression(update);
+        }
+        self.visit_body(&it.body, None);
+    }
+
+    fn visit_for_in_statement(&mut self, it: &ForInStatement<'a>) {
+        self.visit_for_statement_left(&it.left);
+        self.visit_expression(&it.right);
+        self.visit_body(&it.body, None);
+    }
+
+    fn visit_for_of_statement(&mut self, it: &ForOfStatement<'a>) {
+        self.visit_for_statement_left(&it.left);
+        self.visit_expression(&it.right);
+        self.visit_body(&it.body, None);
+    }
+
+    fn visit_while_statement(&mut self, it: &WhileStatement<'a>) {
+        self.visit_expression(&it.test);
+        self.visit_body(&it.body, None);
+    }
+
+    fn visit_do_while_statement(&mut self, it: &DoWhileStatement<'a>) {
+        self.visit_body(&it.body, None);
+        self.visit_expression(&it.test);
+    }
+
+    fn visit_with_statement(&mut self, it: &WithStatement<'a>) {
+        self.visit_expression(&it.object);
+        self.visit_body(&it.body, None);
+    }
+
+    fn visit_switch_statement(&mut self, it: &SwitchStatement<'a>) {
+        self.visit_expression(&it.discriminant);
+        let locations = it.cases.iter().map(|case| case.span).collect();
+        let branch = self.add_branch("switch", it.span, locations);
+        for (i, case) in it.cases.iter().enumerate() {
+            let offset = case
+                .consequent
+                .first()
+                .map_or(case.span.end, |statement| statement.span().start);
+            self.open(offset, Self::branch_counter(branch, i) + ";");
+            if let Some(test) = &case.test {
+                self.visit_expression(test);
+            }
+            self.visit_statements(&case.consequent);
+        }
+    }
+
+    fn visit_conditional_expression(&mut self, it: &ConditionalExpression<'a>) {
+        let branch = self.add_branch(
+            "cond-expr",
+            it.span,
+            vec![it.consequent.span(), it.alternate.span()],
+        );
+        self.visit_expression(&it.test);
+        self.visit_counted_expression(&it.consequent, Self::branch_counter(branch, 0));
+        self.visit_counted_expression(&it.alternate, Self::branch_counter(branch, 1));
+    }
+
+    fn visit_logical_expression(&mut self, it: &LogicalExpression<'a>) {
+        // Chains of the same operator are a single branch, like `a || b || c` in Istanbul
+        let mut operands = Vec::new();
+        logical_operands(it, it.operator, &mut operands);
+        let locations = operands.iter().map(|operand| operand.span()).collect();
+        let branch = self.add_branch("binary-expr", it.span, locations);
+        for (i, operand) in operands.into_iter().enumerate() {
+            self.visit_counted_expression(operand, Self::branch_counter(branch, i));
+        }
+    }
+
+    fn visit_function(&mut self, it: &oxc_ast::ast::Function<'a>, flags: ScopeFlags) {
+        let name = it.id.as_ref().map(|id| id.name.to_string());
+        let decl = it.id.as_ref().map_or(it.span, |id| id.span);
+        let Some(body) = &it.body else {
+            self.function_name = None;
+            return walk::walk_function(self, it, flags);
+        };
+        let function = self.add_function(name, decl, Span::new(it.span.start, body.span.start));
+        self.open(
+            Self::body_start(body),
+            format!("{COUNTERS}().f[{function}]++;"),
+        );
+        walk::walk_function(self, it, flags);
+    }
+
+    fn visit_arrow_function_expression(&mut self, it: &ArrowFunctionExpression<'a>) {
+        let loc = Span::new(it.span.start, it.body.span.start);
+        let function = self.add_function(None, loc, loc);
+        let counter = format!("{COUNTERS}().f[{function}]++");
+        match it.get_expression() {
+            Some(expression) => {
+                self.visit_formal_parameters(&it.params);
+                self.visit_counted_expression(expression, counter);
+            }
+            None => {
+                self.open(Self::body_start(&it.body), counter + ";");
+                walk::walk_arrow_function_expression(self, it);
+            }
+        }
+    }
+
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        if let Some(init) = &it.init
+            && init.is_function()
+        {
+            self.function_name = it.id.get_identifier_name().map(|name| name.to_string());
+        }
+        walk::walk_variable_declarator(self, it);
+    }
+
+    fn visit_object_property(&mut self, it: &ObjectProperty<'a>) {
+        self.visit_property_key(&it.key);
+        if it.value.is_function() {
+            self.function_name = it.key.static_name().map(|name| name.to_string());
+        }
+        self.visit_expression(&it.value);
+    }
+
+    fn visit_method_definition(&mut self, it: &MethodDefinition<'a>) {
+        self.visit_property_key(&it.key);
+        self.function_name = it.key.static_name().map(|name| name.to_string());
+        self.visit_function(&it.value, ScopeFlags::Function);
+    }
+
+    fn visit_property_definition(&mut self, it: &PropertyDefinition<'a>) {
+        self.visit_property_key(&it.key);
+        if let Some(value) = &it.value {
+            if value.is_function() {
+                self.function_name = it.key.static_name().map(|name| name.to_string());
+            }
+            self.visit_expression(value);
+        }
+    }
+}
+
+/// Whether a statement gets its own counter. Blocks only group other statements, and function
+/// declarations are covered by their function counters.
+fn is_counted(statement: &Statement<'_>) -> bool {
+    match statement {
+        Statement::ExportNamedDeclaration(export) => matches!(
+            export.declaration,
+            Some(Declaration::VariableDeclaration(_) | Declaration::ClassDeclaration(_))
+        ),
+        Statement::ExportDefaultDeclaration(export) => !matches!(
+            export.declaration,
+            ExportDefaultDeclarationKind::FunctionDeclaration(_)
+        ),
+        _ => matches!(
+            statement,
+            Statement::ExpressionStatement(_)
+                | Statement::VariableDeclaration(_)
+                | Statement::ClassDeclaration(_)
+                | Statement::IfStatement(_)
+                | Statement::ForStatement(_)
+                | Statement::ForInStatement(_)
+                | Statement::ForOfStatement(_)
+                | Statement::WhileStatement(_)
+                | Statement::DoWhileStatement(_)
+                | Statement::ReturnStatement(_)
+                | Statement::ThrowStatement(_)
+                | Statement::BreakStatement(_)
+                | Statement::ContinueStatement(_)
+                | Statement::SwitchStatement(_)
+                | Statement::TryStatement(_)
+                | Statement::LabeledStatement(_)
+                | Statement::WithStatement(_)
+                | Statement::DebuggerStatement(_)
+        ),
+    }
+}
+
+fn logical_operands<'b, 'a>(
+    expression: &'b LogicalExpression<'a>,
+    operator: LogicalOperator,
+    operands: &mut Vec<&'b Expression<'a>>,
+) {
+    for operand in [&expression.left, &expression.right] {
+        match operand {
+            Expression::LogicalExpression(inner) if inner.operator == operator => {
+                logical_operands(inner, operator, operands)
+            }
+            _ => operands.push(operand),
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{2028}' || c == '\u{2029}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::instrument_module;
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    fn instrument(source: &str) -> String {
+        let result = instrument_module(source, "test.js").unwrap();
+        let allocator = Allocator::default();
+        let parsed = Parser::new(&allocator, &result, SourceType::mjs()).parse();
+        assert!(parsed.errors.is_empty(), "invalid output: {result}");
+        result
+    }
+
+    fn instrumented_code(source: &str) -> String {
+        let result = instrument(source);
+        result[..result.find("\nimport { registerCoverage").unwrap()].to_string()
+    }
+
+    #[test]
+    fn statements_and_functions() {
+        assert_eq!(
+            instrumented_code(
+                "'use strict';\nexport function f(a) {\n  'use strict';\n  return a;\n}\nconst g = () => 1;\n"
+            ),
+            "'use strict';\nexport function f(a) {\n  'use strict';__wasm_rquickjs_cov().f[0]++;\n  __wasm_rquickjs_cov().s[0]++;return a;\n}\n__wasm_rquickjs_cov().s[1]++;const g = () => (__wasm_rquickjs_cov().f[1]++, 1);\n"
+        );
+    }
+
+    #[test]
+    fn if_branches() {
+        assert_eq!(
+            instrumented_code("if (a) b()\nelse if (c) d();\nif (e) { f() }"),
+            "__wasm_rquickjs_cov().s[0]++;if (a) {__wasm_rquickjs_cov().b[0][0]++;__wasm_rquickjs_cov().s[1]++;b()}\nelse {__wasm_rquickjs_cov().b[0][1]++;__wasm_rquickjs_cov().s[2]++;if (c) {__wasm_rquickjs_cov().b[1][0]++;__wasm_rquickjs_cov().s[3]++;d();} else { __wasm_rquickjs_cov().b[1][1]++; }}\n__wasm_rquickjs_cov().s[4]++;if (e) {__wasm_rquickjs_cov().b[2][0]++; __wasm_rquickjs_cov().s[5]++;f() } else { __wasm_rquickjs_cov().b[2][1]++; }"
+        );
+    }
+
+    #[test]
+    fn expression_branches() {
+        assert_eq!(
+            instrumented_code("x = a || b || (c && d) ? 1 : 2;"),
+            "__wasm_rquickjs_cov().s[0]++;x = (__wasm_rquickjs_cov().b[1][0]++, a) || (__wasm_rquickjs_cov().b[1][1]++, b) || (__wasm_rquickjs_cov().b[1][2]++, ((__wasm_rquickjs_cov().b[2][0]++, c) && (__wasm_rquickjs_cov().b[2][1]++, d))) ? (__wasm_rquickjs_cov().b[0][0]++, 1) : (__wasm_rquickjs_cov().b[0][1]++, 2);"
+        );
+    }
+
+    #[test]
+    fn switch_and_loops() {
+        instrument(
+            "#!/usr/bin/env node\nswitch (x) { case 1: case 2: a(); break; default: }\nfor (;;) break;\nlabel: while (x) continue label;\ndo x--; while (x)\nclass C { static { y(); } m() { return 1 } f = function () {} }",
+        );
+    }
+
+    #[test]
+    fn function_names() {
+        let result = instrument(
+            "const f = function () {};\nconst o = { g() {}, h: () => {} };\nclass C { i() {} j = () => 1 }\n(function () {})();",
+        );
+        for name in [
+            r#""name":"f""#,
+            r#""name":"g""#,
+            r#""name":"h""#,
+            r#""name":"i""#,
+            r#""name":"j""#,
+            r#""name":"(anonymous_5)""#,
+        ] {
+            assert!(result.contains(name), "missing {name} in {result}");
+        }
+    }
+}
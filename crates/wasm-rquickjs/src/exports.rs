@@ -2,9 +2,11 @@ use crate::javascript::escape_js_ident;
 use crate::rust_bindgen::RustWitFunction;
 use crate::types::{
     ProcessedParameter, ReturnTypeInformation, WrappedType, get_function_name, get_return_type,
-    ident_in_exported_interface, ident_in_exported_interface_or_global, param_refs_as_tuple,
-    process_parameter, to_original_func_arg_list, to_wrapped_param_refs, type_borrows_resource,
+    ident_in_exported_interface, ident_in_exported_interface_or_global,
+    ident_in_imported_interface, param_refs_as_tuple, process_parameter, to_original_func_arg_list,
+    to_wrapped_param_refs, type_borrows_resource,
 };
+use crate::wit::{HEAP_SNAPSHOT_EXPORT, RUN_TESTS_EXPORT, RUN_TESTS_INTERFACE};
 use crate::{EmbeddingMode, GeneratorContext, JsModuleSpec};
 use anyhow::{Context, anyhow};
 use heck::{ToLowerCamelCase, ToUpperCamelCase};
@@ -25,6 +27,7 @@ pub fn generate_export_impls(
 
     let world_name_lit = LitStr::new(&context.world_name, Span::call_site());
     let with_block = generate_wasi_remaps(context);
+    let run_tests_export = context.options.run_tests_export;
 
     let lib_tokens = quote! {
         #[allow(unsafe_op_in_unsafe_fn)]
@@ -41,12 +44,16 @@ pub fn generate_export_impls(
         mod conversions;
         #[allow(unused)]
         mod internal;
+        #[cfg(feature = "per-call-isolation")]
+        mod isolation;
         #[allow(unused)]
         mod modules;
         mod wrappers;
 
         #module_defs
 
+        static RUN_TESTS_EXPORT: bool = #run_tests_export;
+
         struct Component;
 
         #(#guest_impls)*
@@ -133,6 +140,47 @@ fn generate_guest_impls(context: &GeneratorContext<'_>) -> anyhow::Result<Vec<To
     Ok(result)
 }
 
+/// Generates the optional `run-tests` export, converting the test results of the skeleton to the
+/// types of the `wasm-rquickjs-tests` interface
+fn generate_run_tests_impl(context: &GeneratorContext<'_>) -> anyhow::Result<TokenStream> {
+    let interface_id = context.resolve.packages[context.root_package]
+        .interfaces
+        .get(RUN_TESTS_INTERFACE)
+        .ok_or_else(|| anyhow!("Missing the {RUN_TESTS_INTERFACE} interface"))?;
+    let interface = &context.resolve.interfaces[*interface_id];
+    let test_result = ident_in_imported_interface(
+        context,
+        Ident::new("TestResult", Span::call_site()),
+        RUN_TESTS_INTERFACE,
+        interface,
+    );
+    let test_status = ident_in_imported_interface(
+        context,
+        Ident::new("TestStatus", Span::call_site()),
+        RUN_TESTS_INTERFACE,
+        interface,
+    );
+
+    Ok(quote! {
+        fn run_tests(filter: Option<String>) -> Vec<#test_result> {
+            crate::internal::run_tests(filter)
+                .into_iter()
+                .map(|result| #test_result {
+                    name: result.name,
+                    status: match result.status {
+                        crate::internal::TestStatus::Pass => #test_status::Pass,
+                        crate::internal::TestStatus::Fail => #test_status::Fail,
+                        crate::internal::TestStatus::Skip => #test_status::Skip,
+                        crate::internal::TestStatus::Todo => #test_status::Todo,
+                    },
+                    duration_ms: result.duration_ms,
+                    failure: result.failure,
+                })
+                .collect()
+        }
+    })
+}
+
 /// Generates the implementation of a `Guest` trait for the component, implementing the exported functions.
 ///
 /// The `guest_trait` parameter is a Rust snippet containing the fully-qualified path to the `Guest` trait to
@@ -161,6 +209,18 @@ fn generate_guest_impl(
                             crate::internal::wizer_initialize();
                         }
                     });
+                } else if name == HEAP_SNAPSHOT_EXPORT {
+                    // The optional heap snapshot export is implemented by the skeleton
+                    func_impls.push(quote! {
+                        fn wasm_rquickjs_heap_snapshot() -> String {
+                            crate::internal::heap_snapshot()
+                        }
+                    });
+                } else if context.options.run_tests_export
+                    && interface.is_none()
+                    && name == RUN_TESTS_EXPORT
+                {
+                    func_impls.push(generate_run_tests_impl(context)?);
                 } else {
                     let func_impl =
                         generate_exported_function_impl(context, interface, name, function)?;
@@ -197,6 +257,8 @@ fn generate_guest_impl(
             .ok_or_else(|| anyhow!("Resource type has no name"))?;
         let resource_name_ident =
             Ident::new(&resource_name.to_upper_camel_case(), Span::call_site());
+        let resource_class_name =
+            LitStr::new(&resource_name.to_upper_camel_case(), Span::call_site());
         let resource_name_borrow_ident = Ident::new(
             &format!("{}Borrow", resource_name.to_upper_camel_case()),
             Span::call_site(),
@@ -253,10 +315,7 @@ fn generate_guest_impl(
             impl<'js> rquickjs::IntoJs<'js> for #borrow_wrapper<'_> {
                 fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
                     let inner: &#resource_name_ident = self.get();
-                    let resource_table: rquickjs::Object = ctx.globals().get(crate::internal::RESOURCE_TABLE_NAME)
-                        .expect("Failed to get the resource table");
-                    let resource_instance: rquickjs::Object = resource_table.get(inner.resource_id.to_string())
-                        .expect(&format!("Failed to get resource instance with id {}", inner.resource_id));
+                    let resource_instance = crate::internal::get_resource_instance(ctx, inner.resource_id, #resource_class_name)?;
                     Ok(resource_instance.into_value())
                 }
             }
@@ -264,10 +323,7 @@ fn generate_guest_impl(
             impl<'js> rquickjs::IntoJs<'js> for #owned_wrapper {
                 fn into_js(self, ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Value<'js>> {
                     let inner: &#resource_name_ident = self.get();
-                    let resource_table: rquickjs::Object = ctx.globals().get(crate::internal::RESOURCE_TABLE_NAME)
-                        .expect("Failed to get the resource table");
-                    let resource_instance: rquickjs::Object = resource_table.get(inner.resource_id.to_string())
-                        .expect(&format!("Failed to get resource instance with id {}", inner.resource_id));
+                    let resource_instance = crate::internal::get_resource_instance(ctx, inner.resource_id, #resource_class_name)?;
                     Ok(resource_instance.into_value())
                 }
             }
@@ -294,6 +350,7 @@ fn generate_guest_impl(
                         resource_id
                     };
 
+                    crate::internal::prepare_exported_resource_handle();
                     Ok(#owned_wrapper::new(#resource_name_ident { resource_id }))
                 }
             }
@@ -383,10 +440,13 @@ fn generate_exported_function_impl(
     let wrapped_result = &return_types.wit_level_ret.wrapped_type_ref;
     let unwrap = &return_types.wit_level_ret.unwrap;
     let unwrap_result = unwrap.run(quote! { result });
-    let call = if return_types.expected_exception.is_some() {
-        quote! { call_js_export_returning_result }
+    let (call, error_mapping_args) = if return_types.expected_exception.is_some() {
+        (
+            quote! { call_js_export_returning_result },
+            vec![error_catch_all_case(context)],
+        )
     } else {
-        quote! { call_js_export }
+        (quote! { call_js_export }, vec![])
     };
     let func_impl = quote! {
        fn #func_name(#(#func_arg_list),*) -> #original_result {
@@ -395,6 +455,7 @@ fn generate_exported_function_impl(
                    #wit_package_lit,
                    #js_func_path,
                    #param_refs_tuple
+                   #(, #error_mapping_args)*
                ).await;
                #unwrap_result
            })
@@ -521,7 +582,7 @@ fn generate_exported_resource_function_impl(
 
             quote! {
               fn #func_name_ident(#(#func_arg_list),*) -> Self {
-                  crate::internal::async_exported_function(async move {
+                  let result = crate::internal::async_exported_function(async move {
                     let resource_id = crate::internal::call_js_resource_constructor(
                          #wit_package_lit,
                          #js_resource_path,
@@ -530,21 +591,27 @@ fn generate_exported_resource_function_impl(
                     Self {
                         resource_id
                     }
-                  })
+                  });
+                  // wit-bindgen boxes the returned value as the new handle's representation
+                  crate::internal::prepare_exported_resource_handle();
+                  result
               }
             }
         }
         FunctionKind::Method(_) => {
             let param_refs = param_refs[1..].to_vec();
             let param_refs_tuple = param_refs_as_tuple(&param_refs);
-            let original_result = &return_types.func_ret.original_type_ref;
-            let wrapped_result = &return_types.func_ret.wrapped_type_ref;
-            let unwrap = &return_types.func_ret.unwrap;
+            let original_result = &return_types.wit_level_ret.original_type_ref;
+            let wrapped_result = &return_types.wit_level_ret.wrapped_type_ref;
+            let unwrap = &return_types.wit_level_ret.unwrap;
             let unwrap_result = unwrap.run(quote! { result });
-            let call = if return_types.expected_exception.is_some() {
-                quote! { call_js_resource_method_returning_result }
+            let (call, error_mapping_args) = if return_types.expected_exception.is_some() {
+                (
+                    quote! { call_js_resource_method_returning_result },
+                    vec![error_catch_all_case(context)],
+                )
             } else {
-                quote! { call_js_resource_method }
+                (quote! { call_js_resource_method }, vec![])
             };
             quote! {
                fn #func_name_ident(#(#func_arg_list),*) -> #original_result {
@@ -555,6 +622,7 @@ fn generate_exported_resource_function_impl(
                             self.resource_id,
                             #js_func_name_str,
                             #param_refs_tuple,
+                            #(#error_mapping_args,)*
                        ).await;
                        #unwrap_result
                    })
@@ -567,10 +635,13 @@ fn generate_exported_resource_function_impl(
             let wrapped_result = &return_types.wit_level_ret.wrapped_type_ref;
             let unwrap = &return_types.wit_level_ret.unwrap;
             let unwrap_result = unwrap.run(quote! { result });
-            let call = if return_types.expected_exception.is_some() {
-                quote! { call_js_export_returning_result }
+            let (call, error_mapping_args) = if return_types.expected_exception.is_some() {
+                (
+                    quote! { call_js_export_returning_result },
+                    vec![error_catch_all_case(context)],
+                )
             } else {
-                quote! { call_js_export }
+                (quote! { call_js_export }, vec![])
             };
             quote! {
                fn #func_name_ident(#(#func_arg_list),*) -> #original_result {
@@ -579,6 +650,7 @@ fn generate_exported_resource_function_impl(
                            #wit_package_lit,
                            #js_static_func_path,
                            #param_refs_tuple,
+                           #(#error_mapping_args,)*
                        ).await;
                        #unwrap_result
                    })
@@ -596,6 +668,17 @@ fn generate_exported_resource_function_impl(
     Ok(func_impl)
 }
 
+/// The catch-all error case argument of the `*_returning_result` call wrappers
+fn error_catch_all_case(context: &GeneratorContext<'_>) -> TokenStream {
+    match &context.options.error_catch_all_case {
+        Some(case) => {
+            let case = Lit::Str(LitStr::new(case, Span::call_site()));
+            quote! { Some(#case) }
+        }
+        None => quote! { None },
+    }
+}
+
 fn generate_module_defs(js_modules: &[JsModuleSpec]) -> anyhow::Result<TokenStream> {
     if let Some((export_module, additional_modules)) = js_modules.split_first() {
         let export_module_name = LitStr::new(&export_module.name, Span::call_site());
@@ -0,0 +1,903 @@
+use crate::GeneratorContext;
+use crate::rust_bindgen::escape_rust_ident;
+use anyhow::{Context, anyhow, bail};
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use std::collections::{BTreeMap, BTreeSet};
+use syn::LitStr;
+use wit_parser::{
+    Function, FunctionKind, Handle, InterfaceId, PackageId, Type, TypeDefKind, TypeId, TypeOwner,
+    WorldItem, WorldKey,
+};
+
+/// Version of the wasmtime crates the generated harness depends on. It must be kept in sync
+/// with the version used by this workspace, so the harness behaves like the test suite.
+const WASMTIME_VERSION: &str = "=45.0.0";
+
+/// Generates the `<output>/Cargo.toml` file of the host harness crate.
+pub fn generate_harness_cargo_toml(context: &GeneratorContext<'_>) -> anyhow::Result<()> {
+    let cargo_toml = format!(
+        r#"[package]
+name = "{name}-host-harness"
+version = "0.0.1"
+edition = "2024"
+
+[dependencies]
+anyhow = "1.0.98"
+wasmtime = {{ version = "{WASMTIME_VERSION}", features = ["async", "component-model"] }}
+wasmtime-wasi = "{WASMTIME_VERSION}"
+wasmtime-wasi-http = "{WASMTIME_VERSION}"
+
+[dev-dependencies]
+tokio = {{ version = "1.47.1", features = ["macros", "rt-multi-thread"] }}
+
+[workspace]
+"#,
+        name = context.world_name
+    );
+
+    crate::write_if_changed(context.output.join("Cargo.toml"), cargo_toml)?;
+    Ok(())
+}
+
+/// Generates the `<output>/src/lib.rs` file of the host harness crate.
+///
+/// The generated code runs wasmtime's `bindgen!` on the same world the component implements,
+/// links WASI, WASI HTTP and a recording `wasi:logging` mock, and provides a `Stubs`
+/// implementation of all the world's imports together with typed helpers for every export.
+pub fn generate_harness_lib(context: &GeneratorContext<'_>) -> anyhow::Result<()> {
+    let paths = InterfacePaths::new(context);
+
+    let world_name_lit = LitStr::new(&context.world_name, Span::call_site());
+    let world_ident = Ident::new(&context.world_name.to_upper_camel_case(), Span::call_site());
+    let world_pre_ident = Ident::new(
+        &format!("{}Pre", context.world_name.to_upper_camel_case()),
+        Span::call_site(),
+    );
+
+    let with_block = if paths.remaps.is_empty() {
+        quote! {}
+    } else {
+        let entries = paths.remaps.iter().map(|(wit_name, rust_path)| {
+            let wit_lit = LitStr::new(wit_name, Span::call_site());
+            quote! { #wit_lit: #rust_path }
+        });
+        quote! {
+            with: {
+                #(#entries),*
+            },
+        }
+    };
+
+    let imports = generate_import_stubs(context, &paths)?;
+    let import_bounds = imports.bounds;
+    let stub_impls = imports.impls;
+
+    // The world's `add_to_linker` would link the remapped WASI interfaces a second time, so
+    // the imported interfaces and the world-level imports are linked one by one
+    let mut add_imports_to_linker = imports
+        .linked_interfaces
+        .iter()
+        .map(|path| {
+            quote! {
+                #path::add_to_linker::<_, HasImports<I>>(&mut linker, |state| &mut state.imports)?;
+            }
+        })
+        .collect::<Vec<_>>();
+    if imports.has_world_imports {
+        add_imports_to_linker.push(quote! {
+            bindings::#world_ident::add_to_linker_imports::<_, HasImports<I>>(&mut linker, |state| &mut state.imports)?;
+        });
+    }
+    let has_imports_def = if add_imports_to_linker.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            struct HasImports<I>(std::marker::PhantomData<I>);
+
+            impl<I: 'static> wasmtime::component::HasData for HasImports<I> {
+                type Data<'a> = &'a mut I;
+            }
+        }
+    };
+
+    let add_logging_mock = if imports.imports_logging {
+        quote! {}
+    } else {
+        quote! {
+            // The JS runtime forwards `console` output to wasi:logging/logging when the
+            // `logging` feature is enabled
+            linker.instance("wasi:logging/logging")?.func_wrap(
+                "log",
+                |mut ctx: wasmtime::StoreContextMut<'_, HostState<I>>,
+                 (level, context, message): (LogLevel, String, String)|
+                 -> wasmtime::Result<()> {
+                    ctx.data_mut().log_messages.push((level, context, message));
+                    Ok(())
+                },
+            )?;
+        }
+    };
+
+    let export_helpers = generate_export_helpers(context, &paths)?;
+
+    let lib_tokens = quote! {
+        pub mod bindings {
+            wasmtime::component::bindgen!({
+                path: "wit",
+                world: #world_name_lit,
+                imports: { default: async | trappable },
+                exports: { default: async },
+                #with_block
+            });
+        }
+
+        use std::path::Path;
+        use wasmtime::component::{Component, Linker, ResourceTable};
+        use wasmtime::{Config, Engine, Store};
+        use wasmtime_wasi::{WasiCtx, WasiCtxView, WasiView};
+        use wasmtime_wasi_http::WasiHttpCtx;
+        use wasmtime_wasi_http::p2::{WasiHttpCtxView, WasiHttpView, default_hooks};
+
+        /// Implementation of all the WIT imports of the component, except WASI
+        pub trait Imports: #(#import_bounds +)* Send + 'static {}
+
+        impl<T: #(#import_bounds +)* Send + 'static> Imports for T {}
+
+        /// Implementation of the component's imports that fails every call
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct Stubs;
+
+        #[allow(unused)]
+        fn not_implemented(name: &str) -> wasmtime::Error {
+            wasmtime::format_err!("import {name} is not implemented by the host harness")
+        }
+
+        #(#stub_impls)*
+
+        /// Log level of a message sent to wasi:logging/logging
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, wasmtime::component::ComponentType, wasmtime::component::Lift)]
+        #[component(enum)]
+        #[repr(u8)]
+        pub enum LogLevel {
+            #[component(name = "trace")]
+            Trace,
+            #[component(name = "debug")]
+            Debug,
+            #[component(name = "info")]
+            Info,
+            #[component(name = "warn")]
+            Warn,
+            #[component(name = "error")]
+            Error,
+            #[component(name = "critical")]
+            Critical,
+        }
+
+        /// Store data of a harnessed component instance
+        pub struct HostState<I> {
+            wasi: WasiCtx,
+            wasi_http: WasiHttpCtx,
+            table: ResourceTable,
+            /// Implementation of the component's imports
+            pub imports: I,
+            /// Messages logged through wasi:logging/logging, as (level, context, message)
+            pub log_messages: Vec<(LogLevel, String, String)>,
+        }
+
+        impl<I: Send + 'static> WasiView for HostState<I> {
+            fn ctx(&mut self) -> WasiCtxView<'_> {
+                WasiCtxView {
+                    ctx: &mut self.wasi,
+                    table: &mut self.table,
+                }
+            }
+        }
+
+        impl<I: Send + 'static> WasiHttpView for HostState<I> {
+            fn http(&mut self) -> WasiHttpCtxView<'_> {
+                WasiHttpCtxView {
+                    ctx: &mut self.wasi_http,
+                    table: &mut self.table,
+                    hooks: default_hooks(),
+                }
+            }
+        }
+
+        #has_imports_def
+
+        /// A compiled and linked component, ready to be instantiated any number of times
+        pub struct Harness<I: 'static = Stubs> {
+            engine: Engine,
+            pre: bindings::#world_pre_ident<HostState<I>>,
+        }
+
+        impl<I: Imports> Harness<I> {
+            /// Loads the component from `wasm_path` and links WASI, WASI HTTP, wasi:logging
+            /// and the world's imports. Any other import of the component traps when called.
+            pub fn new(wasm_path: impl AsRef<Path>) -> wasmtime::Result<Self> {
+                let mut config = Config::default();
+                config.wasm_component_model(true);
+                // QuickJS needs a deep stack for recursive JS code
+                config.async_stack_size(32 * 1024 * 1024);
+                config.max_wasm_stack(16 * 1024 * 1024);
+                let engine = Engine::new(&config)?;
+
+                let mut linker = Linker::<HostState<I>>::new(&engine);
+                wasmtime_wasi::p2::add_to_linker_async(&mut linker)?;
+                wasmtime_wasi_http::p2::add_only_http_to_linker_async(&mut linker)?;
+                #add_logging_mock
+                #(#add_imports_to_linker)*
+
+                let component = Component::from_file(&engine, wasm_path)?;
+                linker.define_unknown_imports_as_traps(&component)?;
+                let pre = bindings::#world_pre_ident::new(linker.instantiate_pre(&component)?)?;
+
+                Ok(Self { engine, pre })
+            }
+
+            /// Instantiates the component with the given imports, inheriting the host's stdio
+            pub async fn instantiate(&self, imports: I) -> wasmtime::Result<HarnessInstance<I>> {
+                let wasi = WasiCtx::builder().inherit_stdio().build();
+                self.instantiate_with_wasi(imports, wasi).await
+            }
+
+            /// Instantiates the component with the given imports and WASI context
+            pub async fn instantiate_with_wasi(
+                &self,
+                imports: I,
+                wasi: WasiCtx,
+            ) -> wasmtime::Result<HarnessInstance<I>> {
+                let state = HostState {
+                    wasi,
+                    wasi_http: WasiHttpCtx::new(),
+                    table: ResourceTable::new(),
+                    imports,
+                    log_messages: Vec::new(),
+                };
+                let mut store = Store::new(&self.engine, state);
+                let bindings = self.pre.instantiate_async(&mut store).await?;
+                Ok(HarnessInstance { store, bindings })
+            }
+        }
+
+        /// An instantiated component with typed helpers calling its exports
+        pub struct HarnessInstance<I: 'static = Stubs> {
+            pub store: Store<HostState<I>>,
+            pub bindings: bindings::#world_ident,
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        impl<I: Imports> HarnessInstance<I> {
+            /// The import implementation the instance was created with
+            pub fn imports(&mut self) -> &mut I {
+                &mut self.store.data_mut().imports
+            }
+
+            /// Messages logged through wasi:logging/logging so far
+            pub fn log_messages(&self) -> &[(LogLevel, String, String)] {
+                &self.store.data().log_messages
+            }
+
+            #(#export_helpers)*
+        }
+    };
+
+    let lib_ast: syn::File =
+        syn::parse2(lib_tokens).context("failed to parse generated harness lib.rs tokens")?;
+
+    let lib_path = context.output.join("src").join("lib.rs");
+    let lib_src = prettier_please::unparse(&lib_ast);
+
+    crate::write_if_changed(&lib_path, lib_src)?;
+
+    Ok(())
+}
+
+struct ImportStubs {
+    /// Traits an implementation of the imports must implement
+    bounds: Vec<TokenStream>,
+    /// Implementations of these traits for `Stubs`
+    impls: Vec<TokenStream>,
+    /// Paths of the imported interfaces to add to the linker
+    linked_interfaces: Vec<TokenStream>,
+    /// Whether the world imports functions or resources directly
+    has_world_imports: bool,
+    /// Whether the world imports wasi:logging/logging itself
+    imports_logging: bool,
+}
+
+/// Generates the `Stubs` implementation of the traits `bindgen!` generates for the world's
+/// imports. WASI interfaces are implemented by wasmtime, so they are skipped.
+fn generate_import_stubs(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+) -> anyhow::Result<ImportStubs> {
+    let world = &context.resolve.worlds[context.world];
+
+    let mut bounds = Vec::new();
+    let mut impls = Vec::new();
+    let mut linked_interfaces = Vec::new();
+    let mut has_world_imports = false;
+    let mut imports_logging = false;
+
+    let mut world_functions = Vec::new();
+    let mut world_resources = Vec::new();
+
+    for (key, item) in &world.imports {
+        match item {
+            WorldItem::Interface { id, .. } => {
+                let interface_id = *id;
+                if paths.wasi.contains(&interface_id) {
+                    continue;
+                }
+                let interface = &context.resolve.interfaces[interface_id];
+                let wit_name = context.resolve.name_world_key(key);
+                if wit_name.starts_with("wasi:logging/logging") {
+                    imports_logging = true;
+                }
+
+                let path = paths.path(interface_id)?;
+                linked_interfaces.push(path.clone());
+
+                let mut methods = Vec::new();
+                let mut resource_methods = BTreeMap::<TypeId, Vec<TokenStream>>::new();
+                for function in interface.functions.values() {
+                    let stub = generate_import_stub(context, paths, &wit_name, function)?;
+                    match function.kind.resource() {
+                        None => methods.push(stub),
+                        Some(resource) => resource_methods.entry(resource).or_default().push(stub),
+                    }
+                }
+                for type_id in interface.types.values() {
+                    if matches!(context.resolve.types[*type_id].kind, TypeDefKind::Resource) {
+                        resource_methods.entry(*type_id).or_default();
+                    }
+                }
+
+                for (resource, methods) in resource_methods {
+                    impls.push(generate_resource_stub(
+                        context,
+                        quote! { #path:: },
+                        resource,
+                        methods,
+                    )?);
+                }
+
+                bounds.push(quote! { #path::Host });
+                impls.push(quote! {
+                    impl #path::Host for Stubs {
+                        #(#methods)*
+                    }
+                });
+            }
+            WorldItem::Function(function) => {
+                has_world_imports = true;
+                let stub = generate_import_stub(context, paths, &context.world_name, function)?;
+                world_functions.push((function, stub));
+            }
+            WorldItem::Type { id, .. } => {
+                if matches!(context.resolve.types[*id].kind, TypeDefKind::Resource) {
+                    has_world_imports = true;
+                    world_resources.push(*id);
+                }
+            }
+        }
+    }
+
+    // World-level resource methods are listed among the world's imported functions
+    let mut world_resource_methods = BTreeMap::<TypeId, Vec<TokenStream>>::new();
+    for resource in world_resources {
+        world_resource_methods.entry(resource).or_default();
+    }
+    let mut freestanding_world_functions = Vec::new();
+    for (function, stub) in world_functions {
+        match function.kind.resource() {
+            None => freestanding_world_functions.push(stub),
+            Some(resource) => world_resource_methods
+                .entry(resource)
+                .or_default()
+                .push(stub),
+        }
+    }
+
+    for (resource, methods) in world_resource_methods {
+        let resource_name = context.resolve.types[resource]
+            .name
+            .as_ref()
+            .ok_or_else(|| anyhow!("Imported resource does not have a name"))?;
+        let host_trait = Ident::new(
+            &format!("Host{}", resource_name.to_upper_camel_case()),
+            Span::call_site(),
+        );
+        bounds.push(quote! { bindings::#host_trait });
+        impls.push(generate_resource_stub(
+            context,
+            quote! { bindings:: },
+            resource,
+            methods,
+        )?);
+    }
+
+    if has_world_imports {
+        let imports_trait = Ident::new(
+            &format!("{}Imports", context.world_name.to_upper_camel_case()),
+            Span::call_site(),
+        );
+        bounds.push(quote! { bindings::#imports_trait });
+        impls.push(quote! {
+            impl bindings::#imports_trait for Stubs {
+                #(#freestanding_world_functions)*
+            }
+        });
+    }
+
+    Ok(ImportStubs {
+        bounds,
+        impls,
+        linked_interfaces,
+        has_world_imports,
+        imports_logging,
+    })
+}
+
+/// Generates the `Host<Resource>` trait implementation of `Stubs` for an imported resource
+fn generate_resource_stub(
+    context: &GeneratorContext<'_>,
+    path_prefix: TokenStream,
+    resource: TypeId,
+    methods: Vec<TokenStream>,
+) -> anyhow::Result<TokenStream> {
+    let resource_name = context.resolve.types[resource]
+        .name
+        .as_ref()
+        .ok_or_else(|| anyhow!("Imported resource does not have a name"))?
+        .to_upper_camel_case();
+    let host_trait = Ident::new(&format!("Host{resource_name}"), Span::call_site());
+    let resource_ident = Ident::new(&resource_name, Span::call_site());
+
+    Ok(quote! {
+        impl #path_prefix #host_trait for Stubs {
+            #(#methods)*
+
+            async fn drop(
+                &mut self,
+                _rep: wasmtime::component::Resource<#path_prefix #resource_ident>,
+            ) -> wasmtime::Result<()> {
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Generates a method of an import trait returning a "not implemented" error
+fn generate_import_stub(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+    owner_name: &str,
+    function: &Function,
+) -> anyhow::Result<TokenStream> {
+    let name = match function.kind {
+        FunctionKind::Constructor(_) => "new".to_string(),
+        _ => escape_rust_ident(&function.item_name().to_snake_case()),
+    };
+    let name = Ident::new(&name, Span::call_site());
+
+    let mut params = Vec::new();
+    for param in &function.params {
+        let (param_name, param_type) = (&param.name, &param.ty);
+        let param_name = Ident::new(
+            &format!("_{}", escape_rust_ident(&param_name.to_snake_case())),
+            Span::call_site(),
+        );
+        let param_type = host_type(context, paths, param_type, false)?;
+        params.push(quote! { #param_name: #param_type });
+    }
+    let result = match &function.result {
+        Some(ty) => host_type(context, paths, ty, false)?,
+        None => quote! { () },
+    };
+
+    let qualified_name = LitStr::new(
+        &format!("{owner_name}#{}", function.name),
+        Span::call_site(),
+    );
+
+    Ok(quote! {
+        async fn #name(&mut self, #(#params),*) -> wasmtime::Result<#result> {
+            Err(not_implemented(#qualified_name))
+        }
+    })
+}
+
+/// Generates a typed helper method on `HarnessInstance` for each export of the world
+fn generate_export_helpers(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+) -> anyhow::Result<Vec<TokenStream>> {
+    let world = &context.resolve.worlds[context.world];
+    let mut result = Vec::new();
+
+    // Exported interfaces sharing their name get their helpers prefixed with the package too
+    let mut interface_name_counts = BTreeMap::<&str, usize>::new();
+    for item in world.exports.values() {
+        if let WorldItem::Interface { id, .. } = item
+            && let Some(name) = &context.resolve.interfaces[*id].name
+        {
+            *interface_name_counts.entry(name).or_default() += 1;
+        }
+    }
+
+    for (key, item) in &world.exports {
+        match item {
+            WorldItem::Function(function) => {
+                let helper_name = escape_rust_ident(&function.name.to_snake_case());
+                result.push(generate_export_helper(
+                    context,
+                    paths,
+                    &helper_name,
+                    quote! { self.bindings },
+                    function,
+                )?);
+            }
+            WorldItem::Interface { id, .. } => {
+                let interface = &context.resolve.interfaces[*id];
+                let (accessor, prefix) = match key {
+                    WorldKey::Name(name) => {
+                        (escape_rust_ident(&name.to_snake_case()), name.clone())
+                    }
+                    WorldKey::Interface(_) => {
+                        let package_id = interface
+                            .package
+                            .ok_or_else(|| anyhow!("Exported interface does not have a package"))?;
+                        let name = interface
+                            .name
+                            .as_ref()
+                            .ok_or_else(|| anyhow!("Exported interface does not have a name"))?;
+                        let package = &context.resolve.packages[package_id];
+                        let accessor = format!(
+                            "{}_{}_{}",
+                            package.name.namespace.to_snake_case(),
+                            package_module_name(context, package_id),
+                            escape_rust_ident(&name.to_snake_case())
+                        );
+                        let prefix = if interface_name_counts.get(name.as_str()) > Some(&1) {
+                            accessor.clone()
+                        } else {
+                            name.clone()
+                        };
+                        (accessor, prefix)
+                    }
+                };
+                let accessor = Ident::new(&accessor, Span::call_site());
+
+                for function in interface.functions.values() {
+                    let (helper_name, guest) = match function.kind.resource() {
+                        None => (
+                            format!("{prefix}-{}", function.item_name()),
+                            quote! { self.bindings.#accessor() },
+                        ),
+                        Some(resource) => {
+                            let resource_name = context.resolve.types[resource]
+                                .name
+                                .as_ref()
+                                .ok_or_else(|| anyhow!("Exported resource does not have a name"))?;
+                            let item_name = match function.kind {
+                                FunctionKind::Constructor(_) => "new",
+                                _ => function.item_name(),
+                            };
+                            let resource_accessor =
+                                Ident::new(&resource_name.to_snake_case(), Span::call_site());
+                            (
+                                format!("{prefix}-{resource_name}-{item_name}"),
+                                quote! { self.bindings.#accessor().#resource_accessor() },
+                            )
+                        }
+                    };
+                    result.push(generate_export_helper(
+                        context,
+                        paths,
+                        &helper_name.to_snake_case(),
+                        guest,
+                        function,
+                    )?);
+                }
+            }
+            WorldItem::Type { .. } => {}
+        }
+    }
+
+    Ok(result)
+}
+
+fn generate_export_helper(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+    helper_name: &str,
+    guest: TokenStream,
+    function: &Function,
+) -> anyhow::Result<TokenStream> {
+    let helper_name = Ident::new(helper_name, Span::call_site());
+    let call_name = Ident::new(
+        &format!("call_{}", function.item_name().to_snake_case()),
+        Span::call_site(),
+    );
+
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for param in &function.params {
+        let (param_name, param_type) = (&param.name, &param.ty);
+        let param_name = Ident::new(
+            &escape_rust_ident(&param_name.to_snake_case()),
+            Span::call_site(),
+        );
+        let param_type = host_type(context, paths, param_type, true)?;
+        params.push(quote! { #param_name: #param_type });
+        args.push(param_name);
+    }
+    let result = match &function.result {
+        Some(ty) => host_type(context, paths, ty, false)?,
+        None => quote! { () },
+    };
+
+    let doc = format!(" Calls the exported `{}` function", function.name);
+
+    Ok(quote! {
+        #[doc = #doc]
+        pub async fn #helper_name(&mut self, #(#params),*) -> wasmtime::Result<#result> {
+            #guest.#call_name(&mut self.store, #(#args),*).await
+        }
+    })
+}
+
+/// Rust module paths `bindgen!` generates for the interfaces of the world
+struct InterfacePaths {
+    paths: BTreeMap<InterfaceId, TokenStream>,
+    /// WASI interfaces mapped to the wasmtime-wasi bindings
+    remapped: BTreeMap<InterfaceId, TokenStream>,
+    /// Imported WASI interfaces implemented by wasmtime-wasi and wasmtime-wasi-http
+    wasi: BTreeSet<InterfaceId>,
+    /// `with` entries of the `bindgen!` invocation, keyed by the versioned interface name
+    remaps: BTreeMap<String, TokenStream>,
+}
+
+impl InterfacePaths {
+    fn new(context: &GeneratorContext<'_>) -> Self {
+        let world = &context.resolve.worlds[context.world];
+
+        let mut paths = BTreeMap::new();
+        let mut remapped = BTreeMap::new();
+        let mut remaps = BTreeMap::new();
+        let mut wasi = BTreeSet::new();
+
+        let imports = world.imports.iter().map(|entry| (entry, false));
+        let exports = world.exports.iter().map(|entry| (entry, true));
+        for ((key, item), is_export) in imports.chain(exports) {
+            let WorldItem::Interface { id, .. } = item else {
+                continue;
+            };
+            let interface = &context.resolve.interfaces[*id];
+
+            let mut segments = Vec::new();
+            if is_export {
+                segments.push("exports".to_string());
+            }
+            match key {
+                WorldKey::Name(name) => segments.push(escape_rust_ident(&name.to_snake_case())),
+                WorldKey::Interface(_) => {
+                    if let (Some(package_id), Some(name)) = (interface.package, &interface.name) {
+                        let package = &context.resolve.packages[package_id];
+                        let namespace = package.name.namespace.to_snake_case();
+                        let module = package_module_name(context, package_id);
+                        let name = escape_rust_ident(&name.to_snake_case());
+
+                        if !is_export && is_wasmtime_wasi_package(&package.name.namespace, &module)
+                        {
+                            wasi.insert(*id);
+                        }
+                        if !is_export
+                            && let Some(rust_path) =
+                                wasi_remap(&package.name.namespace, &module, &name)
+                        {
+                            remaps.insert(
+                                package.name.interface_id(interface.name.as_ref().unwrap()),
+                                rust_path.clone(),
+                            );
+                            remapped.insert(*id, rust_path);
+                            continue;
+                        }
+
+                        segments.extend([namespace, module, name]);
+                    }
+                }
+            }
+
+            let segments = segments
+                .iter()
+                .map(|segment| Ident::new(segment, Span::call_site()));
+            paths.insert(*id, quote! { bindings #(::#segments)* });
+        }
+
+        Self {
+            paths,
+            remapped,
+            wasi,
+            remaps,
+        }
+    }
+
+    fn path(&self, interface_id: InterfaceId) -> anyhow::Result<TokenStream> {
+        self.remapped
+            .get(&interface_id)
+            .or_else(|| self.paths.get(&interface_id))
+            .cloned()
+            .ok_or_else(|| anyhow!("Interface {interface_id:?} is not part of the world"))
+    }
+}
+
+/// Whether a WASI package is implemented by wasmtime-wasi or wasmtime-wasi-http
+fn is_wasmtime_wasi_package(namespace: &str, module: &str) -> bool {
+    namespace == "wasi"
+        && matches!(
+            module,
+            "cli" | "clocks" | "filesystem" | "io" | "random" | "sockets" | "http"
+        )
+}
+
+/// Maps WASI interfaces to the corresponding wasmtime-wasi bindings.
+///
+/// wasi:http is not remapped, because `bindgen!` cannot generate the world's linker function
+/// for the feature-gated parts of the wasmtime-wasi-http bindings. Its interfaces still get
+/// linked by wasmtime-wasi-http, and only their types are generated again.
+fn wasi_remap(namespace: &str, module: &str, name: &str) -> Option<TokenStream> {
+    if !is_wasmtime_wasi_package(namespace, module) || module == "http" {
+        return None;
+    }
+    let module_ident = Ident::new(module, Span::call_site());
+    let name_ident = Ident::new(name, Span::call_site());
+    Some(quote! { wasmtime_wasi::p2::bindings::#module_ident::#name_ident })
+}
+
+/// Name of the Rust module `bindgen!` generates for a package, mangling the version into it
+/// when multiple versions of the same package are present
+fn package_module_name(context: &GeneratorContext<'_>, package_id: PackageId) -> String {
+    let package = &context.resolve.packages[package_id];
+    let base = package.name.name.to_snake_case();
+    let has_other_versions = context.resolve.packages.iter().any(|(id, other)| {
+        id != package_id
+            && other.name.namespace == package.name.namespace
+            && other.name.name == package.name.name
+    });
+    match &package.name.version {
+        Some(version) if has_other_versions => {
+            let version = version
+                .to_string()
+                .replace(['.', '-', '+'], "_")
+                .to_snake_case();
+            format!("{base}{version}")
+        }
+        _ => base,
+    }
+}
+
+/// The Rust type `bindgen!` uses for a WIT type on the host side.
+///
+/// With `borrowed` set, the type is the one of an exported function's parameter, where strings
+/// and lists are passed as slices and named types containing them by reference.
+fn host_type(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+    ty: &Type,
+    borrowed: bool,
+) -> anyhow::Result<TokenStream> {
+    Ok(match ty {
+        Type::Bool => quote! { bool },
+        Type::U8 => quote! { u8 },
+        Type::U16 => quote! { u16 },
+        Type::U32 => quote! { u32 },
+        Type::U64 => quote! { u64 },
+        Type::S8 => quote! { i8 },
+        Type::S16 => quote! { i16 },
+        Type::S32 => quote! { i32 },
+        Type::S64 => quote! { i64 },
+        Type::F32 => quote! { f32 },
+        Type::F64 => quote! { f64 },
+        Type::Char => quote! { char },
+        Type::String if borrowed => quote! { &str },
+        Type::String => quote! { String },
+        Type::ErrorContext => bail!("error-context is not supported by the host harness"),
+        Type::Id(type_id) => host_type_id(context, paths, *type_id, borrowed)?,
+    })
+}
+
+fn host_type_id(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+    type_id: TypeId,
+    borrowed: bool,
+) -> anyhow::Result<TokenStream> {
+    let type_def = &context.resolve.types[type_id];
+
+    if let Some(name) = &type_def.name {
+        let ident = Ident::new(&name.to_upper_camel_case(), Span::call_site());
+        let path = match type_def.owner {
+            TypeOwner::Interface(interface_id) => {
+                let interface_path = paths.path(interface_id)?;
+                quote! { #interface_path::#ident }
+            }
+            TypeOwner::World(_) | TypeOwner::None => quote! { bindings::#ident },
+        };
+        let has_list = context.types.get(type_id).has_list;
+        return Ok(if borrowed && has_list {
+            quote! { &#path }
+        } else {
+            path
+        });
+    }
+
+    Ok(match &type_def.kind {
+        TypeDefKind::List(element) => {
+            let element = host_type(context, paths, element, false)?;
+            if borrowed {
+                quote! { &[#element] }
+            } else {
+                quote! { Vec<#element> }
+            }
+        }
+        TypeDefKind::Option(inner) => {
+            let inner = host_type(context, paths, inner, borrowed)?;
+            quote! { Option<#inner> }
+        }
+        TypeDefKind::Result(result) => {
+            let ok = match &result.ok {
+                Some(ty) => host_type(context, paths, ty, borrowed)?,
+                None => quote! { () },
+            };
+            let err = match &result.err {
+                Some(ty) => host_type(context, paths, ty, borrowed)?,
+                None => quote! { () },
+            };
+            quote! { Result<#ok, #err> }
+        }
+        TypeDefKind::Tuple(tuple) => {
+            let types = tuple
+                .types
+                .iter()
+                .map(|ty| host_type(context, paths, ty, borrowed))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            quote! { (#(#types,)*) }
+        }
+        TypeDefKind::Handle(Handle::Own(resource) | Handle::Borrow(resource)) => {
+            host_handle_type(context, paths, *resource)?
+        }
+        TypeDefKind::Type(ty) => host_type(context, paths, ty, borrowed)?,
+        other => bail!(
+            "Unsupported anonymous type in the host harness: {}",
+            other.as_str()
+        ),
+    })
+}
+
+/// Exported resources are represented by `ResourceAny`, imported ones by `Resource<T>`
+fn host_handle_type(
+    context: &GeneratorContext<'_>,
+    paths: &InterfacePaths,
+    resource: TypeId,
+) -> anyhow::Result<TokenStream> {
+    let mut definition = resource;
+    while let TypeDefKind::Type(Type::Id(aliased)) = context.resolve.types[definition].kind {
+        definition = aliased;
+    }
+
+    let is_exported = match context.resolve.types[definition].owner {
+        TypeOwner::Interface(interface_id) => context.is_exported_interface(interface_id),
+        _ => false,
+    };
+    if is_exported {
+        Ok(quote! { wasmtime::component::ResourceAny })
+    } else {
+        let resource_type = host_type_id(context, paths, resource, false)?;
+        Ok(quote! { wasmtime::component::Resource<#resource_type> })
+    }
+}
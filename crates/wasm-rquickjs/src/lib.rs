@@ -2,8 +2,13 @@ use crate::conversions::generate_conversions;
 use crate::exports::generate_export_impls;
 use crate::imports::generate_import_modules;
 use crate::javascript::escape_js_ident;
-use crate::skeleton::{copy_skeleton_lock, copy_skeleton_sources, generate_cargo_toml};
-use crate::wit::{add_get_script_import, add_wizer_init_export};
+use crate::skeleton::{
+    copy_skeleton_build_script, copy_skeleton_lock, copy_skeleton_rquickjs_sys,
+    copy_skeleton_sources, generate_cargo_toml, write_intl_locales,
+};
+use crate::wit::{
+    add_get_script_import, add_heap_snapshot_export, add_run_tests_export, add_wizer_init_export,
+};
 use anyhow::{Context, anyhow};
 use camino::{Utf8Path, Utf8PathBuf};
 use heck::{ToLowerCamelCase, ToSnakeCase, ToUpperCamelCase};
@@ -29,7 +34,9 @@ const WASI_REMAP_NAMESPACES: &[(&str, &str)] = &[
 ];
 
 mod conversions;
+mod coverage;
 mod exports;
+mod host_harness;
 mod imports;
 mod inject;
 mod javascript;
@@ -110,6 +117,31 @@ impl JsModuleSpec {
     }
 }
 
+/// Additional options for [`generate_wrapper_crate_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct WrapperCrateOptions {
+    /// Name of the case of a `result` error type (variant or enum) that thrown JS errors not
+    /// matching any other case get mapped to. Without it, such errors cause a trap.
+    pub error_catch_all_case: Option<String>,
+    /// BCP-47 locales whose ICU4X data gets baked into the component when it is built with the
+    /// `intl-full` feature. Only the language subtags matter, and English is always included.
+    /// When empty, the data for every locale is included.
+    pub intl_locales: Vec<String>,
+    /// Adds a `wasm-rquickjs-heap-snapshot: func() -> string` export to the world, returning a
+    /// heap snapshot of the JS runtime in the Chrome DevTools `.heapsnapshot` format.
+    pub heap_snapshot_export: bool,
+    /// Adds a `run-tests: func(filter: option<string>) -> list<test-result>` export to the world.
+    /// Top-level `node:test` tests and suites of the JS modules are then only registered while
+    /// the modules are evaluated, and the export runs the ones whose full name matches the
+    /// `filter` regular expression, returning the result of each test.
+    pub run_tests_export: bool,
+    /// Instruments the embedded JS modules to collect statement, branch and function coverage,
+    /// written as Istanbul JSON and LCOV reports on `process.exit`, `v8.takeCoverage()` and
+    /// `v8.stopCoverage()`. Modules injected into a binary slot or loaded through composition are
+    /// not instrumented.
+    pub coverage: bool,
+}
+
 /// Generates a Rust wrapper crate for a combination of a WIT package and a JavaScript module.
 ///
 /// The `wit` parameter should point to a WIT root (holding the WIT package of the component, with
@@ -128,6 +160,23 @@ pub fn generate_wrapper_crate(
     js_modules: &[JsModuleSpec],
     output: &Utf8Path,
     world: Option<&str>,
+) -> anyhow::Result<()> {
+    generate_wrapper_crate_with_options(
+        wit,
+        js_modules,
+        output,
+        world,
+        &WrapperCrateOptions::default(),
+    )
+}
+
+/// Generates a Rust wrapper crate like [`generate_wrapper_crate`], with additional options.
+pub fn generate_wrapper_crate_with_options(
+    wit: &Utf8Path,
+    js_modules: &[JsModuleSpec],
+    output: &Utf8Path,
+    world: Option<&str>,
+    options: &WrapperCrateOptions,
 ) -> anyhow::Result<()> {
     // Making sure the target directories exists
     std::fs::create_dir_all(output).context("Failed to create output directory")?;
@@ -146,6 +195,12 @@ pub fn generate_wrapper_crate(
 
     // Copying the skeleton files
     copy_skeleton_sources(context.output).context("Failed to copy skeleton sources")?;
+    copy_skeleton_build_script(context.output).context("Failed to copy skeleton build script")?;
+    copy_skeleton_rquickjs_sys(context.output).context("Failed to copy patched rquickjs-sys")?;
+
+    // Selecting the locales baked in by the `intl-full` feature
+    write_intl_locales(context.output, &options.intl_locales)
+        .context("Failed to write the Intl locale selection")?;
 
     // Copying the WIT package to the output directory
     copy_wit_directory(wit, &context.output.join("wit"))
@@ -160,12 +215,22 @@ pub fn generate_wrapper_crate(
     add_wizer_init_export(&context.output.join("wit"), world)
         .context("Failed to add wizer-initialize export to the WIT world")?;
 
+    if options.heap_snapshot_export {
+        add_heap_snapshot_export(&context.output.join("wit"), world)
+            .context("Failed to add the heap snapshot export to the WIT world")?;
+    }
+
+    if options.run_tests_export {
+        add_run_tests_export(&context.output.join("wit"), world)
+            .context("Failed to add the run-tests export to the WIT world")?;
+    }
+
     // Re-resolve the WIT package after modifications (wizer-initialize export was added)
     let modified_wit = output.join("wit");
-    let context = GeneratorContext::new(output, &modified_wit, world)?;
+    let context = GeneratorContext::new(output, &modified_wit, world)?.with_options(options);
 
     // Copying the JavaScript module to the output directory
-    copy_js_modules(js_modules, context.output)
+    copy_js_modules(js_modules, context.output, options.coverage)
         .context("Failed to copy JavaScript module to output directory")?;
 
     // Generating the lib.rs file implementing the component exports
@@ -211,6 +276,37 @@ pub fn generate_dts(
     Ok(result)
 }
 
+/// Generates a Rust crate for testing the component from the host side.
+///
+/// The crate runs wasmtime's `bindgen!` on the given (or default) world of the WIT package, links
+/// WASI, WASI HTTP and a mock of `wasi:logging`, provides stubs for the world's imports that
+/// can be replaced by custom implementations, and typed helpers calling each export.
+///
+/// The `output` parameter is the root directory where the generated crate is placed.
+pub fn generate_host_harness(
+    wit: &Utf8Path,
+    output: &Utf8Path,
+    world: Option<&str>,
+) -> anyhow::Result<()> {
+    // Making sure the target directories exist
+    std::fs::create_dir_all(output.join("src")).context("Failed to create output/src directory")?;
+
+    // Resolving the WIT package
+    let context = GeneratorContext::new(output, wit, world)?;
+
+    // Generating the Cargo.toml file
+    host_harness::generate_harness_cargo_toml(&context)?;
+
+    // Copying the WIT package to the output directory
+    copy_wit_directory(wit, &context.output.join("wit"))
+        .context("Failed to copy WIT package to output directory")?;
+
+    // Generating the lib.rs file with the bindings and the harness
+    host_harness::generate_harness_lib(&context).context("Failed to generate the host harness")?;
+
+    Ok(())
+}
+
 struct GeneratorContext<'a> {
     output: &'a Utf8Path,
     #[allow(dead_code)]
@@ -223,6 +319,7 @@ struct GeneratorContext<'a> {
     visited_types: RefCell<BTreeSet<TypeId>>,
     world_name: String,
     types: wit_bindgen_core::Types,
+    options: WrapperCrateOptions,
 }
 
 impl<'a> GeneratorContext<'a> {
@@ -250,9 +347,15 @@ impl<'a> GeneratorContext<'a> {
             visited_types: RefCell::new(BTreeSet::new()),
             world_name,
             types,
+            options: WrapperCrateOptions::default(),
         })
     }
 
+    fn with_options(mut self, options: &WrapperCrateOptions) -> Self {
+        self.options = options.clone();
+        self
+    }
+
     fn root_package_name(&self) -> String {
         self.resolve.packages[self.root_package].name.to_string()
     }
@@ -556,16 +659,34 @@ fn copy_dir_if_changed(src: &std::path::Path, dst: &std::path::Path) -> std::io:
     Ok(())
 }
 
-/// Copies the JS module files to `<output>/src/<name>.js` or generates slot files.
-fn copy_js_modules(js_modules: &[JsModuleSpec], output: &Utf8Path) -> anyhow::Result<()> {
+/// Copies the JS module files to `<output>/src/<name>.js`, instrumenting them for code coverage
+/// if requested, or generates slot files.
+fn copy_js_modules(
+    js_modules: &[JsModuleSpec],
+    output: &Utf8Path,
+    coverage: bool,
+) -> anyhow::Result<()> {
     let mut slot_index: u32 = 0;
     for module in js_modules {
         match &module.mode {
             EmbeddingMode::EmbedFile(source) => {
                 let filename = module.file_name();
                 let js_dest = output.join("src").join(filename);
-                copy_if_changed(source, js_dest)
-                    .context(format!("Failed to copy JavaScript module {}", module.name))?;
+                if coverage {
+                    let js = std::fs::read_to_string(source)
+                        .context(format!("Failed to read JavaScript module {}", module.name))?;
+                    let path = source
+                        .canonicalize_utf8()
+                        .unwrap_or_else(|_| source.clone());
+                    let instrumented = coverage::instrument_module(&js, path.as_str()).context(
+                        format!("Failed to instrument JavaScript module {}", module.name),
+                    )?;
+                    write_if_changed(js_dest, instrumented)
+                        .context(format!("Failed to copy JavaScript module {}", module.name))?;
+                } else {
+                    copy_if_changed(source, js_dest)
+                        .context(format!("Failed to copy JavaScript module {}", module.name))?;
+                }
             }
             EmbeddingMode::BinarySlot => {
                 let slot_filename = module.name.replace('/', "_") + ".slot";
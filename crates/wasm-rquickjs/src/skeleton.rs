@@ -1,6 +1,7 @@
 use crate::GeneratorContext;
 use anyhow::anyhow;
 use camino::Utf8Path;
+use icu_locid::Locale;
 use include_dir::{Dir, include_dir};
 use toml_edit::{DocumentMut, value};
 
@@ -52,6 +53,70 @@ pub fn copy_skeleton_lock(output: &Utf8Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Copies the skeleton's `build.rs`, which bakes the ICU4X data for the `intl-full` feature.
+pub fn copy_skeleton_build_script(output: &Utf8Path) -> anyhow::Result<()> {
+    if let Some(build_script) = SKELETON.get_file("build.rs") {
+        let dest = output.join("build.rs");
+        crate::write_if_changed(dest, build_script.contents())?;
+    }
+    Ok(())
+}
+
+/// Copies the skeleton's patched `rquickjs-sys` crate, which the wrapper crate's `Cargo.toml`
+/// uses in place of the published one. Like the skeleton's own, its manifest is stored as
+/// `Cargo.toml_` and gets renamed.
+pub fn copy_skeleton_rquickjs_sys(output: &Utf8Path) -> anyhow::Result<()> {
+    let dir = SKELETON
+        .get_dir("rquickjs-sys")
+        .ok_or_else(|| anyhow!("Missing rquickjs-sys in skeleton"))?;
+    recursive_copy_renaming_manifest(dir, output)
+}
+
+fn recursive_copy_renaming_manifest(dir: &Dir, output: &Utf8Path) -> anyhow::Result<()> {
+    let dir_path = Utf8Path::from_path(dir.path())
+        .ok_or_else(|| anyhow!("Unexpected non-UTF-8 path in skeleton"))?;
+    std::fs::create_dir_all(output.join(dir_path))?;
+
+    for file in dir.files() {
+        let src_path = Utf8Path::from_path(file.path())
+            .ok_or_else(|| anyhow!("Unexpected non-UTF-8 path in skeleton"))?;
+        let dest_path = if src_path.file_name() == Some("Cargo.toml_") {
+            output.join(src_path.with_file_name("Cargo.toml"))
+        } else {
+            output.join(src_path)
+        };
+        crate::write_if_changed(dest_path, file.contents())?;
+    }
+
+    for dir in dir.dirs() {
+        recursive_copy_renaming_manifest(dir, output)?;
+    }
+
+    Ok(())
+}
+
+/// Writes `intl-locales.txt`, read by the build script to select the baked-in ICU4X locales.
+/// An empty selection produces an empty file, which includes every locale.
+pub fn write_intl_locales(output: &Utf8Path, locales: &[String]) -> anyhow::Result<()> {
+    let contents = intl_locales_file(locales)?;
+    crate::write_if_changed(output.join("intl-locales.txt"), contents)?;
+    Ok(())
+}
+
+/// Validates the selected locales, so that an invalid one is reported by the generator instead
+/// of failing the build script of the generated crate, and lists them in canonical form
+fn intl_locales_file(locales: &[String]) -> anyhow::Result<String> {
+    let mut contents = String::new();
+    for locale in locales {
+        let parsed: Locale = locale
+            .trim()
+            .parse()
+            .map_err(|err| anyhow!("Invalid Intl locale '{locale}': {err}"))?;
+        contents.push_str(&format!("{parsed}\n"));
+    }
+    Ok(contents)
+}
+
 /// Copies all source files from the skeleton directory to `<output>/src`.
 pub fn copy_skeleton_sources(output: &Utf8Path) -> anyhow::Result<()> {
     if let Some(src) = SKELETON.get_dir("src") {
@@ -93,3 +158,23 @@ fn recursive_copy_sources(dir: &Dir, output: &Utf8Path) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intl_locales_are_listed_in_canonical_form() {
+        let contents = intl_locales_file(&["de-de".to_string(), "ja".to_string()]).unwrap();
+        assert_eq!(contents, "de-DE\nja\n");
+    }
+
+    #[test]
+    fn invalid_intl_locale_is_rejected() {
+        let err = intl_locales_file(&["de_DE!".to_string()]).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid Intl locale 'de_DE!'"),
+            "{err}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::Context;
 use camino::Utf8Path;
-use wit_encoder::StandaloneFunc;
+use wit_encoder::{Interface, Package, StandaloneFunc, Type, TypeDef, World};
 use wit_parser::Resolve;
 
 pub fn add_get_script_import(wit_root: &Utf8Path, world: Option<&str>) -> anyhow::Result<()> {
@@ -66,6 +66,70 @@ pub fn add_get_script_import(wit_root: &Utf8Path, world: Option<&str>) -> anyhow
 }
 
 pub fn add_wizer_init_export(wit_root: &Utf8Path, world: Option<&str>) -> anyhow::Result<()> {
+    add_function_export(
+        wit_root,
+        world,
+        StandaloneFunc::new("wizer-initialize", false),
+    )
+}
+
+/// Name of the optional export returning a heap snapshot of the JS runtime
+pub const HEAP_SNAPSHOT_EXPORT: &str = "wasm-rquickjs-heap-snapshot";
+
+pub fn add_heap_snapshot_export(wit_root: &Utf8Path, world: Option<&str>) -> anyhow::Result<()> {
+    let mut func = StandaloneFunc::new(HEAP_SNAPSHOT_EXPORT, false);
+    func.set_result(Some(wit_encoder::Type::String));
+    add_function_export(wit_root, world, func)
+}
+
+/// Name of the optional export running the `node:test` tests of the JS modules
+pub const RUN_TESTS_EXPORT: &str = "run-tests";
+
+/// Name of the interface defining the result types of [`RUN_TESTS_EXPORT`]
+pub const RUN_TESTS_INTERFACE: &str = "wasm-rquickjs-tests";
+
+pub fn add_run_tests_export(wit_root: &Utf8Path, world: Option<&str>) -> anyhow::Result<()> {
+    let mut func = StandaloneFunc::new(RUN_TESTS_EXPORT, false);
+    func.set_params(("filter", Type::option(Type::String)));
+    func.set_result(Some(Type::list(Type::named("test-result"))));
+
+    update_world(wit_root, world, |package, world| {
+        // Worlds cannot define types, so the result types live in their own interface
+        let mut interface = Interface::new(RUN_TESTS_INTERFACE);
+        interface.type_def(TypeDef::enum_(
+            "test-status",
+            ["pass", "fail", "skip", "todo"],
+        ));
+        interface.type_def(TypeDef::record(
+            "test-result",
+            [
+                ("name", Type::String),
+                ("status", Type::named("test-status")),
+                ("duration-ms", Type::F64),
+                ("failure", Type::option(Type::String)),
+            ],
+        ));
+        package.interface(interface);
+
+        world.use_type(RUN_TESTS_INTERFACE, "test-result", None);
+        world.function_export(func);
+    })
+}
+
+fn add_function_export(
+    wit_root: &Utf8Path,
+    world: Option<&str>,
+    func: StandaloneFunc,
+) -> anyhow::Result<()> {
+    update_world(wit_root, world, |_, world| world.function_export(func))
+}
+
+/// Rewrites the root WIT package after applying `update` to it and the selected world
+fn update_world(
+    wit_root: &Utf8Path,
+    world: Option<&str>,
+    update: impl FnOnce(&mut Package, &mut World),
+) -> anyhow::Result<()> {
     let mut resolve = Resolve::new();
     let (root_package_id, source_map) = resolve
         .push_path(wit_root)
@@ -99,12 +163,11 @@ pub fn add_wizer_init_export(wit_root: &Utf8Path, world: Option<&str>) -> anyhow
             )
         })?;
 
-    let world = root_package
-        .items_mut()
-        .iter_mut()
-        .find_map(|item| match item {
-            wit_encoder::PackageItem::World(w) if w.name().raw_name() == world_name => Some(w),
-            _ => None,
+    let world_index = root_package
+        .items()
+        .iter()
+        .position(|item| {
+            matches!(item, wit_encoder::PackageItem::World(w) if w.name().raw_name() == world_name)
         })
         .ok_or_else(|| {
             anyhow::anyhow!(
@@ -114,8 +177,15 @@ pub fn add_wizer_init_export(wit_root: &Utf8Path, world: Option<&str>) -> anyhow
             )
         })?;
 
-    let wizer_init_func = StandaloneFunc::new("wizer-initialize", false);
-    world.function_export(wizer_init_func);
+    // Taken out of the package while updating, so both can be modified
+    let wit_encoder::PackageItem::World(mut world) = root_package.items_mut().remove(world_index)
+    else {
+        unreachable!()
+    };
+    update(&mut root_package, &mut world);
+    root_package
+        .items_mut()
+        .insert(world_index, wit_encoder::PackageItem::World(world));
 
     crate::write_if_changed(root_package_path[0], root_package.to_string()).context(format!(
         "Overwriting root WIT package at {:?}",
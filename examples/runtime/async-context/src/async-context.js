@@ -0,0 +1,174 @@
+import { AsyncLocalStorage } from 'node:async_hooks';
+import * as dc from 'node:diagnostics_channel';
+import * as domain from 'node:domain';
+import process from 'node:process';
+
+const als = new AsyncLocalStorage();
+
+function sleep(ms) {
+    return new Promise(resolve => setTimeout(resolve, ms));
+}
+
+function requestId() {
+    return als.getStore()?.id ?? 'none';
+}
+
+async function handle(id, delays) {
+    const seen = [];
+    for (const delay of delays) {
+        await sleep(delay);
+        seen.push(requestId());
+        await null;
+        seen.push(requestId());
+    }
+    return `${id}: ${seen.join(',')}`;
+}
+
+export async function concurrentRuns() {
+    const results = await Promise.all([
+        als.run({ id: 'a' }, () => handle('a', [5, 1, 5])),
+        als.run({ id: 'b' }, () => handle('b', [1, 5, 1])),
+    ]);
+    results.push(`outside: ${requestId()}`);
+    return results.join('\n');
+}
+
+async function fails() {
+    await sleep(1);
+    throw new Error('failed');
+}
+
+export async function rejectedAwait() {
+    const log = [];
+    await als.run({ id: 'r' }, async () => {
+        try {
+            await fails();
+        } catch (e) {
+            log.push(`catch ${e.message}: ${requestId()}`);
+        } finally {
+            log.push(`finally: ${requestId()}`);
+        }
+        await Promise.reject(new Error('again')).catch(() => {});
+        log.push(`after: ${requestId()}`);
+    });
+    log.push(`outside: ${requestId()}`);
+    return log.join('\n');
+}
+
+async function* ticks(count) {
+    for (let i = 0; i < count; i++) {
+        await sleep(1);
+        yield i;
+    }
+}
+
+export async function forAwait() {
+    const log = [];
+    const loop = async (id) => {
+        outer: for await (const i of ticks(3)) {
+            for await (const j of [Promise.resolve(i), i * 10]) {
+                log.push(`${id} ${j}: ${requestId()}`);
+                if (j === 10) continue outer;
+            }
+        }
+        log.push(`${id} done: ${requestId()}`);
+    };
+    await Promise.all([
+        als.run({ id: 'x' }, () => loop('x')),
+        als.run({ id: 'y' }, () => loop('y')),
+    ]);
+    log.push(`outside: ${requestId()}`);
+    return log.join('\n');
+}
+
+export async function asyncGenerator() {
+    const log = [];
+    async function* generate() {
+        log.push(`start: ${requestId()}`);
+        const received = yield 1;
+        log.push(`received ${received}: ${requestId()}`);
+        await sleep(1);
+        log.push(`after sleep: ${requestId()}`);
+        yield 2;
+    }
+    const generator = als.run({ id: 'g' }, () => generate());
+    await als.run({ id: 'first' }, () => generator.next());
+    await als.run({ id: 'second' }, () => generator.next('value'));
+    log.push(`outside: ${requestId()}`);
+    return log.join('\n');
+}
+
+export async function enterWith() {
+    const log = [];
+    async function task() {
+        als.enterWith({ id: 'entered' });
+        await sleep(1);
+        log.push(`task: ${requestId()}`);
+    }
+    await als.run({ id: 'outer' }, async () => {
+        await task();
+        log.push(`outer: ${requestId()}`);
+    });
+    log.push(`outside: ${requestId()}`);
+    return log.join('\n');
+}
+
+export async function callbacks() {
+    const log = [];
+    await als.run({ id: 'c' }, () => new Promise((resolve) => {
+        process.nextTick(() => {
+            log.push(`nextTick: ${requestId()}`);
+            queueMicrotask(() => {
+                setTimeout(() => {
+                    log.push(`setTimeout: ${requestId()}`);
+                    resolve();
+                }, 1);
+            });
+        });
+    }));
+    const run = als.run({ id: 'snapshot' }, () => AsyncLocalStorage.snapshot());
+    log.push(`snapshot: ${run(requestId)}`);
+    const bound = als.run({ id: 'bound' }, () => AsyncLocalStorage.bind(requestId));
+    log.push(`bind: ${bound()}`);
+    log.push(`outside: ${requestId()}`);
+    return log.join('\n');
+}
+
+export async function bindStore() {
+    const log = [];
+    const channel = dc.tracingChannel('async-context');
+    channel.start.bindStore(als, (context) => ({ id: context.name }));
+    channel.subscribe({ start() {}, end() {}, asyncStart() {}, asyncEnd() {}, error() {} });
+    const result = await channel.tracePromise(async () => {
+        log.push(`start: ${requestId()}`);
+        await sleep(1);
+        log.push(`after await: ${requestId()}`);
+        return 'traced';
+    }, { name: 'span' });
+    log.push(`result ${result}: ${requestId()}`);
+    channel.start.unbindStore(als);
+    return log.join('\n');
+}
+
+export async function domains() {
+    const log = [];
+    const d = domain.create();
+    await d.run(async () => {
+        await sleep(1);
+        log.push(`after await: ${process.domain === d}`);
+        await Promise.resolve().then(() => {
+            log.push(`then: ${process.domain === d}`);
+        });
+    });
+    log.push(`outside: ${process.domain === d}`);
+    return log.join('\n');
+}
+
+export async function evaluatedCode() {
+    const log = [];
+    const evaluated = (0, eval)('(async (sleep, requestId) => { await sleep(1); return requestId(); })');
+    const result = await als.run({ id: 'e' }, () => evaluated(sleep, requestId));
+    log.push(`evaluated: ${result}`);
+    log.push(`outside: ${requestId()}`);
+    return log.join('\n');
+}
@@ -0,0 +1,173 @@
+import {
+    AsyncResource,
+    createHook,
+    executionAsyncId,
+    executionAsyncResource,
+    triggerAsyncId,
+} from 'node:async_hooks';
+import * as asyncHooks from 'node:async_hooks';
+import * as fs from 'node:fs';
+import * as net from 'node:net';
+import process from 'node:process';
+
+// Records the lifecycle events of the resources of the given types created by `fn`, directly or
+// from the callbacks of the resources it created
+function record(types, fn) {
+    const log = [];
+    const created = new Set();
+    const resources = new Map();
+    let running = false;
+    const hook = createHook({
+        init(asyncId, type, triggerAsyncId) {
+            if (!running && !created.has(triggerAsyncId)) {
+                return;
+            }
+            created.add(asyncId);
+            if (types.includes(type)) {
+                const trigger = resources.get(triggerAsyncId);
+                resources.set(asyncId, `${type}#${resources.size + 1}`);
+                log.push(`init ${resources.get(asyncId)}` + (trigger ? ` from ${trigger}` : ''));
+            }
+        },
+        before(asyncId) {
+            if (resources.has(asyncId)) {
+                log.push(`before ${resources.get(asyncId)}`);
+            }
+        },
+        after(asyncId) {
+            if (resources.has(asyncId)) {
+                log.push(`after ${resources.get(asyncId)}`);
+            }
+        },
+        destroy(asyncId) {
+            if (resources.has(asyncId)) {
+                log.push(`destroy ${resources.get(asyncId)}`);
+            }
+        },
+        promiseResolve(asyncId) {
+            if (resources.has(asyncId)) {
+                log.push(`resolve ${resources.get(asyncId)}`);
+            }
+        },
+    });
+    const current = () => resources.get(executionAsyncId()) ?? 'none';
+    hook.enable();
+    running = true;
+    const result = fn(log, current);
+    running = false;
+    // Waits for the result without the hook, so the promises doing so are not recorded
+    hook.disable();
+    try {
+        return result.then(
+            () => {
+                hook.disable();
+                return log.join('\n');
+            },
+            (e) => {
+                hook.disable();
+                throw e;
+            },
+        );
+    } finally {
+        hook.enable();
+    }
+}
+
+export function timers() {
+    return record(['Timeout', 'Immediate', 'TickObject', 'Microtask'], (log, current) =>
+        new Promise((resolve) => {
+            setTimeout(() => {
+                log.push(`timeout callback in ${current()}`);
+                setImmediate(() => {
+                    log.push(`immediate callback in ${current()}`);
+                    process.nextTick(() => {
+                        log.push(`tick callback in ${current()}`);
+                        queueMicrotask(() => {
+                            log.push(`microtask callback in ${current()}`);
+                            const cleared = setTimeout(() => {}, 10);
+                            clearTimeout(cleared);
+                            resolve();
+                        });
+                    });
+                });
+            }, 1);
+        }),
+    );
+}
+
+export function promises() {
+    return record(['PROMISE'], (log, current) => {
+        const resolved = new Promise((resolve) => resolve('value'));
+        return resolved.then(() => {
+            const resource = executionAsyncResource();
+            log.push(`then callback in ${current()}, resource is promise: ${resource instanceof Promise}`);
+        });
+    });
+}
+
+export function resources() {
+    return record(['Custom'], async (log, current) => {
+        const outer = new AsyncResource('Custom');
+        const inner = outer.runInAsyncScope(() => {
+            log.push(`outer callback in ${current()}, trigger ${triggerAsyncId() === outer.triggerAsyncId()}`);
+            return new AsyncResource('Custom', { requireManualDestroy: true });
+        });
+        inner.runInAsyncScope(() => log.push(`inner callback in ${current()}`));
+        inner.emitDestroy();
+        outer.emitDestroy();
+        log.push(`outside in ${current()}, top-level id: ${executionAsyncId()}`);
+        try {
+            createHook({ init: 'not a function' });
+        } catch (e) {
+            log.push(`invalid hook: ${e.code}`);
+        }
+        try {
+            new AsyncResource(42);
+        } catch (e) {
+            log.push(`invalid type: ${e.code}`);
+        }
+    });
+}
+
+export function fsCallbacks() {
+    return record(['FSREQCALLBACK'], (log, current) =>
+        new Promise((resolve) => {
+            fs.access('/does-not-exist', (err) => {
+                log.push(`access callback in ${current()}, failed: ${err !== null}`);
+                resolve();
+            });
+        }),
+    );
+}
+
+export function sockets() {
+    return record(['TCPSERVERWRAP', 'TCPWRAP'], (log, current) =>
+        new Promise((resolve, reject) => {
+            const server = net.createServer((socket) => {
+                log.push(`connection in ${current()}`);
+                socket.end('hello');
+            });
+            server.on('error', reject);
+            server.listen(0, '127.0.0.1', () => {
+                const client = net.connect(server.address().port, '127.0.0.1', () => {
+                    log.push(`connect in ${current()}`);
+                });
+                client.on('data', (data) => {
+                    log.push(`data ${data} in ${current()}`);
+                });
+                client.on('end', () => {
+                    client.destroy();
+                    server.close(() => resolve());
+                });
+                client.on('error', reject);
+            });
+        }),
+    );
+}
+
+export function exportedNames() {
+    return [
+        `named: ${Object.keys(asyncHooks).filter(name => name !== 'default').sort().join(', ')}`,
+        `default: ${Object.keys(asyncHooks.default).sort().join(', ')}`,
+    ].join('\n');
+}
@@ -0,0 +1,116 @@
+import * as zlib from 'node:zlib';
+
+const compressSync = {
+    'gzip': zlib.gzipSync,
+    'deflate': zlib.deflateSync,
+    'deflate-raw': zlib.deflateRawSync,
+    'br': zlib.brotliCompressSync,
+};
+
+const decompressSync = {
+    'gzip': zlib.gunzipSync,
+    'deflate': zlib.inflateSync,
+    'deflate-raw': zlib.inflateRawSync,
+    'br': zlib.brotliDecompressSync,
+};
+
+async function pipeThrough(stream, chunks) {
+    const writer = stream.writable.getWriter();
+    const written = (async () => {
+        for (const chunk of chunks) {
+            await writer.write(chunk);
+        }
+        await writer.close();
+    })();
+    // Write errors also error the readable side, which is where they are reported
+    written.catch(() => {});
+
+    const reader = stream.readable.getReader();
+    const parts = [];
+    let length = 0;
+    while (true) {
+        const {done, value} = await reader.read();
+        if (done) break;
+        if (!(value instanceof Uint8Array)) {
+            throw new Error(`Unexpected chunk type: ${Object.prototype.toString.call(value)}`);
+        }
+        parts.push(value);
+        length += value.length;
+    }
+    await written;
+
+    const result = new Uint8Array(length);
+    let offset = 0;
+    for (const part of parts) {
+        result.set(part, offset);
+        offset += part.length;
+    }
+    return result;
+}
+
+function splitIntoChunks(bytes, size) {
+    const chunks = [];
+    for (let i = 0; i < bytes.length; i += size) {
+        chunks.push(bytes.subarray(i, i + size));
+    }
+    return chunks;
+}
+
+async function describeError(promise) {
+    try {
+        await promise;
+        return 'no error';
+    } catch (e) {
+        return `${e.name}`;
+    }
+}
+
+export const roundtrip = async (format, text) => {
+    const input = new TextEncoder().encode(text);
+    const compressed = await pipeThrough(new CompressionStream(format), splitIntoChunks(input, 7));
+    const decompressed = await pipeThrough(new DecompressionStream(format), splitIntoChunks(compressed, 3));
+    return new TextDecoder().decode(decompressed);
+};
+
+export const compressForZlib = async (format, text) => {
+    const compressed = await pipeThrough(new CompressionStream(format), [new TextEncoder().encode(text)]);
+    return decompressSync[format](compressed).toString('utf8');
+};
+
+export const decompressFromZlib = async (format, text) => {
+    const compressed = compressSync[format](Buffer.from(text, 'utf8'));
+    const decompressed = await pipeThrough(new DecompressionStream(format), [compressed.buffer.slice(compressed.byteOffset, compressed.byteOffset + compressed.length)]);
+    return new TextDecoder().decode(decompressed);
+};
+
+export const invalidFormat = (format) => {
+    try {
+        new CompressionStream(format);
+        return 'no error';
+    } catch (e) {
+        return `${e.name} ${e.code}`;
+    }
+};
+
+export const truncatedInput = async (format) => {
+    const compressed = compressSync[format](Buffer.from('hello hello hello hello', 'utf8'));
+    const truncated = compressed.subarray(0, compressed.length - 4);
+    return await describeError(pipeThrough(new DecompressionStream(format), [truncated]));
+};
+
+export const trailingData = async (format) => {
+    const compressed = compressSync[format](Buffer.from('hello hello hello hello', 'utf8'));
+    return await describeError(pipeThrough(new DecompressionStream(format), [compressed, new Uint8Array([1, 2, 3])]));
+};
+
+export const classShape = async () => {
+    const stream = new CompressionStream('gzip');
+    const results = [
+        `toStringTag=${Object.prototype.toString.call(stream)}`,
+        `readable=${stream.readable instanceof ReadableStream}`,
+        `writable=${stream.writable instanceof WritableStream}`,
+        `decompressionToStringTag=${Object.prototype.toString.call(new DecompressionStream('gzip'))}`,
+        `stringChunk=${await describeError(pipeThrough(new CompressionStream('gzip'), ['not a buffer']))}`,
+    ];
+    return results.join(' ');
+};
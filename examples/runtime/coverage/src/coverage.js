@@ -0,0 +1,47 @@
+import { readFileSync } from 'node:fs';
+import * as v8 from 'node:v8';
+
+function sign(n) {
+    if (n > 0) {
+        return 'positive';
+    } else if (n < 0) {
+        return 'negative';
+    }
+    return 'zero';
+}
+
+const parity = (n) => n % 2 === 0 ? 'even' : 'odd';
+
+function size(n) {
+    switch (Math.abs(n)) {
+        case 0:
+        case 1:
+            return 'tiny';
+        case 2:
+            return 'small';
+        default:
+            return 'large';
+    }
+}
+
+function neverCalled() {
+    return 'unreachable';
+}
+
+export const classify = (n) => `${sign(n)} ${parity(n)} ${size(n)}`;
+
+function label(name) {
+    return name || 'anonymous';
+}
+
+// Writes the reports while the call is still running, and reads them back
+export function takeCoverage() {
+    label('');
+    v8.takeCoverage();
+    const directory = process.env.NODE_V8_COVERAGE || '/coverage';
+    return readFileSync(`${directory}/lcov.info`, 'utf8');
+}
+
+export function stopCoverage() {
+    v8.stopCoverage();
+}
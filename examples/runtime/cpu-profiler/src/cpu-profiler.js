@@ -0,0 +1,141 @@
+import inspector from 'node:inspector';
+
+function fib(n) {
+    return n < 2 ? n : fib(n - 1) + fib(n - 2);
+}
+
+function busy(ms) {
+    const end = Date.now() + ms;
+    let result = 0;
+    while (Date.now() < end) {
+        result += fib(15);
+    }
+    return result;
+}
+
+export function busyWork(ms) {
+    busy(ms);
+}
+
+function post(session, method, params) {
+    return new Promise((resolve, reject) => {
+        session.post(method, params, (err, result) => (err ? reject(err) : resolve(result)));
+    });
+}
+
+function parentsOf(profile) {
+    const parents = new Map();
+    for (const node of profile.nodes) {
+        for (const child of node.children) {
+            parents.set(child, node);
+        }
+    }
+    return parents;
+}
+
+function callPath(profile, node) {
+    const parents = parentsOf(profile);
+    const path = [];
+    for (let current = node; current; current = parents.get(current.id)) {
+        path.unshift(current.callFrame.functionName);
+    }
+    return path;
+}
+
+export async function sessionProfile() {
+    const throwingPrepare = () => {
+        throw new Error('prepareStackTrace must not be called by the profiler');
+    };
+    Error.prepareStackTrace = throwingPrepare;
+    const stackTraceLimit = Error.stackTraceLimit;
+
+    const session = new inspector.Session();
+    session.connect();
+    await post(session, 'Profiler.enable');
+    await post(session, 'Profiler.setSamplingInterval', { interval: 100 });
+    await post(session, 'Profiler.start');
+    busy(200);
+    const { profile } = await post(session, 'Profiler.stop');
+    session.disconnect();
+
+    const untouched = Error.prepareStackTrace === throwingPrepare && Error.stackTraceLimit === stackTraceLimit;
+    Error.prepareStackTrace = undefined;
+
+    const hits = profile.nodes.reduce((sum, node) => sum + node.hitCount, 0);
+    const fibNodes = profile.nodes.filter(node => node.callFrame.functionName === 'fib');
+    const fibHits = fibNodes.reduce((sum, node) => sum + node.hitCount, 0);
+    const elapsed = profile.timeDeltas.reduce((sum, delta) => sum + delta, 0);
+    const outermostFib = fibNodes.find(node => callPath(profile, node).filter(name => name === 'fib').length === 1);
+
+    return [
+        `keys ${Object.keys(profile).join(',')}`,
+        `root ${profile.nodes[0].callFrame.functionName} ${profile.nodes[0].id}`,
+        `samples ${profile.samples.length > 10} ${profile.samples.length === profile.timeDeltas.length}`,
+        `hits add up ${hits === profile.samples.length}`,
+        `fib dominates ${fibHits > profile.samples.length / 2}`,
+        `call path ${callPath(profile, outermostFib).slice(-3).join(' > ')}`,
+        `script ${fibNodes.every(node => node.callFrame.url !== '' && node.callFrame.scriptId !== '0')}`,
+        `position ticks ${fibNodes.some(node => node.positionTicks && node.positionTicks.every(tick => tick.line > 0))}`,
+        `timing ${profile.startTime <= profile.endTime && elapsed <= profile.endTime - profile.startTime}`,
+        `stack trace settings untouched ${untouched}`,
+    ].join('\n');
+}
+
+export async function concurrentSessions() {
+    const first = new inspector.Session();
+    const second = new inspector.Session();
+    first.connect();
+    second.connect();
+    for (const session of [first, second]) {
+        await post(session, 'Profiler.enable');
+        await post(session, 'Profiler.setSamplingInterval', { interval: 100 });
+    }
+
+    await post(first, 'Profiler.start');
+    busy(50);
+    await post(second, 'Profiler.start');
+    busy(50);
+    const { profile: firstProfile } = await post(first, 'Profiler.stop');
+    busy(50);
+    const { profile: secondProfile } = await post(second, 'Profiler.stop');
+
+    return [
+        `both recorded ${firstProfile.samples.length > 0} ${secondProfile.samples.length > 0}`,
+        `second started later ${secondProfile.startTime > firstProfile.startTime}`,
+        `first stopped earlier ${firstProfile.endTime < secondProfile.endTime}`,
+    ].join('\n');
+}
+
+export async function sessionErrors() {
+    const results = [];
+    const session = new inspector.Session();
+    try {
+        session.post('Profiler.enable');
+    } catch (err) {
+        results.push(`not connected: ${err.code} ${err.message}`);
+    }
+    session.connect();
+    try {
+        session.connect();
+    } catch (err) {
+        results.push(`connected twice: ${err.code}`);
+    }
+
+    const outcome = async (method, params) => {
+        try {
+            return JSON.stringify(await session.post(method, params));
+        } catch (err) {
+            return `${err.code} ${err.message}`;
+        }
+    };
+    results.push(`start disabled: ${await outcome('Profiler.start')}`);
+    results.push(`enable: ${await outcome('Profiler.enable')}`);
+    results.push(`stop idle: ${await outcome('Profiler.stop')}`);
+    results.push(`start: ${await outcome('Profiler.start')}`);
+    results.push(`interval while profiling: ${await outcome('Profiler.setSamplingInterval', { interval: 10 })}`);
+    results.push(`unknown: ${await outcome('Debugger.enable')}`);
+    session.disconnect();
+    session.connect();
+    results.push(`stop after reconnect: ${await outcome('Profiler.stop')}`);
+    return results.join('\n');
+}
@@ -0,0 +1,27 @@
+export const snapshot = () => {
+    return JSON.stringify({
+        now: Date.now(),
+        date: new Date().toISOString(),
+        random: [Math.random(), Math.random()],
+        uuid: crypto.randomUUID(),
+        bytes: Array.from(crypto.getRandomValues(new Uint8Array(4))),
+    });
+};
+
+export const sleepVirtual = async (ms) => {
+    const start = Date.now();
+    const perfStart = performance.now();
+    const order = [];
+    await new Promise((resolve) => {
+        setTimeout(() => order.push('late'), ms);
+        setTimeout(() => order.push('early'), ms / 2);
+        setTimeout(resolve, ms);
+    });
+    if (order.join(',') !== 'early,late') {
+        throw new Error(`Unexpected timer order: ${order}`);
+    }
+    if (performance.now() - perfStart !== Date.now() - start) {
+        throw new Error('performance.now() is not driven by the virtual clock');
+    }
+    return Date.now() - start;
+};
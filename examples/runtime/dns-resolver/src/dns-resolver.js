@@ -0,0 +1,94 @@
+import dns from 'node:dns';
+import dnsPromises from 'node:dns/promises';
+
+function resolveWith(fn, ...args) {
+    return new Promise((resolve, reject) => {
+        fn(...args, (err, result) => (err ? reject(err) : resolve(result)));
+    });
+}
+
+async function errorCode(promise) {
+    try {
+        await promise;
+        return 'no error';
+    } catch (e) {
+        return `${e.code} ${e.syscall ?? ''} ${e.hostname ?? ''}`.trim();
+    }
+}
+
+export async function records(port) {
+    dns.setServers([`127.0.0.1:${port}`]);
+    const lines = [];
+    lines.push(`A ${JSON.stringify(await resolveWith(dns.resolve4, 'example.test'))}`);
+    lines.push(`A ttl ${JSON.stringify(await resolveWith(dns.resolve4, 'example.test', { ttl: true }))}`);
+    lines.push(`AAAA ${JSON.stringify(await resolveWith(dns.resolve6, 'example.test'))}`);
+    lines.push(`MX ${JSON.stringify(await resolveWith(dns.resolveMx, 'example.test'))}`);
+    lines.push(`TXT ${JSON.stringify(await resolveWith(dns.resolveTxt, 'example.test'))}`);
+    lines.push(`NS ${JSON.stringify(await resolveWith(dns.resolveNs, 'example.test'))}`);
+    lines.push(`SOA ${JSON.stringify(await resolveWith(dns.resolveSoa, 'example.test'))}`);
+    lines.push(`CAA ${JSON.stringify(await resolveWith(dns.resolveCaa, 'example.test'))}`);
+    lines.push(`CNAME ${JSON.stringify(await resolveWith(dns.resolveCname, 'alias.example.test'))}`);
+    lines.push(`SRV ${JSON.stringify(await resolveWith(dns.resolveSrv, '_sip._tcp.example.test'))}`);
+    lines.push(`NAPTR ${JSON.stringify(await resolveWith(dns.resolveNaptr, 'naptr.example.test'))}`);
+    const tlsa = await resolveWith(dns.resolveTlsa, '_443._tcp.example.test');
+    lines.push(`TLSA ${tlsa.map(r => `${r.certUsage} ${r.selector} ${r.match} ${Buffer.from(r.data).toString('hex')}`).join(',')}`);
+    lines.push(`PTR ${JSON.stringify(await resolveWith(dns.resolvePtr, '1.2.0.192.in-addr.arpa'))}`);
+    lines.push(`reverse ${JSON.stringify(await resolveWith(dns.reverse, '192.0.2.1'))}`);
+    lines.push(`resolve MX ${JSON.stringify(await resolveWith(dns.resolve, 'example.test', 'MX'))}`);
+    const any = await dnsPromises.resolveAny('example.test');
+    lines.push(`ANY ${any.map(r => r.type).join(',')}`);
+    lines.push(`promises SRV ${JSON.stringify(await dnsPromises.resolveSrv('_sip._tcp.example.test'))}`);
+    return lines.join('\n');
+}
+
+export async function truncation(port) {
+    dns.setServers([`127.0.0.1:${port}`]);
+    const entries = await dnsPromises.resolveTxt('big.example.test');
+    return `${entries.length} ${entries[0][0].slice(0, 8)} ${entries[19][0].slice(0, 8)}`;
+}
+
+export async function errors(port) {
+    const lines = [];
+    dns.setServers([]);
+    lines.push(`no servers: ${await errorCode(dnsPromises.resolveMx('example.test'))}`);
+    dns.setServers([`127.0.0.1:${port}`]);
+    lines.push(`missing: ${await errorCode(dnsPromises.resolveMx('missing.example.test'))}`);
+    lines.push(`no data: ${await errorCode(dnsPromises.resolveMx('alias.example.test'))}`);
+    lines.push(`servfail: ${await errorCode(dnsPromises.resolveTxt('servfail.example.test'))}`);
+    lines.push(`refused: ${await errorCode(dnsPromises.resolveTxt('refused.example.test'))}`);
+    const resolver = new dnsPromises.Resolver({ timeout: 100, tries: 2 });
+    resolver.setServers([`127.0.0.1:${port}`]);
+    lines.push(`timeout: ${await errorCode(resolver.resolveTxt('drop.example.test'))}`);
+    lines.push(`bad name: ${await errorCode(dnsPromises.resolveTxt(`${'a'.repeat(64)}.example.test`))}`);
+    lines.push(`reverse invalid: ${await errorCode(dnsPromises.reverse('not-an-ip'))}`);
+    try {
+        dns.setServers(['not-an-ip']);
+        lines.push('setServers: no error');
+    } catch (e) {
+        lines.push(`setServers: ${e.code}`);
+    }
+    return lines.join('\n');
+}
+
+export async function resolvers(port) {
+    const lines = [];
+    dns.setServers(['192.0.2.53', `[::1]:${port}`, `127.0.0.1:${port}`]);
+    lines.push(`default servers ${JSON.stringify(dns.getServers())}`);
+
+    const resolver = new dns.Resolver();
+    lines.push(`inherited servers ${JSON.stringify(resolver.getServers())}`);
+    resolver.setServers([`127.0.0.1:${port}`]);
+    lines.push(`resolver servers ${JSON.stringify(resolver.getServers())}`);
+    lines.push(`default unchanged ${JSON.stringify(dns.getServers())}`);
+    lines.push(`resolver MX ${JSON.stringify(await resolveWith(resolver.resolveMx.bind(resolver), 'example.test'))}`);
+
+    dns.setServers([]);
+    lines.push(`resolver still queries ${JSON.stringify(await resolveWith(resolver.resolve4.bind(resolver), 'example.test'))}`);
+
+    const slow = new dnsPromises.Resolver({ timeout: 5000 });
+    slow.setServers([`127.0.0.1:${port}`]);
+    const pending = slow.resolveTxt('drop.example.test');
+    slow.cancel();
+    lines.push(`cancelled: ${await errorCode(pending)}`);
+    return lines.join('\n');
+}
@@ -92,10 +92,11 @@ export async function test() {
         });
     });
 
-    // Test 13: unsupported record types return ENOTIMP
-    results.unsupportedMx = await new Promise((resolve) => {
+    // Test 13: record queries without configured servers fail with ECONNREFUSED
+    dns.setServers([]);
+    results.noServersMx = await new Promise((resolve) => {
         dns.resolveMx('example.com', (err) => {
-            resolve(err && err.code === 'ENOTIMP');
+            resolve(err && err.code === 'ECONNREFUSED');
         });
     });
 
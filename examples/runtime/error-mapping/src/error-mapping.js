@@ -0,0 +1,52 @@
+class NotFoundError extends Error {
+    constructor(message) {
+        super(message);
+        this.name = 'NotFoundError';
+    }
+}
+
+class InvalidInputError extends Error {
+    constructor(message, field) {
+        super(message);
+        this.name = 'InvalidInputError';
+        this.field = field;
+    }
+}
+
+class UnauthorizedError extends Error {
+    constructor() {
+        super('not allowed');
+        this.name = 'UnauthorizedError';
+    }
+}
+
+export const api = {
+    lookup: async (kind) => {
+        switch (kind) {
+            case 'ok':
+                return 'found';
+            case 'not-found':
+                throw new NotFoundError('no such item');
+            case 'invalid':
+                throw new InvalidInputError('must not be empty', 'name');
+            case 'unauthorized':
+                throw new UnauthorizedError();
+            case 'tagged':
+                throw { tag: 'internal', val: 'explicit' };
+            default:
+                throw new TypeError('unexpected');
+        }
+    },
+    openFile: (kind) => {
+        const error = new Error(`${kind}: cannot open file`);
+        error.code = kind.toUpperCase();
+        throw error;
+    },
+    parse: (input) => {
+        const value = Number.parseInt(input, 10);
+        if (Number.isNaN(value)) {
+            throw new RangeError(`not a number: ${input}`);
+        }
+        return value;
+    },
+};
@@ -1045,3 +1045,40 @@ export async function fetchFunctionShape() {
         console.log("fetch function shape: FAILED");
     }
 }
+
+export async function fetchCompressedBodies(port) {
+    console.log("fetch test 36 (compressed response bodies)");
+
+    for (const kind of ["gzip", "deflate", "deflate-raw", "identity", "unknown"]) {
+        const response = await fetch(`http://localhost:${port}/compressed/${kind}`);
+        const text = await response.text();
+        console.log(`${kind}: content-encoding=${response.headers.get("content-encoding")} bodyUsed=${response.bodyUsed} text=${text}`);
+    }
+
+    const accepted = await fetch(`http://localhost:${port}/compressed/identity`);
+    console.log(`Default Accept-Encoding: ${accepted.headers.get("x-accept-encoding")}`);
+    await accepted.arrayBuffer();
+
+    const explicit = await fetch(`http://localhost:${port}/compressed/identity`, {
+        headers: {"Accept-Encoding": "identity"}
+    });
+    console.log(`Explicit Accept-Encoding: ${explicit.headers.get("x-accept-encoding")}`);
+    await explicit.arrayBuffer();
+
+    const streamed = await fetch(`http://localhost:${port}/compressed/gzip`);
+    const reader = streamed.body.getReader();
+    const decoder = new TextDecoder();
+    let streamedText = "";
+    while (true) {
+        const {done, value} = await reader.read();
+        if (done) break;
+        streamedText += decoder.decode(value, {stream: true});
+    }
+    streamedText += decoder.decode();
+    console.log(`Streamed gzip: bodyUsed=${streamed.bodyUsed} text=${streamedText}`);
+
+    const original = await fetch(`http://localhost:${port}/compressed/deflate`);
+    const cloned = original.clone();
+    const [originalBuffer, clonedText] = await Promise.all([original.arrayBuffer(), cloned.text()]);
+    console.log(`Cloned deflate: ${new TextDecoder().decode(originalBuffer) === clonedText} length=${originalBuffer.byteLength}`);
+}
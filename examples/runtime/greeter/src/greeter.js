@@ -0,0 +1,5 @@
+import * as names from 'quickjs:greeter/names';
+
+export const greet = (name) => {
+    return `${names.greeting(name)} (${name.length} characters)`;
+};
@@ -0,0 +1,49 @@
+//! Host side tests of the greeter component, copied into its generated host harness crate.
+//! The path of the compiled component is passed in the `GREETER_WASM` environment variable.
+
+use greeter_host_harness::bindings::quickjs::greeter::names;
+use greeter_host_harness::{Harness, Stubs};
+
+/// Import implementation recording the names it was asked to greet
+#[derive(Default)]
+struct Names {
+    greeted: Vec<String>,
+}
+
+impl names::Host for Names {
+    async fn greeting(&mut self, name: String) -> wasmtime::Result<String> {
+        self.greeted.push(name.clone());
+        Ok(format!("Hello, {name}!"))
+    }
+}
+
+fn wasm_path() -> String {
+    std::env::var("GREETER_WASM").expect("GREETER_WASM is not set")
+}
+
+#[tokio::test]
+async fn greet_with_imports() -> wasmtime::Result<()> {
+    let harness = Harness::<Names>::new(wasm_path())?;
+    let mut instance = harness.instantiate(Names::default()).await?;
+
+    assert_eq!(
+        instance.greet("World").await?,
+        "Hello, World! (5 characters)"
+    );
+    assert_eq!(instance.greet("JS").await?, "Hello, JS! (2 characters)");
+    assert_eq!(instance.imports().greeted, vec!["World", "JS"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn greet_with_stubs() -> wasmtime::Result<()> {
+    let harness = Harness::<Stubs>::new(wasm_path())?;
+    let mut instance = harness.instantiate(Stubs).await?;
+
+    let error = instance.greet("World").await.unwrap_err();
+    assert!(
+        format!("{error:?}").contains("import quickjs:greeter/names#greeting is not implemented"),
+        "unexpected error: {error:?}"
+    );
+    Ok(())
+}
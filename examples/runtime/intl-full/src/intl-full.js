@@ -0,0 +1,148 @@
+// Built with the `intl-full` feature and ICU4X data for `de` and `ja` (English is always included)
+
+const DATE = Date.UTC(2024, 0, 15, 14, 30, 5); // Monday, 2024-01-15T14:30:05Z
+
+function check(label, actual, expected) {
+    const a = JSON.stringify(actual);
+    const e = JSON.stringify(expected);
+    console.log(label + ":", a);
+    if (a !== e) {
+        throw new Error(`${label}: expected ${e}, got ${a}`);
+    }
+}
+
+function run(name, body) {
+    try {
+        body();
+        console.log(name + " PASSED");
+        return true;
+    } catch (e) {
+        console.log(name + " FAILED:", e.message, e.stack);
+        return false;
+    }
+}
+
+// test1: DateTimeFormat with localized patterns and names
+export function test1() {
+    return run("test1", () => {
+        check("de full date",
+            new Intl.DateTimeFormat("de-DE", { weekday: "long", year: "numeric", month: "long", day: "numeric", timeZone: "UTC" }).format(DATE),
+            "Montag, 15. Januar 2024");
+        check("de numeric",
+            new Intl.DateTimeFormat("de-DE", { year: "numeric", month: "2-digit", day: "2-digit", hour: "numeric", minute: "2-digit", second: "2-digit" }).format(DATE),
+            "15.01.2024, 14:30:05");
+        check("de hourCycle",
+            new Intl.DateTimeFormat("de-DE", { hour: "numeric" }).resolvedOptions().hourCycle,
+            "h23");
+        check("de parts",
+            new Intl.DateTimeFormat("de", { month: "short", day: "numeric" }).formatToParts(DATE),
+            [{ type: "day", value: "15" }, { type: "literal", value: ". " }, { type: "month", value: "Jan." }]);
+        check("ja date",
+            new Intl.DateTimeFormat("ja-JP", { year: "numeric", month: "long", day: "numeric", weekday: "short" }).format(DATE),
+            "2024年1月15日(月)");
+        check("en-US default",
+            new Intl.DateTimeFormat(undefined, { year: "numeric", month: "long", day: "numeric" }).format(DATE),
+            "January 15, 2024");
+    });
+}
+
+// test2: NumberFormat with localized symbols, grouping, currencies and percentages
+export function test2() {
+    return run("test2", () => {
+        check("de decimal", new Intl.NumberFormat("de-DE").format(1234567.891), "1.234.567,891");
+        check("de currency",
+            new Intl.NumberFormat("de-DE", { style: "currency", currency: "EUR" }).format(-1234.5),
+            "-1.234,50\u00a0€");
+        check("de percent",
+            new Intl.NumberFormat("de-DE", { style: "percent" }).formatToParts(0.25),
+            [{ type: "integer", value: "25" }, { type: "literal", value: "\u00a0" }, { type: "percentSign", value: "%" }]);
+        check("ja currency",
+            new Intl.NumberFormat("ja-JP", { style: "currency", currency: "JPY" }).format(1234.5),
+            "￥1,235");
+        check("en currency",
+            new Intl.NumberFormat("en-US", { style: "currency", currency: "USD" }).format(1234.5),
+            "$1,234.50");
+        check("toLocaleString", (1234.5).toLocaleString("de-DE"), "1.234,5");
+    });
+}
+
+// test3: PluralRules with the locale's rules
+export function test3() {
+    return run("test3", () => {
+        check("de cardinal", [0, 1, 2].map(n => new Intl.PluralRules("de").select(n)), ["other", "one", "other"]);
+        check("en ordinal",
+            [1, 2, 3, 4, 11].map(n => new Intl.PluralRules("en-US", { type: "ordinal" }).select(n)),
+            ["one", "two", "few", "other", "other"]);
+        check("en visible fraction", new Intl.PluralRules("en", { minimumFractionDigits: 1 }).select(1), "other");
+        check("ja categories", new Intl.PluralRules("ja").resolvedOptions().pluralCategories, ["other"]);
+        check("de range", new Intl.PluralRules("de").selectRange(1, 2), "other");
+    });
+}
+
+// test4: ListFormat and Collator
+export function test4() {
+    return run("test4", () => {
+        check("de conjunction", new Intl.ListFormat("de").format(["A", "B", "C"]), "A, B und C");
+        check("de disjunction",
+            new Intl.ListFormat("de", { type: "disjunction" }).formatToParts(["A", "B"]),
+            [{ type: "element", value: "A" }, { type: "literal", value: " oder " }, { type: "element", value: "B" }]);
+        check("ja conjunction", new Intl.ListFormat("ja").format(["A", "B", "C"]), "A、B、C");
+        check("de sort", ["z", "ä", "a", "Z"].sort(new Intl.Collator("de").compare), ["a", "ä", "z", "Z"]);
+        check("de base", new Intl.Collator("de", { sensitivity: "base" }).compare("a", "ä"), 0);
+        check("numeric", ["a10", "a2"].sort(new Intl.Collator("en", { numeric: true }).compare), ["a2", "a10"]);
+        check("localeCompare", "ä".localeCompare("z", "de"), -1);
+    });
+}
+
+// test5: Segmenter, including dictionary-based Japanese word breaks
+export function test5() {
+    return run("test5", () => {
+        check("ja words",
+            [...new Intl.Segmenter("ja", { granularity: "word" }).segment("こんにちは世界")].map(s => s.segment),
+            ["こんにちは", "世界"]);
+        check("de sentences",
+            [...new Intl.Segmenter("de", { granularity: "sentence" }).segment("Hallo. Welt!")].map(s => s.segment),
+            ["Hallo. ", "Welt!"]);
+        check("graphemes",
+            [...new Intl.Segmenter("en", { granularity: "grapheme" }).segment("é👍🏽")].map(s => s.segment),
+            ["é", "👍🏽"]);
+    });
+}
+
+// test6: RelativeTimeFormat
+export function test6() {
+    return run("test6", () => {
+        check("de auto", new Intl.RelativeTimeFormat("de", { numeric: "auto" }).format(-1, "day"), "gestern");
+        check("de future", new Intl.RelativeTimeFormat("de").format(3, "days"), "in 3 Tagen");
+        check("de past", new Intl.RelativeTimeFormat("de").format(-1, "day"), "vor 1 Tag");
+        check("ja short", new Intl.RelativeTimeFormat("ja", { style: "short" }).format(-2, "hour"), "2 時間前");
+        check("en parts",
+            new Intl.RelativeTimeFormat("en").formatToParts(1000, "year"),
+            [
+                { type: "literal", value: "in " },
+                { type: "integer", value: "1", unit: "year" },
+                { type: "group", value: ",", unit: "year" },
+                { type: "integer", value: "000", unit: "year" },
+                { type: "literal", value: " years" },
+            ]);
+    });
+}
+
+// test7: locale negotiation and the Date.prototype.toLocale*String polyfills
+export function test7() {
+    return run("test7", () => {
+        check("supported", Intl.DateTimeFormat.supportedLocalesOf(["de", "fr", "ja-JP"]), ["de", "ja-JP"]);
+        check("fallback", new Intl.NumberFormat(["fr", "de-AT"]).resolvedOptions().locale, "de-AT");
+        check("default", new Intl.NumberFormat("fr").resolvedOptions().locale, "en-US");
+        check("canonical", Intl.getCanonicalLocales(["DE-de", "en-us"]), ["de-DE", "en-US"]);
+        check("toLocaleDateString", new Date(DATE).toLocaleDateString("de-DE"), "15.1.2024");
+        check("toLocaleTimeString", new Date(DATE).toLocaleTimeString("de-DE", { timeZone: "UTC" }), "14:30:05");
+        let error;
+        try {
+            Intl.getCanonicalLocales("not a locale!");
+        } catch (e) {
+            error = e.constructor.name;
+        }
+        check("invalid tag", error, "RangeError");
+    });
+}
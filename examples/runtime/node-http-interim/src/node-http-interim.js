@@ -0,0 +1,214 @@
+import * as http from 'node:http';
+import * as net from 'node:net';
+
+function listen(server) {
+    return new Promise((resolve) => {
+        server.listen(0, '127.0.0.1', () => resolve(server.address().port));
+    });
+}
+
+function delay(ms) {
+    return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+function readBody(req) {
+    return new Promise((resolve) => {
+        let body = '';
+        req.on('data', (chunk) => body += chunk);
+        req.on('end', () => resolve(body));
+    });
+}
+
+// Opens a raw connection; `steps` receives the socket and a function waiting for the
+// response text to contain a string. Resolves to everything received once the server closes.
+function rawConnection(port, steps) {
+    return new Promise((resolve, reject) => {
+        const socket = net.createConnection({ host: '127.0.0.1', port });
+        let received = '';
+        let waiters = [];
+        const check = () => {
+            const pending = waiters.filter((waiter) => !received.includes(waiter.text));
+            for (const waiter of waiters) {
+                if (!pending.includes(waiter)) waiter.resolve();
+            }
+            waiters = pending;
+        };
+        const waitFor = (text) => new Promise((resolveWait) => {
+            waiters.push({ text, resolve: resolveWait });
+            check();
+        });
+        socket.on('data', (chunk) => {
+            received += chunk.toString('latin1');
+            check();
+        });
+        socket.on('error', reject);
+        socket.on('close', () => resolve(received));
+        socket.on('connect', () => steps(socket, waitFor).catch(reject));
+    });
+}
+
+function statusLines(response) {
+    return response
+        .split('\r\n')
+        .filter((line) => line.startsWith('HTTP/1.1'))
+        .join('\n');
+}
+
+const UPLOAD_REQUEST = 'POST /upload HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n' +
+    'Content-Length: 5\r\nExpect: 100-continue\r\n\r\n';
+
+export const checkContinue = async () => {
+    const log = [];
+    const server = http.createServer(() => log.push('request'));
+    server.on('checkContinue', async (req, res) => {
+        log.push(`checkContinue ${req.url}`);
+        res.writeContinue();
+        const body = await readBody(req);
+        res.end(`received ${body}`);
+    });
+    const port = await listen(server);
+    const response = await rawConnection(port, async (socket, waitFor) => {
+        socket.write(UPLOAD_REQUEST);
+        await waitFor('100 Continue\r\n\r\n');
+        log.push('client got 100 Continue');
+        socket.write('hello');
+    });
+    server.close();
+    log.push(statusLines(response));
+    log.push(response.slice(response.lastIndexOf('\r\n\r\n') + 4));
+    return log.join('\n');
+};
+
+export const automaticContinue = async () => {
+    const server = http.createServer(async (req, res) => {
+        res.end(`received ${await readBody(req)}`);
+    });
+    const port = await listen(server);
+    const response = await rawConnection(port, async (socket, waitFor) => {
+        socket.write(UPLOAD_REQUEST);
+        await waitFor('100 Continue\r\n\r\n');
+        socket.write('hello');
+    });
+    server.close();
+    return `${statusLines(response)}\n${response.slice(response.lastIndexOf('\r\n\r\n') + 4)}`;
+};
+
+export const checkExpectation = async () => {
+    const log = [];
+    const server = http.createServer(() => log.push('request'));
+    server.on('checkExpectation', (req, res) => {
+        log.push(`checkExpectation ${req.headers.expect}`);
+        res.writeHead(402);
+        res.end();
+    });
+    const port = await listen(server);
+    const response = await rawConnection(port, async (socket) => {
+        socket.write('GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nExpect: payment\r\n\r\n');
+    });
+    server.close();
+    log.push(statusLines(response));
+    return log.join('\n');
+};
+
+export const unmetExpectation = async () => {
+    const log = [];
+    const server = http.createServer(() => log.push('request'));
+    const port = await listen(server);
+    const response = await rawConnection(port, async (socket) => {
+        socket.write('GET / HTTP/1.1\r\nHost: localhost\r\nExpect: payment\r\n\r\n');
+    });
+    server.close();
+    log.push(statusLines(response));
+    return log.join('\n');
+};
+
+export const headersTimeout = async () => {
+    const server = http.createServer({
+        headersTimeout: 200,
+        requestTimeout: 1000,
+        connectionsCheckingInterval: 50,
+    }, (req, res) => res.end('unexpected'));
+    const port = await listen(server);
+    const started = Date.now();
+    const response = await rawConnection(port, async (socket) => {
+        socket.write('GET / HTTP/1.1\r\nHost: localhost\r\n');
+        await delay(100);
+        socket.write('X-Slow: 1\r\n');
+    });
+    const elapsed = Date.now() - started;
+    server.close();
+    return `${statusLines(response)}\nwithin limits=${elapsed >= 200 && elapsed < 1000}`;
+};
+
+export const requestTimeout = async () => {
+    const log = [];
+    const server = http.createServer({
+        headersTimeout: 100,
+        requestTimeout: 300,
+        connectionsCheckingInterval: 50,
+    }, () => log.push('request'));
+    server.on('clientError', (err, socket) => {
+        log.push(`clientError ${err.code}`);
+        socket.end('HTTP/1.1 408 Request Timeout\r\nConnection: close\r\nX-Handled: yes\r\n\r\n');
+    });
+    const port = await listen(server);
+    const response = await rawConnection(port, async (socket) => {
+        // The headers arrive in time, the body never completes
+        socket.write('POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 10\r\n\r\nabc');
+    });
+    server.close();
+    log.push(statusLines(response));
+    log.push(`handled=${response.includes('X-Handled: yes')}`);
+    return log.join('\n');
+};
+
+export const keepAliveTimeout = async () => {
+    const server = http.createServer((req, res) => res.end('ok'));
+    server.keepAliveTimeout = 1000;
+    const port = await listen(server);
+    const started = Date.now();
+    const response = await rawConnection(port, async (socket) => {
+        socket.write('GET / HTTP/1.1\r\nHost: localhost\r\n\r\n');
+    });
+    const elapsed = Date.now() - started;
+    server.close();
+    const keepAlive = response.split('\r\n').find((line) => line.startsWith('Keep-Alive:'));
+    return `${statusLines(response)}\n${keepAlive}\nclosed after idle=${elapsed >= 1000}`;
+};
+
+export const informationEvents = async () => {
+    const log = [];
+    const server = net.createServer((socket) => {
+        socket.once('data', () => {
+            socket.write('HTTP/1.1 100 Continue\r\n\r\n');
+            socket.write('HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload\r\n\r\n');
+            socket.end('HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\ndone');
+        });
+    });
+    const port = await listen(server);
+
+    await new Promise((resolve, reject) => {
+        const req = http.request({
+            host: '127.0.0.1',
+            port,
+            method: 'POST',
+            headers: { Expect: '100-continue' },
+            createConnection: () => net.createConnection({ host: '127.0.0.1', port }),
+        }, (res) => {
+            let body = '';
+            res.on('data', (chunk) => body += chunk);
+            res.on('end', () => {
+                log.push(`response ${res.statusCode} ${body}`);
+                resolve();
+            });
+        });
+        req.on('continue', () => log.push('continue'));
+        req.on('information', (info) => {
+            log.push(`information ${info.statusCode} ${info.statusMessage} ${info.httpVersion} ${JSON.stringify(info.headers)}`);
+        });
+        req.on('error', reject);
+        req.end('body');
+    });
+    server.close();
+    return log.join('\n');
+};
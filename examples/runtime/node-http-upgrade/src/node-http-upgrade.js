@@ -0,0 +1,183 @@
+import * as http from 'node:http';
+import * as net from 'node:net';
+import { WebSocketServer } from 'wasm-rquickjs:websocket-server';
+
+function listen(server) {
+    return new Promise((resolve) => {
+        server.listen(0, '127.0.0.1', () => resolve(server.address().port));
+    });
+}
+
+// Sends a raw request and collects everything the server sends until it closes the connection
+function rawExchange(port, request, onData) {
+    return new Promise((resolve, reject) => {
+        const socket = net.createConnection({ host: '127.0.0.1', port });
+        let received = '';
+        socket.on('connect', () => socket.write(request));
+        socket.on('data', (chunk) => {
+            received += chunk.toString('latin1');
+            if (onData) onData(socket, received);
+        });
+        socket.on('error', reject);
+        socket.on('close', () => resolve(received));
+    });
+}
+
+export const rawUpgrade = async () => {
+    const log = [];
+    const server = http.createServer(() => log.push('request'));
+    server.on('upgrade', (req, socket, head) => {
+        log.push(`upgrade ${req.method} ${req.url} upgrade=${req.upgrade} protocol=${req.headers.upgrade}`);
+        log.push(`head ${head.toString()}`);
+        socket.write('HTTP/1.1 101 Switching Protocols\r\nUpgrade: shout\r\nConnection: Upgrade\r\n\r\n');
+        socket.write(head.toString().toUpperCase());
+        socket.on('data', (chunk) => {
+            socket.end(chunk.toString().toUpperCase());
+        });
+    });
+    const port = await listen(server);
+    const response = await rawExchange(
+        port,
+        'GET /shout HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: shout\r\n\r\nhello ',
+        (socket, received) => {
+            if (received.endsWith('HELLO ')) socket.write('world');
+        },
+    );
+    server.close();
+    log.push(...response.split('\r\n'));
+    return log.join('\n');
+};
+
+export const upgradeWithoutListener = async () => {
+    const server = http.createServer((req, res) => {
+        res.end(`upgrade=${req.upgrade}`);
+    });
+    const port = await listen(server);
+    const response = await rawExchange(
+        port,
+        'GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade, close\r\nUpgrade: shout\r\n\r\n',
+    );
+    server.close();
+    const [statusLine] = response.split('\r\n');
+    const body = response.slice(response.indexOf('\r\n\r\n') + 4);
+    return `${statusLine}\n${body}`;
+};
+
+export const switchingProtocolsResponse = async () => {
+    const server = http.createServer((req, res) => {
+        res.writeHead(101, { Connection: 'Upgrade', Upgrade: 'shout' });
+        res.end();
+        req.socket.on('data', (chunk) => {
+            req.socket.end(chunk.toString().toUpperCase());
+        });
+    });
+    const port = await listen(server);
+    const response = await rawExchange(
+        port,
+        'GET / HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: shout\r\n\r\nearly data',
+    );
+    server.close();
+    return response.split('\r\n').filter((line) => !line.startsWith('Date:')).join('\n');
+};
+
+export const websocketEcho = async () => {
+    const log = [];
+    const server = http.createServer();
+    const wss = new WebSocketServer({ server, path: '/echo' });
+    const serverClosed = new Promise((resolve) => {
+        wss.on('connection', (ws, req) => {
+            log.push(`server connection url=${req.url} protocol=${ws.protocol}`);
+            ws.on('message', (data, isBinary) => {
+                log.push(`server message ${isBinary ? 'binary' : 'text'} ${isBinary ? Array.from(data).join(',') : data.toString()}`);
+                ws.send(data, { binary: isBinary });
+            });
+            ws.on('close', (code, reason) => {
+                log.push(`server close ${code} ${reason.toString()} clients=${wss.clients.size}`);
+                resolve();
+            });
+        });
+    });
+    const port = await listen(server);
+
+    await new Promise((resolve) => {
+        const ws = new WebSocket(`ws://127.0.0.1:${port}/echo`, ['chat', 'superchat']);
+        ws.binaryType = 'arraybuffer';
+        let received = 0;
+        ws.onopen = () => {
+            log.push(`client open protocol=${ws.protocol}`);
+            ws.send('hello');
+            ws.send(new Uint8Array([1, 2, 3]));
+        };
+        ws.onmessage = (event) => {
+            const data = typeof event.data === 'string'
+                ? event.data
+                : Array.from(new Uint8Array(event.data)).join(',');
+            log.push(`client message ${data}`);
+            received += 1;
+            if (received === 2) ws.close(1000, 'bye');
+        };
+        ws.onclose = (event) => {
+            log.push(`client close ${event.code}`);
+            resolve();
+        };
+    });
+    await serverClosed;
+    wss.close();
+    server.close();
+    return log.join('\n');
+};
+
+export const websocketServerClose = async () => {
+    const log = [];
+    const server = http.createServer();
+    const wss = new WebSocketServer({ server });
+    wss.on('connection', (ws) => {
+        ws.on('message', (data) => {
+            if (data.toString() === 'ping me') {
+                ws.ping('are you there');
+            }
+        });
+        ws.on('pong', (data) => {
+            log.push(`server pong ${data.toString()}`);
+            ws.close(4001, 'going away');
+        });
+        ws.on('close', (code) => log.push(`server close ${code}`));
+    });
+    const port = await listen(server);
+
+    await new Promise((resolve) => {
+        const ws = new WebSocket(`ws://127.0.0.1:${port}/`);
+        ws.onopen = () => ws.send('ping me');
+        ws.onclose = (event) => {
+            log.push(`client close ${event.code} ${event.reason} wasClean=${event.wasClean}`);
+            resolve();
+        };
+    });
+    await new Promise((resolve) => setTimeout(resolve, 50));
+    wss.close();
+    server.close();
+    return log.join('\n');
+};
+
+export const websocketPathMismatch = async () => {
+    const server = http.createServer();
+    const wss = new WebSocketServer({ server, path: '/echo' });
+    wss.on('connection', () => {
+        throw new Error('unexpected connection');
+    });
+    const port = await listen(server);
+
+    const result = await new Promise((resolve) => {
+        const ws = new WebSocket(`ws://127.0.0.1:${port}/elsewhere`);
+        const events = [];
+        ws.onopen = () => events.push('open');
+        ws.onerror = () => events.push('error');
+        ws.onclose = (event) => {
+            events.push(`close ${event.code}`);
+            resolve(events.join('\n'));
+        };
+    });
+    wss.close();
+    server.close();
+    return result;
+};
@@ -0,0 +1,128 @@
+import { test, describe, it, run, snapshot } from 'node:test';
+import { tap, spec, junit } from 'node:test/reporters';
+import assert from 'node:assert';
+import * as fs from 'node:fs';
+import process from 'node:process';
+
+function defineTests() {
+    test('passes', () => {
+        assert.strictEqual(1 + 1, 2);
+    });
+    test('fails', () => {
+        assert.strictEqual(1 + 1, 3);
+    });
+    test('skipped', { skip: 'not today' }, () => {});
+    test('todo', { todo: true }, () => {});
+    describe('suite', () => {
+        it('first', () => {});
+        it('second', async () => {
+            await new Promise(resolve => setTimeout(resolve, 1));
+        });
+    });
+    test('with subtests', async (t) => {
+        t.diagnostic('hello');
+        await t.test('inner', () => {});
+    });
+}
+
+async function collect(source) {
+    let output = '';
+    for await (const chunk of source) {
+        output += chunk;
+    }
+    return output;
+}
+
+// Durations and stack traces differ between runs
+function normalize(output) {
+    return output
+        .replace(/duration_ms[: ]+[\d.e-]+/g, 'duration_ms *')
+        .replace(/\(\d+(\.\d+)?(e-?\d+)?ms\)/g, '(*ms)')
+        .replace(/time="[\d.]+"/g, 'time="*"')
+        .replace(/hostname="[^"]*"/g, 'hostname="*"')
+        .replace(/\n +stack: \|-(\n {6,}at [^\n]*)*/g, '')
+        .replace(/\n +at [^\n]*/g, '');
+}
+
+// Tests defined while a run() is active are reported to it instead of throwing
+export async function tapReport() {
+    const events = run();
+    defineTests();
+    return normalize(await collect(tap(events)));
+}
+
+export async function specReport() {
+    const events = run();
+    defineTests();
+    return normalize(await collect(events.pipe(new spec())));
+}
+
+export async function junitReport() {
+    const events = run();
+    defineTests();
+    return normalize(await collect(junit(events)));
+}
+
+export async function mockTimers() {
+    const log = [];
+    await test('timers', (t) => {
+        t.mock.timers.enable({ apis: ['setTimeout', 'setInterval', 'Date'], now: 1000 });
+        setTimeout((value) => log.push(`timeout ${value} at ${Date.now()}`), 50, 'a');
+        const cleared = setTimeout(() => log.push('cleared'), 10);
+        clearTimeout(cleared);
+        // setImmediate is not mocked, clearing its timer by number must leave the mock timers alone
+        const survivor = setTimeout(() => log.push('survivor'), 5);
+        const real = setImmediate(() => log.push('real'));
+        log.push(`distinct ids ${+real !== +survivor}`);
+        clearImmediate(+real);
+        const interval = setInterval(() => log.push(`interval at ${Date.now()}`), 20);
+        t.mock.timers.tick(45);
+        log.push(`now ${Date.now()} ${new Date().getTime()}`);
+        clearInterval(interval);
+        t.mock.timers.runAll();
+        t.mock.timers.setTime(5000);
+        log.push(`set ${Date.now()}`);
+        try {
+            t.mock.timers.enable();
+        } catch (e) {
+            log.push(`enable twice ${e.code}`);
+        }
+    });
+    log.push(`restored ${Date.now() > 1e12}`);
+    return log.join('\n');
+}
+
+export async function snapshots() {
+    const log = [];
+    snapshot.setResolveSnapshotPath(() => '/snapshots/runner.snapshot');
+    const events = run();
+    events.on('test:fail', (data) => {
+        log.push(`fail ${data.name}: ${data.details.error.cause.code}`);
+    });
+    events.on('test:pass', (data) => {
+        log.push(`pass ${data.name}`);
+    });
+
+    process.env.NODE_TEST_UPDATE_SNAPSHOTS = '1';
+    test('record', (t) => {
+        t.assert.snapshot({ answer: 42 });
+        t.assert.snapshot('text`with ${template}');
+        t.assert.fileSnapshot('file contents\n', '/snapshots/file.txt');
+    });
+    delete process.env.NODE_TEST_UPDATE_SNAPSHOTS;
+    test('record', (t) => {
+        t.assert.snapshot({ answer: 42 });
+        t.assert.snapshot('text`with ${template}');
+        t.assert.fileSnapshot('file contents\n', '/snapshots/file.txt');
+    });
+    test('record', (t) => {
+        t.assert.snapshot({ answer: 43 });
+    });
+    test('missing', (t) => {
+        t.assert.snapshot(1);
+    });
+
+    await collect(events);
+    log.push(fs.readFileSync('/snapshots/runner.snapshot', 'utf8'));
+    return log.join('\n');
+}
@@ -0,0 +1,34 @@
+let state = 0;
+
+console.log('initializing the module');
+
+export const inc = (delta) => {
+    state += delta;
+    return state;
+};
+
+export const getGlobal = () => {
+    return globalThis.leakedValue;
+};
+
+export const setGlobal = (value) => {
+    globalThis.leakedValue = value;
+};
+
+class Counter {
+    constructor(start) {
+        this.value = start;
+    }
+
+    get() {
+        return this.value;
+    }
+
+    peek() {
+        return this.value;
+    }
+}
+
+export const counters = {
+    Counter: Counter,
+};
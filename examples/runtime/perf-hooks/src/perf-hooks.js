@@ -0,0 +1,103 @@
+import {
+    performance,
+    createHistogram,
+    monitorEventLoopDelay,
+    PerformanceObserver,
+} from 'node:perf_hooks';
+
+function busyWait(ms) {
+    const start = Date.now();
+    while (Date.now() - start < ms) {
+        // Blocks the event loop
+    }
+}
+
+function errorCode(f) {
+    try {
+        f();
+        return 'no error';
+    } catch (e) {
+        return e.code;
+    }
+}
+
+export function histogram() {
+    const h = createHistogram();
+    const empty = [h.count, h.min, h.max, h.mean, h.stddev, h.exceeds];
+    for (let value = 1; value <= 10; value++) {
+        h.record(value);
+    }
+    h.record(100n);
+
+    const bounded = createHistogram({ lowest: 1, highest: 1000, figures: 2 });
+    bounded.record(10);
+    bounded.record(5000);
+    const sum = createHistogram();
+    sum.add(h);
+    sum.add(bounded);
+
+    const delta = createHistogram();
+    delta.recordDelta();
+    busyWait(5);
+    delta.recordDelta();
+
+    return [
+        `empty ${JSON.stringify(empty)}`,
+        `json ${JSON.stringify(h)}`,
+        `percentile ${h.percentile(50)} ${h.percentile(99)} ${h.percentileBigInt(100)}`,
+        `percentiles ${JSON.stringify([...h.percentiles])}`,
+        `exceeds ${bounded.count} ${bounded.exceeds}`,
+        `add ${sum.count} ${sum.exceeds} ${sum.max}`,
+        `delta ${delta.count} ${delta.min >= 4e6}`,
+        `invalid percentile ${errorCode(() => h.percentile(0))}`,
+        `invalid value ${errorCode(() => h.record(1.5))}`,
+        `invalid bounds ${errorCode(() => createHistogram({ lowest: 10, highest: 15 }))}`,
+        `invalid resolution ${errorCode(() => monitorEventLoopDelay({ resolution: 0 }))}`,
+    ].join('\n');
+}
+
+export async function eventLoopDelay() {
+    const eld = monitorEventLoopDelay({ resolution: 5 });
+    const enabled = [eld.enable(), eld.enable()];
+    await new Promise(resolve => setTimeout(resolve, 20));
+    busyWait(50);
+    await new Promise(resolve => setTimeout(resolve, 20));
+    const disabled = [eld.disable(), eld.disable()];
+    return [
+        `enable ${enabled}`,
+        `disable ${disabled}`,
+        `count ${eld.count > 2}`,
+        `max ${eld.max >= 40e6}`,
+        `min ${eld.min >= 4e6}`,
+        `p50 ${eld.percentile(50) < eld.max}`,
+    ].join('\n');
+}
+
+export async function observer() {
+    const entries = [];
+    const obs = new PerformanceObserver((list) => {
+        for (const entry of list.getEntries()) {
+            entries.push(entry);
+        }
+    });
+    obs.observe({ entryTypes: ['mark', 'function', 'gc'] });
+
+    performance.mark('start', { detail: 'details' });
+    const h = createHistogram();
+    const add = performance.timerify(function add(a, b) {
+        busyWait(2);
+        return a + b;
+    }, { histogram: h });
+    const sum = add(1, 2);
+    globalThis.gc();
+
+    await new Promise(resolve => setTimeout(resolve, 10));
+    obs.disconnect();
+
+    return [
+        `supported ${PerformanceObserver.supportedEntryTypes}`,
+        `sum ${sum} ${add.name}`,
+        `histogram ${h.count} ${h.min >= 1e6}`,
+        ...entries.map(e => `${e.entryType} ${e.name} ${JSON.stringify(e.detail)} ${e.duration >= 0}`),
+    ].join('\n');
+}
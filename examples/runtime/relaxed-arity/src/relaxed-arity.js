@@ -0,0 +1,36 @@
+export function withDefault(a, b = 10) {
+    return a + b;
+}
+
+export const withRest = (first, ...rest) => [first, ...rest].join("-");
+
+const logged = (fn) => (...args) => {
+    console.log(`calling ${fn.name}`);
+    return fn(...args);
+};
+
+export const decorated = logged(function add(x, y) {
+    return x + y;
+});
+
+function scale(factor, x, y = 0) {
+    return factor * (x + y);
+}
+
+export const bound = scale.bind(null, 2);
+
+class Counter {
+    constructor(start, step = 1) {
+        this.value = start;
+        this.step = step;
+    }
+
+    add(times, extra = 0) {
+        this.value += times * this.step + extra;
+        return this.value;
+    }
+}
+
+export const counters = {
+    Counter: Counter,
+};
@@ -0,0 +1,36 @@
+import { test, describe, it } from 'node:test';
+import assert from 'node:assert';
+
+let runs = 0;
+
+export function add(a, b) {
+    return a + b;
+}
+
+// Tests only run when the `run-tests` export is called, not when the module is evaluated
+export function registrationRuns() {
+    return runs;
+}
+
+test('adds', () => {
+    runs++;
+    assert.strictEqual(add(1, 2), 3);
+});
+
+test('fails', () => {
+    assert.strictEqual(add(1, 2), 4, 'add is broken');
+});
+
+describe('math', () => {
+    it('waits', async () => {
+        await new Promise(resolve => setTimeout(resolve, 5));
+        assert.strictEqual(add(-1, 1), 0);
+    });
+    it.skip('divides');
+    it.todo('multiplies');
+});
+
+test('with subtests', async (t) => {
+    await t.test('positive', () => assert.strictEqual(add(2, 2), 4));
+    await t.test('negative', () => assert.strictEqual(add(-2, -2), -4));
+});
@@ -0,0 +1,175 @@
+// Built with the `temporal` feature
+
+function check(label, actual, expected) {
+    const a = JSON.stringify(actual);
+    const e = JSON.stringify(expected);
+    console.log(label + ":", a);
+    if (a !== e) {
+        throw new Error(`${label}: expected ${e}, got ${a}`);
+    }
+}
+
+function checkThrows(label, fn, errorType) {
+    try {
+        fn();
+    } catch (e) {
+        console.log(label + ":", e.constructor.name, e.message);
+        if (!(e instanceof errorType)) {
+            throw new Error(`${label}: expected ${errorType.name}, got ${e.constructor.name}`);
+        }
+        return;
+    }
+    throw new Error(`${label}: expected ${errorType.name} to be thrown`);
+}
+
+function run(name, body) {
+    try {
+        body();
+        console.log(name + " PASSED");
+        return true;
+    } catch (e) {
+        console.log(name + " FAILED:", e.message, e.stack);
+        return false;
+    }
+}
+
+// test1: PlainDate parsing, fields and calendar arithmetic
+export function test1() {
+    return run("test1", () => {
+        const date = Temporal.PlainDate.from("2024-01-31");
+        check("toString", date.toString(), "2024-01-31");
+        check("fields", [date.year, date.month, date.monthCode, date.day, date.dayOfWeek, date.dayOfYear],
+            [2024, 1, "M01", 31, 3, 31]);
+        check("add month", date.add({ months: 1 }).toString(), "2024-02-29");
+        checkThrows("add month reject", () => date.add({ months: 1 }, { overflow: "reject" }), RangeError);
+        check("until", date.until("2025-03-01", { largestUnit: "year" }).toString(), "P1Y1M1D");
+        check("since months", Temporal.PlainDate.from("2024-03-31")
+            .since("2023-02-28", { largestUnit: "month", smallestUnit: "month", roundingMode: "halfExpand" })
+            .toString(), "P13M");
+        check("week of year", [Temporal.PlainDate.from("2024-12-30").weekOfYear,
+            Temporal.PlainDate.from("2024-12-30").yearOfWeek], [1, 2025]);
+        check("with", date.with({ day: 15 }).toString(), "2024-01-15");
+        check("compare", Temporal.PlainDate.compare("2024-01-01", "2023-12-31"), 1);
+        check("equals", date.equals({ year: 2024, month: 1, day: 31 }), true);
+        check("calendarName", date.toString({ calendarName: "always" }), "2024-01-31[u-ca=iso8601]");
+        checkThrows("invalid string", () => Temporal.PlainDate.from("2024-02-30"), RangeError);
+        check("constrain", Temporal.PlainDate.from({ year: 2024, month: 2, day: 30 }).toString(), "2024-02-29");
+        checkThrows("valueOf", () => date < date, TypeError);
+        check("toStringTag", Object.prototype.toString.call(date), "[object Temporal.PlainDate]");
+    });
+}
+
+// test2: PlainTime, PlainDateTime, PlainYearMonth and PlainMonthDay
+export function test2() {
+    return run("test2", () => {
+        const time = Temporal.PlainTime.from("12:34:56.789");
+        check("time fields", [time.hour, time.minute, time.second, time.millisecond], [12, 34, 56, 789]);
+        check("time toString", time.toString({ smallestUnit: "second" }), "12:34:56");
+        check("time add", time.add({ hours: 12 }).toString(), "00:34:56.789");
+        check("time round", time.round({ smallestUnit: "minute" }).toString(), "12:35:00");
+        const dateTime = Temporal.PlainDateTime.from("2024-01-01T23:59:59.999");
+        check("datetime round", dateTime.round({ smallestUnit: "second" }).toString(), "2024-01-02T00:00:00");
+        check("datetime until", dateTime.until("2024-01-03T01:00", { largestUnit: "day" }).toString(),
+            "P1DT1H0.001S");
+        check("datetime toPlainDate", dateTime.toPlainDate().toString(), "2024-01-01");
+        const yearMonth = Temporal.PlainYearMonth.from("2024-02");
+        check("yearMonth", [yearMonth.daysInMonth, yearMonth.inLeapYear], [29, true]);
+        check("yearMonth subtract", yearMonth.subtract({ months: 3 }).toString(), "2023-11");
+        check("yearMonth until", Temporal.PlainYearMonth.from("2019-06").until(yearMonth, { largestUnit: "year" })
+            .toString(), "P4Y8M");
+        const monthDay = Temporal.PlainMonthDay.from("--02-29");
+        check("monthDay", [monthDay.monthCode, monthDay.day, monthDay.toString()], ["M02", 29, "02-29"]);
+        check("monthDay toPlainDate", monthDay.toPlainDate({ year: 2023 }).toString(), "2023-02-28");
+        check("json", JSON.stringify({ t: Temporal.PlainTime.from("01:02") }), "{\"t\":\"01:02:00\"}");
+    });
+}
+
+// test3: ZonedDateTime across daylight saving time transitions
+export function test3() {
+    return run("test3", () => {
+        const gap = Temporal.ZonedDateTime.from("2024-03-10T02:30[America/New_York]");
+        check("gap compatible", gap.toString(), "2024-03-10T03:30:00-04:00[America/New_York]");
+        check("hoursInDay", gap.hoursInDay, 23);
+        checkThrows("gap reject", () => Temporal.ZonedDateTime.from("2024-03-10T02:30[America/New_York]",
+            { disambiguation: "reject" }), RangeError);
+        check("overlap later", Temporal.ZonedDateTime.from("2024-11-03T01:30[America/New_York]",
+            { disambiguation: "later" }).toString(), "2024-11-03T01:30:00-05:00[America/New_York]");
+        const berlin = Temporal.ZonedDateTime.from("2024-10-27T02:30+01:00[Europe/Berlin]");
+        check("offset", [berlin.offset, berlin.offsetNanoseconds], ["+01:00", 3600000000000]);
+        check("add hour", berlin.add({ hours: 1 }).toString(), "2024-10-27T03:30:00+01:00[Europe/Berlin]");
+        check("add day", berlin.add({ days: 1 }).toString(), "2024-10-28T02:30:00+01:00[Europe/Berlin]");
+        check("next transition", berlin.getTimeZoneTransition("next").toString(),
+            "2025-03-30T03:00:00+02:00[Europe/Berlin]");
+        check("previous transition", berlin.getTimeZoneTransition({ direction: "previous" }).toString(),
+            "2024-10-27T02:00:00+01:00[Europe/Berlin]");
+        const boaVista = Temporal.ZonedDateTime.from("2000-10-08T12:00[America/Boa_Vista]");
+        check("transition a week later", boaVista.getTimeZoneTransition("next").toString(),
+            "2000-10-14T23:00:00-04:00[America/Boa_Vista]");
+        check("no transition after 2100", Temporal.ZonedDateTime.from("2100-06-01T00:00[America/New_York]")
+            .getTimeZoneTransition("next"), null);
+        check("no transition in UTC", Temporal.Now.zonedDateTimeISO("UTC").getTimeZoneTransition("previous"), null);
+        check("startOfDay", berlin.startOfDay().toString(), "2024-10-27T00:00:00+02:00[Europe/Berlin]");
+        check("with", berlin.with({ hour: 12 }).toString(), "2024-10-27T12:30:00+01:00[Europe/Berlin]");
+        check("withTimeZone", berlin.withTimeZone("+05:30").toString(), "2024-10-27T07:00:00+05:30[+05:30]");
+        check("until", Temporal.ZonedDateTime.from("2024-06-01T12:00[Europe/Berlin]")
+            .until("2024-12-25T08:00[Europe/Berlin]", { largestUnit: "month", smallestUnit: "hour" })
+            .toString(), "P6M23DT20H");
+        check("lmt offset", Temporal.ZonedDateTime.from("1850-01-01T00:00[Europe/Berlin]").offset, "+00:53:28");
+        check("case-insensitive zone", Temporal.ZonedDateTime.from("2024-01-01T00:00[europe/berlin]").timeZoneId,
+            "Europe/Berlin");
+        checkThrows("unknown zone", () => Temporal.ZonedDateTime.from("2024-01-01T00:00[Mars/Olympus]"), RangeError);
+    });
+}
+
+// test4: Duration balancing, rounding and totals
+export function test4() {
+    return run("test4", () => {
+        check("round", Temporal.Duration.from("PT130M").round({ largestUnit: "hour" }).toString(), "PT2H10M");
+        check("round relativeTo", Temporal.Duration.from({ days: 40 })
+            .round({ largestUnit: "month", relativeTo: "2024-01-15" }).toString(), "P1M9D");
+        check("total zoned", Temporal.Duration.from({ hours: 25 })
+            .total({ unit: "day", relativeTo: "2024-03-09T12:00[America/New_York]" }), 1.0833333333333333);
+        check("total plain", Temporal.Duration.from("P1M").total({ unit: "day", relativeTo: "2024-02-10" }), 29);
+        checkThrows("total without relativeTo", () => Temporal.Duration.from("P1M").total("days"), RangeError);
+        check("compare", Temporal.Duration.compare({ months: 1 }, { days: 30 }, { relativeTo: "2024-02-01" }), -1);
+        check("negated", Temporal.Duration.from("P1Y2M3DT4H5M6.789S").negated().toString(), "-P1Y2M3DT4H5M6.789S");
+        check("fractional digits", Temporal.Duration.from("PT1.5S")
+            .toString({ fractionalSecondDigits: 0, roundingMode: "halfExpand" }), "PT2S");
+        check("add", Temporal.Duration.from({ hours: 23 }).add({ minutes: 90 }).toString(), "PT24H30M");
+        check("sign", [Temporal.Duration.from("-PT1S").sign, new Temporal.Duration().blank], [-1, true]);
+        checkThrows("mixed signs", () => new Temporal.Duration(1, -1), RangeError);
+    });
+}
+
+// test5: Instant and conversions between the types
+export function test5() {
+    return run("test5", () => {
+        const instant = Temporal.Instant.from("2024-01-01T00:00:00.123456789Z");
+        check("epochNanoseconds", instant.epochNanoseconds.toString(), "1704067200123456789");
+        check("round", instant.round({ smallestUnit: "millisecond" }).toString(), "2024-01-01T00:00:00.123Z");
+        check("timeZone", Temporal.Instant.fromEpochMilliseconds(1e12).toString({ timeZone: "Asia/Kolkata" }),
+            "2001-09-09T07:16:40+05:30");
+        check("until", Temporal.Instant.from("2020-01-01T00:00Z")
+            .until("2020-01-02T01:00:00.5Z", { largestUnit: "hour" }).toString(), "PT25H0.5S");
+        checkThrows("add days", () => instant.add({ days: 1 }), RangeError);
+        check("toZonedDateTimeISO", instant.toZonedDateTimeISO("Asia/Tokyo").toPlainDateTime().toString(),
+            "2024-01-01T09:00:00.123456789");
+        check("Date.toTemporalInstant", new Date(0).toTemporalInstant().toString(), "1970-01-01T00:00:00Z");
+        check("offset string", Temporal.Instant.from("2024-01-01T05:30+05:30").toString(), "2024-01-01T00:00:00Z");
+    });
+}
+
+// test6: Temporal.Now reads the wall clock in the UTC time zone
+export function test6() {
+    return run("test6", () => {
+        const before = BigInt(Date.now()) * 1_000_000n;
+        const now = Temporal.Now.instant();
+        check("after Date.now", now.epochNanoseconds >= before - 1_000_000n, true);
+        check("timeZoneId", Temporal.Now.timeZoneId(), "UTC");
+        check("plainDateISO", Temporal.Now.plainDateISO().toString(),
+            now.toZonedDateTimeISO("UTC").toPlainDate().toString());
+        check("zonedDateTimeISO", Temporal.Now.zonedDateTimeISO("Europe/Paris").timeZoneId, "Europe/Paris");
+        check("namespace", Object.prototype.toString.call(Temporal), "[object Temporal]");
+        check("enumerable", Object.keys(Temporal), []);
+    });
+}
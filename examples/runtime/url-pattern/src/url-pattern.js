@@ -0,0 +1,229 @@
+import { URLPattern as NodeURLPattern } from 'node:url';
+
+function check(label, actual, expected) {
+    const a = JSON.stringify(actual);
+    const e = JSON.stringify(expected);
+    console.log(label + ":", a);
+    if (a !== e) {
+        throw new Error(`${label}: expected ${e}, got ${a}`);
+    }
+}
+
+function checkThrows(label, fn, errorType) {
+    try {
+        fn();
+    } catch (e) {
+        console.log(label + ":", e.constructor.name, e.message);
+        if (!(e instanceof errorType)) {
+            throw new Error(`${label}: expected ${errorType.name}, got ${e.constructor.name}`);
+        }
+        return;
+    }
+    throw new Error(`${label}: expected ${errorType.name} to be thrown`);
+}
+
+function run(name, body) {
+    try {
+        body();
+        console.log(name + " PASSED");
+        return true;
+    } catch (e) {
+        console.log(name + " FAILED:", e.message, e.stack);
+        return false;
+    }
+}
+
+// test1: routing with named groups, wildcards and the constructor-string form
+export function test1() {
+    return run("test1", () => {
+        const pattern = new URLPattern({ pathname: "/books/:id" });
+        check("pathname", pattern.pathname, "/books/:id");
+        check("protocol", pattern.protocol, "*");
+        check("test", pattern.test("https://example.com/books/123"), true);
+        check("test miss", pattern.test("https://example.com/books/123/pages"), false);
+        const result = pattern.exec("https://example.com/books/123?q=1");
+        check("groups", result.pathname.groups, { id: "123" });
+        check("inputs", result.inputs, ["https://example.com/books/123?q=1"]);
+        check("search", result.search, { input: "q=1", groups: { 0: "q=1" } });
+
+        const string = new URLPattern("https://:sub.example.com/api/*\\?mode=:mode");
+        check("hostname", string.hostname, ":sub.example.com");
+        check("search", string.search, "mode=:mode");
+        check("exec string", string.exec("https://api.example.com/api/v1/users?mode=full").hostname.groups,
+            { sub: "api" });
+        check("wildcard", string.exec("https://api.example.com/api/v1/users?mode=full").pathname.groups,
+            { 0: "v1/users" });
+
+        const relative = new URLPattern("/users/:id(\\d+)", "https://example.com");
+        check("hasRegExpGroups", relative.hasRegExpGroups, true);
+        check("relative", relative.test("https://example.com/users/42"), true);
+        check("relative digits", relative.test("https://example.com/users/abc"), false);
+        check("with base", relative.exec("/users/7", "https://example.com").inputs,
+            ["/users/7", "https://example.com"]);
+    });
+}
+
+// test2: modifiers, regexp groups, ignoreCase and error handling
+export function test2() {
+    return run("test2", () => {
+        const optional = new URLPattern({ pathname: "/files/:name{.:ext}?" });
+        check("optional", optional.exec({ pathname: "/files/readme" }).pathname.groups,
+            { name: "readme", ext: undefined });
+        check("optional present", optional.exec({ pathname: "/files/readme.md" }).pathname.groups,
+            { name: "readme", ext: "md" });
+        const repeated = new URLPattern({ pathname: "/tags/:tag+" });
+        check("repeated", repeated.exec({ pathname: "/tags/a/b/c" }).pathname.groups, { tag: "a/b/c" });
+        const lookahead = new URLPattern({ pathname: "/((?!admin).*)" });
+        check("lookahead", [lookahead.test({ pathname: "/admin" }), lookahead.test({ pathname: "/home" })],
+            [false, true]);
+        const insensitive = new URLPattern({ pathname: "/Foo" }, { ignoreCase: true });
+        check("ignoreCase", insensitive.test({ pathname: "/fOO" }), true);
+        check("encoded", new URLPattern({ pathname: "/café" }).pathname, "/caf%C3%A9");
+        check("default port", new URLPattern("https://example.com:443/").port, "");
+        checkThrows("invalid regexp", () => new URLPattern({ pathname: "/(\\d" }), TypeError);
+        checkThrows("invalid group", () => new URLPattern({ pathname: "/:" }), TypeError);
+        checkThrows("leading ? in group", () => new URLPattern({ pathname: "/(?!admin)" }), TypeError);
+        checkThrows("base with init", () => new URLPattern({ pathname: "/" }, "https://example.com"), TypeError);
+        checkThrows("relative without base", () => new URLPattern("/foo"), TypeError);
+        check("invalid input", new URLPattern({ pathname: "/*" }).exec("not a url"), null);
+    });
+}
+
+// test3: the global, node:url export and object model
+export function test3() {
+    return run("test3", () => {
+        check("node:url", NodeURLPattern === URLPattern, true);
+        check("toStringTag", Object.prototype.toString.call(new URLPattern()), "[object URLPattern]");
+        check("enumerable global", Object.keys(globalThis).includes("URLPattern"), false);
+        const pattern = new URLPattern();
+        check("match anything", pattern.test("https://example.com/anything?at=all#really"), true);
+        checkThrows("brand check", () => URLPattern.prototype.test.call({}, "https://example.com"), TypeError);
+    });
+}
+
+// wpt: runs the URLPattern test data of the web-platform-tests (urlpatterntestdata.json)
+const COMPONENTS = ["protocol", "username", "password", "hostname", "port", "pathname", "search", "hash"];
+const EARLIER_COMPONENTS = {
+    protocol: [],
+    hostname: ["protocol"],
+    port: ["protocol", "hostname"],
+    username: [],
+    password: [],
+    pathname: ["protocol", "hostname", "port"],
+    search: ["protocol", "hostname", "port", "pathname"],
+    hash: ["protocol", "hostname", "port", "pathname", "search"],
+};
+
+function deepEqual(a, b) {
+    if (typeof a !== "object" || typeof b !== "object" || a === null || b === null) {
+        return a === b;
+    }
+    if (Array.isArray(a) !== Array.isArray(b)) {
+        return false;
+    }
+    const keys = Object.keys(a);
+    if (keys.length !== Object.keys(b).length) {
+        return false;
+    }
+    return keys.every((key) => Object.prototype.hasOwnProperty.call(b, key) && deepEqual(a[key], b[key]));
+}
+
+function assert(condition, message) {
+    if (!condition) {
+        throw new Error(message);
+    }
+}
+
+function throwsTypeError(fn) {
+    try {
+        fn();
+    } catch (e) {
+        return e instanceof TypeError;
+    }
+    return false;
+}
+
+function runEntry(entry) {
+    if (entry.expected_obj === "error") {
+        assert(throwsTypeError(() => new URLPattern(...entry.pattern)), "constructor should throw a TypeError");
+        return;
+    }
+    const pattern = new URLPattern(...entry.pattern);
+    const expectedObj = entry.expected_obj || {};
+    let baseURL = null;
+    if (entry.pattern.length > 0 && entry.pattern[0].baseURL) {
+        baseURL = new URL(entry.pattern[0].baseURL);
+    } else if (entry.pattern.length > 1 && typeof entry.pattern[1] === "string") {
+        baseURL = new URL(entry.pattern[1]);
+    }
+    for (const component of COMPONENTS) {
+        let expected = expectedObj[component];
+        if (expected === undefined) {
+            if (entry.exactly_empty_components && entry.exactly_empty_components.includes(component)) {
+                expected = "";
+            } else if (typeof entry.pattern[0] === "object" && entry.pattern[0][component]) {
+                expected = entry.pattern[0][component];
+            } else if (typeof entry.pattern[0] === "object" &&
+                EARLIER_COMPONENTS[component].some((c) => entry.pattern[0][c] !== undefined)) {
+                expected = "*";
+            } else if (baseURL && component !== "username" && component !== "password") {
+                expected = baseURL[component];
+                if (component === "protocol") {
+                    expected = expected.substring(0, expected.length - 1);
+                } else if (component === "search" || component === "hash") {
+                    expected = expected.substring(1);
+                }
+            } else {
+                expected = "*";
+            }
+        }
+        assert(pattern[component] === expected,
+            `pattern.${component}: expected ${JSON.stringify(expected)}, got ${JSON.stringify(pattern[component])}`);
+    }
+    const inputs = entry.inputs || [];
+    if (entry.expected_match === "error") {
+        assert(throwsTypeError(() => pattern.test(...inputs)), "test() should throw a TypeError");
+        assert(throwsTypeError(() => pattern.exec(...inputs)), "exec() should throw a TypeError");
+        return;
+    }
+    assert(pattern.test(...inputs) === !!entry.expected_match, "test() result");
+    const result = pattern.exec(...inputs);
+    if (!entry.expected_match || typeof entry.expected_match !== "object") {
+        assert(result === (entry.expected_match ?? null), `exec() should return ${entry.expected_match}`);
+        return;
+    }
+    const expectedInputs = entry.expected_match.inputs || inputs;
+    assert(deepEqual(result.inputs, expectedInputs),
+        `exec().inputs: expected ${JSON.stringify(expectedInputs)}, got ${JSON.stringify(result.inputs)}`);
+    for (const component of COMPONENTS) {
+        let expected = entry.expected_match[component];
+        if (!expected) {
+            expected = { input: "", groups: {} };
+            if (!entry.exactly_empty_components || !entry.exactly_empty_components.includes(component)) {
+                expected.groups["0"] = "";
+            }
+        }
+        const groups = {};
+        for (const [key, value] of Object.entries(expected.groups)) {
+            groups[key] = value === null ? undefined : value;
+        }
+        expected = { input: expected.input, groups };
+        assert(deepEqual(result[component], expected),
+            `exec().${component}: expected ${JSON.stringify(expected)}, got ${JSON.stringify(result[component])}`);
+    }
+}
+
+export function wpt(data) {
+    const entries = JSON.parse(data);
+    let failed = 0;
+    for (const entry of entries) {
+        try {
+            runEntry(entry);
+        } catch (e) {
+            failed++;
+            console.log(`FAILED: ${JSON.stringify(entry.pattern)} ${JSON.stringify(entry.inputs)}: ${e.message}`);
+        }
+    }
+    console.log(`${entries.length - failed}/${entries.length} URLPattern WPT cases passed`);
+    return failed === 0;
+}
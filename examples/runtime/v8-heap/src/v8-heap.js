@@ -0,0 +1,166 @@
+import v8 from 'node:v8';
+
+class LeakyEntry {
+    constructor(index) {
+        this.index = index;
+        this.label = `leak-marker-${index}`;
+    }
+}
+
+// Reachable from the user module namespace, so it shows up in heap snapshots
+export const cache = [];
+
+export function retainEntries(count) {
+    for (let i = 0; i < count; i++) {
+        cache.push(new LeakyEntry(cache.length));
+    }
+}
+
+class HiddenEntry {
+    constructor(index) {
+        this.label = `hidden-marker-${index}`;
+    }
+}
+
+// Not exported, so it is only reachable through the closures of this module
+const hiddenCache = new Map();
+
+export function retainHidden(count) {
+    for (let i = 0; i < count; i++) {
+        hiddenCache.set(hiddenCache.size, new HiddenEntry(hiddenCache.size));
+    }
+}
+
+export function heapStatistics() {
+    const before = v8.getHeapStatistics();
+    const buffer = new ArrayBuffer(1024 * 1024);
+    const objects = Array.from({ length: 10000 }, (_, i) => ({ i }));
+    const after = v8.getHeapStatistics();
+
+    const spaces = v8.getHeapSpaceStatistics();
+    const spacesTotal = spaces.reduce((sum, space) => sum + space.space_used_size, 0);
+    const usedNow = v8.getHeapStatistics().used_heap_size;
+    const code = v8.getHeapCodeStatistics();
+
+    const results = [
+        `keys ${Object.keys(after).join(',')}`,
+        `used > 0 ${after.used_heap_size > 0}`,
+        `total >= used ${after.total_heap_size >= after.used_heap_size}`,
+        `limit > total ${after.heap_size_limit > after.total_heap_size}`,
+        `available ${after.total_available_size === after.heap_size_limit - after.total_heap_size}`,
+        `peak >= malloced ${after.peak_malloced_memory >= after.malloced_memory}`,
+        `used grew ${after.used_heap_size - before.used_heap_size >= buffer.byteLength + objects.length * 16}`,
+        `spaces ${spaces.map(space => space.space_name).join(',')}`,
+        `spaces add up ${Math.abs(spacesTotal - usedNow) < usedNow * 0.05}`,
+        `code ${code.bytecode_and_metadata_size > 0 && code.code_and_metadata_size > code.bytecode_and_metadata_size}`,
+    ];
+    return results.join('\n');
+}
+
+async function readSnapshot() {
+    const chunks = [];
+    for await (const chunk of v8.getHeapSnapshot()) {
+        chunks.push(chunk);
+    }
+    return JSON.parse(Buffer.concat(chunks).toString());
+}
+
+// Offsets of the nodes of a given type and name
+function nodesNamed(snapshot, type, name) {
+    const fields = snapshot.snapshot.meta.node_fields.length;
+    const typeIndex = snapshot.snapshot.meta.node_types[0].indexOf(type);
+    const nameIndex = snapshot.strings.indexOf(name);
+    const result = [];
+    for (let i = 0; i < snapshot.nodes.length; i += fields) {
+        if (snapshot.nodes[i] === typeIndex && snapshot.nodes[i + 1] === nameIndex) {
+            result.push(i);
+        }
+    }
+    return result;
+}
+
+function edgesFrom(snapshot, nodeOffset) {
+    const nodeFields = snapshot.snapshot.meta.node_fields.length;
+    const edgeFields = snapshot.snapshot.meta.edge_fields.length;
+    let edgeOffset = 0;
+    for (let i = 0; i < nodeOffset; i += nodeFields) {
+        edgeOffset += snapshot.nodes[i + 4] * edgeFields;
+    }
+    const edges = [];
+    for (let i = 0; i < snapshot.nodes[nodeOffset + 4]; i++) {
+        const offset = edgeOffset + i * edgeFields;
+        edges.push(snapshot.edges.slice(offset, offset + edgeFields));
+    }
+    return edges;
+}
+
+// The target of the first edge of a node with the given type and name
+function edgeTarget(snapshot, nodeOffset, type, name) {
+    const edgeTypes = snapshot.snapshot.meta.edge_types[0];
+    const edge = edgesFrom(snapshot, nodeOffset).find(([edgeType, edgeName]) =>
+        edgeTypes[edgeType] === type && snapshot.strings[edgeName] === name);
+    return edge?.[2];
+}
+
+export async function heapSnapshot() {
+    retainEntries(100);
+    retainHidden(30);
+    const usedBefore = v8.getHeapStatistics().used_heap_size;
+    const first = await readSnapshot();
+    retainEntries(50);
+    const second = await readSnapshot();
+
+    const meta = first.snapshot.meta;
+    const nodeFields = meta.node_fields.length;
+    const edgeFields = meta.edge_fields.length;
+    const nodeTypes = meta.node_types[0];
+    const edgeTypes = meta.edge_types[0];
+    const edgeCountTotal = first.nodes.reduce((sum, value, i) => (i % nodeFields === 4 ? sum + value : sum), 0);
+
+    const firstEntries = nodesNamed(first, 'object', 'LeakyEntry');
+    const secondEntries = nodesNamed(second, 'object', 'LeakyEntry');
+    const firstIds = new Set(firstEntries.map(offset => first.nodes[offset + 2]));
+    const keptIds = secondEntries.filter(offset => firstIds.has(second.nodes[offset + 2])).length;
+
+    const entryEdges = edgesFrom(first, firstEntries[0]).map(([type, name]) =>
+        `${edgeTypes[type]}:${typeof name === 'number' && edgeTypes[type] === 'element' ? name : first.strings[name]}`);
+
+    const [cacheNode] = nodesNamed(first, 'object', 'Array').filter(offset =>
+        edgesFrom(first, offset).filter(([type]) => edgeTypes[type] === 'element').length === 100);
+
+    // retainHidden -> (closure variable) -> Map -> entries
+    const [retainHiddenNode] = nodesNamed(first, 'closure', 'retainHidden');
+    const hiddenVariable = edgeTarget(first, retainHiddenNode, 'context', 'hiddenCache');
+    const hiddenMap = hiddenVariable === undefined ? undefined : edgeTarget(first, hiddenVariable, 'internal', 'value');
+    const hiddenEntries = new Set(nodesNamed(first, 'object', 'HiddenEntry'));
+    const retainedHidden = hiddenMap === undefined ? 0 : edgesFrom(first, hiddenMap)
+        .filter(([, , to]) => hiddenEntries.has(to)).length;
+
+    const totalSelfSize = first.nodes.reduce((sum, value, i) => (i % nodeFields === 3 ? sum + value : sum), 0);
+
+    return [
+        `fields ${meta.node_fields.join(',')} / ${meta.edge_fields.join(',')}`,
+        `counts ${first.nodes.length === first.snapshot.node_count * nodeFields} ${first.edges.length === first.snapshot.edge_count * edgeFields} ${edgeCountTotal === first.snapshot.edge_count}`,
+        `root ${nodeTypes[first.nodes[0]]} '${first.strings[first.nodes[1]]}' ${first.nodes[2]}`,
+        `entries ${firstEntries.length} ${secondEntries.length}`,
+        `stable ids ${keptIds}`,
+        `entry edges ${entryEdges.join(',')}`,
+        `marker ${first.strings.includes('leak-marker-42')}`,
+        `cache found ${cacheNode !== undefined}`,
+        `closure ${nodesNamed(first, 'closure', 'retainEntries').length}`,
+        `hidden entries ${hiddenEntries.size} ${first.strings.includes('hidden-marker-7')}`,
+        `hidden map ${hiddenMap === undefined ? 'missing' : nodeTypes[first.nodes[hiddenMap]] + ' ' + first.strings[first.nodes[hiddenMap + 1]]}`,
+        `hidden retained ${retainedHidden}`,
+        `internals ${nodesNamed(first, 'hidden', '(QuickJS internals)').length}`,
+        `total ${totalSelfSize >= usedBefore}`,
+    ].join('\n');
+}
+
+export function writeHeapSnapshot() {
+    const defaultName = v8.writeHeapSnapshot();
+    const explicitName = v8.writeHeapSnapshot('/explicit.heapsnapshot');
+    return [
+        `default ${/^Heap\.\d{8}\.\d{6}\.\d+\.0\.001\.heapsnapshot$/.test(defaultName)}`,
+        `explicit ${explicitName}`,
+    ].join('\n');
+}
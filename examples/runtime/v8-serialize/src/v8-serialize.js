@@ -0,0 +1,187 @@
+import v8 from 'node:v8';
+import { Buffer } from 'node:buffer';
+
+function hex(value) {
+    return v8.serialize(value).toString('hex');
+}
+
+function fromHex(value) {
+    return v8.deserialize(Buffer.from(value, 'hex'));
+}
+
+// Compares against bytes produced by v8.serialize in Node
+export function serializeValues() {
+    const circular = {};
+    circular.self = circular;
+    const shared = { x: 1 };
+
+    return [
+        `object ${hex({ a: 1, b: 'x' })}`,
+        `array ${hex([1, 2])}`,
+        `holey ${hex([1, , 3])}`,
+        `map ${hex(new Map([[1, 2]]))}`,
+        `set ${hex(new Set([1]))}`,
+        `regexp ${hex(/a/gi)}`,
+        `bigint ${hex(123n)}`,
+        `negative bigint ${hex(-(2n ** 70n))}`,
+        `date ${hex(new Date(1e12))}`,
+        `double ${hex(1.5)}`,
+        `negative zero ${hex(-0)}`,
+        `two-byte ${hex({ '€': 1 })}`,
+        `buffer ${hex(Buffer.from([1, 2]))}`,
+        `uint8array ${hex(new Uint8Array([1, 2]))}`,
+        `float64array ${hex(new Float64Array([1.5]))}`,
+        `circular ${hex(circular)}`,
+        `shared ${hex([shared, shared])}`,
+        `boxed ${hex([new Number(3), new String('s'), new Boolean(false)])}`,
+        `integer keys ${hex({ 1: 'a', b: 2 })}`,
+    ].join('\n');
+}
+
+// Decodes payloads written by v8.serialize in Node
+export function deserializeNodePayloads() {
+    const object = fromHex('ff0f6f22016149022201622201787b02');
+    const map = fromHex('ff0f3b490249043a02');
+    const set = fromHex('ff0f2749022c01');
+    const regexp = fromHex('ff0f5222016103');
+    const bigint = fromHex('ff0f5a107b00000000000000');
+    const buffer = fromHex('ff0f5c0a020102');
+    const circular = fromHex('ff0f6f220473656c665e007b01');
+    const shared = fromHex('ff0f41026f22017849027b015e01240002');
+    const oldVersion = fromHex('ff0d6f22016149027b01');
+    const arrayBuffer = fromHex('ff0f42020102');
+
+    return [
+        `object ${JSON.stringify(object)}`,
+        `map ${JSON.stringify([...map])}`,
+        `set ${JSON.stringify([...set])}`,
+        `regexp ${regexp.source} ${regexp.flags}`,
+        `bigint ${bigint}`,
+        `buffer ${Buffer.isBuffer(buffer)} ${JSON.stringify([...buffer])}`,
+        `circular ${circular.self === circular}`,
+        `shared ${shared[0] === shared[1]} ${shared[0].x}`,
+        `old version ${JSON.stringify(oldVersion)}`,
+        `arraybuffer ${arrayBuffer instanceof ArrayBuffer} ${arrayBuffer.byteLength}`,
+    ].join('\n');
+}
+
+export function roundtrip() {
+    const value = {
+        string: 'héllo wörld',
+        unicode: '日本語 🎉',
+        loneSurrogate: 'a\ud800b',
+        numbers: [0, -1, 2147483647, -2147483648, 3.25, NaN, Infinity],
+        nested: { list: [null, undefined, true, false], empty: {} },
+        date: new Date(Date.UTC(2024, 0, 2, 3, 4, 5)),
+        regexp: /x+y/msu,
+        map: new Map([['key', { deep: [1, 2] }], [1, 'one']]),
+        set: new Set(['a', 2n ** 100n, -5n]),
+        buffer: Buffer.from('buffer bytes'),
+        int16: new Int16Array([-1, 2, 300]),
+        bigints: new BigInt64Array([-(2n ** 63n), 1n]),
+        view: new DataView(new Uint8Array([9, 8, 7]).buffer),
+        sparse: Object.assign([1, , 3], { extra: 'prop' }),
+        error: new RangeError('out of range', { cause: 'too big' }),
+    };
+    value.self = value;
+
+    const copy = v8.deserialize(v8.serialize(value));
+    return [
+        `string ${copy.string === value.string}`,
+        `unicode ${copy.unicode === value.unicode}`,
+        `lone surrogate ${copy.loneSurrogate === value.loneSurrogate}`,
+        `numbers ${JSON.stringify(copy.numbers)} ${Object.is(copy.numbers[5], NaN)}`,
+        `nested ${JSON.stringify(copy.nested)} ${copy.nested.list.length} ${1 in copy.nested.list}`,
+        `date ${copy.date.toISOString()}`,
+        `regexp ${copy.regexp}`,
+        `map ${JSON.stringify([...copy.map])}`,
+        `set ${[...copy.set].map(String).join(',')}`,
+        `buffer ${Buffer.isBuffer(copy.buffer)} ${copy.buffer.toString()}`,
+        `int16 ${copy.int16 instanceof Int16Array} ${[...copy.int16]}`,
+        `bigints ${copy.bigints instanceof BigInt64Array} ${[...copy.bigints]}`,
+        `view ${copy.view instanceof DataView} ${copy.view.getUint8(2)}`,
+        `sparse ${copy.sparse.length} ${1 in copy.sparse} ${copy.sparse[2]} ${copy.sparse.extra}`,
+        `error ${copy.error instanceof RangeError} ${copy.error.message} ${copy.error.cause}`,
+        `circular ${copy.self === copy}`,
+    ].join('\n');
+}
+
+export function serializerApi() {
+    const serializer = new v8.Serializer();
+    serializer.writeHeader();
+    serializer.writeUint32(300);
+    serializer.writeUint64(1, 2);
+    serializer.writeDouble(1.5);
+    serializer.writeRawBytes(Buffer.from('hi'));
+    serializer.writeValue('value');
+    const bytes = serializer.releaseBuffer();
+
+    const deserializer = new v8.Deserializer(bytes);
+    deserializer.readHeader();
+    const results = [
+        `bytes ${bytes.toString('hex')}`,
+        `version ${deserializer.getWireFormatVersion()}`,
+        `uint32 ${deserializer.readUint32()}`,
+        `uint64 ${JSON.stringify(deserializer.readUint64())}`,
+        `double ${deserializer.readDouble()}`,
+        `raw ${deserializer.readRawBytes(2).toString()}`,
+        `value ${deserializer.readValue()}`,
+    ];
+
+    // Transferred array buffers are written by ID and supplied by the deserializer
+    const arrayBuffer = new Uint8Array([1, 2, 3]).buffer;
+    const transferring = new v8.Serializer();
+    transferring.writeHeader();
+    transferring.transferArrayBuffer(7, arrayBuffer);
+    transferring.writeValue({ data: arrayBuffer });
+    const transferred = transferring.releaseBuffer();
+    const receiving = new v8.Deserializer(transferred);
+    receiving.readHeader();
+    receiving.transferArrayBuffer(7, arrayBuffer);
+    const received = receiving.readValue();
+    results.push(`transfer ${transferred.toString('hex')} ${received.data === arrayBuffer}`);
+
+    // Custom host objects
+    class CustomSerializer extends v8.Serializer {
+        _writeHostObject(object) {
+            this.writeUint32(object.length);
+        }
+    }
+    class CustomDeserializer extends v8.Deserializer {
+        _readHostObject() {
+            return { length: this.readUint32() };
+        }
+    }
+    const custom = new CustomSerializer();
+    custom._setTreatArrayBufferViewsAsHostObjects(true);
+    custom.writeHeader();
+    custom.writeValue(new Uint8Array(5));
+    const customBytes = custom.releaseBuffer();
+    const customReader = new CustomDeserializer(customBytes);
+    customReader.readHeader();
+    results.push(`host object ${customBytes.toString('hex')} ${JSON.stringify(customReader.readValue())}`);
+
+    return results.join('\n');
+}
+
+function errorOf(fn) {
+    try {
+        fn();
+        return 'no error';
+    } catch (e) {
+        return `${e.name}${e.code ? ` ${e.code}` : ''}: ${e.message}`;
+    }
+}
+
+export function errors() {
+    return [
+        `function ${errorOf(() => v8.serialize({ f() {} }))}`,
+        `symbol ${errorOf(() => v8.serialize(Symbol('x')))}`,
+        `weakmap ${errorOf(() => v8.serialize(new WeakMap()))}`,
+        `truncated ${errorOf(() => v8.deserialize(Buffer.from('ff0f6f', 'hex')))}`,
+        `unknown tag ${errorOf(() => v8.deserialize(Buffer.from('ff0f01', 'hex')))}`,
+        `future version ${errorOf(() => v8.deserialize(Buffer.from('ff20', 'hex')))}`,
+        `not a view ${errorOf(() => new v8.Deserializer('abc'))}`,
+        `raw bytes ${errorOf(() => new v8.Serializer().writeRawBytes('abc'))}`,
+    ].join('\n');
+}
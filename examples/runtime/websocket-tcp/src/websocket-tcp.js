@@ -0,0 +1,93 @@
+// Exercises the wasi:sockets WebSocket client against the test server's /ws/echo route
+
+function echoUrl(port) {
+    return `ws://localhost:${port}/ws/echo`;
+}
+
+function describeData(data) {
+    if (typeof data === 'string') {
+        return `text:${data}`;
+    }
+    return `binary:${Array.from(new Uint8Array(data)).join(',')}`;
+}
+
+function run(url, protocols, onOpen, onMessage) {
+    return new Promise((resolve) => {
+        const log = [];
+        const ws = new WebSocket(url, protocols);
+        ws.binaryType = 'arraybuffer';
+        ws.onopen = () => {
+            log.push(`open protocol=${ws.protocol} readyState=${ws.readyState}`);
+            onOpen(ws, log);
+        };
+        ws.onmessage = (event) => {
+            log.push(`message ${describeData(event.data)}`);
+            onMessage(ws, log, event.data);
+        };
+        ws.onerror = () => log.push('error');
+        ws.onclose = (event) => {
+            log.push(`close code=${event.code} reason=${event.reason} wasClean=${event.wasClean}`);
+            resolve(log.join('\n'));
+        };
+    });
+}
+
+export const echo = (port) => {
+    let received = 0;
+    return run(echoUrl(port), ['other', 'chat'], (ws) => {
+        ws.send('hello');
+        ws.send(new Uint8Array([1, 2, 3]));
+    }, (ws) => {
+        received += 1;
+        if (received === 2) {
+            ws.close(1000, 'done');
+        }
+    });
+};
+
+export const serverClose = (port) =>
+    run(echoUrl(port), [], (ws) => ws.send('close'), () => {});
+
+export const serverPing = (port) =>
+    run(echoUrl(port), [], (ws) => ws.send('ping'), (ws) => ws.close());
+
+export const largeMessage = (port) => {
+    const text = 'x'.repeat(70000);
+    return new Promise((resolve, reject) => {
+        const ws = new WebSocket(echoUrl(port));
+        ws.onopen = () => ws.send(text);
+        ws.onmessage = (event) => {
+            ws.close();
+            resolve(`received ${event.data.length} bytes, equal=${event.data === text}`);
+        };
+        ws.onerror = (event) => reject(new Error(event.message));
+    });
+};
+
+export const handshakeFailure = (port) =>
+    run(`ws://localhost:${port}/todos`, [], () => {}, () => {});
+
+export const secureUrlRejected = () =>
+    run('wss://localhost:1/ws/echo', [], () => {}, () => {});
+
+export const streamEcho = async (port) => {
+    const wss = new WebSocketStream(echoUrl(port), { protocols: ['chat'] });
+    const { readable, writable, protocol } = await wss.opened;
+    const writer = writable.getWriter();
+    const reader = readable.getReader();
+
+    await writer.write('streamed');
+    const first = await reader.read();
+    await writer.write(new Uint8Array([4, 5, 6]));
+    const second = await reader.read();
+    wss.close();
+    // The echo server answers the close handshake with an empty close frame (1005)
+    const closed = await wss.closed;
+
+    return [
+        `protocol=${protocol}`,
+        describeData(first.value),
+        describeData(second.value),
+        `closed code=${closed.closeCode}`,
+    ].join('\n');
+};
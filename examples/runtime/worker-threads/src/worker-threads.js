@@ -0,0 +1,145 @@
+import { Worker, MessageChannel, isMainThread, threadId, setEnvironmentData } from 'node:worker_threads';
+import vm from 'node:vm';
+
+function waitForExit(worker) {
+    return new Promise((resolve) => worker.once('exit', resolve));
+}
+
+export const evalWorkerRoundtrip = async () => {
+    const worker = new Worker(`
+        const { parentPort, workerData, isMainThread, threadId } = require('node:worker_threads');
+        parentPort.postMessage({ greeting: 'hello ' + workerData.name, isMainThread, threadId });
+        parentPort.on('message', (value) => {
+            if (value === 'done') {
+                parentPort.close();
+            } else {
+                parentPort.postMessage(value.map((n) => n * 2));
+            }
+        });
+    `, { eval: true, workerData: { name: 'worker' } });
+
+    const messages = [];
+    worker.on('message', (message) => {
+        messages.push(message);
+        if (Array.isArray(message)) {
+            worker.postMessage('done');
+        }
+    });
+    worker.postMessage([1, 2, 3]);
+    const code = await waitForExit(worker);
+
+    const [hello, doubled] = messages;
+    const sameRealm = Object.getPrototypeOf(hello) === Object.prototype && doubled instanceof Array;
+    return `${isMainThread} ${threadId} ${hello.greeting} ${hello.isMainThread} ${hello.threadId === worker.threadId} ${doubled.join(',')} ${sameRealm} ${code}`;
+};
+
+export const moduleWorker = async () => {
+    const source = `
+        import { parentPort, workerData } from 'node:worker_threads';
+        await new Promise((resolve) => setTimeout(resolve, 10));
+        parentPort.postMessage(workerData.a + workerData.b);
+    `;
+    const worker = new Worker(new URL('data:text/javascript,' + encodeURIComponent(source)), {
+        workerData: { a: 40, b: 2 },
+    });
+    const result = await new Promise((resolve) => worker.once('message', resolve));
+    const code = await waitForExit(worker);
+    return `${result} ${code}`;
+};
+
+export const workerError = async () => {
+    const worker = new Worker(`
+        setTimeout(() => {
+            const error = new RangeError('out of range');
+            error.code = 'ERR_CUSTOM';
+            throw error;
+        }, 0);
+    `, { eval: true });
+    const events = [];
+    worker.on('error', (error) => {
+        events.push(`error ${error instanceof RangeError} ${error.message} ${error.code}`);
+    });
+    events.push(`exit ${await waitForExit(worker)}`);
+    return events.join(', ');
+};
+
+export const workerExitCode = async () => {
+    const worker = new Worker(`
+        const { parentPort } = require('node:worker_threads');
+        parentPort.postMessage('before exit');
+        process.exit(3);
+        parentPort.postMessage('after exit');
+    `, { eval: true });
+    const messages = [];
+    worker.on('message', (message) => messages.push(message));
+    const code = await waitForExit(worker);
+    return `${messages.join(',')} ${code}`;
+};
+
+export const workerTerminate = async () => {
+    const worker = new Worker(`
+        const { parentPort } = require('node:worker_threads');
+        setInterval(() => parentPort.postMessage('tick'), 5);
+    `, { eval: true });
+    await new Promise((resolve) => worker.once('message', resolve));
+    const code = await worker.terminate();
+    return `${code}`;
+};
+
+export const transferPort = async () => {
+    const { port1, port2 } = new MessageChannel();
+    const worker = new Worker(`
+        const { parentPort } = require('node:worker_threads');
+        parentPort.once('message', ({ port }) => {
+            port.postMessage('sent through the transferred port');
+            port.close();
+        });
+    `, { eval: true });
+    const received = new Promise((resolve) => port1.once('message', resolve));
+    worker.postMessage({ port: port2 }, [port2]);
+
+    let notListed = 'no error';
+    const other = new MessageChannel();
+    try {
+        other.port1.postMessage({ port: other.port2 });
+    } catch (error) {
+        notListed = error.name;
+    }
+    other.port1.close();
+
+    const message = await received;
+    const code = await waitForExit(worker);
+    return `${message} ${notListed} ${code}`;
+};
+
+export const environmentData = async () => {
+    setEnvironmentData('config', { level: 3 });
+    const worker = new Worker(`
+        const { parentPort, getEnvironmentData } = require('node:worker_threads');
+        parentPort.postMessage(getEnvironmentData('config').level + ' ' + process.env.WORKER_FLAG + ' ' + process.argv.slice(2).join('+'));
+    `, { eval: true, env: { WORKER_FLAG: 'on' }, argv: ['x', 'y'] });
+    const message = await new Promise((resolve) => worker.once('message', resolve));
+    await waitForExit(worker);
+    setEnvironmentData('config', undefined);
+    return message;
+};
+
+export const vmContextIsolation = () => {
+    const sandbox = { counter: 1 };
+    const context = vm.createContext(sandbox);
+    vm.runInContext('counter += 1; var created = "yes"; globalThis.Array.prototype.polluted = true;', context);
+    vm.runInContext('counter *= 10;', context);
+    const persisted = vm.runInContext('typeof created + " " + created', context);
+
+    const foreignArray = vm.runInContext('[1, 2]', context);
+    const separateRealm = !(foreignArray instanceof Array) && Array.isArray(foreignArray);
+
+    let referenceError = 'no error';
+    try {
+        vm.runInContext('missingName', context);
+    } catch (error) {
+        referenceError = error.name;
+    }
+
+    return `${sandbox.counter} ${sandbox.created} ${persisted} ${separateRealm} ${[].polluted === undefined} ${referenceError}`;
+};
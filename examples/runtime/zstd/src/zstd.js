@@ -0,0 +1,159 @@
+import * as zlib from 'node:zlib';
+
+// "hello zstandard, hello zstandard, hello zstandard" compressed by the reference zstd library at level 3
+const REFERENCE_FRAME = '28b52ffd0058bd00008868656c6c6f207a7374616e646172642c200100099f4b';
+
+function describeError(fn) {
+    try {
+        fn();
+        return 'no error';
+    } catch (e) {
+        return `${e.name} ${e.code}`;
+    }
+}
+
+function collect(stream) {
+    return new Promise((resolve, reject) => {
+        const parts = [];
+        stream.on('data', (chunk) => parts.push(chunk));
+        stream.on('end', () => resolve(Buffer.concat(parts)));
+        stream.on('error', reject);
+    });
+}
+
+export const syncRoundtrip = (text) => {
+    const compressed = zlib.zstdCompressSync(text, {
+        params: {[zlib.constants.ZSTD_c_compressionLevel]: 1},
+    });
+    if (compressed.readUInt32LE(0) !== 0xfd2fb528) {
+        throw new Error(`Missing zstd magic number: ${compressed.subarray(0, 4).toString('hex')}`);
+    }
+    return zlib.zstdDecompressSync(compressed).toString('utf8');
+};
+
+export const callbackRoundtrip = (text) => new Promise((resolve, reject) => {
+    zlib.zstdCompress(Buffer.from(text, 'utf8'), (err, compressed) => {
+        if (err) return reject(err);
+        zlib.zstdDecompress(compressed, {}, (err, decompressed) => {
+            if (err) return reject(err);
+            resolve(decompressed.toString('utf8'));
+        });
+    });
+});
+
+export const streamRoundtrip = async (chunks) => {
+    const compress = zlib.createZstdCompress();
+    const decompress = new zlib.ZstdDecompress();
+    compress.pipe(decompress);
+    const result = collect(decompress);
+
+    for (const [i, chunk] of chunks.entries()) {
+        compress.write(chunk);
+        if (i === 0) {
+            await new Promise((resolve) => compress.flush(resolve));
+        }
+    }
+    compress.end();
+    return (await result).toString('utf8');
+};
+
+export const decompressReferenceFrame = async () => {
+    const frame = Buffer.from(REFERENCE_FRAME, 'hex');
+    const sync = zlib.zstdDecompressSync(frame).toString('utf8');
+    const decompress = zlib.createZstdDecompress();
+    const streamed = collect(decompress);
+    decompress.end(frame);
+    return `${sync}|${(await streamed).toString('utf8')}`;
+};
+
+export const decompressIncrementally = async () => {
+    const lines = [];
+    for (let i = 0; i < 60000; i++) lines.push(`line ${i}`);
+    const text = lines.join('\n');
+    const compressed = zlib.zstdCompressSync(text);
+    const decompress = zlib.createZstdDecompress();
+    const result = collect(decompress);
+    let received = 0;
+    decompress.on('data', (chunk) => received += chunk.length);
+
+    const half = compressed.length >> 1;
+    for (let i = 0; i < half; i += 1000) {
+        decompress.write(compressed.subarray(i, Math.min(i + 1000, half)));
+    }
+    await new Promise((resolve) => setTimeout(resolve, 0));
+    const beforeEnd = received;
+    decompress.end(compressed.subarray(half));
+    const output = (await result).toString('utf8');
+    return `output before end: ${beforeEnd > 0}, complete: ${output === text}`;
+};
+
+export const decompressInvalid = async () => {
+    const compressed = zlib.zstdCompressSync('hello hello hello hello');
+    const results = [
+        describeError(() => zlib.zstdDecompressSync(Buffer.from('not zstd at all'))),
+        describeError(() => zlib.zstdDecompressSync(compressed.subarray(0, compressed.length - 3))),
+    ];
+    const decompress = zlib.createZstdDecompress();
+    const streamed = collect(decompress).then(() => 'no error', (e) => `${e.name} ${e.code}`);
+    decompress.end(Buffer.from('not zstd at all'));
+    results.push(await streamed);
+    return results.join(', ');
+};
+
+export const invalidParams = () => [
+    describeError(() => zlib.zstdCompressSync('x', {params: {999: 1}})),
+    describeError(() => zlib.zstdCompressSync('x', {params: {[zlib.constants.ZSTD_c_compressionLevel]: 'high'}})),
+    describeError(() => zlib.createZstdCompress({params: {[zlib.constants.ZSTD_c_compressionLevel]: 23}})),
+    describeError(() => zlib.createZstdDecompress({params: {[zlib.constants.ZSTD_c_checksumFlag]: 1}})),
+    describeError(() => zlib.createZstdDecompress({params: {[zlib.constants.ZSTD_d_windowLogMax]: 20}})),
+].join(', ');
+
+// Reads the checksum and content size bits of the frame header descriptor
+function describeFrame(frame) {
+    const descriptor = frame[4];
+    return `checksum=${(descriptor >> 2) & 1} contentSize=${descriptor >> 6 !== 0}`;
+}
+
+export const frameFlags = async () => {
+    const c = zlib.constants;
+    const text = 'frame flags '.repeat(40);
+    const frames = [
+        zlib.zstdCompressSync(text),
+        zlib.zstdCompressSync(text, {params: {[c.ZSTD_c_checksumFlag]: 1}}),
+        zlib.zstdCompressSync(text, {params: {[c.ZSTD_c_contentSizeFlag]: 0, [c.ZSTD_c_checksumFlag]: true}}),
+    ];
+    const compress = zlib.createZstdCompress({params: {[c.ZSTD_c_checksumFlag]: 1}});
+    const streamed = collect(compress);
+    compress.end(text);
+    frames.push(await streamed);
+    return frames.map((frame) => {
+        const roundtrip = zlib.zstdDecompressSync(frame).toString('utf8') === text;
+        return `${describeFrame(frame)} roundtrip=${roundtrip}`;
+    }).join(', ');
+};
+
+export const unsupportedParams = () => {
+    const c = zlib.constants;
+    return [
+        {[c.ZSTD_c_compressionLevel]: 19},
+        {[c.ZSTD_c_strategy]: c.ZSTD_btultra},
+        {[c.ZSTD_c_windowLog]: 12},
+        {[c.ZSTD_c_enableLongDistanceMatching]: 1},
+        {[c.ZSTD_c_nbWorkers]: 2},
+        {[c.ZSTD_c_compressionLevel]: -5, [c.ZSTD_c_strategy]: c.ZSTD_fast, [c.ZSTD_c_windowLog]: 20},
+        {[c.ZSTD_c_compressionLevel]: c.ZSTD_CLEVEL_DEFAULT, [c.ZSTD_c_dictIDFlag]: 0},
+    ].map((params) => describeError(() => zlib.createZstdCompress({params}))).join(', ');
+};
+
+export const constantsShape = () => {
+    const c = zlib.constants;
+    return [
+        `modes=${c.ZSTD_COMPRESS},${c.ZSTD_DECOMPRESS}`,
+        `endOps=${c.ZSTD_e_continue},${c.ZSTD_e_flush},${c.ZSTD_e_end}`,
+        `level=${c.ZSTD_c_compressionLevel}`,
+        `checksum=${c.ZSTD_c_checksumFlag}`,
+        `windowLogMax=${c.ZSTD_d_windowLogMax}`,
+        `default=${c.ZSTD_CLEVEL_DEFAULT}`,
+        `classes=${new zlib.ZstdCompress() instanceof zlib.ZstdCompress},${zlib.ZstdDecompress() instanceof zlib.ZstdDecompress}`,
+    ].join(' ');
+};
@@ -36,6 +36,31 @@ pub enum Command {
         /// The WIT world to use
         #[arg(long)]
         world: Option<String>,
+
+        /// Case of the `result` error types that thrown JS errors fall back to when their
+        /// `code` or `name` does not match any other case
+        #[arg(long)]
+        error_catch_all_case: Option<String>,
+
+        /// Locales to bake ICU4X data in for when the crate is built with the `intl-full`
+        /// feature (comma-separated or repeated, e.g. `de-DE,ja`). Defaults to all locales
+        #[arg(long, value_delimiter = ',')]
+        intl_locales: Vec<String>,
+
+        /// Add a `wasm-rquickjs-heap-snapshot` export returning a heap snapshot of the JS
+        /// runtime, for debugging memory leaks
+        #[arg(long)]
+        heap_snapshot_export: bool,
+
+        /// Add a `run-tests` export running the `node:test` tests registered by the JS modules,
+        /// returning the status, duration and failure message of each test
+        #[arg(long)]
+        run_tests_export: bool,
+
+        /// Instrument the embedded JS modules to collect code coverage, written as Istanbul JSON
+        /// and LCOV to the directory in `NODE_V8_COVERAGE` (`/coverage` by default)
+        #[arg(long)]
+        coverage: bool,
     },
     /// Generate TypeScript module definitions
     GenerateDTS {
@@ -51,6 +76,20 @@ pub enum Command {
         #[arg(long)]
         world: Option<String>,
     },
+    /// Generate a Rust crate for testing the component from the host side using wasmtime
+    GenerateHostHarness {
+        /// Path to the WIT package the component implements
+        #[arg(long)]
+        wit: Utf8PathBuf,
+
+        /// Path of the directory to generate the harness crate to
+        #[arg(long)]
+        output: Utf8PathBuf,
+
+        /// The WIT world to use
+        #[arg(long)]
+        world: Option<String>,
+    },
     /// Pre-initialize a WebAssembly component using Wizer to speed up startup
     Optimize {
         /// Path to the input WebAssembly component
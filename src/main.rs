@@ -1,6 +1,9 @@
 use crate::cli::{Args, Command};
 use clap::Parser;
-use wasm_rquickjs::{EmbeddingMode, JsModuleSpec, generate_dts, generate_wrapper_crate};
+use wasm_rquickjs::{
+    EmbeddingMode, JsModuleSpec, WrapperCrateOptions, generate_dts, generate_host_harness,
+    generate_wrapper_crate_with_options,
+};
 
 mod cli;
 
@@ -13,6 +16,11 @@ fn main() {
             wit,
             output,
             world,
+            error_catch_all_case,
+            intl_locales,
+            heap_snapshot_export,
+            run_tests_export,
+            coverage,
         } => {
             let modules = if let Some(js) = maybe_js {
                 vec![JsModuleSpec {
@@ -23,7 +31,21 @@ fn main() {
                 js_modules.iter().cloned().map(JsModuleSpec::from).collect()
             };
 
-            if let Err(err) = generate_wrapper_crate(wit, &modules, output, world.as_deref()) {
+            let options = WrapperCrateOptions {
+                error_catch_all_case: error_catch_all_case.clone(),
+                intl_locales: intl_locales.clone(),
+                heap_snapshot_export: *heap_snapshot_export,
+                run_tests_export: *run_tests_export,
+                coverage: *coverage,
+            };
+
+            if let Err(err) = generate_wrapper_crate_with_options(
+                wit,
+                &modules,
+                output,
+                world.as_deref(),
+                &options,
+            ) {
                 eprintln!("Error generating wrapper crate: {err:#}");
                 std::process::exit(1);
             }
@@ -34,6 +56,12 @@ fn main() {
                 std::process::exit(1);
             }
         }
+        Command::GenerateHostHarness { wit, output, world } => {
+            if let Err(err) = generate_host_harness(wit, output, world.as_deref()) {
+                eprintln!("Error generating host harness: {err:#}");
+                std::process::exit(1);
+            }
+        }
         Command::Optimize {
             input,
             output,
@@ -0,0 +1,251 @@
+//! A minimal authoritative DNS server for the `node:dns` resolver tests. It answers from a
+//! fixed zone over both UDP and TCP on the same port, truncates UDP responses larger than 512
+//! bytes, and has a few names that trigger error responses or no response at all.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::task::JoinHandle;
+
+const TYPE_A: u16 = 1;
+const TYPE_NS: u16 = 2;
+const TYPE_CNAME: u16 = 5;
+const TYPE_SOA: u16 = 6;
+const TYPE_PTR: u16 = 12;
+const TYPE_MX: u16 = 15;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const TYPE_SRV: u16 = 33;
+const TYPE_NAPTR: u16 = 35;
+const TYPE_TLSA: u16 = 52;
+const TYPE_ANY: u16 = 255;
+const TYPE_CAA: u16 = 257;
+
+const RCODE_SERVFAIL: u16 = 2;
+const RCODE_NXDOMAIN: u16 = 3;
+const RCODE_REFUSED: u16 = 5;
+
+const MAX_UDP_SIZE: usize = 512;
+
+/// Starts the server on an ephemeral port of 127.0.0.1 and returns the port
+pub async fn start_dns_server() -> (u16, JoinHandle<()>) {
+    let (udp, tcp) = loop {
+        let udp = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = udp.local_addr().unwrap().port();
+        if let Ok(tcp) = TcpListener::bind(("127.0.0.1", port)).await {
+            break (udp, tcp);
+        }
+    };
+    let port = udp.local_addr().unwrap().port();
+
+    let handle = tokio::spawn(async move {
+        let udp_task = tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            loop {
+                let Ok((len, peer)) = udp.recv_from(&mut buf).await else {
+                    break;
+                };
+                if let Some(response) = respond(&buf[..len], true) {
+                    let _ = udp.send_to(&response, peer).await;
+                }
+            }
+        });
+
+        loop {
+            let Ok((mut stream, _)) = tcp.accept().await else {
+                break;
+            };
+            tokio::spawn(async move {
+                loop {
+                    let mut len = [0u8; 2];
+                    if stream.read_exact(&mut len).await.is_err() {
+                        break;
+                    }
+                    let mut query = vec![0u8; u16::from_be_bytes(len) as usize];
+                    if stream.read_exact(&mut query).await.is_err() {
+                        break;
+                    }
+                    let Some(response) = respond(&query, false) else {
+                        break;
+                    };
+                    let mut framed = (response.len() as u16).to_be_bytes().to_vec();
+                    framed.extend_from_slice(&response);
+                    if stream.write_all(&framed).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        udp_task.abort();
+    });
+
+    (port, handle)
+}
+
+struct Record {
+    rtype: u16,
+    ttl: u32,
+    rdata: Vec<u8>,
+}
+
+fn record(rtype: u16, ttl: u32, rdata: Vec<u8>) -> Record {
+    Record { rtype, ttl, rdata }
+}
+
+fn encode_name(name: &str) -> Vec<u8> {
+    let mut result = Vec::new();
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        result.push(label.len() as u8);
+        result.extend_from_slice(label.as_bytes());
+    }
+    result.push(0);
+    result
+}
+
+fn character_string(value: &str) -> Vec<u8> {
+    let mut result = vec![value.len() as u8];
+    result.extend_from_slice(value.as_bytes());
+    result
+}
+
+fn txt(entries: &[&str]) -> Vec<u8> {
+    entries.iter().flat_map(|e| character_string(e)).collect()
+}
+
+/// The records of the zone for `name`, or `None` if the name does not exist
+fn lookup(name: &str) -> Option<Vec<Record>> {
+    let records = match name {
+        "example.test" => vec![
+            record(TYPE_A, 300, vec![192, 0, 2, 1]),
+            record(TYPE_A, 300, vec![192, 0, 2, 2]),
+            record(
+                TYPE_AAAA,
+                600,
+                vec![0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            ),
+            record(TYPE_MX, 3600, {
+                let mut rdata = 10u16.to_be_bytes().to_vec();
+                rdata.extend(encode_name("mail.example.test"));
+                rdata
+            }),
+            record(TYPE_TXT, 60, txt(&["v=spf1 -all"])),
+            record(TYPE_TXT, 60, txt(&["hello", "world"])),
+            record(TYPE_NS, 3600, encode_name("ns1.example.test")),
+            record(TYPE_SOA, 3600, {
+                let mut rdata = encode_name("ns1.example.test");
+                rdata.extend(encode_name("hostmaster.example.test"));
+                for value in [2024010101u32, 7200, 3600, 1209600, 300] {
+                    rdata.extend(value.to_be_bytes());
+                }
+                rdata
+            }),
+            record(TYPE_CAA, 3600, {
+                let mut rdata = vec![0];
+                rdata.extend(character_string("issue"));
+                rdata.extend_from_slice(b"letsencrypt.org");
+                rdata
+            }),
+        ],
+        "alias.example.test" => vec![record(TYPE_CNAME, 300, encode_name("example.test"))],
+        "_sip._tcp.example.test" => vec![record(TYPE_SRV, 300, {
+            let mut rdata = Vec::new();
+            for value in [10u16, 60, 5060] {
+                rdata.extend(value.to_be_bytes());
+            }
+            rdata.extend(encode_name("sip.example.test"));
+            rdata
+        })],
+        "naptr.example.test" => vec![record(TYPE_NAPTR, 300, {
+            let mut rdata = Vec::new();
+            for value in [100u16, 10] {
+                rdata.extend(value.to_be_bytes());
+            }
+            rdata.extend(character_string("S"));
+            rdata.extend(character_string("SIP+D2U"));
+            rdata.extend(character_string(""));
+            rdata.extend(encode_name("_sip._udp.example.test"));
+            rdata
+        })],
+        "_443._tcp.example.test" => vec![record(
+            TYPE_TLSA,
+            300,
+            vec![3, 1, 1, 0xde, 0xad, 0xbe, 0xef],
+        )],
+        "1.2.0.192.in-addr.arpa" => vec![record(TYPE_PTR, 300, encode_name("host.example.test"))],
+        "big.example.test" => (0..20)
+            .map(|i| record(TYPE_TXT, 60, txt(&[&format!("{i:02}-{}", "x".repeat(57))])))
+            .collect(),
+        _ => return None,
+    };
+    Some(records)
+}
+
+/// Reads the (uncompressed) question name at `offset`, returning it and the offset after it
+fn read_name(message: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    loop {
+        let len = *message.get(offset)? as usize;
+        offset += 1;
+        if len == 0 {
+            break;
+        }
+        labels.push(String::from_utf8_lossy(message.get(offset..offset + len)?).to_lowercase());
+        offset += len;
+    }
+    Some((labels.join("."), offset))
+}
+
+/// Builds the response to `query`, or `None` if the query should be dropped
+fn respond(query: &[u8], udp: bool) -> Option<Vec<u8>> {
+    if query.len() < 12 {
+        return None;
+    }
+    let (name, end) = read_name(query, 12)?;
+    let qtype = u16::from_be_bytes([*query.get(end)?, *query.get(end + 1)?]);
+    let question = query.get(12..end + 4)?;
+
+    let (rcode, answers) = match name.as_str() {
+        "drop.example.test" => return None,
+        "servfail.example.test" => (RCODE_SERVFAIL, Vec::new()),
+        "refused.example.test" => (RCODE_REFUSED, Vec::new()),
+        _ => match lookup(&name) {
+            Some(records) => (
+                0,
+                records
+                    .into_iter()
+                    .filter(|r| qtype == TYPE_ANY || r.rtype == qtype)
+                    .collect(),
+            ),
+            None => (RCODE_NXDOMAIN, Vec::new()),
+        },
+    };
+
+    let mut response = Vec::with_capacity(MAX_UDP_SIZE);
+    response.extend_from_slice(&query[0..2]);
+    // QR, AA and the RD bit of the query
+    let flags = 0x8400 | (u16::from_be_bytes([query[2], query[3]]) & 0x0100) | rcode;
+    response.extend(flags.to_be_bytes());
+    response.extend(1u16.to_be_bytes());
+    response.extend((answers.len() as u16).to_be_bytes());
+    response.extend([0, 0, 0, 0]);
+    response.extend_from_slice(question);
+    for answer in &answers {
+        // Owner name as a compression pointer to the question
+        response.extend([0xc0, 0x0c]);
+        response.extend(answer.rtype.to_be_bytes());
+        response.extend(1u16.to_be_bytes());
+        response.extend(answer.ttl.to_be_bytes());
+        response.extend((answer.rdata.len() as u16).to_be_bytes());
+        response.extend_from_slice(&answer.rdata);
+    }
+
+    if udp && response.len() > MAX_UDP_SIZE {
+        // Truncated: header with TC set, the question and no answers
+        let mut truncated = response[..12].to_vec();
+        truncated[2] |= 0x02;
+        truncated[6..8].copy_from_slice(&[0, 0]);
+        truncated.extend_from_slice(question);
+        return Some(truncated);
+    }
+    Some(response)
+}
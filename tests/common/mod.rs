@@ -1,3 +1,4 @@
+pub mod dns_server;
 pub mod js_subtest_parser;
 pub mod test_server;
 
@@ -16,7 +17,9 @@ use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use wac_graph::types::{Package, SubtypeChecker};
 use wac_graph::{CompositionGraph, EncodeOptions, PackageId, PlugError};
-use wasm_rquickjs::{EmbeddingMode, JsModuleSpec, generate_wrapper_crate};
+use wasm_rquickjs::{
+    EmbeddingMode, JsModuleSpec, WrapperCrateOptions, generate_wrapper_crate_with_options,
+};
 use wasmtime::component::{
     Component, Func, Instance, Linker, ResourceAny, ResourceTable, ResourceType, Val,
 };
@@ -396,6 +399,11 @@ pub enum FeatureCombination {
     Golem,
     FullWithGolem,
     FullNoLoggingWithGolem,
+    PerCallIsolation,
+    Deterministic,
+    RelaxedArity,
+    IntlFull,
+    Temporal,
 }
 
 impl FeatureCombination {
@@ -413,6 +421,11 @@ impl FeatureCombination {
             Self::Golem => "golem",
             Self::FullWithGolem => "full-golem",
             Self::FullNoLoggingWithGolem => "full-no-logging-golem",
+            Self::PerCallIsolation => "per-call-isolation",
+            Self::Deterministic => "deterministic",
+            Self::RelaxedArity => "relaxed-arity",
+            Self::IntlFull => "intl-full",
+            Self::Temporal => "temporal",
         }
     }
 
@@ -440,6 +453,11 @@ impl FeatureCombination {
                     "full-no-logging,golem",
                 ]
             }
+            FeatureCombination::PerCallIsolation => vec!["--features", "per-call-isolation"],
+            FeatureCombination::Deterministic => vec!["--features", "deterministic"],
+            FeatureCombination::RelaxedArity => vec!["--features", "relaxed-arity"],
+            FeatureCombination::IntlFull => vec!["--features", "intl-full"],
+            FeatureCombination::Temporal => vec!["--features", "temporal"],
         }
     }
 }
@@ -843,18 +861,25 @@ impl TestInstance {
         Self::from_prepared(&prepared).await
     }
 
+    /// Instantiates the component with additional environment variables
+    pub async fn new_with_env(wasm_path: &Utf8Path, env: &[(&str, &str)]) -> anyhow::Result<Self> {
+        let prepared = PreparedComponent::new(wasm_path)?;
+        Self::from_parts(&prepared.engine, &prepared.linker, &prepared.component, env).await
+    }
+
     pub async fn from_prepared(prepared: &PreparedComponent) -> anyhow::Result<Self> {
-        Self::from_parts(&prepared.engine, &prepared.linker, &prepared.component).await
+        Self::from_parts(&prepared.engine, &prepared.linker, &prepared.component, &[]).await
     }
 
     pub async fn from_golem_prepared(prepared: &GolemPreparedComponent) -> anyhow::Result<Self> {
-        Self::from_parts(&prepared.engine, &prepared.linker, &prepared.component).await
+        Self::from_parts(&prepared.engine, &prepared.linker, &prepared.component, &[]).await
     }
 
     async fn from_parts(
         engine: &Engine,
         linker: &Linker<Host>,
         component: &Component,
+        env: &[(&str, &str)],
     ) -> anyhow::Result<Self> {
         let stdout_file = NamedUtf8TempFile::new()?;
         let stderr_file = NamedUtf8TempFile::new()?;
@@ -871,6 +896,7 @@ impl TestInstance {
             .arg("second-arg")
             .env("TEST_KEY", "TEST_VALUE")
             .env("TEST_KEY_2", "TEST_VALUE_2")
+            .envs(env)
             .preopened_dir(&temp_dir, "/", DirPerms::all(), FilePerms::all())?
             .inherit_network()
             .allow_ip_name_lookup(true);
@@ -1091,16 +1117,54 @@ impl CompiledTest {
         use_shared_target: bool,
         feature_combination: FeatureCombination,
     ) -> anyhow::Result<CompiledTest> {
-        Self::compile_with_features(path, use_shared_target, feature_combination).await
+        Self::compile_with_features(
+            path,
+            use_shared_target,
+            feature_combination,
+            &WrapperCrateOptions::default(),
+        )
+        .await
     }
 
     pub async fn new_with_features(
         path: &Utf8Path,
         use_shared_target: bool,
         feature_combination: FeatureCombination,
+    ) -> anyhow::Result<CompiledTest> {
+        let compiled = Self::compile_with_features(
+            path,
+            use_shared_target,
+            feature_combination,
+            &WrapperCrateOptions::default(),
+        )
+        .await?;
+        compiled.optimize().await
+    }
+
+    pub async fn new_with_options(
+        path: &Utf8Path,
+        use_shared_target: bool,
+        options: &WrapperCrateOptions,
+    ) -> anyhow::Result<CompiledTest> {
+        let compiled = Self::compile_with_features(
+            path,
+            use_shared_target,
+            FeatureCombination::Normal,
+            options,
+        )
+        .await?;
+        compiled.optimize().await
+    }
+
+    pub async fn new_with_features_and_options(
+        path: &Utf8Path,
+        use_shared_target: bool,
+        feature_combination: FeatureCombination,
+        options: &WrapperCrateOptions,
     ) -> anyhow::Result<CompiledTest> {
         let compiled =
-            Self::compile_with_features(path, use_shared_target, feature_combination).await?;
+            Self::compile_with_features(path, use_shared_target, feature_combination, options)
+                .await?;
         compiled.optimize().await
     }
 
@@ -1108,6 +1172,7 @@ impl CompiledTest {
         path: &Utf8Path,
         use_shared_target: bool,
         feature_combination: FeatureCombination,
+        options: &WrapperCrateOptions,
     ) -> anyhow::Result<CompiledTest> {
         let name = path.file_name().unwrap();
         let wrapper_crate_root = Utf8Path::new("tmp")
@@ -1120,7 +1185,7 @@ impl CompiledTest {
         let shared_target = Utf8Path::new("..").join("..").join("rt-target");
 
         println!("Generating wrapper create for example '{name}' to {wrapper_crate_root}");
-        generate_wrapper_crate(
+        generate_wrapper_crate_with_options(
             &path.join("wit"),
             &[JsModuleSpec {
                 name: name.to_string(),
@@ -1128,6 +1193,7 @@ impl CompiledTest {
             }],
             &wrapper_crate_root,
             None,
+            options,
         )?;
 
         println!("Compiling wrapper crate in {wrapper_crate_root}");
@@ -1,4 +1,5 @@
 use axum::body::Body;
+use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade};
 use axum::extract::{Multipart, Path};
 use axum::http::HeaderMap;
 use axum::response::{AppendHeaders, IntoResponse};
@@ -14,6 +15,29 @@ use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio_util::io::ReaderStream;
 
+const COMPRESSED_TEXT: &str =
+    "hello compressed world, hello compressed world, hello compressed world";
+
+/// `COMPRESSED_TEXT` gzip compressed
+const GZIP_TEXT: &[u8] = &[
+    0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57,
+    0x48, 0xce, 0xcf, 0x2d, 0x28, 0x4a, 0x2d, 0x2e, 0x4e, 0x4d, 0x51, 0x28, 0xcf, 0x2f, 0xca, 0x49,
+    0xd1, 0x51, 0xc8, 0x20, 0x49, 0x1c, 0x00, 0xdb, 0x50, 0x94, 0xf0, 0x46, 0x00, 0x00, 0x00,
+];
+
+/// `COMPRESSED_TEXT` zlib compressed (what HTTP calls `deflate`)
+const ZLIB_TEXT: &[u8] = &[
+    0x78, 0x9c, 0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0x48, 0xce, 0xcf, 0x2d, 0x28, 0x4a, 0x2d, 0x2e,
+    0x4e, 0x4d, 0x51, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0xd1, 0x51, 0xc8, 0x20, 0x49, 0x1c, 0x00, 0xb1,
+    0xa8, 0x1a, 0xac,
+];
+
+/// `COMPRESSED_TEXT` as a raw deflate stream, as sent by some servers for `deflate`
+const RAW_DEFLATE_TEXT: &[u8] = &[
+    0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0x48, 0xce, 0xcf, 0x2d, 0x28, 0x4a, 0x2d, 0x2e, 0x4e, 0x4d,
+    0x51, 0x28, 0xcf, 0x2f, 0xca, 0x49, 0xd1, 0x51, 0xc8, 0x20, 0x49, 0x1c, 0x00,
+];
+
 pub async fn start_test_server() -> (u16, JoinHandle<()>) {
     let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.unwrap();
     let host_http_port = listener.local_addr().unwrap().port();
@@ -209,6 +233,37 @@ pub async fn start_test_server() -> (u16, JoinHandle<()>) {
                     )
                         .into_response()
                 }),
+            )
+            .route(
+                "/compressed/{kind}",
+                get(async move |Path(kind): Path<String>, headers: HeaderMap| {
+                    let (content_encoding, body): (Option<&str>, &[u8]) = match kind.as_str() {
+                        "gzip" => (Some("gzip"), GZIP_TEXT),
+                        "deflate" => (Some("deflate"), ZLIB_TEXT),
+                        "deflate-raw" => (Some("deflate"), RAW_DEFLATE_TEXT),
+                        "unknown" => (Some("compress"), COMPRESSED_TEXT.as_bytes()),
+                        _ => (None, COMPRESSED_TEXT.as_bytes()),
+                    };
+                    let accept_encoding = headers
+                        .get(header::ACCEPT_ENCODING)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or("none")
+                        .to_string();
+
+                    let mut response_headers = HeaderMap::new();
+                    response_headers.insert("x-accept-encoding", accept_encoding.parse().unwrap());
+                    if let Some(content_encoding) = content_encoding {
+                        response_headers
+                            .insert(header::CONTENT_ENCODING, content_encoding.parse().unwrap());
+                    }
+                    (response_headers, body.to_vec()).into_response()
+                }),
+            )
+            .route(
+                "/ws/echo",
+                get(async move |ws: WebSocketUpgrade| {
+                    ws.protocols(["chat"]).on_upgrade(websocket_echo)
+                }),
             );
 
         axum::serve(listener, router).await.unwrap();
@@ -217,6 +272,41 @@ pub async fn start_test_server() -> (u16, JoinHandle<()>) {
     (host_http_port, handle)
 }
 
+/// Echoes text and binary messages; the text message `close` makes the server close the
+/// connection with code 4000, and `ping` is answered with a ping before being echoed
+async fn websocket_echo(mut socket: WebSocket) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let reply = match message {
+            Message::Text(text) if text.as_str() == "close" => {
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: 4000,
+                        reason: "server closing".into(),
+                    })))
+                    .await;
+                continue;
+            }
+            Message::Text(text) if text.as_str() == "ping" => {
+                if socket
+                    .send(Message::Ping(Bytes::from_static(b"are you there")))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+                Message::Text(text)
+            }
+            Message::Text(text) => Message::Text(text),
+            Message::Binary(data) => Message::Binary(data),
+            Message::Ping(_) | Message::Pong(_) => continue,
+            Message::Close(_) => break,
+        };
+        if socket.send(reply).await.is_err() {
+            break;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Todo {
@@ -5,7 +5,7 @@ use camino::Utf8Path;
 use std::process::Command;
 use test_r::core::{DynamicTestRegistration, TestProperties};
 use test_r::{add_test, test_gen};
-use wasm_rquickjs::{EmbeddingMode, JsModuleSpec, generate_wrapper_crate};
+use wasm_rquickjs::{EmbeddingMode, JsModuleSpec, generate_host_harness, generate_wrapper_crate};
 
 #[allow(dead_code)]
 mod common;
@@ -74,3 +74,55 @@ fn compilation_test(
 
     Ok(())
 }
+
+/// Runtime examples covering world-level and interface exports, resources, imports and WASI
+/// imports, used to check that the generated host harness crates compile
+const HOST_HARNESS_EXAMPLES: &[&str] = &[
+    "example1",
+    "example3",
+    "export-interface-name-collision",
+    "greeter",
+    "imports1",
+    "imports2",
+    "pollable",
+    "types-in-exports",
+    "variant-list-roundtrip",
+];
+
+#[test_gen]
+fn gen_host_harness_compilation_tests(r: &mut DynamicTestRegistration) {
+    for example_name in HOST_HARNESS_EXAMPLES {
+        add_test!(
+            r,
+            format!("host_harness_{example_name}"),
+            TestProperties {
+                ..TestProperties::unit_test()
+            },
+            || { host_harness_compilation_test(example_name) }
+        );
+    }
+}
+
+fn host_harness_compilation_test(name: &str) -> anyhow::Result<()> {
+    let example_path = Utf8Path::new("examples").join("runtime").join(name);
+    let harness_crate_root = Utf8Path::new("tmp").join("host-harness").join(name);
+
+    // shared_target is relative to harness_crate_root
+    let shared_target = Utf8Path::new("..").join("..").join("..").join("target");
+
+    println!("Generating host harness for example '{name}' to {harness_crate_root}");
+    generate_host_harness(&example_path.join("wit"), &harness_crate_root, None)?;
+
+    println!("Compiling host harness in {harness_crate_root}");
+    let status = Command::new("cargo")
+        .arg("check")
+        .arg("--all-targets")
+        .arg("--target-dir")
+        .arg(shared_target)
+        .current_dir(&harness_crate_root)
+        .status()?;
+
+    assert!(status.success());
+
+    Ok(())
+}
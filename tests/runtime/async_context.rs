@@ -0,0 +1,159 @@
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "async_context", scope = Cloneable)]
+async fn compiled_async_context() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/async-context");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile async-context")
+}
+
+async fn assert_log(
+    compiled: &CompiledTest,
+    function: &str,
+    expected: &[&str],
+) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("Output:\n{output}");
+    assert_eq!(r?, Some(Val::String(expected.join("\n"))));
+    Ok(())
+}
+
+#[test]
+async fn async_context_concurrent_runs(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "concurrent-runs",
+        &["a: a,a,a,a,a,a", "b: b,b,b,b,b,b", "outside: none"],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_rejected_await(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "rejected-await",
+        &["catch failed: r", "finally: r", "after: r", "outside: none"],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_for_await(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "for-await",
+        &[
+            "x 0: x",
+            "x 0: x",
+            "y 0: y",
+            "y 0: y",
+            "x 1: x",
+            "x 10: x",
+            "y 1: y",
+            "y 10: y",
+            "x 2: x",
+            "x 20: x",
+            "x done: x",
+            "y 2: y",
+            "y 20: y",
+            "y done: y",
+            "outside: none",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_async_generator(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    // Like in Node.js, the generator continues in the context of the caller of `next()`
+    assert_log(
+        compiled,
+        "async-generator",
+        &[
+            "start: first",
+            "received value: second",
+            "after sleep: second",
+            "outside: none",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_enter_with(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "enter-with",
+        &["task: entered", "outer: entered", "outside: none"],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_callbacks(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "callbacks",
+        &[
+            "nextTick: c",
+            "setTimeout: c",
+            "snapshot: snapshot",
+            "bind: bound",
+            "outside: none",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_bind_store(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "bind-store",
+        &["start: span", "after await: span", "result traced: none"],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_domains(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "domains",
+        &["after await: true", "then: true", "outside: false"],
+    )
+    .await
+}
+
+#[test]
+async fn async_context_evaluated_code(
+    #[tagged_as("async_context")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "evaluated-code",
+        &["evaluated: e", "outside: none"],
+    )
+    .await
+}
@@ -0,0 +1,165 @@
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "async_hooks", scope = Cloneable)]
+async fn compiled_async_hooks() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/async-hooks");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile async-hooks")
+}
+
+async fn invoke_log(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("Output:\n{output}");
+    match r? {
+        Some(Val::String(log)) => Ok(log),
+        other => anyhow::bail!("Unexpected result: {other:?}"),
+    }
+}
+
+async fn assert_log(
+    compiled: &CompiledTest,
+    function: &str,
+    expected: &[&str],
+) -> anyhow::Result<()> {
+    assert_eq!(invoke_log(compiled, function).await?, expected.join("\n"));
+    Ok(())
+}
+
+#[test]
+async fn async_hooks_timers(
+    #[tagged_as("async_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "timers",
+        &[
+            "init Timeout#1",
+            "before Timeout#1",
+            "timeout callback in Timeout#1",
+            "init Immediate#2 from Timeout#1",
+            "after Timeout#1",
+            "destroy Timeout#1",
+            "before Immediate#2",
+            "immediate callback in Immediate#2",
+            "init TickObject#3 from Immediate#2",
+            "after Immediate#2",
+            "destroy Immediate#2",
+            "before TickObject#3",
+            "tick callback in TickObject#3",
+            "init Microtask#4 from TickObject#3",
+            "after TickObject#3",
+            "destroy TickObject#3",
+            "before Microtask#4",
+            "microtask callback in Microtask#4",
+            "init Timeout#5 from Microtask#4",
+            "destroy Timeout#5",
+            "after Microtask#4",
+            "destroy Microtask#4",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_hooks_promises(
+    #[tagged_as("async_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "promises",
+        &[
+            "init PROMISE#1",
+            "resolve PROMISE#1",
+            "init PROMISE#2 from PROMISE#1",
+            "before PROMISE#2",
+            "then callback in PROMISE#2, resource is promise: true",
+            "after PROMISE#2",
+            "resolve PROMISE#2",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_hooks_resources(
+    #[tagged_as("async_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "resources",
+        &[
+            "init Custom#1",
+            "before Custom#1",
+            "outer callback in Custom#1, trigger true",
+            "init Custom#2 from Custom#1",
+            "after Custom#1",
+            "before Custom#2",
+            "inner callback in Custom#2",
+            "after Custom#2",
+            "destroy Custom#2",
+            "destroy Custom#1",
+            "outside in none, top-level id: 1",
+            "invalid hook: ERR_ASYNC_CALLBACK",
+            "invalid type: ERR_INVALID_ARG_TYPE",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_hooks_fs_callbacks(
+    #[tagged_as("async_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_log(
+        compiled,
+        "fs-callbacks",
+        &[
+            "init FSREQCALLBACK#1",
+            "before FSREQCALLBACK#1",
+            "access callback in FSREQCALLBACK#1, failed: true",
+            "after FSREQCALLBACK#1",
+            "destroy FSREQCALLBACK#1",
+        ],
+    )
+    .await
+}
+
+#[test]
+async fn async_hooks_sockets(
+    #[tagged_as("async_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    // The order of the client and server side events depends on the scheduling of the host
+    let log = invoke_log(compiled, "sockets").await?;
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.first(), Some(&"init TCPSERVERWRAP#1"));
+    assert!(lines.contains(&"connection in TCPSERVERWRAP#1"));
+    assert!(lines.contains(&"destroy TCPSERVERWRAP#1"));
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("connect in TCPWRAP#"))
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("data hello in TCPWRAP#"))
+    );
+    Ok(())
+}
+
+#[test]
+async fn async_hooks_exported_names(
+    #[tagged_as("async_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let public = "AsyncLocalStorage, AsyncResource, createHook, executionAsyncId, executionAsyncResource, triggerAsyncId";
+    assert_log(
+        compiled,
+        "exported-names",
+        &[&format!("named: {public}"), &format!("default: {public}")],
+    )
+    .await
+}
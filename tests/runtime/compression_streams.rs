@@ -0,0 +1,117 @@
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "compression_streams", scope = Cloneable)]
+async fn compiled_compression_streams() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/compression-streams");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Full)
+        .await
+        .expect("Failed to compile compression-streams")
+}
+
+const FORMATS: [&str; 4] = ["gzip", "deflate", "deflate-raw", "br"];
+
+const TEXT: &str =
+    "The quick brown fox jumps over the lazy dog. The quick brown fox jumps over the lazy dog.";
+
+async fn call(compiled: &CompiledTest, function: &str, args: &[&str]) -> anyhow::Result<String> {
+    let args = args
+        .iter()
+        .map(|arg| Val::String(arg.to_string()))
+        .collect::<Vec<_>>();
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &args).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn compression_streams_roundtrip(
+    #[tagged_as("compression_streams")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    for format in FORMATS {
+        assert_eq!(
+            call(compiled, "roundtrip", &[format, TEXT]).await?,
+            TEXT,
+            "{format}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn compression_streams_interoperate_with_node_zlib(
+    #[tagged_as("compression_streams")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    for format in FORMATS {
+        assert_eq!(
+            call(compiled, "compress-for-zlib", &[format, TEXT]).await?,
+            TEXT,
+            "{format}"
+        );
+        assert_eq!(
+            call(compiled, "decompress-from-zlib", &[format, TEXT]).await?,
+            TEXT,
+            "{format}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn compression_streams_reject_invalid_format(
+    #[tagged_as("compression_streams")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    for format in ["gzip1", "brotli", ""] {
+        assert_eq!(
+            call(compiled, "invalid-format", &[format]).await?,
+            "TypeError ERR_INVALID_ARG_VALUE",
+            "{format}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn decompression_stream_rejects_truncated_input(
+    #[tagged_as("compression_streams")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    for format in FORMATS {
+        assert_eq!(
+            call(compiled, "truncated-input", &[format]).await?,
+            "TypeError",
+            "{format}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn decompression_stream_rejects_trailing_data(
+    #[tagged_as("compression_streams")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    for format in ["gzip", "deflate", "deflate-raw"] {
+        assert_eq!(
+            call(compiled, "trailing-data", &[format]).await?,
+            "TypeError",
+            "{format}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn compression_streams_class_shape(
+    #[tagged_as("compression_streams")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_eq!(
+        call(compiled, "class-shape", &[]).await?,
+        "toStringTag=[object CompressionStream] readable=true writable=true decompressionToStringTag=[object DecompressionStream] stringChunk=TypeError"
+    );
+    Ok(())
+}
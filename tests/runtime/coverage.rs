@@ -0,0 +1,155 @@
+use crate::common::{CompiledTest, TestInstance};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasm_rquickjs::WrapperCrateOptions;
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "coverage", scope = Cloneable)]
+async fn compiled_coverage() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/coverage");
+    CompiledTest::new_with_options(
+        path,
+        true,
+        &WrapperCrateOptions {
+            coverage: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to compile coverage")
+}
+
+async fn classify(instance: &mut TestInstance, n: i32) -> anyhow::Result<String> {
+    let (r, output) = instance
+        .invoke_and_capture_output(None, "classify", &[Val::S32(n)])
+        .await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+/// The Istanbul coverage of the example module
+fn module_coverage(directory: &Utf8Path) -> anyhow::Result<serde_json::Value> {
+    let report: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(directory.join("coverage-final.json"))?)?;
+    let (path, coverage) = report
+        .as_object()
+        .and_then(|modules| modules.iter().next())
+        .ok_or_else(|| anyhow::anyhow!("no module in the report"))?;
+    assert!(path.ends_with("examples/runtime/coverage/src/coverage.js"));
+    Ok(coverage.clone())
+}
+
+/// The counter of the function with the given name
+fn function_count(coverage: &serde_json::Value, name: &str) -> Option<u64> {
+    let (id, _) = coverage["fnMap"]
+        .as_object()?
+        .iter()
+        .find(|(_, function)| function["name"] == name)?;
+    coverage["f"][id].as_u64()
+}
+
+/// The counters of the branches starting on the given line
+fn branch_counts(coverage: &serde_json::Value, line: u64) -> Vec<serde_json::Value> {
+    coverage["branchMap"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, branch)| branch["loc"]["start"]["line"] == line)
+        .map(|(id, _)| coverage["b"][id].clone())
+        .collect()
+}
+
+#[test]
+async fn coverage_reports_collected_across_calls(
+    #[tagged_as("coverage")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    assert_eq!(classify(&mut instance, 3).await?, "positive odd large");
+    assert_eq!(classify(&mut instance, -2).await?, "negative even small");
+
+    // Exported calls do not write the reports by themselves
+    let directory = instance.temp_dir_path().join("coverage");
+    assert!(!directory.join("lcov.info").exists());
+
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "take-coverage", &[])
+        .await;
+    r?;
+    let coverage = module_coverage(&directory)?;
+    assert_eq!(function_count(&coverage, "classify"), Some(2));
+    assert_eq!(function_count(&coverage, "sign"), Some(2));
+    assert_eq!(function_count(&coverage, "parity"), Some(2));
+    assert_eq!(function_count(&coverage, "neverCalled"), Some(0));
+
+    // if (n > 0) ... else if (n < 0)
+    assert_eq!(branch_counts(&coverage, 5), vec![serde_json::json!([1, 1])]);
+    assert_eq!(branch_counts(&coverage, 7), vec![serde_json::json!([1, 0])]);
+    // n % 2 === 0 ? 'even' : 'odd'
+    assert_eq!(
+        branch_counts(&coverage, 13),
+        vec![serde_json::json!([1, 1])]
+    );
+    // case 0, case 1, case 2, default
+    assert_eq!(
+        branch_counts(&coverage, 16),
+        vec![serde_json::json!([0, 0, 1, 1])]
+    );
+
+    let lcov = std::fs::read_to_string(directory.join("lcov.info"))?;
+    assert!(lcov.starts_with("TN:\nSF:"));
+    assert!(lcov.contains("\nFN:27,neverCalled\n"));
+    assert!(lcov.contains("\nFNDA:0,neverCalled\n"));
+    assert!(lcov.contains("\nFNDA:2,sign\n"));
+    assert!(lcov.contains("\nDA:10,0\n"));
+    assert!(lcov.contains("\nDA:28,0\n"));
+    assert!(lcov.contains("\nBRDA:16,3,3,1\n"));
+    assert!(lcov.ends_with("end_of_record\n"));
+    Ok(())
+}
+
+#[test]
+async fn coverage_take_coverage(
+    #[tagged_as("coverage")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance =
+        TestInstance::new_with_env(compiled.wasm_path(), &[("NODE_V8_COVERAGE", "/reports")])
+            .await?;
+    let (r, output) = instance
+        .invoke_and_capture_output(None, "take-coverage", &[])
+        .await;
+    println!("{output}");
+
+    let Some(Val::String(lcov)) = r? else {
+        anyhow::bail!("unexpected result");
+    };
+    assert!(lcov.contains("\nFNDA:1,label\n"));
+    assert!(lcov.contains("\nFNDA:0,classify\n"));
+    assert!(
+        instance
+            .temp_dir_path()
+            .join("reports/coverage-final.json")
+            .exists()
+    );
+    Ok(())
+}
+
+#[test]
+async fn coverage_stop_coverage(
+    #[tagged_as("coverage")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    classify(&mut instance, 1).await?;
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "stop-coverage", &[])
+        .await;
+    r?;
+    classify(&mut instance, 1).await?;
+
+    let coverage = module_coverage(&instance.temp_dir_path().join("coverage"))?;
+    assert_eq!(function_count(&coverage, "classify"), Some(1));
+    Ok(())
+}
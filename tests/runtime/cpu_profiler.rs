@@ -0,0 +1,201 @@
+use crate::common::{CompiledTest, FeatureCombination, TestInstance, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "cpu_profiler", scope = Cloneable)]
+async fn compiled_cpu_profiler() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/cpu-profiler");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile cpu-profiler")
+}
+
+#[test_dep(tagged_as = "cpu_profiler_isolated", scope = Cloneable)]
+async fn compiled_cpu_profiler_isolated() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/cpu-profiler");
+    CompiledTest::new_with_features(path, true, FeatureCombination::PerCallIsolation)
+        .await
+        .expect("Failed to compile cpu-profiler")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+fn function_names(profile: &serde_json::Value) -> Vec<String> {
+    profile["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node["callFrame"]["functionName"].as_str())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[test]
+async fn cpu_profiler_session_profile(
+    #[tagged_as("cpu_profiler")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "session-profile").await?;
+    assert_eq!(
+        result,
+        r#"keys nodes,startTime,endTime,samples,timeDeltas
+root (root) 1
+samples true true
+hits add up true
+fib dominates true
+call path sessionProfile > busy > fib
+script true
+position ticks true
+timing true
+stack trace settings untouched true"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn cpu_profiler_concurrent_sessions(
+    #[tagged_as("cpu_profiler")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "concurrent-sessions").await?;
+    assert_eq!(
+        result,
+        r#"both recorded true true
+second started later true
+first stopped earlier true"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn cpu_profiler_session_errors(
+    #[tagged_as("cpu_profiler")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "session-errors").await?;
+    assert_eq!(
+        result,
+        r#"not connected: ERR_INSPECTOR_NOT_CONNECTED Session is not connected
+connected twice: ERR_INSPECTOR_ALREADY_CONNECTED
+start disabled: ERR_INSPECTOR_COMMAND Inspector error -32000: Profiler is not enabled
+enable: {}
+stop idle: ERR_INSPECTOR_COMMAND Inspector error -32000: No recording profiles found
+start: {}
+interval while profiling: ERR_INSPECTOR_COMMAND Inspector error -32000: Cannot change sampling interval when profiling.
+unknown: ERR_INSPECTOR_COMMAND Inspector error -32601: 'Debugger.enable' wasn't found
+stop after reconnect: ERR_INSPECTOR_COMMAND Inspector error -32000: No recording profiles found"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn cpu_profiler_environment_cpuprofile(
+    #[tagged_as("cpu_profiler")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new_with_env(
+        compiled.wasm_path(),
+        &[
+            ("WASM_RQUICKJS_CPU_PROF", "/run.cpuprofile"),
+            ("WASM_RQUICKJS_CPU_PROF_INTERVAL", "100"),
+        ],
+    )
+    .await?;
+    let path = instance.temp_dir_path().join("run.cpuprofile");
+
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "busy-work", &[Val::U32(100)])
+        .await;
+    r?;
+    let first: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "busy-work", &[Val::U32(100)])
+        .await;
+    r?;
+    let second: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+
+    let sample_count = |profile: &serde_json::Value| {
+        profile["samples"]
+            .as_array()
+            .map(|samples| samples.len())
+            .unwrap_or_default()
+    };
+    let names = function_names(&second);
+    assert!(sample_count(&first) > 10);
+    assert!(sample_count(&second) > sample_count(&first));
+    assert_eq!(first["startTime"], second["startTime"]);
+    assert!(names.iter().any(|name| name == "fib"));
+    assert!(names.iter().any(|name| name == "(idle)"));
+    Ok(())
+}
+
+#[test]
+async fn cpu_profiler_environment_profile_with_isolation(
+    #[tagged_as("cpu_profiler_isolated")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new_with_env(
+        compiled.wasm_path(),
+        &[
+            ("WASM_RQUICKJS_CPU_PROF", "/run.cpuprofile"),
+            ("WASM_RQUICKJS_CPU_PROF_INTERVAL", "100"),
+        ],
+    )
+    .await?;
+    let path = instance.temp_dir_path().join("run.cpuprofile");
+
+    for _ in 0..3 {
+        let (r, _) = instance
+            .invoke_and_capture_output(None, "busy-work", &[Val::U32(100)])
+            .await;
+        r?;
+        // The recording is restored together with the rest of the state before every call, so
+        // each written profile covers the last call
+        let profile: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+        assert!(function_names(&profile).iter().any(|name| name == "fib"));
+    }
+    Ok(())
+}
+
+#[test]
+async fn cpu_profiler_environment_speedscope(
+    #[tagged_as("cpu_profiler")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new_with_env(
+        compiled.wasm_path(),
+        &[("WASM_RQUICKJS_CPU_PROF", "/run.speedscope.json")],
+    )
+    .await?;
+
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "busy-work", &[Val::U32(100)])
+        .await;
+    r?;
+
+    let document: serde_json::Value = serde_json::from_slice(&std::fs::read(
+        instance.temp_dir_path().join("run.speedscope.json"),
+    )?)?;
+    let profile = &document["profiles"][0];
+    let frames = document["shared"]["frames"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    assert_eq!(
+        document["$schema"],
+        "https://www.speedscope.app/file-format-schema.json"
+    );
+    assert_eq!(profile["type"], "sampled");
+    assert_eq!(profile["unit"], "microseconds");
+    assert_eq!(
+        profile["samples"].as_array().map(|s| s.len()),
+        profile["weights"].as_array().map(|w| w.len())
+    );
+    assert!(frames.iter().any(|frame| frame["name"] == "fib"));
+    Ok(())
+}
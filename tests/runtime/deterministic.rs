@@ -0,0 +1,101 @@
+use crate::common::{CompiledTest, FeatureCombination, TestInstance, invoke_and_capture_output};
+use camino::Utf8Path;
+use std::time::{Duration, Instant};
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "deterministic", scope = Cloneable)]
+async fn compiled_deterministic() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/deterministic");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Deterministic)
+        .await
+        .expect("Failed to compile deterministic")
+}
+
+// Without Wizer pre-initialization, so the runtime creates its JS context and reads the seed at
+// startup
+#[test_dep(tagged_as = "deterministic_unoptimized", scope = Cloneable)]
+async fn compiled_deterministic_unoptimized() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/deterministic");
+    CompiledTest::new_unoptimized_with_features(path, true, FeatureCombination::Deterministic)
+        .await
+        .expect("Failed to compile deterministic")
+}
+
+async fn snapshot_with_seed(compiled: &CompiledTest, seed: &str) -> anyhow::Result<String> {
+    let mut test_instance =
+        TestInstance::new_with_env(compiled.wasm_path(), &[("WASM_RQUICKJS_SEED", seed)]).await?;
+    let (r, _) = test_instance
+        .invoke_and_capture_output(None, "snapshot", &[])
+        .await;
+    let Some(Val::String(s)) = r? else {
+        panic!("Expected string")
+    };
+    Ok(s)
+}
+
+#[test]
+async fn seed_selects_random_values(
+    #[tagged_as("deterministic_unoptimized")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let random = |snapshot: &str| -> anyhow::Result<serde_json::Value> {
+        let value: serde_json::Value = serde_json::from_str(snapshot)?;
+        Ok(value["random"].clone())
+    };
+
+    let first = snapshot_with_seed(compiled, "1").await?;
+    let again = snapshot_with_seed(compiled, "1").await?;
+    let other = snapshot_with_seed(compiled, "2").await?;
+
+    assert_eq!(first, again);
+    assert_ne!(random(&first)?, random(&other)?, "{first}\n{other}");
+
+    Ok(())
+}
+
+#[test]
+async fn snapshot_is_reproducible(
+    #[tagged_as("deterministic")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r1, _) = invoke_and_capture_output(compiled.wasm_path(), None, "snapshot", &[]).await;
+    let (r2, _) = invoke_and_capture_output(compiled.wasm_path(), None, "snapshot", &[]).await;
+
+    let Some(Val::String(s1)) = r1? else {
+        panic!("Expected string")
+    };
+    let Some(Val::String(s2)) = r2? else {
+        panic!("Expected string")
+    };
+
+    assert_eq!(s1, s2);
+    assert!(s1.contains(r#""now":1704067200000"#), "{s1}");
+    assert!(s1.contains(r#""date":"2024-01-01T00:00:00.000Z""#), "{s1}");
+
+    Ok(())
+}
+
+#[test]
+async fn timers_advance_virtual_clock(
+    #[tagged_as("deterministic")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut test_instance = TestInstance::new(compiled.wasm_path()).await?;
+
+    let start = Instant::now();
+    let (r, _) = test_instance
+        .invoke_and_capture_output(None, "sleep-virtual", &[Val::U32(600_000)])
+        .await;
+    let real_elapsed = start.elapsed();
+
+    assert_eq!(r?, Some(Val::U32(600_000)));
+    assert!(real_elapsed < Duration::from_secs(60));
+
+    let (r, _) = test_instance
+        .invoke_and_capture_output(None, "snapshot", &[])
+        .await;
+    let Some(Val::String(s)) = r? else {
+        panic!("Expected string")
+    };
+    assert!(s.contains(r#""now":1704067800000"#), "{s}");
+
+    Ok(())
+}
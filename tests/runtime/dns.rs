@@ -146,10 +146,10 @@ async fn dns_module_api(#[tagged_as("dns")] compiled: &CompiledTest) -> anyhow::
             "family mismatch should return ENOTFOUND"
         );
 
-        // Unsupported record types
+        // Record queries without servers
         assert!(
-            result_obj["unsupportedMx"].as_bool().unwrap_or(false),
-            "resolveMx should return ENOTIMP"
+            result_obj["noServersMx"].as_bool().unwrap_or(false),
+            "resolveMx without servers should return ECONNREFUSED"
         );
 
         // Hint flags
@@ -0,0 +1,108 @@
+use crate::common::dns_server::start_dns_server;
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "dns_resolver", scope = Cloneable)]
+async fn compiled_dns_resolver() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/dns-resolver");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile dns-resolver")
+}
+
+async fn call(compiled: &CompiledTest, function: &str, port: u16) -> anyhow::Result<String> {
+    let (r, output) =
+        invoke_and_capture_output(compiled.wasm_path(), None, function, &[Val::U16(port)]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn dns_resolver_records(
+    #[tagged_as("dns_resolver")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_dns_server().await;
+    let result = call(compiled, "records", port).await?;
+    assert_eq!(
+        result,
+        r#"A ["192.0.2.1","192.0.2.2"]
+A ttl [{"address":"192.0.2.1","ttl":300},{"address":"192.0.2.2","ttl":300}]
+AAAA ["2001:db8::1"]
+MX [{"priority":10,"exchange":"mail.example.test"}]
+TXT [["v=spf1 -all"],["hello","world"]]
+NS ["ns1.example.test"]
+SOA {"nsname":"ns1.example.test","hostmaster":"hostmaster.example.test","serial":2024010101,"refresh":7200,"retry":3600,"expire":1209600,"minttl":300}
+CAA [{"critical":0,"issue":"letsencrypt.org"}]
+CNAME ["example.test"]
+SRV [{"priority":10,"weight":60,"port":5060,"name":"sip.example.test"}]
+NAPTR [{"order":100,"preference":10,"flags":"S","service":"SIP+D2U","regexp":"","replacement":"_sip._udp.example.test"}]
+TLSA 3 1 1 deadbeef
+PTR ["host.example.test"]
+reverse ["host.example.test"]
+resolve MX [{"priority":10,"exchange":"mail.example.test"}]
+ANY A,A,AAAA,MX,TXT,TXT,NS,SOA,CAA
+promises SRV [{"priority":10,"weight":60,"port":5060,"name":"sip.example.test"}]"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn dns_resolver_truncation_falls_back_to_tcp(
+    #[tagged_as("dns_resolver")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_dns_server().await;
+    let result = call(compiled, "truncation", port).await?;
+    assert_eq!(result, "20 00-xxxxx 19-xxxxx");
+    Ok(())
+}
+
+#[test]
+async fn dns_resolver_errors(
+    #[tagged_as("dns_resolver")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_dns_server().await;
+    let result = call(compiled, "errors", port).await?;
+    assert_eq!(
+        result,
+        format!(
+            "no servers: ECONNREFUSED queryMx example.test\n\
+             missing: ENOTFOUND queryMx missing.example.test\n\
+             no data: ENODATA queryMx alias.example.test\n\
+             servfail: ESERVFAIL queryTxt servfail.example.test\n\
+             refused: EREFUSED queryTxt refused.example.test\n\
+             timeout: ETIMEOUT queryTxt drop.example.test\n\
+             bad name: EBADNAME queryTxt {}.example.test\n\
+             reverse invalid: EINVAL getHostByAddr not-an-ip\n\
+             setServers: ERR_INVALID_IP_ADDRESS",
+            "a".repeat(64)
+        )
+    );
+    Ok(())
+}
+
+#[test]
+async fn dns_resolver_instances(
+    #[tagged_as("dns_resolver")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_dns_server().await;
+    let result = call(compiled, "resolvers", port).await?;
+    assert_eq!(
+        result,
+        format!(
+            "default servers [\"192.0.2.53\",\"[::1]:{port}\",\"127.0.0.1:{port}\"]\n\
+             inherited servers [\"192.0.2.53\",\"[::1]:{port}\",\"127.0.0.1:{port}\"]\n\
+             resolver servers [\"127.0.0.1:{port}\"]\n\
+             default unchanged [\"192.0.2.53\",\"[::1]:{port}\",\"127.0.0.1:{port}\"]\n\
+             resolver MX [{{\"priority\":10,\"exchange\":\"mail.example.test\"}}]\n\
+             resolver still queries [\"192.0.2.1\",\"192.0.2.2\"]\n\
+             cancelled: ECANCELLED queryTxt drop.example.test"
+        )
+    );
+    Ok(())
+}
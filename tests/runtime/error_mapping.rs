@@ -0,0 +1,138 @@
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasm_rquickjs::WrapperCrateOptions;
+use wasmtime::component::Val;
+
+const API: Option<&str> = Some("quickjs:error-mapping/api");
+
+#[test_dep(tagged_as = "error_mapping", scope = Cloneable)]
+async fn compiled_error_mapping() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/error-mapping");
+    CompiledTest::new_with_options(
+        path,
+        true,
+        &WrapperCrateOptions {
+            error_catch_all_case: Some("internal".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to compile error-mapping")
+}
+
+async fn lookup(compiled: &CompiledTest, kind: &str) -> anyhow::Result<Option<Val>> {
+    let (result, _) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        API,
+        "lookup",
+        &[Val::String(kind.to_string())],
+    )
+    .await;
+    result
+}
+
+fn api_error(case: &str, payload: Option<Val>) -> Option<Val> {
+    Some(Val::Result(Err(Some(Box::new(Val::Variant(
+        case.to_string(),
+        payload.map(Box::new),
+    ))))))
+}
+
+#[test]
+async fn error_name_selects_variant_case(
+    #[tagged_as("error_mapping")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_eq!(
+        lookup(compiled, "ok").await?,
+        Some(Val::Result(Ok(Some(Box::new(Val::String(
+            "found".to_string()
+        ))))))
+    );
+    assert_eq!(
+        lookup(compiled, "not-found").await?,
+        api_error("not-found", Some(Val::String("no such item".to_string())))
+    );
+    assert_eq!(
+        lookup(compiled, "invalid").await?,
+        api_error(
+            "invalid-input",
+            Some(Val::Record(vec![
+                (
+                    "message".to_string(),
+                    Val::String("must not be empty".to_string())
+                ),
+                (
+                    "field".to_string(),
+                    Val::Option(Some(Box::new(Val::String("name".to_string()))))
+                ),
+            ]))
+        )
+    );
+    assert_eq!(
+        lookup(compiled, "unauthorized").await?,
+        api_error("unauthorized", None)
+    );
+    Ok(())
+}
+
+#[test]
+async fn unmatched_errors_use_catch_all_case(
+    #[tagged_as("error_mapping")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    assert_eq!(
+        lookup(compiled, "tagged").await?,
+        api_error("internal", Some(Val::String("explicit".to_string())))
+    );
+    assert_eq!(
+        lookup(compiled, "other").await?,
+        api_error("internal", Some(Val::String("unexpected".to_string())))
+    );
+    Ok(())
+}
+
+#[test]
+async fn error_code_selects_enum_case(
+    #[tagged_as("error_mapping")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    for (kind, expected) in [
+        ("enoent", "enoent"),
+        ("eacces", "eacces"),
+        ("eperm", "internal"),
+    ] {
+        let (result, _) = invoke_and_capture_output(
+            compiled.wasm_path(),
+            API,
+            "open-file",
+            &[Val::String(kind.to_string())],
+        )
+        .await;
+        assert_eq!(
+            result?,
+            Some(Val::Result(Err(Some(Box::new(Val::Enum(
+                expected.to_string()
+            ))))))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+async fn error_message_maps_to_string_error(
+    #[tagged_as("error_mapping")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (result, _) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        API,
+        "parse",
+        &[Val::String("abc".to_string())],
+    )
+    .await;
+    assert_eq!(
+        result?,
+        Some(Val::Result(Err(Some(Box::new(Val::String(
+            "not a number: abc".to_string()
+        ))))))
+    );
+    Ok(())
+}
@@ -886,3 +886,44 @@ async fn fetch_function_shape(#[tagged_as("fetch")] compiled: &CompiledTest) ->
 
     Ok(())
 }
+
+#[test]
+async fn fetch_compressed_bodies(
+    #[tagged_as("fetch")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+
+    let (r, output) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        None,
+        "fetch-compressed-bodies",
+        &[Val::U16(port)],
+    )
+    .await;
+    let _ = r?;
+
+    println!("{output}");
+
+    let text = "hello compressed world, hello compressed world, hello compressed world";
+    assert!(output.contains(&format!(
+        "gzip: content-encoding=gzip bodyUsed=true text={text}\n"
+    )));
+    assert!(output.contains(&format!(
+        "deflate: content-encoding=deflate bodyUsed=true text={text}\n"
+    )));
+    assert!(output.contains(&format!(
+        "deflate-raw: content-encoding=deflate bodyUsed=true text={text}\n"
+    )));
+    assert!(output.contains(&format!(
+        "identity: content-encoding=null bodyUsed=true text={text}\n"
+    )));
+    assert!(output.contains(&format!(
+        "unknown: content-encoding=compress bodyUsed=true text={text}\n"
+    )));
+    assert!(output.contains("Default Accept-Encoding: gzip, deflate\n"));
+    assert!(output.contains("Explicit Accept-Encoding: identity\n"));
+    assert!(output.contains(&format!("Streamed gzip: bodyUsed=true text={text}\n")));
+    assert!(output.contains("Cloned deflate: true length=70\n"));
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::common::{CompiledTest, copy_dir_recursive};
+use camino::Utf8Path;
+use std::process::Command;
+use test_r::{test, test_dep};
+use wasm_rquickjs::generate_host_harness;
+
+#[test_dep(tagged_as = "greeter", scope = Cloneable)]
+async fn compiled_greeter() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/greeter");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile greeter")
+}
+
+/// Runs the example's `tests` against the component through its generated host harness,
+/// calling the exports with the typed helpers and implementing the imports on the host
+#[test]
+async fn host_harness_greeter(
+    #[tagged_as("greeter")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let example_path = Utf8Path::new("examples/runtime/greeter");
+    let harness_crate_root = Utf8Path::new("tmp")
+        .join("host-harness-runtime")
+        .join("greeter");
+
+    // shared_target is relative to harness_crate_root
+    let shared_target = Utf8Path::new("..").join("..").join("..").join("target");
+
+    generate_host_harness(&example_path.join("wit"), &harness_crate_root, None)?;
+    copy_dir_recursive(
+        example_path.join("tests").as_std_path(),
+        harness_crate_root.join("tests").as_std_path(),
+    )?;
+
+    let wasm_path = compiled.wasm_path().canonicalize_utf8()?;
+    let status = Command::new("cargo")
+        .arg("test")
+        .arg("--target-dir")
+        .arg(shared_target)
+        .env("GREETER_WASM", wasm_path)
+        .current_dir(&harness_crate_root)
+        .status()?;
+
+    assert!(status.success());
+
+    Ok(())
+}
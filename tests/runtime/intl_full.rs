@@ -0,0 +1,84 @@
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasm_rquickjs::WrapperCrateOptions;
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "intl_full", scope = Cloneable)]
+async fn compiled_intl_full() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/intl-full");
+    CompiledTest::new_with_features_and_options(
+        path,
+        true,
+        FeatureCombination::IntlFull,
+        &WrapperCrateOptions {
+            intl_locales: vec!["de".to_string(), "ja".to_string()],
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to compile intl-full")
+}
+
+#[test]
+async fn intl_full_test1(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test1", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn intl_full_test2(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test2", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn intl_full_test3(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test3", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn intl_full_test4(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test4", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn intl_full_test5(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test5", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn intl_full_test6(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test6", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn intl_full_test7(#[tagged_as("intl_full")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test7", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
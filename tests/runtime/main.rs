@@ -10,16 +10,24 @@ mod common;
 
 mod abort_controller;
 mod assert;
+mod async_context;
+mod async_hooks;
 mod bigint_roundtrip;
 mod buffer;
 mod cjs_require;
+mod compression_streams;
 mod console;
+mod coverage;
+mod cpu_profiler;
 mod crypto;
+mod deterministic;
 mod diagnostics_channel;
 mod diagnostics_channel_golem;
 mod dns;
+mod dns_resolver;
 mod domain;
 mod encoding;
+mod error_mapping;
 mod example1;
 mod example2;
 mod example3;
@@ -27,24 +35,40 @@ mod export_from_inner_package;
 mod export_interface_name_collision;
 mod fetch;
 mod fs;
+mod host_harness;
 mod imports;
 mod intl;
+mod intl_full;
 mod node_http;
+mod node_http_interim;
+mod node_http_upgrade;
+mod node_test_runner;
 mod os;
 mod path;
+mod per_call_isolation;
+mod perf_hooks;
 mod pollable;
+mod relaxed_arity;
 mod response_constructor;
 mod response_static;
+mod run_tests;
 mod sqlite;
 mod stateful1;
 mod streams;
 mod structured_clone;
+mod temporal;
 mod timeout;
 mod toplevel_timer;
 mod url;
+mod url_pattern;
+mod v8_heap;
+mod v8_serialize;
 mod v8_stack_trace;
 mod variant_list_roundtrip;
+mod websocket_tcp;
+mod worker_threads;
 mod xhr;
+mod zstd;
 
 // Tag suites into 8 groups for parallel CI matrix execution
 tag_suite!(crypto, group1);
@@ -57,6 +81,7 @@ tag_suite!(fs, group3);
 tag_suite!(xhr, group4);
 tag_suite!(assert, group4);
 tag_suite!(dns, group4);
+tag_suite!(dns_resolver, group4);
 tag_suite!(console, group4);
 tag_suite!(encoding, group4);
 
@@ -66,10 +91,12 @@ tag_suite!(diagnostics_channel, group5);
 tag_suite!(diagnostics_channel_golem, group5);
 tag_suite!(pollable, group5);
 tag_suite!(toplevel_timer, group5);
+tag_suite!(deterministic, group5);
 
 tag_suite!(path, group6);
 tag_suite!(domain, group6);
 tag_suite!(stateful1, group6);
+tag_suite!(per_call_isolation, group6);
 tag_suite!(os, group6);
 tag_suite!(export_from_inner_package, group6);
 tag_suite!(export_interface_name_collision, group6);
@@ -77,20 +104,41 @@ tag_suite!(example3, group6);
 tag_suite!(sqlite, group6);
 
 tag_suite!(url, group7);
+tag_suite!(url_pattern, group7);
 tag_suite!(cjs_require, group7);
 tag_suite!(timeout, group7);
 tag_suite!(buffer, group7);
 tag_suite!(bigint_roundtrip, group7);
 tag_suite!(imports, group7);
+tag_suite!(host_harness, group7);
 tag_suite!(variant_list_roundtrip, group7);
+tag_suite!(error_mapping, group7);
 
 tag_suite!(response_static, group8);
 tag_suite!(v8_stack_trace, group8);
+tag_suite!(v8_serialize, group8);
+tag_suite!(v8_heap, group8);
+tag_suite!(cpu_profiler, group8);
+tag_suite!(coverage, group8);
+tag_suite!(perf_hooks, group8);
+tag_suite!(node_test_runner, group8);
+tag_suite!(run_tests, group8);
+tag_suite!(async_context, group8);
+tag_suite!(async_hooks, group8);
 tag_suite!(structured_clone, group8);
 tag_suite!(node_http, group8);
 tag_suite!(intl, group8);
 tag_suite!(example1, group8);
 tag_suite!(example2, group8);
+tag_suite!(relaxed_arity, group8);
+tag_suite!(compression_streams, group8);
+tag_suite!(zstd, group8);
+tag_suite!(websocket_tcp, group8);
+tag_suite!(node_http_upgrade, group8);
+tag_suite!(node_http_interim, group8);
+tag_suite!(worker_threads, group8);
+tag_suite!(intl_full, group8);
+tag_suite!(temporal, group8);
 
 #[test_dep(tagged_as = "example3", scope = Cloneable)]
 async fn compiled_example3() -> CompiledTest {
@@ -0,0 +1,122 @@
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "node_http_interim", scope = Cloneable)]
+async fn compiled_node_http_interim() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/node-http-interim");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Full)
+        .await
+        .expect("Failed to compile node-http-interim")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn node_http_check_continue(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "check-continue").await?;
+    assert_eq!(
+        result,
+        "checkContinue /upload\n\
+         client got 100 Continue\n\
+         HTTP/1.1 100 Continue\n\
+         HTTP/1.1 200 OK\n\
+         received hello"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_automatic_continue(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "automatic-continue").await?;
+    assert_eq!(
+        result,
+        "HTTP/1.1 100 Continue\nHTTP/1.1 200 OK\nreceived hello"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_check_expectation(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "check-expectation").await?;
+    assert_eq!(
+        result,
+        "checkExpectation payment\nHTTP/1.1 402 Payment Required"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_unmet_expectation(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "unmet-expectation").await?;
+    assert_eq!(result, "HTTP/1.1 417 Expectation Failed");
+    Ok(())
+}
+
+#[test]
+async fn node_http_headers_timeout(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "headers-timeout").await?;
+    assert_eq!(result, "HTTP/1.1 408 Request Timeout\nwithin limits=true");
+    Ok(())
+}
+
+#[test]
+async fn node_http_request_timeout(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "request-timeout").await?;
+    assert_eq!(
+        result,
+        "request\n\
+         clientError ERR_HTTP_REQUEST_TIMEOUT\n\
+         HTTP/1.1 408 Request Timeout\n\
+         handled=true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_keep_alive_timeout(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "keep-alive-timeout").await?;
+    assert_eq!(
+        result,
+        "HTTP/1.1 200 OK\nKeep-Alive: timeout=1\nclosed after idle=true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_information_events(
+    #[tagged_as("node_http_interim")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "information-events").await?;
+    assert_eq!(
+        result,
+        "continue\n\
+         information 100 Continue 1.1 {}\n\
+         information 103 Early Hints 1.1 {\"link\":\"</style.css>; rel=preload\"}\n\
+         response 200 done"
+    );
+    Ok(())
+}
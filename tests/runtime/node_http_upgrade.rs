@@ -0,0 +1,107 @@
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "node_http_upgrade", scope = Cloneable)]
+async fn compiled_node_http_upgrade() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/node-http-upgrade");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Full)
+        .await
+        .expect("Failed to compile node-http-upgrade")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn node_http_raw_upgrade(
+    #[tagged_as("node_http_upgrade")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "raw-upgrade").await?;
+    assert_eq!(
+        result,
+        "upgrade GET /shout upgrade=true protocol=shout\n\
+         head hello \n\
+         HTTP/1.1 101 Switching Protocols\n\
+         Upgrade: shout\n\
+         Connection: Upgrade\n\
+         \n\
+         HELLO WORLD"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_upgrade_without_listener(
+    #[tagged_as("node_http_upgrade")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "upgrade-without-listener").await?;
+    assert_eq!(result, "HTTP/1.1 200 OK\nupgrade=false");
+    Ok(())
+}
+
+#[test]
+async fn node_http_switching_protocols_response(
+    #[tagged_as("node_http_upgrade")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "switching-protocols-response").await?;
+    assert_eq!(
+        result,
+        "HTTP/1.1 101 Switching Protocols\n\
+         Connection: Upgrade\n\
+         Upgrade: shout\n\
+         \n\
+         EARLY DATA"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_websocket_server_echo(
+    #[tagged_as("node_http_upgrade")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "websocket-echo").await?;
+    assert_eq!(
+        result,
+        "server connection url=/echo protocol=chat\n\
+         client open protocol=chat\n\
+         server message text hello\n\
+         server message binary 1,2,3\n\
+         client message hello\n\
+         client message 1,2,3\n\
+         client close 1000\n\
+         server close 1000 bye clients=0"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_websocket_server_close(
+    #[tagged_as("node_http_upgrade")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "websocket-server-close").await?;
+    assert_eq!(
+        result,
+        "server pong are you there\n\
+         client close 4001 going away wasClean=true\n\
+         server close 4001"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_http_websocket_server_path_mismatch(
+    #[tagged_as("node_http_upgrade")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "websocket-path-mismatch").await?;
+    assert_eq!(result, "error\nclose 1006");
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::common::{CompiledTest, TestInstance, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "node_test_runner", scope = Cloneable)]
+async fn compiled_node_test_runner() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/node-test-runner");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile node-test-runner")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+fn assert_contains_all(output: &str, expected: &[&str]) {
+    for line in expected {
+        assert!(
+            output.contains(line),
+            "expected {line:?} in reporter output:\n{output}"
+        );
+    }
+}
+
+#[test]
+async fn node_test_runner_tap(
+    #[tagged_as("node_test_runner")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "tap-report").await?;
+    assert!(result.starts_with("TAP version 13\n# Subtest: passes\nok 1 - passes\n"));
+    assert_contains_all(
+        &result,
+        &[
+            "\nnot ok 2 - fails\n  ---\n  duration_ms *\n  failureType: 'testCodeFailure'\n",
+            "\n  code: 'ERR_ASSERTION'\n  name: 'AssertionError'\n  expected: 3\n  actual: 2\n  operator: 'strictEqual'\n  ...\n",
+            "\nok 3 - skipped # SKIP not today\n",
+            "\nok 4 - todo # TODO\n",
+            "\n# Subtest: suite\n    # Subtest: first\n    ok 1 - first\n",
+            "\n    ok 2 - second\n      ---\n      duration_ms *\n      ...\n    1..2\nok 5 - suite\n  ---\n  duration_ms *\n  type: 'suite'\n  ...\n",
+            "\n    ok 1 - inner\n      ---\n      duration_ms *\n      ...\n    1..1\nok 6 - with subtests\n",
+            "\n# hello\n1..6\n# tests 8\n# suites 1\n# pass 5\n# fail 1\n# cancelled 0\n# skipped 1\n# todo 1\n# duration_ms *\n",
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_test_runner_spec(
+    #[tagged_as("node_test_runner")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "spec-report").await?;
+    assert!(result.starts_with("✔ passes (*ms)\n✖ fails (*ms)\n"));
+    assert_contains_all(
+        &result,
+        &[
+            "\n﹣ skipped (*ms) # SKIP not today\n",
+            "\n﹣ todo (*ms) # TODO\n",
+            "\n▶ suite\n  ✔ first (*ms)\n  ✔ second (*ms)\n✔ suite (*ms)\n",
+            "\n▶ with subtests\n  ✔ inner (*ms)\n✔ with subtests (*ms)\nℹ hello\n",
+            "\nℹ tests 8\nℹ suites 1\nℹ pass 5\nℹ fail 1\n",
+            "\n✖ failing tests:\n\n✖ fails (*ms)\n",
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_test_runner_junit(
+    #[tagged_as("node_test_runner")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "junit-report").await?;
+    assert!(result.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<testsuites>\n"));
+    assert!(result.ends_with("\t<!-- duration_ms * -->\n</testsuites>\n"));
+    assert_contains_all(
+        &result,
+        &[
+            "\n\t<testcase name=\"passes\" time=\"*\" classname=\"test\"/>\n",
+            "\n\t<testcase name=\"fails\" time=\"*\" classname=\"test\">\n\t\t<failure type=\"testCodeFailure\" message=\"",
+            "\n\t<testcase name=\"skipped\" time=\"*\" classname=\"test\">\n\t\t<skipped type=\"skipped\" message=\"not today\"/>\n\t</testcase>\n",
+            "\n\t<testsuite name=\"suite\" time=\"*\" disabled=\"0\" errors=\"0\" tests=\"2\" failures=\"0\" skipped=\"0\" hostname=\"*\">\n\t\t<testcase name=\"first\" time=\"*\" classname=\"test\"/>\n",
+            "\n\t<!-- hello -->\n\t<!-- tests 8 -->\n",
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_test_runner_mock_timers(
+    #[tagged_as("node_test_runner")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "mock-timers").await?;
+    assert_eq!(
+        result,
+        "distinct ids true\nsurvivor\ninterval at 1020\ninterval at 1040\nnow 1045 1045\ntimeout a at 1050\nset 5000\nenable twice ERR_INVALID_STATE\nrestored true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn node_test_runner_snapshots(
+    #[tagged_as("node_test_runner")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    let (r, output) = instance
+        .invoke_and_capture_output(None, "snapshots", &[])
+        .await;
+    println!("{output}");
+
+    let Some(Val::String(result)) = r? else {
+        anyhow::bail!("unexpected result");
+    };
+    assert_eq!(
+        result,
+        r#"pass record
+pass record
+fail record: ERR_ASSERTION
+fail missing: ERR_INVALID_STATE
+exports[`record 1`] = `
+{
+  "answer": 42
+}
+`;
+
+exports[`record 2`] = `
+"text\`with \${template}"
+`;
+"#
+    );
+    assert_eq!(
+        std::fs::read_to_string(instance.temp_dir_path().join("snapshots/file.txt"))?,
+        "file contents\n"
+    );
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::common::{CompiledTest, FeatureCombination, TestInstance};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "per_call_isolation", scope = Cloneable)]
+async fn compiled_per_call_isolation() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/per-call-isolation");
+    CompiledTest::new_with_features(path, true, FeatureCombination::PerCallIsolation)
+        .await
+        .expect("Failed to compile per-call-isolation")
+}
+
+#[test]
+async fn module_state_is_reset_between_calls(
+    #[tagged_as("per_call_isolation")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut test_instance = TestInstance::new(compiled.wasm_path()).await?;
+
+    let (r1, _) = test_instance
+        .invoke_and_capture_output(None, "inc", &[Val::S32(1)])
+        .await;
+    let (r2, _) = test_instance
+        .invoke_and_capture_output(None, "inc", &[Val::S32(3)])
+        .await;
+
+    assert_eq!(r1?, Some(Val::S32(1)));
+    assert_eq!(r2?, Some(Val::S32(3)));
+
+    Ok(())
+}
+
+#[test]
+async fn globals_are_reset_between_calls(
+    #[tagged_as("per_call_isolation")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut test_instance = TestInstance::new(compiled.wasm_path()).await?;
+
+    let (r, _) = test_instance
+        .invoke_and_capture_output(None, "set-global", &[Val::String("leaked".to_string())])
+        .await;
+    let _ = r?;
+
+    let (r, _) = test_instance
+        .invoke_and_capture_output(None, "get-global", &[])
+        .await;
+
+    assert_eq!(r?, Some(Val::Option(None)));
+
+    Ok(())
+}
+
+#[test]
+async fn module_is_initialized_once(
+    #[tagged_as("per_call_isolation")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut test_instance = TestInstance::new(compiled.wasm_path()).await?;
+
+    let mut results = Vec::new();
+    let mut output = String::new();
+    for delta in [1, 2, 3] {
+        let (r, o) = test_instance
+            .invoke_and_capture_output(None, "inc", &[Val::S32(delta)])
+            .await;
+        results.push(r?);
+        output = o;
+    }
+
+    // Every call starts from the state right after the initialization, without evaluating the
+    // module again
+    assert_eq!(
+        results,
+        vec![Some(Val::S32(1)), Some(Val::S32(2)), Some(Val::S32(3))]
+    );
+    assert_eq!(output.matches("initializing the module").count(), 1);
+
+    Ok(())
+}
+
+#[test]
+async fn stale_resource_handles_are_reported(
+    #[tagged_as("per_call_isolation")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut test_instance = TestInstance::new(compiled.wasm_path()).await?;
+
+    let (counter, _) = test_instance
+        .invoke_and_capture_output(
+            Some("quickjs:per-call-isolation/counters"),
+            "[constructor]counter",
+            &[Val::S32(10)],
+        )
+        .await;
+    let Some(Val::Resource(counter)) = counter? else {
+        panic!("Expected a resource handle")
+    };
+
+    // The instance created by the constructor call did not survive the end of that call
+    let (r, _) = test_instance
+        .invoke_and_capture_output(
+            Some("quickjs:per-call-isolation/counters"),
+            "[method]counter.get",
+            &[Val::Resource(counter)],
+        )
+        .await;
+    let Some(Val::Result(Err(Some(error)))) = r? else {
+        panic!("Expected an error result")
+    };
+    let Val::String(message) = *error else {
+        panic!("Expected a string error")
+    };
+    assert!(message.contains("does not exist"), "{message}");
+
+    // Without a result type to report it in, the error traps
+    let (r, _) = test_instance
+        .invoke_and_capture_output(
+            Some("quickjs:per-call-isolation/counters"),
+            "[method]counter.peek",
+            &[Val::Resource(counter)],
+        )
+        .await;
+    assert!(r.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "perf_hooks", scope = Cloneable)]
+async fn compiled_perf_hooks() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/perf-hooks");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile perf-hooks")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn perf_hooks_histogram(
+    #[tagged_as("perf_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "histogram").await?;
+    assert_eq!(
+        result,
+        r#"empty [0,9223372036854776000,0,null,null,0]
+json {"count":11,"min":1,"max":100,"mean":14.090909090909092,"exceeds":0,"stddev":27.304526915561905,"percentiles":{"0":1,"50":6,"75":9,"100":100,"87.5":10,"93.75":100}}
+percentile 6 100 100
+percentiles [[0,1],[50,6],[75,9],[87.5,10],[93.75,100],[100,100]]
+exceeds 1 1
+add 12 1 100
+delta 1 true
+invalid percentile ERR_OUT_OF_RANGE
+invalid value ERR_OUT_OF_RANGE
+invalid bounds ERR_OUT_OF_RANGE
+invalid resolution ERR_OUT_OF_RANGE"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn perf_hooks_event_loop_delay(
+    #[tagged_as("perf_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "event-loop-delay").await?;
+    assert_eq!(
+        result,
+        "enable true,false\ndisable true,false\ncount true\nmax true\nmin true\np50 true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn perf_hooks_observer(
+    #[tagged_as("perf_hooks")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "observer").await?;
+    assert_eq!(
+        result,
+        r#"supported function,gc,mark,measure
+sum 3 timerified add
+histogram 1 true
+mark start "details" true
+function add [1,2] true
+gc gc {"kind":4,"flags":4} true"#
+    );
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::common::{CompiledTest, FeatureCombination, TestInstance, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "relaxed_arity", scope = Cloneable)]
+async fn compiled_relaxed_arity() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/relaxed-arity");
+    CompiledTest::new_with_features(path, true, FeatureCombination::RelaxedArity)
+        .await
+        .expect("Failed to compile relaxed-arity")
+}
+
+#[test]
+async fn default_parameters(
+    #[tagged_as("relaxed_arity")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, _) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        None,
+        "with-default",
+        &[Val::S32(1), Val::S32(2)],
+    )
+    .await;
+
+    assert_eq!(r?, Some(Val::S32(3)));
+    Ok(())
+}
+
+#[test]
+async fn rest_parameters(
+    #[tagged_as("relaxed_arity")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, _) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        None,
+        "with-rest",
+        &[
+            Val::String("a".to_string()),
+            Val::String("b".to_string()),
+            Val::String("c".to_string()),
+        ],
+    )
+    .await;
+
+    assert_eq!(r?, Some(Val::String("a-b-c".to_string())));
+    Ok(())
+}
+
+#[test]
+async fn decorated_function(
+    #[tagged_as("relaxed_arity")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        None,
+        "decorated",
+        &[Val::U32(2), Val::U32(3)],
+    )
+    .await;
+
+    assert_eq!(r?, Some(Val::U32(5)));
+    assert_eq!(output, "calling add\n");
+    Ok(())
+}
+
+#[test]
+async fn bound_function(
+    #[tagged_as("relaxed_arity")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, _) = invoke_and_capture_output(
+        compiled.wasm_path(),
+        None,
+        "bound",
+        &[Val::U32(2), Val::U32(3)],
+    )
+    .await;
+
+    assert_eq!(r?, Some(Val::U32(10)));
+    Ok(())
+}
+
+#[test]
+async fn resource_default_parameters(
+    #[tagged_as("relaxed_arity")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut test_instance = TestInstance::new(compiled.wasm_path()).await?;
+
+    let (counter, _) = test_instance
+        .invoke_and_capture_output(
+            Some("quickjs:relaxed-arity/counters"),
+            "[constructor]counter",
+            &[Val::S32(10), Val::S32(2)],
+        )
+        .await;
+    let Some(Val::Resource(counter)) = counter? else {
+        panic!("Expected a resource handle")
+    };
+
+    // The second call reuses the arity decision of the first
+    let mut results = Vec::new();
+    for extra in [1, 5] {
+        let (r, _) = test_instance
+            .invoke_and_capture_output(
+                Some("quickjs:relaxed-arity/counters"),
+                "[method]counter.add",
+                &[Val::Resource(counter), Val::S32(3), Val::S32(extra)],
+            )
+            .await;
+        results.push(r?);
+    }
+
+    assert_eq!(results, vec![Some(Val::S32(17)), Some(Val::S32(28))]);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use crate::common::{CompiledTest, TestInstance};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasm_rquickjs::WrapperCrateOptions;
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "run_tests", scope = Cloneable)]
+async fn compiled_run_tests() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/run-tests");
+    CompiledTest::new_with_options(
+        path,
+        true,
+        &WrapperCrateOptions {
+            run_tests_export: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to compile run-tests")
+}
+
+/// Name, status and failure message of each test result, checking the durations
+fn test_results(result: Option<Val>) -> anyhow::Result<Vec<(String, String, Option<String>)>> {
+    let Some(Val::List(results)) = result else {
+        anyhow::bail!("unexpected result: {result:?}");
+    };
+    results
+        .into_iter()
+        .map(|result| match result {
+            Val::Record(fields) => match fields.as_slice() {
+                [
+                    (_, Val::String(name)),
+                    (_, Val::Enum(status)),
+                    (_, Val::Float64(duration_ms)),
+                    (_, Val::Option(failure)),
+                ] => {
+                    assert!(*duration_ms >= 0.0, "negative duration for {name}");
+                    let failure = match failure.as_deref() {
+                        Some(Val::String(failure)) => Some(failure.clone()),
+                        None => None,
+                        other => anyhow::bail!("unexpected failure: {other:?}"),
+                    };
+                    Ok((name.clone(), status.clone(), failure))
+                }
+                other => anyhow::bail!("unexpected test result: {other:?}"),
+            },
+            other => anyhow::bail!("unexpected test result: {other:?}"),
+        })
+        .collect()
+}
+
+fn expected(results: &[(&str, &str, Option<&str>)]) -> Vec<(String, String, Option<String>)> {
+    results
+        .iter()
+        .map(|(name, status, failure)| {
+            (
+                name.to_string(),
+                status.to_string(),
+                failure.map(|f| f.to_string()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+async fn run_tests_all(#[tagged_as("run_tests")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    assert_eq!(
+        instance.invoke(None, "registration-runs", &[]).await?,
+        Some(Val::U32(0))
+    );
+
+    let (r, output) = instance
+        .invoke_and_capture_output(None, "run-tests", &[Val::Option(None)])
+        .await;
+    println!("{output}");
+    assert_eq!(
+        test_results(r?)?,
+        expected(&[
+            ("adds", "pass", None),
+            ("fails", "fail", Some("add is broken")),
+            ("math > waits", "pass", None),
+            ("math > divides", "skip", None),
+            ("math > multiplies", "todo", None),
+            ("with subtests > positive", "pass", None),
+            ("with subtests > negative", "pass", None),
+            ("with subtests", "pass", None),
+        ])
+    );
+    assert_eq!(
+        instance.invoke(None, "registration-runs", &[]).await?,
+        Some(Val::U32(1))
+    );
+    Ok(())
+}
+
+#[test]
+async fn run_tests_filtered(
+    #[tagged_as("run_tests")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    let filter = Val::Option(Some(Box::new(Val::String(
+        "^math > (waits|divides)$".to_string(),
+    ))));
+    let (r, output) = instance
+        .invoke_and_capture_output(None, "run-tests", std::slice::from_ref(&filter))
+        .await;
+    println!("{output}");
+    assert_eq!(
+        test_results(r?)?,
+        expected(&[
+            ("math > waits", "pass", None),
+            ("math > divides", "skip", None)
+        ])
+    );
+
+    // The registered tests can be run again
+    let r = instance.invoke(None, "run-tests", &[filter]).await;
+    assert_eq!(test_results(r?)?.len(), 2);
+    assert_eq!(
+        instance.invoke(None, "registration-runs", &[]).await?,
+        Some(Val::U32(0))
+    );
+    Ok(())
+}
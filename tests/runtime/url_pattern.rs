@@ -0,0 +1,61 @@
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "url_pattern", scope = Cloneable)]
+async fn compiled_url_pattern() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/url-pattern");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile url-pattern")
+}
+
+#[test]
+async fn url_pattern_test1(
+    #[tagged_as("url_pattern")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test1", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn url_pattern_test2(
+    #[tagged_as("url_pattern")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test2", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+#[test]
+async fn url_pattern_test3(
+    #[tagged_as("url_pattern")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, "test3", &[]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
+
+/// Runs the URLPattern test data of the web-platform-tests, vendored from
+/// https://github.com/web-platform-tests/wpt/blob/master/urlpattern/resources/urlpatterntestdata.json
+#[test]
+async fn url_pattern_wpt(
+    #[tagged_as("url_pattern")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let data =
+        std::fs::read_to_string("examples/runtime/url-pattern/fixtures/urlpatterntestdata.json")?;
+    let (r, output) =
+        invoke_and_capture_output(compiled.wasm_path(), None, "wpt", &[Val::String(data)]).await;
+    let r = r?;
+    println!("Output:\n{}", output);
+    assert_eq!(r, Some(Val::Bool(true)));
+    Ok(())
+}
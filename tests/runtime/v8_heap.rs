@@ -0,0 +1,211 @@
+use crate::common::{CompiledTest, FeatureCombination, TestInstance, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasm_rquickjs::WrapperCrateOptions;
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "v8_heap", scope = Cloneable)]
+async fn compiled_v8_heap() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/v8-heap");
+    CompiledTest::new_with_options(
+        path,
+        true,
+        &WrapperCrateOptions {
+            heap_snapshot_export: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to compile v8-heap")
+}
+
+#[test_dep(tagged_as = "v8_heap_isolated", scope = Cloneable)]
+async fn compiled_v8_heap_isolated() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/v8-heap");
+    CompiledTest::new_with_features_and_options(
+        path,
+        true,
+        FeatureCombination::PerCallIsolation,
+        &WrapperCrateOptions {
+            heap_snapshot_export: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("Failed to compile v8-heap")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+fn count_strings(snapshot: &serde_json::Value, needle: &str) -> usize {
+    snapshot["strings"]
+        .as_array()
+        .map(|strings| {
+            strings
+                .iter()
+                .filter(|s| s.as_str().is_some_and(|s| s.starts_with(needle)))
+                .count()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+async fn v8_heap_statistics(#[tagged_as("v8_heap")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let result = call(compiled, "heap-statistics").await?;
+    assert_eq!(
+        result,
+        r#"keys total_heap_size,total_heap_size_executable,total_physical_size,total_available_size,used_heap_size,heap_size_limit,malloced_memory,peak_malloced_memory,does_zap_garbage,number_of_native_contexts,number_of_detached_contexts,total_global_handles_size,used_global_handles_size,external_memory
+used > 0 true
+total >= used true
+limit > total true
+available true
+peak >= malloced true
+used grew true
+spaces atom_space,string_space,object_space,shape_space,code_space,array_space,other_space
+spaces add up true
+code true"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_heap_snapshot(#[tagged_as("v8_heap")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let result = call(compiled, "heap-snapshot").await?;
+    assert_eq!(
+        result,
+        r#"fields type,name,id,self_size,edge_count,trace_node_id,detachedness / type,name_or_index,to_node
+counts true true true
+root synthetic '' 1
+entries 100 150
+stable ids 100
+entry edges property:label,property:__proto__,internal:map
+marker true
+cache found true
+closure 1
+hidden entries 30 true
+hidden map object Map
+hidden retained 30
+internals 1
+total true"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_write_heap_snapshot(
+    #[tagged_as("v8_heap")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    let (r, output) = instance
+        .invoke_and_capture_output(None, "write-heap-snapshot", &[])
+        .await;
+    println!("{output}");
+
+    let Some(Val::String(result)) = r? else {
+        anyhow::bail!("unexpected result");
+    };
+    assert_eq!(result, "default true\nexplicit /explicit.heapsnapshot");
+
+    let written: serde_json::Value = serde_json::from_slice(&std::fs::read(
+        instance.temp_dir_path().join("explicit.heapsnapshot"),
+    )?)?;
+    assert!(
+        written["snapshot"]["node_count"]
+            .as_u64()
+            .unwrap_or_default()
+            > 0
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_heap_snapshot_export(
+    #[tagged_as("v8_heap")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "retain-entries", &[Val::U32(20)])
+        .await;
+    r?;
+
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "wasm-rquickjs-heap-snapshot", &[])
+        .await;
+    let Some(Val::String(result)) = r? else {
+        anyhow::bail!("unexpected result");
+    };
+
+    let snapshot: serde_json::Value = serde_json::from_str(&result)?;
+    let node_count = snapshot["snapshot"]["node_count"]
+        .as_u64()
+        .unwrap_or_default();
+    let node_fields = snapshot["snapshot"]["meta"]["node_fields"]
+        .as_array()
+        .map(|fields| fields.len())
+        .unwrap_or_default();
+    let nodes = snapshot["nodes"]
+        .as_array()
+        .map(|nodes| nodes.len())
+        .unwrap_or_default();
+
+    assert!(node_count > 0);
+    assert_eq!(nodes as u64, node_count * node_fields as u64);
+    assert_eq!(count_strings(&snapshot, "LeakyEntry"), 1);
+    assert_eq!(count_strings(&snapshot, "leak-marker-"), 20);
+    Ok(())
+}
+
+#[test]
+async fn v8_heap_snapshot_export_module_scope(
+    #[tagged_as("v8_heap")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "retain-hidden", &[Val::U32(20)])
+        .await;
+    r?;
+
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "wasm-rquickjs-heap-snapshot", &[])
+        .await;
+    let Some(Val::String(result)) = r? else {
+        anyhow::bail!("unexpected result");
+    };
+
+    // The cache is not exported, only the closure variable of the module retains it
+    let snapshot: serde_json::Value = serde_json::from_str(&result)?;
+    assert_eq!(count_strings(&snapshot, "HiddenEntry"), 1);
+    assert_eq!(count_strings(&snapshot, "hidden-marker-"), 20);
+    assert_eq!(count_strings(&snapshot, "hiddenCache"), 1);
+    Ok(())
+}
+
+#[test]
+async fn v8_heap_snapshot_export_skips_isolation(
+    #[tagged_as("v8_heap_isolated")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let mut instance = TestInstance::new(compiled.wasm_path()).await?;
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "retain-entries", &[Val::U32(20)])
+        .await;
+    r?;
+
+    // The snapshot shows the context the previous call left behind, not a fresh one
+    let (r, _) = instance
+        .invoke_and_capture_output(None, "wasm-rquickjs-heap-snapshot", &[])
+        .await;
+    let Some(Val::String(result)) = r? else {
+        anyhow::bail!("unexpected result");
+    };
+    let snapshot: serde_json::Value = serde_json::from_str(&result)?;
+    assert_eq!(count_strings(&snapshot, "leak-marker-"), 20);
+    Ok(())
+}
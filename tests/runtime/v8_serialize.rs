@@ -0,0 +1,139 @@
+use crate::common::{CompiledTest, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "v8_serialize", scope = Cloneable)]
+async fn compiled_v8_serialize() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/v8-serialize");
+    CompiledTest::new(path, true)
+        .await
+        .expect("Failed to compile v8-serialize")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn v8_serialize_matches_node_bytes(
+    #[tagged_as("v8_serialize")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "serialize-values").await?;
+    assert_eq!(
+        result,
+        r#"object ff0f6f22016149022201622201787b02
+array ff0f410249024904240002
+holey ff0f61034900490249044906400203
+map ff0f3b490249043a02
+set ff0f2749022c01
+regexp ff0f5222016103
+bigint ff0f5a107b00000000000000
+negative bigint ff0f5a2100000000000000004000000000000000
+date ff0f44000000a2941a6d42
+double ff0f4e000000000000f83f
+negative zero ff0f4e0000000000000080
+two-byte ff0f6f006302ac2049027b01
+buffer ff0f5c0a020102
+uint8array ff0f5c01020102
+float64array ff0f5c0808000000000000f83f
+circular ff0f6f220473656c665e007b01
+shared ff0f41026f22017849027b015e01240002
+boxed ff0f41036e00000000000008407322017378240003
+integer keys ff0f6f490222016122016249047b02"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_deserialize_node_payloads(
+    #[tagged_as("v8_serialize")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "deserialize-node-payloads").await?;
+    assert_eq!(
+        result,
+        r#"object {"a":1,"b":"x"}
+map [[1,2]]
+set [1]
+regexp a gi
+bigint 123
+buffer true [1,2]
+circular true
+shared true 1
+old version {"a":1}
+arraybuffer true 2"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_serialize_roundtrip(
+    #[tagged_as("v8_serialize")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "roundtrip").await?;
+    assert_eq!(
+        result,
+        r#"string true
+unicode true
+lone surrogate true
+numbers [0,-1,2147483647,-2147483648,3.25,null,null] true
+nested {"list":[null,null,true,false],"empty":{}} 4 true
+date 2024-01-02T03:04:05.000Z
+regexp /x+y/msu
+map [["key",{"deep":[1,2]}],[1,"one"]]
+set a,1267650600228229401496703205376,-5
+buffer true buffer bytes
+int16 true -1,2,300
+bigints true -9223372036854775808,1
+view true 7
+sparse 3 false 3 prop
+error true out of range too big
+circular true"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_serializer_api(
+    #[tagged_as("v8_serialize")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "serializer-api").await?;
+    assert_eq!(
+        result,
+        r#"bytes ff0fac028280808010000000000000f83f6869220576616c7565
+version 15
+uint32 300
+uint64 [1,2]
+double 1.5
+raw hi
+value value
+transfer ff0f6f22046461746174077b01 true
+host object ff0f5c05 {"length":5}"#
+    );
+    Ok(())
+}
+
+#[test]
+async fn v8_serialize_errors(
+    #[tagged_as("v8_serialize")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "errors").await?;
+    assert_eq!(
+        result,
+        r#"function Error: f() {} could not be cloned.
+symbol Error: Symbol(x) could not be cloned.
+weakmap Error: #<WeakMap> could not be cloned.
+truncated Error: Unable to deserialize cloned data.
+unknown tag Error: Unable to deserialize cloned data.
+future version Error: Unable to deserialize cloned data due to invalid or unsupported version.
+not a view TypeError ERR_INVALID_ARG_TYPE: buffer must be a TypedArray or a DataView
+raw bytes TypeError ERR_INVALID_ARG_TYPE: source must be a TypedArray or a DataView"#
+    );
+    Ok(())
+}
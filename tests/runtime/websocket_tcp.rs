@@ -0,0 +1,110 @@
+use crate::common::test_server::start_test_server;
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "websocket_tcp", scope = Cloneable)]
+async fn compiled_websocket_tcp() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/websocket-tcp");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Full)
+        .await
+        .expect("Failed to compile websocket-tcp")
+}
+
+async fn call(compiled: &CompiledTest, function: &str, args: &[Val]) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, args).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn websocket_tcp_echo(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+    let result = call(compiled, "echo", &[Val::U16(port)]).await?;
+    assert_eq!(
+        result,
+        "open protocol=chat readyState=1\n\
+         message text:hello\n\
+         message binary:1,2,3\n\
+         close code=1000 reason=done wasClean=true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn websocket_tcp_server_close(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+    let result = call(compiled, "server-close", &[Val::U16(port)]).await?;
+    assert_eq!(
+        result,
+        "open protocol= readyState=1\n\
+         close code=4000 reason=server closing wasClean=true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn websocket_tcp_server_ping(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+    let result = call(compiled, "server-ping", &[Val::U16(port)]).await?;
+    assert_eq!(
+        result,
+        "open protocol= readyState=1\n\
+         message text:ping\n\
+         close code=1000 reason= wasClean=true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn websocket_tcp_large_message(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+    let result = call(compiled, "large-message", &[Val::U16(port)]).await?;
+    assert_eq!(result, "received 70000 bytes, equal=true");
+    Ok(())
+}
+
+#[test]
+async fn websocket_tcp_handshake_failure(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+    let result = call(compiled, "handshake-failure", &[Val::U16(port)]).await?;
+    assert_eq!(result, "error\nclose code=1006 reason= wasClean=false");
+    Ok(())
+}
+
+#[test]
+async fn websocket_tcp_secure_url_rejected(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "secure-url-rejected", &[]).await?;
+    assert_eq!(result, "error\nclose code=1006 reason= wasClean=false");
+    Ok(())
+}
+
+#[test]
+async fn websocket_tcp_stream_echo(
+    #[tagged_as("websocket_tcp")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let (port, _) = start_test_server().await;
+    let result = call(compiled, "stream-echo", &[Val::U16(port)]).await?;
+    assert_eq!(
+        result,
+        "protocol=chat\ntext:streamed\nbinary:4,5,6\nclosed code=1005"
+    );
+    Ok(())
+}
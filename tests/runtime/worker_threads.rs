@@ -0,0 +1,94 @@
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "worker_threads", scope = Cloneable)]
+async fn compiled_worker_threads() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/worker-threads");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Full)
+        .await
+        .expect("Failed to compile worker-threads")
+}
+
+async fn call(compiled: &CompiledTest, function: &str) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, &[]).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn worker_threads_eval_worker_roundtrip(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "eval-worker-roundtrip").await?;
+    assert_eq!(result, "true 0 hello worker false true 2,4,6 true 0");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_module_worker(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "module-worker").await?;
+    assert_eq!(result, "42 0");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_worker_error(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "worker-error").await?;
+    assert_eq!(result, "error true out of range ERR_CUSTOM, exit 1");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_worker_exit_code(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "worker-exit-code").await?;
+    assert_eq!(result, "before exit 3");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_worker_terminate(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "worker-terminate").await?;
+    assert_eq!(result, "1");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_transfer_port(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "transfer-port").await?;
+    assert_eq!(result, "sent through the transferred port DataCloneError 0");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_environment_data(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "environment-data").await?;
+    assert_eq!(result, "3 on x+y");
+    Ok(())
+}
+
+#[test]
+async fn worker_threads_vm_context_isolation(
+    #[tagged_as("worker_threads")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "vm-context-isolation").await?;
+    assert_eq!(result, "20 yes string yes true true ReferenceError");
+    Ok(())
+}
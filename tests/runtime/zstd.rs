@@ -0,0 +1,141 @@
+use crate::common::{CompiledTest, FeatureCombination, invoke_and_capture_output};
+use camino::Utf8Path;
+use test_r::{test, test_dep};
+use wasmtime::component::Val;
+
+#[test_dep(tagged_as = "zstd", scope = Cloneable)]
+async fn compiled_zstd() -> CompiledTest {
+    let path = Utf8Path::new("examples/runtime/zstd");
+    CompiledTest::new_with_features(path, true, FeatureCombination::Full)
+        .await
+        .expect("Failed to compile zstd")
+}
+
+const TEXT: &str =
+    "The quick brown fox jumps over the lazy dog. The quick brown fox jumps over the lazy dog.";
+
+async fn call(compiled: &CompiledTest, function: &str, args: &[Val]) -> anyhow::Result<String> {
+    let (r, output) = invoke_and_capture_output(compiled.wasm_path(), None, function, args).await;
+    println!("{output}");
+
+    match r? {
+        Some(Val::String(result)) => Ok(result),
+        other => anyhow::bail!("unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+async fn zstd_sync_roundtrip(#[tagged_as("zstd")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let result = call(compiled, "sync-roundtrip", &[Val::String(TEXT.to_string())]).await?;
+    assert_eq!(result, TEXT);
+    Ok(())
+}
+
+#[test]
+async fn zstd_callback_roundtrip(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(
+        compiled,
+        "callback-roundtrip",
+        &[Val::String(TEXT.to_string())],
+    )
+    .await?;
+    assert_eq!(result, TEXT);
+    Ok(())
+}
+
+#[test]
+async fn zstd_stream_roundtrip(#[tagged_as("zstd")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let chunks = ["first chunk, ", "second chunk, ", "", "third chunk"];
+    let result = call(
+        compiled,
+        "stream-roundtrip",
+        &[Val::List(
+            chunks
+                .iter()
+                .map(|chunk| Val::String(chunk.to_string()))
+                .collect(),
+        )],
+    )
+    .await?;
+    assert_eq!(result, chunks.concat());
+    Ok(())
+}
+
+#[test]
+async fn zstd_decompresses_reference_frame(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let expected = "hello zstandard, hello zstandard, hello zstandard";
+    let result = call(compiled, "decompress-reference-frame", &[]).await?;
+    assert_eq!(result, format!("{expected}|{expected}"));
+    Ok(())
+}
+
+#[test]
+async fn zstd_decompresses_incrementally(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "decompress-incrementally", &[]).await?;
+    assert_eq!(result, "output before end: true, complete: true");
+    Ok(())
+}
+
+#[test]
+async fn zstd_rejects_invalid_input(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "decompress-invalid", &[]).await?;
+    assert_eq!(
+        result,
+        "Error ZSTD_error_corruption_detected, Error ZSTD_error_corruption_detected, Error ZSTD_error_corruption_detected"
+    );
+    Ok(())
+}
+
+#[test]
+async fn zstd_validates_params(#[tagged_as("zstd")] compiled: &CompiledTest) -> anyhow::Result<()> {
+    let result = call(compiled, "invalid-params", &[]).await?;
+    assert_eq!(
+        result,
+        "RangeError ERR_ZSTD_INVALID_PARAM, TypeError ERR_INVALID_ARG_TYPE, Error ERR_ZLIB_INITIALIZATION_FAILED, RangeError ERR_ZSTD_INVALID_PARAM, no error"
+    );
+    Ok(())
+}
+
+#[test]
+async fn zstd_applies_frame_flags(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "frame-flags", &[]).await?;
+    assert_eq!(
+        result,
+        "checksum=0 contentSize=true roundtrip=true, checksum=1 contentSize=true roundtrip=true, checksum=1 contentSize=false roundtrip=true, checksum=1 contentSize=true roundtrip=true"
+    );
+    Ok(())
+}
+
+#[test]
+async fn zstd_rejects_unsupported_params(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "unsupported-params", &[]).await?;
+    assert_eq!(
+        result,
+        "Error ERR_ZLIB_INITIALIZATION_FAILED, Error ERR_ZLIB_INITIALIZATION_FAILED, Error ERR_ZLIB_INITIALIZATION_FAILED, Error ERR_ZLIB_INITIALIZATION_FAILED, Error ERR_ZLIB_INITIALIZATION_FAILED, no error, no error"
+    );
+    Ok(())
+}
+
+#[test]
+async fn zstd_constants_and_classes(
+    #[tagged_as("zstd")] compiled: &CompiledTest,
+) -> anyhow::Result<()> {
+    let result = call(compiled, "constants-shape", &[]).await?;
+    assert_eq!(
+        result,
+        "modes=10,11 endOps=0,1,2 level=100 checksum=201 windowLogMax=100 default=3 classes=true,true"
+    );
+    Ok(())
+}